@@ -1,8 +1,57 @@
-use bt2_derive::TryFromBtFieldConst;
+use bt2_derive::{FromBtEvent, TryFromBtFieldConst};
 
 #[derive(TryFromBtFieldConst)]
 pub struct Event {
-    data: u64,
+    pub data: u64,
+    #[bt2(rename = "type")]
+    pub kind: Kind,
+    pub payload: Payload,
+    pub header: Header,
+    pub samples: Vec<i64>,
+    pub timestamp: Option<i64>,
+    #[bt2(default)]
+    pub flags: u32,
+    #[bt2(default = "default_name")]
+    pub name: String,
+}
+
+#[derive(TryFromBtFieldConst)]
+pub struct Header {
+    #[bt2(try_from = u64, is_not_zero)]
+    pub valid: bool,
+}
+
+#[derive(TryFromBtFieldConst)]
+pub enum Kind {
+    #[bt2(rename = "KIND_DATA")]
+    Data,
+    #[bt2(rename = "KIND_CONTROL")]
+    Control,
+}
+
+#[derive(TryFromBtFieldConst)]
+pub enum Payload {
+    Empty,
+    Id(u64),
+    Text { value: String },
+}
+
+#[derive(FromBtEvent)]
+pub enum ProviderEvent {
+    #[bt2(event = "provider:event")]
+    Event(Event),
+    #[bt2(event = "provider:header")]
+    Header(Header),
+}
+
+#[derive(FromBtEvent)]
+pub enum AnyEvent {
+    #[bt2(flatten)]
+    Provider(ProviderEvent),
+}
+
+fn default_name() -> String {
+    "unknown".to_string()
 }
 
 fn main() {}
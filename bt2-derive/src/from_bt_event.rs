@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, Type, Variant};
+
+/// How a variant of the event enum is selected.
+enum EventVariant<'a> {
+    /// The event with the full name (`provider:name`) is converted into the variant field.
+    Event {
+        variant: &'a Variant,
+        ty: &'a Type,
+        name: LitStr,
+    },
+    /// The variant field is itself an event enum with its own event names.
+    Flatten { variant: &'a Variant, ty: &'a Type },
+}
+
+pub fn impl_from_bt_event(input: &DeriveInput) -> TokenStream {
+    let Data::Enum(data_enum) = &input.data else {
+        return syn::Error::new_spanned(input, "Only enums are supported").to_compile_error();
+    };
+
+    let variants = match data_enum
+        .variants
+        .iter()
+        .map(parse_variant)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(variants) => variants,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let mut event_names = Vec::new();
+    let mut event_arms = Vec::new();
+    let mut flatten_names = Vec::new();
+    let mut flatten_attempts = Vec::new();
+    for variant in &variants {
+        match variant {
+            EventVariant::Event { variant, ty, name } => {
+                let ident = &variant.ident;
+                event_names.push(name);
+                event_arms.push(quote! {
+                    #name => <#ty as bt2_sys::event::FromBtEvent>::from_event(event)
+                        .map(Self::#ident)
+                        .ok_or_else(|| bt2_sys::event::EventConversionError::conversion_failed(name)),
+                });
+            }
+            EventVariant::Flatten { variant, ty } => {
+                let ident = &variant.ident;
+                flatten_names.push(quote! {
+                    names.extend(<#ty as bt2_sys::event::FromBtEventByName>::event_names());
+                });
+                flatten_attempts.push(quote! {
+                    match <#ty as bt2_sys::event::FromBtEventByName>::try_from_event(event) {
+                        Err(bt2_sys::event::EventConversionError::UnknownEvent(_)) => {}
+                        result => return result.map(Self::#ident),
+                    }
+                });
+            }
+        }
+    }
+
+    let name = &input.ident;
+    quote! {
+        impl bt2_sys::event::FromBtEvent for #name {
+            fn from_event(event: &bt2_sys::event::BtEventConst) -> Option<Self> {
+                <Self as bt2_sys::event::FromBtEventByName>::try_from_event(event).ok()
+            }
+        }
+
+        impl bt2_sys::event::FromBtEventByName for #name {
+            fn event_names() -> Vec<&'static str> {
+                #[allow(unused_mut)]
+                let mut names = vec![#(#event_names),*];
+                #(#flatten_names)*
+                names
+            }
+
+            fn try_from_event(
+                event: &bt2_sys::event::BtEventConst,
+            ) -> Result<Self, bt2_sys::event::EventConversionError> {
+                let event_class = event.get_class();
+                let Some(name) = event_class.get_name() else {
+                    return Err(bt2_sys::event::EventConversionError::Unnamed);
+                };
+
+                match name {
+                    #(#event_arms)*
+                    _ => {
+                        #(#flatten_attempts)*
+                        Err(bt2_sys::event::EventConversionError::unknown_event(name))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn parse_variant(variant: &Variant) -> syn::Result<EventVariant<'_>> {
+    let ty = match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Only tuple variants with a single field are supported",
+            ))
+        }
+    };
+
+    let mut parsed = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("bt2"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if parsed.is_some() {
+                return Err(meta.error("Only one of `event` or `flatten` is allowed"));
+            }
+
+            if meta.path.is_ident("event") {
+                let name: LitStr = meta.value()?.parse()?;
+                if !name.value().contains(':') {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        "The event name must have the form `provider:name`",
+                    ));
+                }
+                parsed = Some(EventVariant::Event { variant, ty, name });
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                parsed = Some(EventVariant::Flatten { variant, ty });
+                Ok(())
+            } else {
+                Err(meta.error("unknown attribute"))
+            }
+        })?;
+    }
+
+    parsed.ok_or_else(|| {
+        syn::Error::new_spanned(
+            variant,
+            "Missing `#[bt2(event = \"provider:name\")]` or `#[bt2(flatten)]` attribute",
+        )
+    })
+}
@@ -1,11 +1,35 @@
+use std::ffi::CString;
 use std::str::FromStr;
 
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, Ident};
+use syn::{
+    parse_macro_input, Data, DataEnum, DeriveInput, Field, Fields, Ident, LitCStr, LitStr,
+    PathArguments, Type,
+};
 
+mod from_bt_event;
+
+/// Derive `TryFrom<BtFieldConst>`.
+///
+/// - Structs with named fields are converted from structure fields, member by member.
+/// - Enums with only unit variants are converted from enumeration fields using the mapping labels.
+/// - Other enums are converted from variant fields using the name of the selected option.
+///   Unit variants ignore the option content, newtype variants convert it, and struct variants
+///   convert it from a structure field.
+///
+/// Struct members which are missing or empty options are converted to `None` for `Option<T>`
+/// fields.
+///
+/// # Attributes
+/// - `#[bt2(rename = "name")]` on a field or variant: use `name` as the member, label, or option
+///   name instead of the Rust identifier.
+/// - `#[bt2(default)]` or `#[bt2(default = "path::to::fn")]` on a field: value to use when the
+///   member is missing.
+/// - `#[bt2(try_from = bool|i64|u64|String|array)]` on a field: type of the member.
+/// - `#[bt2(is_not_zero)]` on a field: convert an integer member into `bool`.
 #[proc_macro_derive(TryFromBtFieldConst, attributes(bt2))]
 pub fn derive_try_from_bt_field_const(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -13,75 +37,218 @@ pub fn derive_try_from_bt_field_const(input: TokenStream) -> TokenStream {
 
     let expanded = match &input.data {
         Data::Struct(data_struct) => impl_from_bt_field_const(name, &data_struct.fields),
-        _ => syn::Error::new_spanned(input, "Only structs are supported").to_compile_error(),
+        Data::Enum(data_enum) => impl_enum_from_bt_field_const(name, data_enum),
+        Data::Union(_) => {
+            syn::Error::new_spanned(input, "Unions are not supported").to_compile_error()
+        }
     };
 
     TokenStream::from(expanded)
 }
 
+/// Derive `FromBtEvent` and `FromBtEventByName` for an enum of events.
+///
+/// Each variant has a single field and one of the attributes:
+/// - `#[bt2(event = "provider:name")]`: the event with this full name is converted into the field
+///   with its `FromBtEvent` implementation.
+/// - `#[bt2(flatten)]`: the field is an event enum itself, which is tried for all other events.
+///
+/// Events matching no variant are rejected with `EventConversionError::UnknownEvent`.
+#[proc_macro_derive(FromBtEvent, attributes(bt2))]
+pub fn derive_from_bt_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(from_bt_event::impl_from_bt_event(&input))
+}
+
 fn impl_from_bt_field_const(name: &Ident, fields: &Fields) -> proc_macro2::TokenStream {
+    if let Fields::Unnamed(_) = fields {
+        return syn::Error::new_spanned(fields, "Tuple structs are not supported")
+            .to_compile_error();
+    }
+
+    let construction = struct_construction(&quote! { Self }, fields);
+
+    quote_spanned! {fields.span()=>
+        impl TryFrom<bt2_sys::field::BtFieldConst> for #name {
+            type Error = bt2_sys::field::ConversionError;
+
+            fn try_from(bt_field: bt2_sys::field::BtFieldConst) -> Result<Self, bt2_sys::field::ConversionError> {
+                #[allow(unused_variables)]
+                let bt_field = bt_field.try_into_struct()?;
+                Ok(#construction)
+            }
+        }
+    }
+}
+
+// Generates the struct expression, expects `bt_field` to be a structure field.
+fn struct_construction(
+    path: &proc_macro2::TokenStream,
+    fields: &Fields,
+) -> proc_macro2::TokenStream {
     let field_conversions = fields.iter().map(|field| {
         let field_name = &field.ident;
         let field_conversion = generate_field_conversion(field);
         quote! { #field_name: #field_conversion }
     });
 
-    quote_spanned! {fields.span()=>
+    quote! {
+        #path {
+            #(#field_conversions),*
+        }
+    }
+}
+
+fn impl_enum_from_bt_field_const(name: &Ident, data_enum: &DataEnum) -> proc_macro2::TokenStream {
+    let labels = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let attributes = parse_attributes(&variant.attrs)?;
+            if attributes.try_from.is_some()
+                || attributes.is_not_zero
+                || attributes.default.is_some()
+            {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "Only the `rename` attribute is supported on enum variants",
+                ));
+            }
+
+            let label = attributes
+                .rename
+                .unwrap_or_else(|| LitStr::new(&variant.ident.to_string(), variant.ident.span()));
+            Ok((variant, c_str_literal(&label)?))
+        })
+        .collect::<syn::Result<Vec<_>>>();
+    let labels = match labels {
+        Ok(labels) => labels,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let body = if data_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        // Enumeration field: match the labels of the mappings containing the value
+        let matches = labels.iter().map(|(variant, label)| {
+            let ident = &variant.ident;
+            quote! {
+                if label == #label {
+                    return Ok(Self::#ident);
+                }
+            }
+        });
+
+        quote! {
+            let bt_field = bt_field.try_into_enumeration()?;
+            for label in bt_field.get_labels()? {
+                #(#matches)*
+            }
+
+            Err(bt2_sys::field::ConversionError::unknown_variant(&bt_field))
+        }
+    } else {
+        // Variant field: match the name of the selected option
+        let matches = labels.iter().map(|(variant, label)| {
+            let ident = &variant.ident;
+            let construction = match &variant.fields {
+                Fields::Unit => quote! { Self::#ident },
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                    Self::#ident(
+                        bt_field
+                            .get_selected_field()
+                            .try_into()
+                            .map_err(Into::<bt2_sys::field::ConversionError>::into)?,
+                    )
+                },
+                Fields::Unnamed(fields) => {
+                    return syn::Error::new_spanned(
+                        fields,
+                        "Only tuple variants with a single field are supported",
+                    )
+                    .to_compile_error();
+                }
+                Fields::Named(_) => {
+                    let construction =
+                        struct_construction(&quote! { Self::#ident }, &variant.fields);
+                    quote! {
+                        {
+                            #[allow(unused_variables)]
+                            let bt_field = bt_field.get_selected_field().try_into_struct()?;
+                            #construction
+                        }
+                    }
+                }
+            };
+
+            quote! {
+                if option == #label {
+                    return Ok(#construction);
+                }
+            }
+        });
+
+        quote! {
+            let bt_field = bt_field.try_into_variant()?;
+            let Some(option) = bt_field.get_selected_option_name() else {
+                return Err(bt2_sys::field::ConversionError::unknown_variant(
+                    format_args!("#{}", bt_field.get_selected_option_index()),
+                ));
+            };
+            #(#matches)*
+
+            Err(bt2_sys::field::ConversionError::unknown_variant(option.to_string_lossy()))
+        }
+    };
+
+    quote! {
         impl TryFrom<bt2_sys::field::BtFieldConst> for #name {
             type Error = bt2_sys::field::ConversionError;
 
-            fn try_from(bt_field: bt2_sys::field::BtFieldConst) -> Result<Self, Self::Error> {
-                let bt_field = bt_field.try_into_struct()?;
-                Ok(Self {
-                    #(#field_conversions),*
-                })
+            fn try_from(bt_field: bt2_sys::field::BtFieldConst) -> Result<Self, bt2_sys::field::ConversionError> {
+                #body
             }
         }
     }
 }
 
-// Generates the conversion code for each field, checking for a `try_from` attribute.
+// Generates the conversion code for each field, checking for the `bt2` attribute.
 fn generate_field_conversion(field: &Field) -> proc_macro2::TokenStream {
-    if field.ident.is_none() {
+    let Some(field_name) = &field.ident else {
         return syn::Error::new_spanned(field, "Tuple structs are not supported")
             .to_compile_error();
-    }
-    let field_name = field.ident.as_ref().unwrap();
+    };
     let field_span = field.span();
-    let field_name_str = format!(r#"c"{field_name}""#)
-        .parse::<proc_macro2::TokenStream>()
-        .unwrap();
-    let try_from_attr = field.attrs.iter().try_fold(None, |acc, attr| {
-        let parsed = parse_attribute(attr);
-        if acc.is_some() {
-            if !matches!(parsed, Ok(None)) {
-                return Err(syn::Error::new_spanned(
-                    attr,
-                    "Multiple `bt2` attributes are not allowed",
-                ));
-            }
-            return Ok(acc);
-        }
-        parsed
-    });
 
-    if let Err(e) = try_from_attr {
-        return e.to_compile_error();
-    }
+    let attributes = match parse_attributes(&field.attrs) {
+        Ok(attributes) => attributes,
+        Err(e) => return e.to_compile_error(),
+    };
+    let member_name = attributes
+        .rename
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&field_name.to_string(), field_name.span()));
+    let member_name_cstr = match c_str_literal(&member_name) {
+        Ok(name) => name,
+        Err(e) => return e.to_compile_error(),
+    };
 
-    let conversion = match try_from_attr.unwrap() {
-        Some(Conversion {
-            try_from,
+    let conversion = match attributes {
+        FieldAttributes {
+            try_from: Some(try_from),
             is_not_zero: true,
-        }) => match try_from {
+            ..
+        } => match try_from {
             TryFromType::Bool => {
-                let conversion = convert(TryFromType::Bool, field_name, field_span);
+                let conversion = convert(TryFromType::Bool, &member_name, field_span);
                 quote_spanned! {field_span=>
                     {#conversion}.get_value()
                 }
             }
             TryFromType::Int | TryFromType::Uint => {
-                let conversion = convert(try_from, field_name, field_span);
+                let conversion = convert(try_from, &member_name, field_span);
                 quote_spanned! {field_span=>
                     0 != {#conversion}.get_value()
                 }
@@ -95,61 +262,89 @@ fn generate_field_conversion(field: &Field) -> proc_macro2::TokenStream {
                     .into_compile_error()
             }
         },
-        Some(Conversion {
-            try_from: TryFromType::Array,
-            is_not_zero: false,
-        }) => {
-            let conversion = convert(TryFromType::Array, field_name, field_span);
+        FieldAttributes {
+            try_from: Some(TryFromType::Array),
+            ..
+        } => {
+            let conversion = convert(TryFromType::Array, &member_name, field_span);
             quote_spanned! {field_span=>
                 {#conversion}.try_into()?
             }
         }
-        Some(Conversion {
-            try_from,
-            is_not_zero: false,
-        }) => {
-            let conversion = convert(try_from, field_name, field_span);
+        FieldAttributes {
+            try_from: Some(try_from),
+            ..
+        } => {
+            let conversion = convert(try_from, &member_name, field_span);
             quote_spanned! {field_span=>
                 {#conversion}.get_value().try_into()?
             }
         }
-        None => default_field_conversion(field),
+        FieldAttributes { try_from: None, .. } => {
+            default_field_conversion(&member_name, field_span)
+        }
+    };
+
+    let is_option = is_option_type(&field.ty);
+    let conversion = if is_option {
+        quote_spanned! {field_span=> Some(#conversion) }
+    } else {
+        conversion
+    };
+
+    let missing = match &attributes.default {
+        Some(DefaultValue::Trait) => quote_spanned! {field_span=> Default::default() },
+        Some(DefaultValue::Path(path)) => quote_spanned! {field_span=> #path() },
+        None if is_option => quote_spanned! {field_span=> None },
+        None => quote_spanned! {field_span=>
+            return Err(bt2_sys::field::StructConversionError::field_not_found(#member_name).into())
+        },
     };
 
     quote_spanned! {field_span=>
+        match bt_field
+            .get_field_by_name_cstr(#member_name_cstr)
+            .and_then(bt2_sys::field::BtFieldConst::into_optional)
         {
-            let Some(bt_field) = bt_field.get_field_by_name_cstr(#field_name_str) else {
-                return Err(bt2_sys::field::StructConversionError::field_not_found(stringify!(#field_name)).into());
-            };
-            #conversion
+            Some(bt_field) => #conversion,
+            None => #missing,
         }
     }
 }
 
-fn convert(try_from: TryFromType, field_name: &Ident, span: Span) -> proc_macro2::TokenStream {
+fn convert(try_from: TryFromType, member_name: &LitStr, span: Span) -> proc_macro2::TokenStream {
     match try_from {
         TryFromType::Bool => {
-            quote_spanned! {span=> bt_field.try_into_bool().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))? }
+            quote_spanned! {span=> bt_field.try_into_bool().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))? }
         }
         TryFromType::Int => {
-            quote_spanned! {span=> bt_field.try_into_int().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))? }
+            quote_spanned! {span=> bt_field.try_into_int().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))? }
         }
         TryFromType::Uint => {
-            quote_spanned! {span=> bt_field.try_into_uint().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))? }
+            quote_spanned! {span=> bt_field.try_into_uint().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))? }
         }
         TryFromType::String => {
-            quote_spanned! {span=> bt_field.try_into_string().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))? }
+            quote_spanned! {span=> bt_field.try_into_string().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))? }
         }
         TryFromType::Array => {
-            quote_spanned! {span=> bt_field.try_into_array().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))? }
+            quote_spanned! {span=> bt_field.try_into_array().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))? }
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Conversion {
-    try_from: TryFromType,
+#[derive(Default)]
+struct FieldAttributes {
+    try_from: Option<TryFromType>,
     is_not_zero: bool,
+    rename: Option<LitStr>,
+    default: Option<DefaultValue>,
+}
+
+enum DefaultValue {
+    /// Use [`Default::default()`].
+    Trait,
+    /// Call the function at the path.
+    Path(syn::ExprPath),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -176,55 +371,82 @@ impl FromStr for TryFromType {
     }
 }
 
-fn parse_attribute(attr: &syn::Attribute) -> syn::Result<Option<Conversion>> {
-    if attr.path().is_ident("bt2") {
-        let mut try_from = None;
-        let mut is_not_zero = false;
-        attr.parse_nested_meta(|meta| {
-            if meta.path.is_ident("try_from") {
-                let expr: syn::Expr = meta.value()?.parse()?;
-                let ty = quote! { #expr }.to_string();
-
-                ty.parse().map_or_else(
-                    |()| Err(meta.error(format!("unknown bt2 type {ty:?}"))),
-                    |ty| {
-                        try_from = Some(ty);
-                        Ok(())
-                    },
-                )
-            } else if meta.path.is_ident("is_not_zero") {
-                is_not_zero = true;
-                Ok(())
-            } else {
-                Err(meta.error("unknown attribute"))
-            }
-        })?;
+fn parse_attributes(attrs: &[syn::Attribute]) -> syn::Result<FieldAttributes> {
+    let mut bt2_attrs = attrs.iter().filter(|attr| attr.path().is_ident("bt2"));
+    let Some(attr) = bt2_attrs.next() else {
+        return Ok(FieldAttributes::default());
+    };
+    if let Some(attr) = bt2_attrs.next() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "Multiple `bt2` attributes are not allowed",
+        ));
+    }
 
-        if is_not_zero && try_from.is_none() {
-            Err(syn::Error::new_spanned(
-                attr,
-                "The `is_non_zero` attribute requires a `try_from` attribute to determine the field type",
-            ))
+    let mut attributes = FieldAttributes::default();
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("try_from") {
+            let expr: syn::Expr = meta.value()?.parse()?;
+            let ty = quote! { #expr }.to_string();
+
+            ty.parse().map_or_else(
+                |()| Err(meta.error(format!("unknown bt2 type {ty:?}"))),
+                |ty| {
+                    attributes.try_from = Some(ty);
+                    Ok(())
+                },
+            )
+        } else if meta.path.is_ident("is_not_zero") {
+            attributes.is_not_zero = true;
+            Ok(())
+        } else if meta.path.is_ident("rename") {
+            attributes.rename = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("default") {
+            attributes.default = Some(if meta.input.peek(syn::Token![=]) {
+                let path: LitStr = meta.value()?.parse()?;
+                DefaultValue::Path(path.parse()?)
+            } else {
+                DefaultValue::Trait
+            });
+            Ok(())
         } else {
-            Ok(Some(Conversion {
-                try_from: try_from.unwrap_or(TryFromType::String),
-                is_not_zero,
-            }))
+            Err(meta.error("unknown attribute"))
         }
+    })?;
+
+    if attributes.is_not_zero && attributes.try_from.is_none() {
+        Err(syn::Error::new_spanned(
+            attr,
+            "The `is_not_zero` attribute requires a `try_from` attribute to determine the field type",
+        ))
     } else {
-        Ok(None)
+        Ok(attributes)
     }
 }
 
-// Provides default conversion based on the field type when no `try_from` attribute is given.
-fn default_field_conversion(field: &Field) -> proc_macro2::TokenStream {
-    let Some(field_name) = &field.ident else {
-        return syn::Error::new_spanned(field, "Tuple structs are not supported")
-            .to_compile_error();
+fn c_str_literal(name: &LitStr) -> syn::Result<LitCStr> {
+    let value = CString::new(name.value())
+        .map_err(|_| syn::Error::new_spanned(name, "Names cannot contain a nul byte"))?;
+    Ok(LitCStr::new(&value, name.span()))
+}
+
+// Whether the field type is `Option<T>`, which is `None` when the member is missing.
+fn is_option_type(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
     };
-    let field_span = field.span();
 
+    path.qself.is_none()
+        && path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Option"
+                && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+        })
+}
+
+// Provides default conversion based on the field type when no `try_from` attribute is given.
+fn default_field_conversion(member_name: &LitStr, field_span: Span) -> proc_macro2::TokenStream {
     quote_spanned! {field_span=>
-        bt_field.try_into().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(stringify!(#field_name), e))?
+        bt_field.try_into().map_err(|e| bt2_sys::field::StructConversionError::field_conversion_error(#member_name, e))?
     }
 }
@@ -0,0 +1,286 @@
+//! Conversion of events emitted by a Rust source component with the derived implementations.
+
+use std::cell::RefCell;
+
+use bt2_derive::{FromBtEvent, TryFromBtFieldConst};
+use bt2_sys::error::{BtError, BtResult};
+use bt2_sys::event::{BtEventClass, EventConversionError, FromBtEventByName};
+use bt2_sys::field::{BtFieldClass, BtFieldClassStructure};
+use bt2_sys::graph::user_component::{
+    BtComponentClassSink, BtComponentClassSource, ComponentMessageIterator, MessageOutput,
+    SelfComponentPortOutput, SelfComponentSink, SelfComponentSource, SelfMessageIterator,
+    SinkComponent, SourceComponent, UpstreamMessageIterator,
+};
+use bt2_sys::graph::BtGraph;
+use bt2_sys::logging::LogLevel;
+use bt2_sys::message::{BtMessageConst, BtMessageType};
+use bt2_sys::stream::BtStream;
+use bt2_sys::value::BtValueConst;
+
+#[derive(Debug, PartialEq, TryFromBtFieldConst)]
+struct Value {
+    value: u64,
+    #[bt2(rename = "label")]
+    name: String,
+    missing: Option<u64>,
+    #[bt2(default = "default_unit")]
+    unit: String,
+}
+
+#[derive(Debug, PartialEq, TryFromBtFieldConst)]
+struct Flag {
+    #[bt2(try_from = u64, is_not_zero)]
+    flag: bool,
+    #[bt2(try_from = u64)]
+    small: u8,
+}
+
+#[derive(Debug, PartialEq, FromBtEvent)]
+enum FlagEvents {
+    #[bt2(event = "test:flag")]
+    Flag(Flag),
+}
+
+#[derive(Debug, PartialEq, FromBtEvent)]
+enum Events {
+    #[bt2(event = "test:value")]
+    Value(Value),
+    #[bt2(flatten)]
+    Flags(FlagEvents),
+}
+
+fn default_unit() -> String {
+    "ms".into()
+}
+
+/// Source emitting a `test:value`, a `test:flag` and an `other:unknown` event.
+struct EventSource;
+
+struct EventIterator {
+    stream: BtStream,
+    value_class: BtEventClass,
+    flag_class: BtEventClass,
+    unknown_class: BtEventClass,
+    /// Index of the next message.
+    next: u64,
+}
+
+impl SourceComponent for EventSource {
+    type Iterator = EventIterator;
+
+    fn initialize(component: &mut SelfComponentSource, _params: BtValueConst) -> BtResult<Self> {
+        component.add_output_port(c"out")?;
+        Ok(Self)
+    }
+}
+
+impl ComponentMessageIterator for EventIterator {
+    type Component = EventSource;
+
+    fn initialize(
+        _component: &EventSource,
+        iterator: &mut SelfMessageIterator,
+        _port: SelfComponentPortOutput,
+    ) -> BtResult<Self> {
+        let mut trace_class = iterator.create_trace_class()?;
+        let mut stream_class = trace_class.create_stream_class()?;
+
+        let mut value_payload = BtFieldClassStructure::new(&mut trace_class)?;
+        value_payload.append_member(
+            c"value",
+            &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+        )?;
+        value_payload.append_member(c"label", &BtFieldClass::new_string(&mut trace_class)?)?;
+        let mut value_class = stream_class.create_event_class()?;
+        value_class.set_name(c"test:value")?;
+        value_class.set_payload_field_class(&value_payload)?;
+
+        let mut flag_payload = BtFieldClassStructure::new(&mut trace_class)?;
+        flag_payload.append_member(
+            c"flag",
+            &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+        )?;
+        flag_payload.append_member(
+            c"small",
+            &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+        )?;
+        let mut flag_class = stream_class.create_event_class()?;
+        flag_class.set_name(c"test:flag")?;
+        flag_class.set_payload_field_class(&flag_payload)?;
+
+        let mut unknown_payload = BtFieldClassStructure::new(&mut trace_class)?;
+        unknown_payload.append_member(
+            c"value",
+            &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+        )?;
+        let mut unknown_class = stream_class.create_event_class()?;
+        unknown_class.set_name(c"other:unknown")?;
+        unknown_class.set_payload_field_class(&unknown_payload)?;
+
+        let mut trace = trace_class.create_trace()?;
+        let stream = stream_class.create_stream(&mut trace)?;
+
+        Ok(Self {
+            stream,
+            value_class,
+            flag_class,
+            unknown_class,
+            next: 0,
+        })
+    }
+
+    fn next(
+        &mut self,
+        iterator: &mut SelfMessageIterator,
+        output: &mut MessageOutput,
+    ) -> BtResult<()> {
+        while !output.is_full() {
+            let message = match self.next {
+                0 => iterator.create_stream_beginning_message(&self.stream.as_const())?,
+                1 => {
+                    let mut message =
+                        iterator.create_event_message(&self.value_class, &self.stream, None)?;
+                    let mut payload = message.get_payload_mut().unwrap();
+                    payload
+                        .get_field_by_name_cstr(c"value")
+                        .unwrap()
+                        .set_uint(42);
+                    payload
+                        .get_field_by_name_cstr(c"label")
+                        .unwrap()
+                        .set_string(c"answer")?;
+                    message.into()
+                }
+                2 => {
+                    let mut message =
+                        iterator.create_event_message(&self.flag_class, &self.stream, None)?;
+                    let mut payload = message.get_payload_mut().unwrap();
+                    payload.get_field_by_name_cstr(c"flag").unwrap().set_uint(3);
+                    payload
+                        .get_field_by_name_cstr(c"small")
+                        .unwrap()
+                        .set_uint(7);
+                    message.into()
+                }
+                3 => {
+                    let mut message =
+                        iterator.create_event_message(&self.unknown_class, &self.stream, None)?;
+                    message
+                        .get_payload_mut()
+                        .unwrap()
+                        .get_field_by_name_cstr(c"value")
+                        .unwrap()
+                        .set_uint(1);
+                    message.into()
+                }
+                4 => iterator.create_stream_end_message(&self.stream.as_const())?,
+                _ => return Err(BtError::End),
+            };
+            self.next += 1;
+            if output.push(message).is_err() {
+                unreachable!("The output is not full");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+thread_local! {
+    static CONVERTED: RefCell<Vec<Result<Events, EventConversionError>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Sink converting the received events into `CONVERTED`.
+struct ConvertingSink {
+    upstream: Option<UpstreamMessageIterator>,
+}
+
+impl SinkComponent for ConvertingSink {
+    fn initialize(component: &mut SelfComponentSink, _params: BtValueConst) -> BtResult<Self> {
+        component.add_input_port(c"in")?;
+        Ok(Self { upstream: None })
+    }
+
+    fn graph_is_configured(&mut self, component: &mut SelfComponentSink) -> BtResult<()> {
+        self.upstream = Some(component.create_message_iterator(component.input_port(0))?);
+        Ok(())
+    }
+
+    fn consume(&mut self, _component: &mut SelfComponentSink) -> BtResult<()> {
+        let mut messages: Vec<BtMessageConst> = Vec::new();
+        self.upstream.as_mut().unwrap().next_into(&mut messages)?;
+        CONVERTED.with_borrow_mut(|converted| {
+            for message in messages {
+                if message.get_type() == BtMessageType::Event {
+                    let message = message.into_event_msg();
+                    converted.push(Events::try_from_event(&message.get_event()));
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Run the event source into the converting sink and return the conversion results.
+fn convert_events() -> Vec<Result<Events, EventConversionError>> {
+    let source_class = BtComponentClassSource::new::<EventSource>(c"events").unwrap();
+    let sink_class = BtComponentClassSink::new::<ConvertingSink>(c"converting").unwrap();
+
+    let mut builder = BtGraph::builder().unwrap();
+    unsafe {
+        let source = builder
+            .add_source_component_unchecked(
+                source_class.as_const(),
+                c"source",
+                None,
+                LogLevel::Warning,
+            )
+            .unwrap();
+        let sink = builder
+            .add_sink_component_unchecked(sink_class.as_const(), c"sink", None, LogLevel::Warning)
+            .unwrap();
+        builder
+            .connect_ports_unchecked(source.get_output_port(0), sink.get_input_port(0))
+            .unwrap();
+    }
+
+    let mut graph = builder.build();
+    // A failing run leaves events out of the results
+    unsafe { graph.run() };
+
+    CONVERTED.take()
+}
+
+#[test]
+fn test_event_names() {
+    assert_eq!(FlagEvents::event_names(), ["test:flag"]);
+    assert_eq!(Events::event_names(), ["test:value", "test:flag"]);
+}
+
+#[test]
+fn test_event_dispatch() {
+    let mut converted = convert_events().into_iter();
+
+    assert_eq!(
+        converted.next().unwrap().unwrap(),
+        Events::Value(Value {
+            value: 42,
+            name: "answer".into(),
+            missing: None,
+            unit: "ms".into(),
+        })
+    );
+    assert_eq!(
+        converted.next().unwrap().unwrap(),
+        Events::Flags(FlagEvents::Flag(Flag {
+            flag: true,
+            small: 7,
+        }))
+    );
+    assert!(matches!(
+        converted.next().unwrap(),
+        Err(EventConversionError::UnknownEvent(name)) if name == "other:unknown"
+    ));
+    assert!(converted.next().is_none());
+}
@@ -0,0 +1,9 @@
+//! Expansion of the derive macros: supported inputs compile, invalid ones are rejected
+//! with the expected error.
+
+#[test]
+fn test_ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,10 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+enum State {
+    #[bt2(default)]
+    Idle,
+    Running,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use bt2_derive::FromBtEvent;
+
+#[derive(FromBtEvent)]
+enum Events {
+    #[bt2(event = "app:tick", flatten)]
+    Tick(u64),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use bt2_derive::FromBtEvent;
+
+#[derive(FromBtEvent)]
+enum Events {
+    Tick(u64),
+}
+
+fn main() {}
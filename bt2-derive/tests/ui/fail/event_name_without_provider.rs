@@ -0,0 +1,9 @@
+use bt2_derive::FromBtEvent;
+
+#[derive(FromBtEvent)]
+enum Events {
+    #[bt2(event = "tick")]
+    Tick(u64),
+}
+
+fn main() {}
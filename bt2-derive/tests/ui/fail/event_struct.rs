@@ -0,0 +1,8 @@
+use bt2_derive::FromBtEvent;
+
+#[derive(FromBtEvent)]
+struct Tick {
+    count: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+struct Flags {
+    #[bt2(is_not_zero)]
+    enabled: bool,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+struct Sample {
+    #[bt2(rename = "v")]
+    #[bt2(default)]
+    value: u64,
+}
+
+fn main() {}
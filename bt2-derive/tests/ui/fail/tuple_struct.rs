@@ -0,0 +1,6 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+struct Sample(u64);
+
+fn main() {}
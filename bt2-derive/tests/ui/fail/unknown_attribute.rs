@@ -0,0 +1,9 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+struct Sample {
+    #[bt2(skip)]
+    value: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+struct Sample {
+    #[bt2(try_from = f64)]
+    value: f64,
+}
+
+fn main() {}
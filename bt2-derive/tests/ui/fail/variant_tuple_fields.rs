@@ -0,0 +1,9 @@
+use bt2_derive::TryFromBtFieldConst;
+
+#[derive(TryFromBtFieldConst)]
+enum Shape {
+    Empty,
+    Segment(u64, u64),
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use bt2_derive::TryFromBtFieldConst;
+use bt2_sys::field::{BtFieldConst, ConversionError};
+
+#[derive(TryFromBtFieldConst)]
+enum State {
+    Idle,
+    #[bt2(rename = "RUNNING")]
+    Running,
+}
+
+fn assert_try_from<T: TryFrom<BtFieldConst, Error = ConversionError>>() {}
+
+fn main() {
+    assert_try_from::<State>();
+}
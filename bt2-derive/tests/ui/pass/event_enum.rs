@@ -0,0 +1,33 @@
+use bt2_derive::{FromBtEvent, TryFromBtFieldConst};
+use bt2_sys::event::FromBtEventByName;
+
+#[derive(TryFromBtFieldConst)]
+struct Start {
+    id: u64,
+}
+
+#[derive(TryFromBtFieldConst)]
+struct Stop {
+    id: u64,
+}
+
+#[derive(FromBtEvent)]
+enum Lifecycle {
+    #[bt2(event = "app:start")]
+    Start(Start),
+    #[bt2(event = "app:stop")]
+    Stop(Stop),
+}
+
+#[derive(FromBtEvent)]
+enum Events {
+    #[bt2(event = "sys:tick")]
+    Tick(Start),
+    #[bt2(flatten)]
+    Lifecycle(Lifecycle),
+}
+
+fn main() {
+    assert_eq!(Lifecycle::event_names(), ["app:start", "app:stop"]);
+    assert_eq!(Events::event_names(), ["sys:tick", "app:start", "app:stop"]);
+}
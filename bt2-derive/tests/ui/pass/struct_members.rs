@@ -0,0 +1,42 @@
+use bt2_derive::TryFromBtFieldConst;
+use bt2_sys::field::{BtFieldConst, ConversionError};
+
+#[derive(TryFromBtFieldConst)]
+struct Members {
+    inferred: u64,
+    #[bt2(rename = "member-name")]
+    renamed: String,
+    missing: Option<i64>,
+    #[bt2(default)]
+    defaulted: u32,
+    #[bt2(default = "default_count")]
+    default_path: u64,
+    #[bt2(try_from = bool)]
+    from_bool: bool,
+    #[bt2(try_from = i64)]
+    from_int: i32,
+    #[bt2(try_from = u64)]
+    from_uint: u8,
+    #[bt2(try_from = String)]
+    from_string: String,
+    #[bt2(try_from = array)]
+    from_array: Vec<u64>,
+    #[bt2(try_from = u64, is_not_zero)]
+    flag: bool,
+    #[bt2(try_from = i64, is_not_zero, rename = "signed_flag")]
+    optional_flag: Option<bool>,
+}
+
+#[derive(TryFromBtFieldConst)]
+struct Empty {}
+
+fn default_count() -> u64 {
+    1
+}
+
+fn assert_try_from<T: TryFrom<BtFieldConst, Error = ConversionError>>() {}
+
+fn main() {
+    assert_try_from::<Members>();
+    assert_try_from::<Empty>();
+}
@@ -0,0 +1,29 @@
+use bt2_derive::TryFromBtFieldConst;
+use bt2_sys::field::{BtFieldConst, ConversionError};
+
+#[derive(TryFromBtFieldConst)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[derive(TryFromBtFieldConst)]
+enum Shape {
+    Empty,
+    #[bt2(rename = "circle")]
+    Radius(u64),
+    Polygon(Vec<u64>),
+    Point(Point),
+    Rectangle {
+        width: u64,
+        #[bt2(rename = "h")]
+        height: u64,
+        label: Option<String>,
+    },
+}
+
+fn assert_try_from<T: TryFrom<BtFieldConst, Error = ConversionError>>() {}
+
+fn main() {
+    assert_try_from::<Shape>();
+}
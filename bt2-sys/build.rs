@@ -12,6 +12,11 @@ fn generate_bindings() {
 
     // Tell cargo to look for Babeltrace2.
     println!("cargo:rustc-link-lib=babeltrace2");
+    // Headers for plugins implemented in Rust, see `bt2-sys/plugin.h`
+    println!(
+        "cargo:include={}/include",
+        env::var("CARGO_MANIFEST_DIR").unwrap()
+    );
 
     // Generate bindings
     let bindings = bindgen::Builder::default()
@@ -0,0 +1,10 @@
+use std::env;
+
+fn main() {
+    // The plugin descriptor is defined by the macros of the babeltrace2 plugin development header
+    cc::Build::new()
+        .file("plugin.c")
+        .include(env::var("DEP_BABELTRACE2_INCLUDE").unwrap())
+        .compile("plugin");
+    println!("cargo:rerun-if-changed=plugin.c");
+}
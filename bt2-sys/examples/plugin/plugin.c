@@ -0,0 +1,17 @@
+/*
+ * Descriptor of the `rust` plugin. The component classes are implemented in `src/lib.rs`.
+ */
+
+#include <babeltrace2/plugin/plugin-dev.h>
+#include <bt2-sys/plugin.h>
+
+BT_PLUGIN_MODULE();
+
+BT_PLUGIN(rust);
+BT_PLUGIN_DESCRIPTION("Example components implemented in Rust");
+
+BT2_SYS_SOURCE_COMPONENT_CLASS(counter, rust_counter);
+BT_PLUGIN_SOURCE_COMPONENT_CLASS_DESCRIPTION(counter, "Emit `counter:tick` events");
+
+BT2_SYS_FILTER_COMPONENT_CLASS(even, rust_even);
+BT_PLUGIN_FILTER_COMPONENT_CLASS_DESCRIPTION(even, "Drop the events with an odd `value` member");
@@ -0,0 +1,204 @@
+//! Example babeltrace2 plugin with components implemented in Rust.
+//!
+//! The `source.rust.counter` component emits `counter:tick` events with an increasing `value`
+//! member, one per millisecond. The number of events is set by its `count` parameter.
+//! The `filter.rust.even` component drops the events with an odd `value` member.
+
+use std::collections::VecDeque;
+
+use bt2_sys::error::{BtError, BtResult};
+use bt2_sys::event::BtEventClass;
+use bt2_sys::field::{BtFieldClass, BtFieldClassStructure};
+use bt2_sys::graph::user_component::{
+    ComponentMessageIterator, FilterComponent, MessageOutput, SelfComponentFilter,
+    SelfComponentPortOutput, SelfComponentSource, SelfMessageIterator, SourceComponent,
+    UpstreamMessageIterator,
+};
+use bt2_sys::message::{BtMessageConst, BtMessageType};
+use bt2_sys::stream::BtStream;
+use bt2_sys::value::BtValueConst;
+
+bt2_sys::export_plugin_module!();
+bt2_sys::export_source_component_class!("rust_counter", Counter);
+bt2_sys::export_filter_component_class!("rust_even", Even);
+
+const DEFAULT_COUNT: u64 = 10;
+
+/// Source of `counter:tick` events.
+struct Counter {
+    count: u64,
+}
+
+impl SourceComponent for Counter {
+    type Iterator = CounterIterator;
+
+    fn initialize(component: &mut SelfComponentSource, params: BtValueConst) -> BtResult<Self> {
+        let count = params
+            .try_into_map()
+            .and_then(|params| params.decode_entry::<Option<u64>>(c"count"))
+            .map_err(|e| BtError::new(&format!("Invalid parameters: {e}")))?
+            .unwrap_or(DEFAULT_COUNT);
+        component.add_output_port(c"out")?;
+
+        Ok(Self { count })
+    }
+}
+
+/// Messages of a stream of one trace: the beginning, the events and the end.
+struct CounterIterator {
+    stream: BtStream,
+    event_class: BtEventClass,
+    count: u64,
+    /// Index of the next message.
+    next: u64,
+}
+
+impl ComponentMessageIterator for CounterIterator {
+    type Component = Counter;
+
+    fn initialize(
+        component: &Counter,
+        iterator: &mut SelfMessageIterator,
+        _port: SelfComponentPortOutput,
+    ) -> BtResult<Self> {
+        let mut trace_class = iterator.create_trace_class()?;
+        let mut clock_class = iterator.create_clock_class()?;
+        clock_class.set_name(c"counter")?;
+        clock_class.set_frequency(1_000);
+
+        let mut stream_class = trace_class.create_stream_class()?;
+        stream_class.set_default_clock_class(&clock_class);
+
+        let mut payload_class = BtFieldClassStructure::new(&mut trace_class)?;
+        payload_class.append_member(
+            c"value",
+            &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+        )?;
+        let mut event_class = stream_class.create_event_class()?;
+        event_class.set_name(c"counter:tick")?;
+        event_class.set_payload_field_class(&payload_class)?;
+
+        // The stream keeps its class and trace alive
+        let mut trace = trace_class.create_trace()?;
+        trace.set_name(c"counter")?;
+        let stream = stream_class.create_stream(&mut trace)?;
+
+        Ok(Self {
+            stream,
+            event_class,
+            count: component.count,
+            next: 0,
+        })
+    }
+
+    fn next(
+        &mut self,
+        iterator: &mut SelfMessageIterator,
+        output: &mut MessageOutput,
+    ) -> BtResult<()> {
+        while !output.is_full() {
+            let message = match self.next {
+                0 => iterator.create_stream_beginning_message(&self.stream.as_const())?,
+                n if n <= self.count => {
+                    let value = n - 1;
+                    let mut message = iterator.create_event_message(
+                        &self.event_class,
+                        &self.stream,
+                        Some(value),
+                    )?;
+                    message
+                        .get_payload_mut()
+                        .unwrap()
+                        .get_field_by_name_cstr(c"value")
+                        .unwrap()
+                        .set_uint(value);
+                    message.into()
+                }
+                n if n == self.count + 1 => {
+                    iterator.create_stream_end_message(&self.stream.as_const())?
+                }
+                _ => return Err(BtError::End),
+            };
+            self.next += 1;
+            if output.push(message).is_err() {
+                unreachable!("The output is not full");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Filter dropping the events with an odd `value` member.
+struct Even;
+
+impl FilterComponent for Even {
+    type Iterator = EvenIterator;
+
+    fn initialize(component: &mut SelfComponentFilter, _params: BtValueConst) -> BtResult<Self> {
+        component.add_input_port(c"in")?;
+        component.add_output_port(c"out")?;
+        Ok(Self)
+    }
+}
+
+struct EvenIterator {
+    upstream: UpstreamMessageIterator,
+    pending: VecDeque<BtMessageConst>,
+}
+
+impl ComponentMessageIterator for EvenIterator {
+    type Component = Even;
+
+    fn initialize(
+        _component: &Even,
+        iterator: &mut SelfMessageIterator,
+        _port: SelfComponentPortOutput,
+    ) -> BtResult<Self> {
+        Ok(Self {
+            upstream: iterator.create_message_iterator(iterator.input_port(0))?,
+            pending: VecDeque::new(),
+        })
+    }
+
+    fn next(
+        &mut self,
+        _iterator: &mut SelfMessageIterator,
+        output: &mut MessageOutput,
+    ) -> BtResult<()> {
+        if self.pending.is_empty() {
+            self.upstream.next_into(&mut self.pending)?;
+        }
+
+        while !output.is_full() {
+            let Some(message) = self.pending.pop_front() else {
+                break;
+            };
+            if is_odd(&message) {
+                continue;
+            }
+            if output.push(message).is_err() {
+                unreachable!("The output is not full");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the message is an event with an odd `value` member.
+fn is_odd(message: &BtMessageConst) -> bool {
+    if message.get_type() != BtMessageType::Event {
+        return false;
+    }
+
+    let message = message.clone().into_event_msg();
+    let Some(payload) = message.get_event().get_payload() else {
+        return false;
+    };
+    payload
+        .into_struct()
+        .get_field_by_name("value")
+        .and_then(|value| u64::try_from(value).ok())
+        .is_some_and(|value| value % 2 == 1)
+}
@@ -0,0 +1,86 @@
+/*
+ * Register component classes implemented in Rust in a babeltrace2 plugin.
+ *
+ * The methods of the components are exported from Rust by the
+ * `export_source_component_class!`, `export_filter_component_class!` and
+ * `export_sink_component_class!` macros of the bt2-sys crate with its `plugin` feature.
+ * `_prefix` must be the prefix given to the macro and `_name` the name of the component class.
+ */
+
+#ifndef BT2_SYS_PLUGIN_H
+#define BT2_SYS_PLUGIN_H
+
+#include <babeltrace2/babeltrace.h>
+#include <babeltrace2/plugin/plugin-dev.h>
+
+/*
+ * Shared objects built by Rust export only the symbols defined in Rust. The functions defined by
+ * `BT_PLUGIN_MODULE()`, by which babeltrace2 finds the plugin descriptors, are renamed here and
+ * exported under their original names by the `export_plugin_module!` macro of the bt2-sys crate.
+ */
+#define __bt_get_begin_section_plugin_descriptors bt2_sys_get_begin_section_plugin_descriptors
+#define __bt_get_end_section_plugin_descriptors bt2_sys_get_end_section_plugin_descriptors
+#define __bt_get_begin_section_plugin_descriptor_attributes \
+    bt2_sys_get_begin_section_plugin_descriptor_attributes
+#define __bt_get_end_section_plugin_descriptor_attributes \
+    bt2_sys_get_end_section_plugin_descriptor_attributes
+#define __bt_get_begin_section_component_class_descriptors \
+    bt2_sys_get_begin_section_component_class_descriptors
+#define __bt_get_end_section_component_class_descriptors \
+    bt2_sys_get_end_section_component_class_descriptors
+#define __bt_get_begin_section_component_class_descriptor_attributes \
+    bt2_sys_get_begin_section_component_class_descriptor_attributes
+#define __bt_get_end_section_component_class_descriptor_attributes \
+    bt2_sys_get_end_section_component_class_descriptor_attributes
+
+#define _BT2_SYS_MESSAGE_ITERATOR_METHODS(_prefix) \
+    bt_message_iterator_class_initialize_method_status _prefix##_iterator_initialize( \
+        bt_self_message_iterator *, bt_self_message_iterator_configuration *, \
+        bt_self_component_port_output *); \
+    bt_message_iterator_class_next_method_status _prefix##_iterator_next( \
+        bt_self_message_iterator *, bt_message_array_const, uint64_t, uint64_t *); \
+    void _prefix##_iterator_finalize(bt_self_message_iterator *);
+
+#define BT2_SYS_SOURCE_COMPONENT_CLASS(_name, _prefix) \
+    bt_component_class_initialize_method_status _prefix##_initialize( \
+        bt_self_component_source *, bt_self_component_source_configuration *, \
+        const bt_value *, void *); \
+    void _prefix##_finalize(bt_self_component_source *); \
+    _BT2_SYS_MESSAGE_ITERATOR_METHODS(_prefix) \
+    BT_PLUGIN_SOURCE_COMPONENT_CLASS(_name, _prefix##_iterator_next); \
+    BT_PLUGIN_SOURCE_COMPONENT_CLASS_INITIALIZE_METHOD(_name, _prefix##_initialize); \
+    BT_PLUGIN_SOURCE_COMPONENT_CLASS_FINALIZE_METHOD(_name, _prefix##_finalize); \
+    BT_PLUGIN_SOURCE_COMPONENT_CLASS_MESSAGE_ITERATOR_CLASS_INITIALIZE_METHOD( \
+        _name, _prefix##_iterator_initialize); \
+    BT_PLUGIN_SOURCE_COMPONENT_CLASS_MESSAGE_ITERATOR_CLASS_FINALIZE_METHOD( \
+        _name, _prefix##_iterator_finalize)
+
+#define BT2_SYS_FILTER_COMPONENT_CLASS(_name, _prefix) \
+    bt_component_class_initialize_method_status _prefix##_initialize( \
+        bt_self_component_filter *, bt_self_component_filter_configuration *, \
+        const bt_value *, void *); \
+    void _prefix##_finalize(bt_self_component_filter *); \
+    _BT2_SYS_MESSAGE_ITERATOR_METHODS(_prefix) \
+    BT_PLUGIN_FILTER_COMPONENT_CLASS(_name, _prefix##_iterator_next); \
+    BT_PLUGIN_FILTER_COMPONENT_CLASS_INITIALIZE_METHOD(_name, _prefix##_initialize); \
+    BT_PLUGIN_FILTER_COMPONENT_CLASS_FINALIZE_METHOD(_name, _prefix##_finalize); \
+    BT_PLUGIN_FILTER_COMPONENT_CLASS_MESSAGE_ITERATOR_CLASS_INITIALIZE_METHOD( \
+        _name, _prefix##_iterator_initialize); \
+    BT_PLUGIN_FILTER_COMPONENT_CLASS_MESSAGE_ITERATOR_CLASS_FINALIZE_METHOD( \
+        _name, _prefix##_iterator_finalize)
+
+#define BT2_SYS_SINK_COMPONENT_CLASS(_name, _prefix) \
+    bt_component_class_initialize_method_status _prefix##_initialize( \
+        bt_self_component_sink *, bt_self_component_sink_configuration *, \
+        const bt_value *, void *); \
+    void _prefix##_finalize(bt_self_component_sink *); \
+    bt_component_class_sink_graph_is_configured_method_status \
+        _prefix##_graph_is_configured(bt_self_component_sink *); \
+    bt_component_class_sink_consume_method_status _prefix##_consume(bt_self_component_sink *); \
+    BT_PLUGIN_SINK_COMPONENT_CLASS(_name, _prefix##_consume); \
+    BT_PLUGIN_SINK_COMPONENT_CLASS_INITIALIZE_METHOD(_name, _prefix##_initialize); \
+    BT_PLUGIN_SINK_COMPONENT_CLASS_FINALIZE_METHOD(_name, _prefix##_finalize); \
+    BT_PLUGIN_SINK_COMPONENT_CLASS_GRAPH_IS_CONFIGURED_METHOD( \
+        _name, _prefix##_graph_is_configured)
+
+#endif /* BT2_SYS_PLUGIN_H */
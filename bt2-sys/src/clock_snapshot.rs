@@ -1,7 +1,16 @@
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+use crate::error::OutOfMemory;
 use crate::raw_bindings::{
-    bt_clock_class, bt_clock_class_origin_is_unix_epoch, bt_clock_snapshot,
+    bt_clock_class, bt_clock_class_create, bt_clock_class_cycles_to_ns_from_origin,
+    bt_clock_class_cycles_to_ns_from_origin_status, bt_clock_class_get_description,
+    bt_clock_class_get_frequency, bt_clock_class_get_name, bt_clock_class_get_offset,
+    bt_clock_class_get_precision, bt_clock_class_origin_is_unix_epoch, bt_clock_class_put_ref,
+    bt_clock_class_set_frequency, bt_clock_class_set_name, bt_clock_class_set_name_status,
+    bt_clock_class_set_origin_is_unix_epoch, bt_clock_snapshot,
     bt_clock_snapshot_borrow_clock_class_const, bt_clock_snapshot_get_ns_from_origin,
-    bt_clock_snapshot_get_ns_from_origin_status, bt_clock_snapshot_get_value,
+    bt_clock_snapshot_get_ns_from_origin_status, bt_clock_snapshot_get_value, bt_self_component,
 };
 use crate::utils::ConstNonNull;
 
@@ -62,4 +71,133 @@ impl BtClockClassConst {
     pub fn origin_is_unix_epoch(&self) -> bool {
         0 != unsafe { bt_clock_class_origin_is_unix_epoch(self.as_ptr()) }
     }
+
+    #[must_use]
+    pub fn get_name(&self) -> Option<&CStr> {
+        let name = unsafe { bt_clock_class_get_name(self.as_ptr()) };
+        if name.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(name) })
+    }
+
+    #[must_use]
+    pub fn get_description(&self) -> Option<&CStr> {
+        let description = unsafe { bt_clock_class_get_description(self.as_ptr()) };
+        if description.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(description) })
+    }
+
+    /// Get the frequency of the clock in Hz.
+    #[must_use]
+    pub fn get_frequency(&self) -> u64 {
+        unsafe { bt_clock_class_get_frequency(self.as_ptr()) }
+    }
+
+    /// Get the precision of the clock in cycles.
+    #[must_use]
+    pub fn get_precision(&self) -> u64 {
+        unsafe { bt_clock_class_get_precision(self.as_ptr()) }
+    }
+
+    /// Get the offset of the clock from its origin as seconds and cycles.
+    #[must_use]
+    pub fn get_offset(&self) -> (i64, u64) {
+        let mut seconds = 0;
+        let mut cycles = 0;
+        unsafe { bt_clock_class_get_offset(self.as_ptr(), &mut seconds, &mut cycles) };
+        (seconds, cycles)
+    }
+
+    /// Convert a clock `value` in cycles to nanoseconds from the origin of the clock.
+    ///
+    /// Returns `None` if an overflow error occurred.
+    #[must_use]
+    pub fn cycles_to_ns_from_origin(&self, value: u64) -> Option<i64> {
+        let mut ns = 0;
+        let status =
+            unsafe { bt_clock_class_cycles_to_ns_from_origin(self.as_ptr(), value, &mut ns) };
+
+        match status {
+            bt_clock_class_cycles_to_ns_from_origin_status::BT_CLOCK_CLASS_CYCLES_TO_NS_FROM_ORIGIN_STATUS_OK => Some(ns),
+            bt_clock_class_cycles_to_ns_from_origin_status::BT_CLOCK_CLASS_CYCLES_TO_NS_FROM_ORIGIN_STATUS_OVERFLOW_ERROR => None,
+            status => unreachable!("Bug: Unknown bt_clock_class_cycles_to_ns_from_origin_status: {}", status.0),
+        }
+    }
+}
+
+impl std::fmt::Debug for BtClockClassConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(BtClockClassConst))
+            .field("name", &self.get_name())
+            .field("frequency", &self.get_frequency())
+            .field("offset", &self.get_offset())
+            .field("origin_is_unix_epoch", &self.origin_is_unix_epoch())
+            .finish()
+    }
+}
+
+/// Owned clock class created by a component implemented in Rust.
+///
+/// The default clock of the streams is set by [`BtStreamClass::set_default_clock_class`].
+///
+/// [`BtStreamClass::set_default_clock_class`]: crate::stream::BtStreamClass::set_default_clock_class
+pub struct BtClockClass(NonNull<bt_clock_class>);
+
+impl BtClockClass {
+    /// # Safety
+    /// The `self_component` must be a valid pointer.
+    pub(crate) unsafe fn new(self_component: *mut bt_self_component) -> Result<Self, OutOfMemory> {
+        NonNull::new(bt_clock_class_create(self_component))
+            .map(Self)
+            .ok_or(OutOfMemory)
+    }
+
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_clock_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtClockClassConst {
+        unsafe { BtClockClassConst::new_unchecked(ConstNonNull::new_unchecked(self.as_ptr())) }
+    }
+
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_name(&mut self, name: &CStr) -> Result<(), OutOfMemory> {
+        match unsafe { bt_clock_class_set_name(self.as_ptr(), name.as_ptr()) } {
+            bt_clock_class_set_name_status::BT_CLOCK_CLASS_SET_NAME_STATUS_OK => Ok(()),
+            bt_clock_class_set_name_status::BT_CLOCK_CLASS_SET_NAME_STATUS_MEMORY_ERROR => {
+                Err(OutOfMemory)
+            }
+            status => unreachable!("Bug: unknown bt_clock_class_set_name_status = {}", status.0),
+        }
+    }
+
+    /// Set the frequency of the clock in Hz. The default is 1 GHz.
+    ///
+    /// # Panics
+    /// If the `frequency` is zero.
+    pub fn set_frequency(&mut self, frequency: u64) {
+        assert_ne!(frequency, 0, "Clock frequency must not be zero");
+        unsafe { bt_clock_class_set_frequency(self.as_ptr(), frequency) }
+    }
+
+    /// Set whether the origin of the clock is the Unix epoch. The default is `true`.
+    pub fn set_origin_is_unix_epoch(&mut self, origin_is_unix_epoch: bool) {
+        unsafe {
+            bt_clock_class_set_origin_is_unix_epoch(self.as_ptr(), origin_is_unix_epoch.into());
+        }
+    }
+}
+
+impl Drop for BtClockClass {
+    fn drop(&mut self) {
+        unsafe { bt_clock_class_put_ref(self.as_ptr()) }
+    }
 }
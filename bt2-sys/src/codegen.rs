@@ -0,0 +1,843 @@
+//! Generation of Rust types for the events of a trace from its metadata.
+//!
+//! The generated code contains a struct deriving `TryFromBtFieldConst` for the payload of each
+//! event and an enum deriving `FromBtEvent` which dispatches on the event names.
+//! It is meant to be run by a build script on a reference trace, so that changes of the
+//! tracepoints cause compilation errors in the code using the generated types:
+//!
+//! ```no_run
+//! // build.rs
+//! use bt2_sys::codegen::EventCodeGenerator;
+//!
+//! let code = EventCodeGenerator::new("Event")
+//!     .provider("my_provider")
+//!     .derive("serde::Serialize")
+//!     .generate_from_trace(c"tests/reference_trace")
+//!     .unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{out_dir}/events.rs"), code).unwrap();
+//! ```
+//!
+//! The crate including the generated code, e.g., with
+//! `include!(concat!(env!("OUT_DIR"), "/events.rs"))`, must depend on `bt2-sys` and `bt2-derive`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::CStr;
+
+use thiserror::Error;
+
+use crate::error::BtError;
+use crate::field::{BtFieldClassConst, BtFieldClassType};
+use crate::iterator::{IteratorConstructionError, MessageIterator};
+use crate::logging::LogLevel;
+use crate::message::BtMessageConstCast;
+use crate::trace::BtTraceClassConst;
+
+/// Generator of the Rust types for the events of a trace.
+#[derive(Debug, Clone)]
+pub struct EventCodeGenerator {
+    enum_name: String,
+    providers: Vec<String>,
+    derives: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum CodeGenError {
+    #[error("Failed to open the trace: {0}")]
+    IteratorConstruction(#[from] IteratorConstructionError),
+
+    #[error("Failed to read the trace: {0}")]
+    Iteration(#[from] BtError),
+
+    #[error("The trace has no events of the selected providers")]
+    NoEvents,
+}
+
+impl EventCodeGenerator {
+    /// Create a generator of the event types and of the dispatch enum named `enum_name`.
+    #[must_use]
+    pub fn new(enum_name: &str) -> Self {
+        Self {
+            enum_name: enum_name.to_owned(),
+            providers: Vec::new(),
+            derives: Vec::new(),
+        }
+    }
+
+    /// Generate only the events of the `provider`.
+    ///
+    /// Can be called several times. The events of all providers are generated by default.
+    #[must_use]
+    pub fn provider(mut self, provider: &str) -> Self {
+        self.providers.push(provider.to_owned());
+        self
+    }
+
+    /// Derive the trait at `path`, e.g., `serde::Serialize`, for all generated types.
+    #[must_use]
+    pub fn derive(mut self, path: &str) -> Self {
+        self.derives.push(path.to_owned());
+        self
+    }
+
+    /// Generate the types for the events of the `trace_class`.
+    ///
+    /// # Errors
+    /// If the trace class has no events of the selected providers.
+    pub fn generate(&self, trace_class: &BtTraceClassConst) -> Result<String, CodeGenError> {
+        let mut events = BTreeMap::new();
+        self.collect_events(trace_class, &mut events);
+        self.emit(&events)
+    }
+
+    /// Generate the types for the events of all traces found in `trace_path`.
+    ///
+    /// All the messages of the traces are read, so a small reference trace should be used.
+    /// An event with the same name in several traces is generated from its first occurrence.
+    ///
+    /// # Errors
+    /// - If the traces cannot be read.
+    /// - If the traces have no events of the selected providers.
+    pub fn generate_from_trace(&self, trace_path: &CStr) -> Result<String, CodeGenError> {
+        let mut iterator = MessageIterator::try_new(&[trace_path], LogLevel::Warning)?;
+        let mut events = BTreeMap::new();
+        for message in iterator.try_iter() {
+            if let BtMessageConstCast::StreamBeginning(message) = message?.cast() {
+                let trace_class = message.get_stream().get_trace().get_class();
+                self.collect_events(&trace_class, &mut events);
+            }
+        }
+
+        self.emit(&events)
+    }
+
+    fn collect_events(
+        &self,
+        trace_class: &BtTraceClassConst,
+        events: &mut BTreeMap<String, Option<FieldLayout>>,
+    ) {
+        for event_class in trace_class
+            .stream_classes()
+            .flat_map(|stream_class| stream_class.event_classes().collect::<Vec<_>>())
+        {
+            let Some(name) = event_class.get_name() else {
+                continue;
+            };
+            let Some((provider, _)) = name.split_once(':') else {
+                continue;
+            };
+            if !self.providers.is_empty() && !self.providers.iter().any(|p| p == provider) {
+                continue;
+            }
+
+            events
+                .entry(name.to_owned())
+                .or_insert_with(|| event_class.get_payload_field_class().map(FieldLayout::new));
+        }
+    }
+
+    fn emit(&self, events: &BTreeMap<String, Option<FieldLayout>>) -> Result<String, CodeGenError> {
+        if events.is_empty() {
+            return Err(CodeGenError::NoEvents);
+        }
+
+        let mut emitter = Emitter {
+            generator: self,
+            items: Vec::new(),
+            type_names: BTreeSet::new(),
+        };
+        emitter.type_names.insert(self.enum_name.clone());
+
+        let mut variants = Vec::new();
+        for (full_name, payload) in events {
+            let (provider, name) = full_name.split_once(':').unwrap_or(("", full_name));
+            let mut type_name = type_ident(name);
+            if emitter.type_names.contains(&type_name) {
+                type_name = format!("{}{type_name}", type_ident(provider));
+            }
+            let type_name = emitter.unique_type_name(&type_name);
+
+            let members = match payload {
+                Some(FieldLayout::Structure(members)) => members.as_slice(),
+                _ => &[],
+            };
+            emitter.emit_struct(
+                &type_name,
+                &format!("Payload of the `{full_name}` event."),
+                full_name,
+                members,
+            );
+            variants.push((full_name, type_name));
+        }
+
+        let variants: String = variants
+            .into_iter()
+            .map(|(full_name, type_name)| {
+                format!("    #[bt2(event = {full_name:?})]\n    {type_name}({type_name}),\n")
+            })
+            .collect();
+        emitter.items.push(format!(
+            "/// Events of the trace, selected by their name.\n{}\npub enum {} {{\n{variants}}}\n",
+            self.derive_attribute(&["::bt2_derive::FromBtEvent"]),
+            self.enum_name,
+        ));
+
+        Ok(format!(
+            "// @generated by `bt2_sys::codegen` from the metadata of a trace. Do not edit.\n\n{}",
+            emitter.items.join("\n")
+        ))
+    }
+
+    fn derive_attribute(&self, derives: &[&str]) -> String {
+        let mut derives: Vec<_> = ["Debug", "Clone"]
+            .into_iter()
+            .chain(derives.iter().copied())
+            .chain(self.derives.iter().map(String::as_str))
+            .collect();
+        let mut seen = BTreeSet::new();
+        derives.retain(|derive| seen.insert(*derive));
+        format!("#[derive({})]", derives.join(", "))
+    }
+}
+
+/// Layout of a field, independent of the lifetime of the trace metadata.
+#[derive(Debug, Clone)]
+enum FieldLayout {
+    Bool,
+    UnsignedInteger {
+        bits: u64,
+    },
+    SignedInteger {
+        bits: u64,
+    },
+    String,
+    Structure(Vec<(String, FieldLayout)>),
+    Array {
+        element: Box<FieldLayout>,
+        length: Option<u64>,
+    },
+    Enumeration {
+        signed: bool,
+        bits: u64,
+        labels: Vec<String>,
+    },
+    Option(Box<FieldLayout>),
+    Variant(Vec<(Option<String>, FieldLayout)>),
+    Unsupported,
+}
+
+impl FieldLayout {
+    fn new(class: BtFieldClassConst) -> Self {
+        let Some(class_type) = class.get_type() else {
+            return Self::Unsupported;
+        };
+
+        match class_type {
+            BtFieldClassType::Bool => Self::Bool,
+            BtFieldClassType::UnsignedInteger => {
+                class
+                    .try_into_unsigned_integer()
+                    .map_or(Self::Unsupported, |class| Self::UnsignedInteger {
+                        bits: class.get_field_value_range(),
+                    })
+            }
+            BtFieldClassType::SignedInteger => {
+                class
+                    .try_into_signed_integer()
+                    .map_or(Self::Unsupported, |class| Self::SignedInteger {
+                        bits: class.get_field_value_range(),
+                    })
+            }
+            BtFieldClassType::String => Self::String,
+            BtFieldClassType::Structure => {
+                class.try_into_struct().map_or(Self::Unsupported, |class| {
+                    Self::Structure(
+                        (0..class.get_member_count())
+                            .map(|i| class.get_member_by_index(i))
+                            .map(|member| {
+                                (member.get_name().to_owned(), Self::new(member.get_class()))
+                            })
+                            .collect(),
+                    )
+                })
+            }
+            BtFieldClassType::Array => {
+                class
+                    .try_into_array()
+                    .map_or(Self::Unsupported, |class| Self::Array {
+                        element: Box::new(Self::new(class.get_element_class())),
+                        length: class.get_static_length(),
+                    })
+            }
+            BtFieldClassType::Enumeration => {
+                class
+                    .try_into_enumeration()
+                    .map_or(Self::Unsupported, |class| Self::Enumeration {
+                        signed: class.is_signed(),
+                        bits: class.get_field_value_range(),
+                        labels: class
+                            .mapping_labels()
+                            .map(|label| label.to_string_lossy().into_owned())
+                            .collect(),
+                    })
+            }
+            BtFieldClassType::Option => {
+                class.try_into_option().map_or(Self::Unsupported, |class| {
+                    Self::Option(Box::new(Self::new(class.get_field_class())))
+                })
+            }
+            BtFieldClassType::Variant => {
+                class.try_into_variant().map_or(Self::Unsupported, |class| {
+                    Self::Variant(
+                        class
+                            .options()
+                            .map(|option| {
+                                (
+                                    option
+                                        .get_name()
+                                        .map(|name| name.to_string_lossy().into_owned()),
+                                    Self::new(option.get_class()),
+                                )
+                            })
+                            .collect(),
+                    )
+                })
+            }
+            _ => Self::Unsupported,
+        }
+    }
+}
+
+struct Emitter<'a> {
+    generator: &'a EventCodeGenerator,
+    items: Vec<String>,
+    type_names: BTreeSet<String>,
+}
+
+impl Emitter<'_> {
+    fn unique_type_name(&mut self, name: &str) -> String {
+        let mut unique = name.to_owned();
+        let mut suffix = 2;
+        while !self.type_names.insert(unique.clone()) {
+            unique = format!("{name}{suffix}");
+            suffix += 1;
+        }
+        unique
+    }
+
+    /// Emit the struct named `name`, which must be unique already.
+    ///
+    /// The `path` of the field, e.g., `provider:event.member`, is used in the documentation.
+    fn emit_struct(
+        &mut self,
+        name: &str,
+        doc: &str,
+        path: &str,
+        members: &[(String, FieldLayout)],
+    ) {
+        let mut fields = Vec::new();
+        let mut field_names = BTreeSet::new();
+        for (member, layout) in members {
+            let mut field = field_ident(member);
+            while !field_names.insert(field.clone()) {
+                field.push('_');
+            }
+
+            let Some(ty) = self.rust_type(
+                layout,
+                &format!("{name}{}", type_ident(member)),
+                &format!("{path}.{member}"),
+                true,
+            ) else {
+                fields.push(format!("    // Unsupported member `{member}` is skipped"));
+                continue;
+            };
+
+            if field != *member {
+                fields.push(format!("    #[bt2(rename = {member:?})]"));
+            }
+            fields.push(format!("    pub {field}: {ty},"));
+        }
+
+        let derive = self
+            .generator
+            .derive_attribute(&["::bt2_derive::TryFromBtFieldConst"]);
+        let body = if fields.is_empty() {
+            "{}".to_owned()
+        } else {
+            format!("{{\n{}\n}}", fields.join("\n"))
+        };
+        self.items
+            .push(format!("/// {doc}\n{derive}\npub struct {name} {body}\n"));
+    }
+
+    /// Emit the types needed for the `layout` and return the Rust type.
+    ///
+    /// Returns `None` if the layout is not supported.
+    /// `Option<T>` is supported only for struct members, i.e., if `is_member` is set.
+    fn rust_type(
+        &mut self,
+        layout: &FieldLayout,
+        name_hint: &str,
+        path: &str,
+        is_member: bool,
+    ) -> Option<String> {
+        Some(match layout {
+            FieldLayout::Bool => "bool".to_owned(),
+            FieldLayout::UnsignedInteger { bits } => integer_type(false, *bits),
+            FieldLayout::SignedInteger { bits } => integer_type(true, *bits),
+            FieldLayout::String => "String".to_owned(),
+            FieldLayout::Structure(members) => {
+                let name = self.unique_type_name(name_hint);
+                self.emit_struct(&name, &format!("Structure `{path}`."), path, members);
+                name
+            }
+            FieldLayout::Array { element, length } => {
+                let element = self.rust_type(
+                    element,
+                    &format!("{name_hint}Element"),
+                    &format!("{path}[]"),
+                    false,
+                )?;
+                match length {
+                    Some(length) => format!("[{element}; {length}]"),
+                    None => format!("Vec<{element}>"),
+                }
+            }
+            FieldLayout::Enumeration {
+                signed,
+                bits,
+                labels,
+            } if labels.is_empty() => integer_type(*signed, *bits),
+            FieldLayout::Enumeration { labels, .. } => {
+                self.emit_enumeration(name_hint, path, labels)
+            }
+            FieldLayout::Option(content) if is_member => {
+                format!(
+                    "Option<{}>",
+                    self.rust_type(content, name_hint, path, false)?
+                )
+            }
+            FieldLayout::Variant(options) => self.emit_variant(name_hint, path, options)?,
+            FieldLayout::Option(_) | FieldLayout::Unsupported => return None,
+        })
+    }
+
+    fn emit_enumeration(&mut self, name_hint: &str, path: &str, labels: &[String]) -> String {
+        let name = self.unique_type_name(name_hint);
+
+        let mut variants = Vec::new();
+        let mut variant_names = BTreeSet::new();
+        for label in labels.iter().collect::<BTreeSet<_>>() {
+            let variant = unique_variant_ident(&mut variant_names, label);
+            if variant != *label {
+                variants.push(format!("    #[bt2(rename = {label:?})]"));
+            }
+            variants.push(format!("    {variant},"));
+        }
+
+        let derive = self.generator.derive_attribute(&[
+            "Copy",
+            "PartialEq",
+            "Eq",
+            "::bt2_derive::TryFromBtFieldConst",
+        ]);
+        self.items.push(format!(
+            "/// Labels of the enumeration `{path}`.\n{derive}\npub enum {name} {{\n{}\n}}\n",
+            variants.join("\n")
+        ));
+        name
+    }
+
+    /// Emit an enum with a variant for each named option.
+    ///
+    /// Returns `None` if no option has content of a supported type, since such enum would be
+    /// converted from an enumeration field.
+    fn emit_variant(
+        &mut self,
+        name_hint: &str,
+        path: &str,
+        options: &[(Option<String>, FieldLayout)],
+    ) -> Option<String> {
+        let name = self.unique_type_name(name_hint);
+
+        let mut variants = Vec::new();
+        let mut variant_names = BTreeSet::new();
+        let mut has_content = false;
+        for (option, layout) in options {
+            let Some(option) = option else {
+                variants.push("    // Unnamed option is skipped".to_owned());
+                continue;
+            };
+
+            let variant = unique_variant_ident(&mut variant_names, option);
+            if variant != *option {
+                variants.push(format!("    #[bt2(rename = {option:?})]"));
+            }
+            match self.rust_type(
+                layout,
+                &format!("{name}{variant}"),
+                &format!("{path}.{option}"),
+                false,
+            ) {
+                Some(ty) => {
+                    has_content = true;
+                    variants.push(format!("    {variant}({ty}),"));
+                }
+                None => variants.push(format!("    {variant},")),
+            }
+        }
+
+        if !has_content {
+            return None;
+        }
+
+        let derive = self
+            .generator
+            .derive_attribute(&["::bt2_derive::TryFromBtFieldConst"]);
+        self.items.push(format!(
+            "/// Options of the variant `{path}`.\n{derive}\npub enum {name} {{\n{}\n}}\n",
+            variants.join("\n")
+        ));
+        Some(name)
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+/// Smallest Rust integer type with at least `bits` bits.
+fn integer_type(signed: bool, bits: u64) -> String {
+    let bits = match bits {
+        0..=8 => 8,
+        9..=16 => 16,
+        17..=32 => 32,
+        _ => 64,
+    };
+    format!("{}{bits}", if signed { "i" } else { "u" })
+}
+
+/// Snake case identifier of a struct field.
+fn field_ident(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert_str(0, "field_");
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// Camel case identifier of a type or an enum variant.
+///
+/// Upper case words, e.g., `RCL_RET_OK`, are converted to `RclRetOk`.
+fn type_ident(name: &str) -> String {
+    let mut ident = String::new();
+    for word in name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let upper_case = word.chars().all(|c| !c.is_ascii_lowercase());
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            ident.push(first.to_ascii_uppercase());
+        }
+        if upper_case {
+            ident.extend(chars.map(|c| c.to_ascii_lowercase()));
+        } else {
+            ident.extend(chars);
+        }
+    }
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) || ident == "Self" {
+        ident.insert(0, 'T');
+    }
+    ident
+}
+
+fn unique_variant_ident(used: &mut BTreeSet<String>, name: &str) -> String {
+    let mut ident = type_ident(name);
+    while !used.insert(ident.clone()) {
+        ident.push('_');
+    }
+    ident
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn structure(members: &[(&str, FieldLayout)]) -> Option<FieldLayout> {
+        Some(FieldLayout::Structure(
+            members
+                .iter()
+                .map(|(name, layout)| ((*name).to_owned(), layout.clone()))
+                .collect(),
+        ))
+    }
+
+    fn generate(events: &[(&str, Option<FieldLayout>)]) -> String {
+        let events = events
+            .iter()
+            .map(|(name, payload)| ((*name).to_owned(), payload.clone()))
+            .collect();
+        EventCodeGenerator::new("Event")
+            .derive("serde::Serialize")
+            .emit(&events)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_generate_structs() {
+        let code = generate(&[
+            (
+                "ros2:rcl_init",
+                structure(&[
+                    ("context_handle", FieldLayout::UnsignedInteger { bits: 64 }),
+                    ("version", FieldLayout::String),
+                ]),
+            ),
+            (
+                "ros2:rcl_node_init",
+                structure(&[
+                    ("node-name", FieldLayout::String),
+                    ("type", FieldLayout::SignedInteger { bits: 12 }),
+                    ("enabled", FieldLayout::Bool),
+                ]),
+            ),
+            ("ros2:rcl_shutdown", None),
+        ]);
+
+        assert_eq!(
+            code,
+            r#"// @generated by `bt2_sys::codegen` from the metadata of a trace. Do not edit.
+
+/// Payload of the `ros2:rcl_init` event.
+#[derive(Debug, Clone, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub struct RclInit {
+    pub context_handle: u64,
+    pub version: String,
+}
+
+/// Payload of the `ros2:rcl_node_init` event.
+#[derive(Debug, Clone, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub struct RclNodeInit {
+    #[bt2(rename = "node-name")]
+    pub node_name: String,
+    #[bt2(rename = "type")]
+    pub type_: i16,
+    pub enabled: bool,
+}
+
+/// Payload of the `ros2:rcl_shutdown` event.
+#[derive(Debug, Clone, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub struct RclShutdown {}
+
+/// Events of the trace, selected by their name.
+#[derive(Debug, Clone, ::bt2_derive::FromBtEvent, serde::Serialize)]
+pub enum Event {
+    #[bt2(event = "ros2:rcl_init")]
+    RclInit(RclInit),
+    #[bt2(event = "ros2:rcl_node_init")]
+    RclNodeInit(RclNodeInit),
+    #[bt2(event = "ros2:rcl_shutdown")]
+    RclShutdown(RclShutdown),
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_nested_types() {
+        let code = generate(&[(
+            "app:sample",
+            structure(&[
+                (
+                    "point",
+                    structure(&[
+                        ("x", FieldLayout::SignedInteger { bits: 64 }),
+                        ("y", FieldLayout::SignedInteger { bits: 64 }),
+                    ])
+                    .unwrap(),
+                ),
+                (
+                    "values",
+                    FieldLayout::Array {
+                        element: Box::new(FieldLayout::UnsignedInteger { bits: 32 }),
+                        length: None,
+                    },
+                ),
+                (
+                    "digest",
+                    FieldLayout::Array {
+                        element: Box::new(FieldLayout::UnsignedInteger { bits: 8 }),
+                        length: Some(4),
+                    },
+                ),
+                (
+                    "state",
+                    FieldLayout::Enumeration {
+                        signed: false,
+                        bits: 8,
+                        labels: vec!["RUNNING".to_owned(), "idle state".to_owned()],
+                    },
+                ),
+                (
+                    "code",
+                    FieldLayout::Enumeration {
+                        signed: true,
+                        bits: 32,
+                        labels: Vec::new(),
+                    },
+                ),
+                (
+                    "parent",
+                    FieldLayout::Option(Box::new(FieldLayout::UnsignedInteger { bits: 64 })),
+                ),
+            ]),
+        )]);
+
+        assert!(code.contains(
+            "pub struct Sample {
+    pub point: SamplePoint,
+    pub values: Vec<u32>,
+    pub digest: [u8; 4],
+    pub state: SampleState,
+    pub code: i32,
+    pub parent: Option<u64>,
+}"
+        ));
+        assert!(code.contains(
+            "/// Structure `app:sample.point`.
+#[derive(Debug, Clone, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub struct SamplePoint {
+    pub x: i64,
+    pub y: i64,
+}"
+        ));
+        assert!(code.contains(
+            "/// Labels of the enumeration `app:sample.state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub enum SampleState {
+    #[bt2(rename = \"RUNNING\")]
+    Running,
+    #[bt2(rename = \"idle state\")]
+    IdleState,
+}"
+        ));
+    }
+
+    #[test]
+    fn test_skip_unsupported_members() {
+        let code = generate(&[(
+            "app:sample",
+            structure(&[
+                ("raw", FieldLayout::Unsupported),
+                (
+                    "optional_elements",
+                    FieldLayout::Array {
+                        element: Box::new(FieldLayout::Option(Box::new(FieldLayout::Bool))),
+                        length: None,
+                    },
+                ),
+                (
+                    "empty_variant",
+                    FieldLayout::Variant(vec![(Some("a".to_owned()), FieldLayout::Unsupported)]),
+                ),
+                ("count", FieldLayout::UnsignedInteger { bits: 16 }),
+            ]),
+        )]);
+
+        assert!(code.contains(
+            "pub struct Sample {
+    // Unsupported member `raw` is skipped
+    // Unsupported member `optional_elements` is skipped
+    // Unsupported member `empty_variant` is skipped
+    pub count: u16,
+}"
+        ));
+        assert!(!code.contains("enum SampleEmptyVariant"));
+    }
+
+    #[test]
+    fn test_generate_variant() {
+        let code = generate(&[(
+            "app:sample",
+            structure(&[(
+                "value",
+                FieldLayout::Variant(vec![
+                    (None, FieldLayout::Bool),
+                    (
+                        Some("int".to_owned()),
+                        FieldLayout::SignedInteger { bits: 64 },
+                    ),
+                    (Some("text-value".to_owned()), FieldLayout::String),
+                    (Some("nothing".to_owned()), FieldLayout::Unsupported),
+                ]),
+            )]),
+        )]);
+
+        assert!(code.contains("    pub value: SampleValue,\n"));
+        assert!(code.contains(
+            "/// Options of the variant `app:sample.value`.
+#[derive(Debug, Clone, ::bt2_derive::TryFromBtFieldConst, serde::Serialize)]
+pub enum SampleValue {
+    // Unnamed option is skipped
+    #[bt2(rename = \"int\")]
+    Int(i64),
+    #[bt2(rename = \"text-value\")]
+    TextValue(String),
+    #[bt2(rename = \"nothing\")]
+    Nothing,
+}"
+        ));
+    }
+
+    #[test]
+    fn test_unique_type_names() {
+        let code = generate(&[
+            ("a:init", structure(&[])),
+            ("b:init", structure(&[])),
+            ("c:event", structure(&[])),
+        ]);
+
+        assert!(code.contains("    Init(Init),\n"));
+        assert!(code.contains("    BInit(BInit),\n"));
+        // The enum name is reserved
+        assert!(code.contains("    CEvent(CEvent),\n"));
+    }
+
+    #[test]
+    fn test_no_events() {
+        assert!(matches!(
+            EventCodeGenerator::new("Event").emit(&BTreeMap::new()),
+            Err(CodeGenError::NoEvents)
+        ));
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(field_ident("Node-Name"), "node_name");
+        assert_eq!(field_ident("2d"), "field_2d");
+        assert_eq!(field_ident("self"), "self_");
+        assert_eq!(type_ident("RCL_RET_OK"), "RclRetOk");
+        assert_eq!(type_ident("rcl_node_init"), "RclNodeInit");
+        assert_eq!(type_ident("Self"), "TSelf");
+        assert_eq!(type_ident("3d"), "T3d");
+        assert_eq!(integer_type(false, 1), "u8");
+        assert_eq!(integer_type(true, 33), "i64");
+    }
+}
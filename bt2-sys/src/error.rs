@@ -7,9 +7,12 @@ use thiserror::Error;
 use crate::iterator::MessageIteratorState;
 use crate::raw_bindings::{
     bt_current_thread_error_append_cause_from_unknown, bt_current_thread_move_error,
-    bt_current_thread_take_error, bt_error, bt_error_borrow_cause_by_index,
-    bt_error_cause_get_message, bt_error_get_cause_count, bt_error_release,
-    bt_graph_run_once_status, bt_message_iterator_next_status,
+    bt_current_thread_take_error, bt_error, bt_error_borrow_cause_by_index, bt_error_cause,
+    bt_error_cause_get_file_name, bt_error_cause_get_line_number, bt_error_cause_get_message,
+    bt_error_cause_get_module_name, bt_error_get_cause_count, bt_error_release,
+    bt_graph_run_once_status, bt_message_iterator_can_seek_beginning_status,
+    bt_message_iterator_can_seek_ns_from_origin_status, bt_message_iterator_next_status,
+    bt_message_iterator_seek_beginning_status, bt_message_iterator_seek_ns_from_origin_status,
 };
 use crate::utils::ConstNonNull;
 
@@ -58,6 +61,22 @@ impl BtError {
         Some(Self::Error(BtErrorWrapper::get()?))
     }
 
+    /// Take the error of the current thread set by a failed babeltrace2 function.
+    pub(crate) fn take_current() -> Self {
+        Self::get_error().expect("Bug: babeltrace2 function failed without setting an error")
+    }
+
+    /// Create an error with `message` as its cause.
+    ///
+    /// Used by components implemented in Rust to report their failures.
+    #[must_use]
+    #[track_caller]
+    pub fn new(message: &str) -> Self {
+        let location = std::panic::Location::caller();
+        Self::throw_common(None, message, location.file(), location.line());
+        Self::take_current()
+    }
+
     #[doc(hidden)]
     pub fn __throw_new(message: &str, file: &str, line: u32) {
         eprintln!("[{file}:{line}] {message}");
@@ -77,6 +96,18 @@ impl BtError {
         }
     }
 
+    /// Get the causes of the error, from the root cause to the most recent one.
+    ///
+    /// Only [`BtError::Error`] has causes, e.g., appended by [`throw!`](crate::throw) and
+    /// [`rethrow!`](crate::rethrow).
+    #[must_use]
+    pub fn causes(&self) -> Vec<BtErrorCause> {
+        match self {
+            Self::Error(error) => error.causes(),
+            Self::End | Self::Again(_) | Self::MemoryError(_) => Vec::new(),
+        }
+    }
+
     fn throw_common(cause: Option<BtErrorWrapper>, message: &str, file: &str, line: u32) {
         let message = CString::new(message).unwrap();
         let file = CString::new(file).unwrap();
@@ -112,6 +143,58 @@ impl BtErrorWrapper {
     }
 }
 
+impl BtErrorWrapper {
+    /// Get the causes of the error, from the root cause to the most recent one.
+    #[must_use]
+    pub fn causes(&self) -> Vec<BtErrorCause> {
+        let cause_count = unsafe { bt_error_get_cause_count(self.as_ptr()) };
+        (0..cause_count)
+            .map(|cause_idx| unsafe {
+                BtErrorCause::from_raw(bt_error_borrow_cause_by_index(self.as_ptr(), cause_idx))
+            })
+            .collect()
+    }
+}
+
+/// Cause of a babeltrace2 error, appended by the library, a component, or the Rust code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtErrorCause {
+    pub message: String,
+    /// Name of the module which appended the cause, e.g., `CTF-FS-SRC` or `Rust Error`.
+    pub module_name: String,
+    pub file_name: String,
+    pub line_number: u64,
+}
+
+impl BtErrorCause {
+    unsafe fn from_raw(cause: *const bt_error_cause) -> Self {
+        let string = |ptr: *const std::ffi::c_char| {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        };
+
+        Self {
+            message: string(bt_error_cause_get_message(cause)),
+            module_name: string(bt_error_cause_get_module_name(cause)),
+            file_name: string(bt_error_cause_get_file_name(cause)),
+            line_number: bt_error_cause_get_line_number(cause),
+        }
+    }
+}
+
+impl Display for BtErrorCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] ({}:{}) {}",
+            self.module_name, self.file_name, self.line_number, self.message
+        )
+    }
+}
+
 impl Drop for BtErrorWrapper {
     fn drop(&mut self) {
         unsafe {
@@ -175,6 +258,50 @@ impl IntoResult<(), BtError> for bt_message_iterator_next_status {
     }
 }
 
+macro_rules! impl_seek_status_into_result {
+    ($status:ident { $ok:ident, $again:ident, $memory_error:ident, $error:ident }) => {
+        impl IntoResult<(), BtError> for $status {
+            fn into_result(self) -> BtResult<()> {
+                match self {
+                    Self::$ok => Ok(()),
+                    Self::$again => Err(BtError::AGAIN),
+                    Self::$memory_error => Err(BtError::MEMORY_ERROR),
+                    Self::$error => Err(BtError::get_error().unwrap()),
+                    status => unreachable!(
+                        concat!("Bug: unknown ", stringify!($status), " = {}"),
+                        status.0
+                    ),
+                }
+            }
+        }
+    };
+}
+
+impl_seek_status_into_result!(bt_message_iterator_can_seek_beginning_status {
+    BT_MESSAGE_ITERATOR_CAN_SEEK_BEGINNING_STATUS_OK,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_BEGINNING_STATUS_AGAIN,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_BEGINNING_STATUS_MEMORY_ERROR,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_BEGINNING_STATUS_ERROR
+});
+impl_seek_status_into_result!(bt_message_iterator_can_seek_ns_from_origin_status {
+    BT_MESSAGE_ITERATOR_CAN_SEEK_NS_FROM_ORIGIN_STATUS_OK,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_NS_FROM_ORIGIN_STATUS_AGAIN,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_NS_FROM_ORIGIN_STATUS_MEMORY_ERROR,
+    BT_MESSAGE_ITERATOR_CAN_SEEK_NS_FROM_ORIGIN_STATUS_ERROR
+});
+impl_seek_status_into_result!(bt_message_iterator_seek_beginning_status {
+    BT_MESSAGE_ITERATOR_SEEK_BEGINNING_STATUS_OK,
+    BT_MESSAGE_ITERATOR_SEEK_BEGINNING_STATUS_AGAIN,
+    BT_MESSAGE_ITERATOR_SEEK_BEGINNING_STATUS_MEMORY_ERROR,
+    BT_MESSAGE_ITERATOR_SEEK_BEGINNING_STATUS_ERROR
+});
+impl_seek_status_into_result!(bt_message_iterator_seek_ns_from_origin_status {
+    BT_MESSAGE_ITERATOR_SEEK_NS_FROM_ORIGIN_STATUS_OK,
+    BT_MESSAGE_ITERATOR_SEEK_NS_FROM_ORIGIN_STATUS_AGAIN,
+    BT_MESSAGE_ITERATOR_SEEK_NS_FROM_ORIGIN_STATUS_MEMORY_ERROR,
+    BT_MESSAGE_ITERATOR_SEEK_NS_FROM_ORIGIN_STATUS_ERROR
+});
+
 impl IntoResult<MessageIteratorState, BtError> for bt_graph_run_once_status {
     fn into_result(self) -> BtResult<MessageIteratorState> {
         match self {
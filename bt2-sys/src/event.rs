@@ -1,16 +1,26 @@
+use std::ffi::CStr;
+use std::fmt::Display;
 use std::marker::PhantomData;
+use std::ptr::NonNull;
 
-use crate::field::{BtFieldClassConst, BtFieldConst};
+use thiserror::Error;
+
+use crate::error::OutOfMemory;
+use crate::field::{BtFieldClassConst, BtFieldClassStructure, BtFieldConst};
 use crate::message::BtMessageConst;
 use crate::raw_bindings::{
     bt_event, bt_event_borrow_class_const, bt_event_borrow_common_context_field_const,
     bt_event_borrow_packet_const, bt_event_borrow_payload_field_const,
     bt_event_borrow_specific_context_field_const, bt_event_borrow_stream_const, bt_event_class,
-    bt_event_class_borrow_payload_field_class_const, bt_event_class_get_id,
-    bt_event_class_get_name,
+    bt_event_class_borrow_payload_field_class_const,
+    bt_event_class_borrow_specific_context_field_class_const,
+    bt_event_class_borrow_stream_class_const, bt_event_class_create, bt_event_class_get_id,
+    bt_event_class_get_log_level, bt_event_class_get_name, bt_event_class_log_level,
+    bt_event_class_put_ref, bt_event_class_set_field_class_status, bt_event_class_set_name,
+    bt_event_class_set_name_status, bt_event_class_set_payload_field_class, bt_stream_class,
 };
-use crate::stream::{BtPacketConst, BtStreamConst};
-use crate::utils::ConstNonNull;
+use crate::stream::{BtPacketConst, BtStreamClassConst, BtStreamConst};
+use crate::utils::{BtProperyAvailabilty, ConstNonNull};
 
 #[repr(transparent)]
 pub struct BtEventConst<'a>(ConstNonNull<bt_event>, PhantomData<&'a BtMessageConst>);
@@ -139,12 +149,242 @@ impl BtEventClassConst {
         )
     }
 
+    /// Get the log level of the event, e.g., of a `tracelog` tracepoint.
+    #[must_use]
+    pub fn get_log_level(&self) -> Option<BtEventClassLogLevel> {
+        let mut log_level = bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG;
+        let availability = unsafe { bt_event_class_get_log_level(self.get_ptr(), &mut log_level) };
+        match availability.into() {
+            BtProperyAvailabilty::Available => Some(log_level.into()),
+            BtProperyAvailabilty::NotAvailable => None,
+        }
+    }
+
     #[must_use]
-    pub fn get_payload_field_class(&self) -> BtFieldClassConst {
+    pub fn get_stream_class(&self) -> BtStreamClassConst {
         unsafe {
-            BtFieldClassConst::new_unchecked(bt_event_class_borrow_payload_field_class_const(
+            BtStreamClassConst::new_unchecked(bt_event_class_borrow_stream_class_const(
                 self.get_ptr(),
             ))
         }
     }
+
+    /// Get the class of the payload field.
+    ///
+    /// Returns `None` if the events have no payload.
+    #[must_use]
+    pub fn get_payload_field_class(&self) -> Option<BtFieldClassConst> {
+        let field_class =
+            unsafe { bt_event_class_borrow_payload_field_class_const(self.get_ptr()) };
+        if field_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtFieldClassConst::new_unchecked(field_class) })
+    }
+
+    /// Get the class of the context field specific to this event class.
+    #[must_use]
+    pub fn get_specific_context_field_class(&self) -> Option<BtFieldClassConst> {
+        let field_class =
+            unsafe { bt_event_class_borrow_specific_context_field_class_const(self.get_ptr()) };
+        if field_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtFieldClassConst::new_unchecked(field_class) })
+    }
+}
+
+impl std::fmt::Debug for BtEventClassConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(BtEventClassConst))
+            .field("id", &self.get_id())
+            .field("name", &self.get_name())
+            .field("log_level", &self.get_log_level())
+            .field("payload", &self.get_payload_field_class())
+            .field("specific_context", &self.get_specific_context_field_class())
+            .finish()
+    }
+}
+
+/// Owned event class created by a component implemented in Rust.
+///
+/// Create it with [`BtStreamClass::create_event_class`](crate::stream::BtStreamClass::create_event_class).
+pub struct BtEventClass(NonNull<bt_event_class>);
+
+impl BtEventClass {
+    /// # Safety
+    /// The `stream_class` must be a valid pointer.
+    pub(crate) unsafe fn new(stream_class: *mut bt_stream_class) -> Result<Self, OutOfMemory> {
+        NonNull::new(bt_event_class_create(stream_class))
+            .map(Self)
+            .ok_or(OutOfMemory)
+    }
+
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_event_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtEventClassConst {
+        unsafe { BtEventClassConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// Set the name of the events, e.g., `ros2:rcl_init`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_name(&mut self, name: &CStr) -> Result<(), OutOfMemory> {
+        match unsafe { bt_event_class_set_name(self.as_ptr(), name.as_ptr()) } {
+            bt_event_class_set_name_status::BT_EVENT_CLASS_SET_NAME_STATUS_OK => Ok(()),
+            bt_event_class_set_name_status::BT_EVENT_CLASS_SET_NAME_STATUS_MEMORY_ERROR => {
+                Err(OutOfMemory)
+            }
+            status => unreachable!("Bug: unknown bt_event_class_set_name_status = {}", status.0),
+        }
+    }
+
+    /// Set the class of the payload field.
+    ///
+    /// The `field_class` cannot be modified afterwards.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_payload_field_class(
+        &mut self,
+        field_class: &BtFieldClassStructure,
+    ) -> Result<(), OutOfMemory> {
+        match unsafe { bt_event_class_set_payload_field_class(self.as_ptr(), field_class.as_ptr()) }
+        {
+            bt_event_class_set_field_class_status::BT_EVENT_CLASS_SET_FIELD_CLASS_STATUS_OK => {
+                Ok(())
+            }
+            bt_event_class_set_field_class_status::BT_EVENT_CLASS_SET_FIELD_CLASS_STATUS_MEMORY_ERROR => {
+                Err(OutOfMemory)
+            }
+            status => unreachable!(
+                "Bug: unknown bt_event_class_set_field_class_status = {}",
+                status.0
+            ),
+        }
+    }
+}
+
+impl Drop for BtEventClass {
+    fn drop(&mut self) {
+        unsafe { bt_event_class_put_ref(self.as_ptr()) }
+    }
+}
+
+/// Severity of an event, from the most to the least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BtEventClassLogLevel {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    DebugSystem,
+    DebugProgram,
+    DebugProcess,
+    DebugModule,
+    DebugUnit,
+    DebugFunction,
+    DebugLine,
+    Debug,
+}
+
+impl From<bt_event_class_log_level> for BtEventClassLogLevel {
+    fn from(value: bt_event_class_log_level) -> Self {
+        match value {
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_EMERGENCY => Self::Emergency,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_ALERT => Self::Alert,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_CRITICAL => Self::Critical,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_ERROR => Self::Error,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_WARNING => Self::Warning,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_NOTICE => Self::Notice,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_INFO => Self::Info,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_SYSTEM => Self::DebugSystem,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_PROGRAM => Self::DebugProgram,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_PROCESS => Self::DebugProcess,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_MODULE => Self::DebugModule,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_UNIT => Self::DebugUnit,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_FUNCTION => {
+                Self::DebugFunction
+            }
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG_LINE => Self::DebugLine,
+            bt_event_class_log_level::BT_EVENT_CLASS_LOG_LEVEL_DEBUG => Self::Debug,
+            _ => unreachable!("Bug: unknown bt_event_class_log_level = {}", value.0),
+        }
+    }
+}
+
+/// Conversion of an event into a Rust type.
+pub trait FromBtEvent: Sized {
+    /// Convert the `event`.
+    ///
+    /// Returns `None` if the event cannot be converted.
+    fn from_event(event: &BtEventConst) -> Option<Self>;
+}
+
+impl<T> FromBtEvent for T
+where
+    T: TryFrom<BtFieldConst>,
+    <T as TryFrom<BtFieldConst>>::Error: Display,
+{
+    /// Convert the payload of the `event`.
+    ///
+    /// # Panics
+    /// If the payload cannot be converted.
+    fn from_event(event: &BtEventConst) -> Option<Self> {
+        Some(event.get_payload()?.try_into().unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse event payload into {}: {e}",
+                std::any::type_name::<T>()
+            )
+        }))
+    }
+}
+
+/// Conversion of events selected by their name, e.g., an enum of the events of a provider.
+///
+/// Implemented by `#[derive(FromBtEvent)]` from `bt2-derive`.
+pub trait FromBtEventByName: FromBtEvent {
+    /// Full names (`provider:name`) of the supported events.
+    fn event_names() -> Vec<&'static str>;
+
+    /// Convert the `event` based on the name of its class.
+    ///
+    /// # Errors
+    /// - If the event is not among [`Self::event_names()`].
+    /// - If the conversion of a supported event fails.
+    fn try_from_event(event: &BtEventConst) -> Result<Self, EventConversionError>;
+}
+
+#[derive(Debug, Error)]
+pub enum EventConversionError {
+    #[error("Event class has no name")]
+    Unnamed,
+
+    #[error("Unsupported event `{0}`")]
+    UnknownEvent(String),
+
+    #[error("Failed to convert event `{0}`")]
+    ConversionFailed(String),
+}
+
+impl EventConversionError {
+    #[must_use]
+    pub fn unknown_event(name: &str) -> Self {
+        Self::UnknownEvent(name.to_owned())
+    }
+
+    #[must_use]
+    pub fn conversion_failed(name: &str) -> Self {
+        Self::ConversionFailed(name.to_owned())
+    }
 }
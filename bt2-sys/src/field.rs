@@ -1,25 +1,59 @@
 use std::convert::Infallible;
 use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
+use std::ops::RangeInclusive;
+use std::ptr::NonNull;
 
 use derive_more::derive::{Deref, Into};
 use thiserror::Error;
 
+use crate::error::OutOfMemory;
 use crate::raw_bindings::{
     bt_field, bt_field_array_borrow_element_field_by_index_const, bt_field_array_get_length,
-    bt_field_bool_get_value, bt_field_borrow_class_const, bt_field_class,
+    bt_field_bool_get_value, bt_field_bool_set_value, bt_field_borrow_class_const, bt_field_class,
+    bt_field_class_array_borrow_element_field_class_const, bt_field_class_array_static_get_length,
+    bt_field_class_bool_create, bt_field_class_enumeration_add_mapping_status,
+    bt_field_class_enumeration_get_mapping_count, bt_field_class_enumeration_mapping_get_label,
+    bt_field_class_enumeration_signed_borrow_mapping_by_index_const,
+    bt_field_class_enumeration_signed_mapping_as_mapping_const,
+    bt_field_class_enumeration_unsigned_add_mapping,
+    bt_field_class_enumeration_unsigned_borrow_mapping_by_index_const,
+    bt_field_class_enumeration_unsigned_create,
+    bt_field_class_enumeration_unsigned_mapping_as_mapping_const, bt_field_class_get_type,
     bt_field_class_integer_get_field_value_range,
     bt_field_class_integer_get_preferred_display_base,
-    bt_field_class_integer_preferred_display_base,
+    bt_field_class_integer_preferred_display_base, bt_field_class_integer_signed_create,
+    bt_field_class_integer_unsigned_create, bt_field_class_option_borrow_field_class_const,
+    bt_field_class_put_ref, bt_field_class_string_create, bt_field_class_structure_append_member,
+    bt_field_class_structure_append_member_status,
     bt_field_class_structure_borrow_member_by_index_const,
-    bt_field_class_structure_borrow_member_by_name_const,
+    bt_field_class_structure_borrow_member_by_name_const, bt_field_class_structure_create,
     bt_field_class_structure_get_member_count, bt_field_class_structure_member,
     bt_field_class_structure_member_borrow_field_class_const,
-    bt_field_class_structure_member_get_name, bt_field_class_type, bt_field_get_class_type,
-    bt_field_integer_signed_get_value, bt_field_integer_unsigned_get_value,
-    bt_field_string_get_length, bt_field_string_get_value,
+    bt_field_class_structure_member_get_name, bt_field_class_type,
+    bt_field_class_variant_borrow_option_by_index_const, bt_field_class_variant_create,
+    bt_field_class_variant_get_option_count, bt_field_class_variant_option,
+    bt_field_class_variant_option_borrow_field_class_const, bt_field_class_variant_option_get_name,
+    bt_field_class_variant_without_selector_append_option,
+    bt_field_class_variant_without_selector_append_option_status,
+    bt_field_enumeration_get_mapping_labels, bt_field_enumeration_get_mapping_labels_status,
+    bt_field_get_class_type, bt_field_integer_signed_get_value, bt_field_integer_signed_set_value,
+    bt_field_integer_unsigned_get_value, bt_field_integer_unsigned_set_value,
+    bt_field_option_borrow_field_const, bt_field_string_get_length, bt_field_string_get_value,
+    bt_field_string_set_value, bt_field_string_set_value_status,
+    bt_field_structure_borrow_member_field_by_index_const,
+    bt_field_structure_borrow_member_field_by_name,
     bt_field_structure_borrow_member_field_by_name_const,
+    bt_field_variant_borrow_selected_option_class_const,
+    bt_field_variant_borrow_selected_option_field,
+    bt_field_variant_borrow_selected_option_field_const,
+    bt_field_variant_get_selected_option_index, bt_field_variant_select_option_by_index,
+    bt_field_variant_select_option_by_index_status, bt_integer_range_set_add_range_status,
+    bt_integer_range_set_unsigned, bt_integer_range_set_unsigned_add_range,
+    bt_integer_range_set_unsigned_create, bt_integer_range_set_unsigned_put_ref,
 };
+use crate::trace::BtTraceClass;
 use crate::utils::ConstNonNull;
 
 #[repr(transparent)]
@@ -49,6 +83,18 @@ pub struct BtFieldArrayConst(BtFieldConst);
 #[derive(Deref)]
 pub struct BtFieldStructureConst(BtFieldConst);
 
+#[repr(transparent)]
+#[derive(Deref)]
+pub struct BtFieldEnumerationConst(BtFieldConst);
+
+#[repr(transparent)]
+#[derive(Deref)]
+pub struct BtFieldOptionConst(BtFieldConst);
+
+#[repr(transparent)]
+#[derive(Deref)]
+pub struct BtFieldVariantConst(BtFieldConst);
+
 /// Casted [`BtFieldConst`] to more specific field types.
 ///
 /// Note: This enum is non-exhaustive because it does not cover all possible field types.
@@ -60,13 +106,16 @@ pub enum BtFieldType {
     String(BtFieldStringConst),
     Array(BtFieldArrayConst),
     Structure(BtFieldStructureConst),
+    Enumeration(BtFieldEnumerationConst),
+    Option(BtFieldOptionConst),
+    Variant(BtFieldVariantConst),
 }
 
 /// Type of the field.
 ///
 /// Note: This enum is non-exhaustive because it does not cover all possible
 /// field types only supported ones.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum BtFieldClassType {
     Bool,
@@ -76,6 +125,10 @@ pub enum BtFieldClassType {
     String,
     Array,
     Structure,
+    /// Unsigned or signed integer with labeled mappings.
+    Enumeration,
+    Option,
+    Variant,
 }
 
 impl BtFieldConst {
@@ -128,7 +181,12 @@ impl BtFieldConst {
             BtFieldClassType::String => BtFieldType::String(BtFieldStringConst(self)),
             BtFieldClassType::Array => BtFieldType::Array(BtFieldArrayConst(self)),
             BtFieldClassType::Structure => BtFieldType::Structure(BtFieldStructureConst(self)),
-            _ => unimplemented!(),
+            BtFieldClassType::Enumeration => {
+                BtFieldType::Enumeration(BtFieldEnumerationConst(self))
+            }
+            BtFieldClassType::Option => BtFieldType::Option(BtFieldOptionConst(self)),
+            BtFieldClassType::Variant => BtFieldType::Variant(BtFieldVariantConst(self)),
+            BtFieldClassType::BitArray => unimplemented!(),
         })
     }
 
@@ -190,6 +248,8 @@ impl BtFieldConst {
 
     /// Attempt to cast the field into an unsigned integer.
     ///
+    /// Unsigned enumeration fields are accepted as well.
+    ///
     /// # Errors
     /// If the field is not an unsigned integer.
     ///
@@ -199,6 +259,9 @@ impl BtFieldConst {
     pub fn try_into_uint(self) -> Result<BtFieldUnsignedIntegerConst, IncorrectTypeError> {
         match self.cast() {
             Ok(BtFieldType::UnsignedInteger(inner)) => Ok(inner),
+            Ok(BtFieldType::Enumeration(inner)) if !inner.is_signed() => {
+                Ok(BtFieldUnsignedIntegerConst(inner.0))
+            }
             Ok(t) => Err(IncorrectTypeError {
                 requested_type: BtFieldClassType::UnsignedInteger,
                 actual_type: Some(t.get_class_type()),
@@ -229,6 +292,8 @@ impl BtFieldConst {
 
     /// Attempt to cast the field into a signed integer.
     ///
+    /// Signed enumeration fields are accepted as well.
+    ///
     /// # Errors
     /// If the field is not a signed integer.
     ///
@@ -238,6 +303,9 @@ impl BtFieldConst {
     pub fn try_into_int(self) -> Result<BtFieldSignedIntegerConst, IncorrectTypeError> {
         match self.cast() {
             Ok(BtFieldType::SignedInteger(inner)) => Ok(inner),
+            Ok(BtFieldType::Enumeration(inner)) if inner.is_signed() => {
+                Ok(BtFieldSignedIntegerConst(inner.0))
+            }
             Ok(t) => Err(IncorrectTypeError {
                 requested_type: BtFieldClassType::SignedInteger,
                 actual_type: Some(t.get_class_type()),
@@ -366,6 +434,138 @@ impl BtFieldConst {
         }
     }
 
+    /// Cast the field into an enumeration.
+    ///
+    /// # Panics
+    /// If the field is not an enumeration.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::try_into_enumeration()`] to attempt to cast the field into an enumeration.
+    #[must_use]
+    pub fn into_enumeration(self) -> BtFieldEnumerationConst {
+        match self.cast() {
+            Ok(BtFieldType::Enumeration(inner)) => inner,
+            Ok(t) => panic!("Expected enumeration, got {:?}", t.get_class_type()),
+            _ => panic!("Expected enumeration, got unsupported type"),
+        }
+    }
+
+    /// Attempt to cast the field into an enumeration.
+    ///
+    /// # Errors
+    /// If the field is not an enumeration.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::into_enumeration()`] to cast the field into an enumeration and panic on failure.
+    pub fn try_into_enumeration(self) -> Result<BtFieldEnumerationConst, IncorrectTypeError> {
+        match self.cast() {
+            Ok(BtFieldType::Enumeration(inner)) => Ok(inner),
+            Ok(t) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Enumeration,
+                actual_type: Some(t.get_class_type()),
+            }),
+            Err(_) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Enumeration,
+                actual_type: None,
+            }),
+        }
+    }
+
+    /// Cast the field into an option.
+    ///
+    /// # Panics
+    /// If the field is not an option.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::try_into_option()`] to attempt to cast the field into an option.
+    #[must_use]
+    pub fn into_option(self) -> BtFieldOptionConst {
+        match self.cast() {
+            Ok(BtFieldType::Option(inner)) => inner,
+            Ok(t) => panic!("Expected option, got {:?}", t.get_class_type()),
+            _ => panic!("Expected option, got unsupported type"),
+        }
+    }
+
+    /// Attempt to cast the field into an option.
+    ///
+    /// # Errors
+    /// If the field is not an option.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::into_option()`] to cast the field into an option and panic on failure.
+    pub fn try_into_option(self) -> Result<BtFieldOptionConst, IncorrectTypeError> {
+        match self.cast() {
+            Ok(BtFieldType::Option(inner)) => Ok(inner),
+            Ok(t) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Option,
+                actual_type: Some(t.get_class_type()),
+            }),
+            Err(_) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Option,
+                actual_type: None,
+            }),
+        }
+    }
+
+    /// Cast the field into a variant.
+    ///
+    /// # Panics
+    /// If the field is not a variant.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::try_into_variant()`] to attempt to cast the field into a variant.
+    #[must_use]
+    pub fn into_variant(self) -> BtFieldVariantConst {
+        match self.cast() {
+            Ok(BtFieldType::Variant(inner)) => inner,
+            Ok(t) => panic!("Expected variant, got {:?}", t.get_class_type()),
+            _ => panic!("Expected variant, got unsupported type"),
+        }
+    }
+
+    /// Attempt to cast the field into a variant.
+    ///
+    /// # Errors
+    /// If the field is not a variant.
+    ///
+    /// # See also
+    /// - [`Self::cast()`] to also obtain the field type.
+    /// - [`Self::into_variant()`] to cast the field into a variant and panic on failure.
+    pub fn try_into_variant(self) -> Result<BtFieldVariantConst, IncorrectTypeError> {
+        match self.cast() {
+            Ok(BtFieldType::Variant(inner)) => Ok(inner),
+            Ok(t) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Variant,
+                actual_type: Some(t.get_class_type()),
+            }),
+            Err(_) => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Variant,
+                actual_type: None,
+            }),
+        }
+    }
+
+    /// Unwrap the content of an option field.
+    ///
+    /// # Returns
+    /// - The content of the field if it is an option field with content.
+    /// - `None` if it is an option field without content.
+    /// - The field itself if it is not an option field.
+    #[must_use]
+    pub fn into_optional(self) -> Option<Self> {
+        match self.cast() {
+            Ok(BtFieldType::Option(inner)) => inner.get_field(),
+            Ok(t) => Some(unsafe { t.clone_unchecked() }),
+            Err((field, _)) => Some(field),
+        }
+    }
+
     pub(crate) unsafe fn clone_unchecked(&self) -> Self {
         unsafe { Self::new_unchecked(self.as_ptr()) }
     }
@@ -464,6 +664,9 @@ impl std::fmt::Debug for BtFieldConst {
             BtFieldType::String(inner) => inner.fmt(f),
             BtFieldType::Array(inner) => inner.fmt(f),
             BtFieldType::Structure(inner) => inner.fmt(f),
+            BtFieldType::Enumeration(inner) => inner.fmt(f),
+            BtFieldType::Option(inner) => inner.fmt(f),
+            BtFieldType::Variant(inner) => inner.fmt(f),
         }
     }
 }
@@ -484,6 +687,9 @@ impl std::fmt::Display for BtFieldConst {
             BtFieldType::String(inner) => inner.fmt(f),
             BtFieldType::Array(inner) => inner.fmt(f),
             BtFieldType::Structure(inner) => inner.fmt(f),
+            BtFieldType::Enumeration(inner) => inner.fmt(f),
+            BtFieldType::Option(inner) => inner.fmt(f),
+            BtFieldType::Variant(inner) => inner.fmt(f),
         }
     }
 }
@@ -498,6 +704,9 @@ impl BtFieldType {
             Self::String(_) => BtFieldClassType::String,
             Self::Array(_) => BtFieldClassType::Array,
             Self::Structure(_) => BtFieldClassType::Structure,
+            Self::Enumeration(_) => BtFieldClassType::Enumeration,
+            Self::Option(_) => BtFieldClassType::Option,
+            Self::Variant(_) => BtFieldClassType::Variant,
         }
     }
 }
@@ -513,6 +722,9 @@ impl std::ops::Deref for BtFieldType {
             Self::String(inner) => inner,
             Self::Array(inner) => inner,
             Self::Structure(inner) => inner,
+            Self::Enumeration(inner) => inner,
+            Self::Option(inner) => inner,
+            Self::Variant(inner) => inner,
         }
     }
 }
@@ -912,7 +1124,7 @@ impl BtFieldStructureConst {
         assert!(index < self.get_field_count());
 
         unsafe {
-            BtFieldConst::new_unchecked(bt_field_array_borrow_element_field_by_index_const(
+            BtFieldConst::new_unchecked(bt_field_structure_borrow_member_field_by_index_const(
                 self.as_ptr(),
                 index,
             ))
@@ -954,6 +1166,187 @@ impl std::fmt::Display for BtFieldStructureConst {
     }
 }
 
+impl BtFieldEnumerationConst {
+    /// Whether the enumeration is signed, i.e., its value is a signed integer.
+    #[must_use]
+    pub fn is_signed(&self) -> bool {
+        let class = unsafe { bt_field_get_class_type(self.as_ptr()) };
+        class == bt_field_class_type::BT_FIELD_CLASS_TYPE_SIGNED_ENUMERATION
+    }
+
+    /// Get the labels of the mappings that contain the value of the field.
+    ///
+    /// The value may be contained in several mappings or in none of them.
+    ///
+    /// # Errors
+    /// If the library fails to allocate the label array.
+    pub fn get_labels(&self) -> Result<Vec<&CStr>, OutOfMemory> {
+        let mut labels = std::ptr::null();
+        let mut count = 0;
+        match unsafe {
+            bt_field_enumeration_get_mapping_labels(self.as_ptr(), &mut labels, &mut count)
+        } {
+            bt_field_enumeration_get_mapping_labels_status::BT_FIELD_ENUMERATION_GET_MAPPING_LABELS_STATUS_OK => {}
+            bt_field_enumeration_get_mapping_labels_status::BT_FIELD_ENUMERATION_GET_MAPPING_LABELS_STATUS_MEMORY_ERROR => {
+                return Err(OutOfMemory);
+            }
+            status => unreachable!(
+                "Bug: Unknown bt_field_enumeration_get_mapping_labels_status: {:?}",
+                status.0
+            ),
+        }
+
+        let count = usize::try_from(count).expect("Label count is too large to convert to usize");
+        Ok((0..count)
+            .map(|i| unsafe { CStr::from_ptr(*labels.add(i)) })
+            .collect())
+    }
+}
+
+impl std::fmt::Debug for BtFieldEnumerationConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct(stringify!(BtFieldEnumerationConst));
+        if self.is_signed() {
+            debug.field("value", &unsafe {
+                bt_field_integer_signed_get_value(self.as_ptr())
+            });
+        } else {
+            debug.field("value", &unsafe {
+                bt_field_integer_unsigned_get_value(self.as_ptr())
+            });
+        }
+        debug
+            .field("labels", &self.get_labels().unwrap_or_default())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for BtFieldEnumerationConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_signed() {
+            write!(f, "{}", unsafe {
+                bt_field_integer_signed_get_value(self.as_ptr())
+            })?;
+        } else {
+            write!(f, "{}", unsafe {
+                bt_field_integer_unsigned_get_value(self.as_ptr())
+            })?;
+        }
+
+        let labels = self.get_labels().unwrap_or_default();
+        if !labels.is_empty() {
+            write!(f, " (")?;
+            for (i, label) in labels.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " | ")?;
+                }
+                write!(f, "{}", label.to_string_lossy())?;
+            }
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BtFieldOptionConst {
+    /// Get the content of the option field.
+    ///
+    /// # Returns
+    /// - `Some(field)` if the option has content.
+    /// - `None` if the option is empty.
+    #[must_use]
+    pub fn get_field(&self) -> Option<BtFieldConst> {
+        unsafe {
+            let field = bt_field_option_borrow_field_const(self.as_ptr());
+            if field.is_null() {
+                return None;
+            }
+
+            Some(BtFieldConst::new_unchecked(field))
+        }
+    }
+}
+
+impl std::fmt::Debug for BtFieldOptionConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(stringify!(BtFieldOptionConst))
+            .field(&self.get_field())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for BtFieldOptionConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get_field() {
+            Some(field) => write!(f, "{field}"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+impl BtFieldVariantConst {
+    /// Get the index of the selected option.
+    #[must_use]
+    pub fn get_selected_option_index(&self) -> u64 {
+        unsafe { bt_field_variant_get_selected_option_index(self.as_ptr()) }
+    }
+
+    /// Get the name of the selected option.
+    ///
+    /// Returns `None` if the option has no name.
+    #[must_use]
+    pub fn get_selected_option_name(&self) -> Option<&CStr> {
+        unsafe {
+            let option = bt_field_variant_borrow_selected_option_class_const(self.as_ptr());
+            let name = bt_field_class_variant_option_get_name(option);
+            if name.is_null() {
+                return None;
+            }
+
+            Some(CStr::from_ptr(name))
+        }
+    }
+
+    /// Get the field of the selected option.
+    #[must_use]
+    pub fn get_selected_field(&self) -> BtFieldConst {
+        unsafe {
+            BtFieldConst::new_unchecked(bt_field_variant_borrow_selected_option_field_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+}
+
+impl std::fmt::Debug for BtFieldVariantConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(BtFieldVariantConst))
+            .field("option", &self.get_selected_option_name())
+            .field("field", &self.get_selected_field())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for BtFieldVariantConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get_selected_option_name() {
+            Some(name) => write!(
+                f,
+                "{}: {}",
+                name.to_string_lossy(),
+                self.get_selected_field()
+            ),
+            None => write!(
+                f,
+                "#{}: {}",
+                self.get_selected_option_index(),
+                self.get_selected_field()
+            ),
+        }
+    }
+}
+
 impl BtFieldClassType {
     #[must_use]
     pub(crate) const fn from_field_class_type(class: bt_field_class_type) -> Option<Self> {
@@ -968,12 +1361,22 @@ impl BtFieldClassType {
             | bt_field_class_type::BT_FIELD_CLASS_TYPE_DYNAMIC_ARRAY_WITH_LENGTH_FIELD => {
                 Self::Array
             }
+            bt_field_class_type::BT_FIELD_CLASS_TYPE_UNSIGNED_ENUMERATION
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_SIGNED_ENUMERATION => Self::Enumeration,
+            bt_field_class_type::BT_FIELD_CLASS_TYPE_OPTION_WITHOUT_SELECTOR_FIELD
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_OPTION_WITH_BOOL_SELECTOR_FIELD
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_OPTION_WITH_UNSIGNED_INTEGER_SELECTOR_FIELD
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_OPTION_WITH_SIGNED_INTEGER_SELECTOR_FIELD => {
+                Self::Option
+            }
+            bt_field_class_type::BT_FIELD_CLASS_TYPE_VARIANT_WITHOUT_SELECTOR_FIELD
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_VARIANT_WITH_UNSIGNED_INTEGER_SELECTOR_FIELD
+            | bt_field_class_type::BT_FIELD_CLASS_TYPE_VARIANT_WITH_SIGNED_INTEGER_SELECTOR_FIELD => {
+                Self::Variant
+            }
             _ => {
                 // TODO: Float
-                // TODO: Enum
                 // TODO: Bit array
-                // TODO: Option
-                // TODO: Variant
                 return None;
             }
         })
@@ -991,81 +1394,422 @@ impl BtFieldClassConst {
     const fn as_ptr(&self) -> *const bt_field_class {
         self.0.as_ptr()
     }
-}
-
-#[repr(transparent)]
-#[derive(Deref, Into)]
-pub struct BtFieldIntegerClassConst(BtFieldClassConst);
 
-impl BtFieldIntegerClassConst {
+    /// Get the type of the fields of this class.
+    ///
+    /// Returns `None` if the type is not supported.
     #[must_use]
-    pub fn get_preferred_display_base(&self) -> PreferedDisplayBase {
-        match unsafe { bt_field_class_integer_get_preferred_display_base(self.as_ptr()) } {
-            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_BINARY => {
-                PreferedDisplayBase::Binary
-            }
-            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_OCTAL => {
-                PreferedDisplayBase::Octal
-            }
-            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_DECIMAL => {
-                PreferedDisplayBase::Decimal
-            }
-            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_HEXADECIMAL => {
-                PreferedDisplayBase::Hexadecimal
-            }
-            _ => unreachable!("Unspecified preferred display base"),
-        }
+    pub fn get_type(&self) -> Option<BtFieldClassType> {
+        let class = unsafe { bt_field_class_get_type(self.as_ptr()) };
+        BtFieldClassType::from_field_class_type(class)
     }
 
-    /// Get the range of the integer field.
+    /// Cast the field class into a structure field class.
     ///
-    /// - For Unsigned Integer, the range is \[0, 2^N - 1\]
-    /// - For Signed Integer, the range is \[-2^N, 2^N - 1\]
+    /// # Panics
+    /// If the field class is not a structure field class.
+    ///
+    /// # See also
+    /// - [`Self::try_into_struct()`] to attempt to cast the field class.
     #[must_use]
-    pub fn get_field_value_range(&self) -> u64 {
-        unsafe { bt_field_class_integer_get_field_value_range(self.as_ptr()) }
+    pub fn into_struct(self) -> BtFieldStructClassConst {
+        self.try_into_struct().unwrap_or_else(|e| panic!("{e}"))
     }
-}
-
-#[repr(transparent)]
-#[derive(Deref, Into)]
-pub struct BtFieldIntegerUnsignedClassConst(BtFieldIntegerClassConst);
-
-#[repr(transparent)]
-#[derive(Deref, Into)]
-pub struct BtFieldIntegerSignedClassConst(BtFieldIntegerClassConst);
-
-#[repr(transparent)]
-#[derive(Deref, Into)]
-pub struct BtFieldStructClassConst(BtFieldClassConst);
-
-#[repr(transparent)]
-pub struct BtFieldStructMemberClassConst(ConstNonNull<bt_field_class_structure_member>);
 
-impl BtFieldStructClassConst {
-    #[must_use]
-    pub fn get_member_count(&self) -> u64 {
-        unsafe { bt_field_class_structure_get_member_count(self.as_ptr()) }
+    /// Attempt to cast the field class into a structure field class.
+    ///
+    /// # Errors
+    /// If the field class is not a structure field class.
+    pub fn try_into_struct(self) -> Result<BtFieldStructClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::Structure) => Ok(BtFieldStructClassConst(self)),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Structure,
+                actual_type,
+            }),
+        }
     }
 
-    /// Get the member with the specified `name`.
-    ///
-    /// The field `name` is converted to a [`CString`] before being passed to the C API.
-    ///
-    /// # Returns
-    /// - `Some(field)` if the field exists.
-    /// - `None` if the field does not exist.
+    /// Cast the field class into an array field class.
     ///
     /// # Panics
-    /// - If the field name cannot be converted to a [`CString`].
+    /// If the field class is not an array field class.
     ///
     /// # See also
-    /// - [`Self::get_member_by_name_cstr()`] to avoid converting the `name` to a [`CString`].
-    /// - [`Self::get_member_by_index()`] to get a field by its index.
+    /// - [`Self::try_into_array()`] to attempt to cast the field class.
     #[must_use]
-    pub fn get_member_by_name(&self, name: &str) -> Option<BtFieldStructMemberClassConst> {
-        let name = CString::new(name).expect(
-            "BtFieldStructClassConst::get_field_by_name(): Failed to convert &str to CString",
+    pub fn into_array(self) -> BtFieldArrayClassConst {
+        self.try_into_array().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Attempt to cast the field class into an array field class.
+    ///
+    /// # Errors
+    /// If the field class is not an array field class.
+    pub fn try_into_array(self) -> Result<BtFieldArrayClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::Array) => Ok(BtFieldArrayClassConst(self)),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Array,
+                actual_type,
+            }),
+        }
+    }
+
+    /// Attempt to cast the field class into an unsigned integer field class.
+    ///
+    /// Unsigned enumeration field classes are accepted as well.
+    ///
+    /// # Errors
+    /// If the field class is not an unsigned integer field class.
+    pub fn try_into_unsigned_integer(
+        self,
+    ) -> Result<BtFieldIntegerUnsignedClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::UnsignedInteger) => Ok(BtFieldIntegerUnsignedClassConst(
+                BtFieldIntegerClassConst(self),
+            )),
+            Some(BtFieldClassType::Enumeration) if !self.is_signed_enumeration() => Ok(
+                BtFieldIntegerUnsignedClassConst(BtFieldIntegerClassConst(self)),
+            ),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::UnsignedInteger,
+                actual_type,
+            }),
+        }
+    }
+
+    /// Attempt to cast the field class into a signed integer field class.
+    ///
+    /// Signed enumeration field classes are accepted as well.
+    ///
+    /// # Errors
+    /// If the field class is not a signed integer field class.
+    pub fn try_into_signed_integer(
+        self,
+    ) -> Result<BtFieldIntegerSignedClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::SignedInteger) => Ok(BtFieldIntegerSignedClassConst(
+                BtFieldIntegerClassConst(self),
+            )),
+            Some(BtFieldClassType::Enumeration) if self.is_signed_enumeration() => Ok(
+                BtFieldIntegerSignedClassConst(BtFieldIntegerClassConst(self)),
+            ),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::SignedInteger,
+                actual_type,
+            }),
+        }
+    }
+
+    /// Attempt to cast the field class into an enumeration field class.
+    ///
+    /// # Errors
+    /// If the field class is not an enumeration field class.
+    pub fn try_into_enumeration(self) -> Result<BtFieldEnumerationClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::Enumeration) => {
+                Ok(BtFieldEnumerationClassConst(BtFieldIntegerClassConst(self)))
+            }
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Enumeration,
+                actual_type,
+            }),
+        }
+    }
+
+    /// Attempt to cast the field class into an option field class.
+    ///
+    /// # Errors
+    /// If the field class is not an option field class.
+    pub fn try_into_option(self) -> Result<BtFieldOptionClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::Option) => Ok(BtFieldOptionClassConst(self)),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Option,
+                actual_type,
+            }),
+        }
+    }
+
+    /// Attempt to cast the field class into a variant field class.
+    ///
+    /// # Errors
+    /// If the field class is not a variant field class.
+    pub fn try_into_variant(self) -> Result<BtFieldVariantClassConst, IncorrectTypeError> {
+        match self.get_type() {
+            Some(BtFieldClassType::Variant) => Ok(BtFieldVariantClassConst(self)),
+            actual_type => Err(IncorrectTypeError {
+                requested_type: BtFieldClassType::Variant,
+                actual_type,
+            }),
+        }
+    }
+
+    fn is_signed_enumeration(&self) -> bool {
+        let class = unsafe { bt_field_class_get_type(self.as_ptr()) };
+        class == bt_field_class_type::BT_FIELD_CLASS_TYPE_SIGNED_ENUMERATION
+    }
+
+    const fn clone_unchecked(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+/// Shows the layout of the fields, e.g., `{"message": UnsignedInteger, "name": String}`.
+impl std::fmt::Debug for BtFieldClassConst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get_type() {
+            Some(BtFieldClassType::Structure) => {
+                let class = BtFieldStructClassConst(self.clone_unchecked());
+                f.debug_map()
+                    .entries(
+                        (0..class.get_member_count())
+                            .map(|i| class.get_member_by_index(i))
+                            .map(|member| (member.get_name().to_owned(), member.get_class())),
+                    )
+                    .finish()
+            }
+            Some(BtFieldClassType::Array) => {
+                let class = BtFieldArrayClassConst(self.clone_unchecked());
+                f.debug_list().entry(&class.get_element_class()).finish()
+            }
+            Some(class_type) => write!(f, "{class_type:?}"),
+            None => write!(f, "Unsupported"),
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldArrayClassConst(BtFieldClassConst);
+
+impl BtFieldArrayClassConst {
+    /// Get the class of the elements of the arrays.
+    #[must_use]
+    pub fn get_element_class(&self) -> BtFieldClassConst {
+        unsafe {
+            BtFieldClassConst::new_unchecked(bt_field_class_array_borrow_element_field_class_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Get the length of the arrays if it is static.
+    ///
+    /// Returns `None` for dynamic arrays.
+    #[must_use]
+    pub fn get_static_length(&self) -> Option<u64> {
+        let class = unsafe { bt_field_class_get_type(self.as_ptr()) };
+        (class == bt_field_class_type::BT_FIELD_CLASS_TYPE_STATIC_ARRAY)
+            .then(|| unsafe { bt_field_class_array_static_get_length(self.as_ptr()) })
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldIntegerClassConst(BtFieldClassConst);
+
+impl BtFieldIntegerClassConst {
+    #[must_use]
+    pub fn get_preferred_display_base(&self) -> PreferedDisplayBase {
+        match unsafe { bt_field_class_integer_get_preferred_display_base(self.as_ptr()) } {
+            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_BINARY => {
+                PreferedDisplayBase::Binary
+            }
+            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_OCTAL => {
+                PreferedDisplayBase::Octal
+            }
+            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_DECIMAL => {
+                PreferedDisplayBase::Decimal
+            }
+            bt_field_class_integer_preferred_display_base::BT_FIELD_CLASS_INTEGER_PREFERRED_DISPLAY_BASE_HEXADECIMAL => {
+                PreferedDisplayBase::Hexadecimal
+            }
+            _ => unreachable!("Unspecified preferred display base"),
+        }
+    }
+
+    /// Get the range of the integer field.
+    ///
+    /// - For Unsigned Integer, the range is \[0, 2^N - 1\]
+    /// - For Signed Integer, the range is \[-2^N, 2^N - 1\]
+    #[must_use]
+    pub fn get_field_value_range(&self) -> u64 {
+        unsafe { bt_field_class_integer_get_field_value_range(self.as_ptr()) }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldIntegerUnsignedClassConst(BtFieldIntegerClassConst);
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldIntegerSignedClassConst(BtFieldIntegerClassConst);
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldEnumerationClassConst(BtFieldIntegerClassConst);
+
+impl BtFieldEnumerationClassConst {
+    /// Whether the values of the enumeration are signed integers.
+    #[must_use]
+    pub fn is_signed(&self) -> bool {
+        self.is_signed_enumeration()
+    }
+
+    #[must_use]
+    pub fn get_mapping_count(&self) -> u64 {
+        unsafe { bt_field_class_enumeration_get_mapping_count(self.as_ptr()) }
+    }
+
+    /// Get the label of the mapping at the specified index.
+    ///
+    /// # Panics
+    /// - If the `index` is out of bounds.
+    #[must_use]
+    pub fn get_mapping_label(&self, index: u64) -> &CStr {
+        assert!(index < self.get_mapping_count());
+
+        unsafe {
+            let mapping = if self.is_signed() {
+                bt_field_class_enumeration_signed_mapping_as_mapping_const(
+                    bt_field_class_enumeration_signed_borrow_mapping_by_index_const(
+                        self.as_ptr(),
+                        index,
+                    ),
+                )
+            } else {
+                bt_field_class_enumeration_unsigned_mapping_as_mapping_const(
+                    bt_field_class_enumeration_unsigned_borrow_mapping_by_index_const(
+                        self.as_ptr(),
+                        index,
+                    ),
+                )
+            };
+            CStr::from_ptr(bt_field_class_enumeration_mapping_get_label(mapping))
+        }
+    }
+
+    /// Iterate over the labels of the mappings.
+    pub fn mapping_labels(&self) -> impl Iterator<Item = &CStr> + '_ {
+        (0..self.get_mapping_count()).map(|i| self.get_mapping_label(i))
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldOptionClassConst(BtFieldClassConst);
+
+impl BtFieldOptionClassConst {
+    /// Get the class of the content of the options.
+    #[must_use]
+    pub fn get_field_class(&self) -> BtFieldClassConst {
+        unsafe {
+            BtFieldClassConst::new_unchecked(bt_field_class_option_borrow_field_class_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldVariantClassConst(BtFieldClassConst);
+
+#[repr(transparent)]
+pub struct BtFieldVariantOptionClassConst(ConstNonNull<bt_field_class_variant_option>);
+
+impl BtFieldVariantClassConst {
+    #[must_use]
+    pub fn get_option_count(&self) -> u64 {
+        unsafe { bt_field_class_variant_get_option_count(self.as_ptr()) }
+    }
+
+    /// Get the option at the specified index.
+    ///
+    /// # Panics
+    /// - If the `index` is out of bounds.
+    #[must_use]
+    pub fn get_option_by_index(&self, index: u64) -> BtFieldVariantOptionClassConst {
+        assert!(index < self.get_option_count());
+
+        unsafe {
+            BtFieldVariantOptionClassConst(ConstNonNull::new_unchecked(
+                bt_field_class_variant_borrow_option_by_index_const(self.as_ptr(), index),
+            ))
+        }
+    }
+
+    /// Iterate over the options.
+    pub fn options(&self) -> impl Iterator<Item = BtFieldVariantOptionClassConst> + '_ {
+        (0..self.get_option_count()).map(|i| self.get_option_by_index(i))
+    }
+}
+
+impl BtFieldVariantOptionClassConst {
+    const fn as_ptr(&self) -> *const bt_field_class_variant_option {
+        self.0.as_ptr()
+    }
+
+    /// Get the name of the option.
+    ///
+    /// Returns `None` if the option has no name.
+    #[must_use]
+    pub fn get_name(&self) -> Option<&CStr> {
+        unsafe {
+            let name = bt_field_class_variant_option_get_name(self.as_ptr());
+            if name.is_null() {
+                return None;
+            }
+
+            Some(CStr::from_ptr(name))
+        }
+    }
+
+    /// Get the class of the option content.
+    #[must_use]
+    pub fn get_class(&self) -> BtFieldClassConst {
+        unsafe {
+            BtFieldClassConst::new_unchecked(
+                bt_field_class_variant_option_borrow_field_class_const(self.as_ptr()),
+            )
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldStructClassConst(BtFieldClassConst);
+
+#[repr(transparent)]
+pub struct BtFieldStructMemberClassConst(ConstNonNull<bt_field_class_structure_member>);
+
+impl BtFieldStructClassConst {
+    #[must_use]
+    pub fn get_member_count(&self) -> u64 {
+        unsafe { bt_field_class_structure_get_member_count(self.as_ptr()) }
+    }
+
+    /// Get the member with the specified `name`.
+    ///
+    /// The field `name` is converted to a [`CString`] before being passed to the C API.
+    ///
+    /// # Returns
+    /// - `Some(field)` if the field exists.
+    /// - `None` if the field does not exist.
+    ///
+    /// # Panics
+    /// - If the field name cannot be converted to a [`CString`].
+    ///
+    /// # See also
+    /// - [`Self::get_member_by_name_cstr()`] to avoid converting the `name` to a [`CString`].
+    /// - [`Self::get_member_by_index()`] to get a field by its index.
+    #[must_use]
+    pub fn get_member_by_name(&self, name: &str) -> Option<BtFieldStructMemberClassConst> {
+        let name = CString::new(name).expect(
+            "BtFieldStructClassConst::get_field_by_name(): Failed to convert &str to CString",
         );
 
         self.get_member_by_name_cstr(name.as_ref())
@@ -1149,6 +1893,420 @@ impl BtFieldStructMemberClassConst {
     }
 }
 
+/// Owned field class created by a component implemented in Rust.
+///
+/// Scalar field classes are members of a [`BtFieldClassStructure`],
+/// which is the payload of an event class.
+pub struct BtFieldClass(NonNull<bt_field_class>);
+
+/// Owned structure field class.
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldClassStructure(BtFieldClass);
+
+/// Owned enumeration field class with unsigned integer values.
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldClassUnsignedEnumeration(BtFieldClass);
+
+/// Owned variant field class without a selector field.
+#[repr(transparent)]
+#[derive(Deref, Into)]
+pub struct BtFieldClassVariant(BtFieldClass);
+
+impl BtFieldClass {
+    fn created(field_class: *mut bt_field_class) -> Result<Self, OutOfMemory> {
+        NonNull::new(field_class).map(Self).ok_or(OutOfMemory)
+    }
+
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_field_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtFieldClassConst {
+        unsafe { BtFieldClassConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// Create a boolean field class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new_bool(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        Self::created(unsafe { bt_field_class_bool_create(trace_class.as_ptr()) })
+    }
+
+    /// Create a 64-bit unsigned integer field class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new_unsigned_integer(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        Self::created(unsafe { bt_field_class_integer_unsigned_create(trace_class.as_ptr()) })
+    }
+
+    /// Create a 64-bit signed integer field class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new_signed_integer(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        Self::created(unsafe { bt_field_class_integer_signed_create(trace_class.as_ptr()) })
+    }
+
+    /// Create a string field class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new_string(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        Self::created(unsafe { bt_field_class_string_create(trace_class.as_ptr()) })
+    }
+}
+
+impl Drop for BtFieldClass {
+    fn drop(&mut self) {
+        unsafe { bt_field_class_put_ref(self.as_ptr()) }
+    }
+}
+
+impl BtFieldClassStructure {
+    /// Create a structure field class without members.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        BtFieldClass::created(unsafe { bt_field_class_structure_create(trace_class.as_ptr()) })
+            .map(Self)
+    }
+
+    /// Append a member named `name` of the class `member_class`.
+    ///
+    /// The `member_class` cannot be modified afterwards.
+    ///
+    /// # Panics
+    /// If the structure already has a member named `name`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn append_member(
+        &mut self,
+        name: &CStr,
+        member_class: &BtFieldClass,
+    ) -> Result<(), OutOfMemory> {
+        assert!(
+            self.as_const()
+                .into_struct()
+                .get_member_by_name_cstr(name)
+                .is_none(),
+            "Structure field class already has a member named {name:?}"
+        );
+
+        match unsafe {
+            bt_field_class_structure_append_member(
+                self.as_ptr(),
+                name.as_ptr(),
+                member_class.as_ptr(),
+            )
+        } {
+            bt_field_class_structure_append_member_status::BT_FIELD_CLASS_STRUCTURE_APPEND_MEMBER_STATUS_OK => Ok(()),
+            bt_field_class_structure_append_member_status::BT_FIELD_CLASS_STRUCTURE_APPEND_MEMBER_STATUS_MEMORY_ERROR => Err(OutOfMemory),
+            status => unreachable!(
+                "Bug: unknown bt_field_class_structure_append_member_status = {}",
+                status.0
+            ),
+        }
+    }
+}
+
+impl BtFieldClassUnsignedEnumeration {
+    /// Create a 64-bit unsigned enumeration field class without mappings.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        BtFieldClass::created(unsafe {
+            bt_field_class_enumeration_unsigned_create(trace_class.as_ptr())
+        })
+        .map(Self)
+    }
+
+    /// Add a mapping labeled `label` of the values in the `ranges`.
+    ///
+    /// # Panics
+    /// - If the enumeration already has a mapping labeled `label`.
+    /// - If `ranges` is empty or contains an empty range.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn add_mapping(
+        &mut self,
+        label: &CStr,
+        ranges: &[RangeInclusive<u64>],
+    ) -> Result<(), OutOfMemory> {
+        assert!(
+            !self
+                .as_const()
+                .try_into_enumeration()
+                .expect("Enumeration field class")
+                .mapping_labels()
+                .any(|existing| existing == label),
+            "Enumeration field class already has a mapping labeled {label:?}"
+        );
+        assert!(
+            !ranges.is_empty() && ranges.iter().all(|range| !range.is_empty()),
+            "Mapping {label:?} must contain values"
+        );
+
+        let range_set = unsafe { bt_integer_range_set_unsigned_create() };
+        if range_set.is_null() {
+            return Err(OutOfMemory);
+        }
+        let result = Self::add_ranges(range_set, ranges).and_then(|()| {
+            match unsafe {
+                bt_field_class_enumeration_unsigned_add_mapping(
+                    self.as_ptr(),
+                    label.as_ptr(),
+                    range_set,
+                )
+            } {
+                bt_field_class_enumeration_add_mapping_status::BT_FIELD_CLASS_ENUMERATION_ADD_MAPPING_STATUS_OK => Ok(()),
+                bt_field_class_enumeration_add_mapping_status::BT_FIELD_CLASS_ENUMERATION_ADD_MAPPING_STATUS_MEMORY_ERROR => Err(OutOfMemory),
+                status => unreachable!(
+                    "Bug: unknown bt_field_class_enumeration_add_mapping_status = {}",
+                    status.0
+                ),
+            }
+        });
+        unsafe { bt_integer_range_set_unsigned_put_ref(range_set) };
+        result
+    }
+
+    fn add_ranges(
+        range_set: *mut bt_integer_range_set_unsigned,
+        ranges: &[RangeInclusive<u64>],
+    ) -> Result<(), OutOfMemory> {
+        for range in ranges {
+            match unsafe {
+                bt_integer_range_set_unsigned_add_range(range_set, *range.start(), *range.end())
+            } {
+                bt_integer_range_set_add_range_status::BT_INTEGER_RANGE_SET_ADD_RANGE_STATUS_OK => {}
+                bt_integer_range_set_add_range_status::BT_INTEGER_RANGE_SET_ADD_RANGE_STATUS_MEMORY_ERROR => return Err(OutOfMemory),
+                status => unreachable!(
+                    "Bug: unknown bt_integer_range_set_add_range_status = {}",
+                    status.0
+                ),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BtFieldClassVariant {
+    /// Create a variant field class without options.
+    ///
+    /// The option of its fields is selected by [`BtField::select_option`].
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new(trace_class: &mut BtTraceClass) -> Result<Self, OutOfMemory> {
+        BtFieldClass::created(unsafe {
+            bt_field_class_variant_create(trace_class.as_ptr(), std::ptr::null_mut())
+        })
+        .map(Self)
+    }
+
+    /// Append an option named `name` of the class `option_class`.
+    ///
+    /// The `option_class` cannot be modified afterwards.
+    ///
+    /// # Panics
+    /// If the variant already has an option named `name`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn append_option(
+        &mut self,
+        name: &CStr,
+        option_class: &BtFieldClass,
+    ) -> Result<(), OutOfMemory> {
+        assert!(
+            !self
+                .as_const()
+                .try_into_variant()
+                .expect("Variant field class")
+                .options()
+                .any(|option| option.get_name() == Some(name)),
+            "Variant field class already has an option named {name:?}"
+        );
+
+        match unsafe {
+            bt_field_class_variant_without_selector_append_option(
+                self.as_ptr(),
+                name.as_ptr(),
+                option_class.as_ptr(),
+            )
+        } {
+            bt_field_class_variant_without_selector_append_option_status::BT_FIELD_CLASS_VARIANT_WITHOUT_SELECTOR_APPEND_OPTION_STATUS_OK => Ok(()),
+            bt_field_class_variant_without_selector_append_option_status::BT_FIELD_CLASS_VARIANT_WITHOUT_SELECTOR_APPEND_OPTION_STATUS_MEMORY_ERROR => Err(OutOfMemory),
+            status => unreachable!(
+                "Bug: unknown bt_field_class_variant_without_selector_append_option_status = {}",
+                status.0
+            ),
+        }
+    }
+}
+
+/// Field of an event created by a component implemented in Rust.
+///
+/// The field can be set until the message of its event is pushed to the output
+/// of the message iterator.
+pub struct BtField<'a>(NonNull<bt_field>, PhantomData<&'a mut bt_field>);
+
+impl BtField<'_> {
+    /// # Safety
+    /// The `field` must be a valid pointer to a field which is not frozen.
+    pub(crate) unsafe fn new_unchecked(field: *mut bt_field) -> Self {
+        Self(NonNull::new_unchecked(field), PhantomData)
+    }
+
+    #[inline]
+    const fn as_ptr(&self) -> *mut bt_field {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtFieldConst {
+        unsafe { BtFieldConst::new_unchecked(self.as_ptr()) }
+    }
+
+    #[must_use]
+    pub fn get_class_type(&self) -> Option<BtFieldClassType> {
+        self.as_const().get_class_type()
+    }
+
+    fn assert_class_type(&self, class_type: BtFieldClassType) {
+        let actual_type = self.get_class_type();
+        assert!(
+            actual_type == Some(class_type),
+            "{}",
+            IncorrectTypeError {
+                requested_type: class_type,
+                actual_type,
+            }
+        );
+    }
+
+    /// # Panics
+    /// If the field is not a boolean field.
+    pub fn set_bool(&mut self, value: bool) {
+        self.assert_class_type(BtFieldClassType::Bool);
+        unsafe { bt_field_bool_set_value(self.as_ptr(), value.into()) }
+    }
+
+    /// Whether the field is an enumeration field with signed or unsigned values.
+    fn is_enumeration(&self, signed: bool) -> bool {
+        self.as_const()
+            .try_into_enumeration()
+            .is_ok_and(|field| field.is_signed() == signed)
+    }
+
+    /// # Panics
+    /// If the field is not an unsigned integer or enumeration field.
+    pub fn set_uint(&mut self, value: u64) {
+        if !self.is_enumeration(false) {
+            self.assert_class_type(BtFieldClassType::UnsignedInteger);
+        }
+        unsafe { bt_field_integer_unsigned_set_value(self.as_ptr(), value) }
+    }
+
+    /// # Panics
+    /// If the field is not a signed integer or enumeration field.
+    pub fn set_int(&mut self, value: i64) {
+        if !self.is_enumeration(true) {
+            self.assert_class_type(BtFieldClassType::SignedInteger);
+        }
+        unsafe { bt_field_integer_signed_set_value(self.as_ptr(), value) }
+    }
+
+    /// # Panics
+    /// If the field is not a string field.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_string(&mut self, value: &CStr) -> Result<(), OutOfMemory> {
+        self.assert_class_type(BtFieldClassType::String);
+        match unsafe { bt_field_string_set_value(self.as_ptr(), value.as_ptr()) } {
+            bt_field_string_set_value_status::BT_FIELD_STRING_SET_VALUE_STATUS_OK => Ok(()),
+            bt_field_string_set_value_status::BT_FIELD_STRING_SET_VALUE_STATUS_MEMORY_ERROR => {
+                Err(OutOfMemory)
+            }
+            status => unreachable!(
+                "Bug: unknown bt_field_string_set_value_status = {}",
+                status.0
+            ),
+        }
+    }
+
+    /// Get the member field named `name` of a structure field.
+    ///
+    /// Returns `None` if the structure has no such member.
+    ///
+    /// # Panics
+    /// If the field is not a structure field.
+    #[must_use]
+    pub fn get_field_by_name_cstr(&mut self, name: &CStr) -> Option<BtField<'_>> {
+        self.assert_class_type(BtFieldClassType::Structure);
+        let field =
+            unsafe { bt_field_structure_borrow_member_field_by_name(self.as_ptr(), name.as_ptr()) };
+        if field.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtField::new_unchecked(field) })
+    }
+
+    /// Select the option at `index` of a variant field.
+    ///
+    /// # Panics
+    /// - If the field is not a variant field.
+    /// - If the `index` is out of bounds.
+    pub fn select_option(&mut self, index: u64) {
+        self.assert_class_type(BtFieldClassType::Variant);
+        let option_count = self
+            .as_const()
+            .get_class()
+            .try_into_variant()
+            .expect("Variant field class")
+            .get_option_count();
+        assert!(
+            index < option_count,
+            "Option {index} is out of bounds for a variant with {option_count} options"
+        );
+
+        match unsafe { bt_field_variant_select_option_by_index(self.as_ptr(), index) } {
+            bt_field_variant_select_option_by_index_status::BT_FIELD_VARIANT_SELECT_OPTION_BY_INDEX_STATUS_OK => {}
+            status => unreachable!(
+                "Bug: unknown bt_field_variant_select_option_by_index_status = {}",
+                status.0
+            ),
+        }
+    }
+
+    /// Get the field of the selected option of a variant field.
+    ///
+    /// The option is selected by [`Self::select_option`].
+    ///
+    /// # Panics
+    /// If the field is not a variant field.
+    #[must_use]
+    pub fn get_selected_option_field(&mut self) -> BtField<'_> {
+        self.assert_class_type(BtFieldClassType::Variant);
+        unsafe {
+            BtField::new_unchecked(bt_field_variant_borrow_selected_option_field(self.as_ptr()))
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConversionError {
     #[error("Cannot convert returned value to string. The value is not valid UTF-8!")]
@@ -1165,6 +2323,19 @@ pub enum ConversionError {
 
     #[error(transparent)]
     ArrayConversionError(#[from] ArrayConversionError),
+
+    #[error("No enum variant matches `{0}`")]
+    UnknownVariant(String),
+
+    #[error(transparent)]
+    OutOfMemory(#[from] OutOfMemory),
+}
+
+impl ConversionError {
+    #[must_use]
+    pub fn unknown_variant(name: impl std::fmt::Display) -> Self {
+        Self::UnknownVariant(name.to_string())
+    }
 }
 
 impl From<Infallible> for ConversionError {
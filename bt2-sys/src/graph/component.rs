@@ -5,7 +5,8 @@ use std::str::Utf8Error;
 use derive_more::derive::From;
 
 use crate::query::{
-    BtQueryError, BtQueryExecutor, SupportInfoParams, SupportInfoResult, SupportInfoResultError,
+    trace_infos_from_result, BtQueryError, BtQueryExecutor, SupportInfoParams, SupportInfoResult,
+    SupportInfoResultError, TraceInfo, TraceInfosError, TraceInfosParams,
 };
 use crate::raw_bindings::{
     bt_component, bt_component_class, bt_component_class_filter,
@@ -45,6 +46,17 @@ impl From<bt_component_class_type> for BtComponentType {
     }
 }
 
+impl std::fmt::Display for BtComponentType {
+    /// Type prefix of a component class name, e.g., `source` in `source.ctf.fs`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Source => "source",
+            Self::Filter => "filter",
+            Self::Sink => "sink",
+        })
+    }
+}
+
 #[derive(From)]
 pub enum BtComponentCasted<'a> {
     Source(BtComponentSourceConst<'a>),
@@ -351,6 +363,28 @@ impl<'a> BtComponentClassConst<'a> {
         };
         SupportInfoResult::try_from(result)
     }
+
+    /// Query the information about the traces and their streams.
+    ///
+    /// Only supported by the `src.ctf.fs` component class.
+    ///
+    /// # Errors
+    /// - If the component class does not support the `trace-infos` query, this function will return [`TraceInfosError::NotSupported`].
+    /// - If the layout of the result of the query is not as expected, this function will return [`TraceInfosError::IncorrectLayout`].
+    /// - If the query fails, this function will return [`TraceInfosError::QueryError`].
+    pub fn query_trace_infos(
+        &self,
+        params: TraceInfosParams,
+    ) -> Result<Vec<TraceInfo>, TraceInfosError> {
+        let params: BtValueMap = params.try_into().map_err(BtQueryError::Memory)?;
+        let mut query = self.create_query(c"babeltrace.trace-infos", &params);
+        let result = match query.query() {
+            Ok(result) => result,
+            Err(BtQueryError::UnknownObject) => return Err(TraceInfosError::NotSupported),
+            Err(err) => return Err(TraceInfosError::QueryError(err)),
+        };
+        trace_infos_from_result(&result)
+    }
 }
 
 impl<'a> BtComponentClassSourceConst<'a> {
@@ -21,6 +21,8 @@ use crate::value::BtValueMap;
 pub mod component;
 pub mod plugin;
 pub mod simple_sink;
+pub mod tee;
+pub mod user_component;
 
 #[repr(transparent)]
 pub struct BtGraph(NonNull<bt_graph>);
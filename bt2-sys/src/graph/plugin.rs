@@ -6,16 +6,25 @@ use thiserror::Error;
 
 use crate::error::{BtErrorWrapper, OutOfMemory};
 use crate::raw_bindings::{
-    bt_plugin, bt_plugin_borrow_filter_component_class_by_name_const,
+    bt_plugin, bt_plugin_borrow_filter_component_class_by_index_const,
+    bt_plugin_borrow_filter_component_class_by_name_const,
+    bt_plugin_borrow_sink_component_class_by_index_const,
     bt_plugin_borrow_sink_component_class_by_name_const,
-    bt_plugin_borrow_source_component_class_by_name_const, bt_plugin_find, bt_plugin_find_status,
-    bt_plugin_get_author, bt_plugin_get_description, bt_plugin_get_license, bt_plugin_get_name,
-    bt_plugin_get_path, bt_plugin_get_ref, bt_plugin_get_version, bt_plugin_put_ref,
+    bt_plugin_borrow_source_component_class_by_index_const,
+    bt_plugin_borrow_source_component_class_by_name_const, bt_plugin_find, bt_plugin_find_all,
+    bt_plugin_find_all_from_dir, bt_plugin_find_all_from_dir_status, bt_plugin_find_all_status,
+    bt_plugin_find_status, bt_plugin_get_author, bt_plugin_get_description,
+    bt_plugin_get_filter_component_class_count, bt_plugin_get_license, bt_plugin_get_name,
+    bt_plugin_get_path, bt_plugin_get_ref, bt_plugin_get_sink_component_class_count,
+    bt_plugin_get_source_component_class_count, bt_plugin_get_version, bt_plugin_put_ref,
+    bt_plugin_set, bt_plugin_set_borrow_plugin_by_index_const, bt_plugin_set_get_plugin_count,
+    bt_plugin_set_put_ref,
 };
 use crate::utils::{BtProperyAvailabilty, ConstNonNull};
 
 use super::component::{
-    BtComponentClassFilterConst, BtComponentClassSinkConst, BtComponentClassSourceConst,
+    BtComponentClassConst, BtComponentClassFilterConst, BtComponentClassSinkConst,
+    BtComponentClassSourceConst,
 };
 
 #[derive(Debug, Error)]
@@ -79,6 +88,107 @@ impl BtPlugin {
         }
     }
 
+    /// Find all plugins in the same locations as [`Self::find_anywhere()`].
+    ///
+    /// The locations are the directories in the `BABELTRACE_PLUGIN_PATH` environment variable,
+    /// the system plugin directory and the plugins built into the library.
+    /// Plugins which fail to load are skipped.
+    ///
+    /// # Errors
+    /// - [`BtPluginLoadError::Memory`] if the memory allocation fails.
+    /// - [`BtPluginLoadError::Other`] if an unknown error occurs.
+    ///
+    /// # Panics
+    /// If Babeltrace2 C API does not uphold its guarantees.
+    pub fn find_all() -> Result<Vec<Self>, BtPluginLoadError> {
+        let mut plugin_set = ptr::null();
+        unsafe {
+            let status: bt_plugin_find_all_status =
+                bt_plugin_find_all(1, 1, 1, 1, 0, &mut plugin_set);
+            match status {
+                bt_plugin_find_all_status::BT_PLUGIN_FIND_ALL_STATUS_OK => {
+                    Ok(Self::from_plugin_set(plugin_set))
+                }
+                bt_plugin_find_all_status::BT_PLUGIN_FIND_ALL_STATUS_NOT_FOUND => Ok(Vec::new()),
+                bt_plugin_find_all_status::BT_PLUGIN_FIND_ALL_STATUS_MEMORY_ERROR => {
+                    Err(BtPluginLoadError::Memory(OutOfMemory))
+                }
+                bt_plugin_find_all_status::BT_PLUGIN_FIND_ALL_STATUS_ERROR => {
+                    let error =
+                        BtErrorWrapper::get().expect("Error should be provided by the C API");
+                    Err(BtPluginLoadError::Other(error))
+                }
+                _ => unreachable!("Unknown bt_plugin_find_all_status = {}", status.0),
+            }
+        }
+    }
+
+    /// Find all plugins in the directory `path`, and in its subdirectories if `recurse` is set.
+    ///
+    /// Plugins which fail to load are skipped.
+    ///
+    /// # Errors
+    /// - [`BtPluginLoadError::Memory`] if the memory allocation fails.
+    /// - [`BtPluginLoadError::Other`] if an unknown error occurs, e.g., the directory does not exist.
+    ///
+    /// # Panics
+    /// If Babeltrace2 C API does not uphold its guarantees.
+    pub fn find_all_from_dir(path: &CStr, recurse: bool) -> Result<Vec<Self>, BtPluginLoadError> {
+        let mut plugin_set = ptr::null();
+        unsafe {
+            let status: bt_plugin_find_all_from_dir_status =
+                bt_plugin_find_all_from_dir(path.as_ptr(), recurse.into(), 0, &mut plugin_set);
+            match status {
+                bt_plugin_find_all_from_dir_status::BT_PLUGIN_FIND_ALL_FROM_DIR_STATUS_OK => {
+                    Ok(Self::from_plugin_set(plugin_set))
+                }
+                bt_plugin_find_all_from_dir_status::BT_PLUGIN_FIND_ALL_FROM_DIR_STATUS_NOT_FOUND => {
+                    Ok(Vec::new())
+                }
+                bt_plugin_find_all_from_dir_status::BT_PLUGIN_FIND_ALL_FROM_DIR_STATUS_MEMORY_ERROR => {
+                    Err(BtPluginLoadError::Memory(OutOfMemory))
+                }
+                bt_plugin_find_all_from_dir_status::BT_PLUGIN_FIND_ALL_FROM_DIR_STATUS_ERROR => {
+                    let error =
+                        BtErrorWrapper::get().expect("Error should be provided by the C API");
+                    Err(BtPluginLoadError::Other(error))
+                }
+                _ => unreachable!("Unknown bt_plugin_find_all_from_dir_status = {}", status.0),
+            }
+        }
+    }
+
+    /// Find a plugin by name in the directory `path`, without its subdirectories.
+    ///
+    /// # Errors
+    /// - [`BtPluginLoadError::NotFound`] if the plugin is not in the directory.
+    /// - Errors of [`Self::find_all_from_dir()`].
+    pub fn find_in_dir(name: &CStr, path: &CStr) -> Result<Self, BtPluginLoadError> {
+        Self::find_all_from_dir(path, false)?
+            .into_iter()
+            .find(|plugin| plugin.name_cstr() == name)
+            .ok_or(BtPluginLoadError::NotFound)
+    }
+
+    /// Take a reference to each plugin of the set and release the set.
+    unsafe fn from_plugin_set(plugin_set: *const bt_plugin_set) -> Vec<Self> {
+        debug_assert!(!plugin_set.is_null());
+        let count = bt_plugin_set_get_plugin_count(plugin_set);
+        let plugins = (0..count)
+            .map(|i| {
+                let plugin = bt_plugin_set_borrow_plugin_by_index_const(plugin_set, i);
+                bt_plugin_get_ref(plugin);
+                Self::new_unchecked(ConstNonNull::new_unchecked(plugin))
+            })
+            .collect();
+        bt_plugin_set_put_ref(plugin_set);
+        plugins
+    }
+
+    fn name_cstr(&self) -> &CStr {
+        unsafe { CStr::from_ptr(bt_plugin_get_name(self.as_ptr())) }
+    }
+
     /// Get the name of the plugin.
     ///
     /// # Panics
@@ -193,6 +303,33 @@ impl BtPlugin {
         }
     }
 
+    /// Get all component classes of the plugin: the sources, the filters and then the sinks.
+    #[must_use]
+    pub fn component_classes(&self) -> Vec<BtComponentClassConst<'_>> {
+        unsafe {
+            let plugin = self.as_ptr();
+            let sources = (0..bt_plugin_get_source_component_class_count(plugin)).map(|i| {
+                BtComponentClassSourceConst::new_unchecked(
+                    bt_plugin_borrow_source_component_class_by_index_const(plugin, i),
+                )
+                .upcast()
+            });
+            let filters = (0..bt_plugin_get_filter_component_class_count(plugin)).map(|i| {
+                BtComponentClassFilterConst::new_unchecked(
+                    bt_plugin_borrow_filter_component_class_by_index_const(plugin, i),
+                )
+                .upcast()
+            });
+            let sinks = (0..bt_plugin_get_sink_component_class_count(plugin)).map(|i| {
+                BtComponentClassSinkConst::new_unchecked(
+                    bt_plugin_borrow_sink_component_class_by_index_const(plugin, i),
+                )
+                .upcast()
+            });
+            sources.chain(filters).chain(sinks).collect()
+        }
+    }
+
     #[must_use]
     pub fn borrow_source_component_class_by_name<'a>(
         &'a self,
@@ -0,0 +1,161 @@
+//! Filter duplicating the messages of its input port to all its output ports.
+//!
+//! An output port of a babeltrace2 component can be connected to a single input port only,
+//! so the filter is needed to feed several sinks with the same messages.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::CStr;
+use std::rc::Rc;
+
+use super::user_component::{
+    ComponentMessageIterator, FilterComponent, MessageOutput, SelfComponentFilter,
+    SelfComponentPortOutput, SelfMessageIterator, UpstreamMessageIterator,
+};
+use crate::error::{BtError, BtResult};
+use crate::message::BtMessageConst;
+use crate::value::{BtValueConst, BtValueMapConst, BtValueUnsignedIntegerConst};
+
+/// Filter with the input port `in` and the output ports `out0`, `out1`, ...
+///
+/// The number of output ports is given by the `output-count` parameter (2 by default).
+pub struct Tee {
+    shared: Rc<RefCell<TeeState>>,
+}
+
+struct TeeState {
+    /// Created by the first initialized downstream iterator.
+    upstream: Option<UpstreamMessageIterator>,
+    /// Messages not yet taken by the iterator of each output port.
+    ///
+    /// `None` if the iterator of the port was finalized.
+    queues: Vec<Option<VecDeque<BtMessageConst>>>,
+    ended: bool,
+}
+
+impl Tee {
+    pub const CLASS_NAME: &CStr = c"tee";
+
+    fn output_count(params: BtValueConst) -> BtResult<u64> {
+        let Ok(params) = BtValueMapConst::try_from(params) else {
+            return Ok(2);
+        };
+        let Some(count) = params.get_with_cstr_key(c"output-count") else {
+            return Ok(2);
+        };
+        match BtValueUnsignedIntegerConst::try_from(count) {
+            Ok(count) if count.get() > 0 => Ok(count.get()),
+            _ => Err(BtError::new(
+                "Parameter `output-count` must be a positive unsigned integer",
+            )),
+        }
+    }
+}
+
+impl FilterComponent for Tee {
+    type Iterator = TeeIterator;
+
+    fn initialize(component: &mut SelfComponentFilter, params: BtValueConst) -> BtResult<Self> {
+        let output_count = Self::output_count(params)?;
+
+        component.add_input_port(c"in")?;
+        for i in 0..output_count {
+            let name = std::ffi::CString::new(format!("out{i}")).unwrap();
+            component.add_output_port(&name)?;
+        }
+
+        Ok(Self {
+            shared: Rc::new(RefCell::new(TeeState {
+                upstream: None,
+                queues: (0..output_count).map(|_| Some(VecDeque::new())).collect(),
+                ended: false,
+            })),
+        })
+    }
+}
+
+/// Message iterator of an output port of [`Tee`].
+pub struct TeeIterator {
+    shared: Rc<RefCell<TeeState>>,
+    index: usize,
+}
+
+impl TeeIterator {
+    fn queue<'a>(&self, state: &'a mut TeeState) -> &'a mut VecDeque<BtMessageConst> {
+        state.queues[self.index]
+            .as_mut()
+            .expect("Bug: queue of a finalized iterator")
+    }
+}
+
+impl Drop for TeeIterator {
+    fn drop(&mut self) {
+        // Stop collecting messages which would never be taken
+        self.shared.borrow_mut().queues[self.index] = None;
+    }
+}
+
+impl ComponentMessageIterator for TeeIterator {
+    type Component = Tee;
+
+    fn initialize(
+        component: &Tee,
+        iterator: &mut SelfMessageIterator,
+        port: SelfComponentPortOutput,
+    ) -> BtResult<Self> {
+        let index = port
+            .name()
+            .to_str()
+            .ok()
+            .and_then(|name| name.strip_prefix("out"))
+            .and_then(|index| index.parse().ok())
+            .ok_or_else(|| BtError::new("Unknown output port"))?;
+
+        let mut state = component.shared.borrow_mut();
+        if state.upstream.is_none() {
+            let port = iterator.input_port(0);
+            state.upstream = Some(iterator.create_message_iterator(port)?);
+        }
+
+        Ok(Self {
+            shared: component.shared.clone(),
+            index,
+        })
+    }
+
+    fn next(
+        &mut self,
+        _iterator: &mut SelfMessageIterator,
+        output: &mut MessageOutput,
+    ) -> BtResult<()> {
+        let mut state = self.shared.borrow_mut();
+        let state = &mut *state;
+
+        if self.queue(state).is_empty() && !state.ended {
+            let mut batch = Vec::new();
+            match state.upstream.as_mut().unwrap().next_into(&mut batch) {
+                Ok(()) => {}
+                Err(BtError::End) => state.ended = true,
+                Err(error) => return Err(error),
+            }
+            for queue in state.queues.iter_mut().flatten() {
+                queue.extend(batch.iter().cloned());
+            }
+        }
+
+        let ended = state.ended;
+        let queue = self.queue(state);
+        while !output.is_full() {
+            let Some(message) = queue.pop_front() else {
+                break;
+            };
+            // The output is not full
+            let _ = output.push(message);
+        }
+
+        if output.is_empty() && ended {
+            return Err(BtError::End);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1926 @@
+//! Components implemented in Rust.
+//!
+//! Implement [`SourceComponent`], [`FilterComponent`] or [`SinkComponent`] and create the
+//! component class with [`BtComponentClassSource::new`], [`BtComponentClassFilter::new`] or
+//! [`BtComponentClassSink::new`]. The class can be added to a graph like the classes loaded
+//! from plugins, or exported from a shared object as a babeltrace2 plugin
+//! (see [`export_filter_component_class`](crate::export_filter_component_class)
+//! and the example plugin in `bt2-sys/examples/plugin`).
+//!
+//! Source and filter components emit messages by message iterators implementing
+//! [`ComponentMessageIterator`]. A message iterator is created for each connected output port.
+//!
+//! Sources describe their traces by a [`BtTraceClass`] created by
+//! [`SelfComponentSource::create_trace_class`], which creates the stream, event and field classes.
+//! The messages of the streams are created by the methods of [`SelfMessageIterator`].
+//!
+//! Errors are reported to babeltrace2 by returning [`BtError`]. Use [`BtError::new`] to create
+//! an error with a message. Panics are caught and reported as errors.
+
+use std::any::Any;
+use std::ffi::{c_void, CStr};
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::{self, NonNull};
+
+use crate::clock_snapshot::{BtClockClass, BtClockClassConst};
+use crate::error::{BtError, BtResult, OutOfMemory};
+use crate::event::BtEventClass;
+use crate::message::{BtEventMessage, BtMessageArrayConst, BtMessageConst};
+use crate::raw_bindings::{
+    bt_component_class_filter, bt_component_class_filter_as_component_class,
+    bt_component_class_filter_create, bt_component_class_filter_put_ref,
+    bt_component_class_filter_set_finalize_method, bt_component_class_filter_set_initialize_method,
+    bt_component_class_initialize_method_status, bt_component_class_set_description,
+    bt_component_class_set_description_status, bt_component_class_set_help,
+    bt_component_class_set_help_status, bt_component_class_set_method_status,
+    bt_component_class_sink, bt_component_class_sink_as_component_class,
+    bt_component_class_sink_consume_method_status, bt_component_class_sink_create,
+    bt_component_class_sink_graph_is_configured_method_status, bt_component_class_sink_put_ref,
+    bt_component_class_sink_set_finalize_method,
+    bt_component_class_sink_set_graph_is_configured_method,
+    bt_component_class_sink_set_initialize_method, bt_component_class_source,
+    bt_component_class_source_as_component_class, bt_component_class_source_create,
+    bt_component_class_source_put_ref, bt_component_class_source_set_finalize_method,
+    bt_component_class_source_set_initialize_method, bt_component_filter_get_input_port_count,
+    bt_component_sink_get_input_port_count, bt_message, bt_message_array_const,
+    bt_message_event_create, bt_message_event_create_with_default_clock_snapshot,
+    bt_message_event_create_with_packet,
+    bt_message_event_create_with_packet_and_default_clock_snapshot, bt_message_iterator,
+    bt_message_iterator_class, bt_message_iterator_class_create,
+    bt_message_iterator_class_initialize_method_status,
+    bt_message_iterator_class_next_method_status, bt_message_iterator_class_put_ref,
+    bt_message_iterator_class_set_finalize_method, bt_message_iterator_class_set_initialize_method,
+    bt_message_iterator_class_set_method_status, bt_message_iterator_create_from_message_iterator,
+    bt_message_iterator_create_from_message_iterator_status,
+    bt_message_iterator_create_from_sink_component,
+    bt_message_iterator_create_from_sink_component_status,
+    bt_message_message_iterator_inactivity_create, bt_message_packet_beginning_create,
+    bt_message_packet_beginning_create_with_default_clock_snapshot, bt_message_packet_end_create,
+    bt_message_packet_end_create_with_default_clock_snapshot, bt_message_stream_beginning_create,
+    bt_message_stream_end_create, bt_port_get_name, bt_port_input_as_port_const,
+    bt_port_is_connected, bt_port_output_as_port_const, bt_self_component,
+    bt_self_component_add_port_status, bt_self_component_filter,
+    bt_self_component_filter_add_input_port, bt_self_component_filter_add_output_port,
+    bt_self_component_filter_as_component_filter, bt_self_component_filter_as_self_component,
+    bt_self_component_filter_borrow_input_port_by_index, bt_self_component_filter_configuration,
+    bt_self_component_get_data, bt_self_component_port_input,
+    bt_self_component_port_input_as_port_input, bt_self_component_port_output,
+    bt_self_component_port_output_as_port_output, bt_self_component_set_data,
+    bt_self_component_sink, bt_self_component_sink_add_input_port,
+    bt_self_component_sink_as_component_sink, bt_self_component_sink_as_self_component,
+    bt_self_component_sink_borrow_input_port_by_index, bt_self_component_sink_configuration,
+    bt_self_component_source, bt_self_component_source_add_output_port,
+    bt_self_component_source_as_self_component, bt_self_component_source_configuration,
+    bt_self_message_iterator, bt_self_message_iterator_borrow_component,
+    bt_self_message_iterator_configuration, bt_self_message_iterator_get_data,
+    bt_self_message_iterator_set_data, bt_value,
+};
+use crate::rethrow;
+use crate::stream::{BtPacket, BtStream, BtStreamClassConst, BtStreamConst};
+use crate::trace::BtTraceClass;
+use crate::value::BtValueConst;
+
+use super::component::{
+    BtComponentClassFilterConst, BtComponentClassSinkConst, BtComponentClassSourceConst,
+};
+
+/// Source component implemented in Rust.
+pub trait SourceComponent: Sized + 'static {
+    /// Message iterator created for each connected output port.
+    type Iterator: ComponentMessageIterator<Component = Self>;
+
+    /// Initialize the component from its parameters.
+    ///
+    /// The output ports must be added here.
+    fn initialize(component: &mut SelfComponentSource, params: BtValueConst) -> BtResult<Self>;
+}
+
+/// Filter component implemented in Rust.
+pub trait FilterComponent: Sized + 'static {
+    /// Message iterator created for each connected output port.
+    type Iterator: ComponentMessageIterator<Component = Self>;
+
+    /// Initialize the component from its parameters.
+    ///
+    /// The input and output ports must be added here.
+    fn initialize(component: &mut SelfComponentFilter, params: BtValueConst) -> BtResult<Self>;
+}
+
+/// Sink component implemented in Rust.
+pub trait SinkComponent: Sized + 'static {
+    /// Initialize the component from its parameters.
+    ///
+    /// The input ports must be added here.
+    fn initialize(component: &mut SelfComponentSink, params: BtValueConst) -> BtResult<Self>;
+
+    /// Called when all ports of the graph are connected.
+    ///
+    /// The upstream message iterators are created here
+    /// by [`SelfComponentSink::create_message_iterator`].
+    fn graph_is_configured(&mut self, component: &mut SelfComponentSink) -> BtResult<()>;
+
+    /// Consume the next messages of the upstream message iterators.
+    ///
+    /// Return [`BtError::End`] when all messages were consumed
+    /// and [`BtError::Again`] if no messages are available yet.
+    fn consume(&mut self, component: &mut SelfComponentSink) -> BtResult<()>;
+}
+
+/// Message iterator of a source or filter component implemented in Rust.
+pub trait ComponentMessageIterator: Sized + 'static {
+    /// Component owning the iterator.
+    type Component;
+
+    /// Create the message iterator of the output `port` of `component`.
+    ///
+    /// Filters create the upstream message iterators here
+    /// by [`SelfMessageIterator::create_message_iterator`].
+    fn initialize(
+        component: &Self::Component,
+        iterator: &mut SelfMessageIterator,
+        port: SelfComponentPortOutput,
+    ) -> BtResult<Self>;
+
+    /// Push the next messages to `output`.
+    ///
+    /// Return [`BtError::End`] when there are no more messages
+    /// and [`BtError::Again`] if no messages are available yet.
+    /// These are ignored if some messages were pushed, so the method is called again.
+    /// Pushed messages are released if an error is returned.
+    fn next(
+        &mut self,
+        iterator: &mut SelfMessageIterator,
+        output: &mut MessageOutput,
+    ) -> BtResult<()>;
+}
+
+/// Owned source component class implemented in Rust.
+pub struct BtComponentClassSource(NonNull<bt_component_class_source>);
+
+/// Owned filter component class implemented in Rust.
+pub struct BtComponentClassFilter(NonNull<bt_component_class_filter>);
+
+/// Owned sink component class implemented in Rust.
+pub struct BtComponentClassSink(NonNull<bt_component_class_sink>);
+
+/// Message iterator class with the methods of `I`.
+fn create_message_iterator_class<I: ComponentMessageIterator, const FILTER: bool>(
+) -> Result<NonNull<bt_message_iterator_class>, OutOfMemory> {
+    unsafe {
+        let class = NonNull::new(bt_message_iterator_class_create(Some(
+            iterator_next::<I, FILTER>,
+        )))
+        .ok_or(OutOfMemory)?;
+        let status = bt_message_iterator_class_set_initialize_method(
+            class.as_ptr(),
+            Some(iterator_initialize::<I, FILTER>),
+        );
+        debug_assert_eq!(
+            status,
+            bt_message_iterator_class_set_method_status::BT_MESSAGE_ITERATOR_CLASS_SET_METHOD_STATUS_OK
+        );
+        let status = bt_message_iterator_class_set_finalize_method(
+            class.as_ptr(),
+            Some(iterator_finalize::<I>),
+        );
+        debug_assert_eq!(
+            status,
+            bt_message_iterator_class_set_method_status::BT_MESSAGE_ITERATOR_CLASS_SET_METHOD_STATUS_OK
+        );
+        Ok(class)
+    }
+}
+
+const SET_METHOD_OK: bt_component_class_set_method_status =
+    bt_component_class_set_method_status::BT_COMPONENT_CLASS_SET_METHOD_STATUS_OK;
+
+impl BtComponentClassSource {
+    /// Create a source component class named `name` implemented by `C`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new<C: SourceComponent>(name: &CStr) -> Result<Self, OutOfMemory> {
+        let iterator_class = create_message_iterator_class::<C::Iterator, false>()?;
+        unsafe {
+            let class = bt_component_class_source_create(name.as_ptr(), iterator_class.as_ptr());
+            // The component class holds its own reference
+            bt_message_iterator_class_put_ref(iterator_class.as_ptr());
+            let class = Self(NonNull::new(class).ok_or(OutOfMemory)?);
+
+            let status = bt_component_class_source_set_initialize_method(
+                class.as_ptr(),
+                Some(source_initialize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+            let status = bt_component_class_source_set_finalize_method(
+                class.as_ptr(),
+                Some(source_finalize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+
+            Ok(class)
+        }
+    }
+
+    const fn as_ptr(&self) -> *mut bt_component_class_source {
+        self.0.as_ptr()
+    }
+
+    /// Set the description shown by `babeltrace2 list-plugins`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_description(&mut self, description: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_description(
+                bt_component_class_source_as_component_class(self.as_ptr()),
+                description,
+            )
+        }
+    }
+
+    /// Set the help shown by `babeltrace2 help`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_help(&mut self, help: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_help(
+                bt_component_class_source_as_component_class(self.as_ptr()),
+                help,
+            )
+        }
+    }
+
+    /// Borrow the class to add its components to a graph.
+    #[must_use]
+    pub fn as_const(&self) -> BtComponentClassSourceConst<'_> {
+        unsafe { BtComponentClassSourceConst::new_unchecked(self.as_ptr()) }
+    }
+}
+
+impl Drop for BtComponentClassSource {
+    fn drop(&mut self) {
+        unsafe { bt_component_class_source_put_ref(self.as_ptr()) }
+    }
+}
+
+impl BtComponentClassFilter {
+    /// Create a filter component class named `name` implemented by `C`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new<C: FilterComponent>(name: &CStr) -> Result<Self, OutOfMemory> {
+        let iterator_class = create_message_iterator_class::<C::Iterator, true>()?;
+        unsafe {
+            let class = bt_component_class_filter_create(name.as_ptr(), iterator_class.as_ptr());
+            // The component class holds its own reference
+            bt_message_iterator_class_put_ref(iterator_class.as_ptr());
+            let class = Self(NonNull::new(class).ok_or(OutOfMemory)?);
+
+            let status = bt_component_class_filter_set_initialize_method(
+                class.as_ptr(),
+                Some(filter_initialize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+            let status = bt_component_class_filter_set_finalize_method(
+                class.as_ptr(),
+                Some(filter_finalize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+
+            Ok(class)
+        }
+    }
+
+    const fn as_ptr(&self) -> *mut bt_component_class_filter {
+        self.0.as_ptr()
+    }
+
+    /// Set the description shown by `babeltrace2 list-plugins`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_description(&mut self, description: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_description(
+                bt_component_class_filter_as_component_class(self.as_ptr()),
+                description,
+            )
+        }
+    }
+
+    /// Set the help shown by `babeltrace2 help`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_help(&mut self, help: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_help(
+                bt_component_class_filter_as_component_class(self.as_ptr()),
+                help,
+            )
+        }
+    }
+
+    /// Borrow the class to add its components to a graph.
+    #[must_use]
+    pub fn as_const(&self) -> BtComponentClassFilterConst<'_> {
+        unsafe { BtComponentClassFilterConst::new_unchecked(self.as_ptr()) }
+    }
+}
+
+impl Drop for BtComponentClassFilter {
+    fn drop(&mut self) {
+        unsafe { bt_component_class_filter_put_ref(self.as_ptr()) }
+    }
+}
+
+impl BtComponentClassSink {
+    /// Create a sink component class named `name` implemented by `C`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn new<C: SinkComponent>(name: &CStr) -> Result<Self, OutOfMemory> {
+        unsafe {
+            let class = bt_component_class_sink_create(name.as_ptr(), Some(sink_consume::<C>));
+            let class = Self(NonNull::new(class).ok_or(OutOfMemory)?);
+
+            let status = bt_component_class_sink_set_initialize_method(
+                class.as_ptr(),
+                Some(sink_initialize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+            let status = bt_component_class_sink_set_graph_is_configured_method(
+                class.as_ptr(),
+                Some(sink_graph_is_configured::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+            let status = bt_component_class_sink_set_finalize_method(
+                class.as_ptr(),
+                Some(sink_finalize::<C>),
+            );
+            debug_assert_eq!(status, SET_METHOD_OK);
+
+            Ok(class)
+        }
+    }
+
+    const fn as_ptr(&self) -> *mut bt_component_class_sink {
+        self.0.as_ptr()
+    }
+
+    /// Set the description shown by `babeltrace2 list-plugins`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_description(&mut self, description: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_description(
+                bt_component_class_sink_as_component_class(self.as_ptr()),
+                description,
+            )
+        }
+    }
+
+    /// Set the help shown by `babeltrace2 help`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_help(&mut self, help: &CStr) -> Result<(), OutOfMemory> {
+        unsafe {
+            set_help(
+                bt_component_class_sink_as_component_class(self.as_ptr()),
+                help,
+            )
+        }
+    }
+
+    /// Borrow the class to add its components to a graph.
+    #[must_use]
+    pub fn as_const(&self) -> BtComponentClassSinkConst<'_> {
+        unsafe { BtComponentClassSinkConst::new_unchecked(self.as_ptr()) }
+    }
+}
+
+impl Drop for BtComponentClassSink {
+    fn drop(&mut self) {
+        unsafe { bt_component_class_sink_put_ref(self.as_ptr()) }
+    }
+}
+
+unsafe fn set_description(
+    class: *mut crate::raw_bindings::bt_component_class,
+    description: &CStr,
+) -> Result<(), OutOfMemory> {
+    match bt_component_class_set_description(class, description.as_ptr()) {
+        bt_component_class_set_description_status::BT_COMPONENT_CLASS_SET_DESCRIPTION_STATUS_OK => {
+            Ok(())
+        }
+        _ => Err(OutOfMemory),
+    }
+}
+
+unsafe fn set_help(
+    class: *mut crate::raw_bindings::bt_component_class,
+    help: &CStr,
+) -> Result<(), OutOfMemory> {
+    match bt_component_class_set_help(class, help.as_ptr()) {
+        bt_component_class_set_help_status::BT_COMPONENT_CLASS_SET_HELP_STATUS_OK => Ok(()),
+        _ => Err(OutOfMemory),
+    }
+}
+
+/// Input port of a component implemented in Rust.
+#[derive(Clone, Copy)]
+pub struct SelfComponentPortInput<'a>(NonNull<bt_self_component_port_input>, PhantomData<&'a ()>);
+
+/// Output port of a component implemented in Rust.
+#[derive(Clone, Copy)]
+pub struct SelfComponentPortOutput<'a>(NonNull<bt_self_component_port_output>, PhantomData<&'a ()>);
+
+impl SelfComponentPortInput<'_> {
+    const fn as_ptr(self) -> *mut bt_self_component_port_input {
+        self.0.as_ptr()
+    }
+
+    fn as_port(self) -> *const crate::raw_bindings::bt_port {
+        unsafe {
+            bt_port_input_as_port_const(bt_self_component_port_input_as_port_input(self.as_ptr()))
+        }
+    }
+
+    /// Name of the port.
+    #[must_use]
+    pub fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(bt_port_get_name(self.as_port())) }
+    }
+
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        unsafe { bt_port_is_connected(self.as_port()) != 0 }
+    }
+}
+
+impl SelfComponentPortOutput<'_> {
+    const fn as_ptr(self) -> *mut bt_self_component_port_output {
+        self.0.as_ptr()
+    }
+
+    fn as_port(self) -> *const crate::raw_bindings::bt_port {
+        unsafe {
+            bt_port_output_as_port_const(bt_self_component_port_output_as_port_output(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Name of the port.
+    #[must_use]
+    pub fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(bt_port_get_name(self.as_port())) }
+    }
+
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        unsafe { bt_port_is_connected(self.as_port()) != 0 }
+    }
+}
+
+fn add_port_result(status: bt_self_component_add_port_status) -> BtResult<()> {
+    match status {
+        bt_self_component_add_port_status::BT_SELF_COMPONENT_ADD_PORT_STATUS_OK => Ok(()),
+        bt_self_component_add_port_status::BT_SELF_COMPONENT_ADD_PORT_STATUS_MEMORY_ERROR => {
+            Err(BtError::MEMORY_ERROR)
+        }
+        bt_self_component_add_port_status::BT_SELF_COMPONENT_ADD_PORT_STATUS_ERROR => {
+            Err(BtError::take_current())
+        }
+        status => unreachable!(
+            "Bug: unknown bt_self_component_add_port_status = {}",
+            status.0
+        ),
+    }
+}
+
+/// Source component being initialized.
+pub struct SelfComponentSource(NonNull<bt_self_component_source>);
+
+impl SelfComponentSource {
+    const fn as_ptr(&self) -> *mut bt_self_component_source {
+        self.0.as_ptr()
+    }
+
+    /// Create a trace class owned by this component.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_trace_class(&mut self) -> Result<BtTraceClass, OutOfMemory> {
+        unsafe { BtTraceClass::new(bt_self_component_source_as_self_component(self.as_ptr())) }
+    }
+
+    /// Create a clock class owned by this component.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_clock_class(&mut self) -> Result<BtClockClass, OutOfMemory> {
+        unsafe { BtClockClass::new(bt_self_component_source_as_self_component(self.as_ptr())) }
+    }
+
+    /// Add an output port named `name`.
+    ///
+    /// # Errors
+    /// If the name is already used by another output port or the memory allocation fails.
+    pub fn add_output_port(&mut self, name: &CStr) -> BtResult<SelfComponentPortOutput<'_>> {
+        let mut port = ptr::null_mut();
+        add_port_result(unsafe {
+            bt_self_component_source_add_output_port(
+                self.as_ptr(),
+                name.as_ptr(),
+                ptr::null_mut(),
+                &mut port,
+            )
+        })?;
+        Ok(SelfComponentPortOutput(
+            NonNull::new(port).unwrap(),
+            PhantomData,
+        ))
+    }
+}
+
+/// Filter component being initialized.
+pub struct SelfComponentFilter(NonNull<bt_self_component_filter>);
+
+impl SelfComponentFilter {
+    const fn as_ptr(&self) -> *mut bt_self_component_filter {
+        self.0.as_ptr()
+    }
+
+    /// Create a trace class owned by this component.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_trace_class(&mut self) -> Result<BtTraceClass, OutOfMemory> {
+        unsafe { BtTraceClass::new(bt_self_component_filter_as_self_component(self.as_ptr())) }
+    }
+
+    /// Create a clock class owned by this component.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_clock_class(&mut self) -> Result<BtClockClass, OutOfMemory> {
+        unsafe { BtClockClass::new(bt_self_component_filter_as_self_component(self.as_ptr())) }
+    }
+
+    /// Add an input port named `name`.
+    ///
+    /// # Errors
+    /// If the name is already used by another input port or the memory allocation fails.
+    pub fn add_input_port(&mut self, name: &CStr) -> BtResult<SelfComponentPortInput<'_>> {
+        let mut port = ptr::null_mut();
+        add_port_result(unsafe {
+            bt_self_component_filter_add_input_port(
+                self.as_ptr(),
+                name.as_ptr(),
+                ptr::null_mut(),
+                &mut port,
+            )
+        })?;
+        Ok(SelfComponentPortInput(
+            NonNull::new(port).unwrap(),
+            PhantomData,
+        ))
+    }
+
+    /// Add an output port named `name`.
+    ///
+    /// # Errors
+    /// If the name is already used by another output port or the memory allocation fails.
+    pub fn add_output_port(&mut self, name: &CStr) -> BtResult<SelfComponentPortOutput<'_>> {
+        let mut port = ptr::null_mut();
+        add_port_result(unsafe {
+            bt_self_component_filter_add_output_port(
+                self.as_ptr(),
+                name.as_ptr(),
+                ptr::null_mut(),
+                &mut port,
+            )
+        })?;
+        Ok(SelfComponentPortOutput(
+            NonNull::new(port).unwrap(),
+            PhantomData,
+        ))
+    }
+}
+
+/// Sink component implemented in Rust.
+pub struct SelfComponentSink(NonNull<bt_self_component_sink>);
+
+impl SelfComponentSink {
+    const fn as_ptr(&self) -> *mut bt_self_component_sink {
+        self.0.as_ptr()
+    }
+
+    /// Add an input port named `name`.
+    ///
+    /// # Errors
+    /// If the name is already used by another input port or the memory allocation fails.
+    pub fn add_input_port(&mut self, name: &CStr) -> BtResult<SelfComponentPortInput<'_>> {
+        let mut port = ptr::null_mut();
+        add_port_result(unsafe {
+            bt_self_component_sink_add_input_port(
+                self.as_ptr(),
+                name.as_ptr(),
+                ptr::null_mut(),
+                &mut port,
+            )
+        })?;
+        Ok(SelfComponentPortInput(
+            NonNull::new(port).unwrap(),
+            PhantomData,
+        ))
+    }
+
+    #[must_use]
+    pub fn input_port_count(&self) -> u64 {
+        unsafe {
+            bt_component_sink_get_input_port_count(bt_self_component_sink_as_component_sink(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Get the input port at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    #[must_use]
+    pub fn input_port(&self, index: u64) -> SelfComponentPortInput<'_> {
+        assert!(index < self.input_port_count());
+        let port =
+            unsafe { bt_self_component_sink_borrow_input_port_by_index(self.as_ptr(), index) };
+        SelfComponentPortInput(NonNull::new(port).unwrap(), PhantomData)
+    }
+
+    /// Create a message iterator of the upstream component connected to `port`.
+    ///
+    /// Can be called only in [`SinkComponent::graph_is_configured`] and later.
+    ///
+    /// # Errors
+    /// If the upstream component fails to create the iterator.
+    pub fn create_message_iterator(
+        &self,
+        port: SelfComponentPortInput,
+    ) -> BtResult<UpstreamMessageIterator> {
+        let mut iterator = ptr::null_mut();
+        let status = unsafe {
+            bt_message_iterator_create_from_sink_component(
+                self.as_ptr(),
+                port.as_ptr(),
+                &mut iterator,
+            )
+        };
+        match status {
+            bt_message_iterator_create_from_sink_component_status::BT_MESSAGE_ITERATOR_CREATE_FROM_SINK_COMPONENT_STATUS_OK => {
+                Ok(UpstreamMessageIterator(NonNull::new(iterator).unwrap()))
+            }
+            bt_message_iterator_create_from_sink_component_status::BT_MESSAGE_ITERATOR_CREATE_FROM_SINK_COMPONENT_STATUS_MEMORY_ERROR => {
+                Err(BtError::MEMORY_ERROR)
+            }
+            bt_message_iterator_create_from_sink_component_status::BT_MESSAGE_ITERATOR_CREATE_FROM_SINK_COMPONENT_STATUS_ERROR => {
+                Err(BtError::take_current())
+            }
+            status => unreachable!(
+                "Bug: unknown bt_message_iterator_create_from_sink_component_status = {}",
+                status.0
+            ),
+        }
+    }
+}
+
+/// Message iterator of a source or filter component implemented in Rust.
+pub struct SelfMessageIterator {
+    ptr: NonNull<bt_self_message_iterator>,
+    /// The component of the iterator, if it is a filter.
+    filter: Option<NonNull<bt_self_component_filter>>,
+}
+
+impl SelfMessageIterator {
+    const fn as_ptr(&self) -> *mut bt_self_message_iterator {
+        self.ptr.as_ptr()
+    }
+
+    /// Create a trace class owned by the component of this iterator.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_trace_class(&mut self) -> Result<BtTraceClass, OutOfMemory> {
+        unsafe { BtTraceClass::new(bt_self_message_iterator_borrow_component(self.as_ptr())) }
+    }
+
+    /// Create a clock class owned by the component of this iterator.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_clock_class(&mut self) -> Result<BtClockClass, OutOfMemory> {
+        unsafe { BtClockClass::new(bt_self_message_iterator_borrow_component(self.as_ptr())) }
+    }
+
+    /// Number of input ports of the filter component of this iterator.
+    ///
+    /// Source components have no input ports.
+    #[must_use]
+    pub fn input_port_count(&self) -> u64 {
+        self.filter.map_or(0, |filter| unsafe {
+            bt_component_filter_get_input_port_count(bt_self_component_filter_as_component_filter(
+                filter.as_ptr(),
+            ))
+        })
+    }
+
+    /// Get the input port at `index` of the filter component of this iterator.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    #[must_use]
+    pub fn input_port(&self, index: u64) -> SelfComponentPortInput<'_> {
+        assert!(index < self.input_port_count());
+        let filter = self.filter.unwrap();
+        let port =
+            unsafe { bt_self_component_filter_borrow_input_port_by_index(filter.as_ptr(), index) };
+        SelfComponentPortInput(NonNull::new(port).unwrap(), PhantomData)
+    }
+
+    /// Create a message iterator of the upstream component connected to `port`.
+    ///
+    /// # Errors
+    /// If the upstream component fails to create the iterator.
+    pub fn create_message_iterator(
+        &self,
+        port: SelfComponentPortInput,
+    ) -> BtResult<UpstreamMessageIterator> {
+        let mut iterator = ptr::null_mut();
+        let status = unsafe {
+            bt_message_iterator_create_from_message_iterator(
+                self.as_ptr(),
+                port.as_ptr(),
+                &mut iterator,
+            )
+        };
+        match status {
+            bt_message_iterator_create_from_message_iterator_status::BT_MESSAGE_ITERATOR_CREATE_FROM_MESSAGE_ITERATOR_STATUS_OK => {
+                Ok(UpstreamMessageIterator(NonNull::new(iterator).unwrap()))
+            }
+            bt_message_iterator_create_from_message_iterator_status::BT_MESSAGE_ITERATOR_CREATE_FROM_MESSAGE_ITERATOR_STATUS_MEMORY_ERROR => {
+                Err(BtError::MEMORY_ERROR)
+            }
+            bt_message_iterator_create_from_message_iterator_status::BT_MESSAGE_ITERATOR_CREATE_FROM_MESSAGE_ITERATOR_STATUS_ERROR => {
+                Err(BtError::take_current())
+            }
+            status => unreachable!(
+                "Bug: unknown bt_message_iterator_create_from_message_iterator_status = {}",
+                status.0
+            ),
+        }
+    }
+
+    fn created_message(message: *mut bt_message) -> BtResult<BtMessageConst> {
+        NonNull::new(message)
+            .map(|message| unsafe { BtMessageConst::new_unchecked(message.as_ptr()) })
+            .ok_or(BtError::MEMORY_ERROR)
+    }
+
+    /// Create a message beginning the `stream`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_stream_beginning_message(
+        &mut self,
+        stream: &BtStreamConst,
+    ) -> BtResult<BtMessageConst> {
+        Self::created_message(unsafe {
+            bt_message_stream_beginning_create(self.as_ptr(), stream.get_ptr())
+        })
+    }
+
+    /// Create a message ending the `stream`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_stream_end_message(
+        &mut self,
+        stream: &BtStreamConst,
+    ) -> BtResult<BtMessageConst> {
+        Self::created_message(unsafe {
+            bt_message_stream_end_create(self.as_ptr(), stream.get_ptr())
+        })
+    }
+
+    /// Create a message informing that there are no messages before `raw_value` of `clock_class`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_inactivity_message(
+        &mut self,
+        clock_class: &BtClockClassConst,
+        raw_value: u64,
+    ) -> BtResult<BtMessageConst> {
+        Self::created_message(unsafe {
+            bt_message_message_iterator_inactivity_create(
+                self.as_ptr(),
+                clock_class.as_ptr(),
+                raw_value,
+            )
+        })
+    }
+
+    /// Create a message beginning the `packet`.
+    ///
+    /// The `clock_value` is the value of the default clock of the stream at the beginning
+    /// of the packet.
+    ///
+    /// # Panics
+    /// If the `clock_value` is given exactly when the stream class does not have
+    /// packet beginning clock values, see [`BtStreamClass::set_supports_packets`].
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_packet_beginning_message(
+        &mut self,
+        packet: &BtPacket,
+        clock_value: Option<u64>,
+    ) -> BtResult<BtMessageConst> {
+        let stream_class = packet.as_const().get_stream().get_class();
+        assert_eq!(
+            clock_value.is_some(),
+            stream_class.packets_have_beginning_default_clock_snapshot(),
+            "Packet beginning clock value must be given exactly when the stream class has it"
+        );
+
+        Self::created_message(unsafe {
+            match clock_value {
+                Some(value) => bt_message_packet_beginning_create_with_default_clock_snapshot(
+                    self.as_ptr(),
+                    packet.as_ptr(),
+                    value,
+                ),
+                None => bt_message_packet_beginning_create(self.as_ptr(), packet.as_ptr()),
+            }
+        })
+    }
+
+    /// Create a message ending the `packet`.
+    ///
+    /// The `clock_value` is the value of the default clock of the stream at the end
+    /// of the packet.
+    ///
+    /// # Panics
+    /// If the `clock_value` is given exactly when the stream class does not have
+    /// packet end clock values, see [`BtStreamClass::set_supports_packets`].
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_packet_end_message(
+        &mut self,
+        packet: &BtPacket,
+        clock_value: Option<u64>,
+    ) -> BtResult<BtMessageConst> {
+        let stream_class = packet.as_const().get_stream().get_class();
+        assert_eq!(
+            clock_value.is_some(),
+            stream_class.packets_have_end_default_clock_snapshot(),
+            "Packet end clock value must be given exactly when the stream class has it"
+        );
+
+        Self::created_message(unsafe {
+            match clock_value {
+                Some(value) => bt_message_packet_end_create_with_default_clock_snapshot(
+                    self.as_ptr(),
+                    packet.as_ptr(),
+                    value,
+                ),
+                None => bt_message_packet_end_create(self.as_ptr(), packet.as_ptr()),
+            }
+        })
+    }
+
+    fn created_event_message(message: *mut bt_message) -> BtResult<BtEventMessage> {
+        NonNull::new(message)
+            .map(|message| unsafe { BtEventMessage::new_unchecked(message.as_ptr()) })
+            .ok_or(BtError::MEMORY_ERROR)
+    }
+
+    fn assert_event_clock_value(stream_class: &BtStreamClassConst, clock_value: Option<u64>) {
+        assert_eq!(
+            clock_value.is_some(),
+            stream_class.get_default_clock_class().is_some(),
+            "Event clock value must be given exactly when the stream class has a default clock"
+        );
+    }
+
+    /// Create a message of an event of `event_class` in the `stream`.
+    ///
+    /// The `clock_value` is the value of the default clock of the stream when the event occurred.
+    /// The payload of the event is set by [`BtEventMessage::get_payload_mut`].
+    ///
+    /// # Panics
+    /// - If the stream class supports packets, use [`Self::create_event_message_in_packet`].
+    /// - If the `clock_value` is given exactly when the stream class has no default clock class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_event_message(
+        &mut self,
+        event_class: &BtEventClass,
+        stream: &BtStream,
+        clock_value: Option<u64>,
+    ) -> BtResult<BtEventMessage> {
+        let stream_class = stream.as_const().get_class();
+        assert!(
+            !stream_class.supports_packets(),
+            "Events of streams with packets must be created in a packet"
+        );
+        Self::assert_event_clock_value(&stream_class, clock_value);
+
+        Self::created_event_message(unsafe {
+            match clock_value {
+                Some(value) => bt_message_event_create_with_default_clock_snapshot(
+                    self.as_ptr(),
+                    event_class.as_ptr(),
+                    stream.as_ptr(),
+                    value,
+                ),
+                None => {
+                    bt_message_event_create(self.as_ptr(), event_class.as_ptr(), stream.as_ptr())
+                }
+            }
+        })
+    }
+
+    /// Create a message of an event of `event_class` in the `packet`.
+    ///
+    /// Like [`Self::create_event_message`] for streams with packets.
+    ///
+    /// # Panics
+    /// If the `clock_value` is given exactly when the stream class has no default clock class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_event_message_in_packet(
+        &mut self,
+        event_class: &BtEventClass,
+        packet: &BtPacket,
+        clock_value: Option<u64>,
+    ) -> BtResult<BtEventMessage> {
+        Self::assert_event_clock_value(&packet.as_const().get_stream().get_class(), clock_value);
+
+        Self::created_event_message(unsafe {
+            match clock_value {
+                Some(value) => bt_message_event_create_with_packet_and_default_clock_snapshot(
+                    self.as_ptr(),
+                    event_class.as_ptr(),
+                    packet.as_ptr(),
+                    value,
+                ),
+                None => bt_message_event_create_with_packet(
+                    self.as_ptr(),
+                    event_class.as_ptr(),
+                    packet.as_ptr(),
+                ),
+            }
+        })
+    }
+}
+
+/// Owned message iterator of an upstream component.
+pub struct UpstreamMessageIterator(NonNull<bt_message_iterator>);
+
+impl UpstreamMessageIterator {
+    /// Append the next messages of the upstream component to `messages`.
+    ///
+    /// # Errors
+    /// - [`BtError::End`] if there are no more messages.
+    /// - [`BtError::Again`] if no messages are available yet.
+    /// - Other errors of the upstream component.
+    pub fn next_into(&mut self, messages: &mut impl Extend<BtMessageConst>) -> BtResult<()> {
+        let mut iterator = crate::wrappers::BtMessageIterator::from(self.0.as_ptr());
+        let mut batch: BtMessageArrayConst = iterator.next()?;
+        messages.extend(batch.iter_mut().filter_map(Option::take));
+        Ok(())
+    }
+}
+
+impl Drop for UpstreamMessageIterator {
+    fn drop(&mut self) {
+        unsafe { crate::raw_bindings::bt_message_iterator_put_ref(self.0.as_ptr()) }
+    }
+}
+
+/// Messages returned by [`ComponentMessageIterator::next`].
+pub struct MessageOutput<'a> {
+    messages: &'a mut [*const bt_message],
+    len: usize,
+}
+
+impl MessageOutput<'_> {
+    /// Maximum number of messages.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.messages.len()
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Release the pushed messages.
+    fn clear(&mut self) {
+        for &message in &self.messages[..self.len] {
+            drop(unsafe { BtMessageConst::new_unchecked(message) });
+        }
+        self.len = 0;
+    }
+
+    /// Append `message` to the output.
+    ///
+    /// # Errors
+    /// Returns the message back if the output is full.
+    pub fn push(&mut self, message: BtMessageConst) -> Result<(), BtMessageConst> {
+        if self.is_full() {
+            return Err(message);
+        }
+
+        // The reference is moved to the output array
+        self.messages[self.len] = message.as_ptr();
+        std::mem::forget(message);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+/// Run `f`, reporting its panic as an error.
+fn catch_panic<T>(f: impl FnOnce() -> BtResult<T>) -> BtResult<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload: Box<dyn Any + Send>| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        Err(BtError::new(&format!("Component panicked: {message}")))
+    })
+}
+
+/// Status of a method implemented in Rust.
+enum MethodStatus {
+    Ok,
+    End,
+    Again,
+    MemoryError,
+    Error,
+}
+
+impl MethodStatus {
+    /// Convert the result of a method, appending `context` to the error causes.
+    fn from_result(result: BtResult<()>, context: &str) -> Self {
+        match result {
+            Ok(()) => Self::Ok,
+            Err(BtError::End) => Self::End,
+            Err(BtError::Again(_)) => Self::Again,
+            Err(BtError::MemoryError(_)) => Self::MemoryError,
+            Err(error @ BtError::Error(_)) => {
+                rethrow!(error, context);
+                Self::Error
+            }
+        }
+    }
+
+    /// Status of a method which cannot end or be retried.
+    fn fallible(self, context: &str) -> Self {
+        match self {
+            Self::End | Self::Again => {
+                BtError::__throw_new(&format!("{context}: unexpected status"), file!(), line!());
+                Self::Error
+            }
+            status => status,
+        }
+    }
+}
+
+impl From<MethodStatus> for bt_component_class_initialize_method_status {
+    fn from(status: MethodStatus) -> Self {
+        match status.fallible("Component initialization") {
+            MethodStatus::Ok => Self::BT_COMPONENT_CLASS_INITIALIZE_METHOD_STATUS_OK,
+            MethodStatus::MemoryError => {
+                Self::BT_COMPONENT_CLASS_INITIALIZE_METHOD_STATUS_MEMORY_ERROR
+            }
+            _ => Self::BT_COMPONENT_CLASS_INITIALIZE_METHOD_STATUS_ERROR,
+        }
+    }
+}
+
+impl From<MethodStatus> for bt_message_iterator_class_initialize_method_status {
+    fn from(status: MethodStatus) -> Self {
+        match status.fallible("Message iterator initialization") {
+            MethodStatus::Ok => Self::BT_MESSAGE_ITERATOR_CLASS_INITIALIZE_METHOD_STATUS_OK,
+            MethodStatus::MemoryError => {
+                Self::BT_MESSAGE_ITERATOR_CLASS_INITIALIZE_METHOD_STATUS_MEMORY_ERROR
+            }
+            _ => Self::BT_MESSAGE_ITERATOR_CLASS_INITIALIZE_METHOD_STATUS_ERROR,
+        }
+    }
+}
+
+impl From<MethodStatus> for bt_component_class_sink_graph_is_configured_method_status {
+    fn from(status: MethodStatus) -> Self {
+        match status.fallible("Sink configuration") {
+            MethodStatus::Ok => Self::BT_COMPONENT_CLASS_SINK_GRAPH_IS_CONFIGURED_METHOD_STATUS_OK,
+            MethodStatus::MemoryError => {
+                Self::BT_COMPONENT_CLASS_SINK_GRAPH_IS_CONFIGURED_METHOD_STATUS_MEMORY_ERROR
+            }
+            _ => Self::BT_COMPONENT_CLASS_SINK_GRAPH_IS_CONFIGURED_METHOD_STATUS_ERROR,
+        }
+    }
+}
+
+impl From<MethodStatus> for bt_component_class_sink_consume_method_status {
+    fn from(status: MethodStatus) -> Self {
+        match status {
+            MethodStatus::Ok => Self::BT_COMPONENT_CLASS_SINK_CONSUME_METHOD_STATUS_OK,
+            MethodStatus::End => Self::BT_COMPONENT_CLASS_SINK_CONSUME_METHOD_STATUS_END,
+            MethodStatus::Again => Self::BT_COMPONENT_CLASS_SINK_CONSUME_METHOD_STATUS_AGAIN,
+            MethodStatus::MemoryError => {
+                Self::BT_COMPONENT_CLASS_SINK_CONSUME_METHOD_STATUS_MEMORY_ERROR
+            }
+            MethodStatus::Error => Self::BT_COMPONENT_CLASS_SINK_CONSUME_METHOD_STATUS_ERROR,
+        }
+    }
+}
+
+impl From<MethodStatus> for bt_message_iterator_class_next_method_status {
+    fn from(status: MethodStatus) -> Self {
+        match status {
+            MethodStatus::Ok => Self::BT_MESSAGE_ITERATOR_CLASS_NEXT_METHOD_STATUS_OK,
+            MethodStatus::End => Self::BT_MESSAGE_ITERATOR_CLASS_NEXT_METHOD_STATUS_END,
+            MethodStatus::Again => Self::BT_MESSAGE_ITERATOR_CLASS_NEXT_METHOD_STATUS_AGAIN,
+            MethodStatus::MemoryError => {
+                Self::BT_MESSAGE_ITERATOR_CLASS_NEXT_METHOD_STATUS_MEMORY_ERROR
+            }
+            MethodStatus::Error => Self::BT_MESSAGE_ITERATOR_CLASS_NEXT_METHOD_STATUS_ERROR,
+        }
+    }
+}
+
+/// Store the initialized component `C` in the data of `self_component`.
+unsafe fn initialize_component<C: 'static>(
+    self_component: *mut bt_self_component,
+    initialize: impl FnOnce() -> BtResult<C>,
+) -> MethodStatus {
+    let result = catch_panic(initialize).map(|component| {
+        bt_self_component_set_data(self_component, Box::into_raw(Box::new(component)).cast());
+    });
+    MethodStatus::from_result(result, "Failed to initialize component")
+}
+
+/// Borrow the component `C` stored in the data of `self_component`.
+unsafe fn component_data<'a, C: 'static>(self_component: *mut bt_self_component) -> &'a mut C {
+    &mut *bt_self_component_get_data(self_component).cast::<C>()
+}
+
+/// Drop the component `C` stored in the data of `self_component`.
+unsafe fn finalize_component<C: 'static>(self_component: *mut bt_self_component) {
+    let data = bt_self_component_get_data(self_component).cast::<C>();
+    if !data.is_null() {
+        drop(Box::from_raw(data));
+    }
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn source_initialize<C: SourceComponent>(
+    self_component: *mut bt_self_component_source,
+    _configuration: *mut bt_self_component_source_configuration,
+    params: *const bt_value,
+    _initialize_method_data: *mut c_void,
+) -> bt_component_class_initialize_method_status {
+    let mut component = SelfComponentSource(NonNull::new(self_component).unwrap());
+    let params = BtValueConst::new_unchecked(params);
+    initialize_component(
+        bt_self_component_source_as_self_component(self_component),
+        || C::initialize(&mut component, params),
+    )
+    .into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn source_finalize<C: SourceComponent>(
+    self_component: *mut bt_self_component_source,
+) {
+    finalize_component::<C>(bt_self_component_source_as_self_component(self_component));
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn filter_initialize<C: FilterComponent>(
+    self_component: *mut bt_self_component_filter,
+    _configuration: *mut bt_self_component_filter_configuration,
+    params: *const bt_value,
+    _initialize_method_data: *mut c_void,
+) -> bt_component_class_initialize_method_status {
+    let mut component = SelfComponentFilter(NonNull::new(self_component).unwrap());
+    let params = BtValueConst::new_unchecked(params);
+    initialize_component(
+        bt_self_component_filter_as_self_component(self_component),
+        || C::initialize(&mut component, params),
+    )
+    .into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn filter_finalize<C: FilterComponent>(
+    self_component: *mut bt_self_component_filter,
+) {
+    finalize_component::<C>(bt_self_component_filter_as_self_component(self_component));
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn sink_initialize<C: SinkComponent>(
+    self_component: *mut bt_self_component_sink,
+    _configuration: *mut bt_self_component_sink_configuration,
+    params: *const bt_value,
+    _initialize_method_data: *mut c_void,
+) -> bt_component_class_initialize_method_status {
+    let mut component = SelfComponentSink(NonNull::new(self_component).unwrap());
+    let params = BtValueConst::new_unchecked(params);
+    initialize_component(
+        bt_self_component_sink_as_self_component(self_component),
+        || C::initialize(&mut component, params),
+    )
+    .into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn sink_graph_is_configured<C: SinkComponent>(
+    self_component: *mut bt_self_component_sink,
+) -> bt_component_class_sink_graph_is_configured_method_status {
+    let sink = component_data::<C>(bt_self_component_sink_as_self_component(self_component));
+    let mut component = SelfComponentSink(NonNull::new(self_component).unwrap());
+    let result = catch_panic(|| sink.graph_is_configured(&mut component));
+    MethodStatus::from_result(result, "Failed to configure sink component").into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn sink_consume<C: SinkComponent>(
+    self_component: *mut bt_self_component_sink,
+) -> bt_component_class_sink_consume_method_status {
+    let sink = component_data::<C>(bt_self_component_sink_as_self_component(self_component));
+    let mut component = SelfComponentSink(NonNull::new(self_component).unwrap());
+    let result = catch_panic(|| sink.consume(&mut component));
+    MethodStatus::from_result(result, "Sink component failed to consume messages").into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn sink_finalize<C: SinkComponent>(
+    self_component: *mut bt_self_component_sink,
+) {
+    finalize_component::<C>(bt_self_component_sink_as_self_component(self_component));
+}
+
+unsafe fn self_message_iterator<const FILTER: bool>(
+    self_iterator: *mut bt_self_message_iterator,
+) -> SelfMessageIterator {
+    let component = bt_self_message_iterator_borrow_component(self_iterator);
+    SelfMessageIterator {
+        ptr: NonNull::new(self_iterator).unwrap(),
+        // The self component of a filter iterator is a filter component
+        filter: FILTER.then(|| NonNull::new(component.cast()).unwrap()),
+    }
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn iterator_initialize<I: ComponentMessageIterator, const FILTER: bool>(
+    self_iterator: *mut bt_self_message_iterator,
+    _configuration: *mut bt_self_message_iterator_configuration,
+    port: *mut bt_self_component_port_output,
+) -> bt_message_iterator_class_initialize_method_status
+where
+    I::Component: 'static,
+{
+    let component =
+        component_data::<I::Component>(bt_self_message_iterator_borrow_component(self_iterator));
+    let mut iterator = self_message_iterator::<FILTER>(self_iterator);
+    let port = SelfComponentPortOutput(NonNull::new(port).unwrap(), PhantomData);
+
+    let result = catch_panic(|| I::initialize(component, &mut iterator, port)).map(|data| {
+        bt_self_message_iterator_set_data(self_iterator, Box::into_raw(Box::new(data)).cast());
+    });
+    MethodStatus::from_result(result, "Failed to initialize message iterator").into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn iterator_next<I: ComponentMessageIterator, const FILTER: bool>(
+    self_iterator: *mut bt_self_message_iterator,
+    messages: bt_message_array_const,
+    capacity: u64,
+    count: *mut u64,
+) -> bt_message_iterator_class_next_method_status {
+    let data = &mut *bt_self_message_iterator_get_data(self_iterator).cast::<I>();
+    let mut iterator = self_message_iterator::<FILTER>(self_iterator);
+    let mut output = MessageOutput {
+        messages: std::slice::from_raw_parts_mut(messages, capacity.try_into().unwrap()),
+        len: 0,
+    };
+
+    let result = catch_panic(|| data.next(&mut iterator, &mut output));
+    match result {
+        Err(BtError::End | BtError::Again(_)) | Ok(()) if !output.is_empty() => {
+            // The messages must be returned, the end is reported by the next call
+            *count = output.len as u64;
+            MethodStatus::Ok
+        }
+        Ok(()) => MethodStatus::Again,
+        result => {
+            // Messages are not returned on failure, release them
+            output.clear();
+            MethodStatus::from_result(result, "Message iterator failed")
+        }
+    }
+    .into()
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn iterator_finalize<I: ComponentMessageIterator>(
+    self_iterator: *mut bt_self_message_iterator,
+) {
+    let data = bt_self_message_iterator_get_data(self_iterator).cast::<I>();
+    if !data.is_null() {
+        drop(Box::from_raw(data));
+    }
+}
+
+/// Raw types used by the plugin export macros.
+#[cfg(feature = "plugin")]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::raw_bindings::{
+        bt_component_class_initialize_method_status, bt_component_class_sink_consume_method_status,
+        bt_component_class_sink_graph_is_configured_method_status, bt_message_array_const,
+        bt_message_iterator_class_initialize_method_status,
+        bt_message_iterator_class_next_method_status, bt_self_component_filter,
+        bt_self_component_filter_configuration, bt_self_component_port_output,
+        bt_self_component_sink, bt_self_component_sink_configuration, bt_self_component_source,
+        bt_self_component_source_configuration, bt_self_message_iterator,
+        bt_self_message_iterator_configuration, bt_value,
+    };
+}
+
+/// Export the functions by which babeltrace2 finds the descriptors of a plugin.
+///
+/// Must be invoked once in a plugin shared object built by Rust, whose C descriptor file
+/// includes the header `bt2-sys/plugin.h` and invokes `BT_PLUGIN_MODULE()`.
+/// The header renames the functions defined by `BT_PLUGIN_MODULE()`, because shared objects
+/// built by Rust export only the symbols defined in Rust.
+#[cfg(feature = "plugin")]
+#[macro_export]
+macro_rules! export_plugin_module {
+    () => {
+        $crate::__export_plugin_section_functions!(
+            begin_section_plugin_descriptors,
+            end_section_plugin_descriptors,
+            begin_section_plugin_descriptor_attributes,
+            end_section_plugin_descriptor_attributes,
+            begin_section_component_class_descriptors,
+            end_section_component_class_descriptors,
+            begin_section_component_class_descriptor_attributes,
+            end_section_component_class_descriptor_attributes
+        );
+    };
+}
+
+#[cfg(feature = "plugin")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_plugin_section_functions {
+    ($($name:ident),+) => {
+        const _: () = {
+            $(
+                #[unsafe(export_name = concat!("__bt_get_", stringify!($name)))]
+                unsafe extern "C" fn $name() -> *const ::std::ffi::c_void {
+                    unsafe extern "C" {
+                        #[link_name = concat!("bt2_sys_get_", stringify!($name))]
+                        fn renamed() -> *const ::std::ffi::c_void;
+                    }
+
+                    renamed()
+                }
+            )+
+        };
+    };
+}
+
+/// Export the methods of the source component `$component` as C functions named
+/// `<$prefix>_initialize`, `<$prefix>_finalize`, `<$prefix>_iterator_initialize`,
+/// `<$prefix>_iterator_next` and `<$prefix>_iterator_finalize`.
+///
+/// The functions are registered in the plugin descriptor by the `BT2_SYS_SOURCE_COMPONENT_CLASS`
+/// macro of the C header `bt2-sys/plugin.h`. Its directory is available to build scripts of
+/// dependent crates in the `DEP_BABELTRACE2_INCLUDE` environment variable.
+#[cfg(feature = "plugin")]
+#[macro_export]
+macro_rules! export_source_component_class {
+    ($prefix:literal, $component:ty) => {
+        const _: () = {
+            use $crate::graph::user_component::__private::*;
+            use $crate::graph::user_component::*;
+
+            #[unsafe(export_name = concat!($prefix, "_initialize"))]
+            unsafe extern "C" fn initialize(
+                self_component: *mut bt_self_component_source,
+                configuration: *mut bt_self_component_source_configuration,
+                params: *const bt_value,
+                data: *mut ::std::ffi::c_void,
+            ) -> bt_component_class_initialize_method_status {
+                source_initialize::<$component>(self_component, configuration, params, data)
+            }
+
+            #[unsafe(export_name = concat!($prefix, "_finalize"))]
+            unsafe extern "C" fn finalize(self_component: *mut bt_self_component_source) {
+                source_finalize::<$component>(self_component);
+            }
+
+            $crate::__export_message_iterator_class!(
+                $prefix,
+                <$component as SourceComponent>::Iterator,
+                false
+            );
+        };
+    };
+}
+
+/// Export the methods of the filter component `$component` as C functions.
+///
+/// Like [`export_source_component_class`], the functions are registered by the
+/// `BT2_SYS_FILTER_COMPONENT_CLASS` macro of the C header `bt2-sys/plugin.h`.
+///
+/// ```c
+/// #include <babeltrace2/plugin/plugin-dev.h>
+/// #include <bt2-sys/plugin.h>
+///
+/// BT_PLUGIN_MODULE();
+/// BT_PLUGIN(ros);
+/// BT2_SYS_FILTER_COMPONENT_CLASS(node, ros_node);
+/// ```
+///
+/// The plugin must also invoke [`export_plugin_module`](crate::export_plugin_module).
+/// See `bt2-sys/examples/plugin` for a complete plugin.
+#[cfg(feature = "plugin")]
+#[macro_export]
+macro_rules! export_filter_component_class {
+    ($prefix:literal, $component:ty) => {
+        const _: () = {
+            use $crate::graph::user_component::__private::*;
+            use $crate::graph::user_component::*;
+
+            #[unsafe(export_name = concat!($prefix, "_initialize"))]
+            unsafe extern "C" fn initialize(
+                self_component: *mut bt_self_component_filter,
+                configuration: *mut bt_self_component_filter_configuration,
+                params: *const bt_value,
+                data: *mut ::std::ffi::c_void,
+            ) -> bt_component_class_initialize_method_status {
+                filter_initialize::<$component>(self_component, configuration, params, data)
+            }
+
+            #[unsafe(export_name = concat!($prefix, "_finalize"))]
+            unsafe extern "C" fn finalize(self_component: *mut bt_self_component_filter) {
+                filter_finalize::<$component>(self_component);
+            }
+
+            $crate::__export_message_iterator_class!(
+                $prefix,
+                <$component as FilterComponent>::Iterator,
+                true
+            );
+        };
+    };
+}
+
+/// Export the methods of the sink component `$component` as C functions named
+/// `<$prefix>_initialize`, `<$prefix>_finalize`, `<$prefix>_graph_is_configured`
+/// and `<$prefix>_consume`.
+///
+/// The functions are registered by the `BT2_SYS_SINK_COMPONENT_CLASS` macro
+/// of the C header `bt2-sys/plugin.h`.
+#[cfg(feature = "plugin")]
+#[macro_export]
+macro_rules! export_sink_component_class {
+    ($prefix:literal, $component:ty) => {
+        const _: () = {
+            use $crate::graph::user_component::__private::*;
+            use $crate::graph::user_component::*;
+
+            #[unsafe(export_name = concat!($prefix, "_initialize"))]
+            unsafe extern "C" fn initialize(
+                self_component: *mut bt_self_component_sink,
+                configuration: *mut bt_self_component_sink_configuration,
+                params: *const bt_value,
+                data: *mut ::std::ffi::c_void,
+            ) -> bt_component_class_initialize_method_status {
+                sink_initialize::<$component>(self_component, configuration, params, data)
+            }
+
+            #[unsafe(export_name = concat!($prefix, "_finalize"))]
+            unsafe extern "C" fn finalize(self_component: *mut bt_self_component_sink) {
+                sink_finalize::<$component>(self_component);
+            }
+
+            #[unsafe(export_name = concat!($prefix, "_graph_is_configured"))]
+            unsafe extern "C" fn graph_is_configured(
+                self_component: *mut bt_self_component_sink,
+            ) -> bt_component_class_sink_graph_is_configured_method_status {
+                sink_graph_is_configured::<$component>(self_component)
+            }
+
+            #[unsafe(export_name = concat!($prefix, "_consume"))]
+            unsafe extern "C" fn consume(
+                self_component: *mut bt_self_component_sink,
+            ) -> bt_component_class_sink_consume_method_status {
+                sink_consume::<$component>(self_component)
+            }
+        };
+    };
+}
+
+#[cfg(feature = "plugin")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_message_iterator_class {
+    ($prefix:literal, $iterator:ty, $filter:literal) => {
+        #[unsafe(export_name = concat!($prefix, "_iterator_initialize"))]
+        unsafe extern "C" fn iterator_initialize_(
+            self_iterator: *mut bt_self_message_iterator,
+            configuration: *mut bt_self_message_iterator_configuration,
+            port: *mut bt_self_component_port_output,
+        ) -> bt_message_iterator_class_initialize_method_status {
+            iterator_initialize::<$iterator, $filter>(self_iterator, configuration, port)
+        }
+
+        #[unsafe(export_name = concat!($prefix, "_iterator_next"))]
+        unsafe extern "C" fn iterator_next_(
+            self_iterator: *mut bt_self_message_iterator,
+            messages: bt_message_array_const,
+            capacity: u64,
+            count: *mut u64,
+        ) -> bt_message_iterator_class_next_method_status {
+            iterator_next::<$iterator, $filter>(self_iterator, messages, capacity, count)
+        }
+
+        #[unsafe(export_name = concat!($prefix, "_iterator_finalize"))]
+        unsafe extern "C" fn iterator_finalize_(self_iterator: *mut bt_self_message_iterator) {
+            iterator_finalize::<$iterator>(self_iterator);
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::field::{BtFieldClass, BtFieldClassStructure};
+    use crate::graph::BtGraph;
+    use crate::logging::LogLevel;
+    use crate::message::BtMessageType;
+    use crate::raw_bindings::bt_graph_run_status;
+    use crate::trace::BtTrace;
+
+    const TICK_COUNT: u64 = 4;
+
+    /// Source of a stream with one packet of `tick` events.
+    struct TickSource;
+
+    struct TickIterator {
+        stream: BtStream,
+        packet: BtPacket,
+        event_class: BtEventClass,
+        /// Index of the next message.
+        next: u64,
+        // The trace is kept alive by the stream, it is owned here to test the drop order
+        _trace: BtTrace,
+    }
+
+    impl SourceComponent for TickSource {
+        type Iterator = TickIterator;
+
+        fn initialize(
+            component: &mut SelfComponentSource,
+            _params: BtValueConst,
+        ) -> BtResult<Self> {
+            component.add_output_port(c"out")?;
+            Ok(Self)
+        }
+    }
+
+    impl ComponentMessageIterator for TickIterator {
+        type Component = TickSource;
+
+        fn initialize(
+            _component: &TickSource,
+            iterator: &mut SelfMessageIterator,
+            _port: SelfComponentPortOutput,
+        ) -> BtResult<Self> {
+            let mut trace_class = iterator.create_trace_class()?;
+            let mut clock_class = iterator.create_clock_class()?;
+            clock_class.set_name(c"monotonic")?;
+            clock_class.set_frequency(1_000);
+
+            let mut stream_class = trace_class.create_stream_class()?;
+            stream_class.set_default_clock_class(&clock_class);
+            stream_class.set_supports_packets(true, true, true);
+
+            let mut payload_class = BtFieldClassStructure::new(&mut trace_class)?;
+            payload_class.append_member(
+                c"count",
+                &BtFieldClass::new_unsigned_integer(&mut trace_class)?,
+            )?;
+            payload_class.append_member(c"parity", &BtFieldClass::new_string(&mut trace_class)?)?;
+            let mut event_class = stream_class.create_event_class()?;
+            event_class.set_name(c"tick")?;
+            event_class.set_payload_field_class(&payload_class)?;
+
+            let mut trace = trace_class.create_trace()?;
+            trace.set_name(c"ticks")?;
+            let stream = stream_class.create_stream(&mut trace)?;
+            let packet = stream.create_packet()?;
+
+            Ok(Self {
+                stream,
+                packet,
+                event_class,
+                next: 0,
+                _trace: trace,
+            })
+        }
+
+        fn next(
+            &mut self,
+            iterator: &mut SelfMessageIterator,
+            output: &mut MessageOutput,
+        ) -> BtResult<()> {
+            while !output.is_full() {
+                let message = match self.next {
+                    0 => iterator.create_stream_beginning_message(&self.stream.as_const())?,
+                    1 => iterator.create_packet_beginning_message(&self.packet, Some(0))?,
+                    n if n < 2 + TICK_COUNT => {
+                        let count = n - 2;
+                        let mut message = iterator.create_event_message_in_packet(
+                            &self.event_class,
+                            &self.packet,
+                            Some(count * 10),
+                        )?;
+                        let mut payload = message.get_payload_mut().unwrap();
+                        payload
+                            .get_field_by_name_cstr(c"count")
+                            .unwrap()
+                            .set_uint(count);
+                        payload
+                            .get_field_by_name_cstr(c"parity")
+                            .unwrap()
+                            .set_string(if count % 2 == 0 { c"even" } else { c"odd" })?;
+                        message.into()
+                    }
+                    n if n == 2 + TICK_COUNT => {
+                        iterator.create_packet_end_message(&self.packet, Some(TICK_COUNT * 10))?
+                    }
+                    n if n == 3 + TICK_COUNT => {
+                        iterator.create_stream_end_message(&self.stream.as_const())?
+                    }
+                    _ => return Err(BtError::End),
+                };
+                self.next += 1;
+                if output.push(message).is_err() {
+                    unreachable!("The output is not full");
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn tick_count(message: &BtMessageConst) -> Option<u64> {
+        if message.get_type() != BtMessageType::Event {
+            return None;
+        }
+
+        let message = message.clone().into_event_msg();
+        let payload = message.get_event().get_payload()?.into_struct();
+        u64::try_from(payload.get_field_by_name("count")?).ok()
+    }
+
+    /// Filter dropping the `tick` events with an odd count.
+    struct EvenFilter;
+
+    struct EvenIterator {
+        upstream: UpstreamMessageIterator,
+        pending: VecDeque<BtMessageConst>,
+    }
+
+    impl FilterComponent for EvenFilter {
+        type Iterator = EvenIterator;
+
+        fn initialize(
+            component: &mut SelfComponentFilter,
+            _params: BtValueConst,
+        ) -> BtResult<Self> {
+            component.add_input_port(c"in")?;
+            component.add_output_port(c"out")?;
+            Ok(Self)
+        }
+    }
+
+    impl ComponentMessageIterator for EvenIterator {
+        type Component = EvenFilter;
+
+        fn initialize(
+            _component: &EvenFilter,
+            iterator: &mut SelfMessageIterator,
+            _port: SelfComponentPortOutput,
+        ) -> BtResult<Self> {
+            Ok(Self {
+                upstream: iterator.create_message_iterator(iterator.input_port(0))?,
+                pending: VecDeque::new(),
+            })
+        }
+
+        fn next(
+            &mut self,
+            _iterator: &mut SelfMessageIterator,
+            output: &mut MessageOutput,
+        ) -> BtResult<()> {
+            if self.pending.is_empty() {
+                self.upstream.next_into(&mut self.pending)?;
+            }
+
+            while !output.is_full() {
+                let Some(message) = self.pending.pop_front() else {
+                    break;
+                };
+                if tick_count(&message).is_some_and(|count| count % 2 == 1) {
+                    continue;
+                }
+                if output.push(message).is_err() {
+                    unreachable!("The output is not full");
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    thread_local! {
+        static RECEIVED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Sink describing the received messages in `RECEIVED`.
+    struct RecordingSink {
+        upstream: Option<UpstreamMessageIterator>,
+    }
+
+    impl SinkComponent for RecordingSink {
+        fn initialize(component: &mut SelfComponentSink, _params: BtValueConst) -> BtResult<Self> {
+            component.add_input_port(c"in")?;
+            Ok(Self { upstream: None })
+        }
+
+        fn graph_is_configured(&mut self, component: &mut SelfComponentSink) -> BtResult<()> {
+            self.upstream = Some(component.create_message_iterator(component.input_port(0))?);
+            Ok(())
+        }
+
+        fn consume(&mut self, _component: &mut SelfComponentSink) -> BtResult<()> {
+            let mut messages = Vec::new();
+            self.upstream.as_mut().unwrap().next_into(&mut messages)?;
+            RECEIVED.with_borrow_mut(|received| {
+                received.extend(messages.into_iter().map(|message| describe(&message)));
+            });
+            Ok(())
+        }
+    }
+
+    fn describe(message: &BtMessageConst) -> String {
+        let Some(count) = tick_count(message) else {
+            return format!("{:?}", message.get_type());
+        };
+
+        let message = message.clone().into_event_msg();
+        let event = message.get_event();
+        let parity = event
+            .get_payload()
+            .unwrap()
+            .into_struct()
+            .get_field_by_name("parity")
+            .unwrap()
+            .into_string();
+        format!(
+            "{} {count} {} @{}",
+            event.get_class().get_name().unwrap(),
+            parity.get_value(),
+            message.get_default_clock_snapshot().get_value()
+        )
+    }
+
+    /// Run the tick source, optionally through the even filter, and return the received messages.
+    fn run_graph(with_filter: bool) -> Vec<String> {
+        let source_class = BtComponentClassSource::new::<TickSource>(c"ticks").unwrap();
+        let filter_class = BtComponentClassFilter::new::<EvenFilter>(c"even").unwrap();
+        let sink_class = BtComponentClassSink::new::<RecordingSink>(c"recording").unwrap();
+
+        let mut builder = BtGraph::builder().unwrap();
+        unsafe {
+            let source = builder
+                .add_source_component_unchecked(
+                    source_class.as_const(),
+                    c"source",
+                    None,
+                    LogLevel::Warning,
+                )
+                .unwrap();
+            let sink = builder
+                .add_sink_component_unchecked(
+                    sink_class.as_const(),
+                    c"sink",
+                    None,
+                    LogLevel::Warning,
+                )
+                .unwrap();
+            if with_filter {
+                let filter = builder
+                    .add_filter_component_unchecked(
+                        filter_class.as_const(),
+                        c"filter",
+                        None,
+                        LogLevel::Warning,
+                    )
+                    .unwrap();
+                builder
+                    .connect_ports_unchecked(source.get_output_port(0), filter.get_input_port(0))
+                    .unwrap();
+                builder
+                    .connect_ports_unchecked(filter.get_output_port(0), sink.get_input_port(0))
+                    .unwrap();
+            } else {
+                builder
+                    .connect_ports_unchecked(source.get_output_port(0), sink.get_input_port(0))
+                    .unwrap();
+            }
+        }
+
+        let mut graph = builder.build();
+        let status = unsafe { graph.run() };
+        assert_eq!(status, bt_graph_run_status::BT_GRAPH_RUN_STATUS_OK);
+
+        RECEIVED.take()
+    }
+
+    #[test]
+    fn test_source_component_creates_messages() {
+        assert_eq!(
+            run_graph(false),
+            [
+                "StreamBeginning",
+                "PacketBeginning",
+                "tick 0 even @0",
+                "tick 1 odd @10",
+                "tick 2 even @20",
+                "tick 3 odd @30",
+                "PacketEnd",
+                "StreamEnd",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_component_forwards_messages() {
+        assert_eq!(
+            run_graph(true),
+            [
+                "StreamBeginning",
+                "PacketBeginning",
+                "tick 0 even @0",
+                "tick 2 even @20",
+                "PacketEnd",
+                "StreamEnd",
+            ]
+        );
+    }
+}
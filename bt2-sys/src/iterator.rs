@@ -1,13 +1,18 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ffi::{c_void, CStr, CString};
 use std::ptr::NonNull;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use thiserror::Error;
 
-use crate::error::{BtError, BtResult, IntoResult, OutOfMemory};
+use crate::error::{BtError, BtErrorCause, BtResult, IntoResult, OutOfMemory};
+use crate::graph::component::{BtComponentFilterConst, BtComponentSourceConst};
 use crate::graph::plugin::{BtPlugin, BtPluginLoadError};
 use crate::graph::simple_sink::SimpleSink;
+use crate::graph::tee::Tee;
+use crate::graph::user_component::BtComponentClassFilter;
 use crate::graph::{AddComponentError, BtGraph, BtGraphBuilder, ConnectPortsError};
 use crate::logging::LogLevel;
 use crate::message::{BtMessageArrayConst, BtMessageConst};
@@ -24,8 +29,230 @@ pub(crate) enum MessageIteratorState {
     Ended,
 }
 
+/// How long to wait before retrying when a live source has no new messages.
+const LIVE_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Set by the SIGINT handler installed by [`stop_on_interrupt`].
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+    // A second SIGINT terminates the process immediately
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
+
+/// End all message iterators when the process receives SIGINT.
+///
+/// The iterators end as if the trace ended, so the messages received so far can still be
+/// processed. This is the only way to stop reading a live session, as the `lttng-live`
+/// source waits for new sessions when the current one is destroyed.
+pub fn stop_on_interrupt() {
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+/// Source of the trace messages.
+#[derive(Debug, Clone, Copy)]
+pub enum TraceSource<'a> {
+    /// CTF traces stored in directories, read by the `src.ctf.fs` component.
+    Files(&'a [&'a CStr]),
+
+    /// Running LTTng session served by `lttng-relayd`, read by the `src.ctf.lttng-live` component.
+    ///
+    /// The URL has the form `net://<relay host>/host/<traced host>/<session name>`.
+    Live(&'a CStr),
+}
+
+impl TraceSource<'_> {
+    const fn retry_delay(&self) -> Option<Duration> {
+        match self {
+            Self::Files(_) => None,
+            Self::Live(_) => Some(LIVE_RETRY_DELAY),
+        }
+    }
+}
+
+/// Component of a babeltrace2 plugin added to the trace processing graph.
+pub struct PluginComponent {
+    plugin: CString,
+    class: CString,
+    params: Option<BtValueMap>,
+}
+
+impl PluginComponent {
+    /// Component of the class `class` from the plugin `plugin` initialized with `params`.
+    #[must_use]
+    pub fn new(plugin: &CStr, class: &CStr, params: Option<BtValueMap>) -> Self {
+        Self {
+            plugin: plugin.to_owned(),
+            class: class.to_owned(),
+            params,
+        }
+    }
+
+    /// `utils.trimmer` filter discarding the messages outside of the time range.
+    ///
+    /// The bounds are in nanoseconds since the clock origin, i.e., the UNIX epoch for LTTng traces.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn trimmer(begin_ns: Option<i64>, end_ns: Option<i64>) -> Result<Self, OutOfMemory> {
+        /// Format the time as `[-]SEC.NANO` accepted by the trimmer.
+        fn format_time(ns: i64) -> String {
+            let sign = if ns < 0 { "-" } else { "" };
+            let abs = ns.unsigned_abs();
+            format!("{sign}{}.{:09}", abs / 1_000_000_000, abs % 1_000_000_000)
+        }
+
+        let mut params = BtValueMap::new()?;
+        if let Some(begin) = begin_ns {
+            params.insert_string("begin", &format_time(begin))?;
+        }
+        if let Some(end) = end_ns {
+            params.insert_string("end", &format_time(end))?;
+        }
+        Ok(Self::new(c"utils", c"trimmer", Some(params)))
+    }
+
+    /// `lttng-utils.debug-info` filter adding the source locations of instruction pointers
+    /// to the events.
+    ///
+    /// The debug information is looked up in `debug_info_dir` in addition to the default
+    /// directories. The paths of binaries are prefixed by `target_prefix` if the trace was
+    /// recorded on another system.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn debug_info(
+        debug_info_dir: Option<&CStr>,
+        target_prefix: Option<&CStr>,
+    ) -> Result<Self, OutOfMemory> {
+        let mut params = BtValueMap::new()?;
+        if let Some(dir) = debug_info_dir {
+            params
+                .insert_with_cstr_key(c"debug-info-dir", &BtValueString::new_cstr(dir)?.into())?;
+        }
+        if let Some(prefix) = target_prefix {
+            params
+                .insert_with_cstr_key(c"target-prefix", &BtValueString::new_cstr(prefix)?.into())?;
+        }
+        Ok(Self::new(c"lttng-utils", c"debug-info", Some(params)))
+    }
+
+    /// `sink.ctf.fs` sink writing the messages as CTF traces to the directory `path`.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn ctf_fs_sink(path: &CStr) -> Result<Self, OutOfMemory> {
+        let mut params = BtValueMap::new()?;
+        params.insert_with_cstr_key(c"path", &BtValueString::new_cstr(path)?.into())?;
+        Ok(Self::new(c"ctf", c"fs", Some(params)))
+    }
+
+    /// Create a copy of the component with a deep copy of its parameters.
+    pub fn try_clone(&self) -> Result<Self, OutOfMemory> {
+        Ok(Self {
+            plugin: self.plugin.clone(),
+            class: self.class.clone(),
+            params: self
+                .params
+                .as_ref()
+                .map(BtValueMap::try_clone)
+                .transpose()?,
+        })
+    }
+
+    fn class_not_found(&self) -> IteratorConstructionError {
+        IteratorConstructionError::ComponentClassNotFound {
+            plugin: self.plugin.to_string_lossy().into_owned(),
+            class: self.class.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// Configuration of the trace processing graph.
+///
+/// The messages of all sources are merged by `utils.muxer` and passed through the filters
+/// in the order they were added. The iterator consumes the messages of the last filter.
+/// Each of the sinks receives a copy of these messages.
 #[derive(Default)]
-struct BatchMessageIteratorInner(RefCell<Option<BtMessageArrayConst>>);
+pub struct GraphConfig {
+    filters: Vec<PluginComponent>,
+    sinks: Vec<PluginComponent>,
+}
+
+impl GraphConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a filter to the chain after the muxer.
+    #[must_use]
+    pub fn filter(mut self, filter: PluginComponent) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Add a sink receiving a copy of the filtered messages.
+    #[must_use]
+    pub fn sink(mut self, sink: PluginComponent) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Create a copy of the configuration, e.g., to construct another graph with it.
+    pub fn try_clone(&self) -> Result<Self, OutOfMemory> {
+        Ok(Self {
+            filters: self
+                .filters
+                .iter()
+                .map(PluginComponent::try_clone)
+                .collect::<Result<_, _>>()?,
+            sinks: self
+                .sinks
+                .iter()
+                .map(PluginComponent::try_clone)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether the graph contains only the sources and the muxer.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty() && self.sinks.is_empty()
+    }
+}
+
+/// Position to which [`MessageIterator::seek()`] moves the iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekTarget {
+    /// First message of the traces.
+    Beginning,
+    /// First message at or after the time in nanoseconds from the clock origin.
+    NsFromOrigin(i64),
+}
+
+#[derive(Default)]
+struct BatchMessageIteratorInner {
+    batch: RefCell<Option<BtMessageArrayConst>>,
+
+    /// Seek requested by the iterator, done before the next batch is taken from upstream.
+    ///
+    /// The upstream iterator is only available in the consume function of the sink.
+    seek: Cell<Option<SeekTarget>>,
+
+    /// Delay before retrying when the upstream iterator has no messages available yet.
+    ///
+    /// Without the delay, the live source would be polled in a busy loop.
+    retry_delay: Option<Duration>,
+}
 
 pub(crate) struct BatchMessageIterator {
     graph: BtGraph,
@@ -52,7 +279,7 @@ impl BatchMessageIterator {
 
         let mut iterator: BtMessageIterator = iterator.into();
 
-        let Ok(mut internal) = this.0.try_borrow_mut() else {
+        let Ok(mut internal) = this.batch.try_borrow_mut() else {
             throw!("Failed to borrow internal state");
             return STATUS_ERROR;
         };
@@ -61,7 +288,22 @@ impl BatchMessageIterator {
             return STATUS_ERROR;
         }
 
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            return STATUS_END;
+        }
+
+        if let Some(target) = this.seek.take() {
+            if let Err(e) = Self::seek(&mut iterator, target, this.retry_delay) {
+                rethrow!(e, "Failed to seek");
+                return STATUS_ERROR;
+            }
+        }
+
         loop {
+            if INTERRUPTED.load(Ordering::Relaxed) {
+                return STATUS_END;
+            }
+
             let result = iterator.next();
             match result {
                 Ok(messages) => {
@@ -70,6 +312,9 @@ impl BatchMessageIterator {
                     return STATUS_OK;
                 }
                 Err(BtError::Again(_)) => {
+                    if let Some(delay) = this.retry_delay {
+                        std::thread::sleep(delay);
+                    }
                     continue;
                 }
                 Err(BtError::End) => {
@@ -83,6 +328,46 @@ impl BatchMessageIterator {
         }
     }
 
+    fn seek(
+        iterator: &mut BtMessageIterator,
+        target: SeekTarget,
+        retry_delay: Option<Duration>,
+    ) -> BtResult<()> {
+        loop {
+            let result = match target {
+                SeekTarget::Beginning => iterator.can_seek_beginning().and_then(|can_seek| {
+                    if can_seek {
+                        iterator.seek_beginning()
+                    } else {
+                        Err(BtError::new(
+                            "The upstream iterator cannot seek its beginning",
+                        ))
+                    }
+                }),
+                SeekTarget::NsFromOrigin(ns) => {
+                    iterator.can_seek_ns_from_origin(ns).and_then(|can_seek| {
+                        if can_seek {
+                            iterator.seek_ns_from_origin(ns)
+                        } else {
+                            Err(BtError::new(&format!(
+                                "The upstream iterator cannot seek to {ns} ns from origin"
+                            )))
+                        }
+                    })
+                }
+            };
+
+            match result {
+                Err(BtError::Again(_)) => {
+                    if let Some(delay) = retry_delay {
+                        std::thread::sleep(delay);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
     unsafe extern "C" fn finalize(user_data: *mut c_void) {
         if user_data.is_null() {
             eprintln!("User data is null in finalize function");
@@ -104,6 +389,9 @@ pub enum IteratorConstructionError {
     #[error("Failed to load component: {0}")]
     ComponentLoadError(&'static str),
 
+    #[error("Component class `{plugin}.{class}` not found")]
+    ComponentClassNotFound { plugin: String, class: String },
+
     #[error("Failed to add component to graph: {0}")]
     AddComponentError(#[from] AddComponentError),
 
@@ -114,9 +402,33 @@ pub enum IteratorConstructionError {
     Error(BtError),
 }
 
+impl IteratorConstructionError {
+    /// Get the causes of the babeltrace2 error, from the root cause to the most recent one.
+    ///
+    /// Empty if the construction did not fail because of a babeltrace2 error.
+    #[must_use]
+    pub fn causes(&self) -> Vec<BtErrorCause> {
+        match self {
+            Self::PluginError(BtPluginLoadError::Other(error))
+            | Self::AddComponentError(AddComponentError::Error(error))
+            | Self::ConnectPortsError(ConnectPortsError::Error(error)) => error.causes(),
+            Self::Error(error) => error.causes(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl BatchMessageIterator {
-    pub fn new(trace_paths: &[&CStr], log_level: LogLevel) -> Self {
-        let shared = Rc::new(BatchMessageIteratorInner::default());
+    pub fn try_new(
+        source: TraceSource,
+        config: GraphConfig,
+        log_level: LogLevel,
+    ) -> Result<Self, IteratorConstructionError> {
+        let shared = Rc::new(BatchMessageIteratorInner {
+            batch: RefCell::default(),
+            seek: Cell::default(),
+            retry_delay: source.retry_delay(),
+        });
         let shared_ptr = Rc::into_raw(shared.clone());
         let sink = SimpleSink {
             initialize_fn: None,
@@ -125,57 +437,163 @@ impl BatchMessageIterator {
             user_data: shared_ptr as *mut c_void,
         };
 
-        let graph_result = Self::construct_graph(trace_paths, sink, log_level);
+        let graph_result = Self::construct_graph(source, config, sink, log_level);
 
         match graph_result {
-            Ok(graph) => Self {
+            Ok(graph) => Ok(Self {
                 graph,
                 internal: shared,
-            },
+            }),
             Err(e) => {
                 let _ = unsafe { Rc::from_raw(shared_ptr) };
-                panic!("Failed to construct trace processing graph: {e}");
+                Err(e)
+            }
+        }
+    }
+
+    fn add_source_components<'a>(
+        graph: &mut BtGraphBuilder,
+        source: TraceSource,
+        log_level: LogLevel,
+    ) -> Result<Vec<BtComponentSourceConst<'a>>, IteratorConstructionError> {
+        let ctf_plugin = BtPlugin::find_anywhere(c"ctf")?;
+
+        match source {
+            TraceSource::Files(trace_paths) => {
+                let source_class = ctf_plugin
+                    .borrow_source_component_class_by_name(c"fs")
+                    .ok_or(IteratorConstructionError::ComponentLoadError("fs"))?;
+
+                let mut source_components = Vec::with_capacity(trace_paths.len());
+                for (i, trace_path) in trace_paths.iter().enumerate() {
+                    let mut path = BtValueArray::new()?;
+                    path.push(&BtValueString::new_cstr(trace_path)?.into())?;
+                    let mut params = BtValueMap::new()?;
+                    params.insert_with_cstr_key(c"inputs", &path.into())?;
+
+                    let source_comp_name = format!("source_{i}");
+                    let source_comp_name = CString::new(source_comp_name).unwrap();
+                    let source = unsafe {
+                        graph.add_source_component_unchecked(
+                            source_class,
+                            &source_comp_name,
+                            Some(params),
+                            log_level,
+                        )
+                    }?;
+
+                    source_components.push(source);
+                }
+
+                Ok(source_components)
+            }
+            TraceSource::Live(url) => {
+                let source_class = ctf_plugin
+                    .borrow_source_component_class_by_name(c"lttng-live")
+                    .ok_or(IteratorConstructionError::ComponentLoadError("lttng-live"))?;
+
+                let mut inputs = BtValueArray::new()?;
+                inputs.push(&BtValueString::new_cstr(url)?.into())?;
+                let mut params = BtValueMap::new()?;
+                params.insert_with_cstr_key(c"inputs", &inputs.into())?;
+                // Wait for the session to be created instead of failing
+                params.insert_with_cstr_key(
+                    c"session-not-found-action",
+                    &BtValueString::new_cstr(c"continue")?.into(),
+                )?;
+
+                let source = unsafe {
+                    graph.add_source_component_unchecked(
+                        source_class,
+                        c"source_live",
+                        Some(params),
+                        log_level,
+                    )
+                }?;
+
+                Ok(vec![source])
             }
         }
     }
 
+    fn add_filter_component<'a>(
+        graph: &mut BtGraphBuilder,
+        component: PluginComponent,
+        name: &CStr,
+        log_level: LogLevel,
+    ) -> Result<BtComponentFilterConst<'a>, IteratorConstructionError> {
+        let plugin = BtPlugin::find_anywhere(&component.plugin)?;
+        let class = plugin
+            .borrow_filter_component_class_by_name(&component.class)
+            .ok_or_else(|| component.class_not_found())?;
+        let filter = unsafe {
+            graph.add_filter_component_unchecked(class, name, component.params, log_level)
+        }?;
+        Ok(filter)
+    }
+
+    /// Add the `sinks` receiving a copy of the messages of `upstream` and return the filter
+    /// providing the messages for the iterator.
+    fn add_tee<'a>(
+        graph: &mut BtGraphBuilder,
+        upstream: BtComponentFilterConst<'a>,
+        sinks: Vec<PluginComponent>,
+        log_level: LogLevel,
+    ) -> Result<BtComponentFilterConst<'a>, IteratorConstructionError> {
+        if sinks.is_empty() {
+            return Ok(upstream);
+        }
+
+        let tee_class = BtComponentClassFilter::new::<Tee>(Tee::CLASS_NAME)?;
+        let mut params = BtValueMap::new()?;
+        params.insert_unsigned_int("output-count", sinks.len() as u64 + 1)?;
+        // The graph keeps its own reference to the class
+        let tee = unsafe {
+            graph.add_filter_component_unchecked(
+                tee_class.as_const(),
+                c"tee",
+                Some(params),
+                log_level,
+            )
+        }?;
+        unsafe {
+            graph.connect_ports_unchecked(upstream.get_output_port(0), tee.get_input_port(0))
+        }?;
+
+        for (i, component) in sinks.into_iter().enumerate() {
+            let plugin = BtPlugin::find_anywhere(&component.plugin)?;
+            let class = plugin
+                .borrow_sink_component_class_by_name(&component.class)
+                .ok_or_else(|| component.class_not_found())?;
+            let name = CString::new(format!("sink_{i}")).unwrap();
+            let sink = unsafe {
+                graph.add_sink_component_unchecked(class, &name, component.params, log_level)
+            }?;
+            unsafe {
+                graph.connect_ports_unchecked(
+                    tee.get_output_port(i as u64 + 1),
+                    sink.get_input_port(0),
+                )
+            }?;
+        }
+
+        Ok(tee)
+    }
+
     fn construct_graph(
-        trace_paths: &[&CStr],
+        source: TraceSource,
+        config: GraphConfig,
         sink: SimpleSink,
         log_level: LogLevel,
     ) -> Result<BtGraph, IteratorConstructionError> {
         let mut graph = BtGraphBuilder::new()?;
 
-        let ctf_plugin = BtPlugin::find_anywhere(c"ctf")?;
         let utils_plugin = BtPlugin::find_anywhere(c"utils")?;
-
-        let source_class = ctf_plugin
-            .borrow_source_component_class_by_name(c"fs")
-            .ok_or(IteratorConstructionError::ComponentLoadError("fs"))?;
         let filter_class = utils_plugin
             .borrow_filter_component_class_by_name(c"muxer")
             .ok_or(IteratorConstructionError::ComponentLoadError("muxer"))?;
 
-        let mut source_components = Vec::with_capacity(trace_paths.len());
-        for (i, trace_path) in trace_paths.iter().enumerate() {
-            let mut path = BtValueArray::new()?;
-            path.push(&BtValueString::new_cstr(trace_path)?.into())?;
-            let mut params = BtValueMap::new()?;
-            params.insert_with_cstr_key(c"inputs", &path.into())?;
-
-            let source_comp_name = format!("source_{i}");
-            let source_comp_name = CString::new(source_comp_name).unwrap();
-            let source = unsafe {
-                graph.add_source_component_unchecked(
-                    source_class,
-                    &source_comp_name,
-                    Some(params),
-                    log_level,
-                )
-            }?;
-
-            source_components.push(source);
-        }
+        let source_components = Self::add_source_components(&mut graph, source, log_level)?;
 
         let muxer = unsafe {
             graph.add_filter_component_unchecked(filter_class, c"muxer", None, log_level)
@@ -203,7 +621,21 @@ impl BatchMessageIterator {
             )
         }?;
 
-        let out_port = muxer.get_output_port(0);
+        let mut last_filter = muxer;
+        for (i, filter) in config.filters.into_iter().enumerate() {
+            let name = CString::new(format!("filter_{i}")).unwrap();
+            let filter = Self::add_filter_component(&mut graph, filter, &name, log_level)?;
+            unsafe {
+                graph.connect_ports_unchecked(
+                    last_filter.get_output_port(0),
+                    filter.get_input_port(0),
+                )
+            }?;
+            last_filter = filter;
+        }
+        let last_filter = Self::add_tee(&mut graph, last_filter, config.sinks, log_level)?;
+
+        let out_port = last_filter.get_output_port(0);
         let in_port = sink.get_input_port(0);
         unsafe { graph.connect_ports_unchecked(out_port, in_port) }?;
 
@@ -211,7 +643,7 @@ impl BatchMessageIterator {
     }
 
     fn next_batch(&mut self) -> BtResult<MessageIteratorState> {
-        let mut internal = self.internal.0.borrow_mut();
+        let mut internal = self.internal.batch.borrow_mut();
         let taken = internal.take();
         drop(taken);
         drop(internal);
@@ -230,63 +662,230 @@ impl BatchMessageIterator {
 
 impl Drop for BatchMessageIterator {
     fn drop(&mut self) {
-        let _ = self.internal.0.borrow_mut().take();
+        let _ = self.internal.batch.borrow_mut().take();
     }
 }
 
-impl Iterator for BatchMessageIterator {
-    type Item = BtMessageArrayConst;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.next_batch() {
-            Ok(MessageIteratorState::Running) => {
-                let mut internal = self.internal.0.borrow_mut();
+impl BatchMessageIterator {
+    /// Get the next batch of messages.
+    ///
+    /// Returns `Ok(None)` when the trace ended.
+    fn try_next(&mut self) -> BtResult<Option<BtMessageArrayConst>> {
+        match self.next_batch()? {
+            MessageIteratorState::Running => {
+                let mut internal = self.internal.batch.borrow_mut();
                 let messages = internal.take().unwrap();
 
-                Some(messages)
-            }
-            Ok(MessageIteratorState::Ended) => None,
-            Err(e) => {
-                panic!("Failed to get next batch of messages: {e}");
+                Ok(Some(messages))
             }
+            MessageIteratorState::Ended => Ok(None),
         }
     }
 }
 
+impl Iterator for BatchMessageIterator {
+    type Item = BtMessageArrayConst;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()
+            .unwrap_or_else(|e| panic!("Failed to get next batch of messages: {e}"))
+    }
+}
+
 pub struct MessageIterator {
     current_batch: Option<BtMessageArrayConst>,
     batch_iterator: BatchMessageIterator,
     current_index: usize,
+    /// Set when the graph failed, after which it must not run again.
+    failed: bool,
+    /// Set when the graph ended, after which it cannot seek anymore.
+    ended: bool,
 }
 
 impl MessageIterator {
+    /// # Panics
+    /// Panics if the trace processing graph cannot be constructed.
+    ///
+    /// # See also
+    /// - [`Self::try_new()`] to handle the construction errors.
     #[must_use]
     pub fn new(trace_paths: &[&CStr], log_level: LogLevel) -> Self {
-        Self {
-            batch_iterator: BatchMessageIterator::new(trace_paths, log_level),
+        Self::from_source(TraceSource::Files(trace_paths), log_level)
+    }
+
+    /// Create an iterator over messages of the traces in `trace_paths`.
+    ///
+    /// # Errors
+    /// If the trace processing graph cannot be constructed, e.g., if a trace is corrupted.
+    pub fn try_new(
+        trace_paths: &[&CStr],
+        log_level: LogLevel,
+    ) -> Result<Self, IteratorConstructionError> {
+        Self::try_with_graph_config(
+            TraceSource::Files(trace_paths),
+            GraphConfig::default(),
+            log_level,
+        )
+    }
+
+    /// Create an iterator over messages of a running LTTng live session.
+    ///
+    /// The iterator waits for the session to be created and does not end by itself.
+    /// Use [`stop_on_interrupt`] to end it.
+    #[must_use]
+    pub fn new_live(url: &CStr, log_level: LogLevel) -> Self {
+        Self::from_source(TraceSource::Live(url), log_level)
+    }
+
+    #[must_use]
+    pub fn from_source(source: TraceSource, log_level: LogLevel) -> Self {
+        Self::with_graph_config(source, GraphConfig::default(), log_level)
+    }
+
+    /// Create an iterator over messages of `source` passed through the graph configured
+    /// by `config`.
+    ///
+    /// # Panics
+    /// Panics if the trace processing graph cannot be constructed.
+    ///
+    /// # See also
+    /// - [`Self::try_with_graph_config()`] to handle the construction errors.
+    #[must_use]
+    pub fn with_graph_config(
+        source: TraceSource,
+        config: GraphConfig,
+        log_level: LogLevel,
+    ) -> Self {
+        Self::try_with_graph_config(source, config, log_level)
+            .unwrap_or_else(|e| panic!("Failed to construct trace processing graph: {e}"))
+    }
+
+    /// Create an iterator over messages of `source` passed through the graph configured
+    /// by `config`.
+    ///
+    /// # Errors
+    /// If the trace processing graph cannot be constructed.
+    pub fn try_with_graph_config(
+        source: TraceSource,
+        config: GraphConfig,
+        log_level: LogLevel,
+    ) -> Result<Self, IteratorConstructionError> {
+        Ok(Self {
+            batch_iterator: BatchMessageIterator::try_new(source, config, log_level)?,
             current_batch: None,
             current_index: 0,
+            failed: false,
+            ended: false,
+        })
+    }
+
+    /// Create an iterator over messages of `source` passed through the graph configured
+    /// by `config`, starting at the first message at or after `ns_from_origin`.
+    ///
+    /// # Errors
+    /// If the trace processing graph cannot be constructed or cannot seek,
+    /// see [`Self::seek()`].
+    pub fn try_starting_at(
+        source: TraceSource,
+        config: GraphConfig,
+        ns_from_origin: i64,
+        log_level: LogLevel,
+    ) -> Result<Self, IteratorConstructionError> {
+        let mut iterator = Self::try_with_graph_config(source, config, log_level)?;
+        iterator
+            .seek_ns_from_origin(ns_from_origin)
+            .map_err(IteratorConstructionError::Error)?;
+        Ok(iterator)
+    }
+
+    /// Move the iteration to `target`.
+    ///
+    /// The messages of the current batch which were not returned yet are discarded.
+    /// babeltrace2 may seek to a time by seeking to the beginning and skipping the
+    /// earlier messages, which still avoids analyzing them.
+    ///
+    /// # Errors
+    /// - If a component of the graph cannot seek, e.g., a live source or a filter added
+    ///   by the [`GraphConfig`]. The iteration cannot continue afterward.
+    /// - If the iteration already ended or failed.
+    pub fn seek(&mut self, target: SeekTarget) -> BtResult<()> {
+        if self.failed || self.ended {
+            return Err(BtError::new("Cannot seek, the iteration already ended"));
+        }
+
+        let _ = self.current_batch.take();
+        self.current_index = 0;
+
+        self.batch_iterator.internal.seek.set(Some(target));
+        let result = self.batch_iterator.try_next();
+        if self.batch_iterator.internal.seek.take().is_some() {
+            // The graph ended before the consume function was called
+            self.ended = true;
+            return Err(BtError::new("Cannot seek, the iteration already ended"));
+        }
+
+        match result {
+            Ok(Some(batch)) => {
+                self.current_batch = Some(batch);
+                Ok(())
+            }
+            Ok(None) => {
+                // Seeked after the last message
+                self.ended = true;
+                Ok(())
+            }
+            Err(e) => {
+                self.failed = true;
+                Err(e)
+            }
         }
     }
-}
 
-impl Iterator for MessageIterator {
-    type Item = BtMessageConst;
+    /// Move the iteration to the first message at or after `ns_from_origin`.
+    ///
+    /// # Errors
+    /// See [`Self::seek()`].
+    pub fn seek_ns_from_origin(&mut self, ns_from_origin: i64) -> BtResult<()> {
+        self.seek(SeekTarget::NsFromOrigin(ns_from_origin))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Move the iteration back to the first message.
+    ///
+    /// # Errors
+    /// See [`Self::seek()`].
+    pub fn seek_beginning(&mut self) -> BtResult<()> {
+        self.seek(SeekTarget::Beginning)
+    }
+
+    /// Get the next message.
+    ///
+    /// Returns `Ok(None)` when the trace ended. After an error, the iteration ends as well.
+    ///
+    /// # Errors
+    /// If babeltrace2 failed to get the next messages, e.g., because a trace is corrupted.
+    /// The causes of the error are available through [`BtError::causes()`].
+    pub fn try_next(&mut self) -> BtResult<Option<BtMessageConst>> {
         loop {
             while self.current_batch.is_none()
                 || self.current_index >= self.current_batch.as_ref().unwrap().len()
             {
                 let _ = self.current_batch.take();
-                match self.batch_iterator.next() {
-                    Some(batch) => {
+                if self.failed || self.ended {
+                    return Ok(None);
+                }
+                match self.batch_iterator.try_next() {
+                    Ok(Some(batch)) => {
                         self.current_batch = Some(batch);
                         self.current_index = 0;
                     }
-                    None => {
+                    Ok(None) => {
                         // No more batches - end of iterator
-                        return None;
+                        self.ended = true;
+                        return Ok(None);
+                    }
+                    Err(e) => {
+                        self.failed = true;
+                        return Err(e);
                     }
                 }
             }
@@ -300,9 +899,30 @@ impl Iterator for MessageIterator {
                 continue;
             }
 
-            return message;
+            return Ok(message);
         }
     }
+
+    /// Iterate over the messages, yielding the babeltrace2 errors instead of panicking.
+    ///
+    /// The iteration ends after the first error.
+    pub fn try_iter(&mut self) -> impl Iterator<Item = BtResult<BtMessageConst>> + '_ {
+        std::iter::from_fn(|| self.try_next().transpose())
+    }
+}
+
+impl Iterator for MessageIterator {
+    type Item = BtMessageConst;
+
+    /// # Panics
+    /// Panics if babeltrace2 fails to get the next messages.
+    ///
+    /// # See also
+    /// - [`MessageIterator::try_next()`] to handle the errors.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()
+            .unwrap_or_else(|e| panic!("Failed to get next batch of messages: {e}"))
+    }
 }
 
 impl Drop for MessageIterator {
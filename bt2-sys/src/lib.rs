@@ -1,4 +1,5 @@
 pub mod clock_snapshot;
+pub mod codegen;
 pub mod error;
 pub mod event;
 pub mod field;
@@ -6,10 +7,13 @@ pub mod graph;
 pub mod iterator;
 pub mod message;
 pub mod query;
+#[cfg(feature = "serde")]
+pub mod serde_bridge;
 pub mod stream;
 pub mod trace;
 mod utils;
 pub mod value;
+pub mod version;
 mod wrappers;
 
 pub mod logging;
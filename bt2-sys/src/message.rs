@@ -6,13 +6,25 @@ use derive_more::derive::{Deref, From};
 
 use crate::clock_snapshot::{BtClockClassConst, BtClockSnapshotConst};
 use crate::event::BtEventConst;
+use crate::field::BtField;
 use crate::raw_bindings::{
-    bt_message, bt_message_event_borrow_default_clock_snapshot_const,
+    bt_event_borrow_payload_field, bt_message,
+    bt_message_discarded_events_borrow_beginning_default_clock_snapshot_const,
+    bt_message_discarded_events_borrow_end_default_clock_snapshot_const,
+    bt_message_discarded_events_borrow_stream_class_default_clock_class_const,
+    bt_message_discarded_events_borrow_stream_const, bt_message_discarded_events_get_count,
+    bt_message_discarded_packets_borrow_beginning_default_clock_snapshot_const,
+    bt_message_discarded_packets_borrow_end_default_clock_snapshot_const,
+    bt_message_discarded_packets_borrow_stream_class_default_clock_class_const,
+    bt_message_discarded_packets_borrow_stream_const, bt_message_discarded_packets_get_count,
+    bt_message_event_borrow_default_clock_snapshot_const, bt_message_event_borrow_event,
     bt_message_event_borrow_event_const,
     bt_message_event_borrow_stream_class_default_clock_class_const, bt_message_get_ref,
-    bt_message_get_type, bt_message_put_ref, bt_message_type,
+    bt_message_get_type, bt_message_put_ref, bt_message_stream_beginning_borrow_stream_const,
+    bt_message_stream_end_borrow_stream_const, bt_message_type,
 };
-use crate::utils::ConstNonNull;
+use crate::stream::BtStreamConst;
+use crate::utils::{BtProperyAvailabilty, ConstNonNull};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BtMessageType {
@@ -138,6 +150,31 @@ impl BtMessageConst {
     }
 }
 
+impl BtStreamBeginningMessageConst {
+    /// Get the beginning stream.
+    ///
+    /// The metadata of the stream, i.e., its class and the class of its trace,
+    /// is known before the first event of the stream.
+    #[must_use]
+    pub fn get_stream(&self) -> BtStreamConst {
+        unsafe {
+            BtStreamConst::new_unchecked(bt_message_stream_beginning_borrow_stream_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+}
+
+impl BtStreamEndMessageConst {
+    /// Get the ending stream.
+    #[must_use]
+    pub fn get_stream(&self) -> BtStreamConst {
+        unsafe {
+            BtStreamConst::new_unchecked(bt_message_stream_end_borrow_stream_const(self.as_ptr()))
+        }
+    }
+}
+
 impl BtEventMessageConst {
     /// Get the event contained in this message.
     #[must_use]
@@ -177,6 +214,146 @@ impl BtEventMessageConst {
     }
 }
 
+impl BtDiscardedEventsMessageConst {
+    /// Get the stream in which the events were discarded.
+    #[must_use]
+    pub fn get_stream(&self) -> BtStreamConst {
+        unsafe {
+            BtStreamConst::new_unchecked(bt_message_discarded_events_borrow_stream_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Get the number of discarded events.
+    ///
+    /// Returns `None` if the source does not know how many events were discarded.
+    #[must_use]
+    pub fn get_count(&self) -> Option<u64> {
+        let mut count = 0;
+        let availability =
+            unsafe { bt_message_discarded_events_get_count(self.as_ptr(), &mut count) };
+        match availability.into() {
+            BtProperyAvailabilty::Available => Some(count),
+            BtProperyAvailabilty::NotAvailable => None,
+        }
+    }
+
+    /// Get the default clock class of the stream class of the discarded events.
+    #[must_use]
+    pub fn get_default_clock_class(&self) -> Option<BtClockClassConst> {
+        unsafe {
+            bt_message_discarded_events_borrow_stream_class_default_clock_class_const(self.as_ptr())
+                .try_into()
+                .ok()
+                .map(|ptr| BtClockClassConst::new_unchecked(ptr))
+        }
+    }
+
+    /// Get snapshot of the default clock at the beginning of the discarded events time range.
+    ///
+    /// Returns `None` if the stream class does not provide the time range of discarded events.
+    #[must_use]
+    pub fn get_beginning_default_clock_snapshot(&self) -> Option<BtClockSnapshotConst> {
+        self.has_default_clock_snapshots().then(|| unsafe {
+            BtClockSnapshotConst::new_unchecked(
+                bt_message_discarded_events_borrow_beginning_default_clock_snapshot_const(
+                    self.as_ptr(),
+                ),
+            )
+        })
+    }
+
+    /// Get snapshot of the default clock at the end of the discarded events time range.
+    ///
+    /// Returns `None` if the stream class does not provide the time range of discarded events.
+    #[must_use]
+    pub fn get_end_default_clock_snapshot(&self) -> Option<BtClockSnapshotConst> {
+        self.has_default_clock_snapshots().then(|| unsafe {
+            BtClockSnapshotConst::new_unchecked(
+                bt_message_discarded_events_borrow_end_default_clock_snapshot_const(self.as_ptr()),
+            )
+        })
+    }
+
+    fn has_default_clock_snapshots(&self) -> bool {
+        self.get_stream()
+            .get_class()
+            .discarded_events_have_default_clock_snapshots()
+    }
+}
+
+impl BtDiscardedPacketsMessageConst {
+    /// Get the stream in which the packets were discarded.
+    #[must_use]
+    pub fn get_stream(&self) -> BtStreamConst {
+        unsafe {
+            BtStreamConst::new_unchecked(bt_message_discarded_packets_borrow_stream_const(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Get the number of discarded packets.
+    ///
+    /// Returns `None` if the source does not know how many packets were discarded.
+    #[must_use]
+    pub fn get_count(&self) -> Option<u64> {
+        let mut count = 0;
+        let availability =
+            unsafe { bt_message_discarded_packets_get_count(self.as_ptr(), &mut count) };
+        match availability.into() {
+            BtProperyAvailabilty::Available => Some(count),
+            BtProperyAvailabilty::NotAvailable => None,
+        }
+    }
+
+    /// Get the default clock class of the stream class of the discarded packets.
+    #[must_use]
+    pub fn get_default_clock_class(&self) -> Option<BtClockClassConst> {
+        unsafe {
+            bt_message_discarded_packets_borrow_stream_class_default_clock_class_const(
+                self.as_ptr(),
+            )
+            .try_into()
+            .ok()
+            .map(|ptr| BtClockClassConst::new_unchecked(ptr))
+        }
+    }
+
+    /// Get snapshot of the default clock at the beginning of the discarded packets time range.
+    ///
+    /// Returns `None` if the stream class does not provide the time range of discarded packets.
+    #[must_use]
+    pub fn get_beginning_default_clock_snapshot(&self) -> Option<BtClockSnapshotConst> {
+        self.has_default_clock_snapshots().then(|| unsafe {
+            BtClockSnapshotConst::new_unchecked(
+                bt_message_discarded_packets_borrow_beginning_default_clock_snapshot_const(
+                    self.as_ptr(),
+                ),
+            )
+        })
+    }
+
+    /// Get snapshot of the default clock at the end of the discarded packets time range.
+    ///
+    /// Returns `None` if the stream class does not provide the time range of discarded packets.
+    #[must_use]
+    pub fn get_end_default_clock_snapshot(&self) -> Option<BtClockSnapshotConst> {
+        self.has_default_clock_snapshots().then(|| unsafe {
+            BtClockSnapshotConst::new_unchecked(
+                bt_message_discarded_packets_borrow_end_default_clock_snapshot_const(self.as_ptr()),
+            )
+        })
+    }
+
+    fn has_default_clock_snapshots(&self) -> bool {
+        self.get_stream()
+            .get_class()
+            .discarded_packets_have_default_clock_snapshots()
+    }
+}
+
 impl Clone for BtMessageConst {
     fn clone(&self) -> Self {
         unsafe {
@@ -195,6 +372,55 @@ impl Drop for BtMessageConst {
     }
 }
 
+/// Event message created by a component implemented in Rust.
+///
+/// The fields of the event can be set until the message is converted into a [`BtMessageConst`]
+/// and pushed to the output of the message iterator.
+pub struct BtEventMessage(NonNull<bt_message>);
+
+impl BtEventMessage {
+    /// # Safety
+    /// The `message` must be a valid pointer to an event message which is not frozen.
+    pub(crate) unsafe fn new_unchecked(message: *mut bt_message) -> Self {
+        Self(NonNull::new_unchecked(message))
+    }
+
+    #[inline]
+    const fn as_ptr(&self) -> *mut bt_message {
+        self.0.as_ptr()
+    }
+
+    /// Get the payload field of the event.
+    ///
+    /// Returns `None` if the event class has no payload field class.
+    #[must_use]
+    pub fn get_payload_mut(&mut self) -> Option<BtField<'_>> {
+        let payload =
+            unsafe { bt_event_borrow_payload_field(bt_message_event_borrow_event(self.as_ptr())) };
+        if payload.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtField::new_unchecked(payload) })
+    }
+}
+
+impl From<BtEventMessage> for BtMessageConst {
+    fn from(message: BtEventMessage) -> Self {
+        // The reference is moved to the constant message
+        let message = std::mem::ManuallyDrop::new(message);
+        unsafe { Self::new_unchecked(message.as_ptr()) }
+    }
+}
+
+impl Drop for BtEventMessage {
+    fn drop(&mut self) {
+        unsafe {
+            bt_message_put_ref(self.as_ptr());
+        }
+    }
+}
+
 pub(crate) struct BtMessageArrayConst(NonNull<*const bt_message>, usize);
 impl BtMessageArrayConst {
     pub(crate) unsafe fn new_unchecked(messages: *mut *const bt_message, count: u64) -> Self {
@@ -13,7 +13,10 @@ use crate::raw_bindings::{
     bt_query_executor_set_logging_level,
 };
 use crate::utils::Const;
-use crate::value::{BtValue, BtValueMap, BtValueRealConst, BtValueStringConst, BtValueTypedConst};
+use crate::value::{
+    BtValue, BtValueArray, BtValueConst, BtValueDecodeError, BtValueMap, BtValueRealConst,
+    BtValueString, BtValueStringConst, BtValueTypedConst, TryFromBtValueConst,
+};
 
 pub struct BtQueryExecutor(NonNull<bt_query_executor>);
 
@@ -210,6 +213,194 @@ impl TryFrom<Const<BtValue>> for SupportInfoResult {
     }
 }
 
+/// Parameters of the `babeltrace.trace-infos` query of the `src.ctf.fs` component class.
+pub struct TraceInfosParams<'a> {
+    /// Paths to the trace directories.
+    pub inputs: &'a [&'a CStr],
+}
+
+impl<'a> TryFrom<TraceInfosParams<'a>> for BtValueMap {
+    type Error = OutOfMemory;
+
+    fn try_from(params: TraceInfosParams<'a>) -> Result<Self, Self::Error> {
+        let mut inputs = BtValueArray::new()?;
+        for input in params.inputs {
+            inputs.push(&BtValueString::new_cstr(input)?.into())?;
+        }
+
+        let mut map = Self::new()?;
+        map.insert_with_cstr_key(c"inputs", &inputs)?;
+        Ok(map)
+    }
+}
+
+/// Time range in nanoseconds from the clock origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRangeNs {
+    pub begin: i64,
+    pub end: i64,
+}
+
+impl TimeRangeNs {
+    /// Smallest range containing both ranges.
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            begin: self.begin.min(other.begin),
+            end: self.end.max(other.end),
+        }
+    }
+
+    /// Range contained in both ranges.
+    ///
+    /// Returns `None` if the ranges do not overlap.
+    #[must_use]
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let range = Self {
+            begin: self.begin.max(other.begin),
+            end: self.end.min(other.end),
+        };
+        (range.begin <= range.end).then_some(range)
+    }
+
+    /// Length of the range in nanoseconds.
+    #[must_use]
+    pub const fn duration_ns(&self) -> u64 {
+        self.end.abs_diff(self.begin)
+    }
+}
+
+/// Information about a trace returned by the `babeltrace.trace-infos` query.
+#[derive(Debug, Clone)]
+pub struct TraceInfo {
+    name: String,
+    range_ns: Option<TimeRangeNs>,
+    intersection_range_ns: Option<TimeRangeNs>,
+    stream_infos: Vec<StreamInfo>,
+}
+
+impl TraceInfo {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn stream_infos(&self) -> &[StreamInfo] {
+        &self.stream_infos
+    }
+
+    /// Time range of all streams of the trace.
+    ///
+    /// If the query does not report the range of the trace, the union of the stream ranges is used.
+    /// Returns `None` if no stream has a time range.
+    #[must_use]
+    pub fn range_ns(&self) -> Option<TimeRangeNs> {
+        self.range_ns.or_else(|| {
+            self.stream_infos
+                .iter()
+                .filter_map(StreamInfo::range_ns)
+                .reduce(TimeRangeNs::union)
+        })
+    }
+
+    /// Time range in which all streams of the trace have events.
+    ///
+    /// If the query does not report the intersection, it is computed from the stream ranges.
+    /// Returns `None` if a stream has no time range or the stream ranges do not overlap.
+    #[must_use]
+    pub fn intersection_range_ns(&self) -> Option<TimeRangeNs> {
+        if self.intersection_range_ns.is_some() {
+            return self.intersection_range_ns;
+        }
+        let mut ranges = self.stream_infos.iter().map(StreamInfo::range_ns);
+        let first = ranges.next()??;
+        ranges.try_fold(first, |acc, range| acc.intersection(range?))
+    }
+}
+
+/// Information about a stream of a trace returned by the `babeltrace.trace-infos` query.
+#[derive(Debug, Clone)]
+pub struct StreamInfo {
+    range_ns: Option<TimeRangeNs>,
+    class_id: Option<u64>,
+    port_name: String,
+}
+
+impl StreamInfo {
+    /// Time range of the stream.
+    ///
+    /// Streams without a clock have no time range.
+    #[must_use]
+    pub const fn range_ns(&self) -> Option<TimeRangeNs> {
+        self.range_ns
+    }
+
+    /// ID of the stream class of the stream, if reported by the query.
+    #[must_use]
+    pub const fn class_id(&self) -> Option<u64> {
+        self.class_id
+    }
+
+    /// Name of the output port of the `src.ctf.fs` component emitting the messages of the stream.
+    #[must_use]
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TraceInfosError {
+    #[error("Incorrect layout of BtValue: {0}")]
+    IncorrectLayout(#[from] BtValueDecodeError),
+
+    #[error("Trace infos query failed. Caused by: {0}")]
+    QueryError(#[from] BtQueryError),
+
+    #[error("Not supported.")]
+    NotSupported,
+}
+
+impl TryFromBtValueConst for TimeRangeNs {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        let map = value.try_into_map()?;
+        Ok(Self {
+            begin: map.decode_entry(c"begin")?,
+            end: map.decode_entry(c"end")?,
+        })
+    }
+}
+
+impl TryFromBtValueConst for StreamInfo {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        let map = value.try_into_map()?;
+        Ok(Self {
+            range_ns: map.decode_entry(c"range-ns")?,
+            class_id: map.decode_entry(c"class-id")?,
+            port_name: map.decode_entry(c"port-name")?,
+        })
+    }
+}
+
+impl TryFromBtValueConst for TraceInfo {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        let map = value.try_into_map()?;
+        Ok(Self {
+            name: map.decode_entry(c"name")?,
+            range_ns: map.decode_entry(c"range-ns")?,
+            intersection_range_ns: map.decode_entry(c"intersection-range-ns")?,
+            stream_infos: map.decode_entry(c"stream-infos")?,
+        })
+    }
+}
+
+/// Parse the result of the `babeltrace.trace-infos` query.
+pub(crate) fn trace_infos_from_result(
+    value: &Const<BtValue>,
+) -> Result<Vec<TraceInfo>, TraceInfosError> {
+    Ok(value.as_const().decode()?)
+}
+
 pub mod support_info {
     use std::ffi::CString;
     use std::mem;
@@ -224,7 +415,7 @@ pub mod support_info {
 
     #[derive(Clone)]
     pub struct Query {
-        component: BtComponentClassConst<'static>,
+        pub(super) component: BtComponentClassConst<'static>,
 
         // The plugin is stored to ensure that the component is valid for the lifetime of the query.
         _plugin: BtPlugin,
@@ -307,3 +498,29 @@ pub mod support_info {
         }
     }
 }
+
+pub mod trace_infos {
+    use std::ffi::CStr;
+
+    use crate::graph::component::BtComponentType;
+
+    use super::support_info::QueryError;
+    use super::{TraceInfo, TraceInfosError, TraceInfosParams};
+
+    /// Prepared `babeltrace.trace-infos` query of the `src.ctf.fs` component class.
+    #[derive(Clone)]
+    pub struct Query(super::support_info::Query);
+
+    impl Query {
+        pub fn new_prepared() -> Result<Self, QueryError> {
+            super::support_info::Query::new_prepared("ctf", "fs", BtComponentType::Source).map(Self)
+        }
+
+        /// Query the time ranges and streams of the traces in `inputs`.
+        pub fn query(&self, inputs: &[&CStr]) -> Result<Vec<TraceInfo>, TraceInfosError> {
+            self.0
+                .component
+                .query_trace_infos(TraceInfosParams { inputs })
+        }
+    }
+}
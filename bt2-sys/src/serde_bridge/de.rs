@@ -0,0 +1,516 @@
+use std::ffi::CStr;
+
+use serde::de::value::{BorrowedStrDeserializer, StrDeserializer, U64Deserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize};
+
+use super::{cast_field, EnumerationValue, SerdeError};
+use crate::field::{
+    BtFieldArrayConst, BtFieldClassType, BtFieldConst, BtFieldStructureConst, BtFieldType,
+    BtFieldVariantConst,
+};
+use crate::value::{BtValueArrayConst, BtValueConst, BtValueTypedConst};
+
+/// Deserialize a field, e.g., the payload of an event, into `T`.
+///
+/// # Errors
+/// If the field does not have the layout expected by `T`.
+pub fn from_field<T: DeserializeOwned>(field: BtFieldConst) -> Result<T, SerdeError> {
+    T::deserialize(FieldDeserializer::new(field))
+}
+
+/// Deserialize a value into `T`.
+///
+/// Strings may be borrowed from the value.
+///
+/// # Errors
+/// If the value does not have the layout expected by `T`.
+pub fn from_value<'a, T: Deserialize<'a>>(value: BtValueConst<'a>) -> Result<T, SerdeError> {
+    T::deserialize(ValueDeserializer::new(value))
+}
+
+/// [`serde::Deserializer`] of a [`BtFieldConst`].
+pub struct FieldDeserializer(BtFieldConst);
+
+impl FieldDeserializer {
+    #[must_use]
+    pub const fn new(field: BtFieldConst) -> Self {
+        Self(field)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer {
+    type Error = SerdeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match cast_field(self.0)? {
+            BtFieldType::Boolean(field) => visitor.visit_bool(field.get_value()),
+            BtFieldType::UnsignedInteger(field) => visitor.visit_u64(field.get_value()),
+            BtFieldType::SignedInteger(field) => visitor.visit_i64(field.get_value()),
+            BtFieldType::String(field) => visitor.visit_str(field.try_get_value()?),
+            BtFieldType::Array(field) => visitor.visit_seq(FieldArrayAccess::new(field)),
+            BtFieldType::Structure(field) => visitor.visit_map(FieldStructureAccess::new(field)),
+            BtFieldType::Enumeration(field) => match EnumerationValue::of(&field) {
+                EnumerationValue::Unsigned(value) => visitor.visit_u64(value),
+                EnumerationValue::Signed(value) => visitor.visit_i64(value),
+            },
+            BtFieldType::Option(field) => match field.get_field() {
+                Some(field) => visitor.visit_some(Self(field)),
+                None => visitor.visit_none(),
+            },
+            BtFieldType::Variant(field) => visitor.visit_map(FieldVariantAccess::new(field)),
+        }
+    }
+
+    /// Fields other than options are always present.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.get_class_type() == Some(BtFieldClassType::Option) {
+            self.deserialize_any(visitor)
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Enumerations select a unit variant by the label of their value
+    /// and variants select a variant by the name of the selected option.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match cast_field(self.0)? {
+            BtFieldType::Enumeration(field) => {
+                let labels = field.get_labels()?;
+                let Some(label) = labels.first() else {
+                    return Err(SerdeError::UnmappedEnumeration(
+                        EnumerationValue::of(&field).into(),
+                    ));
+                };
+                visitor.visit_enum(StrDeserializer::<SerdeError>::new(label.to_str()?))
+            }
+            BtFieldType::Variant(field) => visitor.visit_enum(FieldVariantAccess::new(field)),
+            BtFieldType::String(field) => {
+                visitor.visit_enum(StrDeserializer::<SerdeError>::new(field.try_get_value()?))
+            }
+            field => Err(de::Error::invalid_type(
+                de::Unexpected::Other(&format!("{:?} field", field.get_class_type())),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct FieldArrayAccess {
+    array: BtFieldArrayConst,
+    index: u64,
+}
+
+impl FieldArrayAccess {
+    const fn new(array: BtFieldArrayConst) -> Self {
+        Self { array, index: 0 }
+    }
+}
+
+impl<'de> SeqAccess<'de> for FieldArrayAccess {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index >= self.array.get_length() {
+            return Ok(None);
+        }
+
+        let element = self.array.get_value(self.index);
+        self.index += 1;
+        seed.deserialize(FieldDeserializer(element)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.array.get_length() - self.index).ok()
+    }
+}
+
+struct FieldStructureAccess {
+    structure: BtFieldStructureConst,
+    index: u64,
+}
+
+impl FieldStructureAccess {
+    const fn new(structure: BtFieldStructureConst) -> Self {
+        Self {
+            structure,
+            index: 0,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for FieldStructureAccess {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let class = self.structure.get_class();
+        if self.index >= class.get_member_count() {
+            return Ok(None);
+        }
+
+        let member = class.get_member_by_index(self.index);
+        seed.deserialize(StrDeserializer::<SerdeError>::new(member.get_name()))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let field = self.structure.get_field_by_index(self.index);
+        self.index += 1;
+        seed.deserialize(FieldDeserializer(field))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.structure.get_field_count() - self.index).ok()
+    }
+}
+
+/// Access of a variant field, either as a map with a single entry or as an enum.
+struct FieldVariantAccess {
+    variant: BtFieldVariantConst,
+    visited_key: bool,
+}
+
+impl FieldVariantAccess {
+    const fn new(variant: BtFieldVariantConst) -> Self {
+        Self {
+            variant,
+            visited_key: false,
+        }
+    }
+
+    /// Deserialize the name of the selected option, or its index if it has no name.
+    fn deserialize_option_name<'de, S: DeserializeSeed<'de>>(
+        &self,
+        seed: S,
+    ) -> Result<S::Value, SerdeError> {
+        match self.variant.get_selected_option_name() {
+            Some(name) => seed.deserialize(StrDeserializer::<SerdeError>::new(name.to_str()?)),
+            None => seed.deserialize(U64Deserializer::<SerdeError>::new(
+                self.variant.get_selected_option_index(),
+            )),
+        }
+    }
+
+    fn into_selected_field(self) -> FieldDeserializer {
+        FieldDeserializer(self.variant.get_selected_field())
+    }
+}
+
+impl<'de> MapAccess<'de> for FieldVariantAccess {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        if self.visited_key {
+            return Ok(None);
+        }
+
+        self.visited_key = true;
+        self.deserialize_option_name(seed).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(FieldDeserializer(self.variant.get_selected_field()))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(!self.visited_key))
+    }
+}
+
+impl<'de> EnumAccess<'de> for FieldVariantAccess {
+    type Error = SerdeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let value = self.deserialize_option_name(seed)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for FieldVariantAccess {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self.into_selected_field())
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(self.into_selected_field(), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(self.into_selected_field(), visitor)
+    }
+}
+
+/// [`serde::Deserializer`] of a [`BtValueConst`].
+pub struct ValueDeserializer<'a>(BtValueConst<'a>);
+
+impl<'a> ValueDeserializer<'a> {
+    #[must_use]
+    pub const fn new(value: BtValueConst<'a>) -> Self {
+        Self(value)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = SerdeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.cast() {
+            BtValueTypedConst::Null(_) => visitor.visit_unit(),
+            BtValueTypedConst::Bool(value) => visitor.visit_bool(value.get()),
+            BtValueTypedConst::UnsignedInteger(value) => visitor.visit_u64(value.get()),
+            BtValueTypedConst::SignedInteger(value) => visitor.visit_i64(value.get()),
+            BtValueTypedConst::Real(value) => visitor.visit_f64(value.get()),
+            BtValueTypedConst::String(value) => visitor.visit_borrowed_str(value.get()?),
+            BtValueTypedConst::Array(value) => visitor.visit_seq(ValueArrayAccess::new(value)),
+            BtValueTypedConst::Map(value) => {
+                visitor.visit_map(ValueMapAccess::new(value.entries()))
+            }
+        }
+    }
+
+    /// Null values are `None`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.cast() {
+            BtValueTypedConst::Null(_) => visitor.visit_none(),
+            value => visitor.visit_some(Self(value.into_value())),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Strings select a unit variant and maps with a single entry select the variant by the key.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0.cast() {
+            BtValueTypedConst::String(value) => {
+                visitor.visit_enum(BorrowedStrDeserializer::<SerdeError>::new(value.get()?))
+            }
+            BtValueTypedConst::Map(value) => {
+                let mut entries = value.entries();
+                match (entries.pop(), entries.is_empty()) {
+                    (Some((key, value)), true) => {
+                        visitor.visit_enum(ValueEnumAccess { key, value })
+                    }
+                    _ => Err(de::Error::invalid_length(
+                        usize::try_from(value.length()).unwrap_or(usize::MAX),
+                        &"map with a single entry",
+                    )),
+                }
+            }
+            value => Err(de::Error::invalid_type(
+                de::Unexpected::Other(&format!("{:?} value", value.get_type())),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct ValueArrayAccess<'a> {
+    array: BtValueArrayConst<'a>,
+    index: u64,
+}
+
+impl<'a> ValueArrayAccess<'a> {
+    const fn new(array: BtValueArrayConst<'a>) -> Self {
+        Self { array, index: 0 }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ValueArrayAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index >= self.array.length() {
+            return Ok(None);
+        }
+
+        let element = self.array.get(self.index);
+        self.index += 1;
+        seed.deserialize(ValueDeserializer(element)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.array.length() - self.index).ok()
+    }
+}
+
+struct ValueMapAccess<'a> {
+    entries: std::vec::IntoIter<(&'a CStr, BtValueConst<'a>)>,
+    value: Option<BtValueConst<'a>>,
+}
+
+impl<'a> ValueMapAccess<'a> {
+    fn new(entries: Vec<(&'a CStr, BtValueConst<'a>)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for ValueMapAccess<'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::<SerdeError>::new(key.to_str()?))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| SerdeError::Custom("Map value requested before its key".to_owned()))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// Access of a map entry as an externally tagged enum.
+struct ValueEnumAccess<'a> {
+    key: &'a CStr,
+    value: BtValueConst<'a>,
+}
+
+impl<'de> EnumAccess<'de> for ValueEnumAccess<'de> {
+    type Error = SerdeError;
+    type Variant = ValueDeserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<SerdeError>::new(
+            self.key.to_str()?,
+        ))?;
+        Ok((variant, ValueDeserializer(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for ValueDeserializer<'de> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::IgnoredAny::deserialize(self).map(|_| ())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,492 @@
+//! Integration of fields and values with [`serde`].
+//!
+//! - [`from_field`] deserializes an event payload field into any [`serde::Deserialize`] type.
+//! - [`from_value`] deserializes a value, e.g., component parameters or a query result.
+//! - [`to_value`] serializes any [`serde::Serialize`] type into a value,
+//!   e.g., to build component parameters from a Rust struct.
+//!
+//! [`BtFieldConst`](crate::field::BtFieldConst) and [`BtValueConst`](crate::value::BtValueConst)
+//! also implement [`serde::Serialize`], so they can be written by any serde format.
+//!
+//! Fields are mapped to the serde data model as follows:
+//! - Structures are maps from the member names to their fields.
+//! - Enumerations are their integer value. When deserialized into an enum,
+//!   the label of the first mapping containing the value selects the unit variant.
+//! - Options are `None` or the content of the option.
+//! - Variants are maps with a single entry from the name of the selected option to its field.
+//!   When deserialized into an enum, the option name selects the variant.
+
+mod de;
+mod ser;
+
+use std::ffi::NulError;
+use std::str::Utf8Error;
+
+use thiserror::Error;
+
+use crate::error::OutOfMemory;
+use crate::field::{BtFieldClassType, BtFieldConst, BtFieldEnumerationConst, BtFieldType};
+
+pub use de::{from_field, from_value, FieldDeserializer, ValueDeserializer};
+pub use ser::{to_value, ValueSerializer};
+
+/// Error of the (de)serialization of fields and values.
+#[derive(Debug, Error)]
+pub enum SerdeError {
+    #[error("{0}")]
+    Custom(String),
+
+    #[error(transparent)]
+    OutOfMemory(#[from] OutOfMemory),
+
+    #[error("String is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] Utf8Error),
+
+    #[error("String contains a null byte: {0}")]
+    NulByte(#[from] NulError),
+
+    #[error("Field class is not supported")]
+    UnsupportedField,
+
+    #[error("Enumeration value {0} is not contained in any mapping")]
+    UnmappedEnumeration(i128),
+
+    #[error("Map keys must be strings")]
+    KeyMustBeString,
+}
+
+impl serde::de::Error for SerdeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl serde::ser::Error for SerdeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Cast the field, failing instead of panicking on unsupported field classes.
+fn cast_field(field: BtFieldConst) -> Result<BtFieldType, SerdeError> {
+    if field.get_class_type() == Some(BtFieldClassType::BitArray) {
+        return Err(SerdeError::UnsupportedField);
+    }
+    field.cast().map_err(|_| SerdeError::UnsupportedField)
+}
+
+/// Integer value of an enumeration field.
+enum EnumerationValue {
+    Unsigned(u64),
+    Signed(i64),
+}
+
+impl EnumerationValue {
+    fn of(field: &BtFieldEnumerationConst) -> Self {
+        // Safety: The copy does not outlive the borrowed field.
+        let copy = unsafe { field.clone_unchecked() };
+        if field.is_signed() {
+            Self::Signed(copy.try_into_int().expect("Signed enumeration").get_value())
+        } else {
+            Self::Unsigned(
+                copy.try_into_uint()
+                    .expect("Unsigned enumeration")
+                    .get_value(),
+            )
+        }
+    }
+}
+
+impl From<EnumerationValue> for i128 {
+    fn from(value: EnumerationValue) -> Self {
+        match value {
+            EnumerationValue::Unsigned(value) => value.into(),
+            EnumerationValue::Signed(value) => value.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::error::{BtError, BtResult};
+    use crate::event::BtEventClass;
+    use crate::field::{
+        BtFieldClass, BtFieldClassStructure, BtFieldClassUnsignedEnumeration, BtFieldClassVariant,
+    };
+    use crate::graph::user_component::{
+        BtComponentClassSink, BtComponentClassSource, ComponentMessageIterator, MessageOutput,
+        SelfComponentPortOutput, SelfComponentSink, SelfComponentSource, SelfMessageIterator,
+        SinkComponent, SourceComponent, UpstreamMessageIterator,
+    };
+    use crate::graph::BtGraph;
+    use crate::logging::LogLevel;
+    use crate::message::{BtMessageConst, BtMessageType};
+    use crate::raw_bindings::bt_graph_run_status;
+    use crate::stream::BtStream;
+    use crate::value::BtValueConst;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(u64),
+        Segment(i64, i64),
+        Rectangle { width: u64, height: u64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Params {
+        name: String,
+        count: u64,
+        offset: i64,
+        ratio: f64,
+        enabled: bool,
+        tags: Vec<String>,
+        limits: BTreeMap<String, u64>,
+        parent: Option<String>,
+        missing: Option<u64>,
+        pair: (u8, String),
+        shape: Shape,
+    }
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        from_value(to_value(value).unwrap().as_const()).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_struct() {
+        let params = Params {
+            name: "trace".to_owned(),
+            count: 3,
+            offset: -12,
+            ratio: 0.5,
+            enabled: true,
+            tags: vec!["ros2".to_owned(), "r2r".to_owned()],
+            limits: BTreeMap::from([("low".to_owned(), 1), ("high".to_owned(), 10)]),
+            parent: Some("root".to_owned()),
+            missing: None,
+            pair: (7, "seven".to_owned()),
+            shape: Shape::Rectangle {
+                width: 4,
+                height: 2,
+            },
+        };
+
+        assert_eq!(round_trip(&params), params);
+    }
+
+    #[test]
+    fn test_round_trip_map_and_sequence() {
+        let map = BTreeMap::from([
+            ("empty".to_owned(), Vec::new()),
+            ("values".to_owned(), vec![-1, 0, i64::MAX]),
+        ]);
+        assert_eq!(round_trip(&map), map);
+
+        let sequence = vec![vec![true], vec![false, true]];
+        assert_eq!(round_trip(&sequence), sequence);
+    }
+
+    #[test]
+    fn test_round_trip_options() {
+        assert_eq!(round_trip(&Some(5_u64)), Some(5));
+        assert_eq!(round_trip(&None::<u64>), None);
+        assert_eq!(
+            round_trip(&vec![Some("a".to_owned()), None]),
+            [Some("a".to_owned()), None]
+        );
+    }
+
+    #[test]
+    fn test_round_trip_enums() {
+        let shapes = [
+            Shape::Empty,
+            Shape::Circle(3),
+            Shape::Segment(-1, 1),
+            Shape::Rectangle {
+                width: 4,
+                height: 2,
+            },
+        ];
+        for shape in shapes {
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        // Externally tagged representation
+        let value = to_value(&Shape::Empty).unwrap();
+        assert_eq!(from_value::<String>(value.as_const()).unwrap(), "Empty");
+        let value = to_value(&Shape::Circle(3)).unwrap();
+        assert_eq!(
+            from_value::<BTreeMap<String, u64>>(value.as_const()).unwrap(),
+            BTreeMap::from([("Circle".to_owned(), 3)])
+        );
+        let value = to_value(&Shape::Segment(-1, 1)).unwrap();
+        assert_eq!(
+            from_value::<BTreeMap<String, Vec<i64>>>(value.as_const()).unwrap(),
+            BTreeMap::from([("Segment".to_owned(), vec![-1, 1])])
+        );
+    }
+
+    #[test]
+    fn test_to_value_errors() {
+        assert!(matches!(
+            to_value(&BTreeMap::from([(1, 2)])),
+            Err(SerdeError::KeyMustBeString)
+        ));
+        assert!(matches!(to_value("a\0b"), Err(SerdeError::NulByte(_))));
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    enum State {
+        Idle,
+        Running,
+        Stopped,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Value {
+        Int(i64),
+        Text(String),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Payload {
+        state: State,
+        value: Value,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum RawValue {
+        Int(i64),
+        Text(String),
+    }
+
+    /// Payload with the enumeration as its value and the variant as a map.
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct RawPayload {
+        state: u64,
+        value: BTreeMap<String, RawValue>,
+    }
+
+    /// Source of `state` events with an enumeration and a variant member.
+    struct StateSource;
+
+    struct StateIterator {
+        stream: BtStream,
+        event_class: BtEventClass,
+        /// Index of the next message.
+        next: u64,
+    }
+
+    impl SourceComponent for StateSource {
+        type Iterator = StateIterator;
+
+        fn initialize(
+            component: &mut SelfComponentSource,
+            _params: BtValueConst,
+        ) -> BtResult<Self> {
+            component.add_output_port(c"out")?;
+            Ok(Self)
+        }
+    }
+
+    impl ComponentMessageIterator for StateIterator {
+        type Component = StateSource;
+
+        fn initialize(
+            _component: &StateSource,
+            iterator: &mut SelfMessageIterator,
+            _port: SelfComponentPortOutput,
+        ) -> BtResult<Self> {
+            let mut trace_class = iterator.create_trace_class()?;
+            let mut stream_class = trace_class.create_stream_class()?;
+
+            let mut state_class = BtFieldClassUnsignedEnumeration::new(&mut trace_class)?;
+            state_class.add_mapping(c"IDLE", &[0..=0])?;
+            state_class.add_mapping(c"RUNNING", &[1..=5])?;
+            state_class.add_mapping(c"STOPPED", &[6..=6, 8..=9])?;
+            let mut value_class = BtFieldClassVariant::new(&mut trace_class)?;
+            value_class
+                .append_option(c"int", &BtFieldClass::new_signed_integer(&mut trace_class)?)?;
+            value_class.append_option(c"text", &BtFieldClass::new_string(&mut trace_class)?)?;
+
+            let mut payload_class = BtFieldClassStructure::new(&mut trace_class)?;
+            payload_class.append_member(c"state", &state_class)?;
+            payload_class.append_member(c"value", &value_class)?;
+            let mut event_class = stream_class.create_event_class()?;
+            event_class.set_name(c"state")?;
+            event_class.set_payload_field_class(&payload_class)?;
+
+            let mut trace = trace_class.create_trace()?;
+            let stream = stream_class.create_stream(&mut trace)?;
+
+            Ok(Self {
+                stream,
+                event_class,
+                next: 0,
+            })
+        }
+
+        fn next(
+            &mut self,
+            iterator: &mut SelfMessageIterator,
+            output: &mut MessageOutput,
+        ) -> BtResult<()> {
+            while !output.is_full() {
+                let message = match self.next {
+                    0 => iterator.create_stream_beginning_message(&self.stream.as_const())?,
+                    1 | 2 => {
+                        let mut message =
+                            iterator.create_event_message(&self.event_class, &self.stream, None)?;
+                        let mut payload = message.get_payload_mut().unwrap();
+                        let (state, option) = if self.next == 1 { (3, 0) } else { (9, 1) };
+                        payload
+                            .get_field_by_name_cstr(c"state")
+                            .unwrap()
+                            .set_uint(state);
+                        let mut value = payload.get_field_by_name_cstr(c"value").unwrap();
+                        value.select_option(option);
+                        let mut selected = value.get_selected_option_field();
+                        if option == 0 {
+                            selected.set_int(-7);
+                        } else {
+                            selected.set_string(c"done")?;
+                        }
+                        message.into()
+                    }
+                    3 => iterator.create_stream_end_message(&self.stream.as_const())?,
+                    _ => return Err(BtError::End),
+                };
+                self.next += 1;
+                if output.push(message).is_err() {
+                    unreachable!("The output is not full");
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    type Deserialized = (Result<Payload, SerdeError>, Result<RawPayload, SerdeError>);
+
+    thread_local! {
+        static DESERIALIZED: RefCell<Vec<Deserialized>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Sink deserializing the payloads of the received events into `DESERIALIZED`.
+    struct DeserializingSink {
+        upstream: Option<UpstreamMessageIterator>,
+    }
+
+    impl SinkComponent for DeserializingSink {
+        fn initialize(component: &mut SelfComponentSink, _params: BtValueConst) -> BtResult<Self> {
+            component.add_input_port(c"in")?;
+            Ok(Self { upstream: None })
+        }
+
+        fn graph_is_configured(&mut self, component: &mut SelfComponentSink) -> BtResult<()> {
+            self.upstream = Some(component.create_message_iterator(component.input_port(0))?);
+            Ok(())
+        }
+
+        fn consume(&mut self, _component: &mut SelfComponentSink) -> BtResult<()> {
+            let mut messages: Vec<BtMessageConst> = Vec::new();
+            self.upstream.as_mut().unwrap().next_into(&mut messages)?;
+            DESERIALIZED.with_borrow_mut(|deserialized| {
+                for message in messages {
+                    if message.get_type() != BtMessageType::Event {
+                        continue;
+                    }
+                    let message = message.into_event_msg();
+                    let event = message.get_event();
+                    deserialized.push((
+                        from_field(event.get_payload().unwrap()),
+                        from_field(event.get_payload().unwrap()),
+                    ));
+                }
+            });
+            Ok(())
+        }
+    }
+
+    fn deserialize_payloads() -> Vec<Deserialized> {
+        let source_class = BtComponentClassSource::new::<StateSource>(c"states").unwrap();
+        let sink_class = BtComponentClassSink::new::<DeserializingSink>(c"deserializing").unwrap();
+
+        let mut builder = BtGraph::builder().unwrap();
+        unsafe {
+            let source = builder
+                .add_source_component_unchecked(
+                    source_class.as_const(),
+                    c"source",
+                    None,
+                    LogLevel::Warning,
+                )
+                .unwrap();
+            let sink = builder
+                .add_sink_component_unchecked(
+                    sink_class.as_const(),
+                    c"sink",
+                    None,
+                    LogLevel::Warning,
+                )
+                .unwrap();
+            builder
+                .connect_ports_unchecked(source.get_output_port(0), sink.get_input_port(0))
+                .unwrap();
+        }
+
+        let mut graph = builder.build();
+        let status = unsafe { graph.run() };
+        assert_eq!(status, bt_graph_run_status::BT_GRAPH_RUN_STATUS_OK);
+
+        DESERIALIZED.take()
+    }
+
+    #[test]
+    fn test_from_field_enumeration_and_variant() {
+        let deserialized = deserialize_payloads();
+        assert_eq!(deserialized.len(), 2);
+
+        let (payload, raw_payload) = &deserialized[0];
+        assert_eq!(
+            payload.as_ref().unwrap(),
+            &Payload {
+                state: State::Running,
+                value: Value::Int(-7),
+            }
+        );
+        assert_eq!(
+            raw_payload.as_ref().unwrap(),
+            &RawPayload {
+                state: 3,
+                value: BTreeMap::from([("int".to_owned(), RawValue::Int(-7))]),
+            }
+        );
+
+        let (payload, raw_payload) = &deserialized[1];
+        assert_eq!(
+            payload.as_ref().unwrap(),
+            &Payload {
+                state: State::Stopped,
+                value: Value::Text("done".to_owned()),
+            }
+        );
+        assert_eq!(
+            raw_payload.as_ref().unwrap(),
+            &RawPayload {
+                state: 9,
+                value: BTreeMap::from([("text".to_owned(), RawValue::Text("done".to_owned()))]),
+            }
+        );
+    }
+}
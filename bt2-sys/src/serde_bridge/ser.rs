@@ -0,0 +1,598 @@
+use std::ffi::CString;
+
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+use super::{cast_field, EnumerationValue, SerdeError};
+use crate::field::{BtFieldConst, BtFieldType};
+use crate::value::{
+    BtValue, BtValueArray, BtValueBool, BtValueConst, BtValueMap, BtValueNull, BtValueReal,
+    BtValueSignedInteger, BtValueString, BtValueTypedConst, BtValueUnsignedInteger,
+};
+
+/// Serialize `value` into a [`BtValue`].
+///
+/// Structs and maps become maps, sequences and tuples become arrays and `None` and units
+/// become null. Enums are externally tagged, i.e., unit variants become their name and other
+/// variants become a map with a single entry from their name to their content.
+///
+/// # Errors
+/// If `value` contains unsupported types, e.g., 128-bit integers or maps with non-string keys,
+/// or strings containing a null byte.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<BtValue, SerdeError> {
+    value.serialize(ValueSerializer)
+}
+
+/// [`serde::Serializer`] into a [`BtValue`].
+pub struct ValueSerializer;
+
+fn new_string(value: &str) -> Result<BtValue, SerdeError> {
+    Ok(BtValueString::new_cstr(&CString::new(value)?)?.into())
+}
+
+fn new_tagged(variant: &'static str, value: BtValue) -> Result<BtValue, SerdeError> {
+    let mut map = BtValueMap::new()?;
+    map.insert_with_cstr_key(&CString::new(variant)?, &value)?;
+    Ok(map.into())
+}
+
+impl Serializer for ValueSerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+    type SerializeMap = MapSerializer;
+    type SerializeSeq = ArraySerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = TaggedSerializer<MapSerializer>;
+    type SerializeTuple = ArraySerializer;
+    type SerializeTupleStruct = ArraySerializer;
+    type SerializeTupleVariant = TaggedSerializer<ArraySerializer>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(BtValueBool::new(v)?.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(BtValueSignedInteger::new(v)?.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(BtValueUnsignedInteger::new(v)?.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(BtValueReal::new(v)?.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        new_string(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        new_string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            SerializeSeq::serialize_element(&mut seq, byte)?;
+        }
+        SerializeSeq::end(seq)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(BtValueNull::default().into())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        new_string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        new_tagged(variant, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ArraySerializer(BtValueArray::new()?))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(TaggedSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapSerializer {
+            map: BtValueMap::new()?,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(TaggedSerializer {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub struct ArraySerializer(BtValueArray);
+
+impl SerializeSeq for ArraySerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.0.push(&value.serialize(ValueSerializer)?)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.0.into())
+    }
+}
+
+impl SerializeTuple for ArraySerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleStruct for ArraySerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+pub struct MapSerializer {
+    map: BtValueMap,
+    key: Option<CString>,
+}
+
+impl SerializeMap for MapSerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| SerdeError::Custom("Map value serialized before its key".to_owned()))?;
+        self.map
+            .insert_with_cstr_key(&key, &value.serialize(ValueSerializer)?)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.map.into())
+    }
+}
+
+impl SerializeStruct for MapSerializer {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+/// Serializer of the content of a tuple or struct variant, which is wrapped into a map
+/// with the variant name as its only key.
+pub struct TaggedSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl SerializeTupleVariant for TaggedSerializer<ArraySerializer> {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        new_tagged(self.variant, SerializeSeq::end(self.inner)?)
+    }
+}
+
+impl SerializeStructVariant for TaggedSerializer<MapSerializer> {
+    type Error = SerdeError;
+    type Ok = BtValue;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        SerializeMap::serialize_entry(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        new_tagged(self.variant, SerializeMap::end(self.inner)?)
+    }
+}
+
+/// Serializer of map keys, which must be strings.
+struct MapKeySerializer;
+
+impl MapKeySerializer {
+    fn unsupported<T>(self) -> Result<T, SerdeError> {
+        Err(SerdeError::KeyMustBeString)
+    }
+}
+
+impl Serializer for MapKeySerializer {
+    type Error = SerdeError;
+    type Ok = CString;
+    type SerializeMap = ser::Impossible<CString, SerdeError>;
+    type SerializeSeq = ser::Impossible<CString, SerdeError>;
+    type SerializeStruct = ser::Impossible<CString, SerdeError>;
+    type SerializeStructVariant = ser::Impossible<CString, SerdeError>;
+    type SerializeTuple = ser::Impossible<CString, SerdeError>;
+    type SerializeTupleStruct = ser::Impossible<CString, SerdeError>;
+    type SerializeTupleVariant = ser::Impossible<CString, SerdeError>;
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(CString::new(v)?)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.unsupported()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.unsupported()
+    }
+}
+
+/// Fields are serialized as described in the [module documentation](super).
+///
+/// Serializing a field of an unsupported class fails.
+impl Serialize for BtFieldConst {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Safety: The copy does not outlive the borrowed field.
+        let field = unsafe { self.clone_unchecked() };
+        match cast_field(field).map_err(ser::Error::custom)? {
+            BtFieldType::Boolean(field) => serializer.serialize_bool(field.get_value()),
+            BtFieldType::UnsignedInteger(field) => serializer.serialize_u64(field.get_value()),
+            BtFieldType::SignedInteger(field) => serializer.serialize_i64(field.get_value()),
+            BtFieldType::String(field) => {
+                serializer.serialize_str(field.try_get_value().map_err(ser::Error::custom)?)
+            }
+            BtFieldType::Array(field) => {
+                let length = field.get_length();
+                let mut seq = serializer.serialize_seq(usize::try_from(length).ok())?;
+                for index in 0..length {
+                    seq.serialize_element(&field.get_value(index))?;
+                }
+                seq.end()
+            }
+            BtFieldType::Structure(field) => {
+                let class = field.get_class();
+                let count = class.get_member_count();
+                let mut map = serializer.serialize_map(usize::try_from(count).ok())?;
+                for index in 0..count {
+                    map.serialize_entry(
+                        class.get_member_by_index(index).get_name(),
+                        &field.get_field_by_index(index),
+                    )?;
+                }
+                map.end()
+            }
+            BtFieldType::Enumeration(field) => match EnumerationValue::of(&field) {
+                EnumerationValue::Unsigned(value) => serializer.serialize_u64(value),
+                EnumerationValue::Signed(value) => serializer.serialize_i64(value),
+            },
+            BtFieldType::Option(field) => match field.get_field() {
+                Some(field) => serializer.serialize_some(&field),
+                None => serializer.serialize_none(),
+            },
+            BtFieldType::Variant(field) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                match field.get_selected_option_name() {
+                    Some(name) => map.serialize_key(name.to_str().map_err(ser::Error::custom)?)?,
+                    None => map.serialize_key(&field.get_selected_option_index())?,
+                }
+                map.serialize_value(&field.get_selected_field())?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl Serialize for BtValueConst<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Safety: The copy has the same lifetime as the borrowed value.
+        let value = unsafe { BtValueConst::new_unchecked(self.as_ptr()) };
+        match value.cast() {
+            BtValueTypedConst::Null(_) => serializer.serialize_unit(),
+            BtValueTypedConst::Bool(value) => serializer.serialize_bool(value.get()),
+            BtValueTypedConst::UnsignedInteger(value) => serializer.serialize_u64(value.get()),
+            BtValueTypedConst::SignedInteger(value) => serializer.serialize_i64(value.get()),
+            BtValueTypedConst::Real(value) => serializer.serialize_f64(value.get()),
+            BtValueTypedConst::String(value) => {
+                serializer.serialize_str(value.get().map_err(ser::Error::custom)?)
+            }
+            BtValueTypedConst::Array(value) => {
+                let length = value.length();
+                let mut seq = serializer.serialize_seq(usize::try_from(length).ok())?;
+                for index in 0..length {
+                    seq.serialize_element(&value.get(index))?;
+                }
+                seq.end()
+            }
+            BtValueTypedConst::Map(value) => {
+                let entries = value.entries();
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in &entries {
+                    map.serialize_entry(key.to_str().map_err(ser::Error::custom)?, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl Serialize for BtValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_const().serialize(serializer)
+    }
+}
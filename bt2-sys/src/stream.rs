@@ -1,10 +1,31 @@
-use crate::field::BtFieldConst;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+use crate::clock_snapshot::{BtClockClass, BtClockClassConst};
+use crate::error::OutOfMemory;
+use crate::event::{BtEventClass, BtEventClassConst};
+use crate::field::{BtFieldClassConst, BtFieldConst};
 use crate::raw_bindings::{
-    bt_packet, bt_packet_borrow_context_field_const, bt_packet_borrow_stream_const, bt_stream,
-    bt_stream_borrow_class_const, bt_stream_borrow_trace_const, bt_stream_class,
-    bt_stream_class_supports_packets,
+    bt_packet, bt_packet_borrow_context_field_const, bt_packet_borrow_stream_const,
+    bt_packet_create, bt_packet_put_ref, bt_stream, bt_stream_borrow_class_const,
+    bt_stream_borrow_trace_const, bt_stream_class,
+    bt_stream_class_borrow_default_clock_class_const,
+    bt_stream_class_borrow_event_class_by_id_const,
+    bt_stream_class_borrow_event_class_by_index_const,
+    bt_stream_class_borrow_event_common_context_field_class_const,
+    bt_stream_class_borrow_packet_context_field_class_const,
+    bt_stream_class_borrow_trace_class_const, bt_stream_class_create,
+    bt_stream_class_discarded_events_have_default_clock_snapshots,
+    bt_stream_class_discarded_packets_have_default_clock_snapshots,
+    bt_stream_class_get_event_class_count, bt_stream_class_get_id, bt_stream_class_get_name,
+    bt_stream_class_packets_have_beginning_default_clock_snapshot,
+    bt_stream_class_packets_have_end_default_clock_snapshot, bt_stream_class_put_ref,
+    bt_stream_class_set_default_clock_class, bt_stream_class_set_name,
+    bt_stream_class_set_name_status, bt_stream_class_set_supports_packets,
+    bt_stream_class_supports_packets, bt_stream_create, bt_stream_get_id, bt_stream_get_name,
+    bt_stream_put_ref, bt_trace_class,
 };
-use crate::trace::BtTraceConst;
+use crate::trace::{BtTrace, BtTraceClassConst, BtTraceConst};
 use crate::utils::ConstNonNull;
 
 #[repr(transparent)]
@@ -33,6 +54,25 @@ impl BtStreamConst {
     pub fn get_trace(&self) -> BtTraceConst {
         unsafe { BtTraceConst::new_unchecked(bt_stream_borrow_trace_const(self.get_ptr())) }
     }
+
+    /// Get the numeric ID of the stream, unique within its trace.
+    #[must_use]
+    pub fn get_id(&self) -> u64 {
+        unsafe { bt_stream_get_id(self.get_ptr()) }
+    }
+
+    /// Get the name of the stream.
+    ///
+    /// The `ctf` source names the streams after their data files, e.g., `ust/uid/1000/64-bit/ust_0`.
+    #[must_use]
+    pub fn get_name(&self) -> Option<&CStr> {
+        let name = unsafe { bt_stream_get_name(self.get_ptr()) };
+        if name.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(name) })
+    }
 }
 
 #[repr(transparent)]
@@ -48,10 +88,142 @@ impl BtStreamClassConst {
         self.0.as_ptr()
     }
 
+    /// Get the numeric ID of the stream class, unique within its trace class.
+    #[must_use]
+    pub fn get_id(&self) -> u64 {
+        unsafe { bt_stream_class_get_id(self.get_ptr()) }
+    }
+
+    #[must_use]
+    pub fn get_name(&self) -> Option<&CStr> {
+        let name = unsafe { bt_stream_class_get_name(self.get_ptr()) };
+        if name.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(name) })
+    }
+
+    #[must_use]
+    pub fn get_trace_class(&self) -> BtTraceClassConst {
+        unsafe {
+            BtTraceClassConst::new_unchecked(bt_stream_class_borrow_trace_class_const(
+                self.get_ptr(),
+            ))
+        }
+    }
+
+    /// Get the clock class of the default clock of the streams.
+    #[must_use]
+    pub fn get_default_clock_class(&self) -> Option<BtClockClassConst> {
+        unsafe {
+            bt_stream_class_borrow_default_clock_class_const(self.get_ptr())
+                .try_into()
+                .ok()
+                .map(|ptr| BtClockClassConst::new_unchecked(ptr))
+        }
+    }
+
+    /// Get the class of the context field of the packets, e.g., containing `cpu_id`.
+    #[must_use]
+    pub fn get_packet_context_field_class(&self) -> Option<BtFieldClassConst> {
+        let field_class =
+            unsafe { bt_stream_class_borrow_packet_context_field_class_const(self.get_ptr()) };
+        if field_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtFieldClassConst::new_unchecked(field_class) })
+    }
+
+    /// Get the class of the context field shared by all events of the streams.
+    #[must_use]
+    pub fn get_event_common_context_field_class(&self) -> Option<BtFieldClassConst> {
+        let field_class = unsafe {
+            bt_stream_class_borrow_event_common_context_field_class_const(self.get_ptr())
+        };
+        if field_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtFieldClassConst::new_unchecked(field_class) })
+    }
+
+    #[must_use]
+    pub fn get_event_class_count(&self) -> u64 {
+        unsafe { bt_stream_class_get_event_class_count(self.get_ptr()) }
+    }
+
+    /// Get the event class at the specified `index`.
+    ///
+    /// # Panics
+    /// - If the `index` is out of bounds.
+    #[must_use]
+    pub fn get_event_class_by_index(&self, index: u64) -> BtEventClassConst {
+        assert!(index < self.get_event_class_count());
+
+        unsafe {
+            BtEventClassConst::new_unchecked(bt_stream_class_borrow_event_class_by_index_const(
+                self.get_ptr(),
+                index,
+            ))
+        }
+    }
+
+    /// Get the event class with the specified `id`.
+    ///
+    /// Returns `None` if the stream class has no such event class.
+    #[must_use]
+    pub fn get_event_class_by_id(&self, id: u64) -> Option<BtEventClassConst> {
+        let event_class =
+            unsafe { bt_stream_class_borrow_event_class_by_id_const(self.get_ptr(), id) };
+        if event_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtEventClassConst::new_unchecked(event_class) })
+    }
+
+    pub fn event_classes(&self) -> impl Iterator<Item = BtEventClassConst> + '_ {
+        (0..self.get_event_class_count()).map(|index| self.get_event_class_by_index(index))
+    }
+
     #[must_use]
     pub fn supports_packets(&self) -> bool {
         0 != unsafe { bt_stream_class_supports_packets(self.get_ptr()) }
     }
+
+    /// Whether the packet beginning messages of the streams have a default clock snapshot.
+    #[must_use]
+    pub fn packets_have_beginning_default_clock_snapshot(&self) -> bool {
+        0 != unsafe {
+            bt_stream_class_packets_have_beginning_default_clock_snapshot(self.get_ptr())
+        }
+    }
+
+    /// Whether the packet end messages of the streams have a default clock snapshot.
+    #[must_use]
+    pub fn packets_have_end_default_clock_snapshot(&self) -> bool {
+        0 != unsafe { bt_stream_class_packets_have_end_default_clock_snapshot(self.get_ptr()) }
+    }
+
+    /// Whether the discarded events messages of the streams have beginning and end
+    /// default clock snapshots.
+    #[must_use]
+    pub fn discarded_events_have_default_clock_snapshots(&self) -> bool {
+        0 != unsafe {
+            bt_stream_class_discarded_events_have_default_clock_snapshots(self.get_ptr())
+        }
+    }
+
+    /// Whether the discarded packets messages of the streams have beginning and end
+    /// default clock snapshots.
+    #[must_use]
+    pub fn discarded_packets_have_default_clock_snapshots(&self) -> bool {
+        0 != unsafe {
+            bt_stream_class_discarded_packets_have_default_clock_snapshots(self.get_ptr())
+        }
+    }
 }
 
 #[repr(transparent)]
@@ -82,3 +254,169 @@ impl BtPacketConst {
         unsafe { BtStreamConst::new_unchecked(bt_packet_borrow_stream_const(self.get_ptr())) }
     }
 }
+
+/// Owned stream class created by a component implemented in Rust.
+///
+/// Create it with [`BtTraceClass::create_stream_class`](crate::trace::BtTraceClass::create_stream_class).
+pub struct BtStreamClass(NonNull<bt_stream_class>);
+
+impl BtStreamClass {
+    /// # Safety
+    /// The `trace_class` must be a valid pointer.
+    pub(crate) unsafe fn new(trace_class: *mut bt_trace_class) -> Result<Self, OutOfMemory> {
+        NonNull::new(bt_stream_class_create(trace_class))
+            .map(Self)
+            .ok_or(OutOfMemory)
+    }
+
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_stream_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtStreamClassConst {
+        unsafe { BtStreamClassConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_name(&mut self, name: &CStr) -> Result<(), OutOfMemory> {
+        match unsafe { bt_stream_class_set_name(self.as_ptr(), name.as_ptr()) } {
+            bt_stream_class_set_name_status::BT_STREAM_CLASS_SET_NAME_STATUS_OK => Ok(()),
+            bt_stream_class_set_name_status::BT_STREAM_CLASS_SET_NAME_STATUS_MEMORY_ERROR => {
+                Err(OutOfMemory)
+            }
+            status => unreachable!(
+                "Bug: unknown bt_stream_class_set_name_status = {}",
+                status.0
+            ),
+        }
+    }
+
+    /// Set the clock class of the default clock of the streams.
+    ///
+    /// The messages of the streams must then be created with a value of the default clock.
+    pub fn set_default_clock_class(&mut self, clock_class: &BtClockClass) {
+        unsafe { bt_stream_class_set_default_clock_class(self.as_ptr(), clock_class.as_ptr()) }
+    }
+
+    /// Set whether the streams have packets and whether the packet beginning and end messages
+    /// have a value of the default clock.
+    ///
+    /// # Panics
+    /// If the packet messages have clock values but the stream class has no default clock class
+    /// or if they have clock values without supporting packets.
+    pub fn set_supports_packets(
+        &mut self,
+        supports_packets: bool,
+        with_beginning_clock_value: bool,
+        with_end_clock_value: bool,
+    ) {
+        if with_beginning_clock_value || with_end_clock_value {
+            assert!(
+                supports_packets,
+                "Packet clock values require packets support"
+            );
+            assert!(
+                self.as_const().get_default_clock_class().is_some(),
+                "Packet clock values require a default clock class"
+            );
+        }
+
+        unsafe {
+            bt_stream_class_set_supports_packets(
+                self.as_ptr(),
+                supports_packets.into(),
+                with_beginning_clock_value.into(),
+                with_end_clock_value.into(),
+            );
+        }
+    }
+
+    /// Create an event class with the next available ID.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_event_class(&mut self) -> Result<BtEventClass, OutOfMemory> {
+        unsafe { BtEventClass::new(self.as_ptr()) }
+    }
+
+    /// Create a stream of this class in the `trace`, with the next available ID.
+    ///
+    /// The trace must be of the trace class of this stream class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_stream(&self, trace: &mut BtTrace) -> Result<BtStream, OutOfMemory> {
+        NonNull::new(unsafe { bt_stream_create(self.as_ptr(), trace.as_ptr()) })
+            .map(BtStream)
+            .ok_or(OutOfMemory)
+    }
+}
+
+impl Drop for BtStreamClass {
+    fn drop(&mut self) {
+        unsafe { bt_stream_class_put_ref(self.as_ptr()) }
+    }
+}
+
+/// Owned stream created by a component implemented in Rust.
+pub struct BtStream(NonNull<bt_stream>);
+
+impl BtStream {
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_stream {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtStreamConst {
+        unsafe { BtStreamConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// Create a packet of this stream.
+    ///
+    /// # Panics
+    /// If the stream class does not support packets.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_packet(&self) -> Result<BtPacket, OutOfMemory> {
+        assert!(
+            self.as_const().get_class().supports_packets(),
+            "Stream class must support packets!"
+        );
+
+        NonNull::new(unsafe { bt_packet_create(self.as_ptr()) })
+            .map(BtPacket)
+            .ok_or(OutOfMemory)
+    }
+}
+
+impl Drop for BtStream {
+    fn drop(&mut self) {
+        unsafe { bt_stream_put_ref(self.as_ptr()) }
+    }
+}
+
+/// Owned packet created by a component implemented in Rust.
+pub struct BtPacket(NonNull<bt_packet>);
+
+impl BtPacket {
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_packet {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtPacketConst {
+        unsafe { BtPacketConst::new_unchecked(self.as_ptr()) }
+    }
+}
+
+impl Drop for BtPacket {
+    fn drop(&mut self) {
+        unsafe { bt_packet_put_ref(self.as_ptr()) }
+    }
+}
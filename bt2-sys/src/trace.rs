@@ -1,6 +1,18 @@
 use std::ffi::{CStr, CString};
+use std::ptr::NonNull;
 
-use crate::raw_bindings::{bt_trace, bt_trace_borrow_environment_entry_value_by_name_const};
+use crate::error::OutOfMemory;
+use crate::raw_bindings::{
+    bt_self_component, bt_trace, bt_trace_borrow_class_const,
+    bt_trace_borrow_environment_entry_by_index_const,
+    bt_trace_borrow_environment_entry_value_by_name_const, bt_trace_class,
+    bt_trace_class_borrow_stream_class_by_id_const,
+    bt_trace_class_borrow_stream_class_by_index_const, bt_trace_class_create,
+    bt_trace_class_get_stream_class_count, bt_trace_class_put_ref, bt_trace_create,
+    bt_trace_get_environment_entry_count, bt_trace_get_name, bt_trace_put_ref, bt_trace_set_name,
+    bt_trace_set_name_status,
+};
+use crate::stream::{BtStreamClass, BtStreamClassConst};
 use crate::utils::ConstNonNull;
 use crate::value::{
     BtValueConst, BtValueSignedIntegerConst, BtValueStringConst, BtValueTypedConst,
@@ -22,6 +34,17 @@ impl BtTraceConst {
         self.0.as_ptr()
     }
 
+    /// Get the name of the trace.
+    #[must_use]
+    pub fn get_name(&self) -> Option<&CStr> {
+        let name = unsafe { bt_trace_get_name(self.get_ptr()) };
+        if name.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(name) })
+    }
+
     /// Get an environment entry by name.
     ///
     /// Returns `None` if the environment entry does not exist.
@@ -53,14 +76,193 @@ impl BtTraceConst {
             return None;
         }
 
-        let value = unsafe { BtValueConst::new_unchecked(value) };
+        Some(Self::environment_entry(unsafe {
+            BtValueConst::new_unchecked(value)
+        }))
+    }
+
+    #[must_use]
+    pub fn get_environment_entry_count(&self) -> u64 {
+        unsafe { bt_trace_get_environment_entry_count(self.get_ptr()) }
+    }
+
+    /// Get the name and the value of the environment entry at the specified `index`.
+    ///
+    /// # Panics
+    /// - If the `index` is out of bounds.
+    #[must_use]
+    pub fn get_environment_entry_by_index(&self, index: u64) -> (&CStr, BtEnvironmentEntry<'_>) {
+        assert!(index < self.get_environment_entry_count());
+
+        let mut name = std::ptr::null();
+        let mut value = std::ptr::null();
+        unsafe {
+            bt_trace_borrow_environment_entry_by_index_const(
+                self.get_ptr(),
+                index,
+                &mut name,
+                &mut value,
+            );
+            (
+                CStr::from_ptr(name),
+                Self::environment_entry(BtValueConst::new_unchecked(value)),
+            )
+        }
+    }
+
+    /// Iterate over the names and the values of all environment entries.
+    pub fn environment_entries(&self) -> impl Iterator<Item = (&CStr, BtEnvironmentEntry<'_>)> {
+        (0..self.get_environment_entry_count())
+            .map(|index| self.get_environment_entry_by_index(index))
+    }
+
+    #[must_use]
+    pub fn get_class(&self) -> BtTraceClassConst {
+        unsafe { BtTraceClassConst::new_unchecked(bt_trace_borrow_class_const(self.get_ptr())) }
+    }
 
+    fn environment_entry(value: BtValueConst<'_>) -> BtEnvironmentEntry<'_> {
         match value.cast() {
-            BtValueTypedConst::SignedInteger(value) => Some(BtEnvironmentEntry::Int(value)),
-            BtValueTypedConst::String(value) => Some(BtEnvironmentEntry::String(value)),
+            BtValueTypedConst::SignedInteger(value) => BtEnvironmentEntry::Int(value),
+            BtValueTypedConst::String(value) => BtEnvironmentEntry::String(value),
             _ => unreachable!(
                 "Only signed integer and string environment entries are returned by the C API"
             ),
         }
     }
 }
+
+/// Metadata of a trace: the classes of its streams.
+pub struct BtTraceClassConst(ConstNonNull<bt_trace_class>);
+
+impl BtTraceClassConst {
+    pub(crate) unsafe fn new_unchecked(ptr: *const bt_trace_class) -> Self {
+        Self(ConstNonNull::new_unchecked(ptr))
+    }
+
+    pub(crate) const fn get_ptr(&self) -> *const bt_trace_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn get_stream_class_count(&self) -> u64 {
+        unsafe { bt_trace_class_get_stream_class_count(self.get_ptr()) }
+    }
+
+    /// Get the stream class at the specified `index`.
+    ///
+    /// # Panics
+    /// - If the `index` is out of bounds.
+    #[must_use]
+    pub fn get_stream_class_by_index(&self, index: u64) -> BtStreamClassConst {
+        assert!(index < self.get_stream_class_count());
+
+        unsafe {
+            BtStreamClassConst::new_unchecked(bt_trace_class_borrow_stream_class_by_index_const(
+                self.get_ptr(),
+                index,
+            ))
+        }
+    }
+
+    /// Get the stream class with the specified `id`.
+    ///
+    /// Returns `None` if the trace class has no such stream class.
+    #[must_use]
+    pub fn get_stream_class_by_id(&self, id: u64) -> Option<BtStreamClassConst> {
+        let stream_class =
+            unsafe { bt_trace_class_borrow_stream_class_by_id_const(self.get_ptr(), id) };
+        if stream_class.is_null() {
+            return None;
+        }
+
+        Some(unsafe { BtStreamClassConst::new_unchecked(stream_class) })
+    }
+
+    pub fn stream_classes(&self) -> impl Iterator<Item = BtStreamClassConst> + '_ {
+        (0..self.get_stream_class_count()).map(|index| self.get_stream_class_by_index(index))
+    }
+}
+
+/// Owned trace class created by a component implemented in Rust.
+///
+/// Create it with [`SelfComponentSource::create_trace_class`] or the analogous methods
+/// of filter components and message iterators.
+///
+/// [`SelfComponentSource::create_trace_class`]: crate::graph::user_component::SelfComponentSource::create_trace_class
+pub struct BtTraceClass(NonNull<bt_trace_class>);
+
+impl BtTraceClass {
+    /// # Safety
+    /// The `self_component` must be a valid pointer.
+    pub(crate) unsafe fn new(self_component: *mut bt_self_component) -> Result<Self, OutOfMemory> {
+        NonNull::new(bt_trace_class_create(self_component))
+            .map(Self)
+            .ok_or(OutOfMemory)
+    }
+
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_trace_class {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtTraceClassConst {
+        unsafe { BtTraceClassConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// Create a stream class with the next available ID.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_stream_class(&mut self) -> Result<BtStreamClass, OutOfMemory> {
+        unsafe { BtStreamClass::new(self.as_ptr()) }
+    }
+
+    /// Create a trace of this class.
+    ///
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn create_trace(&self) -> Result<BtTrace, OutOfMemory> {
+        NonNull::new(unsafe { bt_trace_create(self.as_ptr()) })
+            .map(BtTrace)
+            .ok_or(OutOfMemory)
+    }
+}
+
+impl Drop for BtTraceClass {
+    fn drop(&mut self) {
+        unsafe { bt_trace_class_put_ref(self.as_ptr()) }
+    }
+}
+
+/// Owned trace created by a component implemented in Rust.
+pub struct BtTrace(NonNull<bt_trace>);
+
+impl BtTrace {
+    #[inline]
+    pub(crate) const fn as_ptr(&self) -> *mut bt_trace {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_const(&self) -> BtTraceConst {
+        unsafe { BtTraceConst::new_unchecked(self.as_ptr()) }
+    }
+
+    /// # Errors
+    /// If the memory allocation fails.
+    pub fn set_name(&mut self, name: &CStr) -> Result<(), OutOfMemory> {
+        match unsafe { bt_trace_set_name(self.as_ptr(), name.as_ptr()) } {
+            bt_trace_set_name_status::BT_TRACE_SET_NAME_STATUS_OK => Ok(()),
+            bt_trace_set_name_status::BT_TRACE_SET_NAME_STATUS_MEMORY_ERROR => Err(OutOfMemory),
+            status => unreachable!("Bug: unknown bt_trace_set_name_status = {}", status.0),
+        }
+    }
+}
+
+impl Drop for BtTrace {
+    fn drop(&mut self) {
+        unsafe { bt_trace_put_ref(self.as_ptr()) }
+    }
+}
@@ -1,5 +1,5 @@
 use core::str;
-use std::ffi::{CStr, CString};
+use std::ffi::{c_char, c_void, CStr, CString};
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 
@@ -11,15 +11,18 @@ use crate::raw_bindings::{
     bt_value, bt_value_array_append_element, bt_value_array_append_element_status,
     bt_value_array_borrow_element_by_index_const, bt_value_array_create, bt_value_array_get_length,
     bt_value_array_is_empty, bt_value_bool_create_init, bt_value_bool_get, bt_value_bool_set,
-    bt_value_get_ref, bt_value_get_type, bt_value_integer_signed_create_init,
-    bt_value_integer_signed_get, bt_value_integer_signed_set,
+    bt_value_copy, bt_value_copy_status, bt_value_get_ref, bt_value_get_type,
+    bt_value_integer_signed_create_init, bt_value_integer_signed_get, bt_value_integer_signed_set,
     bt_value_integer_unsigned_create_init, bt_value_integer_unsigned_get,
     bt_value_integer_unsigned_set, bt_value_map_borrow_entry_value_const, bt_value_map_create,
-    bt_value_map_insert_bool_entry, bt_value_map_insert_entry, bt_value_map_insert_entry_status,
+    bt_value_map_foreach_entry_const, bt_value_map_foreach_entry_const_func_status,
+    bt_value_map_foreach_entry_const_status, bt_value_map_get_size, bt_value_map_insert_bool_entry,
+    bt_value_map_insert_entry, bt_value_map_insert_entry_status,
     bt_value_map_insert_signed_integer_entry, bt_value_map_insert_string_entry,
-    bt_value_map_insert_unsigned_integer_entry, bt_value_null, bt_value_put_ref,
-    bt_value_real_create_init, bt_value_real_get, bt_value_real_set, bt_value_string_create_init,
-    bt_value_string_get, bt_value_string_set, bt_value_string_set_status, bt_value_type,
+    bt_value_map_insert_unsigned_integer_entry, bt_value_map_is_empty, bt_value_null,
+    bt_value_put_ref, bt_value_real_create_init, bt_value_real_get, bt_value_real_set,
+    bt_value_string_create_init, bt_value_string_get, bt_value_string_set,
+    bt_value_string_set_status, bt_value_type,
 };
 use crate::utils::ConstNonNull;
 
@@ -71,7 +74,22 @@ pub enum BtValueTypedConst<'a> {
     Map(BtValueMapConst<'a>),
 }
 
-impl BtValueTypedConst<'_> {
+impl<'a> BtValueTypedConst<'a> {
+    /// Get the untyped value.
+    #[must_use]
+    pub fn into_value(self) -> BtValueConst<'a> {
+        match self {
+            Self::Null(value) => value.0,
+            Self::Bool(value) => value.0,
+            Self::UnsignedInteger(value) => value.0,
+            Self::SignedInteger(value) => value.0,
+            Self::Real(value) => value.0,
+            Self::String(value) => value.0,
+            Self::Array(value) => value.0,
+            Self::Map(value) => value.0,
+        }
+    }
+
     #[must_use]
     pub const fn get_type(&self) -> BtValueType {
         match self {
@@ -237,7 +255,7 @@ impl<'a> BtValueArrayConst<'a> {
     }
 
     #[must_use]
-    pub fn get(&'a self, index: u64) -> BtValueConst<'a> {
+    pub fn get(&self, index: u64) -> BtValueConst<'a> {
         assert!(index < self.length());
         let ptr = unsafe { bt_value_array_borrow_element_by_index_const(self.as_ptr(), index) };
         unsafe { BtValueConst::new_unchecked(ptr) }
@@ -258,10 +276,222 @@ impl<'a> BtValueMapConst<'a> {
         let ptr = unsafe { bt_value_map_borrow_entry_value_const(self.as_ptr(), key.as_ptr()) };
         ConstNonNull::new(ptr).map(|ptr| unsafe { BtValueConst::new(ptr) })
     }
+
+    /// Get the number of entries.
+    #[must_use]
+    pub fn length(&self) -> u64 {
+        unsafe { bt_value_map_get_size(self.as_ptr()) }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        unsafe { 0 != bt_value_map_is_empty(self.as_ptr()) }
+    }
+
+    /// Get all entries of the map.
+    ///
+    /// The order of the entries is unspecified.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(&'a CStr, BtValueConst<'a>)> {
+        unsafe extern "C" fn push_entry(
+            key: *const c_char,
+            value: *const bt_value,
+            user_data: *mut c_void,
+        ) -> bt_value_map_foreach_entry_const_func_status {
+            let entries = unsafe { &mut *user_data.cast::<Vec<(&CStr, BtValueConst)>>() };
+            entries.push(unsafe { (CStr::from_ptr(key), BtValueConst::new_unchecked(value)) });
+            bt_value_map_foreach_entry_const_func_status::BT_VALUE_MAP_FOREACH_ENTRY_CONST_FUNC_STATUS_OK
+        }
+
+        let mut entries = Vec::with_capacity(usize::try_from(self.length()).unwrap_or_default());
+        let status = unsafe {
+            bt_value_map_foreach_entry_const(
+                self.as_ptr(),
+                Some(push_entry),
+                std::ptr::from_mut(&mut entries).cast(),
+            )
+        };
+        match status {
+            bt_value_map_foreach_entry_const_status::BT_VALUE_MAP_FOREACH_ENTRY_CONST_STATUS_OK => {
+                entries
+            }
+            status => unreachable!(
+                "Bug: Unknown bt_value_map_foreach_entry_const_status: {:?}",
+                status.0
+            ),
+        }
+    }
 }
 
 impl_try_from_using_cast!('a => BtValueTypedConst::Map, BtValueConst<'a>, BtValueMapConst<'a>);
 
+/// Error of decoding a [`BtValueConst`] into a Rust value with [`TryFromBtValueConst`].
+#[derive(Debug, Error)]
+pub enum BtValueDecodeError {
+    #[error(transparent)]
+    IncorrectType(#[from] IncorrectTypeError),
+
+    #[error("Integer {0} is out of range of the target type")]
+    OutOfRange(i128),
+
+    #[error("String is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] str::Utf8Error),
+
+    #[error("Missing map entry `{0}`")]
+    MissingEntry(String),
+
+    #[error("Map entry `{key}`: {source}")]
+    Entry {
+        key: String,
+        source: Box<BtValueDecodeError>,
+    },
+
+    #[error("Array element {index}: {source}")]
+    Element {
+        index: u64,
+        source: Box<BtValueDecodeError>,
+    },
+}
+
+/// Conversion of a [`BtValueConst`] into an owned Rust value.
+///
+/// Structs are decoded from maps by decoding their entries with [`BtValueMapConst::decode_entry`].
+pub trait TryFromBtValueConst: Sized {
+    /// Decode the value.
+    ///
+    /// # Errors
+    /// If the value does not have the expected type or layout.
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError>;
+
+    /// Value decoded from a missing map entry.
+    ///
+    /// Returns `None` if the entry is required.
+    #[must_use]
+    fn from_missing_entry() -> Option<Self> {
+        None
+    }
+}
+
+impl<'a> BtValueConst<'a> {
+    /// Decode the value into a Rust value.
+    ///
+    /// # Errors
+    /// If the value does not have the layout expected by `T`.
+    pub fn decode<T: TryFromBtValueConst>(self) -> Result<T, BtValueDecodeError> {
+        T::try_from_bt_value(self)
+    }
+
+    /// # Errors
+    /// If the value is not a map.
+    pub fn try_into_map(self) -> Result<BtValueMapConst<'a>, BtValueDecodeError> {
+        Ok(BtValueMapConst::try_from(self)?)
+    }
+
+    /// # Errors
+    /// If the value is not an array.
+    pub fn try_into_array(self) -> Result<BtValueArrayConst<'a>, BtValueDecodeError> {
+        Ok(BtValueArrayConst::try_from(self)?)
+    }
+}
+
+impl BtValueMapConst<'_> {
+    /// Decode the entry with the `key`.
+    ///
+    /// A missing entry is decoded with [`TryFromBtValueConst::from_missing_entry`],
+    /// e.g., as `None` for `Option<T>`.
+    ///
+    /// # Errors
+    /// If the entry is missing and required, or its value cannot be decoded.
+    pub fn decode_entry<T: TryFromBtValueConst>(
+        &self,
+        key: &CStr,
+    ) -> Result<T, BtValueDecodeError> {
+        let key_string = || key.to_string_lossy().into_owned();
+        match self.get_with_cstr_key(key) {
+            Some(value) => T::try_from_bt_value(value).map_err(|e| BtValueDecodeError::Entry {
+                key: key_string(),
+                source: Box::new(e),
+            }),
+            None => T::from_missing_entry()
+                .ok_or_else(|| BtValueDecodeError::MissingEntry(key_string())),
+        }
+    }
+}
+
+impl TryFromBtValueConst for bool {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        Ok(BtValueBoolConst::try_from(value)?.get())
+    }
+}
+
+impl TryFromBtValueConst for f64 {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        Ok(BtValueRealConst::try_from(value)?.get())
+    }
+}
+
+impl TryFromBtValueConst for String {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        Ok(BtValueStringConst::try_from(value)?.get()?.to_owned())
+    }
+}
+
+macro_rules! impl_try_from_bt_value_for_int {
+    ($($int:ty),+) => {
+        $(
+            /// Both signed and unsigned integer values are accepted if they fit into the type.
+            impl TryFromBtValueConst for $int {
+                fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+                    let value: i128 = match value.cast() {
+                        BtValueTypedConst::SignedInteger(value) => value.get().into(),
+                        BtValueTypedConst::UnsignedInteger(value) => value.get().into(),
+                        got => {
+                            return Err(IncorrectTypeError {
+                                expected: BtValueType::SignedInteger,
+                                got: got.get_type(),
+                            }
+                            .into())
+                        }
+                    };
+                    value
+                        .try_into()
+                        .map_err(|_| BtValueDecodeError::OutOfRange(value))
+                }
+            }
+        )+
+    };
+}
+
+impl_try_from_bt_value_for_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// `None` if the value is null or the map entry is missing.
+impl<T: TryFromBtValueConst> TryFromBtValueConst for Option<T> {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        match value.cast() {
+            BtValueTypedConst::Null(_) => Ok(None),
+            typed => T::try_from_bt_value(typed.into_value()).map(Some),
+        }
+    }
+
+    fn from_missing_entry() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: TryFromBtValueConst> TryFromBtValueConst for Vec<T> {
+    fn try_from_bt_value(value: BtValueConst<'_>) -> Result<Self, BtValueDecodeError> {
+        let array = value.try_into_array()?;
+        (0..array.length())
+            .map(|index| {
+                T::try_from_bt_value(array.get(index)).map_err(|e| BtValueDecodeError::Element {
+                    index,
+                    source: Box::new(e),
+                })
+            })
+            .collect()
+    }
+}
+
 #[repr(transparent)]
 pub struct BtValue(NonNull<bt_value>);
 
@@ -364,6 +594,18 @@ impl BtValue {
     pub fn as_const(&self) -> BtValueConst<'_> {
         unsafe { BtValueConst::new_unchecked(self.as_ptr()) }
     }
+
+    /// Create a deep copy of the value.
+    pub fn try_clone(&self) -> Result<Self, OutOfMemory> {
+        let mut copy = std::ptr::null_mut();
+        match unsafe { bt_value_copy(self.as_ptr(), &mut copy) } {
+            bt_value_copy_status::BT_VALUE_COPY_STATUS_OK => {
+                Ok(unsafe { Self::new_unchecked(copy) })
+            }
+            bt_value_copy_status::BT_VALUE_COPY_STATUS_MEMORY_ERROR => Err(OutOfMemory),
+            status => unreachable!("Bug: Unknown bt_value_copy_status: {:?}", status.0),
+        }
+    }
 }
 
 impl Drop for BtValue {
@@ -581,6 +823,11 @@ impl BtValueArray {
 impl_try_from_using_cast!(BtValueTyped::Array, BtValue, BtValueArray);
 
 impl BtValueMap {
+    /// Create a deep copy of the map.
+    pub fn try_clone(&self) -> Result<Self, OutOfMemory> {
+        self.0.try_clone().map(Self)
+    }
+
     pub fn new() -> Result<Self, OutOfMemory> {
         let ptr = unsafe { bt_value_map_create() };
         NonNull::new(ptr)
@@ -0,0 +1,63 @@
+//! Version of the babeltrace2 library the crate is linked with.
+
+use std::ffi::{c_char, CStr};
+
+use crate::raw_bindings::{
+    bt_version_get_development_stage, bt_version_get_major, bt_version_get_minor,
+    bt_version_get_name, bt_version_get_patch, bt_version_get_vcs_revision_description,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtLibraryVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Development stage, e.g., `-rc1`.
+    pub development_stage: Option<String>,
+    /// Release name, e.g., `Amqui`.
+    pub name: Option<String>,
+    /// Revision of the library sources, e.g., a git commit.
+    pub vcs_revision: Option<String>,
+}
+
+impl BtLibraryVersion {
+    /// Get the version of the loaded library.
+    #[must_use]
+    pub fn get() -> Self {
+        let string = |ptr: *const c_char| {
+            if ptr.is_null() {
+                None
+            } else {
+                Some(
+                    unsafe { CStr::from_ptr(ptr) }
+                        .to_string_lossy()
+                        .into_owned(),
+                )
+            }
+        };
+
+        unsafe {
+            Self {
+                major: bt_version_get_major(),
+                minor: bt_version_get_minor(),
+                patch: bt_version_get_patch(),
+                development_stage: string(bt_version_get_development_stage()),
+                name: string(bt_version_get_name()),
+                vcs_revision: string(bt_version_get_vcs_revision_description()),
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for BtLibraryVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(stage) = &self.development_stage {
+            write!(f, "{stage}")?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, " \"{name}\"")?;
+        }
+        Ok(())
+    }
+}
@@ -2,7 +2,11 @@ use std::ptr;
 
 use crate::error::{BtResult, IntoResult};
 use crate::message::BtMessageArrayConst;
-use crate::raw_bindings::{bt_message_iterator, bt_message_iterator_next};
+use crate::raw_bindings::{
+    bt_bool, bt_message_iterator, bt_message_iterator_can_seek_beginning,
+    bt_message_iterator_can_seek_ns_from_origin, bt_message_iterator_next,
+    bt_message_iterator_seek_beginning, bt_message_iterator_seek_ns_from_origin,
+};
 
 #[repr(transparent)]
 pub struct BtMessageIterator(*mut bt_message_iterator);
@@ -15,6 +19,38 @@ impl BtMessageIterator {
             .into_result()
             .map(|()| unsafe { BtMessageArrayConst::new_unchecked(messages, count) })
     }
+
+    /// Whether the iterator can seek to the first message at or after `ns_from_origin`.
+    pub fn can_seek_ns_from_origin(&mut self, ns_from_origin: i64) -> BtResult<bool> {
+        let mut can_seek: bt_bool = 0;
+        unsafe {
+            bt_message_iterator_can_seek_ns_from_origin(self.0, ns_from_origin, &mut can_seek)
+        }
+        .into_result()
+        .map(|()| can_seek != 0)
+    }
+
+    /// Seek to the first message at or after `ns_from_origin`.
+    ///
+    /// Check with [`Self::can_seek_ns_from_origin()`] first.
+    pub fn seek_ns_from_origin(&mut self, ns_from_origin: i64) -> BtResult<()> {
+        unsafe { bt_message_iterator_seek_ns_from_origin(self.0, ns_from_origin) }.into_result()
+    }
+
+    /// Whether the iterator can seek to its first message.
+    pub fn can_seek_beginning(&mut self) -> BtResult<bool> {
+        let mut can_seek: bt_bool = 0;
+        unsafe { bt_message_iterator_can_seek_beginning(self.0, &mut can_seek) }
+            .into_result()
+            .map(|()| can_seek != 0)
+    }
+
+    /// Seek to the first message.
+    ///
+    /// Check with [`Self::can_seek_beginning()`] first.
+    pub fn seek_beginning(&mut self) -> BtResult<()> {
+        unsafe { bt_message_iterator_seek_beginning(self.0) }.into_result()
+    }
 }
 
 impl From<*mut bt_message_iterator> for BtMessageIterator {
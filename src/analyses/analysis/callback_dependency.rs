@@ -1,19 +1,17 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
 
 use graph::Graph;
 
-use crate::model::{Callback, CallbackType};
+use crate::model::{Callback, CallbackType, Idx, Model};
 use crate::processed_events::{Event, FullEvent, ros2};
 
-use super::{ArcMutWrapper, EventAnalysis, PublicationInCallback};
+use super::{EventAnalysis, PublicationInCallback};
 
 pub mod graph {
-    use crate::model::Callback;
     use crate::model::display::DisplayCallbackSummary;
+    use crate::model::{Callback, Idx, Model};
 
     use crate::visualization::graphviz_export;
-    use std::sync::{Arc, Mutex};
 
     #[derive(Debug, Clone, Default)]
     pub struct Graph {
@@ -51,12 +49,15 @@ pub mod graph {
             &self.sources
         }
 
-        pub fn print_graph(&self) {
+        pub fn print_graph(&self, model: &Model) {
             println!("Graph:");
             println!("  Nodes:");
             for (i, node) in self.nodes().iter().enumerate() {
-                let callback = node.callback().lock().unwrap();
-                println!("    [{i:4}] Callback{}", DisplayCallbackSummary(&callback));
+                let callback = &model[node.callback()];
+                println!(
+                    "    [{i:4}] Callback{}",
+                    DisplayCallbackSummary(model, callback)
+                );
             }
 
             println!("  Edges:");
@@ -67,13 +68,13 @@ pub mod graph {
             println!("Sources: {:?}", self.sources());
         }
 
-        pub fn as_dot(&self) -> graphviz_export::Graph {
+        pub fn as_dot(&self, model: &Model) -> graphviz_export::Graph {
             let mut graph = graphviz_export::Graph::new();
             graph.set_attribute("rankdir", "LR");
 
             for (i, node) in self.nodes().iter().enumerate() {
-                let callback = node.callback().lock().unwrap();
-                let label = DisplayCallbackSummary(&callback).to_string();
+                let callback = &model[node.callback()];
+                let label = DisplayCallbackSummary(model, callback).to_string();
                 let label = label.replace(", ", "\n");
                 let label = &label[1..label.len() - 1];
                 let node = graph.add_node(&format!("Callback\n{label}"), i);
@@ -92,24 +93,24 @@ pub mod graph {
 
     #[derive(Debug, Clone)]
     pub struct Node {
-        callback: Arc<Mutex<Callback>>,
+        callback: Idx<Callback>,
     }
 
     impl Node {
-        pub(super) const fn new(callback: Arc<Mutex<Callback>>) -> Self {
+        pub(super) const fn new(callback: Idx<Callback>) -> Self {
             Self { callback }
         }
 
-        pub const fn callback(&self) -> &Arc<Mutex<Callback>> {
-            &self.callback
+        pub const fn callback(&self) -> Idx<Callback> {
+            self.callback
         }
     }
 }
 
 #[derive(Debug, Default)]
 pub struct CallbackDependency {
-    timer_driven_callbacks: Vec<ArcMutWrapper<Callback>>,
-    message_driven_callbacks: Vec<ArcMutWrapper<Callback>>,
+    timer_driven_callbacks: Vec<Idx<Callback>>,
+    message_driven_callbacks: Vec<Idx<Callback>>,
     publication_in_callback: PublicationInCallback,
     graph: Option<Box<Graph>>,
 }
@@ -119,16 +120,13 @@ impl CallbackDependency {
         Self::default()
     }
 
-    fn add_callback(&mut self, callback: &Arc<Mutex<Callback>>) {
-        let callback_arc = callback.clone();
-        let callback = callback.lock().unwrap();
-
-        match callback.get_type().unwrap() {
+    fn add_callback(&mut self, callback: Idx<Callback>, model: &Model) {
+        match model[callback].get_type().unwrap() {
             CallbackType::Timer => {
-                self.timer_driven_callbacks.push(callback_arc.into());
+                self.timer_driven_callbacks.push(callback);
             }
             CallbackType::Subscription => {
-                self.message_driven_callbacks.push(callback_arc.into());
+                self.message_driven_callbacks.push(callback);
             }
             CallbackType::Service => {
                 // Ignore service callbacks for now
@@ -137,7 +135,7 @@ impl CallbackDependency {
         }
     }
 
-    fn construct_callback_graph(&mut self) {
+    fn construct_callback_graph(&mut self, model: &Model) {
         let mut nodes = Vec::new();
         let mut edges = Vec::new();
 
@@ -146,39 +144,34 @@ impl CallbackDependency {
 
         let mut sources = Vec::new();
 
-        for callback in &self.timer_driven_callbacks {
-            let node = graph::Node::new(callback.0.clone());
+        for &callback in &self.timer_driven_callbacks {
+            let node = graph::Node::new(callback);
 
             let id = nodes.len();
             nodes.push(node);
             sources.push(id);
-            callback_to_node.insert(callback.clone(), id);
+            callback_to_node.insert(callback, id);
         }
 
-        for callback in &self.message_driven_callbacks {
-            let node = graph::Node::new(callback.0.clone());
+        for &callback in &self.message_driven_callbacks {
+            let node = graph::Node::new(callback);
 
             let id = nodes.len();
             nodes.push(node);
-            callback_to_node.insert(callback.clone(), id);
-
-            let callback = callback.0.lock().unwrap();
-            let subscriber = callback.get_caller().unwrap().unwrap_subscription_ref();
+            callback_to_node.insert(callback, id);
 
-            let subscriber = subscriber.get_arc().expect("Subscriber should be alive");
-            let subscriber = subscriber.lock().unwrap();
-            let topic = subscriber.get_topic().unwrap().to_owned();
+            let subscriber = model[callback].get_caller().unwrap().unwrap_subscription();
+            let topic = model[subscriber].get_topic().unwrap().to_owned();
 
             topic_to_nodes.entry(topic).or_default().push(id);
         }
 
-        for (publisher, callback) in self.publication_in_callback.get_dependency() {
-            let publisher = publisher.0.lock().unwrap();
-            let topic = publisher.get_topic().unwrap().to_owned();
+        for &(publisher, callback) in self.publication_in_callback.get_dependency() {
+            let topic = model[publisher].get_topic().unwrap().to_owned();
 
             if let Some(nodes) = topic_to_nodes.get(&topic) {
                 for &node in nodes {
-                    edges.push((callback_to_node[callback], node));
+                    edges.push((callback_to_node[&callback], node));
                 }
             }
         }
@@ -203,14 +196,23 @@ impl EventAnalysis for CallbackDependency {
         *self = Self::default();
     }
 
-    fn process_event(&mut self, full_event: &FullEvent) {
-        self.publication_in_callback.process_event(full_event);
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        self.publication_in_callback
+            .process_event(full_event, model);
         if let Event::Ros2(ros2::Event::RclcppCallbackRegister(event)) = &full_event.event {
-            self.add_callback(&event.callback);
+            self.add_callback(event.callback, model);
         }
     }
 
-    fn finalize(&mut self) {
-        self.construct_callback_graph();
+    fn finalize(&mut self, model: &Model) {
+        self.construct_callback_graph(model);
+    }
+
+    fn print_rolling_stats(&mut self, _model: &Model) {
+        println!(
+            "Callback dependency: {} timer driven and {} message driven callbacks",
+            self.timer_driven_callbacks.len(),
+            self.message_driven_callbacks.len()
+        );
     }
 }
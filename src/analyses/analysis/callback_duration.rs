@@ -1,17 +1,17 @@
 use serde::{Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
 use std::vec::Vec;
 
+use crate::analysis::utils::{DisplayDurationStats, RollingReport};
 use crate::argsv2::Args;
 use crate::events_common::Context;
-use crate::model::display::{DisplayCallbackSummary, get_node_name_from_weak};
-use crate::model::{Callback, CallbackInstance, Time};
+use crate::model::display::{self, DisplayCallbackSummary};
+use crate::model::{Callback, CallbackCaller, CallbackInstance, Idx, Model, Time};
 use crate::processed_events::{Event, FullEvent, ros2};
 use crate::statistics::{Quantile, Sorted};
 use crate::utils::{DurationDisplayImprecise, WeakKnown};
 
-use super::{AnalysisOutput, ArcMutWrapper, EventAnalysis};
+use super::{AnalysisOutput, EventAnalysis};
 
 #[derive(Debug, Serialize)]
 pub struct ExecutionData {
@@ -31,11 +31,14 @@ where
 
 #[derive(Debug, Default)]
 pub struct CallbackDuration {
-    execution_data: HashMap<ArcMutWrapper<Callback>, Vec<ExecutionData>>,
-    // durations: HashMap<ArcMutWrapper<Callback>, Vec<i64>>,
-    // execution_start_time: HashMap<ArcMutWrapper<Callback>, Vec<Time>>,
-    started_callbacks: HashSet<ArcMutWrapper<CallbackInstance>>,
-    not_ended_callbacks: Vec<ArcMutWrapper<CallbackInstance>>,
+    execution_data: HashMap<Idx<Callback>, Vec<ExecutionData>>,
+    // durations: HashMap<Idx<Callback>, Vec<i64>>,
+    // execution_start_time: HashMap<Idx<Callback>, Vec<Time>>,
+    started_callbacks: HashSet<Idx<CallbackInstance>>,
+    not_ended_callbacks: Vec<Idx<CallbackInstance>>,
+
+    /// Number of executions already printed by the rolling statistics.
+    reported: RollingReport<Idx<Callback>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,6 +56,24 @@ pub struct Record {
     inter_arrival_times: Vec<i64>,
 }
 
+/// Main parameter of the callback caller as a typed value.
+#[derive(Debug, Clone)]
+pub enum CallerParam {
+    Subscription { topic: WeakKnown<String> },
+    Service { name: WeakKnown<String> },
+    Timer { period: WeakKnown<i64> },
+    Unknown,
+}
+
+/// Durations and start times of all executions of a single callback.
+#[derive(Debug, Clone)]
+pub struct CallbackExecutions {
+    pub(crate) node: WeakKnown<String>,
+    pub(crate) caller: CallerParam,
+    pub(crate) start_times: Vec<i64>,
+    pub(crate) durations: Vec<i64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RecordSummary {
     pub(crate) call_count: usize,
@@ -67,6 +88,7 @@ impl CallbackDuration {
             // execution_start_time: HashMap::new(),
             started_callbacks: HashSet::new(),
             not_ended_callbacks: Vec::new(),
+            reported: RollingReport::default(),
         }
     }
 
@@ -80,15 +102,14 @@ impl CallbackDuration {
         Some(duration)
     }
 
-    fn start_callback(&mut self, callback: Arc<Mutex<CallbackInstance>>) {
-        self.started_callbacks.insert(callback.into());
+    fn start_callback(&mut self, callback: Idx<CallbackInstance>) {
+        self.started_callbacks.insert(callback);
     }
 
-    fn end_callback(&mut self, callback: Arc<Mutex<CallbackInstance>>, context: &Context) {
-        let callback = callback.into();
+    fn end_callback(&mut self, callback: Idx<CallbackInstance>, context: &Context, model: &Model) {
         if self.started_callbacks.remove(&callback) {
-            let callback_instance = callback.0.lock().unwrap();
-            let duration = Self::calculate_duration(&callback_instance)
+            let callback_instance = &model[callback];
+            let duration = Self::calculate_duration(callback_instance)
                 .expect("Duration should be known in callback_end");
 
             // self.durations
@@ -101,7 +122,7 @@ impl CallbackDuration {
             //     .push(callback_instance.get_start_time());
 
             self.execution_data
-                .entry(callback_instance.get_callback().into())
+                .entry(callback_instance.get_callback())
                 .or_default()
                 .push(ExecutionData {
                     start_time: callback_instance.get_start_time(),
@@ -114,31 +135,21 @@ impl CallbackDuration {
         }
     }
 
-    fn end_remaining_callbacks(&mut self) {
+    fn end_remaining_callbacks(&mut self, model: &Model) {
         assert!(self.not_ended_callbacks.is_empty());
         self.not_ended_callbacks = self
             .started_callbacks
             .drain()
-            .map(|callback| {
-                {
-                    let callback_instance = callback.0.lock().unwrap();
-                    if let Some(duration) = Self::calculate_duration(&callback_instance) {
-                        unreachable!(
-                            "Callback {callback:?} was not ended but has duration {duration}"
-                        );
-                    };
+            .inspect(|&callback| {
+                if let Some(duration) = Self::calculate_duration(&model[callback]) {
+                    unreachable!("Callback {callback:?} was not ended but has duration {duration}");
                 }
-
-                callback
             })
             .collect();
     }
 
-    fn calculate_duration_summary(
-        &self,
-        callback: &ArcMutWrapper<Callback>,
-    ) -> Option<RecordSummary> {
-        let exec_data = self.execution_data.get(callback)?;
+    fn calculate_duration_summary(&self, callback: Idx<Callback>) -> Option<RecordSummary> {
+        let exec_data = self.execution_data.get(&callback)?;
         debug_assert!(
             !exec_data.is_empty(),
             "Callback should have at least one execution data. Otherwise, it should not be present in the map."
@@ -164,17 +175,14 @@ impl CallbackDuration {
         })
     }
 
-    pub fn get_records(&self) -> Vec<Record> {
+    pub fn get_records(&self, model: &Model) -> Vec<Record> {
         self.execution_data
             .iter()
-            .map(|(callback_arc, data)| {
-                let callback = callback_arc.0.lock().unwrap();
-                let node_name = callback.get_node().map_or(WeakKnown::Unknown, |node_weak| {
-                    get_node_name_from_weak(&node_weak.get_weak())
-                });
+            .map(|(&callback, data)| {
+                let callback = &model[callback];
+                let node_name = get_node_name(model, callback);
                 let callback_type = callback.get_type();
-                let callback_caller = callback.get_caller().unwrap().get_caller_as_string();
-                drop(callback);
+                let callback_caller = callback.get_caller().unwrap().get_caller_as_string(model);
 
                 Record {
                     node: node_name.to_string(),
@@ -188,15 +196,49 @@ impl CallbackDuration {
             .collect()
     }
 
-    pub(crate) fn print_stats(&self) {
+    pub fn get_callback_executions(&self, model: &Model) -> Vec<CallbackExecutions> {
+        self.execution_data
+            .iter()
+            .map(|(&callback, data)| {
+                let callback = &model[callback];
+                let node = get_node_name(model, callback);
+                let caller = match callback.get_caller() {
+                    Some(&CallbackCaller::Subscription(sub)) => CallerParam::Subscription {
+                        topic: model[sub].get_topic().map(ToString::to_string).into(),
+                    },
+                    Some(&CallbackCaller::Service(service)) => CallerParam::Service {
+                        name: model[service].get_name().map(ToString::to_string).into(),
+                    },
+                    Some(&CallbackCaller::Timer(timer)) => CallerParam::Timer {
+                        period: model[timer].get_period().into(),
+                    },
+                    None => CallerParam::Unknown,
+                };
+
+                CallbackExecutions {
+                    node,
+                    caller,
+                    start_times: data
+                        .iter()
+                        .map(|d| d.start_time.timestamp_nanos())
+                        .collect(),
+                    durations: data.iter().map(|d| d.duration).collect(),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn print_stats(&self, model: &Model) {
         println!("Callback duration statistics:");
-        for (i, callback_arc) in self.execution_data.keys().enumerate() {
-            let callback = callback_arc.0.lock().unwrap();
+        for (i, &callback) in self.execution_data.keys().enumerate() {
             let summary = self
-                .calculate_duration_summary(callback_arc)
+                .calculate_duration_summary(callback)
                 .expect("Callback key should exist.");
 
-            println!("- [{i:4}] Callback {}:", DisplayCallbackSummary(&callback));
+            println!(
+                "- [{i:4}] Callback {}:",
+                DisplayCallbackSummary(model, &model[callback])
+            );
             println!("    Call count: {}", summary.call_count);
             if summary.call_count > 0 {
                 println!("    Duration quantiles:");
@@ -210,19 +252,16 @@ impl CallbackDuration {
         }
     }
 
-    pub fn get_durations_for_callback(
-        &self,
-        callback: &ArcMutWrapper<Callback>,
-    ) -> Option<Vec<i64>> {
+    pub fn get_durations_for_callback(&self, callback: Idx<Callback>) -> Option<Vec<i64>> {
         self.execution_data
-            .get(callback)
+            .get(&callback)
             .map(|data| data.iter().map(|data| data.duration).collect::<Vec<_>>())
     }
 
-    pub fn get_all_durations(&self) -> HashMap<ArcMutWrapper<Callback>, Vec<i64>> {
+    pub fn get_all_durations(&self) -> HashMap<Idx<Callback>, Vec<i64>> {
         self.execution_data
             .iter()
-            .map(|(k, v)| (k.clone(), v.iter().map(|data| data.duration).collect()))
+            .map(|(&k, v)| (k, v.iter().map(|data| data.duration).collect()))
             .collect()
     }
 
@@ -243,12 +282,12 @@ impl CallbackDuration {
         Some(inter_callback_time)
     }
 
-    pub fn get_inter_arrival_time(&self, callback: &ArcMutWrapper<Callback>) -> Option<Vec<i64>> {
-        let start_times = self.execution_data.get(callback)?;
+    pub fn get_inter_arrival_time(&self, callback: Idx<Callback>) -> Option<Vec<i64>> {
+        let start_times = self.execution_data.get(&callback)?;
         Self::get_inter_arrival_time_inner(start_times)
     }
 
-    pub fn get_execution_data(&self) -> &HashMap<ArcMutWrapper<Callback>, Vec<ExecutionData>> {
+    pub fn get_execution_data(&self) -> &HashMap<Idx<Callback>, Vec<ExecutionData>> {
         &self.execution_data
     }
 }
@@ -258,31 +297,63 @@ impl EventAnalysis for CallbackDuration {
         self.execution_data.clear();
         self.started_callbacks.clear();
         self.not_ended_callbacks.clear();
+        self.reported.clear();
     }
 
-    fn process_event(&mut self, full_event: &FullEvent) {
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
         match &full_event.event {
             Event::Ros2(ros2::Event::CallbackStart(event)) => {
-                self.start_callback(event.callback.clone());
+                self.start_callback(event.callback);
             }
             Event::Ros2(ros2::Event::CallbackEnd(event)) => {
-                self.end_callback(event.callback.clone(), &full_event.context);
+                self.end_callback(event.callback, &full_event.context, model);
             }
 
             _ => {}
         }
     }
 
-    fn finalize(&mut self) {
+    fn finalize(&mut self, model: &Model) {
         // Make sure all started callbacks are ended. The remaining callbacks are
         // missing the CallbackEnd event.
-        self.end_remaining_callbacks();
+        self.end_remaining_callbacks(model);
+    }
+
+    fn print_rolling_stats(&mut self, model: &Model) {
+        println!("Callback duration since the last report:");
+        for (callback, data) in &self.execution_data {
+            let durations: Vec<i64> = self
+                .reported
+                .new_values(callback, data)
+                .iter()
+                .map(|data| data.duration)
+                .collect();
+            if durations.is_empty() {
+                continue;
+            }
+
+            println!(
+                "  {}: {}",
+                DisplayCallbackSummary(model, &model[*callback]),
+                DisplayDurationStats::with_comma(&durations)
+            );
+        }
     }
 }
 
 impl AnalysisOutput for CallbackDuration {
-    fn write_json(&self, file: &mut std::io::BufWriter<std::fs::File>) -> serde_json::Result<()> {
-        let records = self.get_records();
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
+        let records = self.get_records(model);
         serde_json::to_writer(file, &records)
     }
 }
+
+fn get_node_name(model: &Model, callback: &Callback) -> WeakKnown<String> {
+    callback.get_node(model).map_or(WeakKnown::Unknown, |node| {
+        display::get_node_name(model, node)
+    })
+}
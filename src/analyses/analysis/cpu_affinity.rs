@@ -0,0 +1,907 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::analysis::utils::{RollingReport, callback_name};
+use crate::events_common::Context;
+use crate::extract::RosInterfaceCompleteName;
+use crate::model::display::{DisplayCallbackSummary, get_node_name};
+use crate::model::{Callback, CallbackInstance, Idx, Model, Time};
+use crate::processed_events::{Event, FullEvent, ros2};
+use crate::utils::WeakKnown;
+
+use super::dependency_graph::Node;
+use super::{AnalysisOutput, EventAnalysis};
+
+#[derive(Debug, Error)]
+pub enum AffinitySpecError {
+    #[error("Failed to read affinity spec {path:?}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse affinity spec {path:?}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("Rule #{rule} must specify exactly one of `node` or `process`")]
+    InvalidTarget { rule: usize },
+
+    #[error("Rule #{rule} has invalid CPU list {cpus:?}. Expected a list like \"0,2-3\"")]
+    InvalidCpuList { rule: usize, cpus: String },
+
+    #[error("Rule #{rule} has an empty CPU list")]
+    EmptyCpuList { rule: usize },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AffinityRuleRaw {
+    node: Option<String>,
+    process: Option<String>,
+    cpus: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AffinitySpecRaw {
+    #[serde(default)]
+    rule: Vec<AffinityRuleRaw>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AffinityTarget {
+    /// Fully qualified node name, e.g. `/namespace/node`
+    Node(String),
+
+    /// Process name as reported by the kernel (`procname`)
+    Process(String),
+}
+
+impl std::fmt::Display for AffinityTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Node(name) => write!(f, "node {name}"),
+            Self::Process(name) => write!(f, "process {name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffinityRule {
+    target: AffinityTarget,
+    cpus: BTreeSet<u32>,
+}
+
+/// Expected CPU affinity of nodes and processes.
+///
+/// The spec is a TOML file with a list of rules:
+/// ```toml
+/// [[rule]]
+/// node = "/control/controller"
+/// cpus = "2-3"
+///
+/// [[rule]]
+/// process = "camera_driver"
+/// cpus = "0,1,4"
+/// ```
+///
+/// Process rules are checked against every event emitted by the process.
+/// Node rules are checked against the callback start and end events of the node callbacks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AffinitySpec {
+    rules: Vec<AffinityRule>,
+}
+
+impl AffinitySpec {
+    pub fn from_file(path: &Path) -> Result<Self, AffinitySpecError> {
+        let content = std::fs::read_to_string(path).map_err(|source| AffinitySpecError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        let raw: AffinitySpecRaw =
+            toml::from_str(&content).map_err(|source| AffinitySpecError::Parse {
+                path: path.to_owned(),
+                source,
+            })?;
+
+        Self::from_raw(raw)
+    }
+
+    fn from_raw(raw: AffinitySpecRaw) -> Result<Self, AffinitySpecError> {
+        let rules = raw
+            .rule
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let target = match (rule.node, rule.process) {
+                    (Some(node), None) => AffinityTarget::Node(node),
+                    (None, Some(process)) => AffinityTarget::Process(process),
+                    _ => return Err(AffinitySpecError::InvalidTarget { rule: i }),
+                };
+                let cpus = parse_cpu_list(&rule.cpus).ok_or_else(|| {
+                    AffinitySpecError::InvalidCpuList {
+                        rule: i,
+                        cpus: rule.cpus.clone(),
+                    }
+                })?;
+                if cpus.is_empty() {
+                    return Err(AffinitySpecError::EmptyCpuList { rule: i });
+                }
+
+                Ok(AffinityRule { target, cpus })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    fn process_rules<'a>(
+        &'a self,
+        procname: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a AffinityRule)> + 'a {
+        self.rules.iter().enumerate().filter(move |(_, rule)| {
+            matches!(&rule.target, AffinityTarget::Process(name) if name == procname)
+        })
+    }
+
+    fn node_rules<'a>(
+        &'a self,
+        node: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a AffinityRule)> + 'a {
+        self.rules.iter().enumerate().filter(
+            move |(_, rule)| matches!(&rule.target, AffinityTarget::Node(name) if name == node),
+        )
+    }
+
+    fn has_node_rules(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(rule.target, AffinityTarget::Node(_)))
+    }
+}
+
+/// Parse a CPU list in the format used by `taskset -c` or `isolcpus`, e.g. `0,2-3`.
+fn parse_cpu_list(list: &str) -> Option<BTreeSet<u32>> {
+    let mut cpus = BTreeSet::new();
+    for part in list
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        if let Some((first, last)) = part.split_once('-') {
+            let first: u32 = first.trim().parse().ok()?;
+            let last: u32 = last.trim().parse().ok()?;
+            if first > last {
+                return None;
+            }
+            cpus.extend(first..=last);
+        } else {
+            cpus.insert(part.parse().ok()?);
+        }
+    }
+
+    Some(cpus)
+}
+
+fn format_cpu_set(cpus: &BTreeSet<u32>) -> String {
+    cpus.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct CpuUsage {
+    /// Number of callback instances started on the CPU
+    pub instances: usize,
+
+    /// Total duration of the callback instances started on the CPU
+    pub busy_time: i64,
+}
+
+#[derive(Debug, Default)]
+struct ExecutorStats {
+    cpus: BTreeMap<u32, CpuUsage>,
+    last_cpu: Option<u32>,
+
+    /// Number of times a callback instance started on a different CPU than the previous one
+    migrations: usize,
+
+    /// Number of callback instances which ended on a different CPU than they started
+    migrations_during_execution: usize,
+}
+
+impl ExecutorStats {
+    fn record(&mut self, start_cpu: u32, end_cpu: u32, duration: i64) {
+        let usage = self.cpus.entry(start_cpu).or_default();
+        usage.instances += 1;
+        usage.busy_time += duration;
+
+        if self.last_cpu.is_some_and(|last_cpu| last_cpu != start_cpu) {
+            self.migrations += 1;
+        }
+        if start_cpu != end_cpu {
+            self.migrations_during_execution += 1;
+        }
+        self.last_cpu = Some(end_cpu);
+    }
+}
+
+#[derive(Debug)]
+struct ThreadStats {
+    procname: String,
+    pid: u32,
+    stats: ExecutorStats,
+}
+
+#[derive(Debug, Default)]
+struct HostStats {
+    first_event: Option<Time>,
+    last_event: Option<Time>,
+
+    /// Duration of the callback instances per CPU they started on
+    cpu_callback_time: BTreeMap<u32, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct ViolationKey {
+    rule: usize,
+    hostname: String,
+    pid: u32,
+    tid: u32,
+    cpu: u32,
+}
+
+#[derive(Debug, Default)]
+struct Violation {
+    events: usize,
+    callbacks: Vec<Idx<Callback>>,
+}
+
+/// Analyze on which CPUs the callbacks and threads were running and how often they migrated.
+///
+/// Busy time of a callback instance is attributed to the CPU it started on, the migrations
+/// during its execution are not taken into account. The callback time of a CPU is the sum
+/// of these busy times, it does not include the time the CPU spent outside of callbacks.
+///
+/// If an [`AffinitySpec`] is provided, the CPUs are validated against it
+/// and the violations are reported.
+#[derive(Debug)]
+pub struct CpuAffinity {
+    spec: AffinitySpec,
+
+    started_callbacks: HashMap<Idx<CallbackInstance>, u32>,
+    callbacks: HashMap<Idx<Callback>, ExecutorStats>,
+    threads: BTreeMap<(String, u32), ThreadStats>,
+    hosts: BTreeMap<String, HostStats>,
+
+    violations: BTreeMap<ViolationKey, Violation>,
+
+    /// Numbers of migrations and violations already printed by the rolling statistics.
+    reported_migrations: RollingReport<(String, u32)>,
+    reported_violations: RollingReport<ViolationKey>,
+}
+
+/// CPUs and migrations of a callback or thread stored in the binary bundle.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AffinityStats {
+    pub hostname: String,
+    pub migrations: usize,
+    pub migrations_during_execution: usize,
+    pub cpus: BTreeMap<u32, CpuUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CallbackAffinityExport {
+    pub id: usize,
+    pub name: RosInterfaceCompleteName,
+    pub stats: AffinityStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThreadAffinityExport {
+    pub id: usize,
+    pub procname: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub stats: AffinityStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AffinityViolationExport {
+    pub id: usize,
+    /// Node or process of the violated rule
+    pub target: String,
+    pub allowed_cpus: String,
+    pub hostname: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub cpu: u32,
+    pub events: usize,
+    pub callbacks: Vec<RosInterfaceCompleteName>,
+}
+
+impl AffinityStats {
+    fn new(hostname: &str, stats: &ExecutorStats) -> Self {
+        Self {
+            hostname: hostname.to_owned(),
+            migrations: stats.migrations,
+            migrations_during_execution: stats.migrations_during_execution,
+            cpus: stats.cpus.clone(),
+        }
+    }
+}
+
+impl CpuAffinity {
+    pub fn new(spec: AffinitySpec) -> Self {
+        Self {
+            spec,
+            started_callbacks: HashMap::new(),
+            callbacks: HashMap::new(),
+            threads: BTreeMap::new(),
+            hosts: BTreeMap::new(),
+            violations: BTreeMap::new(),
+            reported_migrations: RollingReport::default(),
+            reported_violations: RollingReport::default(),
+        }
+    }
+
+    fn add_violation(&mut self, rule: usize, context: &Context, callback: Option<Idx<Callback>>) {
+        let violation = self
+            .violations
+            .entry(ViolationKey {
+                rule,
+                hostname: context.hostname().to_owned(),
+                pid: context.vpid(),
+                tid: context.vtid(),
+                cpu: context.cpu_id(),
+            })
+            .or_default();
+        violation.events += 1;
+        if let Some(callback) = callback
+            && !violation.callbacks.contains(&callback)
+        {
+            violation.callbacks.push(callback);
+        }
+    }
+
+    fn check_process_rules(&mut self, context: &Context) {
+        let violated: Vec<usize> = self
+            .spec
+            .process_rules(context.procname())
+            .filter(|(_, rule)| !rule.cpus.contains(&context.cpu_id()))
+            .map(|(i, _)| i)
+            .collect();
+
+        for rule in violated {
+            self.add_violation(rule, context, None);
+        }
+    }
+
+    fn check_node_rules(&mut self, callback: Idx<Callback>, context: &Context, model: &Model) {
+        if !self.spec.has_node_rules() {
+            return;
+        }
+
+        let node_name = model[callback]
+            .get_node(model)
+            .map_or(WeakKnown::Unknown, |node| get_node_name(model, node));
+        let WeakKnown::Known(node_name) = node_name else {
+            return;
+        };
+
+        let violated: Vec<usize> = self
+            .spec
+            .node_rules(&node_name)
+            .filter(|(_, rule)| !rule.cpus.contains(&context.cpu_id()))
+            .map(|(i, _)| i)
+            .collect();
+
+        for rule in violated {
+            self.add_violation(rule, context, Some(callback));
+        }
+    }
+
+    fn start_callback(
+        &mut self,
+        instance: Idx<CallbackInstance>,
+        context: &Context,
+        model: &Model,
+    ) {
+        let callback = model[instance].get_callback();
+        self.check_node_rules(callback, context, model);
+
+        self.started_callbacks.insert(instance, context.cpu_id());
+    }
+
+    fn end_callback(&mut self, instance: Idx<CallbackInstance>, context: &Context, model: &Model) {
+        let callback = model[instance].get_callback();
+        let start_time = model[instance].get_start_time();
+        let end_time = model[instance].get_end_time();
+
+        self.check_node_rules(callback, context, model);
+
+        let Some(start_cpu) = self.started_callbacks.remove(&instance) else {
+            // Start of the callback is not part of the trace.
+            return;
+        };
+        let end_cpu = context.cpu_id();
+        let duration = end_time
+            .expect("Callback instance should be ended in callback_end")
+            .timestamp_nanos()
+            - start_time.timestamp_nanos();
+
+        self.callbacks
+            .entry(callback)
+            .or_default()
+            .record(start_cpu, end_cpu, duration);
+
+        self.threads
+            .entry((context.hostname().to_owned(), context.vtid()))
+            .or_insert_with(|| ThreadStats {
+                procname: context.procname().to_owned(),
+                pid: context.vpid(),
+                stats: ExecutorStats::default(),
+            })
+            .stats
+            .record(start_cpu, end_cpu, duration);
+
+        *self
+            .hosts
+            .entry(context.hostname().to_owned())
+            .or_default()
+            .cpu_callback_time
+            .entry(start_cpu)
+            .or_default() += duration;
+    }
+
+    fn update_host_time(&mut self, context: &Context, time: Time) {
+        if !self.hosts.contains_key(context.hostname()) {
+            self.hosts
+                .insert(context.hostname().to_owned(), HostStats::default());
+        }
+        let host = self.hosts.get_mut(context.hostname()).unwrap();
+
+        host.first_event.get_or_insert(time);
+        host.last_event = Some(time);
+    }
+
+    /// CPUs of the callbacks for the binary bundle.
+    ///
+    /// When the dependency graph is exported too, the callbacks have the ids of its nodes.
+    /// Otherwise, the ids are assigned in the order of the callback names.
+    pub fn callback_affinities(
+        &self,
+        model: &Model,
+        node_ids: Option<&HashMap<Node, usize>>,
+    ) -> Vec<CallbackAffinityExport> {
+        let mut callbacks: Vec<_> = self
+            .callbacks
+            .iter()
+            .map(|(&callback, stats)| {
+                (
+                    node_ids.and_then(|ids| ids.get(&Node::Callback(callback)).copied()),
+                    callback_name(model, callback),
+                    AffinityStats::new(model[callback].get_hostname(), stats),
+                )
+            })
+            .collect();
+        if node_ids.is_none() {
+            callbacks.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+        }
+
+        callbacks
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (id, name, stats))| {
+                Some(CallbackAffinityExport {
+                    id: if node_ids.is_some() { id? } else { index },
+                    name,
+                    stats,
+                })
+            })
+            .collect()
+    }
+
+    /// CPUs of the threads running callbacks for the binary bundle.
+    pub fn thread_affinities(&self) -> Vec<ThreadAffinityExport> {
+        self.threads
+            .iter()
+            .enumerate()
+            .map(|(id, ((hostname, tid), thread))| ThreadAffinityExport {
+                id,
+                procname: thread.procname.clone(),
+                pid: thread.pid,
+                tid: *tid,
+                stats: AffinityStats::new(hostname, &thread.stats),
+            })
+            .collect()
+    }
+
+    /// Violations of the affinity spec for the binary bundle.
+    pub fn affinity_violations(&self, model: &Model) -> Vec<AffinityViolationExport> {
+        self.violations
+            .iter()
+            .enumerate()
+            .map(|(id, (key, violation))| {
+                let rule = &self.spec.rules[key.rule];
+                AffinityViolationExport {
+                    id,
+                    target: rule.target.to_string(),
+                    allowed_cpus: format_cpu_set(&rule.cpus),
+                    hostname: key.hostname.clone(),
+                    pid: key.pid,
+                    tid: key.tid,
+                    cpu: key.cpu,
+                    events: violation.events,
+                    callbacks: violation
+                        .callbacks
+                        .iter()
+                        .map(|&callback| callback_name(model, callback))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn log_violations(&self) {
+        for (key, violation) in &self.violations {
+            let rule = &self.spec.rules[key.rule];
+            log::warn!(target: "cpu_affinity",
+                "Affinity violation of {} [allowed CPUs: {}]: thread {} (pid {}) on {} ran on CPU {} ({} events)",
+                rule.target,
+                format_cpu_set(&rule.cpus),
+                key.tid,
+                key.pid,
+                key.hostname,
+                key.cpu,
+                violation.events,
+            );
+        }
+    }
+}
+
+impl EventAnalysis for CpuAffinity {
+    fn initialize(&mut self) {
+        self.started_callbacks.clear();
+        self.callbacks.clear();
+        self.threads.clear();
+        self.hosts.clear();
+        self.violations.clear();
+        self.reported_migrations.clear();
+        self.reported_violations.clear();
+    }
+
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        let context = &full_event.context;
+        self.update_host_time(context, full_event.time);
+        self.check_process_rules(context);
+
+        match &full_event.event {
+            Event::Ros2(ros2::Event::CallbackStart(event)) => {
+                self.start_callback(event.callback, context, model);
+            }
+            Event::Ros2(ros2::Event::CallbackEnd(event)) => {
+                self.end_callback(event.callback, context, model);
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self, _model: &Model) {
+        self.started_callbacks.clear();
+
+        self.log_violations();
+    }
+
+    fn print_rolling_stats(&mut self, _model: &Model) {
+        println!("CPU migrations since the last report:");
+        for (thread, stats) in &self.threads {
+            let migrations = self
+                .reported_migrations
+                .new_count(thread, stats.stats.migrations);
+            if migrations > 0 {
+                println!(
+                    "  {} [{}@{}]: {migrations} migrations",
+                    stats.procname, thread.1, thread.0
+                );
+            }
+        }
+
+        for (key, violation) in &self.violations {
+            let events = self.reported_violations.new_count(key, violation.events);
+            if events > 0 {
+                println!(
+                    "  Affinity of {} violated on CPU {} [{}@{}]: {events} events",
+                    self.spec.rules[key.rule].target, key.cpu, key.tid, key.hostname
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CallbackEntry {
+    callback: String,
+    hostname: String,
+    migrations: usize,
+    migrations_during_execution: usize,
+    cpus: BTreeMap<u32, CpuUsage>,
+}
+
+#[derive(Debug, Serialize)]
+struct ThreadEntry {
+    hostname: String,
+    procname: String,
+    pid: u32,
+    tid: u32,
+    migrations: usize,
+    migrations_during_execution: usize,
+    cpus: BTreeMap<u32, CpuUsage>,
+}
+
+/// Time spent in the callbacks started on a CPU.
+///
+/// This is not the CPU load, the time outside of callbacks is not known.
+#[derive(Debug, Serialize)]
+struct CpuCallbackTimeEntry {
+    hostname: String,
+    cpu: u32,
+    callback_time: i64,
+
+    /// Ratio of the callback time to the duration of the trace on the host
+    callback_time_ratio: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct ViolationEntry {
+    target: AffinityTarget,
+    allowed_cpus: String,
+    hostname: String,
+    pid: u32,
+    tid: u32,
+    cpu: u32,
+    events: usize,
+    callbacks: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CpuAffinityOutput {
+    callbacks: Vec<CallbackEntry>,
+    threads: Vec<ThreadEntry>,
+    cpu_callback_time: Vec<CpuCallbackTimeEntry>,
+    violations: Vec<ViolationEntry>,
+}
+
+impl AnalysisOutput for CpuAffinity {
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
+        let callbacks = self
+            .callbacks
+            .iter()
+            .map(|(&callback, stats)| {
+                let callback = &model[callback];
+                CallbackEntry {
+                    callback: DisplayCallbackSummary(model, callback).to_string(),
+                    hostname: callback.get_hostname().to_owned(),
+                    migrations: stats.migrations,
+                    migrations_during_execution: stats.migrations_during_execution,
+                    cpus: stats.cpus.clone(),
+                }
+            })
+            .collect();
+
+        let threads = self
+            .threads
+            .iter()
+            .map(|((hostname, tid), thread)| ThreadEntry {
+                hostname: hostname.clone(),
+                procname: thread.procname.clone(),
+                pid: thread.pid,
+                tid: *tid,
+                migrations: thread.stats.migrations,
+                migrations_during_execution: thread.stats.migrations_during_execution,
+                cpus: thread.stats.cpus.clone(),
+            })
+            .collect();
+
+        let cpu_callback_time = self
+            .hosts
+            .iter()
+            .flat_map(|(hostname, host)| {
+                let trace_duration = match (host.first_event, host.last_event) {
+                    (Some(first), Some(last)) => last.timestamp_nanos() - first.timestamp_nanos(),
+                    _ => 0,
+                };
+                host.cpu_callback_time
+                    .iter()
+                    .map(move |(&cpu, &callback_time)| CpuCallbackTimeEntry {
+                        hostname: hostname.clone(),
+                        cpu,
+                        callback_time,
+                        callback_time_ratio: if trace_duration > 0 {
+                            callback_time as f64 / trace_duration as f64
+                        } else {
+                            0.0
+                        },
+                    })
+            })
+            .collect();
+
+        let violations = self
+            .violations
+            .iter()
+            .map(|(key, violation)| {
+                let rule = &self.spec.rules[key.rule];
+                ViolationEntry {
+                    target: rule.target.clone(),
+                    allowed_cpus: format_cpu_set(&rule.cpus),
+                    hostname: key.hostname.clone(),
+                    pid: key.pid,
+                    tid: key.tid,
+                    cpu: key.cpu,
+                    events: violation.events,
+                    callbacks: violation
+                        .callbacks
+                        .iter()
+                        .map(|&callback| {
+                            DisplayCallbackSummary(model, &model[callback]).to_string()
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        serde_json::to_writer(
+            file,
+            &CpuAffinityOutput {
+                callbacks,
+                threads,
+                cpu_callback_time,
+                violations,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::{
+        AffinitySpec, AffinitySpecError, AffinityTarget, CpuAffinity, ExecutorStats, parse_cpu_list,
+    };
+    use crate::analysis::utils::callback_name;
+    use crate::model::{Callback, Model, Timer};
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0"), Some(BTreeSet::from([0])));
+        assert_eq!(
+            parse_cpu_list("0, 2-4,7"),
+            Some(BTreeSet::from([0, 2, 3, 4, 7]))
+        );
+        assert_eq!(parse_cpu_list(""), Some(BTreeSet::new()));
+        assert_eq!(parse_cpu_list("3-1"), None);
+        assert_eq!(parse_cpu_list("a"), None);
+        assert_eq!(parse_cpu_list("1-"), None);
+    }
+
+    #[test]
+    fn test_affinity_spec_parsing() {
+        let raw = toml::from_str(
+            r#"
+            [[rule]]
+            node = "/control/controller"
+            cpus = "2-3"
+
+            [[rule]]
+            process = "driver"
+            cpus = "0"
+            "#,
+        )
+        .unwrap();
+        let spec = AffinitySpec::from_raw(raw).unwrap();
+
+        assert_eq!(spec.rules.len(), 2);
+        assert_eq!(
+            spec.rules[0].target,
+            AffinityTarget::Node("/control/controller".into())
+        );
+        assert_eq!(spec.rules[0].cpus, BTreeSet::from([2, 3]));
+        assert_eq!(
+            spec.rules[1].target,
+            AffinityTarget::Process("driver".into())
+        );
+    }
+
+    #[test]
+    fn test_affinity_spec_validation() {
+        let raw = toml::from_str(
+            r#"
+            [[rule]]
+            node = "/a"
+            process = "a"
+            cpus = "1"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            AffinitySpec::from_raw(raw),
+            Err(AffinitySpecError::InvalidTarget { rule: 0 })
+        ));
+
+        let raw = toml::from_str(
+            r#"
+            [[rule]]
+            node = "/a"
+            cpus = "1"
+
+            [[rule]]
+            node = "/b"
+            cpus = ""
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            AffinitySpec::from_raw(raw),
+            Err(AffinitySpecError::EmptyCpuList { rule: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_executor_stats_migrations() {
+        let mut stats = ExecutorStats::default();
+
+        stats.record(0, 0, 10);
+        // Started on another CPU than the previous instance ended
+        stats.record(1, 1, 20);
+        // Migrated during the execution
+        stats.record(1, 2, 30);
+        // Started on the CPU where the previous instance ended
+        stats.record(2, 2, 40);
+
+        assert_eq!(stats.migrations, 1);
+        assert_eq!(stats.migrations_during_execution, 1);
+        assert_eq!(stats.last_cpu, Some(2));
+        assert_eq!(stats.cpus[&0].instances, 1);
+        assert_eq!(stats.cpus[&1].instances, 2);
+        assert_eq!(stats.cpus[&1].busy_time, 50);
+        assert_eq!(stats.cpus[&2].busy_time, 40);
+    }
+
+    #[test]
+    fn test_affinity_export() {
+        let mut model = Model::default();
+        let timer = model.add(Timer::new(1));
+        let callback = model.add(Callback::new_timer(1, timer, "host".to_owned()));
+
+        let mut analysis = CpuAffinity::new(AffinitySpec::default());
+        let mut stats = ExecutorStats::default();
+        stats.record(0, 1, 10);
+        analysis.callbacks.insert(callback, stats);
+
+        let export = analysis.callback_affinities(&model, None);
+        assert_eq!(export.len(), 1);
+        assert_eq!(export[0].id, 0);
+        assert_eq!(export[0].name, callback_name(&model, callback));
+        assert_eq!(export[0].stats.hostname, "host");
+        assert_eq!(export[0].stats.migrations_during_execution, 1);
+        assert_eq!(export[0].stats.cpus[&0].busy_time, 10);
+
+        assert!(analysis.thread_affinities().is_empty());
+        assert!(analysis.affinity_violations(&model).is_empty());
+    }
+}
@@ -1,15 +1,13 @@
 use std::collections::{HashMap, HashSet};
-use std::ops::Not;
-use std::sync::{Arc, Mutex};
 
 use crate::analysis::utils::DisplayDurationStats;
 use crate::argsv2::extract_args::AnalysisProperty;
 use crate::events_common::Context;
 use crate::extract::{RosChannelCompleteName, RosInterfaceCompleteName};
-use crate::model::display::get_node_name_from_weak;
+use crate::model::display::get_node_name;
 use crate::model::{
-    self, Callback, CallbackCaller, CallbackInstance, CallbackTrigger, Publisher, Service,
-    Subscriber, Time, Timer,
+    self, Callback, CallbackCaller, CallbackInstance, CallbackTrigger, Idx, Model, Publisher,
+    Service, Subscriber, Time, Timer, WithModel,
 };
 use crate::processed_events::{Event, FullEvent, r2r, ros2};
 use crate::statistics::Sorted;
@@ -17,7 +15,8 @@ use crate::utils::{DisplayDuration, Known, WeakKnown};
 use crate::visualization::COLOR_GRADIENT;
 use crate::visualization::graphviz_export::{self, NodeShape};
 
-use super::{ArcMutWrapper, EventAnalysis};
+use super::EventAnalysis;
+use super::topic_rate::{format_rate, mean_rate};
 
 const LATENCY_INVALID: i64 = i64::MAX;
 
@@ -38,26 +37,26 @@ impl From<&Context> for ThreadId {
 
 #[derive(Debug, Default, Clone)]
 pub struct DependencyGraph {
-    ros_nodes: Vec<Arc<Mutex<model::Node>>>,
+    ros_nodes: Vec<Idx<model::Node>>,
 
     edges: HashMap<Edge, EdgeData>,
 
-    publisher_nodes: HashMap<ArcMutWrapper<Publisher>, PublisherNode>,
-    subscriber_nodes: HashMap<ArcMutWrapper<Subscriber>, SubscriberNode>,
-    timer_nodes: HashMap<ArcMutWrapper<Timer>, TimerNode>,
-    callback_nodes: HashMap<ArcMutWrapper<Callback>, CallbackNode>,
+    publisher_nodes: HashMap<Idx<Publisher>, PublisherNode>,
+    subscriber_nodes: HashMap<Idx<Subscriber>, SubscriberNode>,
+    timer_nodes: HashMap<Idx<Timer>, TimerNode>,
+    callback_nodes: HashMap<Idx<Callback>, CallbackNode>,
 
-    last_spin_wake_up_time_for_node: HashMap<ArcMutWrapper<model::Node>, Time>,
-    running_callbacks: HashMap<ThreadId, Arc<Mutex<CallbackInstance>>>,
+    last_spin_wake_up_time_for_node: HashMap<Idx<model::Node>, Time>,
+    running_callbacks: HashMap<ThreadId, Idx<CallbackInstance>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Node {
-    Publisher(ArcMutWrapper<Publisher>),
-    Subscriber(ArcMutWrapper<Subscriber>),
-    Service(ArcMutWrapper<Service>),
-    Timer(ArcMutWrapper<Timer>),
-    Callback(ArcMutWrapper<Callback>),
+    Publisher(Idx<Publisher>),
+    Subscriber(Idx<Subscriber>),
+    Service(Idx<Service>),
+    Timer(Idx<Timer>),
+    Callback(Idx<Callback>),
 }
 
 #[derive(
@@ -117,13 +116,13 @@ pub struct CallbackNode {
     last_activation: Option<Time>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Edge {
-    PublicationInCallback(ArcMutWrapper<Publisher>, ArcMutWrapper<Callback>),
-    SubscriberCallbackInvocation(ArcMutWrapper<Subscriber>, ArcMutWrapper<Callback>),
-    ServiceCallbackInvocation(ArcMutWrapper<Service>, ArcMutWrapper<Callback>),
-    TimerCallbackInvocation(ArcMutWrapper<Timer>, ArcMutWrapper<Callback>),
-    PublisherSubscriberCommunication(ArcMutWrapper<Publisher>, ArcMutWrapper<Subscriber>),
+    PublicationInCallback(Idx<Publisher>, Idx<Callback>),
+    SubscriberCallbackInvocation(Idx<Subscriber>, Idx<Callback>),
+    ServiceCallbackInvocation(Idx<Service>, Idx<Callback>),
+    TimerCallbackInvocation(Idx<Timer>, Idx<Callback>),
+    PublisherSubscriberCommunication(Idx<Publisher>, Idx<Subscriber>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -137,29 +136,27 @@ enum EdgeType {
 
 impl Edge {
     fn source(&self) -> Node {
-        match self {
-            Edge::PublicationInCallback(_publisher, callback) => Node::Callback(callback.clone()),
+        match *self {
+            Edge::PublicationInCallback(_publisher, callback) => Node::Callback(callback),
             Edge::SubscriberCallbackInvocation(subscriber, _callback) => {
-                Node::Subscriber(subscriber.clone())
+                Node::Subscriber(subscriber)
             }
-            Edge::ServiceCallbackInvocation(service, _callback) => Node::Service(service.clone()),
-            Edge::TimerCallbackInvocation(timer, _callback) => Node::Timer(timer.clone()),
+            Edge::ServiceCallbackInvocation(service, _callback) => Node::Service(service),
+            Edge::TimerCallbackInvocation(timer, _callback) => Node::Timer(timer),
             Edge::PublisherSubscriberCommunication(publisher, _subscriber) => {
-                Node::Publisher(publisher.clone())
+                Node::Publisher(publisher)
             }
         }
     }
 
     fn target(&self) -> Node {
-        match self {
-            Edge::PublicationInCallback(publisher, _callback) => Node::Publisher(publisher.clone()),
-            Edge::SubscriberCallbackInvocation(_subscriber, callback) => {
-                Node::Callback(callback.clone())
-            }
-            Edge::ServiceCallbackInvocation(_service, callback) => Node::Callback(callback.clone()),
-            Edge::TimerCallbackInvocation(_timer, callback) => Node::Callback(callback.clone()),
+        match *self {
+            Edge::PublicationInCallback(publisher, _callback) => Node::Publisher(publisher),
+            Edge::SubscriberCallbackInvocation(_subscriber, callback) => Node::Callback(callback),
+            Edge::ServiceCallbackInvocation(_service, callback) => Node::Callback(callback),
+            Edge::TimerCallbackInvocation(_timer, callback) => Node::Callback(callback),
             Edge::PublisherSubscriberCommunication(_publisher, subscriber) => {
-                Node::Subscriber(subscriber.clone())
+                Node::Subscriber(subscriber)
             }
         }
     }
@@ -190,19 +187,25 @@ impl DependencyGraph {
         Self::default()
     }
 
-    pub fn to_dot_graph(&self, color: bool, thickness: bool, min_multiplier: f64) -> DotGraph {
-        DotGraph::new(self, color, thickness, min_multiplier)
+    pub fn to_dot_graph<'a>(
+        &self,
+        model: &'a Model,
+        color: bool,
+        thickness: bool,
+        min_multiplier: f64,
+    ) -> DotGraph<'a> {
+        DotGraph::new(self, model, color, thickness, min_multiplier)
     }
 }
 
 // Calculations
 impl DependencyGraph {
-    fn add_ros_node(&mut self, node: Arc<Mutex<model::Node>>) {
+    fn add_ros_node(&mut self, node: Idx<model::Node>) {
         self.ros_nodes.push(node);
     }
 
-    fn process_timer_invocation(&mut self, timer: &Arc<Mutex<Timer>>, event_time: Time) {
-        let timer_node = self.timer_nodes.entry(timer.clone().into()).or_default();
+    fn process_timer_invocation(&mut self, timer: Idx<Timer>, event_time: Time) {
+        let timer_node = self.timer_nodes.entry(timer).or_default();
         if let Some(previous_activation) = timer_node.last_activation.replace(event_time) {
             let activation_delay =
                 event_time.timestamp_nanos() - previous_activation.timestamp_nanos();
@@ -214,15 +217,16 @@ impl DependencyGraph {
 
     fn process_edge_to_callback(
         &mut self,
-        callback_arc: ArcMutWrapper<Callback>,
+        callback: Idx<Callback>,
         trigger: &CallbackTrigger,
         event_time: Time,
+        model: &Model,
     ) {
         match trigger {
             CallbackTrigger::SubscriptionMessage(msg) => {
-                let message = msg.lock().unwrap();
+                let message = &model[*msg];
                 let subscriber = message.get_subscriber().unwrap();
-                let edge = Edge::SubscriberCallbackInvocation(subscriber.into(), callback_arc);
+                let edge = Edge::SubscriberCallbackInvocation(subscriber, callback);
                 let edge_data = self.edges.entry(edge).or_default();
 
                 if let Some(previous_activation) = edge_data.last_activation.replace(event_time) {
@@ -245,12 +249,12 @@ impl DependencyGraph {
                 let latency = event_time.timestamp_nanos() - receive_time.timestamp_nanos();
                 edge_data.latencies.push(latency);
             }
-            CallbackTrigger::Timer(timer) => {
+            &CallbackTrigger::Timer(timer) => {
                 // Timer and Timer callback have the same data because the timer invocation is the
                 // callback invocation.
                 self.process_timer_invocation(timer, event_time);
 
-                let edge = Edge::TimerCallbackInvocation(timer.clone().into(), callback_arc);
+                let edge = Edge::TimerCallbackInvocation(timer, callback);
                 let edge_data = self.edges.entry(edge).or_default();
 
                 if let Some(previous_activation) = edge_data.last_activation.replace(event_time) {
@@ -269,24 +273,20 @@ impl DependencyGraph {
 
                 // Since the timer and the callback invocations are the same, the latency would be 0.
                 // We change the latency to the time between the last spin wake up and the callback.
-                let timer = timer.lock().unwrap();
-                let node_arc = timer
+                let node = model[timer]
                     .get_node()
-                    .expect("Timer should be associated with a node when invoked.")
-                    .get_arc()
-                    .expect("Node should be alive.");
+                    .expect("Timer should be associated with a node when invoked.");
                 let latency = self
                     .last_spin_wake_up_time_for_node
-                    .get(&node_arc.into())
+                    .get(&node)
                     .map_or(LATENCY_INVALID, |wake_up_time| {
                         event_time.timestamp_nanos() - wake_up_time.timestamp_nanos()
                     });
 
                 edge_data.latencies.push(latency);
             }
-            CallbackTrigger::Service(service_arc) => {
-                let edge =
-                    Edge::ServiceCallbackInvocation(service_arc.clone().into(), callback_arc);
+            &CallbackTrigger::Service(service) => {
+                let edge = Edge::ServiceCallbackInvocation(service, callback);
                 let edge_data = self.edges.entry(edge).or_default();
 
                 if let Some(previous_activation) = edge_data.last_activation.replace(event_time) {
@@ -303,15 +303,12 @@ impl DependencyGraph {
                     debug_assert!(edge_data.activation_delay.is_empty());
                 }
 
-                let service = service_arc.lock().unwrap();
-                let node_arc = service
+                let node = model[service]
                     .get_node()
-                    .expect("Service should be associated with a node when invoked.")
-                    .get_arc()
-                    .expect("Node should be alive.");
+                    .expect("Service should be associated with a node when invoked.");
                 let latency = self
                     .last_spin_wake_up_time_for_node
-                    .get(&node_arc.into())
+                    .get(&node)
                     .map_or(LATENCY_INVALID, |wake_up_time| {
                         event_time.timestamp_nanos() - wake_up_time.timestamp_nanos()
                     });
@@ -326,9 +323,10 @@ impl DependencyGraph {
         event: &ros2::CallbackStart,
         event_time: Time,
         context: &Context,
+        model: &Model,
     ) {
         self.running_callbacks
-            .insert(context.into(), event.callback.clone())
+            .insert(context.into(), event.callback)
             .inspect(|old| {
                 panic!(
                     "Callback {old:?} is already running on vtid {} on host {}",
@@ -337,13 +335,10 @@ impl DependencyGraph {
                 );
             });
 
-        let callback_instance = event.callback.lock().unwrap();
+        let callback_instance = &model[event.callback];
         let callback = callback_instance.get_callback();
 
-        let callback_node = self
-            .callback_nodes
-            .entry(callback.clone().into())
-            .or_default();
+        let callback_node = self.callback_nodes.entry(callback).or_default();
         let previous_activation = callback_node.last_activation.replace(event_time);
         if let Some(previous_activation) = previous_activation {
             debug_assert_eq!(event_time, callback_instance.get_start_time());
@@ -352,7 +347,12 @@ impl DependencyGraph {
             callback_node.activation_delay.push(activation_delay);
         }
 
-        self.process_edge_to_callback(callback.into(), callback_instance.get_trigger(), event_time);
+        self.process_edge_to_callback(
+            callback,
+            &callback_instance.get_trigger(),
+            event_time,
+            model,
+        );
     }
 
     fn process_callback_end(
@@ -360,14 +360,11 @@ impl DependencyGraph {
         event: &ros2::CallbackEnd,
         event_time: Time,
         context: &Context,
+        model: &Model,
     ) {
         self.running_callbacks
             .remove(&context.into())
-            .and_then(|callback| {
-                Arc::ptr_eq(&callback, &event.callback)
-                    .not()
-                    .then_some(callback)
-            })
+            .filter(|&callback| callback != event.callback)
             .inspect(|old| {
                 panic!(
                     "Callback {old:?} is running on vtid {} on host {} instead of expected {:?}",
@@ -377,10 +374,10 @@ impl DependencyGraph {
                 );
             });
 
-        let callback_instance = event.callback.lock().unwrap();
+        let callback_instance = &model[event.callback];
         let callback = callback_instance.get_callback();
 
-        let callback_node = self.callback_nodes.get_mut(&callback.into()).unwrap();
+        let callback_node = self.callback_nodes.get_mut(&callback).unwrap();
         let start_time = callback_node
             .last_activation
             .expect("Last activation should be known. It is set in start event.");
@@ -400,17 +397,14 @@ impl DependencyGraph {
         callback_node.durations.push(duration);
     }
 
-    fn process_rmw_take(&mut self, event: &ros2::RmwTake, event_time: Time) {
+    fn process_rmw_take(&mut self, event: &ros2::RmwTake, event_time: Time, model: &Model) {
         if !event.taken {
             // Only process taken messages
             return;
         }
-        let message = event.message.lock().unwrap();
-        let subscriber_arc = message.get_subscriber().unwrap();
-        let subscriber_node = self
-            .subscriber_nodes
-            .entry(subscriber_arc.clone().into())
-            .or_default();
+        let message = &model[event.message];
+        let subscriber = message.get_subscriber().unwrap();
+        let subscriber_node = self.subscriber_nodes.entry(subscriber).or_default();
         if let Some(previous_take) = subscriber_node.last_take.replace(event_time) {
             let take_delay = event_time.timestamp_nanos() - previous_take.timestamp_nanos();
             subscriber_node.take_delay.push(take_delay);
@@ -418,17 +412,18 @@ impl DependencyGraph {
             debug_assert!(subscriber_node.take_delay.is_empty());
         }
 
-        let Some(publication_message) = message.get_publication_message() else {
+        let Some(publication_message) = message
+            .get_publication_message()
+            .and_then(|publication_message| model.get(publication_message))
+        else {
             // Ignore messages that cannot be associated with a publication message
             return;
         };
 
-        let publication_message = publication_message.lock().unwrap();
-        let publisher_arc = publication_message
+        let publisher = publication_message
             .get_publisher()
             .expect("Publisher should be known.");
-        let edge =
-            Edge::PublisherSubscriberCommunication(publisher_arc.into(), subscriber_arc.into());
+        let edge = Edge::PublisherSubscriberCommunication(publisher, subscriber);
         let edge_data = self.edges.entry(edge).or_default();
 
         if let Some(previous_activation) = edge_data.last_activation.replace(event_time) {
@@ -460,14 +455,12 @@ impl DependencyGraph {
         event: &ros2::RmwPublish,
         event_time: Time,
         context: &Context,
+        model: &Model,
     ) {
-        let publication = event.message.lock().unwrap();
-        let publisher_arc = publication.get_publisher().unwrap();
+        let publication = &model[event.message];
+        let publisher = publication.get_publisher().unwrap();
 
-        let publisher_node = self
-            .publisher_nodes
-            .entry(publisher_arc.clone().into())
-            .or_default();
+        let publisher_node = self.publisher_nodes.entry(publisher).or_default();
 
         if let Some(previous_publication) = publisher_node.last_publication.replace(event_time) {
             let publication_delay =
@@ -477,10 +470,10 @@ impl DependencyGraph {
             debug_assert!(publisher_node.publication_delay.is_empty());
         }
 
-        if let Some(callback_instance_arc) = self.running_callbacks.get(&context.into()) {
-            let callback_instance = callback_instance_arc.lock().unwrap();
-            let callback_arc = callback_instance.get_callback();
-            let edge = Edge::PublicationInCallback(publisher_arc.into(), callback_arc.into());
+        if let Some(&callback_instance) = self.running_callbacks.get(&context.into()) {
+            let callback_instance = &model[callback_instance];
+            let callback = callback_instance.get_callback();
+            let edge = Edge::PublicationInCallback(publisher, callback);
             let edge_data = self.edges.entry(edge).or_default();
 
             if let Some(previous_activation) = edge_data.last_activation.replace(event_time) {
@@ -547,50 +540,43 @@ pub struct MessageLatencyExport {
 }
 
 impl DependencyGraph {
-    pub fn activation_delays(&self, node_ids: &HashMap<Node, usize>) -> Vec<ActivationDelayExport> {
-        let timers = self.timer_nodes.iter().map(|(k, v)| {
-            let id = node_ids[&Node::Timer(k.clone())];
-            let n = k.0.lock().unwrap();
+    pub fn activation_delays(
+        &self,
+        model: &Model,
+        node_ids: &HashMap<Node, usize>,
+    ) -> Vec<ActivationDelayExport> {
+        let timers = self.timer_nodes.iter().map(|(&k, v)| {
+            let id = node_ids[&Node::Timer(k)];
+            let n = &model[k];
             ActivationDelayExport {
                 id,
                 name: RosInterfaceCompleteName {
                     interface: format!("Timer({})", n.get_period().unwrap_or(0)),
-                    node: n
-                        .get_node()
-                        .map_or(WeakKnown::Unknown, |node_weak| {
-                            get_node_name_from_weak(&node_weak.get_weak())
-                        })
-                        .unwrap_or(String::new()),
+                    node: ros_node_name(model, n.get_node()),
                 },
                 activation_delays: v.activation_delay.clone(),
             }
         });
 
-        let callbacks = self.callback_nodes.iter().map(|(k, v)| {
-            let id = node_ids[&Node::Callback(k.clone())];
-            let n = k.0.lock().unwrap();
+        let callbacks = self.callback_nodes.iter().map(|(&k, v)| {
+            let id = node_ids[&Node::Callback(k)];
+            let n = &model[k];
             ActivationDelayExport {
                 id,
                 name: RosInterfaceCompleteName {
                     interface: format!(
                         "Callback({})",
                         match n.get_caller() {
-                            Some(c) => match c {
-                                CallbackCaller::Subscription(arc_weak) => format!(
-                                    "Subscriber(\"{}\")",
-                                    arc_weak.get_arc().unwrap().lock().unwrap().get_topic()
-                                ),
-                                v => v.to_string(),
+                            Some(c) => match *c {
+                                CallbackCaller::Subscription(subscriber) => {
+                                    format!("Subscriber(\"{}\")", model[subscriber].get_topic())
+                                }
+                                _ => model.display(c).to_string(),
                             },
                             None => String::new(),
                         }
                     ),
-                    node: n
-                        .get_node()
-                        .map_or(WeakKnown::Unknown, |node_weak| {
-                            get_node_name_from_weak(&node_weak.get_weak())
-                        })
-                        .unwrap_or(String::new()),
+                    node: ros_node_name(model, n.get_node(model).into()),
                 },
                 activation_delays: v.activation_delay.clone(),
             }
@@ -601,24 +587,20 @@ impl DependencyGraph {
 
     pub fn publication_delays(
         &self,
+        model: &Model,
         node_ids: &HashMap<Node, usize>,
     ) -> Vec<PublicationDelayExport> {
         self.publisher_nodes
             .iter()
-            .map(|(k, v)| {
-                let id = node_ids[&Node::Publisher(k.clone())];
-                let n = k.0.lock().unwrap();
+            .map(|(&k, v)| {
+                let id = node_ids[&Node::Publisher(k)];
+                let n = &model[k];
 
                 PublicationDelayExport {
                     id,
                     name: RosInterfaceCompleteName {
                         interface: format!("Publisher({})", n.get_topic()),
-                        node: n
-                            .get_node()
-                            .map_or(WeakKnown::Unknown, |node_weak| {
-                                get_node_name_from_weak(&node_weak.get_weak())
-                            })
-                            .unwrap_or(String::new()),
+                        node: ros_node_name(model, n.get_node()),
                     },
                     publication_delays: v.publication_delay.clone(),
                 }
@@ -626,23 +608,22 @@ impl DependencyGraph {
             .collect()
     }
 
-    pub fn message_delays(&self, node_ids: &HashMap<Node, usize>) -> Vec<MessagesDelayExport> {
+    pub fn message_delays(
+        &self,
+        model: &Model,
+        node_ids: &HashMap<Node, usize>,
+    ) -> Vec<MessagesDelayExport> {
         self.subscriber_nodes
             .iter()
-            .map(|(k, v)| {
-                let id = node_ids[&Node::Subscriber(k.clone())];
-                let n = k.0.lock().unwrap();
+            .map(|(&k, v)| {
+                let id = node_ids[&Node::Subscriber(k)];
+                let n = &model[k];
 
                 MessagesDelayExport {
                     id,
                     name: RosInterfaceCompleteName {
                         interface: format!("Subscriber({})", n.get_topic()),
-                        node: n
-                            .get_node()
-                            .map_or(WeakKnown::Unknown, |node_weak| {
-                                get_node_name_from_weak(&node_weak.get_weak())
-                            })
-                            .unwrap_or(String::new()),
+                        node: ros_node_name(model, n.get_node()),
                     },
                     messages_delays: v.take_delay.clone(),
                 }
@@ -652,27 +633,25 @@ impl DependencyGraph {
 
     pub fn callback_durations(
         &self,
+        model: &Model,
         node_ids: &HashMap<Node, usize>,
     ) -> Vec<CallbackDurationExport> {
         self.callback_nodes
             .iter()
-            .map(|(k, v)| {
-                let id = node_ids[&Node::Callback(k.clone())];
-                let c = k.0.lock().unwrap();
+            .map(|(&k, v)| {
+                let id = node_ids[&Node::Callback(k)];
+                let c = &model[k];
 
                 CallbackDurationExport {
                     id,
                     name: RosInterfaceCompleteName {
                         interface: format!(
                             "Callback({})",
-                            c.get_caller().map(ToString::to_string).unwrap_or_default()
+                            c.get_caller()
+                                .map(|caller| model.display(caller).to_string())
+                                .unwrap_or_default()
                         ),
-                        node: c
-                            .get_node()
-                            .map_or(WeakKnown::Unknown, |node_weak| {
-                                get_node_name_from_weak(&node_weak.get_weak())
-                            })
-                            .unwrap_or(String::new()),
+                        node: ros_node_name(model, c.get_node(model).into()),
                     },
                     callback_durations: v.durations.clone(),
                 }
@@ -682,41 +661,26 @@ impl DependencyGraph {
 
     pub fn message_latencies(
         &self,
+        model: &Model,
         node_ids: &HashMap<Node, usize>,
         edge_ids: &HashMap<(usize, usize), usize>,
     ) -> Vec<MessageLatencyExport> {
         self.edges
             .iter()
             .map(|(k, v)| {
-                let source = get_node_name_from_graph_node(&k.source());
-                let dest = get_node_name_from_graph_node(&k.target());
+                let source = get_node_name_from_graph_node(model, k.source());
+                let dest = get_node_name_from_graph_node(model, k.target());
 
                 let topic = match k.source() {
-                    Node::Publisher(arc_mut_wrapper) => {
-                        arc_mut_wrapper.0.lock().unwrap().get_topic().to_string()
-                    }
-                    Node::Subscriber(arc_mut_wrapper) => {
-                        arc_mut_wrapper.0.lock().unwrap().get_topic().to_string()
-                    }
-                    Node::Service(arc_mut_wrapper) => {
-                        arc_mut_wrapper.0.lock().unwrap().get_name().to_string()
-                    }
-                    Node::Timer(arc_mut_wrapper) => {
-                        arc_mut_wrapper.0.lock().unwrap().get_period().to_string()
-                    }
+                    Node::Publisher(publisher) => model[publisher].get_topic().to_string(),
+                    Node::Subscriber(subscriber) => model[subscriber].get_topic().to_string(),
+                    Node::Service(service) => model[service].get_name().to_string(),
+                    Node::Timer(timer) => model[timer].get_period().to_string(),
                     _ => match k.target() {
-                        Node::Publisher(arc_mut_wrapper) => {
-                            arc_mut_wrapper.0.lock().unwrap().get_topic().to_string()
-                        }
-                        Node::Subscriber(arc_mut_wrapper) => {
-                            arc_mut_wrapper.0.lock().unwrap().get_topic().to_string()
-                        }
-                        Node::Service(arc_mut_wrapper) => {
-                            arc_mut_wrapper.0.lock().unwrap().get_name().to_string()
-                        }
-                        Node::Timer(arc_mut_wrapper) => {
-                            arc_mut_wrapper.0.lock().unwrap().get_period().to_string()
-                        }
+                        Node::Publisher(publisher) => model[publisher].get_topic().to_string(),
+                        Node::Subscriber(subscriber) => model[subscriber].get_topic().to_string(),
+                        Node::Service(service) => model[service].get_name().to_string(),
+                        Node::Timer(timer) => model[timer].get_period().to_string(),
                         _ => String::new(),
                     },
                 };
@@ -740,8 +704,8 @@ impl DependencyGraph {
     pub fn node_overview(&self, node_ids: &HashMap<Node, usize>) -> Vec<NodeOverviewExport> {
         let mut overview = vec![];
 
-        for publisher in self.publisher_nodes.keys() {
-            let id = node_ids[&Node::Publisher(publisher.clone())];
+        for &publisher in self.publisher_nodes.keys() {
+            let id = node_ids[&Node::Publisher(publisher)];
 
             overview.push(NodeOverviewExport {
                 id,
@@ -750,8 +714,8 @@ impl DependencyGraph {
             });
         }
 
-        for subscriber in self.subscriber_nodes.keys() {
-            let id = node_ids[&Node::Subscriber(subscriber.clone())];
+        for &subscriber in self.subscriber_nodes.keys() {
+            let id = node_ids[&Node::Subscriber(subscriber)];
 
             overview.push(NodeOverviewExport {
                 id,
@@ -760,8 +724,8 @@ impl DependencyGraph {
             });
         }
 
-        for callback in self.callback_nodes.keys() {
-            let id = node_ids[&Node::Callback(callback.clone())];
+        for &callback in self.callback_nodes.keys() {
+            let id = node_ids[&Node::Callback(callback)];
 
             overview.push(NodeOverviewExport {
                 id,
@@ -773,8 +737,8 @@ impl DependencyGraph {
             });
         }
 
-        for timer in self.timer_nodes.keys() {
-            let id = node_ids[&Node::Timer(timer.clone())];
+        for &timer in self.timer_nodes.keys() {
+            let id = node_ids[&Node::Timer(timer)];
 
             overview.push(NodeOverviewExport {
                 id,
@@ -787,41 +751,46 @@ impl DependencyGraph {
     }
 }
 
+fn ros_node_name(model: &Model, node: Known<Idx<model::Node>>) -> String {
+    node.map_or(WeakKnown::Unknown, |node| get_node_name(model, node))
+        .unwrap_or(String::new())
+}
+
 impl EventAnalysis for DependencyGraph {
     fn initialize(&mut self) {
         *self = Self::default();
     }
 
-    fn process_event(&mut self, full_event: &FullEvent) {
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
         let event_time = full_event.time;
 
         match &full_event.event {
             Event::Ros2(ros2::Event::RclNodeInit(event)) => {
-                self.add_ros_node(event.node.clone());
+                self.add_ros_node(event.node);
             }
             Event::Ros2(ros2::Event::CallbackStart(event)) => {
-                self.process_callback_start(event, event_time, &full_event.context);
+                self.process_callback_start(event, event_time, &full_event.context, model);
             }
             Event::Ros2(ros2::Event::CallbackEnd(event)) => {
-                self.process_callback_end(event, event_time, &full_event.context);
+                self.process_callback_end(event, event_time, &full_event.context, model);
             }
 
             Event::Ros2(ros2::Event::RmwTake(event)) => {
-                self.process_rmw_take(event, event_time);
+                self.process_rmw_take(event, event_time, model);
             }
 
             Event::Ros2(ros2::Event::RmwPublish(event)) => {
-                self.process_publication(event, event_time, &full_event.context);
+                self.process_publication(event, event_time, &full_event.context, model);
             }
 
             Event::R2r(r2r::Event::SpinWake(event)) => {
                 self.last_spin_wake_up_time_for_node
-                    .insert(event.node.clone().into(), event_time);
+                    .insert(event.node, event_time);
             }
             Event::R2r(r2r::Event::SpinEnd(event)) => {
                 debug_assert!(
                     self.last_spin_wake_up_time_for_node
-                        .contains_key(&event.node.clone().into()),
+                        .contains_key(&event.node),
                     "Missing spin wake up event."
                 );
             }
@@ -830,9 +799,21 @@ impl EventAnalysis for DependencyGraph {
         }
     }
 
-    fn finalize(&mut self) {
+    fn finalize(&mut self, _model: &Model) {
         self.running_callbacks.clear();
     }
+
+    fn print_rolling_stats(&mut self, _model: &Model) {
+        println!(
+            "Dependency graph: {} nodes, {} publishers, {} subscribers, {} timers, {} callbacks, {} edges",
+            self.ros_nodes.len(),
+            self.publisher_nodes.len(),
+            self.subscriber_nodes.len(),
+            self.timer_nodes.len(),
+            self.callback_nodes.len(),
+            self.edges.len()
+        );
+    }
 }
 
 struct EdgeWeightStats {
@@ -895,14 +876,19 @@ struct DisplayAsDotEdge {
     latencies: Sorted<i64>,
     node_index: Option<usize>,
     edge_type: EdgeType,
+
+    /// Mean message rate in Hz of publisher to subscriber edges
+    rate: Option<f64>,
 }
 
-pub struct DotGraph {
-    graph_node_to_ros_node: HashMap<Node, ArcMutWrapper<model::Node>>,
+pub struct DotGraph<'a> {
+    model: &'a Model,
+
+    graph_node_to_ros_node: HashMap<Node, Idx<model::Node>>,
     node_to_id: HashMap<Node, usize>,
-    ros_nodes: Vec<ArcMutWrapper<model::Node>>,
-    ros_node_to_id: HashMap<ArcMutWrapper<model::Node>, usize>,
-    ros_nodes_min_max_latency_stats: HashMap<ArcMutWrapper<model::Node>, EdgeWeightStats>,
+    ros_nodes: Vec<Idx<model::Node>>,
+    ros_node_to_id: HashMap<Idx<model::Node>, usize>,
+    ros_nodes_min_max_latency_stats: HashMap<Idx<model::Node>, EdgeWeightStats>,
 
     edge_ids: HashMap<(usize, usize), usize>,
 
@@ -915,68 +901,68 @@ pub struct DotGraph {
     min_multiplier: f64,
 }
 
-impl DotGraph {
-    pub fn new(graph: &DependencyGraph, color: bool, thickness: bool, min_multiplier: f64) -> Self {
-        let mut graph_node_to_ros_node: HashMap<Node, ArcMutWrapper<model::Node>> = HashMap::new();
+impl<'a> DotGraph<'a> {
+    pub fn new(
+        graph: &DependencyGraph,
+        model: &'a Model,
+        color: bool,
+        thickness: bool,
+        min_multiplier: f64,
+    ) -> Self {
+        let mut graph_node_to_ros_node: HashMap<Node, Idx<model::Node>> = HashMap::new();
         let mut node_to_id = HashMap::new();
 
         let mut graph_node_id = 1;
 
-        for publisher in graph.publisher_nodes.keys() {
-            let node = Node::Publisher(publisher.clone());
-            node_to_id.insert(node.clone(), graph_node_id);
+        for &publisher in graph.publisher_nodes.keys() {
+            let node = Node::Publisher(publisher);
+            node_to_id.insert(node, graph_node_id);
             graph_node_id += 1;
 
-            let publisher = publisher.0.lock().unwrap();
-            if let Known::Known(ros_node_arc) = publisher.get_node() {
-                let ros_node = ros_node_arc.get_arc().expect("Node should be alive");
-                graph_node_to_ros_node.insert(node, ros_node.clone().into());
+            if let Known::Known(ros_node) = model[publisher].get_node() {
+                graph_node_to_ros_node.insert(node, ros_node);
             }
         }
 
-        for subscriber in graph.subscriber_nodes.keys() {
-            let node = Node::Subscriber(subscriber.clone());
-            node_to_id.insert(node.clone(), graph_node_id);
+        for &subscriber in graph.subscriber_nodes.keys() {
+            let node = Node::Subscriber(subscriber);
+            node_to_id.insert(node, graph_node_id);
             graph_node_id += 1;
 
-            let subscriber = subscriber.0.lock().unwrap();
-            if let Known::Known(ros_node_arc) = subscriber.get_node() {
-                let ros_node = ros_node_arc.get_arc().expect("Node should be alive");
-                graph_node_to_ros_node.insert(node, ros_node.clone().into());
+            if let Known::Known(ros_node) = model[subscriber].get_node() {
+                graph_node_to_ros_node.insert(node, ros_node);
             }
         }
 
-        for timer in graph.timer_nodes.keys() {
-            let node = Node::Timer(timer.clone());
-            node_to_id.insert(node.clone(), graph_node_id);
+        for &timer in graph.timer_nodes.keys() {
+            let node = Node::Timer(timer);
+            node_to_id.insert(node, graph_node_id);
             graph_node_id += 1;
 
-            let timer = timer.0.lock().unwrap();
-            let ros_node = timer.get_node().unwrap().get_arc().unwrap();
-            graph_node_to_ros_node.insert(node, ros_node.clone().into());
+            let ros_node = model[timer].get_node().unwrap();
+            graph_node_to_ros_node.insert(node, ros_node);
         }
 
-        for callback in graph.callback_nodes.keys() {
-            let node = Node::Callback(callback.clone());
-            node_to_id.insert(node.clone(), graph_node_id);
+        for &callback in graph.callback_nodes.keys() {
+            let node = Node::Callback(callback);
+            node_to_id.insert(node, graph_node_id);
             graph_node_id += 1;
 
-            let callback = callback.0.lock().unwrap();
-            let ros_node = callback.get_node().unwrap().get_arc().unwrap();
-            graph_node_to_ros_node.insert(node, ros_node.clone().into());
+            let ros_node = model[callback].get_node(model).unwrap();
+            graph_node_to_ros_node.insert(node, ros_node);
         }
 
         let unique_used_ros_nodes = graph_node_to_ros_node
             .values()
+            .copied()
             .collect::<HashSet<_>>()
             .into_iter()
-            .cloned()
             .collect::<Vec<_>>();
 
         let ros_node_to_id = unique_used_ros_nodes
             .iter()
             .enumerate()
-            .map(|(id, ros_node)| (ros_node.clone(), id))
+            .map(|(id, &ros_node)| (ros_node, id))
             .collect::<HashMap<_, _>>();
 
         let (edges, ros_nodes_min_max_latency_stats, pub_sub_latency_range, edge_ids) =
@@ -988,6 +974,7 @@ impl DotGraph {
             );
 
         Self {
+            model,
             graph_node_to_ros_node,
             ros_nodes: unique_used_ros_nodes,
             ros_node_to_id,
@@ -1013,18 +1000,18 @@ impl DotGraph {
 
 fn process_edges(
     graph_edges: &HashMap<Edge, EdgeData>,
-    graph_node_to_ros_node: &HashMap<Node, ArcMutWrapper<model::Node>>,
-    ros_node_to_id: &HashMap<ArcMutWrapper<model::Node>, usize>,
+    graph_node_to_ros_node: &HashMap<Node, Idx<model::Node>>,
+    ros_node_to_id: &HashMap<Idx<model::Node>, usize>,
     node_to_id: &HashMap<Node, usize>,
 ) -> (
     Vec<DisplayAsDotEdge>,
-    HashMap<ArcMutWrapper<model::Node>, EdgeWeightStats>,
+    HashMap<Idx<model::Node>, EdgeWeightStats>,
     Option<(i64, i64)>,
     HashMap<(usize, usize), usize>,
 ) {
     let (mut pub_sub_min_latency, mut pub_sub_max_latency) = (i64::MAX, i64::MIN);
     let mut edges = Vec::new();
-    let mut ros_nodes_min_max_latency_stats: HashMap<ArcMutWrapper<model::Node>, EdgeWeightStats> =
+    let mut ros_nodes_min_max_latency_stats: HashMap<Idx<model::Node>, EdgeWeightStats> =
         HashMap::new();
     let mut edge_ids = HashMap::new();
 
@@ -1048,21 +1035,21 @@ fn process_edges(
             }
             EdgeType::SubscriberCallbackInvocation if source_ros_node == target_ros_node => {
                 ros_nodes_min_max_latency_stats
-                    .entry(source_ros_node.clone())
+                    .entry(*source_ros_node)
                     .or_default()
                     .update_subscriber_to_callback(median);
                 Some(ros_node_to_id[source_ros_node])
             }
             EdgeType::TimerCallbackInvocation if source_ros_node == target_ros_node => {
                 ros_nodes_min_max_latency_stats
-                    .entry(source_ros_node.clone())
+                    .entry(*source_ros_node)
                     .or_default()
                     .update_timer_to_callback(median);
                 Some(ros_node_to_id[source_ros_node])
             }
             EdgeType::PublicationInCallback if source_ros_node == target_ros_node => {
                 ros_nodes_min_max_latency_stats
-                    .entry(source_ros_node.clone())
+                    .entry(*source_ros_node)
                     .or_default()
                     .update_callback_to_publisher(median);
                 Some(ros_node_to_id[source_ros_node])
@@ -1079,12 +1066,18 @@ fn process_edges(
         edge_ids.insert((source_id, target_id), edge_id);
         edge_id += 1;
 
+        let rate = match edge_type {
+            EdgeType::PublisherSubscriberCommunication => mean_rate(&edge_data.activation_delay),
+            _ => None,
+        };
+
         edges.push(DisplayAsDotEdge {
             source: source_id,
             target: target_id,
             latencies,
             node_index: node_id,
             edge_type,
+            rate,
         });
     }
     let pub_sub_latency_range =
@@ -1102,73 +1095,69 @@ fn process_edges(
     )
 }
 
-fn get_node_name_and_tooltip(node: &Node, ros_node_name: Known<&str>) -> (String, String) {
+fn get_node_name_and_tooltip(
+    model: &Model,
+    node: Node,
+    ros_node_name: Known<&str>,
+) -> (String, String) {
     match node {
-        Node::Publisher(publisher_arc) => {
-            let publisher = publisher_arc.0.lock().unwrap();
-            let topic = publisher.get_topic().to_string();
+        Node::Publisher(publisher) => {
+            let topic = model[publisher].get_topic().to_string();
             let name = format!("Publisher\n{topic}");
             let tooltip = String::new();
 
             (name, tooltip)
         }
-        Node::Subscriber(subscriber_arc) => {
-            let subscriber = subscriber_arc.0.lock().unwrap();
-            let topic = subscriber.get_topic().to_string();
+        Node::Subscriber(subscriber) => {
+            let topic = model[subscriber].get_topic().to_string();
             let name = format!("Subscriber\n{topic}");
             let tooltip = String::new();
 
             (name, tooltip)
         }
-        Node::Timer(timer_arc) => {
-            let timer = timer_arc.0.lock().unwrap();
-            let period = timer.get_period().unwrap();
+        Node::Timer(timer) => {
+            let period = model[timer].get_period().unwrap();
             let name = format!("Timer\n{}", DisplayDuration(period));
             let tooltip = String::new();
 
             (name, tooltip)
         }
-        Node::Callback(callback_arc) => {
-            let callback = callback_arc.0.lock().unwrap();
-            let name = format!(
-                "Callback\n{}",
-                Known::<&CallbackCaller>::from(callback.get_caller())
-            );
+        Node::Callback(callback) => {
+            let caller = model[callback]
+                .get_caller()
+                .map(|caller| model.display(caller));
+            let name = format!("Callback\n{}", Known::<WithModel<_>>::from(caller));
             let tooltip = String::new();
 
             (name, tooltip)
         }
-        Node::Service(service_arc) => {
-            let service = service_arc.0.lock().unwrap();
-            let name = format!("Service\n{}", service.get_name());
+        Node::Service(service) => {
+            let name = format!("Service\n{}", model[service].get_name());
             let tooltip = format!("Node: {ros_node_name}\nSee callback for details",);
             (name, tooltip)
         }
     }
 }
 
-fn get_node_name_from_graph_node(node: &Node) -> String {
-    let x = match node {
-        Node::Publisher(arc_mut_wrapper) => arc_mut_wrapper.0.lock().unwrap().get_node(),
-        Node::Subscriber(arc_mut_wrapper) => arc_mut_wrapper.0.lock().unwrap().get_node(),
-        Node::Service(arc_mut_wrapper) => arc_mut_wrapper.0.lock().unwrap().get_node(),
-        Node::Timer(arc_mut_wrapper) => arc_mut_wrapper.0.lock().unwrap().get_node(),
-        Node::Callback(arc_mut_wrapper) => arc_mut_wrapper.0.lock().unwrap().get_node().into(),
+fn get_node_name_from_graph_node(model: &Model, node: Node) -> String {
+    let node = match node {
+        Node::Publisher(publisher) => model[publisher].get_node(),
+        Node::Subscriber(subscriber) => model[subscriber].get_node(),
+        Node::Service(service) => model[service].get_node(),
+        Node::Timer(timer) => model[timer].get_node(),
+        Node::Callback(callback) => model[callback].get_node(model).into(),
     };
 
-    match x {
-        Known::Known(c) => get_node_name_from_weak(&c.get_weak()),
-        Known::Unknown => WeakKnown::Unknown,
-    }
-    .to_string()
+    node.map_or(WeakKnown::Unknown, |node| get_node_name(model, node))
+        .to_string()
 }
 
-impl std::fmt::Display for DotGraph {
+impl std::fmt::Display for DotGraph<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let cluster_names = self
             .ros_nodes
             .iter()
-            .map(|node| node.0.lock().unwrap().get_full_name().to_string())
+            .map(|&node| self.model[node].get_full_name().to_string())
             .collect::<Vec<_>>();
 
         let mut clusters = vec![Vec::new(); self.ros_node_to_id.len()];
@@ -1184,15 +1173,13 @@ impl std::fmt::Display for DotGraph {
             let ros_node_name =
                 self.graph_node_to_ros_node
                     .get(node)
-                    .map_or(Known::Unknown, |node_arc| {
-                        node_arc
-                            .0
-                            .lock()
-                            .unwrap()
+                    .map_or(Known::Unknown, |&ros_node| {
+                        self.model[ros_node]
                             .get_full_name()
                             .map(ToString::to_string)
                     });
-            let (node_name, tooltip) = get_node_name_and_tooltip(node, ros_node_name.as_deref());
+            let (node_name, tooltip) =
+                get_node_name_and_tooltip(self.model, *node, ros_node_name.as_deref());
 
             let graph_node = graph.add_node(&node_name, *id);
             graph_node.set_shape(NodeShape::Ellipse);
@@ -1200,7 +1187,8 @@ impl std::fmt::Display for DotGraph {
         }
 
         for edge in &self.edges {
-            let graph_edge = graph.add_edge(edge.source, edge.target, "");
+            let label = edge.rate.map(format_rate).unwrap_or_default();
+            let graph_edge = graph.add_edge(edge.source, edge.target, &label);
             graph_edge.set_attribute(
                 "tooltip",
                 &format!(
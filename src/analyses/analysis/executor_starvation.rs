@@ -0,0 +1,815 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::utils::callback_name;
+use crate::events_common::Context;
+use crate::extract::RosInterfaceCompleteName;
+use crate::model::display::DisplayCallbackSummary;
+use crate::model::{
+    Callback, CallbackInstance, CallbackTrigger, Idx, Model, PublicationMessage, Publisher, Time,
+};
+use crate::processed_events::{Event, FullEvent, ros2};
+use crate::utils::DurationDisplayImprecise;
+
+use super::dependency_graph::Node;
+use super::{AnalysisOutput, EventAnalysis};
+
+/// How far into the past are the busy intervals of a thread remembered.
+///
+/// Blocking that happened earlier than this before the callback start is not attributed.
+const BUSY_HISTORY_NS: i64 = 10_000_000_000;
+
+/// Executor thread identified by its hostname and thread ID.
+type ThreadKey = (String, u32);
+
+/// Blocking callback and the blocked callback on a thread.
+type BlockingKey = (ThreadKey, Idx<Callback>, Idx<Callback>);
+
+#[derive(Debug, Clone)]
+struct BusyInterval {
+    start: Time,
+    end: Time,
+    callback: Idx<Callback>,
+}
+
+#[derive(Debug)]
+struct StarvationEvent {
+    thread: ThreadKey,
+    callback: Idx<Callback>,
+    ready_time: Time,
+    /// Publication time of the triggering message, if it was published on the same host.
+    publication_time: Option<Time>,
+    start_time: Time,
+    blocked: i64,
+    blocked_by: Vec<(Idx<Callback>, i64)>,
+}
+
+/// Times of a started callback instance needed to report its blocking.
+#[derive(Debug, Clone, Copy)]
+struct ReadyTime {
+    ready: Time,
+    publication: Option<Time>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct BlockingStats {
+    count: usize,
+    total: i64,
+    max: i64,
+}
+
+/// Detects callbacks that were ready to run but had to wait because other callbacks
+/// were executing on the same executor thread.
+///
+/// The ready time of a callback instance is estimated from its trigger:
+/// - Subscription: the time the message was taken by the subscriber (`rmw_take`, or the
+///   rcl/rclcpp take when the rmw one is missing). The transport latency is not counted
+///   as blocked time. The publication time is reported separately if the message was
+///   published on the same host, because the times of other hosts come from a different clock.
+/// - Timer: the latest expected expiration based on the timer period.
+/// - Service: not known, service callbacks are never reported.
+///
+/// Only the time during which another callback was running on the same thread counts
+/// as blocked time.
+#[derive(Debug)]
+pub struct ExecutorStarvation {
+    threshold: i64,
+
+    busy_intervals: HashMap<ThreadKey, VecDeque<BusyInterval>>,
+    timer_last_ready: HashMap<Idx<Callback>, i64>,
+    ready_times: HashMap<Idx<CallbackInstance>, ReadyTime>,
+    publisher_hosts: HashMap<Idx<Publisher>, String>,
+
+    starvation_events: Vec<StarvationEvent>,
+    blocking: HashMap<BlockingKey, BlockingStats>,
+
+    /// Number of starvation events already printed by the rolling statistics.
+    reported_events: usize,
+}
+
+/// Callback that blocked another callback on an executor thread.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockerExport {
+    pub hostname: String,
+    pub tid: u32,
+    pub blocker: RosInterfaceCompleteName,
+    pub count: usize,
+    pub total: i64,
+    pub max: i64,
+}
+
+/// Instance of a callback blocked for at least the threshold.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StarvationEventExport {
+    pub hostname: String,
+    pub tid: u32,
+    pub ready_time: i64,
+    pub publication_time: Option<i64>,
+    pub start_time: i64,
+    pub blocked: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StarvationStats {
+    pub threshold: i64,
+    pub blocked_by: Vec<BlockerExport>,
+    pub events: Vec<StarvationEventExport>,
+}
+
+/// Starvation of a blocked callback stored in the binary bundle.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutorStarvationExport {
+    pub id: usize,
+    pub name: RosInterfaceCompleteName,
+    pub stats: StarvationStats,
+}
+
+impl ExecutorStarvation {
+    /// Create the analysis reporting callbacks blocked for at least `threshold` nanoseconds.
+    pub fn new(threshold: i64) -> Self {
+        Self {
+            threshold,
+            busy_intervals: HashMap::new(),
+            timer_last_ready: HashMap::new(),
+            ready_times: HashMap::new(),
+            publisher_hosts: HashMap::new(),
+            starvation_events: Vec::new(),
+            blocking: HashMap::new(),
+            reported_events: 0,
+        }
+    }
+
+    fn thread_key(context: &Context) -> ThreadKey {
+        (context.hostname().to_owned(), context.vtid())
+    }
+
+    fn record_publisher_host(&mut self, publisher: Idx<Publisher>, context: &Context) {
+        self.publisher_hosts
+            .entry(publisher)
+            .or_insert_with(|| context.hostname().to_owned());
+    }
+
+    fn record_message_publisher_host(
+        &mut self,
+        message: Idx<PublicationMessage>,
+        context: &Context,
+        model: &Model,
+    ) {
+        let publisher = model[message].get_publisher();
+        if let Some(publisher) = publisher {
+            self.record_publisher_host(publisher, context);
+        }
+    }
+
+    /// Estimate the time when the message triggering the callback was ready.
+    ///
+    /// The message is ready once it is taken by the subscriber.
+    fn subscription_ready_time(trigger: CallbackTrigger, model: &Model) -> Option<Time> {
+        let CallbackTrigger::SubscriptionMessage(message) = trigger else {
+            return None;
+        };
+        let message = &model[message];
+
+        message
+            .get_rmw_receive_time()
+            .or_else(|| message.get_rcl_receive_time())
+            .or_else(|| message.get_rclcpp_receive_time())
+    }
+
+    /// Publication time of the message triggering the callback.
+    ///
+    /// Returns `None` if the message was published on a different or unknown host.
+    fn subscription_publication_time(
+        &self,
+        trigger: CallbackTrigger,
+        hostname: &str,
+        model: &Model,
+    ) -> Option<Time> {
+        let CallbackTrigger::SubscriptionMessage(message) = trigger else {
+            return None;
+        };
+        let publication = model.get(model[message].get_publication_message()?)?;
+
+        let same_host = publication.get_publisher().is_some_and(|publisher| {
+            self.publisher_hosts
+                .get(&publisher)
+                .is_some_and(|publisher_host| publisher_host == hostname)
+        });
+        if same_host {
+            publication.get_publication_time()
+        } else {
+            None
+        }
+    }
+
+    /// Estimate the time when the timer expired.
+    ///
+    /// The timer is assumed to keep its phase. The first instance is used as the phase
+    /// reference, which is moved whenever the timer starts earlier than expected.
+    fn timer_ready_time(&mut self, callback: Idx<Callback>, period: i64, start_time: Time) -> Time {
+        let start = start_time.timestamp_nanos();
+        let ready = match self.timer_last_ready.get(&callback) {
+            Some(&last_ready) if period > 0 => {
+                let expected = last_ready + period;
+                if expected > start {
+                    start
+                } else {
+                    // Skip periods that were missed completely
+                    expected + (start - expected) / period * period
+                }
+            }
+            _ => start,
+        };
+
+        self.timer_last_ready.insert(callback, ready);
+        Time::from_nanos(ready)
+    }
+
+    fn start_callback(&mut self, instance_idx: Idx<CallbackInstance>, model: &Model) {
+        let instance = &model[instance_idx];
+        let start_time = instance.get_start_time();
+        let callback = instance.get_callback();
+
+        let (ready_time, publication_time) = match instance.get_trigger() {
+            trigger @ CallbackTrigger::SubscriptionMessage(_) => (
+                Self::subscription_ready_time(trigger, model),
+                self.subscription_publication_time(trigger, model[callback].get_hostname(), model),
+            ),
+            CallbackTrigger::Timer(timer) => {
+                let period = model[timer].get_period();
+                let ready_time = Option::from(period)
+                    .map(|period| self.timer_ready_time(callback, period, start_time));
+                (ready_time, None)
+            }
+            CallbackTrigger::Service(_) => (None, None),
+        };
+
+        if let Some(ready_time) = ready_time {
+            self.ready_times.insert(
+                instance_idx,
+                ReadyTime {
+                    ready: ready_time.min(start_time),
+                    publication: publication_time,
+                },
+            );
+        }
+    }
+
+    fn end_callback(
+        &mut self,
+        instance_idx: Idx<CallbackInstance>,
+        context: &Context,
+        model: &Model,
+    ) {
+        let instance = &model[instance_idx];
+        let start_time = instance.get_start_time();
+        let end_time = instance
+            .get_end_time()
+            .expect("Callback instance should be ended in callback_end");
+        let callback = instance.get_callback();
+
+        let thread = Self::thread_key(context);
+        let ready_time = self.ready_times.remove(&instance_idx);
+
+        self.report_blocking(model, &thread, callback, ready_time, start_time);
+
+        let history = self.busy_intervals.entry(thread).or_default();
+        while history.front().is_some_and(|interval| {
+            interval.end.timestamp_nanos() < start_time.timestamp_nanos() - BUSY_HISTORY_NS
+        }) {
+            history.pop_front();
+        }
+        history.push_back(BusyInterval {
+            start: start_time,
+            end: end_time,
+            callback,
+        });
+    }
+
+    /// Record the callbacks that blocked `callback` on `thread` if it was blocked
+    /// for at least the threshold.
+    fn report_blocking(
+        &mut self,
+        model: &Model,
+        thread: &ThreadKey,
+        callback: Idx<Callback>,
+        ready_time: Option<ReadyTime>,
+        start_time: Time,
+    ) {
+        let Some(ReadyTime {
+            ready: ready_time,
+            publication: publication_time,
+        }) = ready_time
+        else {
+            return;
+        };
+        let blocked_by = self
+            .busy_intervals
+            .get(thread)
+            .map_or_else(Vec::new, |history| {
+                Self::blocking_callbacks(history, callback, ready_time, start_time)
+            });
+
+        let blocked: i64 = blocked_by.iter().map(|(_, duration)| duration).sum();
+        if blocked < self.threshold || blocked == 0 {
+            return;
+        }
+
+        log::debug!(target: "executor_starvation",
+            "Callback {} was blocked for {} on thread {}@{} [ready={ready_time}, start={start_time}]",
+            DisplayCallbackSummary(model, &model[callback]),
+            DurationDisplayImprecise(blocked),
+            thread.1,
+            thread.0,
+        );
+
+        for (blocker, duration) in &blocked_by {
+            let stats = self
+                .blocking
+                .entry((thread.clone(), *blocker, callback))
+                .or_default();
+            stats.count += 1;
+            stats.total += duration;
+            stats.max = stats.max.max(*duration);
+        }
+
+        self.starvation_events.push(StarvationEvent {
+            thread: thread.clone(),
+            callback,
+            ready_time,
+            publication_time,
+            start_time,
+            blocked,
+            blocked_by,
+        });
+    }
+
+    /// Sum the time other callbacks were running on the thread between `ready_time`
+    /// and `start_time`.
+    fn blocking_callbacks(
+        history: &VecDeque<BusyInterval>,
+        callback: Idx<Callback>,
+        ready_time: Time,
+        start_time: Time,
+    ) -> Vec<(Idx<Callback>, i64)> {
+        let mut blocked_by: Vec<(Idx<Callback>, i64)> = Vec::new();
+
+        // Callbacks on a single thread do not overlap, so the intervals are ordered by their end.
+        for interval in history.iter().rev() {
+            if interval.end <= ready_time {
+                break;
+            }
+            if interval.callback == callback {
+                continue;
+            }
+
+            let overlap = interval.end.min(start_time).timestamp_nanos()
+                - interval.start.max(ready_time).timestamp_nanos();
+            if overlap <= 0 {
+                continue;
+            }
+
+            if let Some((_, duration)) = blocked_by
+                .iter_mut()
+                .find(|(blocker, _)| blocker == &interval.callback)
+            {
+                *duration += overlap;
+            } else {
+                blocked_by.push((interval.callback, overlap));
+            }
+        }
+
+        blocked_by.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        blocked_by
+    }
+
+    /// Starvation of the blocked callbacks for the binary bundle.
+    ///
+    /// When the dependency graph is exported too, the callbacks have the ids of its nodes.
+    /// Otherwise, the ids are assigned in the order of the callback names.
+    pub fn starvation(
+        &self,
+        model: &Model,
+        node_ids: Option<&HashMap<Node, usize>>,
+    ) -> Vec<ExecutorStarvationExport> {
+        let mut stats: HashMap<Idx<Callback>, StarvationStats> = HashMap::new();
+        let mut new_stats = || StarvationStats {
+            threshold: self.threshold,
+            blocked_by: Vec::new(),
+            events: Vec::new(),
+        };
+
+        for (&(ref thread, blocker, blocked), blocking) in self.sorted_blocking() {
+            stats
+                .entry(blocked)
+                .or_insert_with(&mut new_stats)
+                .blocked_by
+                .push(BlockerExport {
+                    hostname: thread.0.clone(),
+                    tid: thread.1,
+                    blocker: callback_name(model, blocker),
+                    count: blocking.count,
+                    total: blocking.total,
+                    max: blocking.max,
+                });
+        }
+        for event in &self.starvation_events {
+            stats
+                .entry(event.callback)
+                .or_insert_with(&mut new_stats)
+                .events
+                .push(StarvationEventExport {
+                    hostname: event.thread.0.clone(),
+                    tid: event.thread.1,
+                    ready_time: event.ready_time.timestamp_nanos(),
+                    publication_time: event.publication_time.map(|time| time.timestamp_nanos()),
+                    start_time: event.start_time.timestamp_nanos(),
+                    blocked: event.blocked,
+                });
+        }
+
+        let mut starvation: Vec<_> = stats
+            .into_iter()
+            .map(|(callback, stats)| {
+                (
+                    node_ids.and_then(|ids| ids.get(&Node::Callback(callback)).copied()),
+                    callback_name(model, callback),
+                    stats,
+                )
+            })
+            .collect();
+        if node_ids.is_none() {
+            starvation.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+        }
+
+        starvation
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (id, name, stats))| {
+                Some(ExecutorStarvationExport {
+                    id: if node_ids.is_some() { id? } else { index },
+                    name,
+                    stats,
+                })
+            })
+            .collect()
+    }
+
+    fn sorted_blocking(&self) -> Vec<(&BlockingKey, &BlockingStats)> {
+        let mut blocking: Vec<_> = self.blocking.iter().collect();
+        blocking.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total));
+        blocking
+    }
+
+    fn log_warnings(&self, model: &Model) {
+        for (&(ref thread, blocker, blocked), stats) in self.sorted_blocking() {
+            log::warn!(target: "executor_starvation",
+                "Callback {} blocked callback {} on thread {}@{} {} times (total {}, max {})",
+                DisplayCallbackSummary(model, &model[blocker]),
+                DisplayCallbackSummary(model, &model[blocked]),
+                thread.1,
+                thread.0,
+                stats.count,
+                DurationDisplayImprecise(stats.total),
+                DurationDisplayImprecise(stats.max),
+            );
+        }
+    }
+}
+
+impl EventAnalysis for ExecutorStarvation {
+    fn initialize(&mut self) {
+        self.busy_intervals.clear();
+        self.timer_last_ready.clear();
+        self.ready_times.clear();
+        self.publisher_hosts.clear();
+        self.starvation_events.clear();
+        self.blocking.clear();
+        self.reported_events = 0;
+    }
+
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        match &full_event.event {
+            Event::Ros2(ros2::Event::CallbackStart(event)) => {
+                self.start_callback(event.callback, model);
+            }
+            Event::Ros2(ros2::Event::CallbackEnd(event)) => {
+                self.end_callback(event.callback, &full_event.context, model);
+            }
+            Event::Ros2(ros2::Event::RmwPublisherInit(ros2::RmwPublisherInit { publisher }))
+            | Event::Ros2(ros2::Event::RclPublisherInit(ros2::RclPublisherInit { publisher })) => {
+                self.record_publisher_host(*publisher, &full_event.context);
+            }
+            Event::Ros2(ros2::Event::RclPublish(ros2::RclPublish { message }))
+            | Event::Ros2(ros2::Event::RmwPublish(ros2::RmwPublish { message })) => {
+                self.record_message_publisher_host(*message, &full_event.context, model);
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self, model: &Model) {
+        self.busy_intervals.clear();
+        self.ready_times.clear();
+
+        self.log_warnings(model);
+    }
+
+    fn print_rolling_stats(&mut self, model: &Model) {
+        println!("Executor starvation since the last report:");
+
+        // Callback -> (number of starvation events, longest blocked time)
+        let mut blocked: HashMap<Idx<Callback>, (usize, i64)> = HashMap::new();
+        for event in &self.starvation_events[self.reported_events..] {
+            let (count, max) = blocked.entry(event.callback).or_default();
+            *count += 1;
+            *max = (*max).max(event.blocked);
+        }
+        self.reported_events = self.starvation_events.len();
+
+        for (callback, (count, max)) in blocked {
+            println!(
+                "  {}: blocked {count} times, max {}",
+                DisplayCallbackSummary(model, &model[callback]),
+                DurationDisplayImprecise(max)
+            );
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BlockerEntry {
+    callback: String,
+    blocked_for: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct StarvationEventEntry {
+    hostname: String,
+    tid: u32,
+    callback: String,
+    ready_time: i64,
+    publication_time: Option<i64>,
+    start_time: i64,
+    blocked: i64,
+    blocked_by: Vec<BlockerEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct BlockingEntry {
+    hostname: String,
+    tid: u32,
+    blocker: String,
+    blocked: String,
+    count: usize,
+    total: i64,
+    max: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ExecutorStarvationOutput {
+    threshold: i64,
+    blocking: Vec<BlockingEntry>,
+    events: Vec<StarvationEventEntry>,
+}
+
+fn callback_summary(model: &Model, callback: Idx<Callback>) -> String {
+    DisplayCallbackSummary(model, &model[callback]).to_string()
+}
+
+impl AnalysisOutput for ExecutorStarvation {
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
+        let blocking = self
+            .sorted_blocking()
+            .into_iter()
+            .map(|(&(ref thread, blocker, blocked), stats)| BlockingEntry {
+                hostname: thread.0.clone(),
+                tid: thread.1,
+                blocker: callback_summary(model, blocker),
+                blocked: callback_summary(model, blocked),
+                count: stats.count,
+                total: stats.total,
+                max: stats.max,
+            })
+            .collect();
+
+        let events = self
+            .starvation_events
+            .iter()
+            .map(|event| StarvationEventEntry {
+                hostname: event.thread.0.clone(),
+                tid: event.thread.1,
+                callback: callback_summary(model, event.callback),
+                ready_time: event.ready_time.timestamp_nanos(),
+                publication_time: event.publication_time.map(|time| time.timestamp_nanos()),
+                start_time: event.start_time.timestamp_nanos(),
+                blocked: event.blocked,
+                blocked_by: event
+                    .blocked_by
+                    .iter()
+                    .map(|&(blocker, duration)| BlockerEntry {
+                        callback: callback_summary(model, blocker),
+                        blocked_for: duration,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        serde_json::to_writer(
+            file,
+            &ExecutorStarvationOutput {
+                threshold: self.threshold,
+                blocking,
+                events,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::{Subscriber, SubscriptionMessage, Timer};
+
+    fn callback(model: &mut Model, handle: u64) -> Idx<Callback> {
+        let timer = model.add(Timer::new(handle));
+        model.add(Callback::new_timer(handle, timer, "host".to_owned()))
+    }
+
+    fn interval(start: i64, end: i64, callback: Idx<Callback>) -> BusyInterval {
+        BusyInterval {
+            start: Time::from_nanos(start),
+            end: Time::from_nanos(end),
+            callback,
+        }
+    }
+
+    fn ready(ready: i64) -> Option<ReadyTime> {
+        Some(ReadyTime {
+            ready: Time::from_nanos(ready),
+            publication: None,
+        })
+    }
+
+    #[test]
+    fn test_blocking_callbacks_sums_overlaps() {
+        let mut model = Model::default();
+        let blocked = callback(&mut model, 1);
+        let first = callback(&mut model, 2);
+        let second = callback(&mut model, 3);
+        let history = VecDeque::from([
+            interval(0, 50, first),
+            interval(60, 90, second),
+            interval(100, 120, blocked),
+            interval(130, 180, first),
+        ]);
+
+        let blocked_by = ExecutorStarvation::blocking_callbacks(
+            &history,
+            blocked,
+            Time::from_nanos(40),
+            Time::from_nanos(170),
+        );
+
+        // The own interval (100..120) is excluded, the others are clipped to 40..170
+        assert_eq!(blocked_by, vec![(first, 10 + 40), (second, 30)]);
+    }
+
+    #[test]
+    fn test_blocking_callbacks_ignores_intervals_before_ready_time() {
+        let mut model = Model::default();
+        let blocked = callback(&mut model, 1);
+        let other = callback(&mut model, 2);
+        let history = VecDeque::from([interval(0, 50, other), interval(60, 70, other)]);
+
+        assert!(
+            ExecutorStarvation::blocking_callbacks(
+                &history,
+                blocked,
+                Time::from_nanos(70),
+                Time::from_nanos(100),
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_blocked_time_below_threshold_is_not_reported() {
+        let mut model = Model::default();
+        let mut analysis = ExecutorStarvation::new(20);
+        let blocked = callback(&mut model, 1);
+        let other = callback(&mut model, 2);
+        let thread: ThreadKey = ("host".to_owned(), 1);
+        analysis
+            .busy_intervals
+            .insert(thread.clone(), VecDeque::from([interval(0, 10, other)]));
+
+        analysis.report_blocking(&model, &thread, blocked, ready(0), Time::from_nanos(10));
+        assert!(analysis.starvation_events.is_empty());
+        assert!(analysis.blocking.is_empty());
+
+        analysis
+            .busy_intervals
+            .insert(thread.clone(), VecDeque::from([interval(0, 30, other)]));
+        analysis.report_blocking(&model, &thread, blocked, ready(0), Time::from_nanos(30));
+        assert_eq!(analysis.starvation_events.len(), 1);
+        assert_eq!(analysis.starvation_events[0].blocked, 30);
+        let stats = analysis.blocking[&(thread, other, blocked)];
+        assert_eq!((stats.count, stats.total, stats.max), (1, 30, 30));
+    }
+
+    #[test]
+    fn test_starvation_export() {
+        let mut model = Model::default();
+        let mut analysis = ExecutorStarvation::new(0);
+        let blocked = callback(&mut model, 1);
+        let other = callback(&mut model, 2);
+        let thread: ThreadKey = ("host".to_owned(), 1);
+        analysis
+            .busy_intervals
+            .insert(thread.clone(), VecDeque::from([interval(0, 30, other)]));
+        analysis.report_blocking(&model, &thread, blocked, ready(0), Time::from_nanos(30));
+        analysis.report_blocking(&model, &thread, blocked, ready(10), Time::from_nanos(40));
+
+        let export = analysis.starvation(&model, None);
+        assert_eq!(export.len(), 1);
+        assert_eq!(export[0].id, 0);
+        assert_eq!(export[0].name, callback_name(&model, blocked));
+
+        let stats = &export[0].stats;
+        assert_eq!(stats.blocked_by.len(), 1);
+        assert_eq!(stats.blocked_by[0].blocker, callback_name(&model, other));
+        assert_eq!(
+            (stats.blocked_by[0].count, stats.blocked_by[0].total),
+            (2, 30 + 20)
+        );
+        assert_eq!(
+            stats
+                .events
+                .iter()
+                .map(|event| (event.ready_time, event.blocked))
+                .collect::<Vec<_>>(),
+            vec![(0, 30), (10, 20)]
+        );
+    }
+
+    #[test]
+    fn test_subscription_ready_time_is_take_time() {
+        let mut model = Model::default();
+        let mut analysis = ExecutorStarvation::new(0);
+        let publisher = model.add(Publisher::default());
+        let subscriber = model.add(Subscriber::default());
+        analysis
+            .publisher_hosts
+            .insert(publisher, "host".to_owned());
+
+        let mut publication = PublicationMessage::new(1);
+        publication.set_publisher(publisher);
+        publication.rcl_publish(Time::from_nanos(100));
+        let mut message = SubscriptionMessage::new(2);
+        message.rmw_take_matched(subscriber, model.add(publication), Time::from_nanos(150));
+        message.rcl_take(Time::from_nanos(160)).unwrap();
+        let trigger = CallbackTrigger::SubscriptionMessage(model.add(message));
+
+        // The transport latency from the publication is not counted as blocked time
+        assert_eq!(
+            ExecutorStarvation::subscription_ready_time(trigger, &model),
+            Some(Time::from_nanos(150))
+        );
+        assert_eq!(
+            analysis.subscription_publication_time(trigger, "host", &model),
+            Some(Time::from_nanos(100))
+        );
+        assert_eq!(
+            analysis.subscription_publication_time(trigger, "other", &model),
+            None
+        );
+    }
+
+    #[test]
+    fn test_timer_ready_time() {
+        let mut model = Model::default();
+        let mut analysis = ExecutorStarvation::new(0);
+        let timer = callback(&mut model, 1);
+        let ready = |analysis: &mut ExecutorStarvation, start| {
+            analysis
+                .timer_ready_time(timer, 100, Time::from_nanos(start))
+                .timestamp_nanos()
+        };
+
+        // The first instance sets the phase
+        assert_eq!(ready(&mut analysis, 1_000), 1_000);
+        // Started late, ready at the expected expiration
+        assert_eq!(ready(&mut analysis, 1_130), 1_100);
+        // Two periods were skipped, ready at the latest expiration
+        assert_eq!(ready(&mut analysis, 1_420), 1_400);
+        // Started earlier than expected, the phase is moved
+        assert_eq!(ready(&mut analysis, 1_450), 1_450);
+        assert_eq!(ready(&mut analysis, 1_560), 1_550);
+    }
+}
@@ -1,75 +1,64 @@
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
 
 use serde::Serialize;
 
-use crate::analysis::utils::DisplayDurationStats;
-use crate::model::display::get_node_name_from_weak;
-use crate::model::{Publisher, Subscriber, SubscriptionMessage};
+use crate::analysis::utils::{DisplayDurationStats, RollingReport};
+use crate::model::display::get_node_name;
+use crate::model::{CallbackTrigger, Idx, Model, Node, Publisher, Subscriber, SubscriptionMessage};
 use crate::processed_events::{Event, FullEvent, ros2};
 use crate::utils::{DurationDisplayImprecise, Known};
 
-use super::{AnalysisOutput, ArcMutWrapper, EventAnalysis};
+use super::{AnalysisOutput, EventAnalysis};
 
-type SubPubKey = (ArcMutWrapper<Subscriber>, Option<ArcMutWrapper<Publisher>>);
+type SubPubKey = (Idx<Subscriber>, Option<Idx<Publisher>>);
 pub struct MessageLatency {
-    messages: HashSet<ArcMutWrapper<SubscriptionMessage>>,
+    /// Messages waiting for the `rclcpp_take` event by their subscriber.
+    ///
+    /// At most [`Subscriber::TAKEN_MESSAGES_MAX`] messages are kept per subscriber,
+    /// older messages are accounted for with the receive time known so far.
+    pending_messages: HashMap<Idx<Subscriber>, VecDeque<Idx<SubscriptionMessage>>>,
     latencies: HashMap<SubPubKey, Vec<i64>>,
+
+    /// Number of latencies already printed by the rolling statistics.
+    reported: RollingReport<SubPubKey>,
 }
 
 #[derive(Debug)]
 pub struct MessageLatencyStats {
     topic: String,
-    subscriber: Arc<Mutex<Subscriber>>,
-    publisher: Option<Arc<Mutex<Publisher>>>,
+    subscriber: Idx<Subscriber>,
+    publisher: Option<Idx<Publisher>>,
     latencies: Vec<i64>,
 }
 
-impl PartialEq for MessageLatencyStats {
-    fn eq(&self, other: &Self) -> bool {
-        self.partial_cmp(other) == Some(Ordering::Equal)
-    }
-}
-
-impl PartialOrd for MessageLatencyStats {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+impl MessageLatencyStats {
+    /// Order the statistics by topic and then by the name of the subscriber node.
+    fn compare(&self, other: &Self, model: &Model) -> Option<Ordering> {
         self.topic.partial_cmp(&other.topic).and_then(|ord| {
             if ord != Ordering::Equal {
                 return Some(ord);
             }
 
-            if Arc::ptr_eq(&self.subscriber, &other.subscriber) {
+            if self.subscriber == other.subscriber {
                 return Some(Ordering::Equal);
             }
-            let sub1 = self.subscriber.lock().unwrap();
-            let sub2 = other.subscriber.lock().unwrap();
 
-            let node1 = sub1.get_node();
-            let node2 = sub2.get_node();
+            let node1 = model[self.subscriber].get_node();
+            let node2 = model[other.subscriber].get_node();
 
             let (node1, node2) = match (node1, node2) {
                 (Known::Known(_), Known::Unknown) => return Some(Ordering::Greater),
                 (Known::Unknown, Known::Known(_)) => return Some(Ordering::Less),
                 (Known::Unknown, Known::Unknown) => return None,
-                (Known::Known(node1), Known::Known(node2)) => (node1.get_arc(), node2.get_arc()),
+                (Known::Known(node1), Known::Known(node2)) => (node1, node2),
             };
 
-            let (node1, node2) = match (node1, node2) {
-                (Some(_), None) => return Some(Ordering::Greater),
-                (None, Some(_)) => return Some(Ordering::Less),
-                (None, None) => return None,
-                (Some(node1), Some(node2)) => (node1, node2),
-            };
-
-            if Arc::ptr_eq(&node1, &node2) {
+            if node1 == node2 {
                 return Some(Ordering::Equal);
             }
 
-            let node1 = node1.lock().unwrap();
-            let node2 = node2.lock().unwrap();
-
-            match (node1.get_name(), node2.get_name()) {
+            match (model[node1].get_name(), model[node2].get_name()) {
                 (Known::Known(_), Known::Unknown) => Some(Ordering::Greater),
                 (Known::Unknown, Known::Known(_)) => Some(Ordering::Less),
                 (Known::Unknown, Known::Unknown) => None,
@@ -82,37 +71,53 @@ impl PartialOrd for MessageLatencyStats {
 impl MessageLatency {
     pub fn new() -> Self {
         Self {
-            messages: HashSet::new(),
+            pending_messages: HashMap::new(),
             latencies: HashMap::new(),
+            reported: RollingReport::default(),
         }
     }
 
-    fn add_message(&mut self, message: Arc<Mutex<SubscriptionMessage>>) {
-        self.messages.insert(message.into());
+    fn add_message(&mut self, message: Idx<SubscriptionMessage>, model: &Model) {
+        let Some(subscriber) = model[message].get_subscriber() else {
+            // The message is missing the subscriber. The latency series cannot be identified.
+            return;
+        };
+
+        let pending = self.pending_messages.entry(subscriber).or_default();
+        pending.push_back(message);
+        if pending.len() > Subscriber::TAKEN_MESSAGES_MAX {
+            let oldest = pending.pop_front().unwrap();
+            self.record_latency(oldest, model);
+        }
     }
 
     fn calculate_latency_and_get_publisher(
         message: &SubscriptionMessage,
-    ) -> (Option<i64>, Option<ArcMutWrapper<Publisher>>) {
+        model: &Model,
+    ) -> (Option<i64>, Option<Idx<Publisher>>) {
         let receive_time = message
             .get_receive_time()
             .expect("Receive time should be known");
-        let (send_time, publisher) =
-            if let Some(publication_message) = message.get_publication_message() {
-                let publication_message = publication_message.lock().unwrap();
-                let send_time = publication_message
-                    .get_publication_time()
-                    .expect("Publication time should be known");
-                let publisher = publication_message.get_publisher().map(Into::into);
-
-                (Some(send_time), publisher)
-            } else if let Some(publication_timestamp) = message.get_sender_timestamp() {
-                // If the publication message is not available, use the sender timestamp
-                (Some(publication_timestamp), None)
-            } else {
-                log::warn!("No publication message or timestamp found for message {message:?}");
-                (None, None)
+        let (send_time, publisher) = if let Some(publication_message) =
+            message.get_publication_message()
+        {
+            let Some(publication_message) = model.get(publication_message) else {
+                log::debug!("Publication of message {message:?} was evicted before it was taken");
+                return (None, None);
             };
+            let send_time = publication_message
+                .get_publication_time()
+                .expect("Publication time should be known");
+            let publisher = publication_message.get_publisher();
+
+            (Some(send_time), publisher)
+        } else if let Some(publication_timestamp) = message.get_sender_timestamp() {
+            // If the publication message is not available, use the sender timestamp
+            (Some(publication_timestamp), None)
+        } else {
+            log::warn!("No publication message or timestamp found for message {message:?}");
+            (None, None)
+        };
 
         send_time.inspect(|send_time| {
             assert!(*send_time <= receive_time);
@@ -124,71 +129,73 @@ impl MessageLatency {
         (latency, publisher)
     }
 
-    fn remove_message(&mut self, message: Arc<Mutex<SubscriptionMessage>>) {
-        let message = message.into();
-        if self.messages.remove(&message) {
-            let message = message.0.lock().unwrap();
-            let (latency_ns, publisher) = Self::calculate_latency_and_get_publisher(&message);
+    fn record_latency(&mut self, message: Idx<SubscriptionMessage>, model: &Model) {
+        let Some(message) = model.get(message) else {
+            log::debug!("Message {message:?} was released before its latency was recorded");
+            return;
+        };
+        let (latency_ns, publisher) = Self::calculate_latency_and_get_publisher(message, model);
+
+        let Some(subscriber) = message.get_subscriber() else {
+            // The message is missing the subscriber. The latency series cannot be identified.
+            return;
+        };
+        let Some(latency_ns) = latency_ns else {
+            return;
+        };
 
-            if message.get_subscriber().is_none() {
-                // The message is missing the subscriber. The latency series cannot be identified.
-                return;
-            }
+        self.latencies
+            .entry((subscriber, publisher))
+            .or_default()
+            .push(latency_ns);
+    }
 
-            self.latencies
-                .entry((message.get_subscriber().unwrap().into(), publisher))
-                .or_default()
-                .push(latency_ns.unwrap());
+    fn remove_message(&mut self, message: Idx<SubscriptionMessage>, model: &Model) {
+        let Some(subscriber) = model[message].get_subscriber() else {
+            return;
+        };
+
+        let Some(pending) = self.pending_messages.get_mut(&subscriber) else {
+            return;
+        };
+        if let Some(position) = pending.iter().position(|&pending| pending == message) {
+            pending.remove(position);
+            self.record_latency(message, model);
         }
     }
 
-    fn remove_remaining_messages(&mut self) {
-        for message in self.messages.drain() {
-            let message = message.0.lock().unwrap();
-            let (latency_ns, publisher) = Self::calculate_latency_and_get_publisher(&message);
-
-            if message.get_subscriber().is_none() {
-                // The message is missing the subscriber. The latency series cannot be identified.
-                continue;
-            }
-
-            self.latencies
-                .entry((message.get_subscriber().unwrap().into(), publisher))
-                .or_default()
-                .push(latency_ns.unwrap());
+    fn remove_remaining_messages(&mut self, model: &Model) {
+        let pending_messages = std::mem::take(&mut self.pending_messages);
+        for message in pending_messages.into_values().flatten() {
+            self.record_latency(message, model);
         }
     }
 
-    pub fn calculate_stats(&self) -> Vec<MessageLatencyStats> {
+    pub fn calculate_stats(&self, model: &Model) -> Vec<MessageLatencyStats> {
         self.latencies
             .iter()
-            .map(|((subscriber_arc, publisher_arc), latencies)| {
-                let subscriber = subscriber_arc.0.lock().unwrap();
-                let topic = subscriber.get_topic();
-
-                MessageLatencyStats {
-                    topic: topic.to_string(),
-                    subscriber: subscriber_arc.0.clone(),
-                    publisher: publisher_arc.as_ref().map(|p| p.0.clone()),
+            .map(
+                |(&(subscriber, publisher), latencies)| MessageLatencyStats {
+                    topic: model[subscriber].get_topic().to_string(),
+                    subscriber,
+                    publisher,
                     latencies: latencies.clone(),
-                }
-            })
+                },
+            )
             .collect()
     }
 
-    pub(crate) fn print_stats(&self) {
+    pub(crate) fn print_stats(&self, model: &Model) {
         println!("Message latency statistics:");
-        let mut stats = self.calculate_stats();
-        stats.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let mut stats = self.calculate_stats(model);
+        stats.sort_unstable_by(|a, b| a.compare(b, model).unwrap_or(Ordering::Equal));
         for (i, stat) in stats.iter().enumerate() {
-            let subscriber = stat.subscriber.lock().unwrap();
             let topic = &stat.topic;
-            let publisher = stat.publisher.as_ref().map(|p| p.lock().unwrap());
 
             println!("- [{i:4}] Topic {topic}:");
-            println!("    Subscriber: {subscriber:#}");
-            if let Some(publisher) = publisher {
-                println!("    Publisher: {publisher}");
+            println!("    Subscriber: {:#}", model.display(&stat.subscriber));
+            if let Some(publisher) = &stat.publisher {
+                println!("    Publisher: {}", model.display(publisher));
             } else {
                 println!("    Publisher: Unknown");
             }
@@ -206,85 +213,106 @@ impl MessageLatency {
 
 impl EventAnalysis for MessageLatency {
     fn initialize(&mut self) {
-        self.messages.clear();
+        self.pending_messages.clear();
         self.latencies.clear();
+        self.reported.clear();
     }
 
-    fn process_event(&mut self, event: &FullEvent) {
+    fn process_event(&mut self, event: &FullEvent, model: &Model) {
         match &event.event {
-            Event::Ros2(ros2::Event::RmwTake(event)) => {
-                self.add_message(event.message.clone());
+            Event::Ros2(ros2::Event::RmwTake(event)) if event.taken => {
+                self.add_message(event.message, model);
             }
-            Event::Ros2(ros2::Event::RclTake(event)) => {
-                let message = event.message.clone();
-                if event.is_new {
-                    self.add_message(message);
-                } else {
-                    assert!(self.messages.contains(&message.into()));
-                }
+            Event::Ros2(ros2::Event::RclTake(event)) if event.is_new => {
+                self.add_message(event.message, model);
             }
             Event::Ros2(ros2::Event::RclCppTake(event)) => {
-                let message = event.message.clone();
                 if event.is_new {
-                    self.add_message(message.clone());
-                } else {
-                    assert!(self.messages.contains(&message.clone().into()));
+                    self.add_message(event.message, model);
                 }
 
-                self.remove_message(message);
+                self.remove_message(event.message, model);
+            }
+            Event::Ros2(ros2::Event::CallbackStart(event)) => {
+                // Messages without the RclCppTake event are taken once they trigger the callback.
+                // The message is released from the model after the callback ends.
+                if let CallbackTrigger::SubscriptionMessage(message) =
+                    model[event.callback].get_trigger()
+                {
+                    self.remove_message(message, model);
+                }
             }
 
             _ => {}
         }
     }
 
-    fn finalize(&mut self) {
+    fn finalize(&mut self, model: &Model) {
         // Make sure all messages are accounted for. The remaining messages are
         // missing the RclCppTake event.
-        self.remove_remaining_messages();
+        self.remove_remaining_messages(model);
+    }
+
+    fn print_rolling_stats(&mut self, model: &Model) {
+        println!("Message latency since the last report:");
+        for (key, latencies) in &self.latencies {
+            let new_latencies = self.reported.new_values(key, latencies);
+            if new_latencies.is_empty() {
+                continue;
+            }
+
+            let subscriber = &model[key.0];
+            let node = node_name_or_unknown(model, subscriber.get_node());
+            println!(
+                "  {} [{node}]: {}",
+                subscriber.get_topic(),
+                DisplayDurationStats::with_comma(new_latencies)
+            );
+        }
     }
 }
 
 impl AnalysisOutput for MessageLatency {
-    fn write_json(&self, file: &mut std::io::BufWriter<std::fs::File>) -> serde_json::Result<()> {
-        let stats = self.calculate_stats();
-        let stats: Vec<MessageLatencyExport> = stats.into_iter().map(Into::into).collect();
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
+        let stats = self.calculate_stats(model);
+        let stats: Vec<MessageLatencyExport> = stats
+            .into_iter()
+            .map(|stats| MessageLatencyExport::new(stats, model))
+            .collect();
         serde_json::to_writer(file, &stats)
     }
 }
 
 #[derive(Debug, Serialize)]
-struct MessageLatencyExport {
-    topic: String,
-    subscriber_node: String,
-    publisher_node: String,
-    latencies: Vec<i64>,
+pub struct MessageLatencyExport {
+    pub(crate) topic: String,
+    pub(crate) subscriber_node: String,
+    pub(crate) publisher_node: String,
+    pub(crate) latencies: Vec<i64>,
 }
 
-impl From<MessageLatencyStats> for MessageLatencyExport {
-    fn from(value: MessageLatencyStats) -> Self {
-        let subscriber = value.subscriber.lock().unwrap();
-        let subscriber_node = subscriber
-            .get_node()
-            .map(|node| get_node_name_from_weak(&node.get_weak()).unwrap_or("Unknown".to_string()))
-            .unwrap_or("Unknown".to_string());
-        let publisher_node = value.publisher.as_ref().map_or_else(
+impl MessageLatencyExport {
+    pub(crate) fn new(stats: MessageLatencyStats, model: &Model) -> Self {
+        let subscriber_node = node_name_or_unknown(model, model[stats.subscriber].get_node());
+        let publisher_node = stats.publisher.map_or_else(
             || "Unknown".to_string(),
-            |p| {
-                let publisher = p.lock().unwrap().get_node();
-                publisher
-                    .map(|node| {
-                        get_node_name_from_weak(&node.get_weak()).unwrap_or("Unknown".to_string())
-                    })
-                    .unwrap_or("Unknown".to_string())
-            },
+            |publisher| node_name_or_unknown(model, model[publisher].get_node()),
         );
 
         Self {
-            topic: value.topic,
+            topic: stats.topic,
             subscriber_node,
             publisher_node,
-            latencies: value.latencies,
+            latencies: stats.latencies,
         }
     }
 }
+
+fn node_name_or_unknown(model: &Model, node: Known<Idx<Node>>) -> String {
+    node.map(|node| get_node_name(model, node).unwrap_or("Unknown".to_string()))
+        .unwrap_or("Unknown".to_string())
+}
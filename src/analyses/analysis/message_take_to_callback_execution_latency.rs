@@ -1,17 +1,19 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
 
-use crate::analysis::utils::DisplayDurationStats;
+use crate::analysis::utils::{DisplayDurationStats, RollingReport};
 use crate::model::display::DisplayCallbackSummary;
-use crate::model::{Callback, CallbackInstance, CallbackTrigger};
+use crate::model::{Callback, CallbackInstance, CallbackTrigger, Idx, Model};
 use crate::processed_events::{Event, FullEvent, ros2};
 use crate::utils::DurationDisplayImprecise;
 
-use super::{AnalysisOutput, ArcMutWrapper, EventAnalysis};
+use super::{AnalysisOutput, EventAnalysis};
 
 #[derive(Debug, Default)]
 pub struct MessageTakeToCallbackLatency {
-    latencies: HashMap<ArcMutWrapper<Callback>, Vec<i64>>,
+    latencies: HashMap<Idx<Callback>, Vec<i64>>,
+
+    /// Number of latencies already printed by the rolling statistics.
+    reported: RollingReport<Idx<Callback>>,
 }
 
 impl MessageTakeToCallbackLatency {
@@ -19,17 +21,17 @@ impl MessageTakeToCallbackLatency {
         Self::default()
     }
 
-    fn process_callback_start(&mut self, callback: Arc<Mutex<CallbackInstance>>) {
-        let callback_instance = callback.lock().unwrap();
+    fn process_callback_start(&mut self, callback: Idx<CallbackInstance>, model: &Model) {
+        let callback_instance = &model[callback];
 
         if let CallbackTrigger::SubscriptionMessage(msg) = callback_instance.get_trigger() {
-            let message = msg.lock().unwrap();
+            let message = &model[msg];
             let receive_time = message.get_receive_time().unwrap();
             let start_time = callback_instance.get_start_time();
             let latency = start_time.timestamp_nanos() - receive_time.timestamp_nanos();
 
             self.latencies
-                .entry(callback_instance.get_callback().into())
+                .entry(callback_instance.get_callback())
                 .or_default()
                 .push(latency);
         } else {
@@ -37,12 +39,13 @@ impl MessageTakeToCallbackLatency {
         }
     }
 
-    pub(crate) fn print_stats(&self) {
+    pub(crate) fn print_stats(&self, model: &Model) {
         println!("Message take to callback execution latency statistics:");
-        for (i, (callback, latencies)) in self.latencies.iter().enumerate() {
-            let callback = callback.0.lock().unwrap();
-
-            println!("- [{i:4}] Callback {}:", DisplayCallbackSummary(&callback));
+        for (i, (&callback, latencies)) in self.latencies.iter().enumerate() {
+            println!(
+                "- [{i:4}] Callback {}:",
+                DisplayCallbackSummary(model, &model[callback])
+            );
             println!("    Call count: {}", latencies.len());
             let display = DisplayDurationStats::with_comma(latencies);
             println!("    Latency: {display}");
@@ -59,17 +62,34 @@ impl MessageTakeToCallbackLatency {
 impl EventAnalysis for MessageTakeToCallbackLatency {
     fn initialize(&mut self) {
         self.latencies.clear();
+        self.reported.clear();
     }
 
-    fn process_event(&mut self, full_event: &FullEvent) {
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
         if let Event::Ros2(ros2::Event::CallbackStart(event)) = &full_event.event {
-            self.process_callback_start(event.callback.clone());
+            self.process_callback_start(event.callback, model);
         }
     }
 
-    fn finalize(&mut self) {
+    fn finalize(&mut self, _model: &Model) {
         // Nothing to do
     }
+
+    fn print_rolling_stats(&mut self, model: &Model) {
+        println!("Message take to callback execution latency since the last report:");
+        for (callback, latencies) in &self.latencies {
+            let new_latencies = self.reported.new_values(callback, latencies);
+            if new_latencies.is_empty() {
+                continue;
+            }
+
+            println!(
+                "  {}: {}",
+                DisplayCallbackSummary(model, &model[*callback]),
+                DisplayDurationStats::with_comma(new_latencies)
+            );
+        }
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -79,18 +99,19 @@ struct ExportEntry {
 }
 
 impl AnalysisOutput for MessageTakeToCallbackLatency {
-    fn write_json(&self, file: &mut std::io::BufWriter<std::fs::File>) -> serde_json::Result<()> {
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
         let latencies: Vec<ExportEntry> = self
             .latencies
             .iter()
             .map(|(callback, latencies)| ExportEntry {
-                topic: callback
-                    .0
-                    .lock()
-                    .unwrap()
+                topic: model[*callback]
                     .get_caller()
                     .unwrap()
-                    .get_caller_as_string()
+                    .get_caller_as_string(model)
                     .unwrap(),
                 latencies: latencies.clone(),
             })
@@ -1,13 +1,11 @@
 use std::fs::File;
-use std::hash::Hash;
 use std::io::BufWriter;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
 
+use crate::model::Model;
 use crate::processed_events::FullEvent;
-use derive_more::derive::From;
 
-mod utils;
+pub(crate) mod utils;
 
 pub mod dependency_graph;
 pub use dependency_graph::DependencyGraph;
@@ -33,6 +31,21 @@ pub use utilization::Utilization;
 pub mod spin_duration;
 pub use spin_duration::SpinDuration;
 
+pub mod executor_starvation;
+pub use executor_starvation::ExecutorStarvation;
+
+pub mod cpu_affinity;
+pub use cpu_affinity::CpuAffinity;
+
+pub mod topic_rate;
+pub use topic_rate::TopicRate;
+
+pub mod timeline;
+pub use timeline::Timeline;
+
+pub mod otlp_spans;
+pub use otlp_spans::OtlpSpans;
+
 pub trait EventAnalysis {
     /// Initialize the analysis
     ///
@@ -40,48 +53,34 @@ pub trait EventAnalysis {
     fn initialize(&mut self);
 
     /// Process an event
-    fn process_event(&mut self, event: &FullEvent);
+    ///
+    /// The objects referenced by the event are resolved in `model`.
+    /// Messages and callback and spin instances are released from the model after the event
+    /// ending their lifecycle, so indices kept for later events are looked up with [`Model::get`].
+    fn process_event(&mut self, event: &FullEvent, model: &Model);
 
     /// Finalize the analysis
     ///
     /// This method is called after all events have been processed
-    fn finalize(&mut self);
+    fn finalize(&mut self, model: &Model);
+
+    /// Print statistics of the events processed since the previous call
+    ///
+    /// This method is called periodically during live analysis.
+    fn print_rolling_stats(&mut self, _model: &Model) {}
 }
 
 pub trait AnalysisOutput {
-    fn write_json(&self, file: &mut BufWriter<File>) -> serde_json::Result<()>;
+    fn write_json(&self, model: &Model, file: &mut BufWriter<File>) -> serde_json::Result<()>;
 }
 
 pub trait AnalysisOutputExt: AnalysisOutput {
-    fn write_json_to_output_dir(&self, path: &Path) -> std::io::Result<()> {
+    fn write_json_to_output_dir(&self, model: &Model, path: &Path) -> std::io::Result<()> {
         std::fs::create_dir_all(path.parent().unwrap())?;
         let out_file = File::create(path)?;
         let mut out_file = BufWriter::new(out_file);
-        self.write_json(&mut out_file).map_err(Into::into)
+        self.write_json(model, &mut out_file).map_err(Into::into)
     }
 }
 
 impl<T: AnalysisOutput> AnalysisOutputExt for T {}
-
-#[derive(Debug, From)]
-struct ArcMutWrapper<T>(Arc<Mutex<T>>);
-
-impl<T> PartialEq for ArcMutWrapper<T> {
-    fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.0, &other.0)
-    }
-}
-
-impl<T> Clone for ArcMutWrapper<T> {
-    fn clone(&self) -> Self {
-        Self(self.0.clone())
-    }
-}
-
-impl<T> Eq for ArcMutWrapper<T> {}
-
-impl<T> Hash for ArcMutWrapper<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        Arc::as_ptr(&self.0).hash(state);
-    }
-}
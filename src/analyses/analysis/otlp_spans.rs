@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+
+use serde::Serialize;
+
+use crate::events_common::Context;
+use crate::model::{
+    Callback, CallbackCaller, CallbackInstance, CallbackTrigger, Idx, Model, Node,
+    PublicationMessage, Publisher,
+};
+use crate::processed_events::{Event, FullEvent, ros2};
+use crate::utils::DisplayDuration;
+
+use super::{AnalysisOutput, EventAnalysis};
+
+/// Process identified by its hostname and process ID.
+type ProcessKey = (String, u32);
+
+#[derive(Debug)]
+struct ProcessInfo {
+    hostname: String,
+    vpid: u32,
+    procname: String,
+}
+
+/// Identification of a span within the exported traces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SpanContext {
+    trace_id: u128,
+    span_id: u64,
+}
+
+/// Kind of a span as defined by OpenTelemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanKind {
+    Internal = 1,
+    Server = 2,
+    Producer = 4,
+    Consumer = 5,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SpanSource {
+    Callback(Idx<Callback>),
+    /// Message from its publication until the start of the callback processing it.
+    Message(Option<Idx<Publisher>>),
+}
+
+#[derive(Debug)]
+struct SpanRecord {
+    context: SpanContext,
+    parent_span_id: Option<u64>,
+    kind: SpanKind,
+    source: SpanSource,
+    /// Index into [`OtlpSpans::processes`].
+    process: usize,
+    tid: u32,
+    start: i64,
+    end: i64,
+    /// Time when the message was taken by the subscriber.
+    take_time: Option<i64>,
+}
+
+/// Message published on a thread, possibly by a running callback.
+#[derive(Debug, Clone, Copy)]
+struct Publication {
+    process: usize,
+    tid: u32,
+    time: i64,
+    publisher: Option<Idx<Publisher>>,
+    /// Span of the callback running on the publishing thread.
+    parent: Option<SpanContext>,
+}
+
+/// Spans of callback instances and message hops in the OpenTelemetry format.
+///
+/// Each callback instance is a span. A callback processing a message is a child
+/// of the span of the message hop, which is a child of the callback that published
+/// the message. The trace ID is thus propagated along the causal chains of callbacks.
+/// Callbacks triggered by timers, services or messages with unknown publication start
+/// a new trace.
+///
+/// The spans are grouped by processes, which are resources with their hostname and PID.
+#[derive(Debug, Default)]
+pub struct OtlpSpans {
+    processes: Vec<ProcessInfo>,
+    process_indices: HashMap<ProcessKey, usize>,
+
+    spans: Vec<SpanRecord>,
+    next_span_id: u64,
+    next_trace_id: u64,
+
+    running: HashMap<(usize, u32), SpanContext>,
+    pending: HashMap<Idx<CallbackInstance>, SpanRecord>,
+    publications: HashMap<Idx<PublicationMessage>, Publication>,
+}
+
+impl OtlpSpans {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn process_index(&mut self, context: &Context) -> usize {
+        let key = (context.hostname().to_owned(), context.vpid());
+        *self.process_indices.entry(key).or_insert_with(|| {
+            self.processes.push(ProcessInfo {
+                hostname: context.hostname().to_owned(),
+                vpid: context.vpid(),
+                procname: context.procname().to_owned(),
+            });
+            self.processes.len() - 1
+        })
+    }
+
+    fn new_span_id(&mut self) -> u64 {
+        self.next_span_id += 1;
+        self.next_span_id
+    }
+
+    /// Create the context of the root span of a new trace starting at `start`.
+    ///
+    /// The start time is a part of the trace ID to avoid collisions between exports.
+    fn new_trace(&mut self, start: i64) -> SpanContext {
+        self.next_trace_id += 1;
+        SpanContext {
+            trace_id: (u128::from(start.cast_unsigned()) << 64) | u128::from(self.next_trace_id),
+            span_id: self.new_span_id(),
+        }
+    }
+
+    /// Create the context of a span that is a child of `parent` or the root of a new trace.
+    fn child_of(&mut self, parent: Option<SpanContext>, start: i64) -> SpanContext {
+        match parent {
+            Some(parent) => SpanContext {
+                trace_id: parent.trace_id,
+                span_id: self.new_span_id(),
+            },
+            None => self.new_trace(start),
+        }
+    }
+
+    fn record_publication(
+        &mut self,
+        message: Idx<PublicationMessage>,
+        context: &Context,
+        time: i64,
+        model: &Model,
+    ) {
+        let process = self.process_index(context);
+        let parent = self.running.get(&(process, context.vtid())).copied();
+        let publisher = model[message].get_publisher();
+
+        // The first layer publishing the message is the closest to the publishing callback
+        self.publications.entry(message).or_insert(Publication {
+            process,
+            tid: context.vtid(),
+            time,
+            publisher,
+            parent,
+        });
+    }
+
+    /// Record the span of the message hop from its publication to the callback starting
+    /// at `start`.
+    ///
+    /// Returns the context of the message span.
+    fn record_message(
+        &mut self,
+        publication: Publication,
+        take_time: Option<i64>,
+        start: i64,
+    ) -> SpanContext {
+        let context = self.child_of(publication.parent, publication.time);
+        self.spans.push(SpanRecord {
+            context,
+            parent_span_id: publication.parent.map(|parent| parent.span_id),
+            kind: SpanKind::Producer,
+            source: SpanSource::Message(publication.publisher),
+            process: publication.process,
+            tid: publication.tid,
+            start: publication.time,
+            end: start,
+            take_time,
+        });
+        context
+    }
+
+    fn start_callback(
+        &mut self,
+        instance_idx: Idx<CallbackInstance>,
+        context: &Context,
+        model: &Model,
+    ) {
+        let process = self.process_index(context);
+        let instance = &model[instance_idx];
+        let start = instance.get_start_time().timestamp_nanos();
+
+        let (kind, parent) = match instance.get_trigger() {
+            CallbackTrigger::SubscriptionMessage(message) => {
+                let message = &model[message];
+                let publication = message
+                    .get_publication_message()
+                    .and_then(|publication| self.publications.get(&publication).copied());
+                let take_time = message
+                    .get_receive_time()
+                    .map(|time| time.timestamp_nanos());
+                let parent = publication
+                    .map(|publication| self.record_message(publication, take_time, start));
+                (SpanKind::Consumer, parent)
+            }
+            CallbackTrigger::Service(_) => (SpanKind::Server, None),
+            CallbackTrigger::Timer(_) => (SpanKind::Internal, None),
+        };
+
+        let span_context = self.child_of(parent, start);
+        self.running.insert((process, context.vtid()), span_context);
+        self.pending.insert(
+            instance_idx,
+            SpanRecord {
+                context: span_context,
+                parent_span_id: parent.map(|parent| parent.span_id),
+                kind,
+                source: SpanSource::Callback(instance.get_callback()),
+                process,
+                tid: context.vtid(),
+                start,
+                end: start,
+                take_time: None,
+            },
+        );
+    }
+
+    fn end_callback(&mut self, instance: Idx<CallbackInstance>, model: &Model) {
+        let Some(mut span) = self.pending.remove(&instance) else {
+            return;
+        };
+        let end = model[instance]
+            .get_end_time()
+            .expect("Callback instance should be ended in callback_end");
+
+        self.running.remove(&(span.process, span.tid));
+        span.end = end.timestamp_nanos();
+        self.spans.push(span);
+    }
+
+    fn span_name_and_attributes(source: SpanSource, model: &Model) -> (String, Vec<KeyValue>) {
+        let mut attributes = Vec::new();
+        let mut add_string = |key: &'static str, value: Option<&str>| {
+            if let Some(value) = value {
+                attributes.push(KeyValue::string(key, value));
+            }
+        };
+
+        let name = match source {
+            SpanSource::Callback(callback) => {
+                let callback = &model[callback];
+                let node = callback.get_node(model);
+                add_string(
+                    "ros.node",
+                    node.and_then(|node| Option::<&str>::from(model[node].get_full_name())),
+                );
+                add_string("ros.callback", callback.get_name());
+
+                match callback.get_caller() {
+                    Some(&CallbackCaller::Subscription(subscriber)) => {
+                        let topic = Option::<&str>::from(model[subscriber].get_topic());
+                        add_string("ros.topic", topic);
+                        format!("subscription {}", topic.unwrap_or("unknown"))
+                    }
+                    Some(&CallbackCaller::Service(service)) => {
+                        let name = Option::<&str>::from(model[service].get_name());
+                        add_string("ros.service", name);
+                        format!("service {}", name.unwrap_or("unknown"))
+                    }
+                    Some(&CallbackCaller::Timer(timer)) => match model[timer].get_period().into() {
+                        Some(period) => format!("timer {}", DisplayDuration(period)),
+                        None => "timer".to_owned(),
+                    },
+                    None => "callback".to_owned(),
+                }
+            }
+            SpanSource::Message(publisher) => {
+                let publisher = publisher.map(|publisher| &model[publisher]);
+                let topic =
+                    publisher.and_then(|publisher| Option::<&str>::from(publisher.get_topic()));
+                add_string(
+                    "ros.node",
+                    publisher
+                        .and_then(|publisher| Option::<Idx<Node>>::from(publisher.get_node()))
+                        .and_then(|node| Option::<&str>::from(model[node].get_full_name())),
+                );
+                add_string("ros.topic", topic);
+                format!("publish {}", topic.unwrap_or("unknown"))
+            }
+        };
+
+        (name, attributes)
+    }
+
+    fn resource_spans(&self, model: &Model) -> Vec<ResourceSpans> {
+        let mut spans_by_process: Vec<Vec<OtlpSpan>> =
+            self.processes.iter().map(|_| Vec::new()).collect();
+
+        for span in &self.spans {
+            let (name, mut attributes) = Self::span_name_and_attributes(span.source, model);
+            attributes.push(KeyValue::int("thread.id", span.tid.into()));
+
+            spans_by_process[span.process].push(OtlpSpan {
+                trace_id: format!("{:032x}", span.context.trace_id),
+                span_id: format!("{:016x}", span.context.span_id),
+                parent_span_id: span.parent_span_id.map(|span_id| format!("{span_id:016x}")),
+                name,
+                kind: span.kind as u8,
+                start_time_unix_nano: span.start.to_string(),
+                end_time_unix_nano: span.end.to_string(),
+                attributes,
+                events: span
+                    .take_time
+                    .map(|time| SpanEvent {
+                        time_unix_nano: time.to_string(),
+                        name: "take",
+                    })
+                    .into_iter()
+                    .collect(),
+            });
+        }
+
+        self.processes
+            .iter()
+            .zip(spans_by_process)
+            .filter(|(_, spans)| !spans.is_empty())
+            .map(|(process, spans)| ResourceSpans {
+                resource: Resource {
+                    attributes: vec![
+                        KeyValue::string("service.name", &process.procname),
+                        KeyValue::string("host.name", &process.hostname),
+                        KeyValue::int("process.pid", process.vpid.into()),
+                    ],
+                },
+                scope_spans: vec![ScopeSpans {
+                    scope: Scope {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                    },
+                    spans,
+                }],
+            })
+            .collect()
+    }
+}
+
+impl EventAnalysis for OtlpSpans {
+    fn initialize(&mut self) {
+        *self = Self::default();
+    }
+
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        let time = full_event.time.timestamp_nanos();
+
+        match &full_event.event {
+            Event::Ros2(ros2::Event::CallbackStart(event)) => {
+                self.start_callback(event.callback, &full_event.context, model);
+            }
+            Event::Ros2(ros2::Event::CallbackEnd(event)) => {
+                self.end_callback(event.callback, model);
+            }
+            Event::Ros2(ros2::Event::RclcppPublish(ros2::RclcppPublish { message }))
+            | Event::Ros2(ros2::Event::RclcppIntraPublish(ros2::RclcppIntraPublish { message }))
+            | Event::Ros2(ros2::Event::RclPublish(ros2::RclPublish { message }))
+            | Event::Ros2(ros2::Event::RmwPublish(ros2::RmwPublish { message })) => {
+                self.record_publication(*message, &full_event.context, time, model);
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self, _model: &Model) {
+        self.running.clear();
+        self.pending.clear();
+        self.publications.clear();
+    }
+}
+
+// Structures of the OTLP/JSON encoding of `ExportTraceServiceRequest`.
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TracesData {
+    resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Resource,
+    scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeSpans {
+    scope: Scope,
+    spans: Vec<OtlpSpan>,
+}
+
+#[derive(Debug, Serialize)]
+struct Scope {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OtlpSpan {
+    trace_id: String,
+    span_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_span_id: Option<String>,
+    name: String,
+    kind: u8,
+    /// 64-bit integers are encoded as strings.
+    start_time_unix_nano: String,
+    end_time_unix_nano: String,
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<SpanEvent>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpanEvent {
+    time_unix_nano: String,
+    name: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: &'static str,
+    value: AnyValue,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum AnyValue {
+    StringValue(String),
+    /// 64-bit integers are encoded as strings.
+    IntValue(String),
+}
+
+impl KeyValue {
+    fn string(key: &'static str, value: &str) -> Self {
+        Self {
+            key,
+            value: AnyValue::StringValue(value.to_owned()),
+        }
+    }
+
+    fn int(key: &'static str, value: i64) -> Self {
+        Self {
+            key,
+            value: AnyValue::IntValue(value.to_string()),
+        }
+    }
+}
+
+impl AnalysisOutput for OtlpSpans {
+    fn write_json(&self, model: &Model, file: &mut BufWriter<File>) -> serde_json::Result<()> {
+        let data = TracesData {
+            resource_spans: self.resource_spans(model),
+        };
+        serde_json::to_writer(file, &data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn context(vtid: u32) -> Context {
+        Context::new(0, 10, vtid, "talker".into(), "host".into())
+    }
+
+    #[test]
+    fn test_trace_propagation() {
+        let mut spans = OtlpSpans::new();
+        let process = spans.process_index(&context(10));
+        let publishing_callback = spans.new_trace(1_000);
+
+        let publication = Publication {
+            process,
+            tid: 10,
+            time: 1_500,
+            publisher: None,
+            parent: Some(publishing_callback),
+        };
+        let message = spans.record_message(publication, Some(1_800), 2_000);
+        let consumer = spans.child_of(Some(message), 2_000);
+
+        assert_eq!(message.trace_id, publishing_callback.trace_id);
+        assert_eq!(consumer.trace_id, publishing_callback.trace_id);
+        assert_ne!(message.span_id, publishing_callback.span_id);
+        assert_ne!(consumer.span_id, message.span_id);
+
+        let span = &spans.spans[0];
+        assert_eq!(span.parent_span_id, Some(publishing_callback.span_id));
+        assert_eq!((span.start, span.end), (1_500, 2_000));
+
+        let unrelated = spans.child_of(None, 3_000);
+        assert_ne!(unrelated.trace_id, publishing_callback.trace_id);
+    }
+
+    #[test]
+    fn test_otlp_json() {
+        let model = Model::default();
+        let mut spans = OtlpSpans::new();
+        let process = spans.process_index(&context(11));
+        let publication = Publication {
+            process,
+            tid: 11,
+            time: 1_500,
+            publisher: None,
+            parent: None,
+        };
+        spans.record_message(publication, None, 2_000);
+
+        let json = serde_json::to_value(TracesData {
+            resource_spans: spans.resource_spans(&model),
+        })
+        .unwrap();
+
+        assert_eq!(
+            json["resourceSpans"][0]["resource"]["attributes"][2],
+            serde_json::json!({"key": "process.pid", "value": {"intValue": "10"}})
+        );
+        let span = &json["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], "00000000000005dc0000000000000001");
+        assert_eq!(span["spanId"], "0000000000000001");
+        assert_eq!(span.get("parentSpanId"), None);
+        assert_eq!(span["name"], "publish unknown");
+        assert_eq!(span["kind"], 4);
+        assert_eq!(span["startTimeUnixNano"], "1500");
+        assert_eq!(span["endTimeUnixNano"], "2000");
+    }
+}
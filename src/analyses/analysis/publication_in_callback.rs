@@ -1,13 +1,12 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
 
 use crate::events_common::Context;
 use crate::model::display::DisplayCallbackSummary;
-use crate::model::{Callback, CallbackInstance, PublicationMessage, Publisher};
+use crate::model::{Callback, CallbackInstance, Idx, Model, PublicationMessage, Publisher};
 use crate::processed_events::{Event, FullEvent, ros2};
 use crate::utils::DisplayDebug;
 
-use super::{ArcMutWrapper, EventAnalysis};
+use super::EventAnalysis;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Id {
@@ -17,8 +16,8 @@ struct Id {
 
 #[derive(Debug, Default)]
 pub struct PublicationInCallback {
-    active_callbacks: HashMap<Id, ArcMutWrapper<CallbackInstance>>,
-    dependency: HashSet<(ArcMutWrapper<Publisher>, ArcMutWrapper<Callback>)>,
+    active_callbacks: HashMap<Id, Idx<CallbackInstance>>,
+    dependency: HashSet<(Idx<Publisher>, Idx<Callback>)>,
 }
 
 impl PublicationInCallback {
@@ -26,13 +25,13 @@ impl PublicationInCallback {
         Self::default()
     }
 
-    fn activate_callback(&mut self, callback: Arc<Mutex<CallbackInstance>>, context: &Context) {
+    fn activate_callback(&mut self, callback: Idx<CallbackInstance>, context: &Context) {
         let id = Id {
             vtid: context.vtid(),
             hostname: context.hostname().to_string(),
         };
 
-        if let Some(old) = self.active_callbacks.insert(id, callback.into()) {
+        if let Some(old) = self.active_callbacks.insert(id, callback) {
             panic!(
                 "Callback {old:?} is already active on vtid {} on host {}",
                 context.vtid(),
@@ -41,7 +40,7 @@ impl PublicationInCallback {
         }
     }
 
-    fn deactivate_callback(&mut self, callback: &Arc<Mutex<CallbackInstance>>, context: &Context) {
+    fn deactivate_callback(&mut self, callback: Idx<CallbackInstance>, context: &Context) {
         let id = Id {
             vtid: context.vtid(),
             hostname: context.hostname().to_string(),
@@ -49,7 +48,7 @@ impl PublicationInCallback {
 
         if let Some(old) = self.active_callbacks.remove(&id) {
             assert!(
-                Arc::ptr_eq(&old.0, callback),
+                old == callback,
                 "Callback {old:?} is not active on vtid {} on host {}",
                 context.vtid(),
                 context.hostname(),
@@ -65,40 +64,39 @@ impl PublicationInCallback {
 
     fn process_publication(
         &mut self,
-        publication: Arc<Mutex<PublicationMessage>>,
+        publication: Idx<PublicationMessage>,
         context: &Context,
+        model: &Model,
     ) {
         let id = Id {
             vtid: context.vtid(),
             hostname: context.hostname().to_string(),
         };
 
-        if let Some(callback) = self.active_callbacks.get(&id) {
-            let callback = callback.0.lock().unwrap();
-            let publication_msg = publication.lock().unwrap();
-            let publisher_arc = publication_msg.get_publisher().unwrap();
+        if let Some(&callback) = self.active_callbacks.get(&id) {
+            let publisher = model[publication].get_publisher().unwrap();
 
             self.dependency
-                .insert((publisher_arc.into(), callback.get_callback().into()));
+                .insert((publisher, model[callback].get_callback()));
         }
     }
 
-    pub(super) const fn get_dependency(
-        &self,
-    ) -> &HashSet<(ArcMutWrapper<Publisher>, ArcMutWrapper<Callback>)> {
+    pub(super) const fn get_dependency(&self) -> &HashSet<(Idx<Publisher>, Idx<Callback>)> {
         &self.dependency
     }
 
-    pub(crate) fn write_stats(&self, output: &mut impl std::io::Write) -> std::io::Result<()> {
+    pub(crate) fn write_stats(
+        &self,
+        model: &Model,
+        output: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
         writeln!(output, "Publication in callbacks statistic:")?;
-        for (i, (publisher, callback)) in self.dependency.iter().enumerate() {
-            let publisher = publisher.0.lock().unwrap();
-            let callback = callback.0.lock().unwrap();
+        for (i, &(publisher, callback)) in self.dependency.iter().enumerate() {
             writeln!(
                 output,
                 "- [{i:4}] Callback {}\n\t-> Publisher {}",
-                DisplayCallbackSummary(&callback),
-                DisplayDebug(publisher.get_topic())
+                DisplayCallbackSummary(model, &model[callback]),
+                DisplayDebug(model[publisher].get_topic())
             )?;
         }
         Ok(())
@@ -110,22 +108,22 @@ impl EventAnalysis for PublicationInCallback {
         *self = Self::default();
     }
 
-    fn process_event(&mut self, full_event: &FullEvent) {
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
         match &full_event.event {
             Event::Ros2(ros2::Event::CallbackStart(event)) => {
-                self.activate_callback(event.callback.clone(), &full_event.context);
+                self.activate_callback(event.callback, &full_event.context);
             }
             Event::Ros2(ros2::Event::CallbackEnd(event)) => {
-                self.deactivate_callback(&event.callback, &full_event.context);
+                self.deactivate_callback(event.callback, &full_event.context);
             }
             Event::Ros2(ros2::Event::RmwPublish(event)) => {
-                self.process_publication(event.message.clone(), &full_event.context);
+                self.process_publication(event.message, &full_event.context, model);
             }
             _ => {}
         }
     }
 
-    fn finalize(&mut self) {
+    fn finalize(&mut self, _model: &Model) {
         // Nothing to do
     }
 }
@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 
-use crate::analysis::utils::DisplayDurationStats;
-use crate::model::Node;
+use crate::analysis::utils::{DisplayDurationStats, RollingReport};
+use crate::model::{Idx, Model, Node};
 use crate::processed_events::{self, Event};
 use crate::utils::DurationDisplayImprecise;
 
-use super::{AnalysisOutput, ArcMutWrapper, EventAnalysis};
+use super::{AnalysisOutput, EventAnalysis};
 
 #[derive(Debug, Default)]
 pub struct SpinDuration {
-    processing_durations: HashMap<ArcMutWrapper<Node>, Vec<i64>>,
+    processing_durations: HashMap<Idx<Node>, Vec<i64>>,
+
+    /// Number of durations already printed by the rolling statistics.
+    reported: RollingReport<Idx<Node>>,
 }
 
 impl SpinDuration {
@@ -17,10 +20,10 @@ impl SpinDuration {
         Self::default()
     }
 
-    pub(crate) fn print_stats(&self) {
+    pub(crate) fn print_stats(&self, model: &Model) {
         println!("Spin duration statistics:");
-        for (i, (node, durations)) in self.processing_durations.iter().enumerate() {
-            let node = node.0.lock().unwrap();
+        for (i, (&node, durations)) in self.processing_durations.iter().enumerate() {
+            let node = &model[node];
 
             println!("- [{i:4}] Node {}:", node.get_full_name());
             println!("    Call count: {}", durations.len());
@@ -39,29 +42,45 @@ impl SpinDuration {
 impl EventAnalysis for SpinDuration {
     fn initialize(&mut self) {
         self.processing_durations.clear();
+        self.reported.clear();
     }
 
-    fn process_event(&mut self, full_event: &crate::processed_events::FullEvent) {
+    fn process_event(&mut self, full_event: &crate::processed_events::FullEvent, model: &Model) {
         if let Event::R2r(processed_events::r2r::Event::SpinEnd(event)) = &full_event.event {
-            let node = event.node.clone();
-            let spin = event.spin.as_ref().unwrap();
-            let spin_guard = spin.lock().unwrap();
-            let start_time = spin_guard
+            let node = event.node;
+            let spin = &model[event.spin.unwrap()];
+            let start_time = spin
                 .get_wake_time()
                 .expect("Bug: Spin end event without wake time set");
-            let end_time = spin_guard
+            let end_time = spin
                 .get_end_time()
                 .expect("Bug: Spin end event without end time set");
             let duration = end_time.timestamp_nanos() - start_time.timestamp_nanos();
 
             self.processing_durations
-                .entry(node.into())
+                .entry(node)
                 .or_default()
                 .push(duration);
         }
     }
 
-    fn finalize(&mut self) {}
+    fn finalize(&mut self, _model: &Model) {}
+
+    fn print_rolling_stats(&mut self, model: &Model) {
+        println!("Spin duration since the last report:");
+        for (node, durations) in &self.processing_durations {
+            let new_durations = self.reported.new_values(node, durations);
+            if new_durations.is_empty() {
+                continue;
+            }
+
+            println!(
+                "  {}: {}",
+                model[*node].get_full_name(),
+                DisplayDurationStats::with_comma(new_durations)
+            );
+        }
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -71,12 +90,16 @@ struct SpinDurationEntry {
 }
 
 impl AnalysisOutput for SpinDuration {
-    fn write_json(&self, file: &mut std::io::BufWriter<std::fs::File>) -> serde_json::Result<()> {
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
         let spin_durations: Vec<SpinDurationEntry> = self
             .processing_durations
             .iter()
             .map(|(node, durations)| SpinDurationEntry {
-                node: node.0.lock().unwrap().get_full_name().unwrap().to_owned(),
+                node: model[*node].get_full_name().unwrap().to_owned(),
                 spin_duration: durations.clone(),
             })
             .collect();
@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::events_common::Context;
+use crate::model::display::DisplayCallbackSummary;
+use crate::model::{
+    Callback, CallbackInstance, CallbackTrigger, Idx, Model, Node, PublicationMessage, Publisher,
+    SpinInstance,
+};
+use crate::processed_events::{Event, FullEvent, r2r, ros2};
+
+use super::{AnalysisOutput, EventAnalysis};
+
+/// Thread identified by its hostname, process ID and thread ID.
+type ThreadKey = (String, u32, u32);
+
+#[derive(Debug)]
+struct ThreadInfo {
+    hostname: String,
+    vpid: u32,
+    vtid: u32,
+    procname: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SliceKind {
+    Callback(Idx<Callback>),
+    /// Executor waiting for work.
+    Wait(Idx<Node>),
+    /// Executor processing the work after waking up.
+    Spin(Idx<Node>),
+}
+
+#[derive(Debug)]
+struct Slice {
+    /// Index into [`Timeline::threads`].
+    thread: usize,
+    start: i64,
+    end: i64,
+    kind: SliceKind,
+}
+
+/// Point on a thread track.
+#[derive(Debug, Clone, Copy)]
+struct FlowPoint {
+    thread: usize,
+    time: i64,
+}
+
+/// Message published at `from` and consumed by the callback starting at `to`.
+#[derive(Debug)]
+struct Flow {
+    publisher: Option<Idx<Publisher>>,
+    from: FlowPoint,
+    to: FlowPoint,
+}
+
+/// Timeline of callback executions, executor spins and message flows per thread.
+///
+/// The timeline is written in the Chrome Trace Event format, which can be opened
+/// in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`:
+/// - Each process is identified by its hostname and PID and each of its threads is a track.
+/// - Each callback instance is a slice named after the callback.
+/// - r2r executor spins are split into a wait slice and a spin slice processing the work.
+/// - Each message consumed by a subscription callback is a flow arrow from its publication
+///   to the start of the callback.
+///
+/// The timestamps are relative to the earliest event of the timeline.
+#[derive(Debug, Default)]
+pub struct Timeline {
+    threads: Vec<ThreadInfo>,
+    thread_indices: HashMap<ThreadKey, usize>,
+
+    slices: Vec<Slice>,
+    flows: Vec<Flow>,
+
+    publications: HashMap<Idx<PublicationMessage>, FlowPoint>,
+    spin_threads: HashMap<Idx<SpinInstance>, usize>,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn thread_index(&mut self, context: &Context) -> usize {
+        let key = (
+            context.hostname().to_owned(),
+            context.vpid(),
+            context.vtid(),
+        );
+        *self.thread_indices.entry(key).or_insert_with(|| {
+            self.threads.push(ThreadInfo {
+                hostname: context.hostname().to_owned(),
+                vpid: context.vpid(),
+                vtid: context.vtid(),
+                procname: context.procname().to_owned(),
+            });
+            self.threads.len() - 1
+        })
+    }
+
+    fn record_publication(&mut self, message: Idx<PublicationMessage>, point: FlowPoint) {
+        // The first layer publishing the message is the closest to the publishing callback
+        self.publications.entry(message).or_insert(point);
+    }
+
+    fn start_callback(&mut self, instance: Idx<CallbackInstance>, thread: usize, model: &Model) {
+        let instance = &model[instance];
+        let CallbackTrigger::SubscriptionMessage(message) = instance.get_trigger() else {
+            return;
+        };
+        let Some(publication) = model[message].get_publication_message() else {
+            return;
+        };
+        let Some(&from) = self.publications.get(&publication) else {
+            return;
+        };
+
+        self.flows.push(Flow {
+            publisher: model
+                .get(publication)
+                .and_then(PublicationMessage::get_publisher),
+            from,
+            to: FlowPoint {
+                thread,
+                time: instance.get_start_time().timestamp_nanos(),
+            },
+        });
+    }
+
+    fn end_callback(&mut self, instance: Idx<CallbackInstance>, thread: usize, model: &Model) {
+        let instance = &model[instance];
+        let end = instance
+            .get_end_time()
+            .expect("Callback instance should be ended in callback_end");
+
+        self.slices.push(Slice {
+            thread,
+            start: instance.get_start_time().timestamp_nanos(),
+            end: end.timestamp_nanos(),
+            kind: SliceKind::Callback(instance.get_callback()),
+        });
+    }
+
+    fn end_spin(&mut self, node: Idx<Node>, spin: Option<Idx<SpinInstance>>, model: &Model) {
+        let Some(spin) = spin else {
+            return;
+        };
+        let Some(thread) = self.spin_threads.remove(&spin) else {
+            return;
+        };
+        let spin = &model[spin];
+        let start = spin.get_start_time().timestamp_nanos();
+        let Some(wake) = spin.get_wake_time().map(|time| time.timestamp_nanos()) else {
+            return;
+        };
+
+        self.slices.push(Slice {
+            thread,
+            start,
+            end: wake,
+            kind: SliceKind::Wait(node),
+        });
+        if let Some(end) = spin.get_end_time() {
+            self.slices.push(Slice {
+                thread,
+                start: wake,
+                end: end.timestamp_nanos(),
+                kind: SliceKind::Spin(node),
+            });
+        }
+    }
+
+    /// Earliest time of the timeline, the origin of the written timestamps.
+    fn time_origin(&self) -> i64 {
+        let slice_starts = self.slices.iter().map(|slice| slice.start);
+        let flow_starts = self.flows.iter().map(|flow| flow.from.time);
+        slice_starts.chain(flow_starts).min().unwrap_or_default()
+    }
+
+    /// Process IDs of the written trace.
+    ///
+    /// The PIDs of different hosts may collide, so the processes are numbered
+    /// in the order of their hostname and PID.
+    fn process_ids(&self) -> HashMap<(&str, u32), u32> {
+        let mut processes: Vec<_> = self
+            .threads
+            .iter()
+            .map(|thread| (thread.hostname.as_str(), thread.vpid))
+            .collect();
+        processes.sort_unstable();
+        processes.dedup();
+
+        processes.into_iter().zip(1..).collect()
+    }
+
+    fn trace_events(&self, model: &Model) -> Vec<TraceEvent> {
+        let origin = self.time_origin();
+        let timestamp = |time: i64| (time - origin) as f64 / 1000.0;
+        let pids = self.process_ids();
+        let track = |thread: usize| {
+            let thread = &self.threads[thread];
+            (pids[&(thread.hostname.as_str(), thread.vpid)], thread.vtid)
+        };
+
+        let mut events = Vec::new();
+
+        let mut processes: Vec<_> = pids.iter().collect();
+        processes.sort_unstable_by_key(|&(_, pid)| pid);
+        for (&(hostname, vpid), &pid) in processes {
+            // Name the process after its main thread if it is known
+            let procname = self
+                .threads
+                .iter()
+                .filter(|thread| thread.hostname == hostname && thread.vpid == vpid)
+                .min_by_key(|thread| thread.vtid != vpid)
+                .map_or("", |thread| thread.procname.as_str());
+            events.push(TraceEvent::metadata(
+                "process_name",
+                pid,
+                0,
+                format!("{procname} {vpid} ({hostname})"),
+            ));
+        }
+        for (index, thread) in self.threads.iter().enumerate() {
+            let (pid, tid) = track(index);
+            events.push(TraceEvent::metadata(
+                "thread_name",
+                pid,
+                tid,
+                thread.procname.clone(),
+            ));
+        }
+
+        for slice in &self.slices {
+            let (pid, tid) = track(slice.thread);
+            let mut args = Map::new();
+            let (name, category, node) = match slice.kind {
+                SliceKind::Callback(callback) => {
+                    let callback = &model[callback];
+                    if let Some(name) = callback.get_name() {
+                        args.insert("symbol".into(), name.into());
+                    }
+                    (
+                        DisplayCallbackSummary(model, callback).to_string(),
+                        "callback",
+                        callback.get_node(model),
+                    )
+                }
+                SliceKind::Wait(node) => ("wait".to_owned(), "executor", Some(node)),
+                SliceKind::Spin(node) => ("spin".to_owned(), "executor", Some(node)),
+            };
+            if let Some(node_name) =
+                node.and_then(|node| Option::<&str>::from(model[node].get_full_name()))
+            {
+                args.insert("node".into(), Value::from(node_name));
+            }
+
+            events.push(TraceEvent {
+                name,
+                cat: Some(category),
+                ph: "X",
+                ts: timestamp(slice.start),
+                dur: Some((slice.end - slice.start) as f64 / 1000.0),
+                pid,
+                tid,
+                id: None,
+                bp: None,
+                args,
+            });
+        }
+
+        for (id, flow) in self.flows.iter().enumerate() {
+            let topic = flow
+                .publisher
+                .and_then(|publisher| Option::<&str>::from(model[publisher].get_topic()))
+                .unwrap_or("unknown topic");
+            for (point, ph, bp) in [(flow.from, "s", None), (flow.to, "f", Some("e"))] {
+                let (pid, tid) = track(point.thread);
+                events.push(TraceEvent {
+                    name: topic.to_owned(),
+                    cat: Some("message"),
+                    ph,
+                    ts: timestamp(point.time),
+                    dur: None,
+                    pid,
+                    tid,
+                    id: Some(id),
+                    bp,
+                    args: Map::new(),
+                });
+            }
+        }
+
+        events
+    }
+}
+
+impl EventAnalysis for Timeline {
+    fn initialize(&mut self) {
+        self.threads.clear();
+        self.thread_indices.clear();
+        self.slices.clear();
+        self.flows.clear();
+        self.publications.clear();
+        self.spin_threads.clear();
+    }
+
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        let point = |thread| FlowPoint {
+            thread,
+            time: full_event.time.timestamp_nanos(),
+        };
+
+        match &full_event.event {
+            Event::Ros2(ros2::Event::CallbackStart(event)) => {
+                let thread = self.thread_index(&full_event.context);
+                self.start_callback(event.callback, thread, model);
+            }
+            Event::Ros2(ros2::Event::CallbackEnd(event)) => {
+                let thread = self.thread_index(&full_event.context);
+                self.end_callback(event.callback, thread, model);
+            }
+            Event::Ros2(ros2::Event::RclcppPublish(ros2::RclcppPublish { message }))
+            | Event::Ros2(ros2::Event::RclcppIntraPublish(ros2::RclcppIntraPublish { message }))
+            | Event::Ros2(ros2::Event::RclPublish(ros2::RclPublish { message }))
+            | Event::Ros2(ros2::Event::RmwPublish(ros2::RmwPublish { message })) => {
+                let thread = self.thread_index(&full_event.context);
+                self.record_publication(*message, point(thread));
+            }
+            Event::R2r(r2r::Event::SpinStart(event)) => {
+                let thread = self.thread_index(&full_event.context);
+                self.spin_threads.insert(event.spin, thread);
+            }
+            Event::R2r(r2r::Event::SpinEnd(event)) => {
+                self.end_spin(event.node, event.spin, model);
+            }
+            Event::R2r(r2r::Event::SpinTimeout(event)) => {
+                self.end_spin(event.node, event.spin, model);
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self, _model: &Model) {
+        self.publications.clear();
+        self.spin_threads.clear();
+    }
+}
+
+/// Event of the Chrome Trace Event format.
+#[derive(Debug, Serialize)]
+struct TraceEvent {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cat: Option<&'static str>,
+    ph: &'static str,
+    /// Microseconds since the time origin.
+    ts: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    pid: u32,
+    tid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bp: Option<&'static str>,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    args: Map<String, Value>,
+}
+
+impl TraceEvent {
+    fn metadata(kind: &str, pid: u32, tid: u32, name: String) -> Self {
+        let mut args = Map::new();
+        args.insert("name".into(), name.into());
+        Self {
+            name: kind.to_owned(),
+            cat: None,
+            ph: "M",
+            ts: 0.0,
+            dur: None,
+            pid,
+            tid,
+            id: None,
+            bp: None,
+            args,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace {
+    trace_events: Vec<TraceEvent>,
+    display_time_unit: &'static str,
+    other_data: OtherData,
+}
+
+#[derive(Debug, Serialize)]
+struct OtherData {
+    /// Nanoseconds since the UNIX epoch of the timestamp 0.
+    time_origin_ns: i64,
+}
+
+impl AnalysisOutput for Timeline {
+    fn write_json(&self, model: &Model, file: &mut BufWriter<File>) -> serde_json::Result<()> {
+        let trace = ChromeTrace {
+            trace_events: self.trace_events(model),
+            display_time_unit: "ns",
+            other_data: OtherData {
+                time_origin_ns: self.time_origin(),
+            },
+        };
+        serde_json::to_writer(file, &trace)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Time;
+
+    fn context(vpid: u32, vtid: u32) -> Context {
+        Context::new(0, vpid, vtid, "talker".into(), "host".into())
+    }
+
+    #[test]
+    fn test_thread_index() {
+        let mut timeline = Timeline::new();
+
+        assert_eq!(timeline.thread_index(&context(10, 10)), 0);
+        assert_eq!(timeline.thread_index(&context(10, 11)), 1);
+        assert_eq!(timeline.thread_index(&context(10, 10)), 0);
+        assert_eq!(timeline.threads.len(), 2);
+    }
+
+    #[test]
+    fn test_spin_slices() {
+        let mut model = Model::default();
+        let node = model.add(Node::new(1));
+        let mut timeline = Timeline::new();
+        let thread = timeline.thread_index(&context(10, 10));
+
+        let spin = model.add(SpinInstance::new(
+            node,
+            Time::from_nanos(1_000),
+            std::time::Duration::ZERO,
+        ));
+        model[spin].set_wake_time(Time::from_nanos(3_000));
+        model[spin].set_end_time(Time::from_nanos(4_500));
+        timeline.spin_threads.insert(spin, thread);
+        timeline.end_spin(node, Some(spin), &model);
+
+        let events = timeline.trace_events(&model);
+        let slices: Vec<_> = events
+            .iter()
+            .filter(|event| event.ph == "X")
+            .map(|event| (event.name.as_str(), event.ts, event.dur))
+            .collect();
+        assert_eq!(slices, [("wait", 0.0, Some(2.0)), ("spin", 2.0, Some(1.5))]);
+        assert!(timeline.spin_threads.is_empty());
+    }
+}
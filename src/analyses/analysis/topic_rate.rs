@@ -0,0 +1,714 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::extract::RosInterfaceCompleteName;
+use crate::model::display::get_node_name;
+use crate::model::{Idx, Model, Publisher, Subscriber, Time};
+use crate::processed_events::{Event, FullEvent, ros2};
+use crate::utils::{Known, WeakKnown};
+
+use super::dependency_graph::Node;
+use super::{AnalysisOutput, EventAnalysis};
+
+/// A window is reported as a rate drop when its rate is below this fraction of the nominal rate.
+const RATE_DROP_RATIO: f64 = 0.8;
+
+/// Rates commonly used in ROS 2 systems in Hz.
+///
+/// The detected nominal frequency is snapped to one of these when it is close enough.
+const COMMON_RATES: [f64; 17] = [
+    1.0, 2.0, 5.0, 10.0, 15.0, 20.0, 25.0, 30.0, 40.0, 50.0, 60.0, 100.0, 200.0, 250.0, 400.0,
+    500.0, 1000.0,
+];
+
+/// Maximum relative difference of the measured rate to a common rate to snap to it.
+const COMMON_RATE_TOLERANCE: f64 = 0.05;
+
+/// Minimum number of messages expected in a window to detect rate drops.
+///
+/// With fewer messages, a single late message would be reported as a drop.
+const MIN_MESSAGES_PER_WINDOW: f64 = 4.0;
+
+const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+
+/// Mean rate in Hz computed from periods between consecutive messages.
+pub(crate) fn mean_rate(periods: &[i64]) -> Option<f64> {
+    let total: i64 = periods.iter().sum();
+    (total > 0).then(|| periods.len() as f64 * NANOS_PER_SEC / total as f64)
+}
+
+/// Format a rate in Hz with a precision suitable for graph labels.
+pub(crate) fn format_rate(rate: f64) -> String {
+    if rate >= 100.0 {
+        format!("{rate:.0} Hz")
+    } else if rate >= 1.0 {
+        format!("{rate:.1} Hz")
+    } else {
+        format!("{rate:.3} Hz")
+    }
+}
+
+/// Detect the nominal frequency from the median period.
+///
+/// The frequency is snapped to a common rate if it is within 5 % of it.
+/// Otherwise, it is rounded to two significant digits.
+fn detect_nominal_rate(median_period: i64) -> Option<f64> {
+    if median_period <= 0 {
+        return None;
+    }
+
+    let rate = NANOS_PER_SEC / median_period as f64;
+    let common = COMMON_RATES
+        .iter()
+        .copied()
+        .min_by(|a, b| ((a - rate).abs()).total_cmp(&(b - rate).abs()))
+        .filter(|common| (common - rate).abs() / common <= COMMON_RATE_TOLERANCE);
+
+    Some(common.unwrap_or_else(|| {
+        let digits = 1 - rate.log10().floor() as i32;
+        if digits >= 0 {
+            let scale = 10f64.powi(digits);
+            (rate * scale).round() / scale
+        } else {
+            let scale = 10f64.powi(-digits);
+            (rate / scale).round() * scale
+        }
+    }))
+}
+
+/// Round a period to three significant digits.
+///
+/// Used to keep the histogram of periods small while still detecting the nominal rate precisely.
+fn quantize_period(period: i64) -> i64 {
+    let mut scale = 1;
+    while period / scale >= 1000 {
+        scale *= 10;
+    }
+    (period + scale / 2) / scale * scale
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateDrop {
+    pub start: i64,
+    pub end: i64,
+
+    /// Lowest rate among the windows of this drop in Hz
+    pub min_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateStats {
+    pub message_count: usize,
+
+    /// Mean rate in Hz
+    pub mean_rate: Option<f64>,
+
+    /// Nominal frequency in Hz detected from the median period
+    pub nominal_rate: Option<f64>,
+
+    pub min_period: Option<i64>,
+    pub max_period: Option<i64>,
+    pub mean_period: Option<i64>,
+    pub std_dev_period: Option<f64>,
+
+    /// Sliding windows where the rate dropped below 80 % of the nominal rate
+    ///
+    /// Drops are detected only if at least 4 messages are expected in a window.
+    pub drops: Vec<RateDrop>,
+}
+
+/// Rate statistics computed incrementally from message times.
+///
+/// The message times are not stored. Only the number of messages in each half of the sliding
+/// window and a histogram of periods rounded to three significant digits are kept,
+/// so the memory grows with the trace duration and not with the number of messages.
+#[derive(Debug, Clone)]
+struct RateAccumulator {
+    /// Length of a half of the sliding window in nanoseconds
+    step: i64,
+
+    first: Option<Time>,
+    last: Option<Time>,
+    message_count: usize,
+
+    min_period: i64,
+    max_period: i64,
+    /// Running mean of the periods (Welford's algorithm)
+    period_mean: f64,
+    /// Running sum of squared differences from the mean (Welford's algorithm)
+    period_m2: f64,
+    period_histogram: BTreeMap<i64, usize>,
+
+    /// Number of messages in consecutive half windows starting at the first message
+    half_windows: Vec<u32>,
+}
+
+impl RateAccumulator {
+    fn new(window: i64) -> Self {
+        Self {
+            step: (window / 2).max(1),
+            first: None,
+            last: None,
+            message_count: 0,
+            min_period: i64::MAX,
+            max_period: i64::MIN,
+            period_mean: 0.0,
+            period_m2: 0.0,
+            period_histogram: BTreeMap::new(),
+            half_windows: Vec::new(),
+        }
+    }
+
+    /// Record a message. The times are expected to be recorded in non-decreasing order.
+    fn record(&mut self, time: Time) {
+        let first = *self.first.get_or_insert(time);
+
+        if let Some(last) = self.last {
+            let period = (time.timestamp_nanos() - last.timestamp_nanos()).max(0);
+            let periods = self.message_count as f64;
+            let delta = period as f64 - self.period_mean;
+            self.period_mean += delta / periods;
+            self.period_m2 += delta * (period as f64 - self.period_mean);
+            self.min_period = self.min_period.min(period);
+            self.max_period = self.max_period.max(period);
+            *self
+                .period_histogram
+                .entry(quantize_period(period))
+                .or_default() += 1;
+        }
+
+        let index = usize::try_from((time.timestamp_nanos() - first.timestamp_nanos()) / self.step)
+            .unwrap_or(0);
+        if self.half_windows.len() <= index {
+            self.half_windows.resize(index + 1, 0);
+        }
+        self.half_windows[index] += 1;
+
+        self.last = Some(time);
+        self.message_count += 1;
+    }
+
+    /// Median of the periods rounded to three significant digits.
+    fn median_period(&self) -> Option<i64> {
+        let periods = self.message_count.checked_sub(1)?;
+        let mut remaining = periods / 2;
+        for (&period, &count) in &self.period_histogram {
+            if remaining < count {
+                return Some(period);
+            }
+            remaining -= count;
+        }
+        None
+    }
+
+    /// Compute the rate statistics.
+    ///
+    /// The sliding windows span up to `trace_end` so that stalls at the end of the trace
+    /// are detected too.
+    fn stats(&self, trace_end: Option<Time>) -> RateStats {
+        let (Some(first), Some(last)) = (self.first, self.last) else {
+            return RateStats::empty(self.message_count);
+        };
+        let periods = self.message_count - 1;
+        if periods == 0 {
+            return RateStats::empty(self.message_count);
+        }
+
+        let total = last.timestamp_nanos() - first.timestamp_nanos();
+        let nominal_rate = self.median_period().and_then(detect_nominal_rate);
+        let drops = nominal_rate
+            .map(|nominal_rate| {
+                let end = trace_end.unwrap_or(last).max(last).timestamp_nanos();
+                find_drops(
+                    &self.half_windows,
+                    first.timestamp_nanos(),
+                    self.step,
+                    end,
+                    nominal_rate,
+                )
+            })
+            .unwrap_or_default();
+        let std_dev_period = (self.period_m2 / (periods as f64 - 1.0)).sqrt();
+
+        RateStats {
+            message_count: self.message_count,
+            mean_rate: (total > 0).then(|| periods as f64 * NANOS_PER_SEC / total as f64),
+            nominal_rate,
+            min_period: Some(self.min_period),
+            max_period: Some(self.max_period),
+            mean_period: Some(total / periods as i64),
+            std_dev_period: std_dev_period.is_finite().then_some(std_dev_period),
+            drops,
+        }
+    }
+}
+
+impl RateStats {
+    fn empty(message_count: usize) -> Self {
+        Self {
+            message_count,
+            mean_rate: None,
+            nominal_rate: None,
+            min_period: None,
+            max_period: None,
+            mean_period: None,
+            std_dev_period: None,
+            drops: Vec::new(),
+        }
+    }
+}
+
+/// Find windows with a rate lower than expected.
+///
+/// `half_windows` contains the number of messages in consecutive intervals of length `step`
+/// starting at `first`. The windows consist of two of these intervals, so they have length
+/// `2 * step` and start every `step`. Windows are checked up to `end`.
+/// Overlapping windows with a rate drop are merged.
+fn find_drops(
+    half_windows: &[u32],
+    first: i64,
+    step: i64,
+    end: i64,
+    nominal_rate: f64,
+) -> Vec<RateDrop> {
+    let window = 2 * step;
+    if nominal_rate * window as f64 / NANOS_PER_SEC < MIN_MESSAGES_PER_WINDOW {
+        return Vec::new();
+    }
+
+    let threshold = nominal_rate * RATE_DROP_RATIO;
+    let count = |index: usize| half_windows.get(index).copied().unwrap_or(0);
+
+    let mut drops: Vec<RateDrop> = Vec::new();
+    let mut index = 0;
+    let mut window_start = first;
+    while window_start + window <= end {
+        let window_end = window_start + window;
+        let messages = count(index) + count(index + 1);
+
+        let rate = f64::from(messages) * NANOS_PER_SEC / window as f64;
+        if rate < threshold {
+            match drops.last_mut() {
+                Some(drop) if drop.end >= window_start => {
+                    drop.end = window_end;
+                    drop.min_rate = drop.min_rate.min(rate);
+                }
+                _ => drops.push(RateDrop {
+                    start: window_start,
+                    end: window_end,
+                    min_rate: rate,
+                }),
+            }
+        }
+
+        index += 1;
+        window_start += step;
+    }
+
+    drops
+}
+
+/// Analyze the rate of messages on topics similarly to `ros2 topic hz`.
+///
+/// Publishers are measured by their publications and subscribers by the taken messages.
+#[derive(Debug)]
+pub struct TopicRate {
+    window: i64,
+    trace_end: Option<Time>,
+
+    publications: HashMap<Idx<Publisher>, RateAccumulator>,
+    takes: HashMap<Idx<Subscriber>, RateAccumulator>,
+
+    /// Publications of all publishers of a topic
+    topic_publications: HashMap<String, RateAccumulator>,
+
+    /// Number of publications and time of the last one at the previous rolling report
+    reported: HashMap<String, (usize, Time)>,
+}
+
+#[derive(Debug, Serialize)]
+struct EndpointRate {
+    node: String,
+    #[serde(flatten)]
+    stats: RateStats,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicRateEntry {
+    topic: String,
+
+    /// Rate of all publications on the topic
+    #[serde(flatten)]
+    stats: RateStats,
+
+    publishers: Vec<EndpointRate>,
+    subscribers: Vec<EndpointRate>,
+}
+
+#[derive(Debug, Serialize)]
+struct TopicRateOutput {
+    window: i64,
+    topics: Vec<TopicRateEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TopicRateExport {
+    pub id: usize,
+    pub name: RosInterfaceCompleteName,
+    pub stats: RateStats,
+}
+
+fn node_name_or_empty(model: &Model, node: Known<Idx<crate::model::Node>>) -> String {
+    node.map_or(WeakKnown::Unknown, |node| get_node_name(model, node))
+        .unwrap_or(String::new())
+}
+
+impl TopicRate {
+    /// Create the analysis with sliding windows of `window` nanoseconds for rate drop detection.
+    pub fn new(window: i64) -> Self {
+        Self {
+            window,
+            trace_end: None,
+            publications: HashMap::new(),
+            takes: HashMap::new(),
+            topic_publications: HashMap::new(),
+            reported: HashMap::new(),
+        }
+    }
+
+    fn publisher_name(model: &Model, publisher: Idx<Publisher>) -> (String, String) {
+        let publisher = &model[publisher];
+        (
+            publisher.get_topic().to_string(),
+            node_name_or_empty(model, publisher.get_node()),
+        )
+    }
+
+    fn subscriber_name(model: &Model, subscriber: Idx<Subscriber>) -> (String, String) {
+        let subscriber = &model[subscriber];
+        (
+            subscriber.get_topic().to_string(),
+            node_name_or_empty(model, subscriber.get_node()),
+        )
+    }
+
+    fn topics(&self, model: &Model) -> Vec<TopicRateEntry> {
+        // Topic -> (publishers, subscribers)
+        let mut topics: BTreeMap<String, (Vec<EndpointRate>, Vec<EndpointRate>)> = BTreeMap::new();
+
+        for (&publisher, rate) in &self.publications {
+            let (topic, node) = Self::publisher_name(model, publisher);
+            topics.entry(topic).or_default().0.push(EndpointRate {
+                node,
+                stats: rate.stats(self.trace_end),
+            });
+        }
+
+        for (&subscriber, rate) in &self.takes {
+            let (topic, node) = Self::subscriber_name(model, subscriber);
+            topics.entry(topic).or_default().1.push(EndpointRate {
+                node,
+                stats: rate.stats(self.trace_end),
+            });
+        }
+
+        topics
+            .into_iter()
+            .map(|(topic, (mut publishers, mut subscribers))| {
+                publishers.sort_by(|a, b| a.node.cmp(&b.node));
+                subscribers.sort_by(|a, b| a.node.cmp(&b.node));
+
+                let stats = self
+                    .topic_publications
+                    .get(&topic)
+                    .map_or_else(|| RateStats::empty(0), |rate| rate.stats(self.trace_end));
+
+                TopicRateEntry {
+                    topic,
+                    stats,
+                    publishers,
+                    subscribers,
+                }
+            })
+            .collect()
+    }
+
+    /// Rate statistics of publishers and subscribers for the binary bundle.
+    ///
+    /// When the dependency graph is exported too, the elements have the ids of its nodes.
+    /// Otherwise, the ids are assigned in the order of the topic and node names.
+    pub fn topic_rates(
+        &self,
+        model: &Model,
+        node_ids: Option<&HashMap<Node, usize>>,
+    ) -> Vec<TopicRateExport> {
+        let publishers = self.publications.iter().map(|(&publisher, rate)| {
+            let (topic, node) = Self::publisher_name(model, publisher);
+            (
+                node_ids.and_then(|ids| ids.get(&Node::Publisher(publisher)).copied()),
+                RosInterfaceCompleteName {
+                    interface: format!("Publisher({topic})"),
+                    node,
+                },
+                rate,
+            )
+        });
+
+        let subscribers = self.takes.iter().map(|(&subscriber, rate)| {
+            let (topic, node) = Self::subscriber_name(model, subscriber);
+            (
+                node_ids.and_then(|ids| ids.get(&Node::Subscriber(subscriber)).copied()),
+                RosInterfaceCompleteName {
+                    interface: format!("Subscriber({topic})"),
+                    node,
+                },
+                rate,
+            )
+        });
+
+        let mut rates: Vec<_> = publishers.chain(subscribers).collect();
+        if node_ids.is_none() {
+            rates.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+        }
+
+        rates
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (id, name, rate))| {
+                Some(TopicRateExport {
+                    id: if node_ids.is_some() { id? } else { index },
+                    name,
+                    stats: rate.stats(self.trace_end),
+                })
+            })
+            .collect()
+    }
+
+    fn log_drops(&self, model: &Model) {
+        for topic in self.topics(model) {
+            let endpoints = std::iter::once(("all publishers", String::new(), &topic.stats))
+                .chain(
+                    topic
+                        .publishers
+                        .iter()
+                        .map(|p| ("publisher", p.node.clone(), &p.stats)),
+                )
+                .chain(
+                    topic
+                        .subscribers
+                        .iter()
+                        .map(|s| ("subscriber", s.node.clone(), &s.stats)),
+                );
+
+            for (kind, node, stats) in endpoints {
+                let (Some(nominal_rate), Some(lowest)) = (
+                    stats.nominal_rate,
+                    stats
+                        .drops
+                        .iter()
+                        .map(|d| d.min_rate)
+                        .min_by(f64::total_cmp),
+                ) else {
+                    continue;
+                };
+
+                log::warn!(target: "topic_rate",
+                    "Rate of topic {} dropped {} times ({kind} {node}): nominal {}, lowest {}",
+                    topic.topic,
+                    stats.drops.len(),
+                    format_rate(nominal_rate),
+                    format_rate(lowest),
+                );
+            }
+        }
+    }
+}
+
+impl EventAnalysis for TopicRate {
+    fn initialize(&mut self) {
+        self.trace_end = None;
+        self.publications.clear();
+        self.takes.clear();
+        self.topic_publications.clear();
+        self.reported.clear();
+    }
+
+    fn process_event(&mut self, full_event: &FullEvent, model: &Model) {
+        self.trace_end = Some(full_event.time);
+
+        match &full_event.event {
+            Event::Ros2(ros2::Event::RmwPublish(event)) => {
+                let message = &model[event.message];
+                if let Some(publisher) = message.get_publisher() {
+                    let topic = model[publisher].get_topic().to_string();
+                    self.topic_publications
+                        .entry(topic)
+                        .or_insert_with(|| RateAccumulator::new(self.window))
+                        .record(full_event.time);
+                    self.publications
+                        .entry(publisher)
+                        .or_insert_with(|| RateAccumulator::new(self.window))
+                        .record(full_event.time);
+                }
+            }
+            Event::Ros2(ros2::Event::RmwTake(event)) if event.taken => {
+                let message = &model[event.message];
+                if let Some(subscriber) = message.get_subscriber() {
+                    self.takes
+                        .entry(subscriber)
+                        .or_insert_with(|| RateAccumulator::new(self.window))
+                        .record(full_event.time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self, model: &Model) {
+        self.log_drops(model);
+    }
+
+    fn print_rolling_stats(&mut self, _model: &Model) {
+        println!("Topic rate since the last report:");
+        let mut topics: Vec<_> = self.topic_publications.iter().collect();
+        topics.sort_by_key(|(topic, _)| *topic);
+
+        for (topic, rate) in topics {
+            let (Some(first), Some(last)) = (rate.first, rate.last) else {
+                continue;
+            };
+            let count = rate.message_count;
+            // The rate is measured from the last message of the previous report
+            let (messages, periods, since) =
+                match self.reported.insert(topic.clone(), (count, last)) {
+                    Some((reported, since)) => (count - reported, count - reported, since),
+                    None => (count, count - 1, first),
+                };
+            if messages == 0 {
+                continue;
+            }
+
+            let elapsed = last.timestamp_nanos() - since.timestamp_nanos();
+            if elapsed > 0 {
+                println!(
+                    "  {topic}: {messages} messages, {}",
+                    format_rate(periods as f64 * NANOS_PER_SEC / elapsed as f64)
+                );
+            } else {
+                println!("  {topic}: {messages} messages");
+            }
+        }
+    }
+}
+
+impl AnalysisOutput for TopicRate {
+    fn write_json(
+        &self,
+        model: &Model,
+        file: &mut std::io::BufWriter<std::fs::File>,
+    ) -> serde_json::Result<()> {
+        serde_json::to_writer(
+            file,
+            &TopicRateOutput {
+                window: self.window,
+                topics: self.topics(model),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RateAccumulator, RateDrop, detect_nominal_rate, find_drops, quantize_period};
+    use crate::model::Time;
+
+    const MS: i64 = 1_000_000;
+
+    #[test]
+    fn test_detect_nominal_rate() {
+        assert_eq!(detect_nominal_rate(100_000_000), Some(10.0));
+        // 9.8 Hz is close enough to 10 Hz
+        assert_eq!(detect_nominal_rate(102_040_816), Some(10.0));
+        assert_eq!(detect_nominal_rate(1_000_000), Some(1000.0));
+        // 7.3 Hz is not a common rate
+        assert_eq!(detect_nominal_rate(136_986_301), Some(7.3));
+        assert_eq!(detect_nominal_rate(8_130_081), Some(120.0));
+        assert_eq!(detect_nominal_rate(0), None);
+    }
+
+    #[test]
+    fn test_quantize_period() {
+        assert_eq!(quantize_period(0), 0);
+        assert_eq!(quantize_period(999), 999);
+        assert_eq!(quantize_period(100_049_999), 100_000_000);
+        assert_eq!(quantize_period(102_551_000), 103_000_000);
+    }
+
+    /// Messages at 10 Hz from 0 s to 10 s with no messages between 3.9 s and 6 s.
+    fn accumulator_with_gap() -> RateAccumulator {
+        let mut rate = RateAccumulator::new(1000 * MS);
+        for time in (0..=10_000 * MS)
+            .step_by(100 * MS as usize)
+            .filter(|time| !(4000 * MS..6000 * MS).contains(time))
+        {
+            rate.record(Time::from_nanos(time));
+        }
+        rate
+    }
+
+    #[test]
+    fn test_rate_stats_with_gap() {
+        let stats = accumulator_with_gap().stats(None);
+
+        assert_eq!(stats.message_count, 81);
+        assert_eq!(stats.nominal_rate, Some(10.0));
+        assert_eq!(stats.min_period, Some(100 * MS));
+        assert_eq!(stats.max_period, Some(2100 * MS));
+        assert_eq!(stats.mean_period, Some(125 * MS));
+        assert_eq!(stats.mean_rate, Some(8.0));
+        assert_eq!(
+            stats.drops,
+            vec![RateDrop {
+                start: 3500 * MS,
+                end: 6500 * MS,
+                min_rate: 0.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rate_stats_stall_at_trace_end() {
+        let stats = accumulator_with_gap().stats(Some(Time::from_nanos(12_000 * MS)));
+
+        assert_eq!(
+            stats.drops,
+            vec![
+                RateDrop {
+                    start: 3500 * MS,
+                    end: 6500 * MS,
+                    min_rate: 0.0,
+                },
+                RateDrop {
+                    start: 9500 * MS,
+                    end: 12_000 * MS,
+                    min_rate: 0.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_drops_requires_enough_messages_per_window() {
+        // Only 2 messages are expected in a window at 2 Hz
+        assert!(find_drops(&[1, 0, 0, 0], 0, 500 * MS, 2000 * MS, 2.0).is_empty());
+        assert_eq!(
+            find_drops(&[5, 5, 0, 5], 0, 500 * MS, 2000 * MS, 10.0),
+            vec![RateDrop {
+                start: 500 * MS,
+                end: 2000 * MS,
+                min_rate: 5.0,
+            }]
+        );
+    }
+}
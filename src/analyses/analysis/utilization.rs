@@ -1,14 +1,15 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::model::display::DisplayCallbackSummary;
-use crate::model::{Callback, CallbackType};
+use crate::model::{Callback, CallbackType, Idx, Model};
 use crate::statistics::{Mean, Quantile, Sorted};
 
+use super::CallbackDuration;
 use super::callback_duration::ExecutionData;
-use super::{ArcMutWrapper, CallbackDuration};
 
 pub struct Utilization<'a> {
     callback_analysis: &'a CallbackDuration,
+    model: &'a Model,
 }
 
 trait ReductionFunction:
@@ -22,24 +23,27 @@ impl<F> ReductionFunction for F where
 }
 
 impl<'a> Utilization<'a> {
-    pub fn new(callback_analysis: &'a CallbackDuration) -> Self {
-        Self { callback_analysis }
+    pub fn new(callback_analysis: &'a CallbackDuration, model: &'a Model) -> Self {
+        Self {
+            callback_analysis,
+            model,
+        }
     }
 
-    fn get_thread_callback_map(&self) -> HashMap<(String, u32), HashSet<ArcMutWrapper<Callback>>> {
-        let mut thread_callback_map: HashMap<(String, u32), HashSet<ArcMutWrapper<Callback>>> =
+    fn get_thread_callback_map(&self) -> HashMap<(String, u32), HashSet<Idx<Callback>>> {
+        let mut thread_callback_map: HashMap<(String, u32), HashSet<Idx<Callback>>> =
             HashMap::new();
-        for (callback_arc, execution_data) in self.callback_analysis.get_execution_data() {
+        for (&callback_idx, execution_data) in self.callback_analysis.get_execution_data() {
             execution_data
                 .iter()
                 .map(|data| data.tid)
                 .for_each(|thread| {
-                    let callback = callback_arc.0.lock().unwrap();
+                    let callback = &self.model[callback_idx];
                     let hostname = callback.get_hostname().to_string();
                     thread_callback_map
                         .entry((hostname.clone(), thread))
                         .or_default()
-                        .insert(callback_arc.clone());
+                        .insert(callback_idx);
                 });
         }
 
@@ -49,32 +53,24 @@ impl<'a> Utilization<'a> {
     fn calculate_utilization_per_callback_internal(
         &self,
         reduction_function: impl ReductionFunction,
-    ) -> HashMap<ArcMutWrapper<Callback>, HashMap<u32, f64>> {
+    ) -> HashMap<Idx<Callback>, HashMap<u32, f64>> {
         let mut thread_utilization = HashMap::new();
-        for (callback_arc, execution_data) in self.callback_analysis.get_execution_data() {
-            let callback = callback_arc.0.lock().unwrap();
+        for (&callback_idx, execution_data) in self.callback_analysis.get_execution_data() {
+            let callback = &self.model[callback_idx];
             let callback_is_timer = callback
                 .get_type()
                 .map_or(false, |t| t == CallbackType::Timer);
 
             let inter_arrival_time = callback_is_timer.then_some(()).and_then(|()| {
-                callback
-                    .get_caller()
-                    .unwrap()
-                    .unwrap_timer_ref()
-                    .get_arc()
-                    .unwrap()
-                    .lock()
-                    .unwrap()
-                    .get_period()
-                    .into()
+                let timer = callback.get_caller().unwrap().unwrap_timer();
+                self.model[timer].get_period().into()
             });
 
             let Some(inter_arrival_time) = inter_arrival_time.map_or_else(
                 || {
                     let ict = self
                         .callback_analysis
-                        .get_inter_arrival_time(callback_arc)?;
+                        .get_inter_arrival_time(callback_idx)?;
                     ict.as_slice().mean()
                 },
                 Some,
@@ -103,7 +99,7 @@ impl<'a> Utilization<'a> {
                 inter_arrival_time as f64,
             );
 
-            thread_utilization.insert(callback_arc.clone(), utilization_per_thread);
+            thread_utilization.insert(callback_idx, utilization_per_thread);
         }
 
         thread_utilization
@@ -111,7 +107,7 @@ impl<'a> Utilization<'a> {
 
     pub fn calculate_utilization_per_callback_real(
         &self,
-    ) -> HashMap<ArcMutWrapper<Callback>, HashMap<u32, f64>> {
+    ) -> HashMap<Idx<Callback>, HashMap<u32, f64>> {
         self.calculate_utilization_per_callback_internal(
             |execution_times_and_counts_per_thread, execution_data, inter_arrival_time| {
                 let total_count = execution_data.len();
@@ -129,7 +125,7 @@ impl<'a> Utilization<'a> {
     pub fn calculate_utilization_per_callback(
         &self,
         execution_duration_quantile: Quantile,
-    ) -> HashMap<ArcMutWrapper<Callback>, HashMap<u32, f64>> {
+    ) -> HashMap<Idx<Callback>, HashMap<u32, f64>> {
         self.calculate_utilization_per_callback_internal(
             |execution_times_and_counts_per_thread, execution_data, inter_arrival_time| {
                 let total_count = execution_data.len();
@@ -165,12 +161,13 @@ impl<'a> Utilization<'a> {
     }
 
     pub fn calculate_total_utilization(
-        thread_utilization_per_callback: &HashMap<ArcMutWrapper<Callback>, HashMap<u32, f64>>,
+        &self,
+        thread_utilization_per_callback: &HashMap<Idx<Callback>, HashMap<u32, f64>>,
     ) -> HashMap<(String, u32), f64> {
         let mut utilization_per_thread_map = HashMap::new();
 
-        for (callback_arc, utilization_per_thread) in thread_utilization_per_callback {
-            let callback = callback_arc.0.lock().unwrap();
+        for (&callback, utilization_per_thread) in thread_utilization_per_callback {
+            let callback = &self.model[callback];
             let hostname = callback.get_hostname().to_string();
 
             for (thread, utilization) in utilization_per_thread {
@@ -191,7 +188,7 @@ impl<'a> Utilization<'a> {
     ) -> std::io::Result<()> {
         let thread_callback_map = self.get_thread_callback_map();
         let per_callback_utilization = self.calculate_utilization_per_callback(quantile);
-        let utilization_per_thread = Self::calculate_total_utilization(&per_callback_utilization);
+        let utilization_per_thread = self.calculate_total_utilization(&per_callback_utilization);
         let mut utilization_per_thread: Vec<_> = utilization_per_thread.into_iter().collect();
         utilization_per_thread.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).reverse());
 
@@ -199,7 +196,7 @@ impl<'a> Utilization<'a> {
             writer,
             "Utilization statistics for duration quantile {quantile}:"
         )?;
-        Self::write_utilization(
+        self.write_utilization(
             writer,
             &utilization_per_thread,
             &per_callback_utilization,
@@ -210,12 +207,12 @@ impl<'a> Utilization<'a> {
     pub fn write_stats_real(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
         let thread_callback_map = self.get_thread_callback_map();
         let per_callback_utilization = self.calculate_utilization_per_callback_real();
-        let utilization_per_thread = Self::calculate_total_utilization(&per_callback_utilization);
+        let utilization_per_thread = self.calculate_total_utilization(&per_callback_utilization);
         let mut utilization_per_thread: Vec<_> = utilization_per_thread.into_iter().collect();
         utilization_per_thread.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).reverse());
 
         writeln!(writer, "Utilization statistics for real execution times:")?;
-        Self::write_utilization(
+        self.write_utilization(
             writer,
             &utilization_per_thread,
             &per_callback_utilization,
@@ -224,10 +221,11 @@ impl<'a> Utilization<'a> {
     }
 
     fn write_utilization(
+        &self,
         writer: &mut impl std::io::Write,
         utilization_per_thread: &[((String, u32), f64)],
-        per_callback_utilization: &HashMap<ArcMutWrapper<Callback>, HashMap<u32, f64>>,
-        thread_callback_map: &HashMap<(String, u32), HashSet<ArcMutWrapper<Callback>>>,
+        per_callback_utilization: &HashMap<Idx<Callback>, HashMap<u32, f64>>,
+        thread_callback_map: &HashMap<(String, u32), HashSet<Idx<Callback>>>,
     ) -> std::io::Result<()> {
         for (key @ (hostname, thread), utilization) in utilization_per_thread {
             let callbacks = thread_callback_map.get(key).unwrap();
@@ -240,24 +238,21 @@ impl<'a> Utilization<'a> {
             )?;
             let mut utilization_per_callback = callbacks
                 .iter()
-                .filter_map(|callback_arc| {
-                    per_callback_utilization
-                        .get(callback_arc)
-                        .map(|utilization| {
-                            let utilization = utilization.get(thread).copied().unwrap_or(0.0);
-                            (callback_arc, utilization)
-                        })
+                .filter_map(|callback| {
+                    per_callback_utilization.get(callback).map(|utilization| {
+                        let utilization = utilization.get(thread).copied().unwrap_or(0.0);
+                        (callback, utilization)
+                    })
                 })
                 .collect::<Vec<_>>();
             utilization_per_callback.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).reverse());
 
-            for (callback_arc, utilization) in utilization_per_callback {
-                let callback = callback_arc.0.lock().unwrap();
+            for (&callback, utilization) in utilization_per_callback {
                 writeln!(
                     writer,
                     "    {:9.5} % from Callback {}",
                     utilization * 100.0,
-                    DisplayCallbackSummary(&callback),
+                    DisplayCallbackSummary(self.model, &self.model[callback]),
                 )?;
             }
         }
@@ -1,6 +1,63 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
 use crate::argsv2::Args;
+use crate::extract::RosInterfaceCompleteName;
+use crate::model::display::get_node_name;
+use crate::model::{Callback, Idx, Model};
 use crate::statistics::Sorted;
-use crate::utils::DurationDisplayImprecise;
+use crate::utils::{DurationDisplayImprecise, WeakKnown};
+
+/// Bookkeeping of the values already printed by the rolling statistics.
+///
+/// Remembers how many values of each key were reported, so that the next report contains
+/// only the values added since.
+#[derive(Debug)]
+pub(crate) struct RollingReport<K>(HashMap<K, usize>);
+
+impl<K> Default for RollingReport<K> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash + Clone> RollingReport<K> {
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Number of values of `key` added since the previous report given their `total` count.
+    pub(crate) fn new_count(&mut self, key: &K, total: usize) -> usize {
+        let reported = self.0.entry(key.clone()).or_default();
+        let new = total.saturating_sub(*reported);
+        *reported = total;
+        new
+    }
+
+    /// Values of `key` added since the previous report.
+    pub(crate) fn new_values<'a, T>(&mut self, key: &K, values: &'a [T]) -> &'a [T] {
+        let new = self.new_count(key, values.len());
+        &values[values.len() - new..]
+    }
+}
+
+/// Name of a callback in the binary bundle, in the same format as the dependency graph uses.
+pub(crate) fn callback_name(model: &Model, callback: Idx<Callback>) -> RosInterfaceCompleteName {
+    let callback = &model[callback];
+    RosInterfaceCompleteName {
+        interface: format!(
+            "Callback({})",
+            callback
+                .get_caller()
+                .map(|caller| model.display(caller).to_string())
+                .unwrap_or_default()
+        ),
+        node: callback
+            .get_node(model)
+            .map_or(WeakKnown::Unknown, |node| get_node_name(model, node))
+            .unwrap_or(String::new()),
+    }
+}
 
 pub struct DisplayDurationStats<'a>(&'a [i64], &'a str);
 
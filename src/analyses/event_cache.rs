@@ -0,0 +1,412 @@
+//! On-disk cache of the events decoded from traces.
+//!
+//! Decoding the traces with babeltrace2 takes most of the analysis time. The first analysis of
+//! a set of traces stores the decoded ROS events in the cache directory, and later analyses of
+//! the same traces replay them from the cache without reading the traces at all.
+//!
+//! The cache stores the raw events as decoded from the traces, not the processed events or the
+//! model. Every analysis replays the raw events through the processor and the analyses, which is
+//! cheap compared to the decoding.
+//!
+//! The cache file is a sequence of postcard encoded records, each prefixed by its length.
+//! The first record is a [`CacheHeader`], followed by the events and a final [`CacheRecord::End`].
+//!
+//! The cache file name and the header are derived from SHA-256 hashes of the traces (see
+//! [`EventCache::for_traces`]), so they are stable across program builds and platforms.
+
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use color_eyre::eyre::{Context, OptionExt, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::raw_events::FullEvent;
+
+/// Version of the cache format.
+///
+/// Must be incremented whenever the format or the cached events change.
+const CACHE_VERSION: u32 = 2;
+
+/// Extension of the cache files.
+const CACHE_EXTENSION: &str = "events";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheHeader {
+    version: u32,
+    metadata_hash: [u8; 32],
+}
+
+/// Counters of the trace contents which are not stored in the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SkippedCounts {
+    pub(crate) other_events: usize,
+    pub(crate) other_messages: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum CacheRecord {
+    Event(FullEvent),
+    End(SkippedCounts),
+}
+
+/// Location and expected contents of the cache of a set of traces.
+#[derive(Debug, Clone)]
+pub(crate) struct EventCache {
+    path: PathBuf,
+    header: CacheHeader,
+}
+
+impl EventCache {
+    /// Locate the cache of `trace_paths` in `cache_dir`.
+    ///
+    /// The cache file is named after the SHA-256 hash of the canonical trace paths. Its header
+    /// contains the SHA-256 hash of the trace metadata and of the sizes and modification times
+    /// of the trace files, so that the cache is not used when the traces change.
+    pub(crate) fn for_traces(cache_dir: &Path, trace_paths: &[&CStr]) -> Result<Self> {
+        let mut key_hasher = Sha256::new();
+        let mut metadata_hasher = Sha256::new();
+        for trace_path in trace_paths {
+            let trace_path = trace_path
+                .to_str()
+                .wrap_err("Trace path is not valid UTF-8")?;
+            let trace_path = Path::new(trace_path)
+                .canonicalize()
+                .wrap_err_with(|| format!("Failed to resolve trace path: `{trace_path}`"))?;
+            update_field(&mut key_hasher, trace_path.as_os_str().as_encoded_bytes());
+            hash_trace_files(&trace_path, &mut metadata_hasher)?;
+        }
+
+        // 128 bits of the hash are enough to avoid collisions of the file names
+        let key = key_hasher.finalize();
+        let file_name: String = key[..16].iter().map(|byte| format!("{byte:02x}")).collect();
+
+        Ok(Self {
+            path: cache_dir.join(file_name).with_extension(CACHE_EXTENSION),
+            header: CacheHeader {
+                version: CACHE_VERSION,
+                metadata_hash: metadata_hasher.finalize().into(),
+            },
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open the cache for reading.
+    ///
+    /// Returns `None` if the cache does not exist or was created from different traces
+    /// or by a different version of this program.
+    pub(crate) fn open(&self) -> Result<Option<EventCacheReader>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .wrap_err_with(|| format!("Failed to open event cache: `{:?}`", self.path));
+            }
+        };
+        let mut reader = BufReader::new(file);
+
+        let header = match read_frame::<CacheHeader>(&mut reader) {
+            Ok(Some(header)) => header,
+            Ok(None) | Err(_) => {
+                log::info!("Ignoring invalid event cache: `{:?}`", self.path);
+                return Ok(None);
+            }
+        };
+        if header != self.header {
+            log::info!("Ignoring outdated event cache: `{:?}`", self.path);
+            return Ok(None);
+        }
+
+        Ok(Some(EventCacheReader {
+            reader,
+            finished: false,
+        }))
+    }
+
+    /// Create a new cache, replacing the existing one when finished.
+    pub(crate) fn create(&self) -> Result<EventCacheWriter> {
+        let parent = self.path.parent().unwrap();
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create directory: `{parent:?}`"))?;
+
+        let tmp_path = self.path.with_extension(format!("{CACHE_EXTENSION}.tmp"));
+        let file = File::create(&tmp_path)
+            .wrap_err_with(|| format!("Failed to create file: `{tmp_path:?}`"))?;
+        let mut writer = EventCacheWriter {
+            writer: BufWriter::new(file),
+            tmp_path,
+            path: self.path.clone(),
+            finished: false,
+        };
+        writer.write(&self.header)?;
+
+        Ok(writer)
+    }
+}
+
+/// Add a field prefixed by its length to the hash, so that consecutive fields cannot be confused.
+fn update_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+/// Hash the contents of the metadata files and the paths, sizes and modification times
+/// of all files of the trace.
+fn hash_trace_files(trace_path: &Path, hasher: &mut Sha256) -> Result<()> {
+    for entry in WalkDir::new(trace_path).sort_by_file_name() {
+        let entry = entry.wrap_err("Failed to read trace directory")?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        let metadata = entry
+            .metadata()
+            .wrap_err_with(|| format!("Failed to read file metadata: `{path:?}`"))?;
+        let relative_path = path.strip_prefix(trace_path).unwrap();
+        update_field(hasher, relative_path.as_os_str().as_encoded_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        hasher.update(modified.as_secs().to_le_bytes());
+        hasher.update(modified.subsec_nanos().to_le_bytes());
+
+        if entry.file_name() == "metadata" {
+            let contents = std::fs::read(path)
+                .wrap_err_with(|| format!("Failed to read trace metadata: `{path:?}`"))?;
+            update_field(hasher, &contents);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a record prefixed by its length.
+///
+/// Returns `None` at the end of the file.
+fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> Result<Option<T>> {
+    let mut len = [0; size_of::<u32>()];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e).wrap_err("Failed to read event cache"),
+    }
+
+    let mut buf = vec![0; u32::from_le_bytes(len) as usize];
+    reader
+        .read_exact(&mut buf)
+        .wrap_err("Failed to read event cache")?;
+    let record = postcard::from_bytes(&buf).wrap_err("Failed to decode event cache record")?;
+
+    Ok(Some(record))
+}
+
+/// Reader of the events stored in a cache.
+#[derive(Debug)]
+pub(crate) struct EventCacheReader {
+    reader: BufReader<File>,
+    finished: bool,
+}
+
+impl EventCacheReader {
+    /// Read the next record.
+    ///
+    /// Returns `None` after the [`CacheRecord::End`] record.
+    pub(crate) fn read(&mut self) -> Result<Option<CacheRecord>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let record = read_frame(&mut self.reader)?
+            .ok_or_eyre("Event cache ended unexpectedly. Remove it and run the analysis again.")?;
+        self.finished = matches!(record, CacheRecord::End(_));
+
+        Ok(Some(record))
+    }
+}
+
+/// Writer of a new cache.
+///
+/// The events are written to a temporary file, which replaces the cache
+/// only after all events were written by [`EventCacheWriter::finish`].
+/// The temporary file is removed if the writer is dropped before it is finished.
+#[derive(Debug)]
+pub(crate) struct EventCacheWriter {
+    writer: BufWriter<File>,
+    tmp_path: PathBuf,
+    path: PathBuf,
+    finished: bool,
+}
+
+impl EventCacheWriter {
+    fn write(&mut self, record: &impl Serialize) -> Result<()> {
+        let buf = postcard::to_allocvec(record).wrap_err("Failed to encode event cache record")?;
+        let len = u32::try_from(buf.len()).wrap_err("Event cache record is too large")?;
+        self.writer
+            .write_all(&len.to_le_bytes())
+            .and_then(|()| self.writer.write_all(&buf))
+            .wrap_err_with(|| format!("Failed to write event cache: `{:?}`", self.tmp_path))
+    }
+
+    pub(crate) fn write_event(&mut self, event: &FullEvent) -> Result<()> {
+        // Same encoding as `CacheRecord::Event`, without cloning the event
+        #[derive(Serialize)]
+        enum CacheRecordRef<'a> {
+            Event(&'a FullEvent),
+        }
+
+        self.write(&CacheRecordRef::Event(event))
+    }
+
+    /// Finish the cache and replace the previous one.
+    pub(crate) fn finish(mut self, skipped: SkippedCounts) -> Result<()> {
+        self.write(&CacheRecord::End(skipped))?;
+        self.writer
+            .flush()
+            .wrap_err_with(|| format!("Failed to write event cache: `{:?}`", self.tmp_path))?;
+        std::fs::rename(&self.tmp_path, &self.path)
+            .wrap_err_with(|| format!("Failed to replace event cache: `{:?}`", self.path))?;
+        self.finished = true;
+
+        Ok(())
+    }
+}
+
+impl Drop for EventCacheWriter {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        if let Err(e) = std::fs::remove_file(&self.tmp_path)
+            && e.kind() != ErrorKind::NotFound
+        {
+            log::warn!(
+                "Failed to remove unfinished event cache `{:?}`: {e}",
+                self.tmp_path
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+    use std::path::PathBuf;
+
+    use super::{CacheRecord, EventCache, SkippedCounts};
+    use crate::events_common::{Context, Time};
+    use crate::raw_events::{FullEvent, ros2};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn event(time: i64, message: u64) -> FullEvent {
+        FullEvent {
+            context: Context::new(1, 100, 101, "talker".into(), "robot".into()),
+            time: Time::from_nanos(time),
+            event: ros2::Event::RclcppPublish(ros2::RclcppPublish { message }).into(),
+        }
+    }
+
+    fn cache_for(trace_dir: &TempDir, cache_dir: &TempDir) -> EventCache {
+        let trace_path = CString::new(trace_dir.0.to_str().unwrap()).unwrap();
+        EventCache::for_traces(&cache_dir.0, &[&trace_path]).unwrap()
+    }
+
+    #[test]
+    fn test_event_cache_round_trip() {
+        let trace_dir = TempDir::new("r2ta-event-cache-round-trip-trace");
+        let cache_dir = TempDir::new("r2ta-event-cache-round-trip-cache");
+        std::fs::write(trace_dir.0.join("metadata"), "/* CTF 1.8 */").unwrap();
+
+        let cache = cache_for(&trace_dir, &cache_dir);
+        assert!(cache.open().unwrap().is_none());
+
+        let events = [event(10, 0x1000), event(20, 0x2000)];
+        let skipped = SkippedCounts {
+            other_events: 3,
+            other_messages: 4,
+        };
+        let mut writer = cache.create().unwrap();
+        for event in &events {
+            writer.write_event(event).unwrap();
+        }
+        // The cache is not readable before it is finished
+        assert!(cache.open().unwrap().is_none());
+        writer.finish(skipped).unwrap();
+
+        let mut reader = cache.open().unwrap().expect("Cache should be valid");
+        for expected in &events {
+            let Some(CacheRecord::Event(event)) = reader.read().unwrap() else {
+                panic!("Expected an event record");
+            };
+            assert_eq!(format!("{event:?}"), format!("{expected:?}"));
+        }
+        let Some(CacheRecord::End(read_skipped)) = reader.read().unwrap() else {
+            panic!("Expected the end record");
+        };
+        assert_eq!(read_skipped, skipped);
+        assert!(reader.read().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unfinished_event_cache_is_removed() {
+        let trace_dir = TempDir::new("r2ta-event-cache-unfinished-trace");
+        let cache_dir = TempDir::new("r2ta-event-cache-unfinished-cache");
+        std::fs::write(trace_dir.0.join("metadata"), "/* CTF 1.8 */").unwrap();
+
+        let cache = cache_for(&trace_dir, &cache_dir);
+        let mut writer = cache.create().unwrap();
+        writer.write_event(&event(10, 0x1000)).unwrap();
+        drop(writer);
+
+        assert!(cache.open().unwrap().is_none());
+        assert_eq!(std::fs::read_dir(&cache_dir.0).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_event_cache_invalidated_by_trace_change() {
+        let trace_dir = TempDir::new("r2ta-event-cache-invalidated-trace");
+        let cache_dir = TempDir::new("r2ta-event-cache-invalidated-cache");
+        std::fs::write(trace_dir.0.join("metadata"), "/* CTF 1.8 */").unwrap();
+
+        let cache = cache_for(&trace_dir, &cache_dir);
+        cache
+            .create()
+            .unwrap()
+            .finish(SkippedCounts::default())
+            .unwrap();
+        assert!(cache_for(&trace_dir, &cache_dir).open().unwrap().is_some());
+
+        std::fs::write(trace_dir.0.join("metadata"), "/* CTF 1.8 */ trace {};").unwrap();
+        let changed = cache_for(&trace_dir, &cache_dir);
+        assert_eq!(changed.path(), cache.path());
+        assert!(changed.open().unwrap().is_none());
+    }
+}
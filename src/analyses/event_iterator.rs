@@ -1,26 +1,53 @@
+use std::collections::HashSet;
 use std::ffi::CStr;
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-use bt2_sys::message::BtMessageType;
+use bt2_sys::clock_snapshot::BtClockSnapshotConst;
+use bt2_sys::message::{BtMessageConst, BtMessageConstCast, BtMessageType};
+use bt2_sys::stream::BtStreamConst;
 
-use color_eyre::eyre::{Context, Result};
+use color_eyre::eyre::{Context, Result, eyre};
+use serde::Serialize;
 
 use bt2_sys::logging::LogLevel;
 
-use bt2_sys::iterator::MessageIterator;
+use bt2_sys::error::BtErrorCause;
+use bt2_sys::iterator::{GraphConfig, IteratorConstructionError, MessageIterator, TraceSource};
 
 use crate::analyses::analysis;
+use crate::analyses::event_cache::{
+    CacheRecord, EventCache, EventCacheReader, EventCacheWriter, SkippedCounts,
+};
+use crate::analyses::progress::{Progress, traces_time_range};
+use crate::argsv2::analysis_args::ProgressMode;
+use crate::model::{Model, Time};
+use crate::raw_events::metadata::{self, MetadataIssue};
 use crate::{processed_events, processor, raw_events};
 
 pub(crate) struct ProcessedEventsIter<'a> {
-    pub(crate) iter: MessageIterator,
+    pub(crate) source: EventSource,
+    /// Cache to which the events decoded from the traces are written.
+    pub(crate) cache_writer: Option<EventCacheWriter>,
     pub(crate) on_unprocessed_event: fn(raw_events::FullEvent),
     pub(crate) analyses: Vec<&'a mut dyn analysis::EventAnalysis>,
     pub(crate) processor: processor::Processor,
 
-    // Counters
+    pub(crate) rolling_stats: Option<RollingStats>,
+    pub(crate) progress: Option<Progress>,
+
+    pub(crate) counters: EventCounters,
+    /// Issues of the trace metadata which were already reported.
+    metadata_issues: HashSet<MetadataIssue>,
+    /// Cleared if some traces could not be read, in which case the events must not be cached.
+    complete: bool,
+}
+
+/// Counters of the iterated events and messages.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub(crate) struct EventCounters {
     pub(crate) ros_processed_events: usize,
     pub(crate) ros_unsupported_events: usize,
     pub(crate) ros_processing_failures: usize,
@@ -28,6 +55,137 @@ pub(crate) struct ProcessedEventsIter<'a> {
     pub(crate) other_messages: usize,
 }
 
+impl EventCounters {
+    /// Number of all events, including the unsupported ones.
+    pub(crate) const fn events(&self) -> usize {
+        self.ros_processed_events
+            + self.ros_unsupported_events
+            + self.ros_processing_failures
+            + self.other_events
+    }
+}
+
+/// Events or packets lost by the tracer, e.g., because its ring buffer of a CPU was full.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiscardedReport {
+    what: &'static str,
+    count: Option<u64>,
+    trace: Option<String>,
+    /// Named by the `ctf` source after the data file, which ends with the CPU number
+    /// for per-CPU buffers.
+    stream: String,
+    begin: Option<Time>,
+    end: Option<Time>,
+}
+
+impl DiscardedReport {
+    /// # Panics
+    /// Panics if the message is neither a discarded events nor a discarded packets message.
+    fn from_message(message: BtMessageConst) -> Self {
+        let time = |snapshot: Option<BtClockSnapshotConst>| {
+            snapshot?.get_value_from_origin().map(Time::from_nanos)
+        };
+        let (what, count, stream, begin, end) = match message.cast() {
+            BtMessageConstCast::DiscardedEvents(message) => (
+                "events",
+                message.get_count(),
+                message.get_stream(),
+                time(message.get_beginning_default_clock_snapshot()),
+                time(message.get_end_default_clock_snapshot()),
+            ),
+            BtMessageConstCast::DiscardedPackets(message) => (
+                "packets",
+                message.get_count(),
+                message.get_stream(),
+                time(message.get_beginning_default_clock_snapshot()),
+                time(message.get_end_default_clock_snapshot()),
+            ),
+            _ => panic!("Message is neither of type DiscardedEvents nor DiscardedPackets"),
+        };
+
+        Self {
+            what,
+            count,
+            trace: stream
+                .get_trace()
+                .get_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            stream: Self::stream_name(&stream),
+            begin,
+            end,
+        }
+    }
+
+    fn stream_name(stream: &BtStreamConst) -> String {
+        stream.get_name().map_or_else(
+            || format!("#{}", stream.get_id()),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+impl std::fmt::Display for DiscardedReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.count {
+            Some(count) => write!(f, "Tracer discarded {count} {}", self.what)?,
+            None => write!(f, "Tracer discarded an unknown number of {}", self.what)?,
+        }
+        write!(f, " in stream {}", self.stream)?;
+        if let Some(trace) = &self.trace {
+            write!(f, " of trace {trace}")?;
+        }
+        if let (Some(begin), Some(end)) = (self.begin, self.end) {
+            write!(f, " between {begin} and {end}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of the raw events.
+pub(crate) enum EventSource {
+    /// Events decoded by babeltrace2.
+    Trace(MessageIterator),
+    /// Events previously decoded from traces and stored in a cache.
+    Cache(EventCacheReader),
+}
+
+/// Schedule of printing the rolling statistics of the analyses.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RollingStats {
+    interval: Duration,
+    last_report: Instant,
+}
+
+impl RollingStats {
+    pub(crate) fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last_report: now,
+        }
+    }
+
+    /// Print the rolling statistics of `analyses` if `interval` elapsed since the last report.
+    ///
+    /// Returns whether the statistics were printed.
+    fn print_if_due(
+        &mut self,
+        now: Instant,
+        analyses: &mut [&mut dyn analysis::EventAnalysis],
+        model: &Model,
+    ) -> bool {
+        if now.duration_since(self.last_report) < self.interval {
+            return false;
+        }
+        self.last_report = now;
+
+        for analysis in analyses {
+            analysis.print_rolling_stats(model);
+        }
+
+        true
+    }
+}
+
 pub(crate) fn convert(level: clap_verbosity_flag::log::Level) -> LogLevel {
     match level {
         clap_verbosity_flag::log::Level::Error => LogLevel::Error,
@@ -39,26 +197,85 @@ pub(crate) fn convert(level: clap_verbosity_flag::log::Level) -> LogLevel {
 }
 
 impl<'a> ProcessedEventsIter<'a> {
-    pub(crate) fn new<L: clap_verbosity_flag::LogLevel>(
-        trace_paths: &[&CStr],
+    /// Create an iterator over the events of `source`.
+    ///
+    /// If the graph cannot be constructed for several trace files, the unreadable traces
+    /// are reported and the remaining ones are analyzed.
+    pub(crate) fn from_source<L: clap_verbosity_flag::LogLevel>(
+        source: TraceSource,
+        graph: GraphConfig,
         verbosity: &clap_verbosity_flag::Verbosity<L>,
-    ) -> Self {
+    ) -> Result<Self> {
         let log_level = convert(
             verbosity
                 .log_level()
                 .unwrap_or(clap_verbosity_flag::log::Level::Error),
         );
+
+        let retry_graph = graph.try_clone()?;
+        let error = match MessageIterator::try_with_graph_config(source, graph, log_level) {
+            Ok(iter) => return Ok(Self::new(EventSource::Trace(iter))),
+            Err(error) => error,
+        };
+        let construction_error = |error: IteratorConstructionError| {
+            eyre!(
+                "Failed to construct the trace processing graph: {}",
+                describe_bt_error(&error, &error.causes())
+            )
+        };
+
+        let TraceSource::Files(trace_paths @ [_, _, ..]) = source else {
+            return Err(construction_error(error));
+        };
+        let readable_paths: Vec<_> = trace_paths
+            .iter()
+            .copied()
+            .filter(
+                |&trace_path| match MessageIterator::try_new(&[trace_path], log_level) {
+                    Ok(_) => true,
+                    Err(error) => {
+                        log::error!(
+                            "Skipping unreadable trace {trace_path:?}: {}",
+                            describe_bt_error(&error, &error.causes())
+                        );
+                        false
+                    }
+                },
+            )
+            .collect();
+        if readable_paths.is_empty() || readable_paths.len() == trace_paths.len() {
+            return Err(construction_error(error));
+        }
+
+        let iter = MessageIterator::try_with_graph_config(
+            TraceSource::Files(&readable_paths),
+            retry_graph,
+            log_level,
+        )
+        .map_err(construction_error)?;
+        let mut iter = Self::new(EventSource::Trace(iter));
+        iter.complete = false;
+        Ok(iter)
+    }
+
+    /// Create an iterator replaying the events stored in a cache.
+    pub(crate) fn from_cache(reader: EventCacheReader) -> Self {
+        Self::new(EventSource::Cache(reader))
+    }
+
+    fn new(source: EventSource) -> Self {
         Self {
-            iter: MessageIterator::new(trace_paths, log_level),
+            source,
+            cache_writer: None,
             on_unprocessed_event: |_event| {}, // Do nothing by default
             analyses: Vec::new(),
             processor: processor::Processor::new(),
+            rolling_stats: None,
+            progress: None,
 
-            ros_processed_events: 0,
-            ros_unsupported_events: 0,
-            ros_processing_failures: 0,
-            other_events: 0,
-            other_messages: 0,
+            counters: EventCounters::default(),
+            metadata_issues: HashSet::new(),
+            complete: true,
         }
     }
 
@@ -87,6 +304,132 @@ impl<'a> ProcessedEventsIter<'a> {
         self.on_unprocessed_event = on_unprocessed_event;
     }
 
+    /// Store the events decoded from the traces in a cache.
+    ///
+    /// The cache is finished when all events were iterated.
+    pub(crate) fn set_cache_writer(&mut self, writer: EventCacheWriter) {
+        self.cache_writer = Some(writer);
+    }
+
+    /// Whether all events of the source are iterated, i.e., no trace was skipped
+    /// because it could not be read.
+    pub(crate) const fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Print the rolling statistics of all analyses every `interval`.
+    pub(crate) fn set_rolling_stats_interval(&mut self, interval: Duration) {
+        self.rolling_stats = Some(RollingStats::new(interval, Instant::now()));
+    }
+
+    /// Print the progress of the iteration to stderr.
+    pub(crate) fn set_progress(&mut self, progress: Progress) {
+        self.progress = Some(progress);
+    }
+
+    fn print_rolling_stats_if_due(&mut self) {
+        if let Some(rolling_stats) = &mut self.rolling_stats {
+            rolling_stats.print_if_due(Instant::now(), &mut self.analyses, self.processor.model());
+        }
+    }
+
+    /// Evict messages which can no longer be matched according to the `retention` limits.
+    ///
+    /// Must be called before the first event is processed.
+    pub(crate) fn set_message_retention(&mut self, retention: processor::MessageRetention) {
+        self.processor = processor::Processor::with_retention(retention);
+    }
+
+    /// Decode the ROS event from a babeltrace2 message and store it in the cache.
+    ///
+    /// Returns `None` if the message is not a supported event.
+    fn decode_message(&mut self, message: BtMessageConst) -> Option<raw_events::FullEvent> {
+        let event_msg = match message.get_type() {
+            BtMessageType::StreamBeginning => {
+                self.check_metadata(message);
+                return None;
+            }
+            BtMessageType::StreamEnd
+            | BtMessageType::PacketBeginning
+            | BtMessageType::PacketEnd => {
+                // Silently skip these messages
+                return None;
+            }
+            BtMessageType::MessageIteratorInactivity if self.rolling_stats.is_some() => {
+                // Live sources send inactivity messages regularly while waiting for events
+                self.counters.other_messages += 1;
+                return None;
+            }
+            BtMessageType::DiscardedEvents | BtMessageType::DiscardedPackets => {
+                log::warn!("{}", DiscardedReport::from_message(message));
+                self.counters.other_messages += 1;
+                return None;
+            }
+            BtMessageType::MessageIteratorInactivity => {
+                log::warn!(
+                    "Skipping babeltrace2 message of type {:?}",
+                    message.get_type()
+                );
+                self.counters.other_messages += 1;
+                return None;
+            }
+            BtMessageType::Event => message.into_event_msg(),
+        };
+
+        let Some(event) = raw_events::get_full_event(&event_msg) else {
+            let event = event_msg.get_event();
+            log::debug!("Unsupported event: {event:?}");
+
+            // Skip unsupported events
+            self.counters.other_events += 1;
+            return None;
+        };
+
+        if let Some(writer) = &mut self.cache_writer
+            && let Err(err) = writer.write_event(&event)
+        {
+            log::warn!("Disabling the event cache: {err:?}");
+            self.cache_writer = None;
+        }
+
+        Some(event)
+    }
+
+    /// Report the issues of the metadata of the trace of a beginning stream.
+    fn check_metadata(&mut self, message: BtMessageConst) {
+        let BtMessageConstCast::StreamBeginning(message) = message.cast() else {
+            panic!("Message is not of type StreamBeginning");
+        };
+        // Live traces get new tracepoints as the traced applications register them
+        let check_tracepoints = self.rolling_stats.is_none();
+        let trace = message.get_stream().get_trace();
+        for issue in metadata::check_trace(&trace, check_tracepoints) {
+            if self.metadata_issues.contains(&issue) {
+                continue;
+            }
+            if issue.is_fatal() {
+                log::warn!("{issue}");
+            } else {
+                log::info!("{issue}");
+            }
+            self.metadata_issues.insert(issue);
+        }
+    }
+
+    /// Finish the cache after all events were decoded.
+    fn finish_cache(&mut self) {
+        let Some(writer) = self.cache_writer.take() else {
+            return;
+        };
+        let skipped = SkippedCounts {
+            other_events: self.counters.other_events,
+            other_messages: self.counters.other_messages,
+        };
+        if let Err(err) = writer.finish(skipped) {
+            log::warn!("Failed to save the event cache: {err:?}");
+        }
+    }
+
     pub(crate) fn log_counters(&self) {
         log::info!(target: "trace_counters",
             "Ros events:\n\
@@ -94,12 +437,17 @@ impl<'a> ProcessedEventsIter<'a> {
         - failed to process: {}\n\
         - unsupported: {}\n\
         Other events: {}\n\
-        Other messages: {}",
-            self.ros_processed_events,
-            self.ros_processing_failures,
-            self.ros_unsupported_events,
-            self.other_events,
-            self.other_messages
+        Other messages: {}\n\
+        Retained messages:\n\
+        - peak: {}\n\
+        - evicted: {}",
+            self.counters.ros_processed_events,
+            self.counters.ros_processing_failures,
+            self.counters.ros_unsupported_events,
+            self.counters.other_events,
+            self.counters.other_messages,
+            self.processor.peak_retained_messages(),
+            self.processor.evicted_messages()
         );
     }
 
@@ -111,11 +459,11 @@ impl<'a> ProcessedEventsIter<'a> {
             - unsupported: {}\n\
             Other events: {}\n\
             Other messages: {}",
-            self.ros_processed_events,
-            self.ros_processing_failures,
-            self.ros_unsupported_events,
-            self.other_events,
-            self.other_messages
+            self.counters.ros_processed_events,
+            self.counters.ros_processing_failures,
+            self.counters.ros_unsupported_events,
+            self.counters.other_events,
+            self.counters.other_messages
         );
     }
 }
@@ -124,68 +472,197 @@ impl Iterator for ProcessedEventsIter<'_> {
     type Item = Result<processed_events::FullEvent>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        for message in self.iter.by_ref() {
-            let event = match message.get_type() {
-                BtMessageType::StreamBeginning
-                | BtMessageType::StreamEnd
-                | BtMessageType::PacketBeginning
-                | BtMessageType::PacketEnd => {
-                    // Silently skip these messages
-                    continue;
-                }
-                BtMessageType::DiscardedEvents
-                | BtMessageType::DiscardedPackets
-                | BtMessageType::MessageIteratorInactivity => {
-                    log::warn!(
-                        "Skipping babeltrace2 message of type {:?}",
-                        message.get_type()
-                    );
-                    self.other_messages += 1;
-                    continue;
-                }
-                BtMessageType::Event => {
-                    let event_msg = message.into_event_msg();
-                    raw_events::get_full_event(&event_msg).ok_or(event_msg)
+        loop {
+            self.print_rolling_stats_if_due();
+
+            let event = match &mut self.source {
+                EventSource::Trace(iter) => {
+                    let message = match iter.try_next() {
+                        Ok(Some(message)) => message,
+                        Ok(None) => {
+                            self.finish_cache();
+                            break;
+                        }
+                        Err(error) => {
+                            log::error!(
+                                "Failed to read the traces, only the events read so far are analyzed: {}",
+                                describe_bt_error(&error, &error.causes())
+                            );
+                            // The cache would miss the remaining events
+                            self.complete = false;
+                            self.cache_writer = None;
+                            break;
+                        }
+                    };
+                    let Some(event) = self.decode_message(message) else {
+                        continue;
+                    };
+                    event
                 }
+                EventSource::Cache(reader) => match reader.read() {
+                    Ok(Some(CacheRecord::Event(event))) => event,
+                    Ok(Some(CacheRecord::End(skipped))) => {
+                        self.counters.other_events += skipped.other_events;
+                        self.counters.other_messages += skipped.other_messages;
+                        continue;
+                    }
+                    Ok(None) => break,
+                    Err(err) => return Some(Err(err)),
+                },
             };
 
-            let Ok(event) = event else {
-                let event_msg = event.unwrap_err();
-                let event = event_msg.get_event();
-                log::debug!("Unsupported event: {event:?}");
+            if let Some(progress) = &mut self.progress {
+                progress.update(Instant::now(), event.time, &self.counters);
+            }
 
-                // Skip unsupported events
-                self.other_events += 1;
-                continue;
-            };
             match self.processor.process_raw_event(event) {
                 Ok(processor::MaybeProcessed::Processed(processed)) => {
-                    self.ros_processed_events += 1;
+                    self.counters.ros_processed_events += 1;
                     for analysis in &mut self.analyses {
-                        (*analysis).process_event(&processed);
+                        (*analysis).process_event(&processed, self.processor.model());
                     }
                     return Some(Ok(processed));
                 }
                 Ok(processor::MaybeProcessed::Raw(raw)) => {
-                    self.ros_unsupported_events += 1;
+                    self.counters.ros_unsupported_events += 1;
                     (self.on_unprocessed_event)(raw);
                     continue;
                 }
                 Err(err) => {
-                    self.ros_processing_failures += 1;
+                    self.counters.ros_processing_failures += 1;
                     return Some(Err(err));
                 }
             }
         }
 
+        if let Some(progress) = &mut self.progress {
+            progress.finish(Instant::now(), &self.counters);
+        }
+
         for analysis in &mut self.analyses {
-            analysis.finalize();
+            analysis.finalize(self.processor.model());
         }
 
         None
     }
 }
 
+/// Process all events of the traces with the given analyses.
+///
+/// Returns the model reconstructed from the events, which is referenced by the analyses.
+pub(crate) fn analyze_traces<'a, L: clap_verbosity_flag::LogLevel>(
+    trace_paths: &[&CStr],
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+    analyses: impl IntoIterator<Item = &'a mut dyn analysis::EventAnalysis>,
+) -> Result<Model> {
+    analyze_source(
+        TraceSource::Files(trace_paths),
+        AnalyzeOptions::default(),
+        verbose,
+        analyses,
+    )
+}
+
+/// Options of [`analyze_source`].
+#[derive(Default)]
+pub(crate) struct AnalyzeOptions<'a> {
+    /// If set, the rolling statistics of the analyses are printed periodically.
+    pub(crate) stats_interval: Option<Duration>,
+    /// Eviction of the messages which can no longer be matched.
+    pub(crate) retention: processor::MessageRetention,
+    /// If set, the events of trace files are read from the cache in the directory
+    /// if it is up to date, otherwise they are stored there for the next analysis.
+    ///
+    /// The cache is not used if `graph` adds any components, as the cached events
+    /// would not pass through them.
+    pub(crate) cache_dir: Option<&'a Path>,
+    /// Printing of the progress of the analysis to stderr.
+    pub(crate) progress: ProgressMode,
+    /// Components added to the trace processing graph.
+    pub(crate) graph: GraphConfig,
+}
+
+/// Process all events from `source` with the given analyses.
+///
+/// Returns the model reconstructed from the events, which is referenced by the analyses.
+pub(crate) fn analyze_source<'a, L: clap_verbosity_flag::LogLevel>(
+    source: TraceSource,
+    options: AnalyzeOptions,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+    analyses: impl IntoIterator<Item = &'a mut dyn analysis::EventAnalysis>,
+) -> Result<Model> {
+    let AnalyzeOptions {
+        stats_interval,
+        retention,
+        cache_dir,
+        progress,
+        graph,
+    } = options;
+
+    if let TraceSource::Live(_) = source {
+        bt2_sys::iterator::stop_on_interrupt();
+    }
+
+    let mut iter = match (cache_dir, source) {
+        (Some(cache_dir), TraceSource::Files(trace_paths)) if graph.is_empty() => {
+            let cache = EventCache::for_traces(cache_dir, trace_paths)?;
+            if let Some(reader) = cache.open()? {
+                println!("Reading events from cache: {}", cache.path().display());
+                ProcessedEventsIter::from_cache(reader)
+            } else {
+                let mut iter = ProcessedEventsIter::from_source(source, graph, verbose)?;
+                if iter.is_complete() {
+                    iter.set_cache_writer(cache.create()?);
+                }
+                iter
+            }
+        }
+        _ => ProcessedEventsIter::from_source(source, graph, verbose)?,
+    };
+    iter.set_message_retention(retention);
+    if let Some(interval) = stats_interval {
+        iter.set_rolling_stats_interval(interval);
+    }
+    if let Some(format) = progress.format(matches!(source, TraceSource::Live(_))) {
+        let range = match source {
+            TraceSource::Files(trace_paths) => traces_time_range(trace_paths),
+            TraceSource::Live(_) => None,
+        };
+        iter.set_progress(Progress::new(format, range, Instant::now()));
+    }
+
+    iter.add_add_analysis(analyses);
+
+    iter.set_on_unprocessed_event(|event| {
+        log::debug!("Unprocessed event: {event:?}");
+    });
+
+    while let Some(event) = iter.next() {
+        let event = event.wrap_err("Failed to process event")?;
+        log::trace!("{}", iter.processor.model().display(&event));
+    }
+
+    iter.log_counters();
+
+    Ok(iter.processor.into_model())
+}
+
+/// Describe a babeltrace2 error by its causes, the most recent cause first.
+///
+/// Falls back to `error` itself if it has no causes.
+pub(crate) fn describe_bt_error(error: &dyn std::fmt::Display, causes: &[BtErrorCause]) -> String {
+    if causes.is_empty() {
+        return error.to_string();
+    }
+
+    causes
+        .iter()
+        .rev()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n  Caused by: ")
+}
+
 pub(crate) fn get_buf_writer_for_path(path: &Path) -> Result<BufWriter<File>> {
     std::fs::create_dir_all(path.parent().unwrap())
         .wrap_err_with(|| format!("Failed to create directory: `{:?}`", path.parent()))?;
@@ -193,3 +670,115 @@ pub(crate) fn get_buf_writer_for_path(path: &Path) -> Result<BufWriter<File>> {
         File::create(path).wrap_err_with(|| format!("Failed to create file: `{path:?}`"))?;
     Ok(BufWriter::new(out_file))
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use bt2_sys::error::BtErrorCause;
+
+    use super::{DiscardedReport, RollingStats, describe_bt_error};
+    use crate::analyses::analysis::EventAnalysis;
+    use crate::analyses::analysis::utils::RollingReport;
+    use crate::model::{Model, Time};
+    use crate::processed_events::FullEvent;
+
+    /// Analysis recording its rolling reports instead of printing them.
+    #[derive(Default)]
+    struct RecordingAnalysis {
+        values: Vec<i64>,
+        reported: RollingReport<()>,
+        reports: Vec<Vec<i64>>,
+    }
+
+    impl EventAnalysis for RecordingAnalysis {
+        fn initialize(&mut self) {}
+
+        fn process_event(&mut self, _event: &FullEvent, _model: &Model) {}
+
+        fn finalize(&mut self, _model: &Model) {}
+
+        fn print_rolling_stats(&mut self, _model: &Model) {
+            let new_values = self.reported.new_values(&(), &self.values);
+            if !new_values.is_empty() {
+                self.reports.push(new_values.to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_stats_report_new_values() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut rolling_stats = RollingStats::new(Duration::from_secs(1), start);
+        let mut analysis = RecordingAnalysis::default();
+        let model = Model::default();
+
+        analysis.values.extend([1, 2]);
+        assert!(!rolling_stats.print_if_due(at(500), &mut [&mut analysis], &model));
+        assert!(analysis.reports.is_empty());
+        assert!(rolling_stats.print_if_due(at(1000), &mut [&mut analysis], &model));
+        assert_eq!(analysis.reports, [vec![1, 2]]);
+
+        // The interval starts at the last report
+        analysis.values.push(3);
+        assert!(!rolling_stats.print_if_due(at(1900), &mut [&mut analysis], &model));
+        assert!(rolling_stats.print_if_due(at(2000), &mut [&mut analysis], &model));
+        assert_eq!(analysis.reports, [vec![1, 2], vec![3]]);
+
+        // Nothing new to report
+        assert!(rolling_stats.print_if_due(at(3000), &mut [&mut analysis], &model));
+        assert_eq!(analysis.reports.len(), 2);
+    }
+
+    #[test]
+    fn test_discarded_report() {
+        let begin = Time::from_nanos(1_000);
+        let end = Time::from_nanos(2_000);
+        let mut report = DiscardedReport {
+            what: "events",
+            count: Some(42),
+            trace: Some("ros2_trace".into()),
+            stream: "ust/uid/1000/64-bit/ros2_3".into(),
+            begin: Some(begin),
+            end: Some(end),
+        };
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "Tracer discarded 42 events in stream ust/uid/1000/64-bit/ros2_3 \
+                of trace ros2_trace between {begin} and {end}"
+            )
+        );
+
+        report.what = "packets";
+        report.count = None;
+        report.trace = None;
+        report.begin = None;
+        assert_eq!(
+            report.to_string(),
+            "Tracer discarded an unknown number of packets in stream ust/uid/1000/64-bit/ros2_3"
+        );
+    }
+
+    #[test]
+    fn test_describe_bt_error() {
+        let cause = |module_name: &str, message: &str| BtErrorCause {
+            message: message.into(),
+            module_name: module_name.into(),
+            file_name: "file.c".into(),
+            line_number: 7,
+        };
+        let causes = [
+            cause("CTF-FS-SRC", "Cannot open file"),
+            cause("Rust Error", "Failed to get next batch of messages"),
+        ];
+
+        assert_eq!(
+            describe_bt_error(&"ignored", &causes),
+            "[Rust Error] (file.c:7) Failed to get next batch of messages\n  \
+            Caused by: [CTF-FS-SRC] (file.c:7) Cannot open file"
+        );
+        assert_eq!(describe_bt_error(&"Out of memory", &[]), "Out of memory");
+    }
+}
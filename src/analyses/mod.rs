@@ -1,15 +1,19 @@
 use std::ffi::CStr;
 use std::io::Write;
 
+use bt2_sys::iterator::TraceSource;
 use color_eyre::eyre::Context;
 
 use crate::analyses::analysis::AnalysisOutputExt;
-use crate::analyses::event_iterator::get_buf_writer_for_path;
+use crate::analyses::event_iterator::{AnalyzeOptions, get_buf_writer_for_path};
 use crate::argsv2::analysis_args::AnalysisArgs;
+use crate::model::Model;
 use crate::utils::binary_sql_store::BinarySqlStore;
 
 pub mod analysis;
+pub mod event_cache;
 pub mod event_iterator;
+pub mod progress;
 
 #[derive(Default)]
 pub struct Analyses {
@@ -19,6 +23,14 @@ pub struct Analyses {
     message_take_to_callback_analysis: Option<analysis::MessageTakeToCallbackLatency>,
     dependency_graph: Option<analysis::DependencyGraph>,
     spin_duration_analysis: Option<analysis::SpinDuration>,
+    executor_starvation_analysis: Option<analysis::ExecutorStarvation>,
+    cpu_affinity_analysis: Option<analysis::CpuAffinity>,
+    topic_rate_analysis: Option<analysis::TopicRate>,
+    timeline: Option<analysis::Timeline>,
+    otlp_spans: Option<analysis::OtlpSpans>,
+
+    /// Objects reconstructed from the analyzed trace, referenced by the analyses.
+    model: Model,
 }
 
 impl Analyses {
@@ -39,9 +51,17 @@ impl Analyses {
             ))
             .chain(option_to_dyn_iter(&mut self.dependency_graph))
             .chain(option_to_dyn_iter(&mut self.spin_duration_analysis))
+            .chain(option_to_dyn_iter(&mut self.executor_starvation_analysis))
+            .chain(option_to_dyn_iter(&mut self.cpu_affinity_analysis))
+            .chain(option_to_dyn_iter(&mut self.topic_rate_analysis))
+            .chain(option_to_dyn_iter(&mut self.timeline))
+            .chain(option_to_dyn_iter(&mut self.otlp_spans))
     }
 
-    pub fn add_analyses_from_args(&mut self, args: &crate::argsv2::analysis_args::AnalysisArgs) {
+    pub fn add_analyses_from_args(
+        &mut self,
+        args: &crate::argsv2::analysis_args::AnalysisArgs,
+    ) -> color_eyre::eyre::Result<()> {
         if args.message_latency_enabled() {
             self.message_latency_analysis = Some(analysis::MessageLatency::new());
         }
@@ -69,58 +89,152 @@ impl Analyses {
         if args.spin_duration_enabled() {
             self.spin_duration_analysis = Some(analysis::SpinDuration::new());
         }
+
+        if args.executor_starvation_enabled() {
+            self.executor_starvation_analysis = Some(analysis::ExecutorStarvation::new(
+                args.starvation_threshold().as_nanos(),
+            ));
+        }
+
+        if args.cpu_affinity_enabled() {
+            let spec = args
+                .affinity_spec()
+                .map(analysis::cpu_affinity::AffinitySpec::from_file)
+                .transpose()?
+                .unwrap_or_default();
+            self.cpu_affinity_analysis = Some(analysis::CpuAffinity::new(spec));
+        }
+
+        if args.topic_rate_enabled() {
+            self.topic_rate_analysis =
+                Some(analysis::TopicRate::new(args.rate_window().as_nanos()));
+        }
+
+        if args.chrome_trace_enabled() {
+            self.timeline = Some(analysis::Timeline::new());
+        }
+
+        if args.otlp_enabled() {
+            self.otlp_spans = Some(analysis::OtlpSpans::new());
+        }
+
+        Ok(())
     }
 
     pub fn analyze_trace<L: clap_verbosity_flag::LogLevel>(
         &mut self,
         trace_paths: Vec<&CStr>,
+        options: AnalyzeOptions,
         verbose: &clap_verbosity_flag::Verbosity<L>,
     ) -> color_eyre::eyre::Result<()> {
-        let mut iter = event_iterator::ProcessedEventsIter::new(&trace_paths, verbose);
-
-        iter.add_add_analysis(self.all_as_mut());
-
-        iter.set_on_unprocessed_event(|event| {
-            log::debug!("Unprocessed event: {event:?}");
-        });
-
-        for event in &mut iter {
-            let event = event.wrap_err("Failed to process event")?;
-            log::trace!("{event}");
-        }
-
-        iter.log_counters();
+        self.model = event_iterator::analyze_source(
+            TraceSource::Files(&trace_paths),
+            options,
+            verbose,
+            self.all_as_mut(),
+        )?;
+        Ok(())
+    }
 
+    /// Analyze a running LTTng live session.
+    ///
+    /// The rolling statistics are printed every `options.stats_interval`.
+    pub fn analyze_live<L: clap_verbosity_flag::LogLevel>(
+        &mut self,
+        url: &CStr,
+        options: AnalyzeOptions,
+        verbose: &clap_verbosity_flag::Verbosity<L>,
+    ) -> color_eyre::eyre::Result<()> {
+        self.model = event_iterator::analyze_source(
+            TraceSource::Live(url),
+            options,
+            verbose,
+            self.all_as_mut(),
+        )?;
         Ok(())
     }
 
     pub fn save_output(&self, args: &AnalysisArgs) -> color_eyre::eyre::Result<()> {
+        let model = &self.model;
+
+        // The timeline and spans are not part of the binary bundle
+        if let Some(path) = args.chrome_trace_path() {
+            let analysis = self.timeline.as_ref().unwrap();
+            analysis
+                .write_json_to_output_dir(model, &path)
+                .wrap_err("Failed to write the Chrome trace")?;
+        }
+
+        if let Some(path) = args.otlp_path() {
+            let analysis = self.otlp_spans.as_ref().unwrap();
+            analysis
+                .write_json_to_output_dir(model, &path)
+                .wrap_err("Failed to write the OTLP spans")?;
+        }
+
         if args.bundle_output()
             && let Some(path) = args.binary_bundle_path()
         {
-            if let Some(graph_analysis) = &self.dependency_graph {
-                let mut store = BinarySqlStore::new(&path)?;
+            if self.dependency_graph.is_none()
+                && self.topic_rate_analysis.is_none()
+                && self.executor_starvation_analysis.is_none()
+                && self.cpu_affinity_analysis.is_none()
+            {
+                return Ok(());
+            }
+
+            let mut store = BinarySqlStore::new(&path)?;
 
-                let dot_graph = graph_analysis.to_dot_graph(false, false, 1.0);
+            let dot_graph = self
+                .dependency_graph
+                .as_ref()
+                .map(|graph_analysis| graph_analysis.to_dot_graph(model, false, false, 1.0));
 
+            if let (Some(graph_analysis), Some(dot_graph)) = (&self.dependency_graph, &dot_graph) {
                 store.insert(&[crate::utils::binary_sql_store::DependencyGraph {
                     graph: dot_graph.to_string(),
                 }])?;
 
+                store.insert(&graph_analysis.message_latencies(
+                    model,
+                    dot_graph.node_ids(),
+                    dot_graph.edge_ids(),
+                ))?;
+                store.insert(&graph_analysis.activation_delays(model, dot_graph.node_ids()))?;
+                store.insert(&graph_analysis.publication_delays(model, dot_graph.node_ids()))?;
+                store.insert(&graph_analysis.callback_durations(model, dot_graph.node_ids()))?;
+                store.insert(&graph_analysis.message_delays(model, dot_graph.node_ids()))?;
+                store.insert(&graph_analysis.node_overview(dot_graph.node_ids()))?;
+            }
+
+            if let Some(topic_rate) = &self.topic_rate_analysis {
                 store.insert(
-                    &graph_analysis.message_latencies(dot_graph.node_ids(), dot_graph.edge_ids()),
+                    &topic_rate.topic_rates(model, dot_graph.as_ref().map(|g| g.node_ids())),
+                )?;
+            }
+
+            if let Some(starvation) = &self.executor_starvation_analysis {
+                store.insert(
+                    &starvation.starvation(model, dot_graph.as_ref().map(|g| g.node_ids())),
                 )?;
-                store.insert(&graph_analysis.activation_delays(dot_graph.node_ids()))?;
-                store.insert(&graph_analysis.publication_delays(dot_graph.node_ids()))?;
-                store.insert(&graph_analysis.callback_durations(dot_graph.node_ids()))?;
-                store.insert(&graph_analysis.message_delays(dot_graph.node_ids()))?;
-                store.insert(&graph_analysis.node_overview(dot_graph.node_ids()))?;
+            }
+
+            if let Some(affinity) = &self.cpu_affinity_analysis {
+                store.insert(
+                    &affinity.callback_affinities(model, dot_graph.as_ref().map(|g| g.node_ids())),
+                )?;
+                store.insert(&affinity.thread_affinities())?;
+                store.insert(&affinity.affinity_violations(model))?;
             }
         } else {
             if let Some(path) = args.dependency_graph_path() {
                 let analysis = self.dependency_graph.as_ref().unwrap();
-                let dot_output =
-                    analysis.to_dot_graph(args.color(), args.thickness(), args.min_multiplier());
+                let dot_output = analysis.to_dot_graph(
+                    model,
+                    args.color(),
+                    args.thickness(),
+                    args.min_multiplier(),
+                );
                 let mut writer = get_buf_writer_for_path(&path)?;
                 writer
                     .write_fmt(format_args!("{dot_output}"))
@@ -133,40 +247,61 @@ impl Analyses {
                 let graph = analysis.get_graph().unwrap();
                 let mut writer = get_buf_writer_for_path(&path)?;
                 writer
-                    .write_fmt(format_args!("{}", graph.as_dot()))
+                    .write_fmt(format_args!("{}", graph.as_dot(model)))
                     .wrap_err("Failed to write callback graph")?;
             }
 
             if let Some(path) = args.message_latency_path() {
                 let analysis = self.message_latency_analysis.as_ref().unwrap();
-                analysis.write_json_to_output_dir(&path)?;
+                analysis.write_json_to_output_dir(model, &path)?;
             }
 
             if let Some(path) = args.callback_duration_path() {
                 let analysis = self.callback_analysis.as_ref().unwrap();
-                analysis.write_json_to_output_dir(&path)?;
+                analysis.write_json_to_output_dir(model, &path)?;
             }
 
             if let Some(path) = args.message_take_to_callback_latency_path() {
                 let analysis = self.message_take_to_callback_analysis.as_ref().unwrap();
                 analysis
-                    .write_json_to_output_dir(&path)
+                    .write_json_to_output_dir(model, &path)
                     .wrap_err("Failed to write message take to callback latency stats")?;
             }
 
             if let Some(path) = args.spin_duration_path() {
                 let analysis = self.spin_duration_analysis.as_ref().unwrap();
                 analysis
-                    .write_json_to_output_dir(&path)
+                    .write_json_to_output_dir(model, &path)
                     .wrap_err("Failed to write spin duration stats")?;
             }
 
+            if let Some(path) = args.executor_starvation_path() {
+                let analysis = self.executor_starvation_analysis.as_ref().unwrap();
+                analysis
+                    .write_json_to_output_dir(model, &path)
+                    .wrap_err("Failed to write executor starvation stats")?;
+            }
+
+            if let Some(path) = args.cpu_affinity_path() {
+                let analysis = self.cpu_affinity_analysis.as_ref().unwrap();
+                analysis
+                    .write_json_to_output_dir(model, &path)
+                    .wrap_err("Failed to write CPU affinity stats")?;
+            }
+
+            if let Some(path) = args.topic_rate_path() {
+                let analysis = self.topic_rate_analysis.as_ref().unwrap();
+                analysis
+                    .write_json_to_output_dir(model, &path)
+                    .wrap_err("Failed to write topic rate stats")?;
+            }
+
             if let Some(path) = args.callback_publications_path() {
                 let analysis = self.callback_dependency_analysis.as_ref().unwrap();
                 let analysis = analysis.get_publication_in_callback_analysis();
                 let mut writer = get_buf_writer_for_path(&path)?;
                 analysis
-                    .write_stats(&mut writer)
+                    .write_stats(model, &mut writer)
                     .wrap_err("Failed to write publication in callback stats")?;
 
                 // TODO: Implement JSON output
@@ -175,7 +310,7 @@ impl Analyses {
 
             if let Some(path) = args.utilization_path() {
                 let analysis = self.callback_analysis.as_ref().unwrap();
-                let utilization = analysis::Utilization::new(analysis);
+                let utilization = analysis::Utilization::new(analysis, model);
 
                 let mut writer = get_buf_writer_for_path(&path)?;
                 utilization
@@ -188,7 +323,7 @@ impl Analyses {
 
             if let Some(path) = args.real_utilization_path() {
                 let analysis = self.callback_analysis.as_ref().unwrap();
-                let utilization = analysis::Utilization::new(analysis);
+                let utilization = analysis::Utilization::new(analysis, model);
 
                 let mut writer = get_buf_writer_for_path(&path)?;
                 utilization
@@ -196,7 +331,7 @@ impl Analyses {
                     .wrap_err("Failed to write real utilization stats")?;
 
                 // TODO: Implement JSON output
-                // utilization.write_json_to_output_dir(&path)?;
+                // utilization.write_json_to_output_dir(model, &path)?;
             }
         }
 
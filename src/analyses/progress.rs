@@ -0,0 +1,279 @@
+//! Progress of the analysis printed to stderr.
+
+use std::ffi::CStr;
+use std::fmt::Display;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use bt2_sys::query::{TimeRangeNs, trace_infos};
+use serde::Serialize;
+
+use crate::analyses::event_iterator::EventCounters;
+use crate::events_common::Time;
+
+/// Format of the progress reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProgressFormat {
+    /// Single line rewritten by each report, for terminals.
+    Text,
+    /// One JSON object per line, for wrapper programs.
+    Json,
+}
+
+impl ProgressFormat {
+    const fn interval(self) -> Duration {
+        match self {
+            Self::Text => Duration::from_millis(200),
+            Self::Json => Duration::from_secs(1),
+        }
+    }
+}
+
+/// Query the time range of all traces.
+///
+/// Returns `None` if the range is not available.
+pub(crate) fn traces_time_range(trace_paths: &[&CStr]) -> Option<TimeRangeNs> {
+    let traces = trace_infos::Query::new_prepared()
+        .map_err(|e| log::warn!("Failed to prepare trace infos query: {e}"))
+        .ok()?
+        .query(trace_paths)
+        .map_err(|e| log::warn!("Failed to query trace infos: {e}"))
+        .ok()?;
+
+    for trace in &traces {
+        match trace.range_ns() {
+            Some(range) => log::debug!(
+                "Trace {} has {} streams from {} to {}",
+                trace.name(),
+                trace.stream_infos().len(),
+                Time::from_nanos(range.begin),
+                Time::from_nanos(range.end),
+            ),
+            None => log::debug!(
+                "Trace {} has {} streams without time range",
+                trace.name(),
+                trace.stream_infos().len(),
+            ),
+        }
+    }
+
+    traces
+        .iter()
+        .filter_map(bt2_sys::query::TraceInfo::range_ns)
+        .reduce(TimeRangeNs::union)
+}
+
+/// Progress of the analysis with throughput and estimated remaining time.
+#[derive(Debug)]
+pub(crate) struct Progress {
+    format: ProgressFormat,
+    /// Time range of the analyzed traces, if known.
+    range: Option<TimeRangeNs>,
+    start: Instant,
+    last_report: Instant,
+    /// Time of the last iterated event.
+    current: Option<Time>,
+}
+
+impl Progress {
+    pub(crate) const fn new(
+        format: ProgressFormat,
+        range: Option<TimeRangeNs>,
+        now: Instant,
+    ) -> Self {
+        Self {
+            format,
+            range,
+            start: now,
+            last_report: now,
+            current: None,
+        }
+    }
+
+    /// Record the time of the iterated event and print a report if it is due.
+    pub(crate) fn update(&mut self, now: Instant, time: Time, counters: &EventCounters) {
+        self.current = Some(time);
+        if now.duration_since(self.last_report) >= self.format.interval() {
+            self.last_report = now;
+            self.print(&self.report(now, counters, false));
+        }
+    }
+
+    /// Print the final report.
+    pub(crate) fn finish(&mut self, now: Instant, counters: &EventCounters) {
+        self.print(&self.report(now, counters, true));
+        if self.format == ProgressFormat::Text {
+            eprintln!();
+        }
+    }
+
+    fn report(&self, now: Instant, counters: &EventCounters, done: bool) -> ProgressReport {
+        let elapsed = now.duration_since(self.start).as_secs_f64();
+        let fraction = if done {
+            Some(1.0)
+        } else {
+            self.range.zip(self.current).and_then(|(range, current)| {
+                let length = range.end - range.begin;
+                (length > 0).then(|| {
+                    ((current.timestamp_nanos() - range.begin) as f64 / length as f64)
+                        .clamp(0.0, 1.0)
+                })
+            })
+        };
+
+        ProgressReport {
+            done,
+            trace_time_ns: self.current.map(Time::timestamp_nanos),
+            fraction,
+            elapsed_s: elapsed,
+            events_per_s: if elapsed > 0.0 {
+                counters.events() as f64 / elapsed
+            } else {
+                0.0
+            },
+            eta_s: fraction
+                .filter(|&fraction| fraction > 0.0)
+                .map(|fraction| elapsed * (1.0 - fraction) / fraction),
+            counters: *counters,
+        }
+    }
+
+    fn print(&self, report: &ProgressReport) {
+        let mut stderr = std::io::stderr().lock();
+        // Progress is best effort, a closed stderr must not stop the analysis
+        let _ = match self.format {
+            ProgressFormat::Text => write!(stderr, "\r\x1b[K{report}"),
+            ProgressFormat::Json => serde_json::to_writer(&mut stderr, report)
+                .map_err(std::io::Error::from)
+                .and_then(|()| writeln!(stderr)),
+        };
+    }
+}
+
+/// Single progress report.
+#[derive(Debug, Clone, Serialize)]
+struct ProgressReport {
+    done: bool,
+    /// Timestamp of the last iterated event in nanoseconds since the UNIX epoch.
+    trace_time_ns: Option<i64>,
+    /// Fraction of the trace time range already analyzed.
+    fraction: Option<f64>,
+    elapsed_s: f64,
+    events_per_s: f64,
+    /// Estimated remaining time.
+    eta_s: Option<f64>,
+    #[serde(flatten)]
+    counters: EventCounters,
+}
+
+struct DisplaySeconds(f64);
+
+impl Display for DisplaySeconds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.0.round() as u64;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours > 0 {
+            write!(f, "{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            write!(f, "{minutes}:{seconds:02}")
+        }
+    }
+}
+
+impl Display for ProgressReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(fraction) = self.fraction {
+            write!(f, "{:5.1}% | ", fraction * 100.0)?;
+        }
+        if let Some(time) = self.trace_time_ns {
+            write!(f, "{} | ", Time::from_nanos(time))?;
+        }
+        write!(
+            f,
+            "{} events ({:.0}/s) | elapsed {}",
+            self.counters.events(),
+            self.events_per_s,
+            DisplaySeconds(self.elapsed_s)
+        )?;
+        if let Some(eta) = self.eta_s
+            && !self.done
+        {
+            write!(f, " | ETA {}", DisplaySeconds(eta))?;
+        }
+        write!(
+            f,
+            " | processed {}, unsupported {}, failed {}, other {}",
+            self.counters.ros_processed_events,
+            self.counters.ros_unsupported_events,
+            self.counters.ros_processing_failures,
+            self.counters.other_events
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use bt2_sys::query::TimeRangeNs;
+
+    use super::{DisplaySeconds, Progress, ProgressFormat};
+    use crate::analyses::event_iterator::EventCounters;
+    use crate::events_common::Time;
+
+    #[test]
+    fn test_progress_report_eta() {
+        let start = Instant::now();
+        let mut progress = Progress::new(
+            ProgressFormat::Json,
+            Some(TimeRangeNs {
+                begin: 1_000,
+                end: 5_000,
+            }),
+            start,
+        );
+        let counters = EventCounters {
+            ros_processed_events: 900,
+            other_events: 100,
+            ..Default::default()
+        };
+
+        progress.current = Some(Time::from_nanos(2_000));
+        let report = progress.report(start + Duration::from_secs(10), &counters, false);
+        assert_eq!(report.fraction, Some(0.25));
+        assert_eq!(report.events_per_s, 100.0);
+        assert_eq!(report.eta_s, Some(30.0));
+
+        let report = progress.report(start + Duration::from_secs(20), &counters, true);
+        assert_eq!(report.fraction, Some(1.0));
+        assert_eq!(report.eta_s, Some(0.0));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["done"], true);
+        assert_eq!(json["trace_time_ns"], 2_000);
+        assert_eq!(json["ros_processed_events"], 900);
+        assert_eq!(json["other_events"], 100);
+    }
+
+    #[test]
+    fn test_progress_without_range() {
+        let start = Instant::now();
+        let mut progress = Progress::new(ProgressFormat::Text, None, start);
+        progress.current = Some(Time::from_nanos(2_000));
+
+        let report = progress.report(
+            start + Duration::from_secs(1),
+            &EventCounters::default(),
+            false,
+        );
+        assert_eq!(report.fraction, None);
+        assert_eq!(report.eta_s, None);
+    }
+
+    #[test]
+    fn test_display_seconds() {
+        assert_eq!(DisplaySeconds(5.4).to_string(), "0:05");
+        assert_eq!(DisplaySeconds(125.0).to_string(), "2:05");
+        assert_eq!(DisplaySeconds(3725.0).to_string(), "1:02:05");
+    }
+}
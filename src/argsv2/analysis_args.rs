@@ -1,11 +1,17 @@
 use std::borrow::Cow;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
+use bt2_sys::iterator::{GraphConfig, PluginComponent};
 use clap::builder::ArgPredicate;
-use clap::{Parser, ValueHint};
+use clap::{Parser, ValueEnum, ValueHint};
+use color_eyre::eyre::{Context, ensure};
 
+use crate::analyses::progress::ProgressFormat;
+use crate::argsv2::helpers::resolve_time_range;
 use crate::statistics::Quantile;
+use crate::utils::{ByteSize, NsDuration, TraceTime};
 
 pub(super) mod filenames {
     pub const DEPENDENCY_GRAPH: &str = "dependency_graph.dot";
@@ -17,6 +23,11 @@ pub(super) mod filenames {
     pub const UTILIZATION: &str = "utilization.txt";
     pub const REAL_UTILIZATION: &str = "real_utilization.txt";
     pub const SPIN_DURATION: &str = "spin_duration.json";
+    pub const EXECUTOR_STARVATION: &str = "executor_starvation.json";
+    pub const CPU_AFFINITY: &str = "cpu_affinity.json";
+    pub const TOPIC_RATE: &str = "topic_rate.json";
+    pub const CHROME_TRACE: &str = "timeline.json";
+    pub const OTLP_SPANS: &str = "spans.otlp.json";
 
     pub const BINARY_BUNDLE: &str = "r2ta_results.sqlite";
 }
@@ -41,6 +52,11 @@ pub struct AnalysisArgs {
             ("utilization", ArgPredicate::IsPresent, "false"),
             ("real_utilization", ArgPredicate::IsPresent, "false"),
             ("spin_duration", ArgPredicate::IsPresent, "false"),
+            ("executor_starvation", ArgPredicate::IsPresent, "false"),
+            ("cpu_affinity", ArgPredicate::IsPresent, "false"),
+            ("topic_rate", ArgPredicate::IsPresent, "false"),
+            ("chrome_trace", ArgPredicate::IsPresent, "false"),
+            ("otlp", ArgPredicate::IsPresent, "false"),
             ]))]
     all: bool,
 
@@ -80,6 +96,41 @@ pub struct AnalysisArgs {
     #[arg(long, value_name = "FILENAME", default_missing_value = filenames::SPIN_DURATION, num_args = 0..=1, require_equals = true, default_value_if("all", "true", filenames::SPIN_DURATION))]
     spin_duration: Option<PathBuf>,
 
+    /// Detect callbacks blocked by other callbacks running on the same executor thread
+    #[arg(long, value_name = "FILENAME", default_missing_value = filenames::EXECUTOR_STARVATION, num_args = 0..=1, require_equals = true, default_value_if("all", "true", filenames::EXECUTOR_STARVATION))]
+    executor_starvation: Option<PathBuf>,
+
+    /// Analyze on which CPUs the callbacks ran and the migrations of threads and callbacks
+    #[arg(long, value_name = "FILENAME", default_missing_value = filenames::CPU_AFFINITY, num_args = 0..=1, require_equals = true, default_value_ifs([
+        ("all", ArgPredicate::Equals("true".into()), filenames::CPU_AFFINITY),
+        ("affinity_spec", ArgPredicate::IsPresent, filenames::CPU_AFFINITY),
+        ]))]
+    cpu_affinity: Option<PathBuf>,
+
+    /// Analyze the rate of messages on topics per publisher and subscriber
+    #[arg(long, value_name = "FILENAME", default_missing_value = filenames::TOPIC_RATE, num_args = 0..=1, require_equals = true, default_value_if("all", "true", filenames::TOPIC_RATE))]
+    topic_rate: Option<PathBuf>,
+
+    /// Export the timeline of callbacks, executor spins and messages in the Chrome Trace Event format
+    ///
+    /// Each thread is a track with a slice for each callback execution. Messages are flow arrows
+    /// from their publication to the callback processing them. Open the file
+    /// in <https://ui.perfetto.dev> or `chrome://tracing`.
+    ///
+    /// The timeline is not exported by `--all`, because its size grows with the trace.
+    #[arg(long, visible_alias = "perfetto", value_name = "FILENAME", default_missing_value = filenames::CHROME_TRACE, num_args = 0..=1, require_equals = true)]
+    chrome_trace: Option<PathBuf>,
+
+    /// Export the callbacks and messages as OpenTelemetry spans in the OTLP/JSON format
+    ///
+    /// Each callback instance is a span. Each message is a child span of the callback publishing
+    /// it and the parent span of the callback processing it, so causal chains of callbacks share
+    /// a trace ID. The file can be loaded into OpenTelemetry tools without a live collector.
+    ///
+    /// The spans are not exported by `--all`, because their size grows with the trace.
+    #[arg(long, value_name = "FILENAME", default_missing_value = filenames::OTLP_SPANS, num_args = 0..=1, require_equals = true)]
+    otlp: Option<PathBuf>,
+
     /// File path of the binary bundle output
     #[arg(long, value_name = "FILENAME", default_value = filenames::BINARY_BUNDLE, num_args = 0..=1)]
     binary_bundle: Option<PathBuf>,
@@ -133,10 +184,111 @@ pub struct AnalysisArgs {
     #[arg(long, default_value = "5.0")]
     min_multiplier: f64,
 
+    /// Minimum time a callback has to be blocked to be reported by the executor starvation analysis.
+    ///
+    /// Supported units are: ns, us, ms, s, min (e.g. `500us`, `1.5ms`).
+    #[arg(long, default_value = "1ms", value_name = "DURATION")]
+    starvation_threshold: NsDuration,
+
+    /// TOML file with the expected CPU affinity of nodes and processes.
+    ///
+    /// Each `[[rule]]` contains either `node` (full node name) or `process` (process name)
+    /// and `cpus` (CPU list, e.g. `"2-3,6"`). Violations are reported by the CPU affinity analysis,
+    /// which is enabled by this option.
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    affinity_spec: Option<PathBuf>,
+
+    /// Length of the sliding window used to detect topic rate drops.
+    ///
+    /// Supported units are: ns, us, ms, s, min.
+    #[arg(long, default_value = "1s", value_name = "DURATION")]
+    rate_window: NsDuration,
+
+    /// Maximum time a message is kept for matching publications with subscriptions.
+    ///
+    /// Messages published or received longer than this before the currently processed event
+    /// are evicted. This bounds the memory used by long traces at the cost of not matching
+    /// messages delayed by more than the horizon. Supported units are: ns, us, ms, s, min.
+    #[arg(long, value_name = "DURATION")]
+    match_horizon: Option<NsDuration>,
+
+    /// Approximate memory limit of the messages kept for matching publications with subscriptions.
+    ///
+    /// When the limit is exceeded, the oldest messages are evicted.
+    /// The data collected by the analyses is not limited.
+    /// Supported units are: B, kB, MB, GB, KiB, MiB, GiB (e.g. `512MiB`).
+    #[arg(long, value_name = "SIZE")]
+    max_memory: Option<ByteSize>,
+
+    /// URL of a running LTTng live session to analyze instead of trace directories
+    ///
+    /// The URL has the form `net://<relay host>/host/<traced host>/<session>`.
+    /// The analysis waits for the session to be created and runs until interrupted with Ctrl+C.
+    /// The results are saved afterwards.
+    #[arg(long, value_name = "URL", conflicts_with = "trace_paths")]
+    live: Option<String>,
+
+    /// Directory for caching the raw ROS events decoded from the traces.
+    ///
+    /// The first analysis of traces stores their raw events in the directory. Later analyses
+    /// of the same traces read the raw events from the cache instead of decoding the traces;
+    /// the events are still processed and analyzed again.
+    /// The cache is recreated when the trace files change.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, conflicts_with_all = ["live", "begin", "end", "debug_info", "trace_copy"])]
+    cache_dir: Option<PathBuf>,
+
+    /// Interval of printing rolling statistics when analyzing a live session.
+    ///
+    /// Supported units are: ns, us, ms, s, min.
+    #[arg(long, default_value = "5s", value_name = "DURATION", requires = "live", value_parser = parse_stats_interval)]
+    stats_interval: NsDuration,
+
+    /// Printing of the analysis progress to stderr.
+    ///
+    /// The progress shows the analyzed part of the traces, the event throughput, the estimated
+    /// remaining time and the event counters.
+    #[arg(long, value_enum, default_value_t, value_name = "MODE")]
+    progress: ProgressMode,
+
+    /// Only analyze the events at or after this time.
+    ///
+    /// The time is an RFC 3339 timestamp (e.g. `2024-05-01T12:00:00Z`), a number
+    /// of nanoseconds since the UNIX epoch or a duration from the beginning of the traces
+    /// prefixed by `+` (e.g. `+10s`).
+    #[arg(long, value_name = "TIME")]
+    begin: Option<TraceTime>,
+
+    /// Only analyze the events at or before this time.
+    ///
+    /// The time has the same format as `--begin`.
+    #[arg(long, value_name = "TIME")]
+    end: Option<TraceTime>,
+
+    /// Add the source locations of instruction pointers to the events.
+    ///
+    /// The locations are resolved by the `lttng-utils.debug-info` component
+    /// and stored in the trace copy written by `--trace-copy`.
+    #[arg(long)]
+    debug_info: bool,
+
+    /// Directory with debug information files searched in addition to the default directories.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, requires = "debug_info")]
+    debug_info_dir: Option<PathBuf>,
+
+    /// Prefix of the paths of binaries, if the trace was recorded on another system.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, requires = "debug_info")]
+    debug_info_target_prefix: Option<PathBuf>,
+
+    /// Write a copy of the analyzed events as CTF traces to the directory.
+    ///
+    /// The copy contains only the events selected by `--begin` and `--end`.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+    trace_copy: Option<PathBuf>,
+
     /// Paths to directories to search for the trace to analyze
     ///
     /// All subdirectories are automatically searched too.
-    #[arg(value_parser, num_args = 1.., required = true, value_hint = ValueHint::DirPath)]
+    #[arg(value_parser, num_args = 1.., required_unless_present = "live", value_hint = ValueHint::DirPath)]
     trace_paths: Vec<PathBuf>,
 
     /// Only the directories specified by `TRACE_PATHS` are searched for traces, not their subdirectories.
@@ -144,22 +296,124 @@ pub struct AnalysisArgs {
     exact_trace_path: bool,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Progress line when stderr is a terminal and the analyzed traces are not a live session
+    #[default]
+    Auto,
+    /// Progress line rewritten in place
+    Text,
+    /// JSON object per line, for programs wrapping the analyzer
+    Json,
+    /// No progress
+    Never,
+}
+
+impl ProgressMode {
+    /// Format of the progress of analyzing traces or a live session (if `live` is set).
+    ///
+    /// Returns `None` if the progress should not be printed.
+    pub(crate) fn format(self, live: bool) -> Option<ProgressFormat> {
+        match self {
+            Self::Auto => {
+                (!live && std::io::stderr().is_terminal()).then_some(ProgressFormat::Text)
+            }
+            Self::Text => Some(ProgressFormat::Text),
+            Self::Json => Some(ProgressFormat::Json),
+            Self::Never => None,
+        }
+    }
+}
+
+fn parse_stats_interval(interval: &str) -> Result<NsDuration, String> {
+    let interval = interval.parse::<NsDuration>().map_err(|e| e.to_string())?;
+    if interval.as_nanos() == 0 {
+        return Err("The interval must be greater than zero".to_owned());
+    }
+    Ok(interval)
+}
+
 impl AnalysisArgs {
     pub fn trace_paths(&self) -> &[PathBuf] {
         &self.trace_paths
     }
 
-    pub fn trace_paths_cstring(&self) -> Vec<CString> {
-        self.trace_paths
-            .iter()
-            .map(|p| CString::new(p.to_str().unwrap()).unwrap())
-            .collect::<Vec<_>>()
-    }
-
     pub const fn is_exact_path(&self) -> bool {
         self.exact_trace_path
     }
 
+    pub const fn match_horizon(&self) -> Option<NsDuration> {
+        self.match_horizon
+    }
+
+    pub const fn max_memory(&self) -> Option<ByteSize> {
+        self.max_memory
+    }
+
+    pub fn live_url(&self) -> Option<&str> {
+        self.live.as_deref()
+    }
+
+    pub const fn stats_interval(&self) -> NsDuration {
+        self.stats_interval
+    }
+
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    pub const fn progress(&self) -> ProgressMode {
+        self.progress
+    }
+
+    /// Components added to the trace processing graph by the trimming, debug info
+    /// and trace copy options.
+    ///
+    /// The time range of the `trace_paths` is queried if `--begin` or `--end` is relative
+    /// to the beginning of the traces.
+    pub fn graph_config(&self, trace_paths: &[&CStr]) -> color_eyre::Result<GraphConfig> {
+        fn path_cstring(path: &Path) -> color_eyre::Result<CString> {
+            CString::new(path.as_os_str().as_encoded_bytes())
+                .wrap_err_with(|| format!("Invalid path: {}", path.display()))
+        }
+
+        let mut config = GraphConfig::new();
+
+        if self.begin.is_some() || self.end.is_some() {
+            ensure!(
+                self.live.is_none()
+                    || !(self.begin.is_some_and(TraceTime::is_relative)
+                        || self.end.is_some_and(TraceTime::is_relative)),
+                "Times relative to the beginning of the traces are not supported for live sessions"
+            );
+            let (begin, end) = resolve_time_range(self.begin, self.end, trace_paths)?;
+            config = config.filter(PluginComponent::trimmer(begin, end)?);
+        }
+
+        if self.debug_info {
+            let dir = self
+                .debug_info_dir
+                .as_deref()
+                .map(path_cstring)
+                .transpose()?;
+            let prefix = self
+                .debug_info_target_prefix
+                .as_deref()
+                .map(path_cstring)
+                .transpose()?;
+            config = config.filter(PluginComponent::debug_info(
+                dir.as_deref(),
+                prefix.as_deref(),
+            )?);
+        }
+
+        if let Some(path) = &self.trace_copy {
+            config = config.sink(PluginComponent::ctf_fs_sink(&path_cstring(path)?)?);
+        }
+
+        Ok(config)
+    }
+
     fn concatenate_with_out_path<'a>(&'a self, path: &'a Path) -> Cow<'a, Path> {
         if path.is_absolute() {
             path.into()
@@ -206,6 +460,26 @@ impl AnalysisArgs {
         self.spin_duration.is_some()
     }
 
+    pub fn executor_starvation_enabled(&self) -> bool {
+        self.executor_starvation.is_some()
+    }
+
+    pub fn cpu_affinity_enabled(&self) -> bool {
+        self.cpu_affinity.is_some()
+    }
+
+    pub fn topic_rate_enabled(&self) -> bool {
+        self.topic_rate.is_some()
+    }
+
+    pub fn chrome_trace_enabled(&self) -> bool {
+        self.chrome_trace.is_some()
+    }
+
+    pub fn otlp_enabled(&self) -> bool {
+        self.otlp.is_some()
+    }
+
     pub fn dependency_graph_path(&self) -> Option<Cow<'_, Path>> {
         self.dependency_graph
             .as_ref()
@@ -260,6 +534,36 @@ impl AnalysisArgs {
             .map(|p| self.concatenate_with_out_path(p))
     }
 
+    pub fn executor_starvation_path(&self) -> Option<Cow<'_, Path>> {
+        self.executor_starvation
+            .as_ref()
+            .map(|p| self.concatenate_with_out_path(p))
+    }
+
+    pub fn cpu_affinity_path(&self) -> Option<Cow<'_, Path>> {
+        self.cpu_affinity
+            .as_ref()
+            .map(|p| self.concatenate_with_out_path(p))
+    }
+
+    pub fn topic_rate_path(&self) -> Option<Cow<'_, Path>> {
+        self.topic_rate
+            .as_ref()
+            .map(|p| self.concatenate_with_out_path(p))
+    }
+
+    pub fn chrome_trace_path(&self) -> Option<Cow<'_, Path>> {
+        self.chrome_trace
+            .as_ref()
+            .map(|p| self.concatenate_with_out_path(p))
+    }
+
+    pub fn otlp_path(&self) -> Option<Cow<'_, Path>> {
+        self.otlp
+            .as_ref()
+            .map(|p| self.concatenate_with_out_path(p))
+    }
+
     pub fn binary_bundle_path(&self) -> Option<Cow<'_, Path>> {
         self.binary_bundle
             .as_ref()
@@ -289,6 +593,18 @@ impl AnalysisArgs {
     pub const fn min_multiplier(&self) -> f64 {
         self.min_multiplier
     }
+
+    pub const fn starvation_threshold(&self) -> NsDuration {
+        self.starvation_threshold
+    }
+
+    pub fn affinity_spec(&self) -> Option<&Path> {
+        self.affinity_spec.as_deref()
+    }
+
+    pub const fn rate_window(&self) -> NsDuration {
+        self.rate_window
+    }
 }
 
 #[cfg(test)]
@@ -298,8 +614,11 @@ mod test {
 
     use clap::Parser;
 
+    use super::ProgressMode;
+    use crate::analyses::progress::ProgressFormat;
     use crate::argsv2::Args;
     use crate::argsv2::analysis_args::filenames;
+    use crate::utils::{ByteSize, NsDuration, TraceTime};
 
     #[test]
     fn test_basic_args_parsing() {
@@ -335,6 +654,239 @@ mod test {
         assert!(args.is_exact_path());
     }
 
+    #[test]
+    fn test_message_retention_args() {
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--match-horizon",
+            "2s",
+            "--max-memory",
+            "1.5GiB",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+
+        assert_eq!(
+            args.match_horizon(),
+            Some(NsDuration::from_nanos(2_000_000_000))
+        );
+        assert_eq!(
+            args.max_memory().map(ByteSize::as_bytes),
+            Some(3 * 512 * 1024 * 1024)
+        );
+
+        let args = Args::try_parse_from(["program", "analyze", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert_eq!(args.match_horizon(), None);
+        assert_eq!(args.max_memory(), None);
+
+        assert!(
+            Args::try_parse_from(["program", "analyze", "--max-memory", "1TB", "/tmp/trace"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_live_url() {
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--live",
+            "net://localhost/host/robot/session",
+            "--stats-interval",
+            "10s",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+
+        assert_eq!(args.live_url(), Some("net://localhost/host/robot/session"));
+        assert_eq!(
+            args.stats_interval(),
+            NsDuration::from_nanos(10_000_000_000)
+        );
+        assert!(args.trace_paths().is_empty());
+
+        assert!(
+            Args::try_parse_from([
+                "program",
+                "analyze",
+                "--live",
+                "net://localhost",
+                "/tmp/trace"
+            ])
+            .is_err()
+        );
+        assert!(
+            Args::try_parse_from([
+                "program",
+                "analyze",
+                "--live",
+                "net://localhost",
+                "--stats-interval",
+                "0s"
+            ])
+            .is_err()
+        );
+        assert!(Args::try_parse_from(["program", "analyze"]).is_err());
+    }
+
+    #[test]
+    fn test_cache_dir() {
+        let args = Args::try_parse_from(["program", "analyze", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert_eq!(args.cache_dir(), None);
+
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--cache-dir",
+            "/tmp/cache",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+        assert_eq!(args.cache_dir(), Some(Path::new("/tmp/cache")));
+
+        assert!(
+            Args::try_parse_from([
+                "program",
+                "analyze",
+                "--live",
+                "net://localhost",
+                "--cache-dir",
+                "/tmp/cache"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_relative_times() {
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--begin",
+            "+1.5s",
+            "--end",
+            "1714564860000000000",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+        let begin = args.begin.unwrap();
+        assert!(begin.is_relative());
+        assert_eq!(
+            begin,
+            TraceTime::FromBeginning(NsDuration::from_nanos(1_500_000_000))
+        );
+        assert_eq!(begin.resolve(Some(1_000)), Some(1_500_001_000));
+        assert_eq!(begin.resolve(None), None);
+        assert_eq!(begin.resolve(Some(i64::MAX)), None);
+
+        let end = args.end.unwrap();
+        assert!(!end.is_relative());
+        assert_eq!(end.resolve(None), Some(1_714_564_860_000_000_000));
+
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--live",
+            "net://localhost",
+            "--end",
+            "+10s",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+        assert!(args.graph_config(&[]).is_err());
+    }
+
+    #[test]
+    fn test_graph_args() {
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--begin",
+            "2024-05-01T12:00:00.5Z",
+            "--end",
+            "1714564860000000000",
+            "--debug-info",
+            "--debug-info-dir",
+            "/usr/lib/debug",
+            "--trace-copy",
+            "/tmp/copy",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+        assert_eq!(
+            args.begin,
+            Some(TraceTime::Absolute(1_714_564_800_500_000_000))
+        );
+        assert_eq!(
+            args.end,
+            Some(TraceTime::Absolute(1_714_564_860_000_000_000))
+        );
+        assert!(args.debug_info);
+        assert_eq!(args.debug_info_dir, Some(PathBuf::from("/usr/lib/debug")));
+        assert_eq!(args.trace_copy, Some(PathBuf::from("/tmp/copy")));
+
+        assert!(
+            Args::try_parse_from(["program", "analyze", "--begin", "noon", "/tmp/trace"]).is_err()
+        );
+        assert!(
+            Args::try_parse_from(["program", "analyze", "--begin", "+noon", "/tmp/trace"]).is_err()
+        );
+        // The cached events would not pass through the graph components
+        assert!(
+            Args::try_parse_from([
+                "program",
+                "analyze",
+                "--cache-dir",
+                "/tmp/cache",
+                "--end",
+                "0",
+                "/tmp/trace"
+            ])
+            .is_err()
+        );
+        assert!(
+            Args::try_parse_from([
+                "program",
+                "analyze",
+                "--debug-info-dir",
+                "/usr/lib/debug",
+                "/tmp/trace"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_progress_mode() {
+        let args = Args::try_parse_from(["program", "analyze", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert_eq!(args.progress(), ProgressMode::Auto);
+        // Never printed for live sessions unless requested
+        assert_eq!(ProgressMode::Auto.format(true), None);
+
+        let args = Args::try_parse_from(["program", "analyze", "--progress", "json", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert_eq!(args.progress(), ProgressMode::Json);
+        assert_eq!(args.progress().format(true), Some(ProgressFormat::Json));
+        assert_eq!(ProgressMode::Never.format(false), None);
+
+        assert!(
+            Args::try_parse_from(["program", "analyze", "--progress", "bar", "/tmp/trace"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_output_directory() {
         // This test will be skipped if /tmp doesn't exist
@@ -516,4 +1068,81 @@ mod test {
             "Absolute path should not be concatenated with out_dir"
         );
     }
+
+    #[test]
+    fn test_starvation_threshold_parsing() {
+        let args = Args::try_parse_from(["program", "analyze", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert_eq!(
+            args.starvation_threshold(),
+            NsDuration::from_nanos(1_000_000)
+        );
+
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--starvation-threshold",
+            "250us",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+        assert_eq!(args.starvation_threshold(), NsDuration::from_nanos(250_000));
+
+        let result = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--starvation-threshold",
+            "250",
+            "/tmp/trace",
+        ]);
+        assert!(result.is_err(), "Threshold without unit should be rejected");
+    }
+
+    #[test]
+    fn test_affinity_spec_enables_cpu_affinity() {
+        let args = Args::try_parse_from([
+            "program",
+            "analyze",
+            "--message-latency",
+            "--affinity-spec",
+            "affinity.toml",
+            "/tmp/trace",
+        ])
+        .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+        .into_analysis_args();
+
+        assert!(!args.all);
+        assert!(args.cpu_affinity_enabled());
+        assert_eq!(
+            args.cpu_affinity,
+            Some(PathBuf::from(filenames::CPU_AFFINITY))
+        );
+        assert_eq!(args.affinity_spec(), Some(Path::new("affinity.toml")));
+
+        let args = Args::try_parse_from(["program", "analyze", "--message-latency", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert!(!args.cpu_affinity_enabled());
+    }
+
+    #[test]
+    fn test_chrome_trace_not_in_all() {
+        let args = Args::try_parse_from(["program", "analyze", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert!(args.all);
+        assert!(!args.chrome_trace_enabled());
+        assert!(!args.otlp_enabled());
+
+        let args = Args::try_parse_from(["program", "analyze", "--perfetto", "/tmp/trace"])
+            .unwrap_or_else(|e| panic!("Failed to parse arguments: {e}"))
+            .into_analysis_args();
+        assert!(!args.all);
+        assert_eq!(
+            args.chrome_trace,
+            Some(PathBuf::from(filenames::CHROME_TRACE))
+        );
+    }
 }
@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+
+#[derive(Debug, Clone, Args)]
+pub struct CheckArgs {
+    /// Budget file with the rules to check (TOML or YAML)
+    ///
+    /// Each `[[rule]]` contains `metric` (`message_latency`, `callback_duration`
+    /// or `timer_jitter`), optional selectors (`topic`, `node`, `service`, `period`),
+    /// `statistic` (`min`, `max`, `mean`, `median` or a percentile like `p99`)
+    /// and the `max` allowed duration (e.g. `"5ms"`).
+    ///
+    /// For `message_latency`, `node` selects the subscriber node. The ends of the channel
+    /// are selected by `publisher_node` and `subscriber_node`.
+    #[arg(long, short = 'b', value_name = "FILE", value_hint = ValueHint::FilePath)]
+    budget: PathBuf,
+
+    /// Write the verdict as JUnit XML to this file
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    junit: Option<PathBuf>,
+
+    /// Write the verdict as JSON to this file
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    json: Option<PathBuf>,
+
+    /// Paths to directories to search for the trace to check
+    ///
+    /// All subdirectories are automatically searched too.
+    #[arg(value_parser, num_args = 1.., required = true, value_hint = ValueHint::DirPath)]
+    trace_paths: Vec<PathBuf>,
+
+    /// Only the directories specified by `TRACE_PATHS` are searched for traces, not their subdirectories.
+    #[arg(long)]
+    exact_trace_path: bool,
+}
+
+impl CheckArgs {
+    pub fn budget_path(&self) -> &Path {
+        &self.budget
+    }
+
+    pub fn junit_path(&self) -> Option<&Path> {
+        self.junit.as_deref()
+    }
+
+    pub fn json_path(&self) -> Option<&Path> {
+        self.json.as_deref()
+    }
+
+    pub fn trace_paths(&self) -> &[PathBuf] {
+        &self.trace_paths
+    }
+
+    pub const fn is_exact_path(&self) -> bool {
+        self.exact_trace_path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::argsv2::{Args, TracerCommand};
+
+    fn parse(args: &[&str]) -> CheckArgs {
+        let args = Args::try_parse_from(args).unwrap();
+        match args.command {
+            TracerCommand::Check(check_args) => check_args,
+            _ => panic!("Expected check subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_check_args() {
+        let args = parse(&[
+            "program",
+            "check",
+            "--budget",
+            "budget.toml",
+            "--junit",
+            "report.xml",
+            "/tmp/trace",
+        ]);
+
+        assert_eq!(args.budget_path(), Path::new("budget.toml"));
+        assert_eq!(args.junit_path(), Some(Path::new("report.xml")));
+        assert_eq!(args.json_path(), None);
+        assert_eq!(args.trace_paths(), [PathBuf::from("/tmp/trace")]);
+        assert!(!args.is_exact_path());
+    }
+
+    #[test]
+    fn test_check_requires_budget() {
+        assert!(Args::try_parse_from(["program", "check", "/tmp/trace"]).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+
+use crate::argsv2::analysis_args::filenames;
+use crate::statistics::Quantile;
+
+/// Source of the results to compare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareInput<'a> {
+    /// Binary bundle produced by the analysis
+    Bundle(PathBuf),
+    /// Directory to search for traces
+    Trace(&'a Path),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CompareArgs {
+    /// Baseline binary bundle, directory containing r2ta_results.sqlite file or trace directory
+    #[arg(value_name = "BASELINE", value_hint = ValueHint::AnyPath)]
+    baseline: PathBuf,
+
+    /// Binary bundle, directory containing r2ta_results.sqlite file or trace directory
+    /// to compare with the baseline
+    #[arg(value_name = "CANDIDATE", value_hint = ValueHint::AnyPath)]
+    candidate: PathBuf,
+
+    /// Write the comparison as JSON to this file
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    json: Option<PathBuf>,
+
+    /// Write a DOT graph of nodes and topics colored by the regression magnitude to this file
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    dot: Option<PathBuf>,
+
+    /// Quantiles to compare.
+    ///
+    /// The quantiles must be in the range [0, 1].
+    #[arg(
+        long,
+        value_parser,
+        value_delimiter = ',',
+        default_value = "0.5,0.90,0.99"
+    )]
+    quantiles: Vec<Quantile>,
+
+    /// Significance level of the Mann-Whitney U test
+    ///
+    /// Shifts with a higher p-value are reported as unchanged.
+    #[arg(long, default_value = "0.01", value_name = "ALPHA")]
+    significance: f64,
+
+    /// Minimal relative change of the median to report a regression or an improvement
+    #[arg(long, default_value = "0.05", value_name = "RATIO")]
+    min_shift: f64,
+
+    /// Only the directories specified by `BASELINE` and `CANDIDATE` are searched for traces, not their subdirectories.
+    #[arg(long)]
+    exact_trace_path: bool,
+}
+
+impl CompareArgs {
+    fn input(path: &Path) -> CompareInput<'_> {
+        if path.is_file() {
+            CompareInput::Bundle(path.to_owned())
+        } else if path.join(filenames::BINARY_BUNDLE).is_file() {
+            CompareInput::Bundle(path.join(filenames::BINARY_BUNDLE))
+        } else {
+            CompareInput::Trace(path)
+        }
+    }
+
+    pub fn baseline(&self) -> CompareInput<'_> {
+        Self::input(&self.baseline)
+    }
+
+    pub fn candidate(&self) -> CompareInput<'_> {
+        Self::input(&self.candidate)
+    }
+
+    pub fn json_path(&self) -> Option<&Path> {
+        self.json.as_deref()
+    }
+
+    pub fn dot_path(&self) -> Option<&Path> {
+        self.dot.as_deref()
+    }
+
+    pub fn quantiles(&self) -> &[Quantile] {
+        &self.quantiles
+    }
+
+    pub const fn significance(&self) -> f64 {
+        self.significance
+    }
+
+    pub const fn min_shift(&self) -> f64 {
+        self.min_shift
+    }
+
+    pub const fn is_exact_path(&self) -> bool {
+        self.exact_trace_path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::argsv2::{Args, TracerCommand};
+
+    fn parse(args: &[&str]) -> CompareArgs {
+        let args = Args::try_parse_from(args).unwrap();
+        match args.command {
+            TracerCommand::Compare(compare_args) => compare_args,
+            _ => panic!("Expected compare subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_compare_args() {
+        let args = parse(&["program", "compare", "/nonexistent/a", "/nonexistent/b"]);
+
+        assert_eq!(
+            args.baseline(),
+            CompareInput::Trace(Path::new("/nonexistent/a"))
+        );
+        assert_eq!(
+            args.candidate(),
+            CompareInput::Trace(Path::new("/nonexistent/b"))
+        );
+        assert_eq!(args.quantiles().len(), 3);
+        assert!((args.significance() - 0.01).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_compare_requires_two_inputs() {
+        assert!(Args::try_parse_from(["program", "compare", "/tmp/a"]).is_err());
+    }
+}
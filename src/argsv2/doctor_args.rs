@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+
+#[derive(Debug, Clone, Args)]
+pub struct DoctorArgs {
+    /// Directories to load the babeltrace2 plugins from instead of the default locations
+    ///
+    /// The subdirectories are not searched.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+    plugin_dir: Vec<PathBuf>,
+
+    /// Print the help text of each component class
+    #[arg(long)]
+    component_help: bool,
+}
+
+impl DoctorArgs {
+    pub fn plugin_dirs(&self) -> &[PathBuf] {
+        &self.plugin_dir
+    }
+
+    pub const fn component_help(&self) -> bool {
+        self.component_help
+    }
+}
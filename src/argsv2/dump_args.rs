@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueEnum, ValueHint};
+
+use crate::utils::TraceTime;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    /// One JSON object per line with the payload as a nested object
+    #[default]
+    Jsonl,
+    /// Comma separated values with the payload as a JSON string
+    Csv,
+    /// Parquet file with the payload as a JSON string
+    Parquet,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DumpArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    format: DumpFormat,
+
+    /// Write the events to this file instead of the standard output
+    #[arg(long, short = 'o', value_name = "FILE", value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+
+    /// Process the events and add the names of the node, topic and callback they refer to
+    ///
+    /// The names are resolved from the initialization events of the traces. Names that are
+    /// not known yet when an event is processed are left empty.
+    #[arg(long)]
+    processed: bool,
+
+    /// Only dump the events with these names
+    ///
+    /// A name is either the full name of the event (e.g. `ros2:rcl_publish`)
+    /// or the name without the provider (e.g. `rcl_publish`).
+    #[arg(long, value_name = "NAME", value_delimiter = ',')]
+    events: Vec<String>,
+
+    /// Only dump the events at or after this time.
+    ///
+    /// The time is an RFC 3339 timestamp (e.g. `2024-05-01T12:00:00Z`), a number
+    /// of nanoseconds since the UNIX epoch or a duration from the beginning of the traces
+    /// prefixed by `+` (e.g. `+10s`).
+    #[arg(long, value_name = "TIME")]
+    begin: Option<TraceTime>,
+
+    /// Only dump the events at or before this time.
+    ///
+    /// The time has the same format as `--begin`.
+    #[arg(long, value_name = "TIME")]
+    end: Option<TraceTime>,
+
+    /// Paths to directories to search for the traces to dump
+    ///
+    /// All subdirectories are automatically searched too.
+    #[arg(value_parser, num_args = 1.., required = true, value_hint = ValueHint::DirPath)]
+    trace_paths: Vec<PathBuf>,
+
+    /// Only the directories specified by `TRACE_PATHS` are searched for traces, not their subdirectories.
+    #[arg(long)]
+    exact_trace_path: bool,
+}
+
+impl DumpArgs {
+    pub const fn format(&self) -> DumpFormat {
+        self.format
+    }
+
+    pub fn output_path(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    pub const fn is_processed(&self) -> bool {
+        self.processed
+    }
+
+    pub fn event_names(&self) -> &[String] {
+        &self.events
+    }
+
+    pub const fn begin(&self) -> Option<TraceTime> {
+        self.begin
+    }
+
+    pub const fn end(&self) -> Option<TraceTime> {
+        self.end
+    }
+
+    pub fn trace_paths(&self) -> &[PathBuf] {
+        &self.trace_paths
+    }
+
+    pub const fn is_exact_path(&self) -> bool {
+        self.exact_trace_path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::argsv2::{Args, TracerCommand};
+
+    fn parse(args: &[&str]) -> DumpArgs {
+        let args = Args::try_parse_from(args).unwrap();
+        match args.command {
+            TracerCommand::Dump(dump_args) => dump_args,
+            _ => panic!("Expected dump subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_dump_args_defaults() {
+        let args = parse(&["program", "dump", "/tmp/trace"]);
+
+        assert_eq!(args.format(), DumpFormat::Jsonl);
+        assert_eq!(args.output_path(), None);
+        assert!(!args.is_processed());
+        assert!(args.event_names().is_empty());
+        assert_eq!(args.begin(), None);
+        assert_eq!(args.end(), None);
+        assert_eq!(args.trace_paths(), [PathBuf::from("/tmp/trace")]);
+        assert!(!args.is_exact_path());
+    }
+
+    #[test]
+    fn test_dump_args() {
+        let args = parse(&[
+            "program",
+            "dump",
+            "--format",
+            "csv",
+            "-o",
+            "events.csv",
+            "--processed",
+            "--events",
+            "ros2:rcl_publish,callback_start",
+            "--begin",
+            "+1s",
+            "--end",
+            "2000000000",
+            "/tmp/trace",
+        ]);
+
+        assert_eq!(args.format(), DumpFormat::Csv);
+        assert_eq!(args.output_path(), Some(Path::new("events.csv")));
+        assert!(args.is_processed());
+        assert_eq!(args.event_names(), ["ros2:rcl_publish", "callback_start"]);
+        assert!(args.begin().is_some_and(TraceTime::is_relative));
+        assert_eq!(args.end(), Some(TraceTime::Absolute(2_000_000_000)));
+    }
+
+    #[test]
+    fn test_dump_args_parquet() {
+        let args = parse(&["program", "dump", "--format", "parquet", "/tmp/trace"]);
+
+        assert_eq!(args.format(), DumpFormat::Parquet);
+    }
+
+    #[test]
+    fn test_dump_requires_trace_paths() {
+        assert!(Args::try_parse_from(["program", "dump"]).is_err());
+    }
+}
@@ -50,6 +50,12 @@ pub enum ExtractContentArgs {
     Graph,
     /// Extract property data for a node
     Property(ExtractPropertyArgs),
+    /// Extract topic rate statistics of publishers and subscribers
+    TopicRate(ExtractTopicRateArgs),
+    /// Extract callbacks blocked by other callbacks on the same executor thread
+    ExecutorStarvation(ExtractExecutorStarvationArgs),
+    /// Extract the CPUs of callbacks and threads and the affinity violations
+    CpuAffinity,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -72,6 +78,34 @@ impl ExtractPropertyArgs {
     }
 }
 
+#[derive(Debug, Clone, Args)]
+pub struct ExtractTopicRateArgs {
+    /// Identifies the publisher or subscriber for which to extract the statistics
+    ///
+    /// If not present, the statistics of all publishers and subscribers are extracted.
+    element_id: Option<i64>,
+}
+
+impl ExtractTopicRateArgs {
+    pub fn element_id(&self) -> Option<i64> {
+        self.element_id
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ExtractExecutorStarvationArgs {
+    /// Identifies the blocked callback for which to extract the starvation
+    ///
+    /// If not present, the starvation of all blocked callbacks is extracted.
+    element_id: Option<i64>,
+}
+
+impl ExtractExecutorStarvationArgs {
+    pub fn element_id(&self) -> Option<i64> {
+        self.element_id
+    }
+}
+
 #[derive(
     Debug,
     Display,
@@ -80,6 +114,8 @@ impl ExtractPropertyArgs {
     Copy,
     PartialEq,
     Eq,
+    PartialOrd,
+    Ord,
     Hash,
     serde::Serialize,
     serde::Deserialize,
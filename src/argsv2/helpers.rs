@@ -1,12 +1,13 @@
 use std::ffi::{CStr, CString};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use bt2_sys::graph::component::BtComponentType;
 use bt2_sys::query::support_info;
-use color_eyre::eyre::ensure;
+use color_eyre::eyre::{OptionExt, ensure};
 use walkdir::WalkDir;
 
-use crate::argsv2::Args;
+use crate::analyses::progress::traces_time_range;
+use crate::utils::TraceTime;
 
 // Valid trace path should have a weight set to 0.75 so we set the threshold slightly lower.
 const TRACE_PATH_LIKELIHOOD_THRESHOLD: f64 = 0.74;
@@ -51,22 +52,35 @@ pub fn find_trace_paths(search_path: &Path) -> Vec<CString> {
     trace_paths
 }
 
-pub fn prepare_trace_paths() -> color_eyre::Result<Vec<CString>> {
-    let trace_paths: Vec<_> = if Args::get_analyses_args().is_exact_path() {
-        Args::get_analyses_args()
-            .trace_paths_cstring()
-            .into_iter()
-            .filter_map(|path| {
-                if is_trace_path(&path) {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
+/// Find the traces in `trace_paths` and print them.
+///
+/// If `exact` is set, only the given directories are checked, not their subdirectories.
+pub fn prepare_trace_paths(
+    trace_paths: &[PathBuf],
+    exact: bool,
+) -> color_eyre::Result<Vec<CString>> {
+    let trace_paths = find_traces(trace_paths, exact)?;
+
+    println!("Found traces:");
+    for path in &trace_paths {
+        println!("  {}", path.to_string_lossy());
+    }
+
+    Ok(trace_paths)
+}
+
+/// Find the traces in `trace_paths` without printing them.
+///
+/// Fails if no trace is found.
+pub fn find_traces(trace_paths: &[PathBuf], exact: bool) -> color_eyre::Result<Vec<CString>> {
+    let trace_paths: Vec<_> = if exact {
+        trace_paths
+            .iter()
+            .map(|p| CString::new(p.to_str().unwrap()).unwrap())
+            .filter(|path| is_trace_path(path))
             .collect()
     } else {
-        Args::get_analyses_args()
-            .trace_paths()
+        trace_paths
             .iter()
             .map(AsRef::as_ref)
             .flat_map(find_trace_paths)
@@ -78,10 +92,42 @@ pub fn prepare_trace_paths() -> color_eyre::Result<Vec<CString>> {
         "No traces found in the provided paths."
     );
 
-    println!("Found traces:");
-    for path in &trace_paths {
-        println!("  {}", path.to_string_lossy());
+    Ok(trace_paths)
+}
+
+/// Resolve `begin` and `end` to nanoseconds since the UNIX epoch.
+///
+/// The time range of the `trace_paths` is queried only if a time is relative
+/// to the beginning of the traces.
+pub fn resolve_time_range(
+    begin: Option<TraceTime>,
+    end: Option<TraceTime>,
+    trace_paths: &[&CStr],
+) -> color_eyre::Result<(Option<i64>, Option<i64>)> {
+    let traces_begin = if begin.is_some_and(TraceTime::is_relative)
+        || end.is_some_and(TraceTime::is_relative)
+    {
+        let range = traces_time_range(trace_paths)
+            .ok_or_eyre("Cannot resolve relative times, the time range of the traces is unknown")?;
+        Some(range.begin)
+    } else {
+        None
+    };
+    let resolve = |time: Option<TraceTime>| {
+        time.map(|time| {
+            time.resolve(traces_begin)
+                .ok_or_eyre("The time is out of range")
+        })
+        .transpose()
+    };
+    let (begin, end) = (resolve(begin)?, resolve(end)?);
+
+    if let (Some(begin), Some(end)) = (begin, end) {
+        ensure!(
+            begin <= end,
+            "The begin time must not be after the end time"
+        );
     }
 
-    Ok(trace_paths)
+    Ok((begin, end))
 }
@@ -4,6 +4,10 @@ use clap::{Parser, Subcommand};
 use clap_verbosity_flag::{Verbosity, WarnLevel};
 
 pub mod analysis_args;
+pub mod check_args;
+pub mod compare_args;
+pub mod doctor_args;
+pub mod dump_args;
 pub mod extract_args;
 pub mod helpers;
 pub mod plot_args;
@@ -71,6 +75,32 @@ pub enum TracerCommand {
     /// Retrieve data from binary bundle produced by the analysis
     #[display("extract")]
     Extract(#[clap(subcommand)] extract_args::ExtractArgs),
+
+    /// Check a ROS 2 trace against a latency budget
+    ///
+    /// Exits with code 2 if any rule of the budget is violated.
+    #[display("check")]
+    Check(check_args::CheckArgs),
+
+    /// Compare results of two traces or binary bundles
+    ///
+    /// Elements are matched by their ROS names. Reports added and removed nodes,
+    /// topics and edges and quantile shifts of the matched elements.
+    #[display("compare")]
+    Compare(compare_args::CompareArgs),
+
+    /// Write the events of ROS 2 traces with their context and payload
+    ///
+    /// With `--processed`, the events are enriched with the names of the node, topic
+    /// and callback they refer to.
+    #[display("dump")]
+    Dump(dump_args::DumpArgs),
+
+    /// Print the babeltrace2 library and plugins used to read the traces
+    ///
+    /// Exits with an error if a component class needed to read traces is missing.
+    #[display("doctor")]
+    Doctor(doctor_args::DoctorArgs),
 }
 
 #[cfg(test)]
@@ -0,0 +1,412 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::statistics::{Quantile, QuantileConversionError};
+use crate::utils::{DurationDisplayImprecise, NsDuration, NsDurationParseError};
+
+#[derive(Debug, Error)]
+pub enum BudgetError {
+    #[error("Failed to read budget file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse TOML budget file: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Failed to parse YAML budget file: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Unknown budget file format {0:?}, expected .toml, .yaml or .yml")]
+    UnknownFormat(PathBuf),
+    #[error("Budget file does not contain any rule")]
+    NoRules,
+    #[error("Rule {rule}: invalid duration {value:?}: {source}")]
+    InvalidDuration {
+        rule: usize,
+        value: String,
+        #[source]
+        source: NsDurationParseError,
+    },
+    #[error("Rule {rule}: {source}")]
+    InvalidStatistic {
+        rule: usize,
+        #[source]
+        source: StatisticParseError,
+    },
+    #[error("Rule {rule}: selector `{selector}` cannot be used with metric {metric}")]
+    UnsupportedSelector {
+        rule: usize,
+        selector: &'static str,
+        metric: Metric,
+    },
+    #[error("Rule {rule}: selectors `topic`, `service` and `period` are mutually exclusive")]
+    ConflictingSelectors { rule: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum StatisticParseError {
+    #[error("unknown statistic {0:?}, expected min, max, mean, median or a percentile like p99")]
+    Unknown(String),
+    #[error("invalid percentile {0:?}: {1}")]
+    InvalidPercentile(String, #[source] QuantileConversionError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, derive_more::Display)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    #[display("message_latency")]
+    MessageLatency,
+    #[display("callback_duration")]
+    CallbackDuration,
+    #[display("timer_jitter")]
+    TimerJitter,
+}
+
+/// Statistic of a series of durations that is compared with the budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Statistic {
+    Min,
+    Max,
+    Mean,
+    Median,
+    Percentile(Quantile),
+}
+
+impl FromStr for Statistic {
+    type Err = StatisticParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "mean" | "avg" => Ok(Self::Mean),
+            "median" => Ok(Self::Median),
+            other => {
+                let percentile = other
+                    .strip_prefix('p')
+                    .and_then(|p| p.parse::<f64>().ok())
+                    .ok_or_else(|| StatisticParseError::Unknown(s.to_owned()))?;
+                Quantile::new(percentile / 100.0)
+                    .map(Self::Percentile)
+                    .map_err(|e| StatisticParseError::InvalidPercentile(s.to_owned(), e))
+            }
+        }
+    }
+}
+
+impl Display for Statistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::Mean => write!(f, "mean"),
+            Self::Median => write!(f, "median"),
+            // Round away the floating point error of the conversion to a quantile
+            Self::Percentile(q) => write!(f, "p{}", (q.value() * 1e8).round() / 1e6),
+        }
+    }
+}
+
+/// Callbacks, topics or timers the rule applies to.
+///
+/// Unset selectors match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selector {
+    /// Node of the callback or timer, or the subscriber node of a message latency
+    pub node: Option<String>,
+    pub publisher_node: Option<String>,
+    pub subscriber_node: Option<String>,
+    pub topic: Option<String>,
+    pub service: Option<String>,
+    pub period: Option<i64>,
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(node) = &self.node {
+            parts.push(format!("node={node}"));
+        }
+        if let Some(node) = &self.publisher_node {
+            parts.push(format!("publisher_node={node}"));
+        }
+        if let Some(node) = &self.subscriber_node {
+            parts.push(format!("subscriber_node={node}"));
+        }
+        if let Some(topic) = &self.topic {
+            parts.push(format!("topic={topic}"));
+        }
+        if let Some(service) = &self.service {
+            parts.push(format!("service={service}"));
+        }
+        if let Some(period) = self.period {
+            parts.push(format!("period={}", DurationDisplayImprecise(period)));
+        }
+
+        if parts.is_empty() {
+            write!(f, "*")
+        } else {
+            write!(f, "{}", parts.join(","))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub metric: Metric,
+    pub selector: Selector,
+    pub statistic: Statistic,
+    /// Maximum allowed value of the statistic in nanoseconds.
+    pub max: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    name: Option<String>,
+    metric: Metric,
+    node: Option<String>,
+    publisher_node: Option<String>,
+    subscriber_node: Option<String>,
+    topic: Option<String>,
+    service: Option<String>,
+    period: Option<String>,
+    statistic: String,
+    max: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawBudget {
+    #[serde(default, rename = "rule", alias = "rules")]
+    rules: Vec<RawRule>,
+}
+
+fn parse_duration(rule: usize, value: String) -> Result<i64, BudgetError> {
+    value
+        .parse::<NsDuration>()
+        .map(NsDuration::as_nanos)
+        .map_err(|source| BudgetError::InvalidDuration {
+            rule,
+            value,
+            source,
+        })
+}
+
+impl RawRule {
+    fn validate(self, rule: usize) -> Result<Rule, BudgetError> {
+        let statistic = self
+            .statistic
+            .parse()
+            .map_err(|source| BudgetError::InvalidStatistic { rule, source })?;
+        let max = parse_duration(rule, self.max)?;
+        let period = self
+            .period
+            .map(|period| parse_duration(rule, period))
+            .transpose()?;
+
+        let selector = Selector {
+            node: self.node,
+            publisher_node: self.publisher_node,
+            subscriber_node: self.subscriber_node,
+            topic: self.topic,
+            service: self.service,
+            period,
+        };
+
+        let unsupported = match self.metric {
+            Metric::MessageLatency => vec![
+                ("service", selector.service.is_some()),
+                ("period", selector.period.is_some()),
+            ],
+            Metric::CallbackDuration => vec![
+                ("publisher_node", selector.publisher_node.is_some()),
+                ("subscriber_node", selector.subscriber_node.is_some()),
+            ],
+            Metric::TimerJitter => vec![
+                ("topic", selector.topic.is_some()),
+                ("service", selector.service.is_some()),
+                ("publisher_node", selector.publisher_node.is_some()),
+                ("subscriber_node", selector.subscriber_node.is_some()),
+            ],
+        };
+        if let Some((selector, _)) = unsupported.into_iter().find(|(_, used)| *used) {
+            return Err(BudgetError::UnsupportedSelector {
+                rule,
+                selector,
+                metric: self.metric,
+            });
+        }
+
+        let callers = [
+            selector.topic.is_some(),
+            selector.service.is_some(),
+            selector.period.is_some(),
+        ];
+        if callers.into_iter().filter(|set| *set).count() > 1 {
+            return Err(BudgetError::ConflictingSelectors { rule });
+        }
+
+        let name = self.name.unwrap_or_else(|| {
+            format!(
+                "{statistic} {} [{selector}] <= {}",
+                self.metric,
+                DurationDisplayImprecise(max)
+            )
+        });
+
+        Ok(Rule {
+            name,
+            metric: self.metric,
+            selector,
+            statistic,
+            max,
+        })
+    }
+}
+
+/// Parse budget rules from a file.
+///
+/// The format is selected by the file extension.
+pub fn load_budget(path: &Path) -> Result<Vec<Rule>, BudgetError> {
+    let content = std::fs::read_to_string(path).map_err(|source| BudgetError::Read {
+        path: path.to_owned(),
+        source,
+    })?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => parse_toml(&content),
+        Some("yaml" | "yml") => parse_yaml(&content),
+        _ => Err(BudgetError::UnknownFormat(path.to_owned())),
+    }
+}
+
+fn parse_toml(content: &str) -> Result<Vec<Rule>, BudgetError> {
+    validate_rules(toml::from_str(content)?)
+}
+
+fn parse_yaml(content: &str) -> Result<Vec<Rule>, BudgetError> {
+    validate_rules(serde_yaml::from_str(content)?)
+}
+
+fn validate_rules(budget: RawBudget) -> Result<Vec<Rule>, BudgetError> {
+    if budget.rules.is_empty() {
+        return Err(BudgetError::NoRules);
+    }
+
+    budget
+        .rules
+        .into_iter()
+        .enumerate()
+        .map(|(i, rule)| rule.validate(i + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_statistic_parsing() {
+        assert_eq!("max".parse::<Statistic>().unwrap(), Statistic::Max);
+        assert_eq!("Median".parse::<Statistic>().unwrap(), Statistic::Median);
+        assert_eq!(
+            "p99".parse::<Statistic>().unwrap(),
+            Statistic::Percentile(Quantile::new(0.99).unwrap())
+        );
+        assert_eq!("p99.9".parse::<Statistic>().unwrap().to_string(), "p99.9");
+        assert!("p101".parse::<Statistic>().is_err());
+        assert!("q50".parse::<Statistic>().is_err());
+    }
+
+    #[test]
+    fn test_budget_formats() {
+        let toml = r#"
+            [[rule]]
+            metric = "message_latency"
+            topic = "/cmd_vel"
+            statistic = "p99"
+            max = "5ms"
+
+            [[rule]]
+            name = "ctrl jitter"
+            metric = "timer_jitter"
+            node = "/ctrl"
+            statistic = "max"
+            max = "200us"
+        "#;
+        let yaml = r#"
+rules:
+  - metric: message_latency
+    topic: /cmd_vel
+    statistic: p99
+    max: 5ms
+  - name: ctrl jitter
+    metric: timer_jitter
+    node: /ctrl
+    statistic: max
+    max: 200us
+"#;
+
+        let rules = parse_toml(toml).unwrap();
+        assert_eq!(rules, parse_yaml(yaml).unwrap());
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].metric, Metric::MessageLatency);
+        assert_eq!(rules[0].selector.topic.as_deref(), Some("/cmd_vel"));
+        assert_eq!(rules[0].max, 5_000_000);
+        assert_eq!(rules[1].name, "ctrl jitter");
+        assert_eq!(rules[1].max, 200_000);
+    }
+
+    #[test]
+    fn test_budget_validation() {
+        let unsupported = r#"
+            [[rule]]
+            metric = "timer_jitter"
+            topic = "/cmd_vel"
+            statistic = "max"
+            max = "1ms"
+        "#;
+        assert!(matches!(
+            parse_toml(unsupported),
+            Err(BudgetError::UnsupportedSelector { rule: 1, .. })
+        ));
+
+        let conflicting = r#"
+            [[rule]]
+            metric = "callback_duration"
+            topic = "/cmd_vel"
+            period = "10ms"
+            statistic = "p90"
+            max = "2ms"
+        "#;
+        assert!(matches!(
+            parse_toml(conflicting),
+            Err(BudgetError::ConflictingSelectors { rule: 1 })
+        ));
+
+        let channel_node = r#"
+            [[rule]]
+            metric = "callback_duration"
+            publisher_node = "/ctrl"
+            statistic = "max"
+            max = "1ms"
+        "#;
+        assert!(matches!(
+            parse_toml(channel_node),
+            Err(BudgetError::UnsupportedSelector {
+                rule: 1,
+                selector: "publisher_node",
+                ..
+            })
+        ));
+
+        assert!(matches!(parse_toml(""), Err(BudgetError::NoRules)));
+    }
+}
@@ -0,0 +1,375 @@
+use std::ffi::CString;
+
+use color_eyre::eyre::Context;
+use serde::Serialize;
+
+use crate::analyses::analysis::callback_duration::{CallbackExecutions, CallerParam};
+use crate::analyses::analysis::message_latency::MessageLatencyExport;
+use crate::analyses::analysis::{CallbackDuration, EventAnalysis, MessageLatency};
+use crate::analyses::event_iterator::analyze_traces;
+use crate::argsv2::check_args::CheckArgs;
+use crate::argsv2::helpers::prepare_trace_paths;
+use crate::statistics::{Mean, Sorted};
+use crate::utils::{DurationDisplayImprecise, WeakKnown};
+
+mod budget;
+mod report;
+
+pub use budget::{Metric, Rule, Selector, Statistic};
+
+/// Exit code of the `check` subcommand when any rule of the budget is violated.
+pub const VIOLATION_EXIT_CODE: i32 = 2;
+
+#[derive(Debug, Serialize)]
+pub struct SeriesResult {
+    pub name: String,
+    pub samples: usize,
+    pub value: i64,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleResult {
+    pub name: String,
+    pub metric: Metric,
+    pub selector: String,
+    pub statistic: String,
+    pub max: i64,
+    pub passed: bool,
+    /// Results of all series matched by the rule.
+    ///
+    /// The rule fails if it does not match any series.
+    pub series: Vec<SeriesResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Verdict {
+    pub passed: bool,
+    pub rules: usize,
+    pub failed_rules: usize,
+    pub results: Vec<RuleResult>,
+}
+
+/// Data of the trace the budget rules are evaluated against.
+#[derive(Debug, Default)]
+struct CheckData {
+    latencies: Vec<MessageLatencyExport>,
+    callbacks: Vec<CallbackExecutions>,
+}
+
+/// Values of a single topic, callback or timer matched by a rule.
+struct Series {
+    name: String,
+    values: Vec<i64>,
+}
+
+fn statistic_value(statistic: Statistic, values: &[i64]) -> Option<i64> {
+    match statistic {
+        Statistic::Min => values.iter().min().copied(),
+        Statistic::Max => values.iter().max().copied(),
+        Statistic::Mean => values.mean(),
+        Statistic::Median => Sorted::from_unsorted(values).median().copied(),
+        Statistic::Percentile(quantile) => {
+            Sorted::from_unsorted(values).quantile(quantile).copied()
+        }
+    }
+}
+
+fn matches(selector: Option<&String>, value: &WeakKnown<String>) -> bool {
+    selector.is_none_or(|selector| value.eq_inner(selector))
+}
+
+fn caller_description(caller: &CallerParam) -> String {
+    match caller {
+        CallerParam::Subscription { topic } => format!("subscription {topic}"),
+        CallerParam::Service { name } => format!("service {name}"),
+        CallerParam::Timer { period } => {
+            format!("timer {}", period.map(DurationDisplayImprecise))
+        }
+        CallerParam::Unknown => "unknown caller".to_owned(),
+    }
+}
+
+fn caller_matches(rule: &Rule, caller: &CallerParam) -> bool {
+    let selector = &rule.selector;
+    match caller {
+        CallerParam::Subscription { topic } => {
+            selector.service.is_none()
+                && selector.period.is_none()
+                && matches(selector.topic.as_ref(), topic)
+        }
+        CallerParam::Service { name } => {
+            selector.topic.is_none()
+                && selector.period.is_none()
+                && matches(selector.service.as_ref(), name)
+        }
+        CallerParam::Timer { period } => {
+            selector.topic.is_none()
+                && selector.service.is_none()
+                && selector
+                    .period
+                    .is_none_or(|selected| period.eq_inner(&selected))
+        }
+        CallerParam::Unknown => {
+            selector.topic.is_none() && selector.service.is_none() && selector.period.is_none()
+        }
+    }
+}
+
+/// The `node` selector matches the subscriber node of the channel.
+fn latency_matches(selector: &Selector, latency: &MessageLatencyExport) -> bool {
+    let selected = |selector: Option<&String>, value: &String| {
+        selector.is_none_or(|selector| selector == value)
+    };
+
+    selected(selector.topic.as_ref(), &latency.topic)
+        && selected(selector.node.as_ref(), &latency.subscriber_node)
+        && selected(selector.publisher_node.as_ref(), &latency.publisher_node)
+        && selected(selector.subscriber_node.as_ref(), &latency.subscriber_node)
+}
+
+/// Absolute difference between the timer callback inter-arrival times and the timer period.
+fn timer_jitter(start_times: &[i64], period: i64) -> Vec<i64> {
+    start_times
+        .windows(2)
+        .map(|w| (w[1] - w[0] - period).abs())
+        .collect()
+}
+
+impl CheckData {
+    fn series(&self, rule: &Rule) -> Vec<Series> {
+        let selector = &rule.selector;
+        match rule.metric {
+            Metric::MessageLatency => self
+                .latencies
+                .iter()
+                .filter(|latency| latency_matches(selector, latency))
+                .map(|latency| Series {
+                    name: format!(
+                        "{} {} -> {}",
+                        latency.topic, latency.publisher_node, latency.subscriber_node
+                    ),
+                    values: latency.latencies.clone(),
+                })
+                .collect(),
+            Metric::CallbackDuration => self
+                .callbacks
+                .iter()
+                .filter(|callback| {
+                    matches(selector.node.as_ref(), &callback.node)
+                        && caller_matches(rule, &callback.caller)
+                })
+                .map(|callback| Series {
+                    name: format!("{} {}", callback.node, caller_description(&callback.caller)),
+                    values: callback.durations.clone(),
+                })
+                .collect(),
+            Metric::TimerJitter => self
+                .callbacks
+                .iter()
+                .filter(|callback| matches(selector.node.as_ref(), &callback.node))
+                .filter_map(|callback| {
+                    let CallerParam::Timer {
+                        period: WeakKnown::Known(period),
+                    } = callback.caller
+                    else {
+                        return None;
+                    };
+                    if selector.period.is_some_and(|selected| selected != period) {
+                        return None;
+                    }
+
+                    Some(Series {
+                        name: format!("{} {}", callback.node, caller_description(&callback.caller)),
+                        values: timer_jitter(&callback.start_times, period),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn evaluate(&self, rule: &Rule) -> RuleResult {
+        let series: Vec<SeriesResult> = self
+            .series(rule)
+            .into_iter()
+            .filter_map(|series| {
+                let value = statistic_value(rule.statistic, &series.values)?;
+                Some(SeriesResult {
+                    name: series.name,
+                    samples: series.values.len(),
+                    value,
+                    passed: value <= rule.max,
+                })
+            })
+            .collect();
+
+        RuleResult {
+            name: rule.name.clone(),
+            metric: rule.metric,
+            selector: rule.selector.to_string(),
+            statistic: rule.statistic.to_string(),
+            max: rule.max,
+            passed: !series.is_empty() && series.iter().all(|series| series.passed),
+            series,
+        }
+    }
+}
+
+fn collect_data<L: clap_verbosity_flag::LogLevel>(
+    rules: &[Rule],
+    trace_paths: &[CString],
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<CheckData> {
+    let mut message_latency = rules
+        .iter()
+        .any(|rule| rule.metric == Metric::MessageLatency)
+        .then(MessageLatency::new);
+    let mut callback_duration = rules
+        .iter()
+        .any(|rule| matches!(rule.metric, Metric::CallbackDuration | Metric::TimerJitter))
+        .then(CallbackDuration::new);
+
+    let trace_paths: Vec<_> = trace_paths.iter().map(CString::as_c_str).collect();
+    let analyses = message_latency
+        .iter_mut()
+        .map(|analysis| analysis as &mut dyn EventAnalysis)
+        .chain(
+            callback_duration
+                .iter_mut()
+                .map(|analysis| analysis as &mut dyn EventAnalysis),
+        );
+    let model = analyze_traces(&trace_paths, verbose, analyses)?;
+
+    Ok(CheckData {
+        latencies: message_latency
+            .map(|analysis| {
+                analysis
+                    .calculate_stats(&model)
+                    .into_iter()
+                    .map(|stats| MessageLatencyExport::new(stats, &model))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        callbacks: callback_duration
+            .map(|analysis| analysis.get_callback_executions(&model))
+            .unwrap_or_default(),
+    })
+}
+
+fn print_verdict(verdict: &Verdict) {
+    println!(
+        "Budget check: {} of {} rules passed",
+        verdict.rules - verdict.failed_rules,
+        verdict.rules
+    );
+    for rule in &verdict.results {
+        println!(
+            "  [{}] {}",
+            if rule.passed { "PASS" } else { "FAIL" },
+            rule.name
+        );
+        if rule.series.is_empty() {
+            println!("      No data matched the rule");
+        }
+        for series in &rule.series {
+            println!(
+                "      {} {}: {} (max {}, {} samples){}",
+                rule.statistic,
+                series.name,
+                DurationDisplayImprecise(series.value),
+                DurationDisplayImprecise(rule.max),
+                series.samples,
+                if series.passed { "" } else { " VIOLATED" }
+            );
+        }
+    }
+}
+
+/// Evaluate the budget from `args` against the trace, print the summary
+/// and write the requested reports.
+pub fn run_check<L: clap_verbosity_flag::LogLevel>(
+    args: &CheckArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<Verdict> {
+    let rules = budget::load_budget(args.budget_path()).wrap_err("Failed to load the budget")?;
+    let trace_paths = prepare_trace_paths(args.trace_paths(), args.is_exact_path())?;
+
+    let data = collect_data(&rules, &trace_paths, verbose)?;
+
+    let results: Vec<RuleResult> = rules.iter().map(|rule| data.evaluate(rule)).collect();
+    let failed_rules = results.iter().filter(|result| !result.passed).count();
+    let verdict = Verdict {
+        passed: failed_rules == 0,
+        rules: results.len(),
+        failed_rules,
+        results,
+    };
+
+    print_verdict(&verdict);
+
+    if let Some(path) = args.json_path() {
+        report::write_json(&verdict, path).wrap_err("Failed to write JSON verdict")?;
+    }
+    if let Some(path) = args.junit_path() {
+        report::write_junit(&verdict, path).wrap_err("Failed to write JUnit report")?;
+    }
+
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::statistics::Quantile;
+
+    #[test]
+    fn test_statistic_value() {
+        let values = [5, 1, 4, 2, 3];
+        assert_eq!(statistic_value(Statistic::Min, &values), Some(1));
+        assert_eq!(statistic_value(Statistic::Max, &values), Some(5));
+        assert_eq!(statistic_value(Statistic::Mean, &values), Some(3));
+        assert_eq!(statistic_value(Statistic::Median, &values), Some(3));
+        assert_eq!(
+            statistic_value(Statistic::Percentile(Quantile::new(0.8).unwrap()), &values),
+            Some(4)
+        );
+        assert_eq!(statistic_value(Statistic::Max, &[]), None);
+    }
+
+    #[test]
+    fn test_timer_jitter() {
+        assert_eq!(timer_jitter(&[0, 10, 21, 29], 10), [0, 1, 2]);
+        assert!(timer_jitter(&[0], 10).is_empty());
+    }
+
+    #[test]
+    fn test_latency_selectors() {
+        let latency = MessageLatencyExport {
+            topic: "/cmd_vel".to_owned(),
+            subscriber_node: "/driver".to_owned(),
+            publisher_node: "/planner".to_owned(),
+            latencies: vec![],
+        };
+        let select = |selector: Selector| latency_matches(&selector, &latency);
+
+        assert!(select(Selector::default()));
+        assert!(select(Selector {
+            node: Some("/driver".to_owned()),
+            ..Selector::default()
+        }));
+        assert!(!select(Selector {
+            node: Some("/planner".to_owned()),
+            ..Selector::default()
+        }));
+        assert!(select(Selector {
+            topic: Some("/cmd_vel".to_owned()),
+            publisher_node: Some("/planner".to_owned()),
+            subscriber_node: Some("/driver".to_owned()),
+            ..Selector::default()
+        }));
+        assert!(!select(Selector {
+            subscriber_node: Some("/planner".to_owned()),
+            ..Selector::default()
+        }));
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::utils::DurationDisplayImprecise;
+
+use super::{RuleResult, Verdict};
+
+pub fn write_json(verdict: &Verdict, path: &Path) -> color_eyre::eyre::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, verdict)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Escape a string for use in XML text or attribute values.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Number of test cases and failures of the rule.
+///
+/// A rule without any matched series is reported as a single failed test case.
+fn testcase_counts(rule: &RuleResult) -> (usize, usize) {
+    if rule.series.is_empty() {
+        (1, 1)
+    } else {
+        let failures = rule.series.iter().filter(|series| !series.passed).count();
+        (rule.series.len(), failures)
+    }
+}
+
+fn write_testsuite(output: &mut impl Write, rule: &RuleResult) -> std::io::Result<()> {
+    let (tests, failures) = testcase_counts(rule);
+
+    writeln!(
+        output,
+        r#"  <testsuite name="{}" tests="{tests}" failures="{failures}">"#,
+        xml_escape(&rule.name)
+    )?;
+
+    if rule.series.is_empty() {
+        writeln!(
+            output,
+            r#"    <testcase classname="{}" name="no data">"#,
+            rule.metric
+        )?;
+        writeln!(
+            output,
+            r#"      <failure message="No data matched the rule [{}]"/>"#,
+            xml_escape(&rule.selector)
+        )?;
+        writeln!(output, "    </testcase>")?;
+    }
+
+    for series in &rule.series {
+        write!(
+            output,
+            r#"    <testcase classname="{}" name="{}">"#,
+            rule.metric,
+            xml_escape(&series.name)
+        )?;
+        if !series.passed {
+            let message = format!(
+                "{} {} exceeds the budget of {} ({} samples)",
+                rule.statistic,
+                DurationDisplayImprecise(series.value),
+                DurationDisplayImprecise(rule.max),
+                series.samples
+            );
+            write!(output, r#"<failure message="{}"/>"#, xml_escape(&message))?;
+        }
+        writeln!(output, "</testcase>")?;
+    }
+
+    writeln!(output, "  </testsuite>")
+}
+
+pub fn write_junit(verdict: &Verdict, path: &Path) -> color_eyre::eyre::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    let (tests, failures) = verdict
+        .results
+        .iter()
+        .map(testcase_counts)
+        .fold((0, 0), |(tests, failures), (t, f)| {
+            (tests + t, failures + f)
+        });
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<testsuites name="Ros2TraceAnalyzer check" tests="{tests}" failures="{failures}">"#
+    )?;
+    for rule in &verdict.results {
+        write_testsuite(&mut file, rule)?;
+    }
+    writeln!(file, "</testsuites>")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape(r#"/a -> "b" & <c>"#),
+            "/a -&gt; &quot;b&quot; &amp; &lt;c&gt;"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::argsv2::extract_args::AnalysisProperty;
+use crate::visualization::COLOR_GRADIENT;
+use crate::visualization::graphviz_export::Graph;
+
+use super::{Change, Comparison, ComparisonData, ElementComparison, ElementName};
+
+/// Relative median shift at which the regression is drawn with the most intense color.
+const MAX_COLOR_SHIFT: f64 = 1.0;
+
+const ADDED_COLOR: &str = "blue";
+const REMOVED_COLOR: &str = "gray";
+
+fn change_color(comparison: &ElementComparison) -> Option<String> {
+    match comparison.change {
+        Change::Regression => {
+            let shift = comparison.median_shift.unwrap_or(MAX_COLOR_SHIFT);
+            let value = 0.5 + 0.5 * (shift / MAX_COLOR_SHIFT).min(1.0);
+            Some(COLOR_GRADIENT.color(value as f32).to_string())
+        }
+        Change::Improvement => Some(COLOR_GRADIENT.color(0.0).to_string()),
+        Change::Unchanged | Change::InsufficientData => None,
+    }
+}
+
+/// Pick the element with the largest regression, or the largest improvement if there is none.
+fn most_significant<'a>(
+    elements: impl Iterator<Item = &'a ElementComparison>,
+) -> Option<&'a ElementComparison> {
+    let shift = |element: &&ElementComparison| element.median_shift.unwrap_or_default();
+    let (regressions, improvements): (Vec<_>, Vec<_>) = elements
+        .filter(|element| matches!(element.change, Change::Regression | Change::Improvement))
+        .partition(|element| element.change == Change::Regression);
+
+    regressions
+        .into_iter()
+        .max_by(|a, b| shift(a).total_cmp(&shift(b)))
+        .or_else(|| {
+            improvements
+                .into_iter()
+                .min_by(|a, b| shift(a).total_cmp(&shift(b)))
+        })
+}
+
+fn shift_label(comparison: &ElementComparison) -> String {
+    super::format_shift(comparison.median_shift)
+}
+
+/// Graph of ROS nodes connected by topics.
+///
+/// Nodes are colored by the most significant change of their callback durations and edges
+/// by the change of the message latency. Added elements are blue, removed elements are gray
+/// and dashed.
+pub(super) fn comparison_graph(
+    comparison: &Comparison,
+    baseline: &ComparisonData,
+    candidate: &ComparisonData,
+) -> Graph {
+    let mut graph = Graph::new();
+
+    let nodes: BTreeSet<&str> = baseline
+        .nodes()
+        .union(&candidate.nodes())
+        .copied()
+        .collect();
+    let mut node_ids = HashMap::new();
+    for (id, name) in nodes.into_iter().enumerate() {
+        node_ids.insert(name, id);
+
+        let callbacks = comparison.elements.iter().filter(|element| {
+            element.property == AnalysisProperty::CallbackDuration
+                && matches!(&element.element, ElementName::Interface(interface) if interface.node == name)
+        });
+        let change = most_significant(callbacks);
+
+        let label = match change {
+            Some(change) => format!("{name}\ncallback {}", shift_label(change)),
+            None => name.to_owned(),
+        };
+        let node = graph.add_node(&label, id);
+
+        if comparison.added_nodes.iter().any(|added| added == name) {
+            node.set_attribute("color", ADDED_COLOR);
+        } else if comparison
+            .removed_nodes
+            .iter()
+            .any(|removed| removed == name)
+        {
+            node.set_attribute("color", REMOVED_COLOR);
+            node.set_attribute("style", "dashed");
+        } else if let Some(color) = change.and_then(change_color) {
+            node.set_attribute("color", &color);
+            node.set_attribute("penwidth", "2");
+        }
+    }
+
+    let edges: BTreeSet<_> = baseline
+        .edges()
+        .union(&candidate.edges())
+        .copied()
+        .filter(|edge| edge.source_node != edge.destination_node)
+        .collect();
+    for edge in edges {
+        let (Some(&source), Some(&target)) = (
+            node_ids.get(edge.source_node.as_str()),
+            node_ids.get(edge.destination_node.as_str()),
+        ) else {
+            continue;
+        };
+
+        let latency = comparison.elements.iter().find(|element| {
+            element.property == AnalysisProperty::MessageLatency
+                && matches!(&element.element, ElementName::Channel(channel) if channel == edge)
+        });
+        let label = match latency {
+            Some(latency) if latency.change != Change::InsufficientData => {
+                format!("{}\n{}", edge.topic, shift_label(latency))
+            }
+            _ => edge.topic.clone(),
+        };
+        let graph_edge = graph.add_edge(source, target, &label);
+
+        if comparison.added_edges.contains(edge) {
+            graph_edge.set_attribute("color", ADDED_COLOR);
+        } else if comparison.removed_edges.contains(edge) {
+            graph_edge.set_attribute("color", REMOVED_COLOR);
+            graph_edge.set_attribute("style", "dashed");
+        } else if let Some(color) = latency.and_then(change_color) {
+            graph_edge.set_attribute("color", &color);
+            graph_edge.set_attribute("penwidth", "2");
+        }
+    }
+
+    graph
+}
@@ -0,0 +1,493 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::CString;
+use std::path::Path;
+
+use color_eyre::eyre::Context;
+use derive_more::Display;
+use serde::Serialize;
+
+use crate::analyses::analysis::dependency_graph::{
+    ActivationDelayExport, CallbackDurationExport, MessageLatencyExport, MessagesDelayExport,
+    PublicationDelayExport,
+};
+use crate::analyses::analysis::{DependencyGraph, EventAnalysis};
+use crate::analyses::event_iterator::{analyze_traces, get_buf_writer_for_path};
+use crate::argsv2::compare_args::{CompareArgs, CompareInput};
+use crate::argsv2::extract_args::AnalysisProperty;
+use crate::argsv2::helpers::prepare_trace_paths;
+use crate::extract::{RosChannelCompleteName, RosInterfaceCompleteName};
+use crate::statistics::{Quantile, Sorted, mann_whitney_u};
+use crate::utils::DurationDisplayImprecise;
+use crate::utils::binary_sql_store::BinarySqlStore;
+
+mod dot;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Display, Serialize)]
+#[serde(untagged)]
+pub enum ElementName {
+    Interface(RosInterfaceCompleteName),
+    Channel(RosChannelCompleteName),
+}
+
+type ElementKey = (AnalysisProperty, ElementName);
+
+/// Samples of all elements of one side of the comparison.
+///
+/// Elements are identified by their ROS names, because the numeric IDs differ between runs.
+/// Samples of elements with the same name are merged.
+#[derive(Debug, Default)]
+struct ComparisonData {
+    elements: BTreeMap<ElementKey, Vec<i64>>,
+}
+
+impl ComparisonData {
+    fn add(&mut self, property: AnalysisProperty, name: ElementName, values: Vec<i64>) {
+        self.elements
+            .entry((property, name))
+            .or_default()
+            .extend(values);
+    }
+
+    fn from_exports(
+        callback_durations: Vec<CallbackDurationExport>,
+        activation_delays: Vec<ActivationDelayExport>,
+        publication_delays: Vec<PublicationDelayExport>,
+        message_delays: Vec<MessagesDelayExport>,
+        message_latencies: Vec<MessageLatencyExport>,
+    ) -> Self {
+        let mut data = Self::default();
+
+        for export in callback_durations {
+            data.add(
+                AnalysisProperty::CallbackDuration,
+                ElementName::Interface(export.name),
+                export.callback_durations,
+            );
+        }
+        for export in activation_delays {
+            data.add(
+                AnalysisProperty::ActivationDelay,
+                ElementName::Interface(export.name),
+                export.activation_delays,
+            );
+        }
+        for export in publication_delays {
+            data.add(
+                AnalysisProperty::PublicationDelay,
+                ElementName::Interface(export.name),
+                export.publication_delays,
+            );
+        }
+        for export in message_delays {
+            data.add(
+                AnalysisProperty::MessageDelay,
+                ElementName::Interface(export.name),
+                export.messages_delays,
+            );
+        }
+        for export in message_latencies {
+            data.add(
+                AnalysisProperty::MessageLatency,
+                ElementName::Channel(export.name),
+                export.messages_latencies,
+            );
+        }
+
+        data
+    }
+
+    fn from_bundle(path: &Path) -> color_eyre::eyre::Result<Self> {
+        let store = BinarySqlStore::open(path)?;
+
+        Ok(Self::from_exports(
+            store.get_all()?,
+            store.get_all()?,
+            store.get_all()?,
+            store.get_all()?,
+            store.get_all()?,
+        ))
+    }
+
+    fn from_trace<L: clap_verbosity_flag::LogLevel>(
+        path: &Path,
+        exact: bool,
+        verbose: &clap_verbosity_flag::Verbosity<L>,
+    ) -> color_eyre::eyre::Result<Self> {
+        let trace_paths = prepare_trace_paths(&[path.to_owned()], exact)?;
+        let trace_paths: Vec<_> = trace_paths.iter().map(CString::as_c_str).collect();
+
+        let mut graph = DependencyGraph::new();
+        let model = analyze_traces(
+            &trace_paths,
+            verbose,
+            [&mut graph as &mut dyn EventAnalysis],
+        )?;
+
+        let dot_graph = graph.to_dot_graph(&model, false, false, 1.0);
+        let node_ids = dot_graph.node_ids();
+
+        Ok(Self::from_exports(
+            graph.callback_durations(&model, node_ids),
+            graph.activation_delays(&model, node_ids),
+            graph.publication_delays(&model, node_ids),
+            graph.message_delays(&model, node_ids),
+            graph.message_latencies(&model, node_ids, dot_graph.edge_ids()),
+        ))
+    }
+
+    fn load<L: clap_verbosity_flag::LogLevel>(
+        input: CompareInput,
+        exact: bool,
+        verbose: &clap_verbosity_flag::Verbosity<L>,
+    ) -> color_eyre::eyre::Result<Self> {
+        match input {
+            CompareInput::Bundle(path) => Self::from_bundle(&path)
+                .wrap_err_with(|| format!("Failed to read binary bundle {path:?}")),
+            CompareInput::Trace(path) => Self::from_trace(path, exact, verbose)
+                .wrap_err_with(|| format!("Failed to analyze trace {path:?}")),
+        }
+    }
+
+    fn nodes(&self) -> BTreeSet<&str> {
+        self.elements
+            .keys()
+            .flat_map(|(_, name)| match name {
+                ElementName::Interface(name) => vec![name.node.as_str()],
+                ElementName::Channel(name) => {
+                    vec![name.source_node.as_str(), name.destination_node.as_str()]
+                }
+            })
+            .filter(|node| !node.is_empty())
+            .collect()
+    }
+
+    fn topics(&self) -> BTreeSet<&str> {
+        self.elements
+            .keys()
+            .filter_map(|(_, name)| match name {
+                ElementName::Interface(name) => name
+                    .interface
+                    .strip_prefix("Publisher(")
+                    .or_else(|| name.interface.strip_prefix("Subscriber("))
+                    .and_then(|topic| topic.strip_suffix(')')),
+                ElementName::Channel(_) => None,
+            })
+            .collect()
+    }
+
+    fn edges(&self) -> BTreeSet<&RosChannelCompleteName> {
+        self.elements
+            .keys()
+            .filter_map(|(_, name)| match name {
+                ElementName::Channel(name) => Some(name),
+                ElementName::Interface(_) => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    #[display("regression")]
+    Regression,
+    #[display("improvement")]
+    Improvement,
+    #[display("unchanged")]
+    Unchanged,
+    /// One of the sides has no samples
+    #[display("insufficient data")]
+    InsufficientData,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuantileShift {
+    pub quantile: f64,
+    pub baseline: i64,
+    pub candidate: i64,
+    /// `(candidate - baseline) / baseline`, unknown if the baseline is zero
+    pub relative_shift: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ElementComparison {
+    pub property: AnalysisProperty,
+    pub element: ElementName,
+    pub baseline_samples: usize,
+    pub candidate_samples: usize,
+    pub median_shift: Option<f64>,
+    pub quantiles: Vec<QuantileShift>,
+    /// p-value of the two-sided Mann-Whitney U test
+    pub p_value: Option<f64>,
+    pub change: Change,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    pub added_topics: Vec<String>,
+    pub removed_topics: Vec<String>,
+    pub added_edges: Vec<RosChannelCompleteName>,
+    pub removed_edges: Vec<RosChannelCompleteName>,
+    pub elements: Vec<ElementComparison>,
+}
+
+fn relative_shift(baseline: i64, candidate: i64) -> Option<f64> {
+    (baseline != 0).then(|| (candidate - baseline) as f64 / baseline as f64)
+}
+
+/// Items of `a` that are not in `b`.
+fn difference<T: Ord + ToOwned + ?Sized>(a: &BTreeSet<&T>, b: &BTreeSet<&T>) -> Vec<T::Owned> {
+    a.difference(b).map(|item| (*item).to_owned()).collect()
+}
+
+/// Parameters of the comparison of two sample sets.
+#[derive(Debug, Clone, Copy)]
+struct Thresholds<'a> {
+    quantiles: &'a [Quantile],
+    significance: f64,
+    min_shift: f64,
+}
+
+fn compare_samples(
+    property: AnalysisProperty,
+    element: ElementName,
+    baseline: &[i64],
+    candidate: &[i64],
+    thresholds: Thresholds,
+) -> ElementComparison {
+    let baseline_sorted = Sorted::from_unsorted(baseline);
+    let candidate_sorted = Sorted::from_unsorted(candidate);
+
+    let quantiles = thresholds
+        .quantiles
+        .iter()
+        .filter_map(|&q| {
+            let baseline = *baseline_sorted.quantile(q)?;
+            let candidate = *candidate_sorted.quantile(q)?;
+            Some(QuantileShift {
+                quantile: q.value(),
+                baseline,
+                candidate,
+                relative_shift: relative_shift(baseline, candidate),
+            })
+        })
+        .collect();
+
+    let median_shift = baseline_sorted
+        .median()
+        .zip(candidate_sorted.median())
+        .and_then(|(&baseline, &candidate)| relative_shift(baseline, candidate));
+    let p_value = mann_whitney_u(baseline, candidate);
+
+    let change = match (p_value, median_shift) {
+        (None, _) => Change::InsufficientData,
+        (Some(p), Some(shift))
+            if p < thresholds.significance && shift.abs() >= thresholds.min_shift =>
+        {
+            if shift > 0.0 {
+                Change::Regression
+            } else {
+                Change::Improvement
+            }
+        }
+        _ => Change::Unchanged,
+    };
+
+    ElementComparison {
+        property,
+        element,
+        baseline_samples: baseline.len(),
+        candidate_samples: candidate.len(),
+        median_shift,
+        quantiles,
+        p_value,
+        change,
+    }
+}
+
+fn compare(
+    baseline: &ComparisonData,
+    candidate: &ComparisonData,
+    thresholds: Thresholds,
+) -> Comparison {
+    let elements = baseline
+        .elements
+        .iter()
+        .filter_map(|(key, baseline_samples)| {
+            let candidate_samples = candidate.elements.get(key)?;
+            let (property, element) = key.clone();
+            Some(compare_samples(
+                property,
+                element,
+                baseline_samples,
+                candidate_samples,
+                thresholds,
+            ))
+        })
+        .collect();
+
+    let (baseline_nodes, candidate_nodes) = (baseline.nodes(), candidate.nodes());
+    let (baseline_topics, candidate_topics) = (baseline.topics(), candidate.topics());
+    let (baseline_edges, candidate_edges) = (baseline.edges(), candidate.edges());
+
+    Comparison {
+        added_nodes: difference(&candidate_nodes, &baseline_nodes),
+        removed_nodes: difference(&baseline_nodes, &candidate_nodes),
+        added_topics: difference(&candidate_topics, &baseline_topics),
+        removed_topics: difference(&baseline_topics, &candidate_topics),
+        added_edges: difference(&candidate_edges, &baseline_edges),
+        removed_edges: difference(&baseline_edges, &candidate_edges),
+        elements,
+    }
+}
+
+fn print_list<T: std::fmt::Display>(title: &str, added: &[T], removed: &[T]) {
+    println!("{title}: {} added, {} removed", added.len(), removed.len());
+    for item in added {
+        println!("  + {item}");
+    }
+    for item in removed {
+        println!("  - {item}");
+    }
+}
+
+fn format_shift(shift: Option<f64>) -> String {
+    shift.map_or_else(|| "?".to_owned(), |shift| format!("{:+.1}%", shift * 100.0))
+}
+
+fn print_comparison(comparison: &Comparison) {
+    print_list("Nodes", &comparison.added_nodes, &comparison.removed_nodes);
+    print_list(
+        "Topics",
+        &comparison.added_topics,
+        &comparison.removed_topics,
+    );
+    print_list("Edges", &comparison.added_edges, &comparison.removed_edges);
+
+    for change in [Change::Regression, Change::Improvement] {
+        let mut elements: Vec<_> = comparison
+            .elements
+            .iter()
+            .filter(|element| element.change == change)
+            .collect();
+        elements.sort_by(|a, b| {
+            b.median_shift
+                .unwrap_or_default()
+                .abs()
+                .total_cmp(&a.median_shift.unwrap_or_default().abs())
+        });
+
+        println!("{}s: {}", change.to_string().to_uppercase(), elements.len());
+        for element in elements {
+            println!(
+                "  {} {}: median {} (p-value {:.2e})",
+                element.property,
+                element.element,
+                format_shift(element.median_shift),
+                element.p_value.unwrap_or(1.0)
+            );
+            for shift in &element.quantiles {
+                println!(
+                    "      {:5}: {} -> {} ({})",
+                    shift.quantile,
+                    DurationDisplayImprecise(shift.baseline),
+                    DurationDisplayImprecise(shift.candidate),
+                    format_shift(shift.relative_shift)
+                );
+            }
+        }
+    }
+
+    let count = |change| {
+        comparison
+            .elements
+            .iter()
+            .filter(|element| element.change == change)
+            .count()
+    };
+    println!(
+        "Unchanged: {}, insufficient data: {}",
+        count(Change::Unchanged),
+        count(Change::InsufficientData)
+    );
+}
+
+pub fn run_compare<L: clap_verbosity_flag::LogLevel>(
+    args: &CompareArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<()> {
+    let baseline = ComparisonData::load(args.baseline(), args.is_exact_path(), verbose)?;
+    let candidate = ComparisonData::load(args.candidate(), args.is_exact_path(), verbose)?;
+
+    let comparison = compare(
+        &baseline,
+        &candidate,
+        Thresholds {
+            quantiles: args.quantiles(),
+            significance: args.significance(),
+            min_shift: args.min_shift(),
+        },
+    );
+
+    print_comparison(&comparison);
+
+    if let Some(path) = args.json_path() {
+        let mut writer = get_buf_writer_for_path(path)?;
+        serde_json::to_writer(&mut writer, &comparison)
+            .wrap_err("Failed to write the comparison")?;
+    }
+
+    if let Some(path) = args.dot_path() {
+        use std::io::Write;
+
+        let graph = dot::comparison_graph(&comparison, &baseline, &candidate);
+        let mut writer = get_buf_writer_for_path(path)?;
+        write!(writer, "{graph}").wrap_err("Failed to write the comparison graph")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn interface(node: &str, interface: &str) -> ElementName {
+        ElementName::Interface(RosInterfaceCompleteName {
+            interface: interface.to_owned(),
+            node: node.to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_compare() {
+        let quantiles = [Quantile::new(0.5).unwrap()];
+        let thresholds = Thresholds {
+            quantiles: &quantiles,
+            significance: 0.01,
+            min_shift: 0.05,
+        };
+
+        let mut baseline = ComparisonData::default();
+        let mut candidate = ComparisonData::default();
+        let property = AnalysisProperty::CallbackDuration;
+        baseline.add(property, interface("/a", "cb"), (100..200).collect());
+        candidate.add(property, interface("/a", "cb"), (150..250).collect());
+        baseline.add(property, interface("/b", "cb"), (100..200).collect());
+        candidate.add(property, interface("/b", "cb"), (100..200).rev().collect());
+        baseline.add(property, interface("/old", "cb"), vec![1]);
+        candidate.add(property, interface("/new", "cb"), vec![1]);
+
+        let comparison = compare(&baseline, &candidate, thresholds);
+
+        assert_eq!(comparison.added_nodes, ["/new"]);
+        assert_eq!(comparison.removed_nodes, ["/old"]);
+        assert_eq!(comparison.elements.len(), 2);
+        assert_eq!(comparison.elements[0].change, Change::Regression);
+        assert_eq!(comparison.elements[0].quantiles[0].baseline, 149);
+        assert_eq!(comparison.elements[0].quantiles[0].candidate, 199);
+        assert_eq!(comparison.elements[1].change, Change::Unchanged);
+    }
+}
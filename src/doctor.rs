@@ -0,0 +1,390 @@
+//! Diagnostics of the babeltrace2 installation used to read the traces.
+
+use std::ffi::CString;
+use std::fmt::Display;
+use std::io::Write;
+
+use bt2_sys::graph::component::{BtComponentClassConst, BtComponentType};
+use bt2_sys::graph::plugin::{BtPlugin, BtPluginLoadError};
+use bt2_sys::version::BtLibraryVersion;
+use color_eyre::eyre::{Context, bail, ensure, eyre};
+
+use crate::argsv2::doctor_args::DoctorArgs;
+
+/// Environment variables changing where babeltrace2 finds its plugins.
+const ENVIRONMENT_VARIABLES: &[&str] = &[
+    "BABELTRACE_PLUGIN_PATH",
+    "LIBBABELTRACE2_PLUGIN_PROVIDER_DIR",
+];
+
+/// Component class of a babeltrace2 plugin, e.g., `source.ctf.fs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ComponentClassId {
+    typ: BtComponentType,
+    plugin: &'static str,
+    class: &'static str,
+}
+
+impl Display for ComponentClassId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.typ, self.plugin, self.class)
+    }
+}
+
+/// Component class used by the analyzer.
+struct UsedClass {
+    id: ComponentClassId,
+    /// Option needing the class, `None` if the class is needed to read any traces.
+    option: Option<&'static str>,
+}
+
+const CTF_FS_SOURCE: ComponentClassId = ComponentClassId {
+    typ: BtComponentType::Source,
+    plugin: "ctf",
+    class: "fs",
+};
+const CTF_LIVE_SOURCE: ComponentClassId = ComponentClassId {
+    typ: BtComponentType::Source,
+    plugin: "ctf",
+    class: "lttng-live",
+};
+const UTILS_MUXER: ComponentClassId = ComponentClassId {
+    typ: BtComponentType::Filter,
+    plugin: "utils",
+    class: "muxer",
+};
+
+const USED_CLASSES: &[UsedClass] = &[
+    UsedClass {
+        id: CTF_FS_SOURCE,
+        option: None,
+    },
+    UsedClass {
+        id: UTILS_MUXER,
+        option: None,
+    },
+    UsedClass {
+        id: CTF_LIVE_SOURCE,
+        option: Some("--live"),
+    },
+    UsedClass {
+        id: ComponentClassId {
+            typ: BtComponentType::Filter,
+            plugin: "utils",
+            class: "trimmer",
+        },
+        option: Some("--begin, --end"),
+    },
+    UsedClass {
+        id: ComponentClassId {
+            typ: BtComponentType::Filter,
+            plugin: "lttng-utils",
+            class: "debug-info",
+        },
+        option: Some("--debug-info"),
+    },
+    UsedClass {
+        id: ComponentClassId {
+            typ: BtComponentType::Sink,
+            plugin: "ctf",
+            class: "fs",
+        },
+        option: Some("--trace-copy"),
+    },
+];
+
+/// Owned description of a loaded plugin.
+#[derive(Debug, Clone)]
+struct PluginInfo {
+    name: String,
+    version: Option<String>,
+    path: Option<String>,
+    classes: Vec<ClassInfo>,
+}
+
+#[derive(Debug, Clone)]
+struct ClassInfo {
+    typ: BtComponentType,
+    name: String,
+    description: Option<String>,
+    help: Option<String>,
+}
+
+impl PluginInfo {
+    fn new(plugin: &BtPlugin) -> Self {
+        Self {
+            name: plugin.name().to_owned(),
+            version: plugin.version().map(|version| version.to_string()),
+            path: plugin.path().map(str::to_owned),
+            classes: plugin
+                .component_classes()
+                .iter()
+                .map(ClassInfo::new)
+                .collect(),
+        }
+    }
+
+    fn class(&self, id: ComponentClassId) -> Option<&ClassInfo> {
+        self.classes
+            .iter()
+            .find(|class| class.typ == id.typ && class.name == id.class)
+    }
+}
+
+impl Display for PluginInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, " {version}")?;
+        }
+        match &self.path {
+            Some(path) => write!(f, " ({path})"),
+            None => write!(f, " (built-in)"),
+        }
+    }
+}
+
+impl ClassInfo {
+    fn new(class: &BtComponentClassConst) -> Self {
+        let string = |string: Result<&str, _>| {
+            string.map_or_else(|_| "<invalid UTF-8>".to_owned(), str::to_owned)
+        };
+        Self {
+            typ: class.get_type(),
+            name: string(class.name()),
+            description: class.description().map(string),
+            help: class.help_text().map(string),
+        }
+    }
+}
+
+/// Problem with a component class used by the analyzer.
+#[derive(Debug)]
+enum ClassProblem<'a> {
+    PluginNotFound,
+    ClassNotFound(&'a PluginInfo),
+}
+
+impl Display for ClassProblem<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PluginNotFound => write!(f, "plugin not found"),
+            Self::ClassNotFound(plugin) => write!(
+                f,
+                "plugin {plugin} has no such component class, it is incompatible"
+            ),
+        }
+    }
+}
+
+/// Find the component class `id` among the `plugins`.
+///
+/// The first plugin with the name is used, like babeltrace2 does.
+fn find_class(
+    plugins: &[PluginInfo],
+    id: ComponentClassId,
+) -> Result<&ClassInfo, ClassProblem<'_>> {
+    let plugin = plugins
+        .iter()
+        .find(|plugin| plugin.name == id.plugin)
+        .ok_or(ClassProblem::PluginNotFound)?;
+    plugin.class(id).ok_or(ClassProblem::ClassNotFound(plugin))
+}
+
+/// Check that the plugins needed to read traces, or a live session if `live` is set, are available.
+///
+/// Fails early with a clear message instead of when the trace processing graph is constructed.
+pub fn check_plugins(live: bool) -> color_eyre::Result<()> {
+    let source = if live { CTF_LIVE_SOURCE } else { CTF_FS_SOURCE };
+    for id in [source, UTILS_MUXER] {
+        let name = CString::new(id.plugin).unwrap();
+        let plugin = match BtPlugin::find_anywhere(&name) {
+            Ok(plugin) => PluginInfo::new(&plugin),
+            Err(BtPluginLoadError::NotFound) => bail!(
+                "The babeltrace2 plugin `{}` needed to read traces was not found. Install the \
+                 babeltrace2 plugins or add their directory to `BABELTRACE_PLUGIN_PATH`. \
+                 Run the `doctor` subcommand for details",
+                id.plugin
+            ),
+            Err(e) => bail!("Failed to load the babeltrace2 plugin `{}`: {e}", id.plugin),
+        };
+        ensure!(
+            plugin.class(id).is_some(),
+            "The babeltrace2 plugin {plugin} has no component class `{id}`, it is incompatible \
+             with the analyzer. Run the `doctor` subcommand for details"
+        );
+    }
+    Ok(())
+}
+
+/// Print the babeltrace2 library version, the plugins and the status of the component
+/// classes used by the analyzer.
+///
+/// Returns whether all component classes needed to read traces are available.
+fn write_report(
+    out: &mut impl Write,
+    library_version: &str,
+    environment: &[(&str, Option<String>)],
+    plugins: &[PluginInfo],
+    component_help: bool,
+) -> std::io::Result<bool> {
+    writeln!(out, "babeltrace2 library: {library_version}")?;
+    for (variable, value) in environment {
+        writeln!(out, "{variable}: {}", value.as_deref().unwrap_or("<unset>"))?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Plugins:")?;
+    if plugins.is_empty() {
+        writeln!(out, "  No plugins found")?;
+    }
+    for plugin in plugins {
+        writeln!(out, "  {plugin}")?;
+        for class in &plugin.classes {
+            write!(out, "    {}.{}.{}", class.typ, plugin.name, class.name)?;
+            match &class.description {
+                Some(description) => writeln!(out, ": {description}")?,
+                None => writeln!(out)?,
+            }
+            if let Some(help) = class.help.as_ref().filter(|_| component_help) {
+                for line in help.lines() {
+                    writeln!(out, "        {line}")?;
+                }
+            }
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Component classes used by the analyzer:")?;
+    let mut complete = true;
+    for used in USED_CLASSES {
+        let usage = used.option.unwrap_or("reading traces");
+        match find_class(plugins, used.id) {
+            Ok(_) => writeln!(out, "  [ok]      {} ({usage})", used.id)?,
+            Err(problem) => {
+                complete &= used.option.is_some();
+                writeln!(out, "  [missing] {} ({usage}): {problem}", used.id)?;
+            }
+        }
+    }
+
+    Ok(complete)
+}
+
+pub fn run_doctor(args: &DoctorArgs) -> color_eyre::Result<()> {
+    let plugins = if args.plugin_dirs().is_empty() {
+        BtPlugin::find_all().map_err(|e| eyre!("Failed to find the babeltrace2 plugins: {e}"))?
+    } else {
+        let mut plugins = Vec::new();
+        for dir in args.plugin_dirs() {
+            let path = CString::new(dir.as_os_str().as_encoded_bytes())
+                .wrap_err_with(|| format!("Invalid path: {}", dir.display()))?;
+            plugins
+                .extend(BtPlugin::find_all_from_dir(&path, false).map_err(|e| {
+                    eyre!("Failed to load the plugins from {}: {e}", dir.display())
+                })?);
+        }
+        plugins
+    };
+    let plugins: Vec<_> = plugins.iter().map(PluginInfo::new).collect();
+
+    let environment: Vec<_> = ENVIRONMENT_VARIABLES
+        .iter()
+        .map(|&variable| (variable, std::env::var(variable).ok()))
+        .collect();
+
+    let complete = write_report(
+        &mut std::io::stdout().lock(),
+        &BtLibraryVersion::get().to_string(),
+        &environment,
+        &plugins,
+        args.component_help(),
+    )?;
+    ensure!(
+        complete,
+        "Component classes needed to read traces are missing"
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn plugin(name: &str, classes: &[(BtComponentType, &str)]) -> PluginInfo {
+        PluginInfo {
+            name: name.to_owned(),
+            version: Some("2.0.0".to_owned()),
+            path: Some(format!(
+                "/usr/lib/babeltrace2/plugins/babeltrace-plugin-{name}.so"
+            )),
+            classes: classes
+                .iter()
+                .map(|&(typ, name)| ClassInfo {
+                    typ,
+                    name: name.to_owned(),
+                    description: Some(format!("Description of {name}")),
+                    help: Some("Line 1\nLine 2".to_owned()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_class() {
+        let plugins = [plugin("ctf", &[(BtComponentType::Sink, "fs")])];
+
+        assert!(matches!(
+            find_class(&plugins, CTF_FS_SOURCE),
+            Err(ClassProblem::ClassNotFound(plugin)) if plugin.name == "ctf"
+        ));
+        assert!(matches!(
+            find_class(&plugins, UTILS_MUXER),
+            Err(ClassProblem::PluginNotFound)
+        ));
+        assert!(find_class(&plugins, USED_CLASSES[5].id).is_ok());
+    }
+
+    #[test]
+    fn test_write_report() {
+        let plugins = [
+            plugin(
+                "ctf",
+                &[
+                    (BtComponentType::Source, "fs"),
+                    (BtComponentType::Sink, "fs"),
+                ],
+            ),
+            plugin("utils", &[(BtComponentType::Filter, "muxer")]),
+        ];
+        let mut out = Vec::new();
+        let complete = write_report(
+            &mut out,
+            "2.0.6 \"Amqui\"",
+            &[("BABELTRACE_PLUGIN_PATH", None)],
+            &plugins,
+            true,
+        )
+        .unwrap();
+        assert!(complete);
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with(
+            "babeltrace2 library: 2.0.6 \"Amqui\"\nBABELTRACE_PLUGIN_PATH: <unset>\n"
+        ));
+        assert!(out.contains(
+            "  ctf 2.0.0 (/usr/lib/babeltrace2/plugins/babeltrace-plugin-ctf.so)\n    source.ctf.fs: Description of fs\n        Line 1\n        Line 2\n"
+        ));
+        assert!(out.contains("  [ok]      filter.utils.muxer (reading traces)\n"));
+        assert!(out.contains(
+            "  [missing] filter.utils.trimmer (--begin, --end): plugin utils 2.0.0 (/usr/lib/babeltrace2/plugins/babeltrace-plugin-utils.so) has no such component class, it is incompatible\n"
+        ));
+        assert!(out.contains(
+            "  [missing] filter.lttng-utils.debug-info (--debug-info): plugin not found\n"
+        ));
+
+        let mut out = Vec::new();
+        assert!(!write_report(&mut out, "2.0.6", &[], &plugins[..1], false).unwrap());
+    }
+}
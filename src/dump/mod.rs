@@ -0,0 +1,166 @@
+use std::ffi::CString;
+use std::io::Write;
+
+use bt2_sys::iterator::MessageIterator;
+use bt2_sys::message::BtMessageType;
+use color_eyre::eyre::{Context, Result, eyre};
+
+use crate::analyses::event_iterator::{convert, describe_bt_error};
+use crate::argsv2::dump_args::DumpArgs;
+use crate::argsv2::helpers::{find_traces, resolve_time_range};
+use crate::processor::{self, Processor};
+use crate::raw_events;
+
+mod names;
+mod writer;
+
+use names::ResolvedNames;
+use writer::{DumpRecord, DumpWriter};
+
+/// Selection of the events by their names.
+#[derive(Debug)]
+struct EventFilter<'a> {
+    names: &'a [String],
+}
+
+impl EventFilter<'_> {
+    /// Whether the event with the full name `event_name` (e.g. `ros2:rcl_publish`) is selected.
+    ///
+    /// All events are selected if no names are given.
+    fn matches(&self, event_name: &str) -> bool {
+        let short_name = event_name
+            .split_once(':')
+            .map_or(event_name, |(_, name)| name);
+        self.names.is_empty()
+            || self
+                .names
+                .iter()
+                .any(|name| name == event_name || name == short_name)
+    }
+}
+
+/// Write the ROS events of the traces selected by `args`.
+pub fn run_dump<L: clap_verbosity_flag::LogLevel>(
+    args: &DumpArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> Result<()> {
+    // Found traces are not printed, the events may be written to the standard output
+    let trace_paths = find_traces(args.trace_paths(), args.is_exact_path())?;
+    for path in &trace_paths {
+        log::info!("Found trace {}", path.to_string_lossy());
+    }
+    let trace_paths_cstr: Vec<_> = trace_paths.iter().map(CString::as_c_str).collect();
+
+    let (begin, end) = resolve_time_range(args.begin(), args.end(), &trace_paths_cstr)?;
+    let filter = EventFilter {
+        names: args.event_names(),
+    };
+
+    let output: Box<dyn Write + Send> = match args.output_path() {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .wrap_err_with(|| format!("Failed to create file: `{}`", path.display()))?,
+        )),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout())),
+    };
+    let mut writer = DumpWriter::new(output, args.format(), args.is_processed())?;
+
+    let log_level = convert(
+        verbose
+            .log_level()
+            .unwrap_or(clap_verbosity_flag::log::Level::Error),
+    );
+    let mut iter = MessageIterator::try_new(&trace_paths_cstr, log_level).map_err(|error| {
+        eyre!(
+            "Failed to construct the trace processing graph: {}",
+            describe_bt_error(&error, &error.causes())
+        )
+    })?;
+    let mut processor = args.is_processed().then(Processor::new);
+    let mut unsupported_events = 0_usize;
+
+    while let Some(message) = iter.try_next().map_err(|error| {
+        eyre!(
+            "Failed to read the traces: {}",
+            describe_bt_error(&error, &error.causes())
+        )
+    })? {
+        if message.get_type() != BtMessageType::Event {
+            continue;
+        }
+        let message = message.into_event_msg();
+        let Some(event) = raw_events::get_full_event(&message) else {
+            unsupported_events += 1;
+            continue;
+        };
+        let time = event.time.timestamp_nanos();
+        if end.is_some_and(|end| time > end) {
+            // The events are ordered by time
+            break;
+        }
+
+        let bt_event = message.get_event();
+        let event_class = bt_event.get_class();
+        let event_name = event_class.get_name().unwrap_or_default();
+        let selected = begin.is_none_or(|begin| time >= begin) && filter.matches(event_name);
+        let context = event.context.clone();
+
+        // Events outside the selection are still processed, they initialize the named objects
+        let names = match &mut processor {
+            None => None,
+            Some(processor) => match processor.process_raw_event(event) {
+                Ok(processor::MaybeProcessed::Processed(processed)) => {
+                    Some(ResolvedNames::resolve(&processed.event, processor.model()))
+                }
+                Ok(processor::MaybeProcessed::Raw(_)) => Some(ResolvedNames::default()),
+                Err(err) => {
+                    log::warn!("Failed to process event {event_name}: {err:?}");
+                    Some(ResolvedNames::default())
+                }
+            },
+        };
+        if !selected {
+            continue;
+        }
+
+        let payload = match bt_event.get_payload() {
+            Some(payload) => serde_json::to_value(&payload)
+                .wrap_err_with(|| format!("Failed to serialize the payload of {event_name}"))?,
+            None => serde_json::Value::Null,
+        };
+        writer.write(&DumpRecord {
+            time,
+            event: event_name,
+            context: &context,
+            payload,
+            names,
+        })?;
+    }
+
+    writer.finish()?;
+
+    if unsupported_events > 0 {
+        log::info!("Skipped {unsupported_events} events that are not ROS events");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::EventFilter;
+
+    #[test]
+    fn test_event_filter() {
+        let names = ["ros2:rcl_publish".to_owned(), "callback_start".to_owned()];
+        let filter = EventFilter { names: &names };
+
+        assert!(filter.matches("ros2:rcl_publish"));
+        assert!(filter.matches("ros2:callback_start"));
+        assert!(!filter.matches("ros2:rmw_publish"));
+        assert!(!filter.matches("r2r:rcl_publish"));
+
+        let filter = EventFilter { names: &[] };
+        assert!(filter.matches("ros2:rmw_publish"));
+    }
+}
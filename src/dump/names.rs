@@ -0,0 +1,137 @@
+use serde::Serialize;
+
+use crate::model::{Callback, CallbackCaller, Idx, Model, Node, Publisher, Subscriber};
+use crate::processed_events::{Event, r2r, ros2};
+
+/// Names of the ROS objects a processed event refers to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResolvedNames {
+    pub node: Option<String>,
+    /// Topic of a publisher or subscriber, or name of a service.
+    pub topic: Option<String>,
+    pub callback: Option<String>,
+}
+
+impl ResolvedNames {
+    /// Resolve the names of the objects referenced by `event`.
+    ///
+    /// The names that are not known in `model` are left empty.
+    pub fn resolve(event: &Event, model: &Model) -> Self {
+        let mut names = Self::default();
+        match event {
+            Event::Ros2(event) => names.resolve_ros2(event, model),
+            Event::R2r(event) => names.resolve_r2r(event, model),
+        }
+        names
+    }
+
+    fn resolve_ros2(&mut self, event: &ros2::Event, model: &Model) {
+        match event {
+            ros2::Event::RclInit(_) => {}
+            ros2::Event::RclNodeInit(e) => self.set_node(e.node, model),
+            ros2::Event::RmwPublisherInit(ros2::RmwPublisherInit { publisher })
+            | ros2::Event::RclPublisherInit(ros2::RclPublisherInit { publisher }) => {
+                self.set_publisher(*publisher, model);
+            }
+            ros2::Event::RclcppPublish(ros2::RclcppPublish { message })
+            | ros2::Event::RclcppIntraPublish(ros2::RclcppIntraPublish { message })
+            | ros2::Event::RclPublish(ros2::RclPublish { message })
+            | ros2::Event::RmwPublish(ros2::RmwPublish { message }) => {
+                if let Some(publisher) = model[*message].get_publisher() {
+                    self.set_publisher(publisher, model);
+                }
+            }
+            ros2::Event::RmwSubscriptionInit(ros2::RmwSubscriptionInit { subscription })
+            | ros2::Event::RclSubscriptionInit(ros2::RclSubscriptionInit { subscription })
+            | ros2::Event::RclcppSubscriptionInit(ros2::RclcppSubscriptionInit { subscription }) => {
+                self.set_subscriber(*subscription, model);
+            }
+            ros2::Event::RmwTake(ros2::RmwTake { message, .. })
+            | ros2::Event::RclTake(ros2::RclTake { message, .. })
+            | ros2::Event::RclCppTake(ros2::RclCppTake { message, .. }) => {
+                if let Some(subscriber) = model[*message].get_subscriber() {
+                    self.set_subscriber(subscriber, model);
+                }
+            }
+            ros2::Event::RclServiceInit(e) => {
+                let service = &model[e.service];
+                self.topic = Option::from(service.get_name()).map(ToOwned::to_owned);
+                self.set_node_if_known(service.get_node().into(), model);
+            }
+            ros2::Event::RclClientInit(e) => {
+                let client = &model[e.client];
+                self.topic = Option::from(client.get_service_name()).map(ToOwned::to_owned);
+                self.set_node_if_known(client.get_node().into(), model);
+            }
+            ros2::Event::RclTimerInit(ros2::RclTimerInit { timer })
+            | ros2::Event::RclcppTimerLinkNode(ros2::RclcppTimerLinkNode { timer }) => {
+                self.set_node_if_known(model[*timer].get_node().into(), model);
+            }
+            ros2::Event::RclcppSubscriptionCallbackAdded(
+                ros2::RclcppSubscriptionCallbackAdded { callback },
+            )
+            | ros2::Event::RclCppServiceCallbackAdded(ros2::RclCppServiceCallbackAdded {
+                callback,
+            })
+            | ros2::Event::RclcppTimerCallbackAdded(ros2::RclcppTimerCallbackAdded { callback })
+            | ros2::Event::RclcppCallbackRegister(ros2::RclcppCallbackRegister { callback }) => {
+                self.set_callback(*callback, model);
+            }
+            ros2::Event::CallbackStart(ros2::CallbackStart { callback, .. })
+            | ros2::Event::CallbackEnd(ros2::CallbackEnd { callback }) => {
+                let callback = model[*callback].get_callback();
+                self.set_callback(callback, model);
+            }
+        }
+    }
+
+    fn resolve_r2r(&mut self, event: &r2r::Event, model: &Model) {
+        match event {
+            r2r::Event::SpinStart(r2r::SpinStart { node, .. })
+            | r2r::Event::SpinEnd(r2r::SpinEnd { node, .. })
+            | r2r::Event::SpinWake(r2r::SpinWake { node, .. })
+            | r2r::Event::SpinTimeout(r2r::SpinTimeout { node, .. }) => self.set_node(*node, model),
+            r2r::Event::UpdateTime(e) => self.set_subscriber(e.subscriber, model),
+        }
+    }
+
+    fn set_node(&mut self, node: Idx<Node>, model: &Model) {
+        self.node = Option::from(model[node].get_full_name()).map(ToOwned::to_owned);
+    }
+
+    fn set_node_if_known(&mut self, node: Option<Idx<Node>>, model: &Model) {
+        if let Some(node) = node {
+            self.set_node(node, model);
+        }
+    }
+
+    fn set_publisher(&mut self, publisher: Idx<Publisher>, model: &Model) {
+        let publisher = &model[publisher];
+        self.topic = Option::from(publisher.get_topic()).map(ToOwned::to_owned);
+        self.set_node_if_known(publisher.get_node().into(), model);
+    }
+
+    fn set_subscriber(&mut self, subscriber: Idx<Subscriber>, model: &Model) {
+        let subscriber = &model[subscriber];
+        self.topic = Option::from(subscriber.get_topic()).map(ToOwned::to_owned);
+        self.set_node_if_known(subscriber.get_node().into(), model);
+        if let Some(callback) = subscriber.get_callback().into() {
+            self.callback = model[callback].get_name().map(ToOwned::to_owned);
+        }
+    }
+
+    fn set_callback(&mut self, callback: Idx<Callback>, model: &Model) {
+        let callback = &model[callback];
+        self.callback = callback.get_name().map(ToOwned::to_owned);
+        self.set_node_if_known(callback.get_node(model), model);
+        self.topic = match callback.get_caller() {
+            Some(CallbackCaller::Subscription(subscriber)) => {
+                Option::from(model[*subscriber].get_topic()).map(ToOwned::to_owned)
+            }
+            Some(CallbackCaller::Service(service)) => {
+                Option::from(model[*service].get_name()).map(ToOwned::to_owned)
+            }
+            Some(CallbackCaller::Timer(_)) | None => None,
+        };
+    }
+}
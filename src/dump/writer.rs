@@ -0,0 +1,400 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::builder::{StringBuilder, TimestampNanosecondBuilder, UInt32Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use color_eyre::eyre::{Context, Result};
+use parquet::arrow::ArrowWriter;
+use serde::Serialize;
+
+use crate::argsv2::dump_args::DumpFormat;
+use crate::events_common::Context as EventContext;
+
+use super::names::ResolvedNames;
+
+/// A single dumped event.
+#[derive(Debug, Serialize)]
+pub struct DumpRecord<'a> {
+    /// Nanoseconds since the UNIX epoch.
+    pub time: i64,
+    pub event: &'a str,
+    #[serde(flatten)]
+    pub context: &'a EventContext,
+    pub payload: serde_json::Value,
+    /// Only present for processed events.
+    #[serde(flatten)]
+    pub names: Option<ResolvedNames>,
+}
+
+const CSV_HEADER: [&str; 8] = [
+    "time", "event", "cpu_id", "vpid", "vtid", "procname", "hostname", "payload",
+];
+const CSV_NAMES_HEADER: [&str; 3] = ["node", "topic", "callback"];
+
+/// Number of events buffered before they are written as one Parquet record batch.
+const PARQUET_BATCH_SIZE: usize = 8192;
+
+pub enum DumpWriter {
+    Jsonl(Box<dyn Write + Send>),
+    Csv {
+        writer: Box<csv::Writer<Box<dyn Write + Send>>>,
+        with_names: bool,
+    },
+    Parquet(Box<ParquetDump>),
+}
+
+/// Columns of the events not yet written to the Parquet file.
+pub struct ParquetDump {
+    writer: ArrowWriter<Box<dyn Write + Send>>,
+    schema: SchemaRef,
+    time: TimestampNanosecondBuilder,
+    event: StringBuilder,
+    cpu_id: UInt32Builder,
+    vpid: UInt32Builder,
+    vtid: UInt32Builder,
+    procname: StringBuilder,
+    hostname: StringBuilder,
+    payload: StringBuilder,
+    /// Node, topic and callback columns, only present for processed events.
+    names: Option<[StringBuilder; 3]>,
+    rows: usize,
+}
+
+impl ParquetDump {
+    fn new(output: Box<dyn Write + Send>, with_names: bool) -> Result<Self> {
+        let mut fields = vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new("event", DataType::Utf8, false),
+            Field::new("cpu_id", DataType::UInt32, false),
+            Field::new("vpid", DataType::UInt32, false),
+            Field::new("vtid", DataType::UInt32, false),
+            Field::new("procname", DataType::Utf8, false),
+            Field::new("hostname", DataType::Utf8, false),
+            Field::new("payload", DataType::Utf8, false),
+        ];
+        if with_names {
+            fields.extend(CSV_NAMES_HEADER.map(|name| Field::new(name, DataType::Utf8, true)));
+        }
+        let schema = Arc::new(Schema::new(fields));
+        let writer = ArrowWriter::try_new(output, schema.clone(), None)?;
+
+        Ok(Self {
+            writer,
+            schema,
+            time: TimestampNanosecondBuilder::new().with_timezone("UTC"),
+            event: StringBuilder::new(),
+            cpu_id: UInt32Builder::new(),
+            vpid: UInt32Builder::new(),
+            vtid: UInt32Builder::new(),
+            procname: StringBuilder::new(),
+            hostname: StringBuilder::new(),
+            payload: StringBuilder::new(),
+            names: with_names.then(|| std::array::from_fn(|_| StringBuilder::new())),
+            rows: 0,
+        })
+    }
+
+    fn append(&mut self, record: &DumpRecord) -> Result<()> {
+        let context = record.context;
+        self.time.append_value(record.time);
+        self.event.append_value(record.event);
+        self.cpu_id.append_value(context.cpu_id());
+        self.vpid.append_value(context.vpid());
+        self.vtid.append_value(context.vtid());
+        self.procname.append_value(context.procname());
+        self.hostname.append_value(context.hostname());
+        self.payload.append_value(record.payload.to_string());
+        if let Some(columns) = &mut self.names {
+            let names = record.names.clone().unwrap_or_default();
+            for (column, name) in columns
+                .iter_mut()
+                .zip([names.node, names.topic, names.callback])
+            {
+                column.append_option(name);
+            }
+        }
+
+        self.rows += 1;
+        if self.rows == PARQUET_BATCH_SIZE {
+            self.flush_batch()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the buffered events as one record batch.
+    fn flush_batch(&mut self) -> Result<()> {
+        if self.rows == 0 {
+            return Ok(());
+        }
+
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(self.time.finish()),
+            Arc::new(self.event.finish()),
+            Arc::new(self.cpu_id.finish()),
+            Arc::new(self.vpid.finish()),
+            Arc::new(self.vtid.finish()),
+            Arc::new(self.procname.finish()),
+            Arc::new(self.hostname.finish()),
+            Arc::new(self.payload.finish()),
+        ];
+        if let Some(names) = &mut self.names {
+            columns.extend(
+                names
+                    .iter_mut()
+                    .map(|column| Arc::new(column.finish()) as ArrayRef),
+            );
+        }
+
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.writer.write(&batch)?;
+        self.rows = 0;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.flush_batch()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+impl DumpWriter {
+    /// Create a writer of the `format`.
+    ///
+    /// The CSV header and the Parquet schema contain the name columns if `with_names` is set.
+    pub fn new(
+        output: Box<dyn Write + Send>,
+        format: DumpFormat,
+        with_names: bool,
+    ) -> Result<Self> {
+        Ok(match format {
+            DumpFormat::Jsonl => Self::Jsonl(output),
+            DumpFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(output);
+                if with_names {
+                    writer.write_record(CSV_HEADER.iter().chain(&CSV_NAMES_HEADER))?;
+                } else {
+                    writer.write_record(CSV_HEADER)?;
+                }
+                Self::Csv {
+                    writer: Box::new(writer),
+                    with_names,
+                }
+            }
+            DumpFormat::Parquet => Self::Parquet(Box::new(ParquetDump::new(output, with_names)?)),
+        })
+    }
+
+    pub fn write(&mut self, record: &DumpRecord) -> Result<()> {
+        match self {
+            Self::Jsonl(output) => {
+                serde_json::to_writer(&mut *output, record)?;
+                writeln!(output)?;
+            }
+            Self::Csv { writer, with_names } => {
+                let context = record.context;
+                let mut fields = vec![
+                    record.time.to_string(),
+                    record.event.to_owned(),
+                    context.cpu_id().to_string(),
+                    context.vpid().to_string(),
+                    context.vtid().to_string(),
+                    context.procname().to_owned(),
+                    context.hostname().to_owned(),
+                    record.payload.to_string(),
+                ];
+                if *with_names {
+                    let names = record.names.clone().unwrap_or_default();
+                    fields.extend(
+                        [names.node, names.topic, names.callback].map(Option::unwrap_or_default),
+                    );
+                }
+                writer.write_record(&fields)?;
+            }
+            Self::Parquet(dump) => dump.append(record)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Self::Jsonl(mut output) => output.flush().map_err(Into::into),
+            Self::Csv { mut writer, .. } => writer.flush().map_err(Into::into),
+            Self::Parquet(dump) => dump.finish(),
+        }
+        .wrap_err("Failed to flush the dumped events")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{TimestampNanosecondType, UInt32Type};
+    use arrow_array::{Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+
+    /// Output shared with the test after the writer is dropped.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn dump(format: DumpFormat, names: Option<ResolvedNames>) -> String {
+        String::from_utf8(dump_bytes(format, names)).unwrap()
+    }
+
+    fn dump_bytes(format: DumpFormat, names: Option<ResolvedNames>) -> Vec<u8> {
+        let output = SharedOutput::default();
+        let context = EventContext::new(1, 100, 101, "talker".into(), "host".into());
+        let record = DumpRecord {
+            time: 42,
+            event: "ros2:rcl_publish",
+            context: &context,
+            payload: serde_json::json!({"message": 7}),
+            names: names.clone(),
+        };
+
+        let mut writer =
+            DumpWriter::new(Box::new(output.clone()), format, names.is_some()).unwrap();
+        writer.write(&record).unwrap();
+        writer.finish().unwrap();
+
+        output.0.lock().unwrap().clone()
+    }
+
+    /// Read the only record batch of a dumped Parquet file.
+    fn read_parquet(data: Vec<u8>) -> RecordBatch {
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(data))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert!(reader.next().is_none());
+        batch
+    }
+
+    fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> &'a StringArray {
+        batch.column_by_name(name).unwrap().as_string::<i32>()
+    }
+
+    #[test]
+    fn test_jsonl() {
+        assert_eq!(
+            dump(DumpFormat::Jsonl, None),
+            concat!(
+                r#"{"time":42,"event":"ros2:rcl_publish","cpu_id":1,"vpid":100,"vtid":101,"#,
+                r#""procname":"talker","hostname":"host","payload":{"message":7}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_jsonl_names() {
+        let names = ResolvedNames {
+            node: Some("/talker".into()),
+            topic: Some("/chatter".into()),
+            callback: None,
+        };
+        let line = dump(DumpFormat::Jsonl, Some(names));
+        assert!(line.ends_with("\"node\":\"/talker\",\"topic\":\"/chatter\",\"callback\":null}\n"));
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            dump(DumpFormat::Csv, None),
+            concat!(
+                "time,event,cpu_id,vpid,vtid,procname,hostname,payload\n",
+                "42,ros2:rcl_publish,1,100,101,talker,host,\"{\"\"message\"\":7}\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_csv_names() {
+        let names = ResolvedNames {
+            node: Some("/talker".into()),
+            ..Default::default()
+        };
+        let csv = dump(DumpFormat::Csv, Some(names));
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("time,event,cpu_id,vpid,vtid,procname,hostname,payload,node,topic,callback")
+        );
+        assert!(lines.next().unwrap().ends_with(",/talker,,"));
+    }
+
+    #[test]
+    fn test_parquet() {
+        let batch = read_parquet(dump_bytes(DumpFormat::Parquet, None));
+
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>(),
+            CSV_HEADER
+        );
+        let time = batch
+            .column_by_name("time")
+            .unwrap()
+            .as_primitive::<TimestampNanosecondType>();
+        assert_eq!(time.value(0), 42);
+        assert_eq!(string_column(&batch, "event").value(0), "ros2:rcl_publish");
+        for (name, value) in [("cpu_id", 1), ("vpid", 100), ("vtid", 101)] {
+            let column = batch
+                .column_by_name(name)
+                .unwrap()
+                .as_primitive::<UInt32Type>();
+            assert_eq!(column.value(0), value);
+        }
+        assert_eq!(string_column(&batch, "procname").value(0), "talker");
+        assert_eq!(string_column(&batch, "hostname").value(0), "host");
+        assert_eq!(
+            string_column(&batch, "payload").value(0),
+            r#"{"message":7}"#
+        );
+    }
+
+    #[test]
+    fn test_parquet_names() {
+        let names = ResolvedNames {
+            node: Some("/talker".into()),
+            ..Default::default()
+        };
+        let batch = read_parquet(dump_bytes(DumpFormat::Parquet, Some(names)));
+
+        assert_eq!(
+            batch.num_columns(),
+            CSV_HEADER.len() + CSV_NAMES_HEADER.len()
+        );
+        assert_eq!(string_column(&batch, "node").value(0), "/talker");
+        assert!(string_column(&batch, "topic").is_null(0));
+        assert!(string_column(&batch, "callback").is_null(0));
+    }
+}
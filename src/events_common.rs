@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 // Reexport the time struct from model.rs
 pub use crate::model::Time;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Context {
     cpu_id: u32,
     vpid: u32,
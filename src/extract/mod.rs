@@ -6,21 +6,26 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::analyses::analysis::cpu_affinity::{
+    AffinityViolationExport, CallbackAffinityExport, ThreadAffinityExport,
+};
 use crate::analyses::analysis::dependency_graph::{
     ActivationDelayExport, CallbackDurationExport, MessageLatencyExport, MessagesDelayExport,
     NodeOverviewExport, PublicationDelayExport,
 };
+use crate::analyses::analysis::executor_starvation::ExecutorStarvationExport;
+use crate::analyses::analysis::topic_rate::TopicRateExport;
 use crate::argsv2::extract_args::AnalysisProperty;
 use crate::utils::binary_sql_store::{BinarySQLStoreError, BinarySqlStore};
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Display, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Debug)]
 #[display("{node}::{interface}")]
 pub struct RosInterfaceCompleteName {
     pub interface: String,
     pub node: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Display, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Debug)]
 #[display("{source_node}-({topic})>{destination_node}")]
 pub struct RosChannelCompleteName {
     pub source_node: String,
@@ -28,6 +33,14 @@ pub struct RosChannelCompleteName {
     pub topic: String,
 }
 
+/// Results of the CPU affinity analysis stored in the binary bundle.
+#[derive(Serialize, Debug)]
+pub struct CpuAffinityData {
+    pub callbacks: Vec<CallbackAffinityExport>,
+    pub threads: Vec<ThreadAffinityExport>,
+    pub violations: Vec<AffinityViolationExport>,
+}
+
 pub enum PlottableData {
     I64(Vec<i64>),
 }
@@ -55,6 +68,61 @@ pub fn extract_graph(input: &Path) -> color_eyre::eyre::Result<String> {
     Ok(store.get_dependency_graph()?.graph)
 }
 
+/// Extract the topic rate statistics of an element, or of all elements if `element_id` is `None`.
+pub fn extract_topic_rates(
+    input: &Path,
+    element_id: Option<i64>,
+) -> color_eyre::eyre::Result<Vec<TopicRateExport>> {
+    let store = BinarySqlStore::open(input)?;
+
+    let Some(element_id) = element_id else {
+        return Ok(store.get_all::<TopicRateExport>()?);
+    };
+
+    let element_id = element_id as usize;
+    let rate = store
+        .get_by_id::<TopicRateExport>(element_id)
+        .map_err(|e| match e {
+            BinarySQLStoreError::NoResults => DataExtractionError::NoSuchElement(element_id),
+            _ => e.into(),
+        })?;
+
+    Ok(vec![rate])
+}
+
+/// Extract the starvation of a blocked callback, or of all callbacks if `element_id` is `None`.
+pub fn extract_executor_starvation(
+    input: &Path,
+    element_id: Option<i64>,
+) -> color_eyre::eyre::Result<Vec<ExecutorStarvationExport>> {
+    let store = BinarySqlStore::open(input)?;
+
+    let Some(element_id) = element_id else {
+        return Ok(store.get_all::<ExecutorStarvationExport>()?);
+    };
+
+    let element_id = element_id as usize;
+    let starvation = store
+        .get_by_id::<ExecutorStarvationExport>(element_id)
+        .map_err(|e| match e {
+            BinarySQLStoreError::NoResults => DataExtractionError::NoSuchElement(element_id),
+            _ => e.into(),
+        })?;
+
+    Ok(vec![starvation])
+}
+
+/// Extract the CPUs and migrations of callbacks and threads and the affinity violations.
+pub fn extract_cpu_affinity(input: &Path) -> color_eyre::eyre::Result<CpuAffinityData> {
+    let store = BinarySqlStore::open(input)?;
+
+    Ok(CpuAffinityData {
+        callbacks: store.get_all()?,
+        threads: store.get_all()?,
+        violations: store.get_all()?,
+    })
+}
+
 pub fn extract_property(
     input: &Path,
     element_id: i64,
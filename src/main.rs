@@ -2,6 +2,10 @@
 
 mod analyses;
 mod argsv2;
+mod check;
+mod compare;
+mod doctor;
+mod dump;
 mod events_common;
 mod extract;
 mod model;
@@ -15,11 +19,17 @@ mod visualization;
 
 use std::ffi::CString;
 use std::io::Write;
+use std::time::Duration;
 
 use argsv2::Args;
 use argsv2::helpers::prepare_trace_paths;
+use color_eyre::eyre::Context;
 
+use crate::analyses::event_iterator::AnalyzeOptions;
 use crate::argsv2::analysis_args::AnalysisArgs;
+use crate::argsv2::check_args::CheckArgs;
+use crate::argsv2::compare_args::CompareArgs;
+use crate::argsv2::dump_args::DumpArgs;
 use crate::argsv2::extract_args::ExtractArgs;
 use crate::argsv2::plot_args::{PlotArgs, PlotOutputFormat};
 use crate::argsv2::viewer_args::ViewerArgs;
@@ -30,14 +40,40 @@ fn run_analysis<L: clap_verbosity_flag::LogLevel>(
     args: &AnalysisArgs,
     verbose: &clap_verbosity_flag::Verbosity<L>,
 ) -> color_eyre::eyre::Result<()> {
-    let trace_paths = prepare_trace_paths()?;
-    let trace_paths_cstr: Vec<_> = trace_paths.iter().map(CString::as_c_str).collect();
-
     let mut analyses = analyses::Analyses::default();
 
-    analyses.add_analyses_from_args(args);
+    analyses.add_analyses_from_args(args)?;
+
+    doctor::check_plugins(args.live_url().is_some())?;
+
+    let trace_paths = if args.live_url().is_none() {
+        prepare_trace_paths(args.trace_paths(), args.is_exact_path())?
+    } else {
+        Vec::new()
+    };
+    let trace_paths_cstr: Vec<_> = trace_paths.iter().map(CString::as_c_str).collect();
+
+    let options = AnalyzeOptions {
+        stats_interval: None,
+        retention: processor::MessageRetention::new(args.match_horizon(), args.max_memory()),
+        cache_dir: args.cache_dir(),
+        progress: args.progress(),
+        graph: args.graph_config(&trace_paths_cstr)?,
+    };
 
-    analyses.analyze_trace(trace_paths_cstr, verbose)?;
+    if let Some(url) = args.live_url() {
+        let url = CString::new(url).wrap_err("Invalid live session URL")?;
+        let interval = u64::try_from(args.stats_interval().as_nanos())
+            .map(Duration::from_nanos)
+            .wrap_err("The stats interval must not be negative")?;
+        let options = AnalyzeOptions {
+            stats_interval: Some(interval),
+            ..options
+        };
+        analyses.analyze_live(&url, options, verbose)?;
+    } else {
+        analyses.analyze_trace(trace_paths_cstr, options, verbose)?;
+    }
 
     analyses.save_output(args)?;
 
@@ -97,11 +133,55 @@ fn run_extract(args: &ExtractArgs) -> color_eyre::eyre::Result<()> {
 
             data.export(&mut output)?;
         }
+        argsv2::extract_args::ExtractContentArgs::TopicRate(args) => {
+            let rates = extract::extract_topic_rates(&source_file, args.element_id())?;
+
+            writeln!(output, "{}", serde_json::to_string(&rates)?)?;
+        }
+        argsv2::extract_args::ExtractContentArgs::ExecutorStarvation(args) => {
+            let starvation = extract::extract_executor_starvation(&source_file, args.element_id())?;
+
+            writeln!(output, "{}", serde_json::to_string(&starvation)?)?;
+        }
+        argsv2::extract_args::ExtractContentArgs::CpuAffinity => {
+            let affinity = extract::extract_cpu_affinity(&source_file)?;
+
+            writeln!(output, "{}", serde_json::to_string(&affinity)?)?;
+        }
     }
 
     Ok(())
 }
 
+fn run_check<L: clap_verbosity_flag::LogLevel>(
+    args: &CheckArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<()> {
+    doctor::check_plugins(false)?;
+    let verdict = check::run_check(args, verbose)?;
+
+    if !verdict.passed {
+        std::process::exit(check::VIOLATION_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+fn run_compare<L: clap_verbosity_flag::LogLevel>(
+    args: &CompareArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<()> {
+    compare::run_compare(args, verbose)
+}
+
+fn run_dump<L: clap_verbosity_flag::LogLevel>(
+    args: &DumpArgs,
+    verbose: &clap_verbosity_flag::Verbosity<L>,
+) -> color_eyre::eyre::Result<()> {
+    doctor::check_plugins(false)?;
+    dump::run_dump(args, verbose)
+}
+
 fn main() -> color_eyre::eyre::Result<()> {
     color_eyre::install()?;
 
@@ -116,5 +196,9 @@ fn main() -> color_eyre::eyre::Result<()> {
         argsv2::TracerCommand::Plot(plot_args) => run_plotting(plot_args),
         argsv2::TracerCommand::Viewer(viewer_args) => run_viewer(viewer_args),
         argsv2::TracerCommand::Extract(extract_args) => run_extract(extract_args),
+        argsv2::TracerCommand::Check(check_args) => run_check(check_args, &args.verbose),
+        argsv2::TracerCommand::Compare(compare_args) => run_compare(compare_args, &args.verbose),
+        argsv2::TracerCommand::Dump(dump_args) => run_dump(dump_args, &args.verbose),
+        argsv2::TracerCommand::Doctor(doctor_args) => doctor::run_doctor(doctor_args),
     }
 }
@@ -0,0 +1,308 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Callback, CallbackInstance, Client, Node, PublicationMessage, Publisher, Service, SpinInstance,
+    Subscriber, SubscriptionMessage, Timer,
+};
+
+/// Typed index of an object stored in an [`Arena`].
+///
+/// The generation distinguishes the objects that reused the slot of a released object.
+pub struct Idx<T> {
+    index: u32,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Idx<T> {
+    const fn new(index: usize, generation: u32) -> Self {
+        assert!(index <= u32::MAX as usize, "Arena index overflow");
+        #[allow(clippy::cast_possible_truncation)]
+        Self {
+            index: index as u32,
+            generation,
+            _marker: PhantomData,
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Clone for Idx<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Idx<T> {}
+
+impl<T> PartialEq for Idx<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Idx<T> {}
+
+impl<T> PartialOrd for Idx<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Idx<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.index, self.generation).cmp(&(other.index, other.generation))
+    }
+}
+
+impl<T> Hash for Idx<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> Debug for Idx<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let type_name = std::any::type_name::<T>();
+        let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+        write!(f, "{type_name}#{}", self.index)?;
+        if self.generation > 0 {
+            write!(f, "@{}", self.generation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Only the index is serialized, the objects that can be released are never serialized.
+impl<T> Serialize for Idx<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.index.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Idx<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(|index| Self {
+            index,
+            generation: 0,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    item: Option<T>,
+}
+
+/// Storage of objects addressed by [`Idx`].
+///
+/// Objects of the ROS application are never removed, so their index stays valid for the lifetime
+/// of the arena. Short-lived objects (messages, callback and spin instances) are released once
+/// they can no longer be referenced by the next events. Their slot is then reused by a later
+/// object with a new generation, so a stale index is detected instead of addressing the new object.
+#[derive(Debug)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn push(&mut self, item: T) -> Idx<T> {
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            debug_assert!(slot.item.is_none());
+            slot.item = Some(item);
+            return Idx::new(index as usize, slot.generation);
+        }
+
+        let idx = Idx::new(self.slots.len(), 0);
+        self.slots.push(Slot {
+            generation: 0,
+            item: Some(item),
+        });
+        idx
+    }
+
+    pub fn get(&self, idx: Idx<T>) -> Option<&T> {
+        self.slots
+            .get(idx.index())
+            .filter(|slot| slot.generation == idx.generation)
+            .and_then(|slot| slot.item.as_ref())
+    }
+
+    pub fn get_mut(&mut self, idx: Idx<T>) -> Option<&mut T> {
+        self.slots
+            .get_mut(idx.index())
+            .filter(|slot| slot.generation == idx.generation)
+            .and_then(|slot| slot.item.as_mut())
+    }
+
+    /// Remove the object and free its slot. Returns `None` if it was already released.
+    pub fn remove(&mut self, idx: Idx<T>) -> Option<T> {
+        let slot = self
+            .slots
+            .get_mut(idx.index())
+            .filter(|slot| slot.generation == idx.generation)?;
+        let item = slot.item.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(idx.index);
+        Some(item)
+    }
+}
+
+impl<T> Index<Idx<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, idx: Idx<T>) -> &Self::Output {
+        self.get(idx)
+            .unwrap_or_else(|| panic!("{idx:?} was already released"))
+    }
+}
+
+impl<T> IndexMut<Idx<T>> for Arena<T> {
+    fn index_mut(&mut self, idx: Idx<T>) -> &mut Self::Output {
+        self.get_mut(idx)
+            .unwrap_or_else(|| panic!("{idx:?} was already released"))
+    }
+}
+
+/// Objects of the ROS 2 application reconstructed from the trace.
+///
+/// The model is owned by the [`Processor`](crate::processor::Processor), which is the only one
+/// modifying it. Analyses receive it as a read-only context and address the objects by [`Idx`].
+#[derive(Debug, Default)]
+pub struct Model {
+    nodes: Arena<Node>,
+    subscribers: Arena<Subscriber>,
+    publishers: Arena<Publisher>,
+    services: Arena<Service>,
+    clients: Arena<Client>,
+    timers: Arena<Timer>,
+    callbacks: Arena<Callback>,
+
+    publication_messages: Arena<PublicationMessage>,
+    subscription_messages: Arena<SubscriptionMessage>,
+    callback_instances: Arena<CallbackInstance>,
+    spin_instances: Arena<SpinInstance>,
+}
+
+/// Object stored in its own arena of the [`Model`].
+pub trait ModelObject: Sized {
+    fn arena(model: &Model) -> &Arena<Self>;
+
+    fn arena_mut(model: &mut Model) -> &mut Arena<Self>;
+}
+
+macro_rules! impl_model_object {
+    ($($object:ident => $arena:ident),* $(,)?) => {
+        $(
+            impl ModelObject for $object {
+                fn arena(model: &Model) -> &Arena<Self> {
+                    &model.$arena
+                }
+
+                fn arena_mut(model: &mut Model) -> &mut Arena<Self> {
+                    &mut model.$arena
+                }
+            }
+        )*
+    };
+}
+
+impl_model_object!(
+    Node => nodes,
+    Subscriber => subscribers,
+    Publisher => publishers,
+    Service => services,
+    Client => clients,
+    Timer => timers,
+    Callback => callbacks,
+    PublicationMessage => publication_messages,
+    SubscriptionMessage => subscription_messages,
+    CallbackInstance => callback_instances,
+    SpinInstance => spin_instances,
+);
+
+impl Model {
+    pub fn add<T: ModelObject>(&mut self, object: T) -> Idx<T> {
+        T::arena_mut(self).push(object)
+    }
+
+    /// Get the object if it was not released.
+    pub fn get<T: ModelObject>(&self, idx: Idx<T>) -> Option<&T> {
+        T::arena(self).get(idx)
+    }
+
+    pub fn get_mut<T: ModelObject>(&mut self, idx: Idx<T>) -> Option<&mut T> {
+        T::arena_mut(self).get_mut(idx)
+    }
+
+    /// Remove a short-lived object from the model. Its index must not be used afterwards.
+    pub(crate) fn release<T: ModelObject>(&mut self, idx: Idx<T>) -> Option<T> {
+        T::arena_mut(self).remove(idx)
+    }
+}
+
+impl<T: ModelObject> Index<Idx<T>> for Model {
+    type Output = T;
+
+    fn index(&self, idx: Idx<T>) -> &Self::Output {
+        &T::arena(self)[idx]
+    }
+}
+
+impl<T: ModelObject> IndexMut<Idx<T>> for Model {
+    fn index_mut(&mut self, idx: Idx<T>) -> &mut Self::Output {
+        &mut T::arena_mut(self)[idx]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arena_indices_are_stable() {
+        let mut model = Model::default();
+        let first = model.add(Node::new(1));
+        let second = model.add(Node::new(2));
+
+        assert_ne!(first, second);
+        assert_eq!(model[first].get_rcl_handle(), 1);
+        assert_eq!(model[second].get_rcl_handle(), 2);
+    }
+
+    #[test]
+    fn test_released_slot_is_reused_with_new_generation() {
+        let mut model = Model::default();
+        let first = model.add(PublicationMessage::new(1));
+        assert!(model.release(first).is_some());
+        assert!(model.release(first).is_none());
+
+        let second = model.add(PublicationMessage::new(1));
+        assert_eq!(first.index(), second.index());
+        assert_ne!(first, second);
+        assert!(model.get(first).is_none());
+        assert!(model.get(second).is_some());
+    }
+}
@@ -1,17 +1,69 @@
-use std::string::ToString;
-use std::sync::{Mutex, Weak};
-
-use crate::utils::{
-    DisplayArcMutex, DisplayArcWeakMutex, DisplayDebug, DisplayDuration, DisplayLargeDuration,
-    Known, WeakKnown,
-};
+use crate::utils::{DisplayDebug, DisplayDuration, DisplayLargeDuration, Known, WeakKnown};
 
 use super::{
-    Callback, CallbackCaller, CallbackInstance, CallbackType, Client, DdsGid, Gid, Name, Node,
-    PartiallyKnown, PublicationMessage, Publisher, RmwGid, Service, SpinInstance, Subscriber,
-    SubscriptionMessage, Time, Timer,
+    Callback, CallbackCaller, CallbackInstance, CallbackType, Client, DdsGid, Gid, Idx, Model,
+    ModelObject, Name, Node, PartiallyKnown, PublicationMessage, Publisher, RmwGid, Service,
+    SpinInstance, Subscriber, SubscriptionMessage, Time, Timer,
 };
 
+/// Formatting of objects that reference other objects of the [`Model`].
+pub trait DisplayWithModel {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+}
+
+/// [`Display`](std::fmt::Display) adapter for [`DisplayWithModel`] objects.
+pub struct WithModel<'a, T: ?Sized> {
+    model: &'a Model,
+    value: &'a T,
+}
+
+impl<T: DisplayWithModel + ?Sized> std::fmt::Display for WithModel<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt_with_model(self.model, f)
+    }
+}
+
+impl Model {
+    pub fn display<'a, T: DisplayWithModel + ?Sized>(&'a self, value: &'a T) -> WithModel<'a, T> {
+        WithModel { model: self, value }
+    }
+}
+
+impl<T: ModelObject + DisplayWithModel> DisplayWithModel for Idx<T> {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match model.get(*self) {
+            Some(object) => object.fmt_with_model(model, f),
+            None => write!(f, "{self:?}(released)"),
+        }
+    }
+}
+
+/// Display a referenced object in its alternate form or as `...` if `skip` is set.
+struct DisplayReferenced<'a, T> {
+    model: &'a Model,
+    idx: Idx<T>,
+    skip: bool,
+}
+
+impl<'a, T> DisplayReferenced<'a, T> {
+    const fn new(model: &'a Model, idx: Idx<T>, skip: bool) -> Self {
+        Self { model, idx, skip }
+    }
+}
+
+impl<T: ModelObject + DisplayWithModel> std::fmt::Display for DisplayReferenced<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.skip {
+            return write!(f, "...");
+        }
+        write!(f, "{:#}", self.model.display(&self.idx))
+    }
+}
+
+pub fn get_node_name(model: &Model, node: Idx<Node>) -> WeakKnown<String> {
+    model[node].get_full_name().map(ToString::to_string).into()
+}
+
 impl std::fmt::Debug for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Time").field(&self.as_datetime()).finish()
@@ -56,20 +108,17 @@ impl std::fmt::Display for Node {
     }
 }
 
-pub fn get_node_name_from_weak(node_weak: &Weak<Mutex<Node>>) -> WeakKnown<String> {
-    let Some(node) = node_weak.upgrade() else {
-        return WeakKnown::Dropped;
-    };
-    let node = node.lock().unwrap();
-    node.get_full_name().map(ToString::to_string).into()
+impl DisplayWithModel for Node {
+    fn fmt_with_model(&self, _model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
 }
 
-impl std::fmt::Display for Publisher {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Publisher {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let node = self
             .node
-            .as_ref()
-            .map(|node| DisplayArcWeakMutex::new(node, f.alternate()));
+            .map(|node| DisplayReferenced::new(model, node, f.alternate()));
 
         write!(
             f,
@@ -99,16 +148,14 @@ impl std::fmt::Display for DisplayPublisherWithoutNode<'_> {
     }
 }
 
-impl std::fmt::Display for Subscriber {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Subscriber {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let node = self
             .node
-            .as_ref()
-            .map(|node| DisplayArcWeakMutex::new(node, false));
+            .map(|node| DisplayReferenced::new(model, node, false));
         let callback = self
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, f.alternate()));
+            .map(|callback| DisplayReferenced::new(model, callback, f.alternate()));
 
         write!(
             f,
@@ -122,25 +169,14 @@ impl std::fmt::Display for Subscriber {
     }
 }
 
-pub fn get_subscriber_topic_from_weak(
-    subscriber_weak: &Weak<Mutex<Subscriber>>,
-) -> WeakKnown<String> {
-    let Some(node) = subscriber_weak.upgrade() else {
-        return WeakKnown::Dropped;
-    };
-    let subscriber = node.lock().unwrap();
-    subscriber.get_topic().map(ToString::to_string).into()
-}
-
 pub(super) struct DisplaySubscriberWithoutNode<'a>(pub &'a Subscriber);
 
-impl std::fmt::Display for DisplaySubscriberWithoutNode<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for DisplaySubscriberWithoutNode<'_> {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let callback = self
             .0
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, false));
+            .map(|callback| DisplayReferenced::new(model, callback, false));
 
         write!(
             f,
@@ -154,16 +190,14 @@ impl std::fmt::Display for DisplaySubscriberWithoutNode<'_> {
     }
 }
 
-impl std::fmt::Display for Service {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Service {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let node = self
             .node
-            .as_ref()
-            .map(|node| DisplayArcWeakMutex::new(node, false));
+            .map(|node| DisplayReferenced::new(model, node, false));
         let callback = self
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, f.alternate()));
+            .map(|callback| DisplayReferenced::new(model, callback, f.alternate()));
 
         write!(
             f,
@@ -176,23 +210,14 @@ impl std::fmt::Display for Service {
     }
 }
 
-pub fn get_service_name_from_weak(service_weak: &Weak<Mutex<Service>>) -> WeakKnown<String> {
-    let Some(service) = service_weak.upgrade() else {
-        return WeakKnown::Dropped;
-    };
-    let service = service.lock().unwrap();
-    service.get_name().map(ToString::to_string).into()
-}
-
 pub(super) struct DisplayServiceWithoutNode<'a>(pub &'a Service);
 
-impl std::fmt::Display for DisplayServiceWithoutNode<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for DisplayServiceWithoutNode<'_> {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let callback = self
             .0
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, false));
+            .map(|callback| DisplayReferenced::new(model, callback, false));
 
         write!(
             f,
@@ -205,12 +230,11 @@ impl std::fmt::Display for DisplayServiceWithoutNode<'_> {
     }
 }
 
-impl std::fmt::Display for Client {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Client {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let node = self
             .node
-            .as_ref()
-            .map(|node| DisplayArcWeakMutex::new(node, f.alternate()));
+            .map(|node| DisplayReferenced::new(model, node, f.alternate()));
 
         write!(
             f,
@@ -236,16 +260,14 @@ impl std::fmt::Display for DisplayClientWithoutNode<'_> {
     }
 }
 
-impl std::fmt::Display for Timer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Timer {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let node = self
             .node
-            .as_ref()
-            .map(|node| DisplayArcWeakMutex::new(node, false));
+            .map(|node| DisplayReferenced::new(model, node, false));
         let callback = self
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, f.alternate()));
+            .map(|callback| DisplayReferenced::new(model, callback, f.alternate()));
         let period = self.period.map(DisplayDuration);
 
         write!(
@@ -256,23 +278,14 @@ impl std::fmt::Display for Timer {
     }
 }
 
-pub fn get_timer_period_from_weak(timer_weak: &Weak<Mutex<Timer>>) -> WeakKnown<i64> {
-    let Some(timer) = timer_weak.upgrade() else {
-        return WeakKnown::Dropped;
-    };
-    let timer = timer.lock().unwrap();
-    timer.get_period().into()
-}
-
 pub(super) struct TimerDisplayWithoutNode<'a>(pub &'a Timer);
 
-impl std::fmt::Display for TimerDisplayWithoutNode<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for TimerDisplayWithoutNode<'_> {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let callback = self
             .0
             .callback
-            .as_ref()
-            .map(|callback| DisplayArcMutex::new(callback, false));
+            .map(|callback| DisplayReferenced::new(model, callback, false));
 
         let period = self.0.period.map(DisplayDuration);
 
@@ -284,8 +297,8 @@ impl std::fmt::Display for TimerDisplayWithoutNode<'_> {
     }
 }
 
-impl std::fmt::Display for Callback {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Callback {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if f.alternate() {
             write!(
                 f,
@@ -300,7 +313,7 @@ impl std::fmt::Display for Callback {
                         f,
                         "(handle={:x}, caller=Subscriber{}, name={})",
                         self.handle,
-                        DisplayArcWeakMutex::new(subscriber, false),
+                        DisplayReferenced::new(model, *subscriber, false),
                         self.name.as_ref().map(DisplayDebug)
                     )
                 }
@@ -309,7 +322,7 @@ impl std::fmt::Display for Callback {
                         f,
                         "(handle={:x}, caller=Service{}, name={})",
                         self.handle,
-                        DisplayArcWeakMutex::new(service, false),
+                        DisplayReferenced::new(model, *service, false),
                         self.name.as_ref().map(DisplayDebug)
                     )
                 }
@@ -318,7 +331,7 @@ impl std::fmt::Display for Callback {
                         f,
                         "(handle={:x}, caller=Timer{}, name={})",
                         self.handle,
-                        DisplayArcWeakMutex::new(timer, false),
+                        DisplayReferenced::new(model, *timer, false),
                         self.name.as_ref().map(DisplayDebug)
                     )
                 }
@@ -335,47 +348,36 @@ impl std::fmt::Display for Callback {
     }
 }
 
-pub struct DisplayCallbackSummary<'a>(pub &'a Callback);
+pub struct DisplayCallbackSummary<'a>(pub &'a Model, pub &'a Callback);
 
 impl std::fmt::Display for DisplayCallbackSummary<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let this = self.0;
+        let Self(model, this) = *self;
         let Some(caller) = this.get_caller() else {
             return write!(f, "(UNKNOWN)");
         };
-        let Some(node) = this.get_node() else {
+        let Some(node) = this.get_node(model) else {
             let typ = CallbackType::from(caller);
             return write!(f, "({typ:?})");
         };
 
-        let node_name = node.get_arc().map_or_else(
-            || Known::Known(DisplayDebug("DROPPED".to_owned())),
-            |node_arc| {
-                let node = node_arc.lock().unwrap();
-                node.get_full_name()
-                    .map(std::borrow::ToOwned::to_owned)
-                    .map(DisplayDebug)
-            },
-        );
-
-        match caller {
+        let node_name = model[node]
+            .get_full_name()
+            .map(std::borrow::ToOwned::to_owned)
+            .map(DisplayDebug);
+
+        match *caller {
             CallbackCaller::Subscription(sub) => {
-                let sub = sub.get_arc().unwrap();
-                let sub = sub.lock().unwrap();
-                let topic = sub.get_topic().map(DisplayDebug);
+                let topic = model[sub].get_topic().map(DisplayDebug);
                 write!(f, "(node={node_name}, Subscriber({topic}))")
             }
             CallbackCaller::Service(service) => {
-                let service = service.get_arc().unwrap();
-                let service = service.lock().unwrap();
-                let name = service.get_name().map(DisplayDebug);
+                let name = model[service].get_name().map(DisplayDebug);
 
                 write!(f, "(node={node_name}, Service({name}))")
             }
             CallbackCaller::Timer(timer) => {
-                let timer = timer.get_arc().unwrap();
-                let timer = timer.lock().unwrap();
-                let period = timer.get_period().map(DisplayDuration);
+                let period = model[timer].get_period().map(DisplayDuration);
 
                 write!(f, "(node={node_name}, Timer({period}))")
             }
@@ -383,24 +385,26 @@ impl std::fmt::Display for DisplayCallbackSummary<'_> {
     }
 }
 
-impl std::fmt::Display for CallbackCaller {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Subscription(subscriber) => DisplayArcWeakMutex::new(subscriber, false)
-                .map(|subscriber, f| {
-                    write!(
-                        f,
-                        "Subscriber({})",
-                        subscriber.get_topic().map(DisplayDebug)
-                    )
-                })
-                .fmt(f),
-            Self::Service(service) => DisplayArcWeakMutex::new(service, false)
-                .map(|service, f| write!(f, "Service({})", service.get_name().map(DisplayDebug)))
-                .fmt(f),
-            Self::Timer(timer) => DisplayArcWeakMutex::new(timer, false)
-                .map(|timer, f| write!(f, "Timer({})", timer.get_period().map(DisplayDuration)))
-                .fmt(f),
+impl DisplayWithModel for CallbackCaller {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Subscription(subscriber) => write!(
+                f,
+                "Subscriber({})",
+                model[subscriber].get_topic().map(DisplayDebug)
+            ),
+            Self::Service(service) => {
+                write!(
+                    f,
+                    "Service({})",
+                    model[service].get_name().map(DisplayDebug)
+                )
+            }
+            Self::Timer(timer) => write!(
+                f,
+                "Timer({})",
+                model[timer].get_period().map(DisplayDuration)
+            ),
         }
     }
 }
@@ -473,12 +477,11 @@ impl std::fmt::Debug for Gid {
     }
 }
 
-impl std::fmt::Display for PublicationMessage {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for PublicationMessage {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let publisher = self
             .publisher
-            .as_ref()
-            .map(|publisher| publisher.lock().unwrap());
+            .map(|publisher| model.display(&model[publisher]));
         write!(
             f,
             "(ptr={:x}, sender_timestamp={}, publisher={publisher})",
@@ -487,15 +490,14 @@ impl std::fmt::Display for PublicationMessage {
     }
 }
 
-impl std::fmt::Display for SubscriptionMessage {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for SubscriptionMessage {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let subscriber = self
             .subscriber
-            .as_ref()
-            .map(|subscriber| subscriber.lock().unwrap());
+            .map(|subscriber| model.display(&model[subscriber]));
         match &self.message {
             PartiallyKnown::Fully(message) => {
-                let message = message.lock().unwrap();
+                let message = model.display(message);
                 write!(
                     f,
                     "(ptr={:x}, message={message}, subscriber={subscriber})",
@@ -520,9 +522,9 @@ impl std::fmt::Display for SubscriptionMessage {
     }
 }
 
-impl std::fmt::Display for CallbackInstance {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let callback = self.callback.lock().unwrap();
+impl DisplayWithModel for CallbackInstance {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let callback = model.display(&self.callback);
         write!(
             f,
             "(start_time={}, end_time={}, callback={callback})",
@@ -531,9 +533,9 @@ impl std::fmt::Display for CallbackInstance {
     }
 }
 
-impl std::fmt::Display for SpinInstance {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let node = DisplayArcWeakMutex::new(&self.node, false);
+impl DisplayWithModel for SpinInstance {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let node = DisplayReferenced::new(model, self.node, false);
         let timeout = DisplayLargeDuration(self.timeout.as_nanos());
         write!(
             f,
@@ -1,19 +1,20 @@
+mod arena;
 pub(crate) mod display;
 
 use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
 use chrono::{Local, TimeZone};
 use derive_more::derive::Unwrap;
-use display::{
-    get_service_name_from_weak, get_subscriber_topic_from_weak, get_timer_period_from_weak,
-};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::raw_events;
-use crate::utils::{ArcWeak, CyclicDependency, DisplayDuration, Known, WeakKnown};
+use crate::utils::{DisplayDuration, Known};
+
+pub use arena::{Idx, Model, ModelObject};
+pub use display::{DisplayWithModel, WithModel};
 
 const GID_SIZE: usize = 16;
 const GID_SUFFIX_SIZE: usize = 8;
@@ -39,7 +40,7 @@ impl AlreadyInitializedError {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Time {
     /// Nanoseconds since the UNIX epoch (1970-01-01 00:00:00 UTC)
     timestamp: i64,
@@ -92,13 +93,14 @@ pub struct Node {
     rmw_handle: Known<u64>,
     full_name: Known<Name>,
 
-    subscribers: Vec<Arc<Mutex<Subscriber>>>,
-    publishers: Vec<Arc<Mutex<Publisher>>>,
-    services: Vec<Arc<Mutex<Service>>>,
-    clients: Vec<Arc<Mutex<Client>>>,
-    timers: Vec<Arc<Mutex<Timer>>>,
+    subscribers: Vec<Idx<Subscriber>>,
+    publishers: Vec<Idx<Publisher>>,
+    services: Vec<Idx<Service>>,
+    clients: Vec<Idx<Client>>,
+    timers: Vec<Idx<Timer>>,
 
-    spin_instance: Option<Arc<Mutex<SpinInstance>>>,
+    // Running spin, updated by later r2r tracepoints.
+    spin_instance: Option<Idx<SpinInstance>>,
 }
 
 impl Node {
@@ -116,23 +118,23 @@ impl Node {
         }
     }
 
-    pub fn add_subscriber(&mut self, subscriber: Arc<Mutex<Subscriber>>) {
+    pub fn add_subscriber(&mut self, subscriber: Idx<Subscriber>) {
         self.subscribers.push(subscriber);
     }
 
-    pub fn add_publisher(&mut self, publisher: Arc<Mutex<Publisher>>) {
+    pub fn add_publisher(&mut self, publisher: Idx<Publisher>) {
         self.publishers.push(publisher);
     }
 
-    pub fn add_service(&mut self, service: Arc<Mutex<Service>>) {
+    pub fn add_service(&mut self, service: Idx<Service>) {
         self.services.push(service);
     }
 
-    pub fn add_client(&mut self, client: Arc<Mutex<Client>>) {
+    pub fn add_client(&mut self, client: Idx<Client>) {
         self.clients.push(client);
     }
 
-    pub fn add_timer(&mut self, timer: Arc<Mutex<Timer>>) {
+    pub fn add_timer(&mut self, timer: Idx<Timer>) {
         self.timers.push(timer);
     }
 
@@ -146,6 +148,10 @@ impl Node {
         self.full_name = Known::new(Name::new(namespace, name));
     }
 
+    pub fn get_rcl_handle(&self) -> u64 {
+        self.rcl_handle
+    }
+
     pub fn get_full_name(&self) -> Known<&str> {
         self.full_name.as_ref().map(Name::get_full_name)
     }
@@ -158,71 +164,71 @@ impl Node {
         self.full_name.as_ref().map(Name::get_name)
     }
 
-    pub fn timers(&self) -> &[Arc<Mutex<Timer>>] {
+    pub fn timers(&self) -> &[Idx<Timer>] {
         &self.timers
     }
 
-    pub fn subscribers(&self) -> &[Arc<Mutex<Subscriber>>] {
+    pub fn subscribers(&self) -> &[Idx<Subscriber>] {
         &self.subscribers
     }
 
-    pub fn publishers(&self) -> &[Arc<Mutex<Publisher>>] {
+    pub fn publishers(&self) -> &[Idx<Publisher>] {
         &self.publishers
     }
 
-    pub fn services(&self) -> &[Arc<Mutex<Service>>] {
+    pub fn services(&self) -> &[Idx<Service>] {
         &self.services
     }
 
-    pub fn clients(&self) -> &[Arc<Mutex<Client>>] {
+    pub fn clients(&self) -> &[Idx<Client>] {
         &self.clients
     }
 
-    pub fn take_spin_instance(&mut self) -> Option<Arc<Mutex<SpinInstance>>> {
+    pub fn take_spin_instance(&mut self) -> Option<Idx<SpinInstance>> {
         self.spin_instance.take()
     }
 
     pub fn replace_spin_instance(
         &mut self,
-        spin_instance: Arc<Mutex<SpinInstance>>,
-    ) -> Option<Arc<Mutex<SpinInstance>>> {
+        spin_instance: Idx<SpinInstance>,
+    ) -> Option<Idx<SpinInstance>> {
         self.spin_instance.replace(spin_instance)
     }
 
-    pub fn borrow_spin_instance(&self) -> Option<&Arc<Mutex<SpinInstance>>> {
-        self.spin_instance.as_ref()
+    pub fn get_spin_instance(&self) -> Option<Idx<SpinInstance>> {
+        self.spin_instance
     }
 
-    pub fn print_node_info(&self) {
+    pub fn print_node_info(&self, model: &Model) {
         println!("Node{self}");
-        for subscriber in self.subscribers() {
+        for &subscriber in self.subscribers() {
             println!(
                 "  Subscriber{:#}",
-                display::DisplaySubscriberWithoutNode(&subscriber.lock().unwrap())
+                model.display(&display::DisplaySubscriberWithoutNode(&model[subscriber]))
             );
         }
-        for publisher in self.publishers() {
+        for &publisher in self.publishers() {
             println!(
                 "  Publisher{:#}",
-                display::DisplayPublisherWithoutNode(&publisher.lock().unwrap())
+                display::DisplayPublisherWithoutNode(&model[publisher])
             );
         }
-        for service in self.services() {
+        for &service in self.services() {
             println!(
                 "  Service{:#}",
-                display::DisplayServiceWithoutNode(&service.lock().unwrap())
+                model.display(&display::DisplayServiceWithoutNode(&model[service]))
             );
         }
-        for client in self.clients() {
+        for &client in self.clients() {
             println!(
                 "  Client{:#}",
-                display::DisplayClientWithoutNode(&client.lock().unwrap())
+                display::DisplayClientWithoutNode(&model[client])
             );
         }
-        for timer in self.timers() {
+        for &timer in self.timers() {
             println!(
                 "  Timer{:#}",
-                display::TimerDisplayWithoutNode(&timer.lock().unwrap())
+                model.display(&display::TimerDisplayWithoutNode(&model[timer]))
             );
         }
     }
@@ -237,14 +243,12 @@ pub struct Subscriber {
     rmw_gid: Known<RmwGid>,
 
     topic_name: Known<String>,
-    node: Known<ArcWeak<Mutex<Node>>>,
+    node: Known<Idx<Node>>,
     queue_depth: Known<usize>,
 
-    callback: Known<Arc<Mutex<Callback>>>,
-    // TODO: messages that are taken but not yet processed by the callback cause a memory leak
-    // at the end of the program. (strong reference count cycle: Subscriber -> Message -> Subscriber)
-    // For now, we just limit the number of messages stored here.
-    taken_message: VecDeque<Arc<Mutex<SubscriptionMessage>>>,
+    callback: Known<Idx<Callback>>,
+    // Messages taken by rmw that did not trigger the callback yet.
+    taken_message: VecDeque<Idx<SubscriptionMessage>>,
 
     removed: bool,
 }
@@ -252,7 +256,7 @@ pub struct Subscriber {
 impl Subscriber {
     // The maximum number of taken messages that the subscriber stores.
     // This constant was chosen to match the number of messages stored by the R2R's channels.
-    const TAKEN_MESSAGES_MAX: usize = 11;
+    pub(crate) const TAKEN_MESSAGES_MAX: usize = 11;
 
     pub fn rmw_init(
         &mut self,
@@ -279,10 +283,9 @@ impl Subscriber {
         rcl_handle: u64,
         topic_name: String,
         queue_depth: usize,
-        node: Weak<Mutex<Node>>,
+        node: Idx<Node>,
     ) -> Result<(), AlreadyInitializedError> {
         assert!(!self.is_removed());
-        let node = node.into();
         if self.rcl_handle.is_unknown() {
             self.rcl_handle = Known::new(rcl_handle);
             self.topic_name = Known::new(topic_name);
@@ -329,8 +332,8 @@ impl Subscriber {
 
     pub fn set_callback(
         &mut self,
-        callback: Arc<Mutex<Callback>>,
-    ) -> Result<(), AlreadySetError<&Self, Arc<Mutex<Callback>>>> {
+        callback: Idx<Callback>,
+    ) -> Result<(), AlreadySetError<&Self, Idx<Callback>>> {
         assert!(!self.is_removed());
         if self.callback.is_unknown() {
             self.callback = Known::new(callback);
@@ -346,8 +349,8 @@ impl Subscriber {
 
     pub fn replace_taken_message(
         &mut self,
-        message: Arc<Mutex<SubscriptionMessage>>,
-    ) -> Option<Arc<Mutex<SubscriptionMessage>>> {
+        message: Idx<SubscriptionMessage>,
+    ) -> Option<Idx<SubscriptionMessage>> {
         assert!(!self.is_removed());
 
         // We store a maximum of `TAKEN_MESSAGES_MAX` messages. These messages are used by callbacks.
@@ -381,7 +384,7 @@ impl Subscriber {
         self.rclcpp_handle
     }
 
-    pub fn take_message(&mut self) -> Option<Arc<Mutex<SubscriptionMessage>>> {
+    pub fn take_message(&mut self) -> Option<Idx<SubscriptionMessage>> {
         self.taken_message.pop_front()
     }
 
@@ -389,12 +392,12 @@ impl Subscriber {
         self.topic_name.as_deref()
     }
 
-    pub fn get_node(&self) -> Known<ArcWeak<Mutex<Node>>> {
-        self.node.clone()
+    pub fn get_node(&self) -> Known<Idx<Node>> {
+        self.node
     }
 
-    pub fn get_callback(&self) -> Known<Arc<Mutex<Callback>>> {
-        self.callback.clone()
+    pub fn get_callback(&self) -> Known<Idx<Callback>> {
+        self.callback
     }
 
     pub fn mark_removed(&mut self) {
@@ -406,19 +409,6 @@ impl Subscriber {
     }
 }
 
-impl CyclicDependency for Subscriber {
-    fn break_cycle(&mut self) {
-        self.node.as_mut().map(ArcWeak::downgrade_in_place);
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.node
-            .as_mut()
-            .map(ArcWeak::upgrade_in_place)
-            .unwrap_or(true)
-    }
-}
-
 #[derive(Debug, Default)]
 pub struct Publisher {
     rmw_handle: Known<u64>,
@@ -428,7 +418,7 @@ pub struct Publisher {
     rmw_gid: Known<RmwGid>,
 
     topic_name: Known<String>,
-    node: Known<ArcWeak<Mutex<Node>>>,
+    node: Known<Idx<Node>>,
     queue_depth: Known<usize>,
 
     removed: bool,
@@ -460,14 +450,14 @@ impl Publisher {
         rcl_handle: u64,
         topic_name: String,
         queue_depth: usize,
-        node: Weak<Mutex<Node>>,
+        node: Idx<Node>,
     ) -> Result<(), AlreadyInitializedError> {
         assert!(!self.is_removed());
         if self.rcl_handle.is_unknown() {
             self.rcl_handle = Known::new(rcl_handle);
             self.topic_name = Known::new(topic_name);
             self.queue_depth = Known::new(queue_depth);
-            self.node = Known::new(node.into());
+            self.node = Known::new(node);
             Ok(())
         } else {
             Err(AlreadyInitializedError::new(
@@ -523,8 +513,8 @@ impl Publisher {
         self.topic_name.as_deref()
     }
 
-    pub fn get_node(&self) -> Known<ArcWeak<Mutex<Node>>> {
-        self.node.clone()
+    pub fn get_node(&self) -> Known<Idx<Node>> {
+        self.node
     }
 
     pub fn mark_removed(&mut self) {
@@ -550,19 +540,6 @@ impl Publisher {
     }
 }
 
-impl CyclicDependency for Publisher {
-    fn break_cycle(&mut self) {
-        self.node.as_mut().map(ArcWeak::downgrade_in_place);
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.node
-            .as_mut()
-            .map(ArcWeak::upgrade_in_place)
-            .unwrap_or(true)
-    }
-}
-
 #[derive(Debug)]
 pub struct Service {
     rmw_handle: Known<u64>,
@@ -570,8 +547,8 @@ pub struct Service {
     rclcpp_handle: Known<u64>,
 
     name: Known<String>,
-    node: Known<ArcWeak<Mutex<Node>>>,
-    callback: Known<Arc<Mutex<Callback>>>,
+    node: Known<Idx<Node>>,
+    callback: Known<Idx<Callback>>,
 
     removed: bool,
 }
@@ -593,7 +570,7 @@ impl Service {
         &mut self,
         rmw_handle: u64,
         name: String,
-        node: &Arc<Mutex<Node>>,
+        node: Idx<Node>,
     ) -> Result<(), AlreadyInitializedError> {
         assert!(!self.is_removed());
         if !self.name.is_unknown()
@@ -605,15 +582,15 @@ impl Service {
 
         self.rmw_handle = Known::new(rmw_handle);
         self.name = Known::new(name);
-        self.node = Known::new(Arc::downgrade(node).into());
+        self.node = Known::new(node);
 
         Ok(())
     }
 
     pub fn set_callback(
         &mut self,
-        callback: Arc<Mutex<Callback>>,
-    ) -> Result<(), AlreadySetError<&Self, Arc<Mutex<Callback>>>> {
+        callback: Idx<Callback>,
+    ) -> Result<(), AlreadySetError<&Self, Idx<Callback>>> {
         assert!(!self.is_removed());
         if !self.callback.is_unknown() {
             return Err(AlreadySetError {
@@ -627,8 +604,8 @@ impl Service {
         Ok(())
     }
 
-    pub fn get_node(&self) -> Known<ArcWeak<Mutex<Node>>> {
-        self.node.clone()
+    pub fn get_node(&self) -> Known<Idx<Node>> {
+        self.node
     }
 
     pub fn get_name(&self) -> Known<&str> {
@@ -644,24 +621,11 @@ impl Service {
     }
 }
 
-impl CyclicDependency for Service {
-    fn break_cycle(&mut self) {
-        self.node.as_mut().map(ArcWeak::downgrade_in_place);
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.node
-            .as_mut()
-            .map(ArcWeak::upgrade_in_place)
-            .unwrap_or(true)
-    }
-}
-
 #[derive(Debug, Default)]
 pub struct Client {
     rcl_handle: u64,
     rmw_handle: Known<u64>,
-    node: Known<ArcWeak<Mutex<Node>>>,
+    node: Known<Idx<Node>>,
     service_name: Known<String>,
 
     removed: bool,
@@ -682,7 +646,7 @@ impl Client {
         &mut self,
         rmw_handle: u64,
         service_name: String,
-        node: &Arc<Mutex<Node>>,
+        node: Idx<Node>,
     ) -> Result<(), AlreadyInitializedError> {
         assert!(!self.is_removed());
 
@@ -693,31 +657,26 @@ impl Client {
             return Err(AlreadyInitializedError::new("Client", "rcl_client_init"));
         }
         self.rmw_handle = Known::new(rmw_handle);
-        self.node = Known::new(Arc::downgrade(node).into());
+        self.node = Known::new(node);
         self.service_name = Known::new(service_name);
 
         Ok(())
     }
 
-    pub fn mark_removed(&mut self) {
-        self.removed = true;
+    pub fn get_node(&self) -> Known<Idx<Node>> {
+        self.node
     }
 
-    pub fn is_removed(&self) -> bool {
-        self.removed
+    pub fn get_service_name(&self) -> Known<&str> {
+        self.service_name.as_deref()
     }
-}
 
-impl CyclicDependency for Client {
-    fn break_cycle(&mut self) {
-        self.node.as_mut().map(ArcWeak::downgrade_in_place);
+    pub fn mark_removed(&mut self) {
+        self.removed = true;
     }
 
-    fn create_cycle(&mut self) -> bool {
-        self.node
-            .as_mut()
-            .map(ArcWeak::upgrade_in_place)
-            .unwrap_or(true)
+    pub fn is_removed(&self) -> bool {
+        self.removed
     }
 }
 
@@ -726,8 +685,8 @@ pub struct Timer {
     rcl_handle: u64,
     period: Known<i64>,
 
-    callback: Known<Arc<Mutex<Callback>>>,
-    node: Known<ArcWeak<Mutex<Node>>>,
+    callback: Known<Idx<Callback>>,
+    node: Known<Idx<Node>>,
 
     removed: bool,
 }
@@ -755,8 +714,8 @@ impl Timer {
 
     pub fn set_callback(
         &mut self,
-        callback: Arc<Mutex<Callback>>,
-    ) -> Result<(), AlreadySetError<&Self, Arc<Mutex<Callback>>>> {
+        callback: Idx<Callback>,
+    ) -> Result<(), AlreadySetError<&Self, Idx<Callback>>> {
         assert!(!self.is_removed());
         if !self.callback.is_unknown() {
             return Err(AlreadySetError {
@@ -770,20 +729,17 @@ impl Timer {
         Ok(())
     }
 
-    pub fn link_node(
-        &mut self,
-        node: &Arc<Mutex<Node>>,
-    ) -> Result<(), AlreadySetError<&Self, Arc<Mutex<Node>>>> {
+    pub fn link_node(&mut self, node: Idx<Node>) -> Result<(), AlreadySetError<&Self, Idx<Node>>> {
         assert!(!self.is_removed());
         if !self.node.is_unknown() {
             return Err(AlreadySetError {
                 object: self,
-                new_value: Arc::clone(node),
+                new_value: node,
                 msg: "Timer node already set",
             });
         }
 
-        self.node = Known::new(Arc::downgrade(node).into());
+        self.node = Known::new(node);
         Ok(())
     }
 
@@ -791,8 +747,8 @@ impl Timer {
         self.period
     }
 
-    pub fn get_node(&self) -> Known<ArcWeak<Mutex<Node>>> {
-        self.node.clone()
+    pub fn get_node(&self) -> Known<Idx<Node>> {
+        self.node
     }
 
     pub fn mark_removed(&mut self) {
@@ -804,19 +760,6 @@ impl Timer {
     }
 }
 
-impl CyclicDependency for Timer {
-    fn break_cycle(&mut self) {
-        self.node.as_mut().map(ArcWeak::downgrade_in_place);
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.node
-            .as_mut()
-            .map(ArcWeak::upgrade_in_place)
-            .unwrap_or(true)
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CallbackType {
     Subscription,
@@ -824,12 +767,11 @@ pub enum CallbackType {
     Timer,
 }
 
-#[derive(Debug, Unwrap)]
-#[unwrap(ref)]
+#[derive(Debug, Clone, Copy, Unwrap)]
 pub enum CallbackCaller {
-    Subscription(ArcWeak<Mutex<Subscriber>>),
-    Service(ArcWeak<Mutex<Service>>),
-    Timer(ArcWeak<Mutex<Timer>>),
+    Subscription(Idx<Subscriber>),
+    Service(Idx<Service>),
+    Timer(Idx<Timer>),
 }
 
 impl CallbackCaller {
@@ -838,40 +780,31 @@ impl CallbackCaller {
     /// For subscriptions, it returns the topic name.
     /// For services, it returns the service name.
     /// For timers, it returns the period.
-    pub fn get_caller_as_string(&self) -> WeakKnown<String> {
-        match self {
-            Self::Subscription(sub) => get_subscriber_topic_from_weak(&sub.get_weak()),
-            Self::Service(service) => get_service_name_from_weak(&service.get_weak()),
-            Self::Timer(timer) => get_timer_period_from_weak(&timer.get_weak())
+    pub fn get_caller_as_string(&self, model: &Model) -> Known<String> {
+        match *self {
+            Self::Subscription(sub) => model[sub].get_topic().map(ToString::to_string),
+            Self::Service(service) => model[service].get_name().map(ToString::to_string),
+            Self::Timer(timer) => model[timer]
+                .get_period()
                 .map(|period| DisplayDuration(period).to_string()),
         }
     }
 
-    /// Returns whether the caller of the callback has been marked as removed
-    /// or `None` if its weak reference cannot be upgraded.
-    pub fn is_removed(&self) -> Option<bool> {
-        match self {
-            Self::Subscription(sub) => Some(sub.get_arc()?.lock().unwrap().is_removed()),
-            Self::Service(service) => Some(service.get_arc()?.lock().unwrap().is_removed()),
-            Self::Timer(timer) => Some(timer.get_arc()?.lock().unwrap().is_removed()),
+    /// Returns whether the caller of the callback has been marked as removed.
+    pub fn is_removed(&self, model: &Model) -> bool {
+        match *self {
+            Self::Subscription(sub) => model[sub].is_removed(),
+            Self::Service(service) => model[service].is_removed(),
+            Self::Timer(timer) => model[timer].is_removed(),
         }
     }
-}
 
-impl CyclicDependency for CallbackCaller {
-    fn break_cycle(&mut self) {
-        match self {
-            Self::Subscription(sub) => sub.downgrade_in_place(),
-            Self::Service(service) => service.downgrade_in_place(),
-            Self::Timer(timer) => timer.downgrade_in_place(),
-        }
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        match self {
-            Self::Subscription(sub) => sub.upgrade_in_place(),
-            Self::Service(service) => service.upgrade_in_place(),
-            Self::Timer(timer) => timer.upgrade_in_place(),
+    /// Returns the node of the caller if it is known.
+    pub fn get_node(&self, model: &Model) -> Known<Idx<Node>> {
+        match *self {
+            Self::Subscription(sub) => model[sub].get_node(),
+            Self::Service(service) => model[service].get_node(),
+            Self::Timer(timer) => model[timer].get_node(),
         }
     }
 }
@@ -897,49 +830,34 @@ pub struct Callback {
     handle: u64,
     caller: Known<CallbackCaller>,
     name: Known<String>,
-    running_instance: Option<Arc<Mutex<CallbackInstance>>>,
+    running_instance: Option<Idx<CallbackInstance>>,
     hostname: String,
 
     is_removed: bool,
 }
 
 impl Callback {
-    fn new(handle: u64, caller: CallbackCaller, hostname: String) -> Arc<Mutex<Self>> {
-        Arc::new(Mutex::new(Self {
+    fn new(handle: u64, caller: CallbackCaller, hostname: String) -> Self {
+        Self {
             handle,
             caller: Known::Known(caller),
             name: Known::Unknown,
             running_instance: None,
             hostname,
             is_removed: false,
-        }))
+        }
     }
 
-    pub fn new_subscription(
-        handle: u64,
-        caller: &Arc<Mutex<Subscriber>>,
-        hostname: String,
-    ) -> Arc<Mutex<Self>> {
-        let caller = CallbackCaller::Subscription(Arc::downgrade(caller).into());
-        Self::new(handle, caller, hostname)
+    pub fn new_subscription(handle: u64, caller: Idx<Subscriber>, hostname: String) -> Self {
+        Self::new(handle, CallbackCaller::Subscription(caller), hostname)
     }
 
-    pub fn new_service(
-        handle: u64,
-        caller: &Arc<Mutex<Service>>,
-        hostname: String,
-    ) -> Arc<Mutex<Self>> {
-        let caller = CallbackCaller::Service(Arc::downgrade(caller).into());
-        Self::new(handle, caller, hostname)
+    pub fn new_service(handle: u64, caller: Idx<Service>, hostname: String) -> Self {
+        Self::new(handle, CallbackCaller::Service(caller), hostname)
     }
 
-    pub fn new_timer(
-        handle: u64,
-        caller: &Arc<Mutex<Timer>>,
-        hostname: String,
-    ) -> Arc<Mutex<Self>> {
-        let caller = CallbackCaller::Timer(Arc::downgrade(caller).into());
-        Self::new(handle, caller, hostname)
+    pub fn new_timer(handle: u64, caller: Idx<Timer>, hostname: String) -> Self {
+        Self::new(handle, CallbackCaller::Timer(caller), hostname)
     }
 
     pub fn set_name(&mut self, name: String) -> Result<(), AlreadySetError<&Self, String>> {
@@ -956,7 +874,7 @@ impl Callback {
         }
     }
 
-    pub fn take_running_instance(&mut self) -> Option<Arc<Mutex<CallbackInstance>>> {
+    pub fn take_running_instance(&mut self) -> Option<Idx<CallbackInstance>> {
         self.running_instance.take()
     }
 
@@ -984,56 +902,24 @@ impl Callback {
         self.caller.as_ref().into()
     }
 
-    pub fn get_node(&self) -> Option<ArcWeak<Mutex<Node>>> {
-        let caller = Option::<&CallbackCaller>::from(self.caller.as_ref())?;
-
-        match caller {
-            CallbackCaller::Subscription(subscriber) => {
-                subscriber.get_arc()?.lock().unwrap().get_node().into()
-            }
-            CallbackCaller::Service(service) => {
-                service.get_arc()?.lock().unwrap().get_node().into()
-            }
-            CallbackCaller::Timer(timer) => timer.get_arc()?.lock().unwrap().get_node().into(),
-        }
+    pub fn get_node(&self, model: &Model) -> Option<Idx<Node>> {
+        self.get_caller()?.get_node(model).into()
     }
 
     pub fn get_hostname(&self) -> &str {
         &self.hostname
     }
 
-    pub fn mark_removed(&mut self) {
-        assert!(
-            self.get_caller()
-                .map_or(Some(true), CallbackCaller::is_removed)
-                .unwrap_or(true)
-        );
-        self.is_removed = true;
-    }
-
     #[inline]
     pub fn is_removed(&self) -> bool {
         self.is_removed
     }
 }
 
-impl CyclicDependency for Callback {
-    fn break_cycle(&mut self) {
-        self.caller.as_mut().map(CyclicDependency::break_cycle);
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.caller
-            .as_mut()
-            .map(CyclicDependency::create_cycle)
-            .unwrap_or(true)
-    }
-}
-
 #[derive(Debug)]
 pub struct PublicationMessage {
     ptr: u64,
-    publisher: Known<Arc<Mutex<Publisher>>>,
+    publisher: Known<Idx<Publisher>>,
     sender_timestamp: Known<Time>,
     rmw_publish_time: Known<Time>,
     rcl_publish_time: Known<Time>,
@@ -1052,11 +938,11 @@ impl PublicationMessage {
         }
     }
 
-    pub fn get_publisher(&self) -> Option<Arc<Mutex<Publisher>>> {
-        self.publisher.clone().into()
+    pub fn get_publisher(&self) -> Option<Idx<Publisher>> {
+        self.publisher.into()
     }
 
-    pub(crate) fn set_publisher(&mut self, publisher: Arc<Mutex<Publisher>>) {
+    pub(crate) fn set_publisher(&mut self, publisher: Idx<Publisher>) {
         assert!(
             self.publisher.is_unknown(),
             "PublicationMessage publisher already set. {self:#?}"
@@ -1064,7 +950,7 @@ impl PublicationMessage {
         self.publisher = Known::new(publisher);
     }
 
-    pub(crate) fn replace_publisher(&mut self, publisher: Arc<Mutex<Publisher>>) {
+    pub(crate) fn replace_publisher(&mut self, publisher: Idx<Publisher>) {
         self.publisher = Known::new(publisher);
     }
 
@@ -1130,8 +1016,8 @@ pub enum PartiallyKnown<T, U> {
 #[derive(Debug)]
 pub struct SubscriptionMessage {
     ptr: u64,
-    message: PartiallyKnown<Arc<Mutex<PublicationMessage>>, Time>,
-    subscriber: Known<Arc<Mutex<Subscriber>>>,
+    message: PartiallyKnown<Idx<PublicationMessage>, Time>,
+    subscriber: Known<Idx<Subscriber>>,
     rmw_receive_time: Known<Time>,
     rcl_receive_time: Known<Time>,
     rclcpp_receive_time: Known<Time>,
@@ -1151,8 +1037,8 @@ impl SubscriptionMessage {
 
     pub fn rmw_take_matched(
         &mut self,
-        subscriber: Arc<Mutex<Subscriber>>,
-        published_message: Arc<Mutex<PublicationMessage>>,
+        subscriber: Idx<Subscriber>,
+        published_message: Idx<PublicationMessage>,
         time: Time,
     ) {
         assert!(
@@ -1166,7 +1052,7 @@ impl SubscriptionMessage {
 
     pub fn rmw_take_unmatched(
         &mut self,
-        subscriber: Arc<Mutex<Subscriber>>,
+        subscriber: Idx<Subscriber>,
         publication_time: i64,
         time: Time,
     ) {
@@ -1205,9 +1091,13 @@ impl SubscriptionMessage {
         Ok(())
     }
 
-    pub fn get_publication_message(&self) -> Option<Arc<Mutex<PublicationMessage>>> {
-        match &self.message {
-            PartiallyKnown::Fully(message) => Some(message.clone()),
+    /// Matched publication of the message.
+    ///
+    /// The publication can be released before the subscription message when it is evicted by the
+    /// message retention, so it has to be looked up with [`Model::get`].
+    pub fn get_publication_message(&self) -> Option<Idx<PublicationMessage>> {
+        match self.message {
+            PartiallyKnown::Fully(message) => Some(message),
             _ => None,
         }
     }
@@ -1237,8 +1127,8 @@ impl SubscriptionMessage {
             .or_else(|| self.get_rmw_receive_time())
     }
 
-    pub fn get_subscriber(&self) -> Option<Arc<Mutex<Subscriber>>> {
-        self.subscriber.clone().into()
+    pub fn get_subscriber(&self) -> Option<Idx<Subscriber>> {
+        self.subscriber.into()
     }
 }
 
@@ -1270,11 +1160,11 @@ impl RmwGid {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum CallbackTrigger {
-    SubscriptionMessage(Arc<Mutex<SubscriptionMessage>>),
-    Service(Arc<Mutex<Service>>),
-    Timer(Arc<Mutex<Timer>>),
+    SubscriptionMessage(Idx<SubscriptionMessage>),
+    Service(Idx<Service>),
+    Timer(Idx<Timer>),
 }
 
 impl From<CallbackTrigger> for CallbackType {
@@ -1291,48 +1181,43 @@ impl From<CallbackTrigger> for CallbackType {
 pub struct CallbackInstance {
     start_time: Time,
     end_time: Known<Time>,
-    callback: Arc<Mutex<Callback>>,
+    callback: Idx<Callback>,
     trigger: CallbackTrigger,
 }
 
 impl CallbackInstance {
-    pub fn new(callback: Arc<Mutex<Callback>>, start_time: Time) -> Arc<Mutex<Self>> {
-        let callback_arc = callback;
-        let mut callback = callback_arc.lock().unwrap();
+    /// Start a new instance of the `callback` and set it as its running instance.
+    ///
+    /// For subscription callbacks, the oldest message taken by the subscriber triggers the callback.
+    pub fn new(model: &mut Model, callback_idx: Idx<Callback>, start_time: Time) -> Idx<Self> {
+        let callback = &model[callback_idx];
         assert!(
             callback.running_instance.is_none(),
             "Callback already has a running instance. {callback:#?}"
         );
 
-        let trigger = match callback.caller.as_ref().unwrap() {
-            CallbackCaller::Subscription(weak) => {
-                let subscriber = weak.get_arc().unwrap();
-                let mut subscriber = subscriber.lock().unwrap();
-                let message = subscriber.take_message().unwrap_or_else(|| {
+        let trigger = match *callback.caller.as_ref().unwrap() {
+            CallbackCaller::Subscription(subscriber_idx) => {
+                let message = model[subscriber_idx].take_message().unwrap_or_else(|| {
                     panic!(
-                        "Subscriber does not have a message to trigger the callback at time {start_time}.\n{subscriber:#?}\n{callback:#?}",
+                        "Subscriber does not have a message to trigger the callback at time {start_time}.\n{:#?}\n{:#?}",
+                        model[subscriber_idx], model[callback_idx]
                     )
                 });
                 CallbackTrigger::SubscriptionMessage(message)
             }
-            CallbackCaller::Service(weak) => {
-                let service = weak.get_arc().unwrap();
-                CallbackTrigger::Service(service)
-            }
-            CallbackCaller::Timer(weak) => {
-                let timer = weak.get_arc().unwrap();
-                CallbackTrigger::Timer(timer)
-            }
+            CallbackCaller::Service(service) => CallbackTrigger::Service(service),
+            CallbackCaller::Timer(timer) => CallbackTrigger::Timer(timer),
         };
 
-        let new = Arc::new(Mutex::new(Self {
+        let new = model.add(Self {
             start_time,
             end_time: Known::Unknown,
-            callback: callback_arc.clone(),
+            callback: callback_idx,
             trigger,
-        }));
+        });
 
-        callback.running_instance = Some(new.clone());
+        model[callback_idx].running_instance = Some(new);
 
         new
     }
@@ -1346,8 +1231,8 @@ impl CallbackInstance {
         self.end_time = Known::Known(time);
     }
 
-    pub fn get_callback(&self) -> Arc<Mutex<Callback>> {
-        self.callback.clone()
+    pub fn get_callback(&self) -> Idx<Callback> {
+        self.callback
     }
 
     pub fn get_start_time(&self) -> Time {
@@ -1358,8 +1243,8 @@ impl CallbackInstance {
         self.end_time.into()
     }
 
-    pub fn get_trigger(&self) -> &CallbackTrigger {
-        &self.trigger
+    pub fn get_trigger(&self) -> CallbackTrigger {
+        self.trigger
     }
 }
 
@@ -1370,19 +1255,19 @@ pub struct SpinInstance {
     end_time: Known<Time>,
     timeout: Duration,
     timeouted: Known<bool>,
-    node: ArcWeak<Mutex<Node>>,
+    node: Idx<Node>,
 }
 
 impl SpinInstance {
-    pub fn new(node: &Arc<Mutex<Node>>, start_time: Time, timeout: Duration) -> Arc<Mutex<Self>> {
-        Arc::new(Mutex::new(Self {
+    pub fn new(node: Idx<Node>, start_time: Time, timeout: Duration) -> Self {
+        Self {
             start_time,
             wake_time: Known::Unknown,
             end_time: Known::Unknown,
             timeout,
             timeouted: Known::Unknown,
-            node: Arc::downgrade(node).into(),
-        }))
+            node,
+        }
     }
 
     pub fn set_wake_time(&mut self, time: Time) {
@@ -1427,13 +1312,3 @@ impl SpinInstance {
         self.end_time.into()
     }
 }
-
-impl CyclicDependency for SpinInstance {
-    fn break_cycle(&mut self) {
-        self.node.downgrade_in_place();
-    }
-
-    fn create_cycle(&mut self) -> bool {
-        self.node.upgrade_in_place()
-    }
-}
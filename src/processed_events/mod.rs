@@ -1,14 +1,29 @@
-use std::sync::{Arc, Mutex};
-
 use derive_more::derive::From;
 
 use crate::events_common::{Context, Time};
+use crate::model::{DisplayWithModel, Model};
 
 pub mod ros2;
 
 pub mod r2r;
 
-type RefCount<T> = Arc<Mutex<T>>;
+/// Implement [`DisplayWithModel`] for events displaying a single model object.
+macro_rules! impl_display_with_model {
+    ($($event:ident => $fmt:literal, $field:ident;)*) => {
+        $(
+            impl DisplayWithModel for $event {
+                fn fmt_with_model(
+                    &self,
+                    model: &Model,
+                    f: &mut std::fmt::Formatter<'_>,
+                ) -> std::fmt::Result {
+                    write!(f, $fmt, model.display(&self.$field))
+                }
+            }
+        )*
+    };
+}
+use impl_display_with_model;
 
 #[derive(Debug, Clone, From)]
 pub enum Event {
@@ -16,11 +31,11 @@ pub enum Event {
     R2r(r2r::Event),
 }
 
-impl std::fmt::Display for Event {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl DisplayWithModel for Event {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Ros2(e) => write!(f, "ros2:{e}"),
-            Self::R2r(e) => write!(f, "r2r:{e}"),
+            Self::Ros2(e) => write!(f, "ros2:{}", model.display(e)),
+            Self::R2r(e) => write!(f, "r2r:{}", model.display(e)),
         }
     }
 }
@@ -32,8 +47,14 @@ pub struct FullEvent {
     pub event: Event,
 }
 
-impl std::fmt::Display for FullEvent {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}] {} {:?}", self.time, self.event, self.context)
+impl DisplayWithModel for FullEvent {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {:?}",
+            self.time,
+            model.display(&self.event),
+            self.context
+        )
     }
 }
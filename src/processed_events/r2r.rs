@@ -1,56 +1,62 @@
-use derive_more::derive::{Display, From};
+use derive_more::derive::From;
 
-use crate::model::{Node, SpinInstance, Subscriber};
+use crate::model::{DisplayWithModel, Idx, Model, Node, SpinInstance, Subscriber};
 
-use super::RefCount;
+use super::impl_display_with_model;
 
-#[derive(Debug, Clone, From, Display)]
+#[derive(Debug, Clone, From)]
 pub enum Event {
-    #[display("spin_start({_0})")]
     SpinStart(SpinStart),
-    #[display("spin_end({_0})")]
     SpinEnd(SpinEnd),
-    #[display("spin_wake({_0})")]
     SpinWake(SpinWake),
-    #[display("spin_timeout({_0})")]
     SpinTimeout(SpinTimeout),
-    #[display("update_time({_0})")]
     UpdateTime(UpdateTime),
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Spin({})", spin.lock().unwrap())]
+impl DisplayWithModel for Event {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SpinStart(e) => write!(f, "spin_start({})", model.display(e)),
+            Self::SpinEnd(e) => write!(f, "spin_end({})", model.display(e)),
+            Self::SpinWake(e) => write!(f, "spin_wake({})", model.display(e)),
+            Self::SpinTimeout(e) => write!(f, "spin_timeout({})", model.display(e)),
+            Self::UpdateTime(e) => write!(f, "update_time({})", model.display(e)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct SpinStart {
-    pub node: RefCount<Node>,
-    pub spin: RefCount<SpinInstance>,
+    pub node: Idx<Node>,
+    pub spin: Idx<SpinInstance>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SpinEnd {
-    pub node: RefCount<Node>,
-    pub spin: Option<RefCount<SpinInstance>>,
+    pub node: Idx<Node>,
+    pub spin: Option<Idx<SpinInstance>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SpinWake {
-    pub node: RefCount<Node>,
-    pub spin: Option<RefCount<SpinInstance>>,
+    pub node: Idx<Node>,
+    pub spin: Option<Idx<SpinInstance>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SpinTimeout {
-    pub node: RefCount<Node>,
-    pub spin: Option<RefCount<SpinInstance>>,
+    pub node: Idx<Node>,
+    pub spin: Option<Idx<SpinInstance>>,
 }
 
 macro_rules! impl_display_for_spin_events {
     ($($event:ident),*) => {
         $(
-            impl std::fmt::Display for $event {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            impl DisplayWithModel for $event {
+                fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                     match &self.spin {
-                        Some(spin) => write!(f, "Spin({})", spin.lock().unwrap()),
-                        None => write!(f, "Node({})", self.node.lock().unwrap()),
+                        Some(spin) => write!(f, "Spin({})", model.display(spin)),
+                        None => write!(f, "Node({})", model.display(&self.node)),
                     }
                 }
             }
@@ -60,10 +66,25 @@ macro_rules! impl_display_for_spin_events {
 
 impl_display_for_spin_events!(SpinEnd, SpinWake, SpinTimeout);
 
-#[derive(Debug, Clone, Display)]
-#[display("subscriber: {}, new_time: {time_s}.{time_ns:09}", subscriber.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct UpdateTime {
-    pub subscriber: RefCount<Subscriber>,
+    pub subscriber: Idx<Subscriber>,
     pub time_s: i32,
     pub time_ns: u32,
 }
+
+impl DisplayWithModel for UpdateTime {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "subscriber: {}, new_time: {}.{:09}",
+            model.display(&self.subscriber),
+            self.time_s,
+            self.time_ns
+        )
+    }
+}
+
+impl_display_with_model!(
+    SpinStart => "Spin({})", spin;
+);
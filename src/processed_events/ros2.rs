@@ -1,87 +1,112 @@
 use derive_more::derive::{Display, From};
 
 use crate::model::{
-    Callback, CallbackInstance, Client, Node, PublicationMessage, Publisher, Service, Subscriber,
-    SubscriptionMessage, Timer,
+    Callback, CallbackInstance, Client, DisplayWithModel, Idx, Model, Node, PublicationMessage,
+    Publisher, Service, Subscriber, SubscriptionMessage, Timer,
 };
 
-use super::RefCount;
+use super::impl_display_with_model;
 
-#[derive(Debug, Clone, From, Display)]
+#[derive(Debug, Clone, From)]
 pub enum Event {
-    #[display("rcl_init({_0})")]
     RclInit(RclInit),
 
-    #[display("rcl_node_init({_0})")]
     RclNodeInit(RclNodeInit),
 
-    #[display("rmw_publisher_init({_0})")]
     RmwPublisherInit(RmwPublisherInit),
 
-    #[display("rcl_publisher_init({_0})")]
     RclPublisherInit(RclPublisherInit),
 
-    #[display("rclcpp_publish({_0})")]
     RclcppPublish(RclcppPublish),
 
-    #[display("rclcpp_intra_publish({_0})")]
     RclcppIntraPublish(RclcppIntraPublish),
 
-    #[display("rcl_publish({_0})")]
     RclPublish(RclPublish),
 
-    #[display("rmw_publish({_0})")]
     RmwPublish(RmwPublish),
 
-    #[display("rmw_subscription_init({_0})")]
     RmwSubscriptionInit(RmwSubscriptionInit),
 
-    #[display("rcl_subscription_init({_0})")]
     RclSubscriptionInit(RclSubscriptionInit),
 
-    #[display("rclcpp_subscription_init({_0})")]
     RclcppSubscriptionInit(RclcppSubscriptionInit),
 
-    #[display("rclcpp_subscription_callback_added({_0})")]
     RclcppSubscriptionCallbackAdded(RclcppSubscriptionCallbackAdded),
 
-    #[display("rmw_take({_0})")]
     RmwTake(RmwTake),
 
-    #[display("rcl_take({_0})")]
     RclTake(RclTake),
 
-    #[display("rclcpp_take({_0})")]
     RclCppTake(RclCppTake),
 
-    #[display("rcl_service_init({_0})")]
     RclServiceInit(RclServiceInit),
 
-    #[display("rclcpp_service_callback_added({_0})")]
     RclCppServiceCallbackAdded(RclCppServiceCallbackAdded),
 
-    #[display("rcl_client_init({_0})")]
     RclClientInit(RclClientInit),
 
-    #[display("rcl_timer_init({_0})")]
     RclTimerInit(RclTimerInit),
 
-    #[display("rclcpp_timer_callback_added({_0})")]
     RclcppTimerCallbackAdded(RclcppTimerCallbackAdded),
 
-    #[display("rclcpp_timer_link_node({_0})")]
     RclcppTimerLinkNode(RclcppTimerLinkNode),
 
-    #[display("rclcpp_callback_register({_0})")]
     RclcppCallbackRegister(RclcppCallbackRegister),
 
-    #[display("callback_start({_0})")]
     CallbackStart(CallbackStart),
 
-    #[display("callback_end({_0})")]
     CallbackEnd(CallbackEnd),
 }
 
+impl DisplayWithModel for Event {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RclInit(e) => write!(f, "rcl_init({})", model.display(e)),
+            Self::RclNodeInit(e) => write!(f, "rcl_node_init({})", model.display(e)),
+            Self::RmwPublisherInit(e) => write!(f, "rmw_publisher_init({})", model.display(e)),
+            Self::RclPublisherInit(e) => write!(f, "rcl_publisher_init({})", model.display(e)),
+            Self::RclcppPublish(e) => write!(f, "rclcpp_publish({})", model.display(e)),
+            Self::RclcppIntraPublish(e) => write!(f, "rclcpp_intra_publish({})", model.display(e)),
+            Self::RclPublish(e) => write!(f, "rcl_publish({})", model.display(e)),
+            Self::RmwPublish(e) => write!(f, "rmw_publish({})", model.display(e)),
+            Self::RmwSubscriptionInit(e) => {
+                write!(f, "rmw_subscription_init({})", model.display(e))
+            }
+            Self::RclSubscriptionInit(e) => {
+                write!(f, "rcl_subscription_init({})", model.display(e))
+            }
+            Self::RclcppSubscriptionInit(e) => {
+                write!(f, "rclcpp_subscription_init({})", model.display(e))
+            }
+            Self::RclcppSubscriptionCallbackAdded(e) => write!(
+                f,
+                "rclcpp_subscription_callback_added({})",
+                model.display(e)
+            ),
+            Self::RmwTake(e) => write!(f, "rmw_take({})", model.display(e)),
+            Self::RclTake(e) => write!(f, "rcl_take({})", model.display(e)),
+            Self::RclCppTake(e) => write!(f, "rclcpp_take({})", model.display(e)),
+            Self::RclServiceInit(e) => write!(f, "rcl_service_init({})", model.display(e)),
+            Self::RclCppServiceCallbackAdded(e) => {
+                write!(f, "rclcpp_service_callback_added({})", model.display(e))
+            }
+            Self::RclClientInit(e) => write!(f, "rcl_client_init({})", model.display(e)),
+            Self::RclTimerInit(e) => write!(f, "rcl_timer_init({})", model.display(e)),
+            Self::RclcppTimerCallbackAdded(e) => {
+                write!(f, "rclcpp_timer_callback_added({})", model.display(e))
+            }
+            Self::RclcppTimerLinkNode(e) => {
+                write!(f, "rclcpp_timer_link_node({})", model.display(e))
+            }
+            Self::RclcppCallbackRegister(e) => {
+                write!(f, "rclcpp_callback_register({})", model.display(e))
+            }
+            Self::CallbackStart(e) => write!(f, "callback_start({})", model.display(e)),
+            Self::CallbackEnd(e) => write!(f, "callback_end({})", model.display(e)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Display)]
 #[display("context: 0x{context_handle:x}, version: {version}")]
 pub struct RclInit {
@@ -89,162 +114,181 @@ pub struct RclInit {
     pub version: String,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Node({})", node.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclNodeInit {
-    pub node: RefCount<Node>,
+    pub node: Idx<Node>,
 }
 
 // Publisher
 
-#[derive(Debug, Clone, Display)]
-#[display("Publisher({})", publisher.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RmwPublisherInit {
-    pub publisher: RefCount<Publisher>,
+    pub publisher: Idx<Publisher>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Publisher({})", publisher.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclPublisherInit {
-    pub publisher: RefCount<Publisher>,
+    pub publisher: Idx<Publisher>,
 }
 
 // publish
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppPublish {
     /// message pointer
-    pub message: RefCount<PublicationMessage>,
+    pub message: Idx<PublicationMessage>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppIntraPublish {
-    pub message: RefCount<PublicationMessage>,
+    pub message: Idx<PublicationMessage>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclPublish {
-    pub message: RefCount<PublicationMessage>,
+    pub message: Idx<PublicationMessage>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RmwPublish {
-    pub message: RefCount<PublicationMessage>,
+    pub message: Idx<PublicationMessage>,
 }
 
 // Subscription
-#[derive(Debug, Clone, Display)]
-#[display("Subscriber({})", subscription.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RmwSubscriptionInit {
-    pub subscription: RefCount<Subscriber>,
+    pub subscription: Idx<Subscriber>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Subscriber({})", subscription.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclSubscriptionInit {
-    pub subscription: RefCount<Subscriber>,
+    pub subscription: Idx<Subscriber>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Subscriber({})", subscription.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppSubscriptionInit {
-    pub subscription: RefCount<Subscriber>,
+    pub subscription: Idx<Subscriber>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Callback({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppSubscriptionCallbackAdded {
-    pub callback: RefCount<Callback>,
+    pub callback: Idx<Callback>,
 }
 
 // Take
 
-#[derive(Debug, Clone, Display)]
-#[display("taken={taken}, Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RmwTake {
-    pub message: RefCount<SubscriptionMessage>,
+    pub message: Idx<SubscriptionMessage>,
     pub taken: bool,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclTake {
-    pub message: RefCount<SubscriptionMessage>,
+    pub message: Idx<SubscriptionMessage>,
     /// Specifies if the message is new, i.e., it was not taken before by RMW.
     pub is_new: bool,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Message({})", message.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclCppTake {
-    pub message: RefCount<SubscriptionMessage>,
+    pub message: Idx<SubscriptionMessage>,
     /// Specifies if the message is new, i.e., it was not taken before by RMW or RCL.
     pub is_new: bool,
 }
 
 // Service
 
-#[derive(Debug, Clone, Display)]
-#[display("Service({})", service.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclServiceInit {
-    pub service: RefCount<Service>,
+    pub service: Idx<Service>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Callback({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclCppServiceCallbackAdded {
-    pub callback: RefCount<Callback>,
+    pub callback: Idx<Callback>,
 }
 
 // Client
 
-#[derive(Debug, Clone, Display)]
-#[display("Client({})", client.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclClientInit {
-    pub client: RefCount<Client>,
+    pub client: Idx<Client>,
 }
 
 // Timer
 
-#[derive(Debug, Clone, Display)]
-#[display("Timer({})", timer.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclTimerInit {
-    pub timer: RefCount<Timer>,
+    pub timer: Idx<Timer>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Callback({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppTimerCallbackAdded {
-    pub callback: RefCount<Callback>,
+    pub callback: Idx<Callback>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("Timer({})", timer.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppTimerLinkNode {
-    pub timer: RefCount<Timer>,
+    pub timer: Idx<Timer>,
 }
 
 // Callback
 
-#[derive(Debug, Clone, Display)]
-#[display("Callback({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct RclcppCallbackRegister {
-    pub callback: RefCount<Callback>,
+    pub callback: Idx<Callback>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("CallbackInstance({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct CallbackStart {
     pub is_intra_process: bool,
-    pub callback: RefCount<CallbackInstance>,
+    pub callback: Idx<CallbackInstance>,
 }
 
-#[derive(Debug, Clone, Display)]
-#[display("CallbackInstance({})", callback.lock().unwrap())]
+#[derive(Debug, Clone)]
 pub struct CallbackEnd {
-    pub callback: RefCount<CallbackInstance>,
+    pub callback: Idx<CallbackInstance>,
+}
+
+impl DisplayWithModel for RclInit {
+    fn fmt_with_model(&self, _model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
 }
+
+impl DisplayWithModel for RmwTake {
+    fn fmt_with_model(&self, model: &Model, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "taken={}, Message({})",
+            self.taken,
+            model.display(&self.message)
+        )
+    }
+}
+
+impl_display_with_model!(
+    RclNodeInit => "Node({})", node;
+    RmwPublisherInit => "Publisher({})", publisher;
+    RclPublisherInit => "Publisher({})", publisher;
+    RclcppPublish => "Message({})", message;
+    RclcppIntraPublish => "Message({})", message;
+    RclPublish => "Message({})", message;
+    RmwPublish => "Message({})", message;
+    RmwSubscriptionInit => "Subscriber({})", subscription;
+    RclSubscriptionInit => "Subscriber({})", subscription;
+    RclcppSubscriptionInit => "Subscriber({})", subscription;
+    RclcppSubscriptionCallbackAdded => "Callback({})", callback;
+    RclTake => "Message({})", message;
+    RclCppTake => "Message({})", message;
+    RclServiceInit => "Service({})", service;
+    RclCppServiceCallbackAdded => "Callback({})", callback;
+    RclClientInit => "Client({})", client;
+    RclTimerInit => "Timer({})", timer;
+    RclcppTimerCallbackAdded => "Callback({})", callback;
+    RclcppTimerLinkNode => "Timer({})", timer;
+    RclcppCallbackRegister => "Callback({})", callback;
+    CallbackStart => "CallbackInstance({})", callback;
+    CallbackEnd => "CallbackInstance({})", callback;
+);
@@ -1,170 +1,65 @@
-use std::sync::{Arc, Mutex};
-
 use derive_more::derive::Display;
 use thiserror::Error;
 
 use crate::events_common::Context;
 use crate::model::{
-    AlreadyInitializedError, Callback, Client, Node, PublicationMessage, Publisher, Service,
-    Subscriber, SubscriptionMessage, Time, Timer,
+    AlreadyInitializedError, Callback, Client, DisplayWithModel, Idx, Model, ModelObject, Node,
+    PublicationMessage, Publisher, Service, Subscriber, SubscriptionMessage, Time, Timer,
 };
 use crate::raw_events;
-use crate::utils::CyclicDependency;
 
+/// Description of an object involved in a processing error.
+///
+/// The object is rendered when the error is created, so that the error does not keep
+/// the [`Model`] borrowed.
 #[derive(Debug, Display)]
-pub enum Object {
-    #[display("Node{}", _0.lock().unwrap())]
-    Node(Arc<Mutex<Node>>),
-
-    #[display("Subscriber{}", _0.lock().unwrap())]
-    Subscriber(Arc<Mutex<Subscriber>>),
-
-    #[display("Publisher{}", _0.lock().unwrap())]
-    Publisher(Arc<Mutex<Publisher>>),
-
-    #[display("Service{}", _0.lock().unwrap())]
-    Service(Arc<Mutex<Service>>),
-
-    #[display("Client{}", _0.lock().unwrap())]
-    Client(Arc<Mutex<Client>>),
-
-    #[display("Timer{}", _0.lock().unwrap())]
-    Timer(Arc<Mutex<Timer>>),
-
-    #[display("Callback{}", _0.lock().unwrap())]
-    Callback(Arc<Mutex<Callback>>),
-
-    #[display("PublishedMessage")]
-    PublishedMessage(Arc<Mutex<PublicationMessage>>),
-
-    #[display("SubscribedMessage")]
-    SubscribedMessage(Arc<Mutex<SubscriptionMessage>>),
+#[display("{object_type:?}{description}")]
+pub struct Object {
+    object_type: ObjectType,
+    description: String,
 }
 
 impl Object {
-    pub fn as_type(&self) -> ObjectType {
-        match self {
-            Self::Node(_) => ObjectType::Node,
-            Self::Subscriber(_) => ObjectType::Subscriber,
-            Self::Publisher(_) => ObjectType::Publisher,
-            Self::Service(_) => ObjectType::Service,
-            Self::Client(_) => ObjectType::Client,
-            Self::Timer(_) => ObjectType::Timer,
-            Self::Callback(_) => ObjectType::Callback,
-            Self::PublishedMessage(_) => ObjectType::PublishedMessage,
-            Self::SubscribedMessage(_) => ObjectType::SubscribedMessage,
+    pub fn new<T: ObjectKind + DisplayWithModel>(model: &Model, object: &T) -> Self {
+        Self {
+            object_type: T::OBJECT_TYPE,
+            description: model.display(object).to_string(),
         }
     }
-}
-
-impl From<Arc<Mutex<Node>>> for Object {
-    fn from(node_arc: Arc<Mutex<Node>>) -> Self {
-        Self::Node(node_arc)
-    }
-}
-
-impl From<Arc<Mutex<Subscriber>>> for Object {
-    fn from(subscriber_arc: Arc<Mutex<Subscriber>>) -> Self {
-        subscriber_arc.lock().unwrap().create_cycle();
-        Self::Subscriber(subscriber_arc)
-    }
-}
-
-impl From<Arc<Mutex<Publisher>>> for Object {
-    fn from(publisher_arc: Arc<Mutex<Publisher>>) -> Self {
-        publisher_arc.lock().unwrap().create_cycle();
-        Self::Publisher(publisher_arc)
-    }
-}
-
-impl From<Arc<Mutex<Service>>> for Object {
-    fn from(service_arc: Arc<Mutex<Service>>) -> Self {
-        service_arc.lock().unwrap().create_cycle();
-        Self::Service(service_arc)
-    }
-}
 
-impl From<Arc<Mutex<Client>>> for Object {
-    fn from(client_arc: Arc<Mutex<Client>>) -> Self {
-        client_arc.lock().unwrap().create_cycle();
-        Self::Client(client_arc)
+    pub const fn as_type(&self) -> ObjectType {
+        self.object_type
     }
 }
 
-impl From<Arc<Mutex<Timer>>> for Object {
-    fn from(timer_arc: Arc<Mutex<Timer>>) -> Self {
-        timer_arc.lock().unwrap().create_cycle();
-        Self::Timer(timer_arc)
-    }
+pub trait ObjectKind {
+    const OBJECT_TYPE: ObjectType;
 }
 
-impl From<Arc<Mutex<Callback>>> for Object {
-    fn from(callback_arc: Arc<Mutex<Callback>>) -> Self {
-        callback_arc.lock().unwrap().create_cycle();
-        Self::Callback(callback_arc)
-    }
+macro_rules! impl_object_kind {
+    ($($object:ident),* $(,)?) => {
+        $(
+            impl ObjectKind for $object {
+                const OBJECT_TYPE: ObjectType = ObjectType::$object;
+            }
+        )*
+    };
 }
 
-impl From<Arc<Mutex<PublicationMessage>>> for Object {
-    fn from(publication_arc: Arc<Mutex<PublicationMessage>>) -> Self {
-        Self::PublishedMessage(publication_arc)
-    }
-}
+impl_object_kind!(
+    Node, Subscriber, Publisher, Service, Client, Timer, Callback
+);
 
-impl From<Arc<Mutex<SubscriptionMessage>>> for Object {
-    fn from(subscription_arc: Arc<Mutex<SubscriptionMessage>>) -> Self {
-        Self::SubscribedMessage(subscription_arc)
-    }
+impl ObjectKind for PublicationMessage {
+    const OBJECT_TYPE: ObjectType = ObjectType::PublishedMessage;
 }
 
-impl<T> From<&T> for Object
-where
-    T: Into<Self> + Clone,
-{
-    fn from(value: &T) -> Self {
-        value.clone().into()
-    }
+impl ObjectKind for SubscriptionMessage {
+    const OBJECT_TYPE: ObjectType = ObjectType::SubscribedMessage;
 }
 
-impl<T> From<&mut T> for Object
-where
-    T: Into<Self> + Clone,
-{
-    fn from(value: &mut T) -> Self {
-        value.clone().into()
-    }
-}
-
-impl Drop for Object {
-    fn drop(&mut self) {
-        match self {
-            Self::Subscriber(subscriber) => {
-                let mut subscriber = subscriber.lock().unwrap();
-                subscriber.break_cycle();
-            }
-            Self::Publisher(publisher) => {
-                let mut publisher = publisher.lock().unwrap();
-                publisher.break_cycle();
-            }
-            Self::Service(service) => {
-                let mut service = service.lock().unwrap();
-                service.break_cycle();
-            }
-            Self::Client(client) => {
-                let mut client = client.lock().unwrap();
-                client.break_cycle();
-            }
-            Self::Timer(timer) => {
-                let mut timer = timer.lock().unwrap();
-                timer.break_cycle();
-            }
-            Self::Callback(callback) => {
-                let mut callback = callback.lock().unwrap();
-                callback.break_cycle();
-            }
-            _ => {}
-        }
-    }
+impl<T: ObjectKind + ModelObject> ObjectKind for Idx<T> {
+    const OBJECT_TYPE: ObjectType = T::OBJECT_TYPE;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -192,18 +87,18 @@ impl AlreadyExists {
     pub fn new(
         key: u64,
         key_variable: &'static str,
-        new_object: impl Into<Object>,
-        old_object: impl Into<Object>,
+        new_object: Object,
+        old_object: Object,
     ) -> Self {
         Self {
             key,
             key_variable,
-            new_object: new_object.into(),
-            old_object: old_object.into(),
+            new_object,
+            old_object,
         }
     }
 
-    pub fn with_id(id: u64, old_object: impl Into<Object>, new_object: impl Into<Object>) -> Self {
+    pub fn with_id(id: u64, old_object: Object, new_object: Object) -> Self {
         Self::new(id, stringify!(id), new_object, old_object)
     }
 
@@ -265,9 +160,9 @@ impl NotFound {
         Self::new(key, "address", ObjectType::PublishedMessage)
     }
 
-    pub fn dependent_object(self, object: impl Into<Object>) -> ObjectMissingDependency {
+    pub fn dependent_object(self, object: Object) -> ObjectMissingDependency {
         ObjectMissingDependency {
-            object: object.into(),
+            object,
             cause: self,
         }
     }
@@ -1,22 +1,26 @@
 mod error;
 mod r2r;
+mod retention;
 mod ros2;
 
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::{Arc, Mutex};
 
 use color_eyre::{Report, Result};
+use derive_more::derive::From;
 use thiserror::Error;
 
 use crate::events_common::{Context, Time};
 use crate::model::{
-    Callback, Client, Node, PublicationMessage, Publisher, Service, Subscriber,
-    SubscriptionMessage, Timer,
+    Callback, CallbackInstance, Client, Idx, Model, ModelObject, Node, PublicationMessage,
+    Publisher, Service, SpinInstance, Subscriber, SubscriptionMessage, Timer,
 };
 use crate::utils::Known;
 use crate::{processed_events, raw_events};
 
+pub use retention::MessageRetention;
+use retention::RetainedMessages;
+
 pub enum MaybeProcessed<P, R> {
     Processed(P),
     Raw(R),
@@ -93,54 +97,15 @@ impl<T> IntoId<T> for T {
     }
 }
 
-trait MapGetAsResult<K, V> {
-    fn get_or_err(&self, key: K, key_name: &'static str) -> Result<&Arc<Mutex<V>>, error::NotFound>
-    where
-        K: std::hash::Hash + Eq + Debug,
-        V: Debug;
-}
-
-impl MapGetAsResult<Id<u64>, Node> for HashMap<Id<u64>, Arc<Mutex<Node>>> {
-    fn get_or_err(
-        &self,
-        key: Id<u64>,
-        key_name: &'static str,
-    ) -> Result<&Arc<Mutex<Node>>, error::NotFound> {
-        self.get(&key)
-            .ok_or_else(|| error::NotFound::new(key.id, key_name, error::ObjectType::Node))
-    }
-}
-
-impl MapGetAsResult<Id<u64>, Subscriber> for HashMap<Id<u64>, Arc<Mutex<Subscriber>>> {
-    fn get_or_err(
-        &self,
-        key: Id<u64>,
-        key_name: &'static str,
-    ) -> Result<&Arc<Mutex<Subscriber>>, error::NotFound> {
-        self.get(&key)
-            .ok_or_else(|| error::NotFound::new(key.id, key_name, error::ObjectType::Subscriber))
-    }
+trait MapGetAsResult<V> {
+    fn get_or_err(&self, key: Id<u64>, key_name: &'static str) -> Result<Idx<V>, error::NotFound>;
 }
 
-impl MapGetAsResult<Id<u64>, Publisher> for HashMap<Id<u64>, Arc<Mutex<Publisher>>> {
-    fn get_or_err(
-        &self,
-        key: Id<u64>,
-        key_name: &'static str,
-    ) -> Result<&Arc<Mutex<Publisher>>, error::NotFound> {
+impl<V: ModelObject + error::ObjectKind> MapGetAsResult<V> for HashMap<Id<u64>, Idx<V>> {
+    fn get_or_err(&self, key: Id<u64>, key_name: &'static str) -> Result<Idx<V>, error::NotFound> {
         self.get(&key)
-            .ok_or_else(|| error::NotFound::new(key.id, key_name, error::ObjectType::Publisher))
-    }
-}
-
-impl MapGetAsResult<Id<u64>, Service> for HashMap<Id<u64>, Arc<Mutex<Service>>> {
-    fn get_or_err(
-        &self,
-        key: Id<u64>,
-        key_name: &'static str,
-    ) -> Result<&Arc<Mutex<Service>>, error::NotFound> {
-        self.get(&key)
-            .ok_or_else(|| error::NotFound::new(key.id, key_name, error::ObjectType::Service))
+            .copied()
+            .ok_or_else(|| error::NotFound::new(key.id, key_name, V::OBJECT_TYPE))
     }
 }
 
@@ -148,33 +113,54 @@ impl MapGetAsResult<Id<u64>, Service> for HashMap<Id<u64>, Arc<Mutex<Service>>>
 pub struct Processor {
     hostname_to_host_id: HashMap<String, u32>,
 
-    nodes_by_rcl: HashMap<Id<u64>, Arc<Mutex<Node>>>,
+    model: Model,
+
+    nodes_by_rcl: HashMap<Id<u64>, Idx<Node>>,
 
-    subscribers_by_rmw: HashMap<Id<u64>, Arc<Mutex<Subscriber>>>,
-    subscribers_by_rcl: HashMap<Id<u64>, Arc<Mutex<Subscriber>>>,
-    subscribers_by_rclcpp: HashMap<Id<u64>, Arc<Mutex<Subscriber>>>,
+    subscribers_by_rmw: HashMap<Id<u64>, Idx<Subscriber>>,
+    subscribers_by_rcl: HashMap<Id<u64>, Idx<Subscriber>>,
+    subscribers_by_rclcpp: HashMap<Id<u64>, Idx<Subscriber>>,
 
-    publishers_by_rmw: HashMap<Id<u64>, Arc<Mutex<Publisher>>>,
-    publishers_by_rcl: HashMap<Id<u64>, Arc<Mutex<Publisher>>>,
+    publishers_by_rmw: HashMap<Id<u64>, Idx<Publisher>>,
+    publishers_by_rcl: HashMap<Id<u64>, Idx<Publisher>>,
 
-    services_by_rcl: HashMap<Id<u64>, Arc<Mutex<Service>>>,
+    services_by_rcl: HashMap<Id<u64>, Idx<Service>>,
 
-    clients_by_rcl: HashMap<Id<u64>, Arc<Mutex<Client>>>,
+    clients_by_rcl: HashMap<Id<u64>, Idx<Client>>,
 
-    timers_by_rcl: HashMap<Id<u64>, Arc<Mutex<Timer>>>,
+    timers_by_rcl: HashMap<Id<u64>, Idx<Timer>>,
 
-    callbacks_by_id: HashMap<Id<u64>, Arc<Mutex<Callback>>>,
+    callbacks_by_id: HashMap<Id<u64>, Idx<Callback>>,
 
     /// Id by publication timestamp and topic
-    published_messages: HashMap<(i64, Known<String>), Arc<Mutex<PublicationMessage>>>,
+    published_messages: RetainedMessages<(i64, Known<String>), Idx<PublicationMessage>>,
     /// Id by message ptr
-    received_messages: HashMap<Id<u64>, Arc<Mutex<SubscriptionMessage>>>,
+    received_messages: RetainedMessages<Id<u64>, Idx<SubscriptionMessage>>,
 
     // temporary
     /// Id by message ptr
-    published_messages_by_rclcpp: HashMap<Id<u64>, Arc<Mutex<PublicationMessage>>>,
+    published_messages_by_rclcpp: HashMap<Id<u64>, Idx<PublicationMessage>>,
     /// Id by message ptr
-    published_messages_by_rcl: HashMap<Id<u64>, Arc<Mutex<PublicationMessage>>>,
+    published_messages_by_rcl: HashMap<Id<u64>, Idx<PublicationMessage>>,
+
+    /// Short-lived objects whose lifecycle ended with the last processed event.
+    ///
+    /// They are released before processing the next event, so the analyses can still look them up
+    /// while handling the event that ended their lifecycle.
+    pending_release: Vec<Released>,
+
+    retention: MessageRetention,
+    peak_retained_messages: usize,
+    evicted_messages: usize,
+}
+
+/// Short-lived object of the [`Model`] waiting to be released.
+#[derive(Debug, Clone, Copy, From)]
+enum Released {
+    PublicationMessage(Idx<PublicationMessage>),
+    SubscriptionMessage(Idx<SubscriptionMessage>),
+    CallbackInstance(Idx<CallbackInstance>),
+    SpinInstance(Idx<SpinInstance>),
 }
 
 impl Processor {
@@ -182,6 +168,100 @@ impl Processor {
         Self::default()
     }
 
+    /// Create a processor that evicts messages which can no longer be matched
+    /// according to the `retention` limits.
+    pub fn with_retention(retention: MessageRetention) -> Self {
+        let mut processor = Self::default();
+        let track_order = !retention.is_unbounded();
+        processor.published_messages.set_track_order(track_order);
+        processor.received_messages.set_track_order(track_order);
+        processor.retention = retention;
+        processor
+    }
+
+    /// Highest number of messages retained at once for matching.
+    pub const fn peak_retained_messages(&self) -> usize {
+        self.peak_retained_messages
+    }
+
+    /// Number of messages evicted before the end of the trace.
+    pub const fn evicted_messages(&self) -> usize {
+        self.evicted_messages
+    }
+
+    fn retained_messages(&self) -> usize {
+        self.published_messages.len()
+            + self.received_messages.len()
+            + self.published_messages_by_rclcpp.len()
+            + self.published_messages_by_rcl.len()
+    }
+
+    fn evict_messages(&mut self, time: Time) {
+        self.peak_retained_messages = self.peak_retained_messages.max(self.retained_messages());
+
+        if self.retention.is_unbounded() {
+            return;
+        }
+
+        let horizon = self.retention.horizon();
+        let model = &mut self.model;
+        self.evicted_messages += self.published_messages.evict(
+            time,
+            horizon,
+            self.retention.max_published(),
+            |message| {
+                model.release(message);
+            },
+        );
+        let pending_release = &mut self.pending_release;
+        self.evicted_messages +=
+            self.received_messages
+                .evict(time, horizon, self.retention.max_received(), |message| {
+                    Self::forget_received_message(model, pending_release, message);
+                });
+    }
+
+    /// Release the objects whose lifecycle ended with the previous event.
+    fn release_pending(&mut self) {
+        for object in self.pending_release.drain(..) {
+            match object {
+                Released::PublicationMessage(idx) => {
+                    self.model.release(idx);
+                }
+                Released::SubscriptionMessage(idx) => {
+                    self.model.release(idx);
+                }
+                Released::CallbackInstance(idx) => {
+                    self.model.release(idx);
+                }
+                Released::SpinInstance(idx) => {
+                    self.model.release(idx);
+                }
+            }
+        }
+    }
+
+    fn release_later(&mut self, object: impl Into<Released>) {
+        self.pending_release.push(object.into());
+    }
+
+    /// Release a message that is no longer retained for matching by its pointer.
+    ///
+    /// Messages taken by a subscriber are released once they are dropped from its queue or once
+    /// the callback they triggered ends. Only the messages unknown to any subscriber are released here.
+    fn forget_received_message(
+        model: &Model,
+        pending_release: &mut Vec<Released>,
+        message: Idx<SubscriptionMessage>,
+    ) {
+        if model
+            .get(message)
+            .is_some_and(|message| message.get_subscriber().is_none())
+        {
+            pending_release.push(message.into());
+        }
+    }
+
     fn host_to_host_id(&mut self, hostname: &str) -> u32 {
         let next_id = self
             .hostname_to_host_id
@@ -194,28 +274,26 @@ impl Processor {
             .or_insert(next_id)
     }
 
-    pub fn get_all_nodes(&self) -> Vec<Arc<Mutex<Node>>> {
-        self.nodes_by_rcl.values().cloned().collect()
+    /// Objects reconstructed from the events processed so far.
+    pub const fn model(&self) -> &Model {
+        &self.model
     }
 
-    pub fn print_objects(&self) {
-        println!("Nodes:");
-        for (i, node) in self.nodes_by_rcl.values().enumerate() {
-            let node = node.lock().unwrap();
-            print!(" [{i}] ");
-            node.print_node_info();
-        }
+    pub fn into_model(self) -> Model {
+        self.model
     }
 
-    fn get_timer_by_rcl_handle(&self, id: Id<u64>) -> Result<&Arc<Mutex<Timer>>, error::NotFound> {
+    fn get_timer_by_rcl_handle(&self, id: Id<u64>) -> Result<Idx<Timer>, error::NotFound> {
         self.timers_by_rcl
             .get(&id)
+            .copied()
             .ok_or(error::NotFound::timer(id.id))
     }
 
-    fn get_callback_by_id(&self, id: Id<u64>) -> Result<&Arc<Mutex<Callback>>, error::NotFound> {
+    fn get_callback_by_id(&self, id: Id<u64>) -> Result<Idx<Callback>, error::NotFound> {
         self.callbacks_by_id
             .get(&id)
+            .copied()
             .ok_or(error::NotFound::callback(id.id))
     }
 
@@ -223,6 +301,9 @@ impl Processor {
         &mut self,
         full_event: raw_events::FullEvent,
     ) -> Result<MaybeProcessed<processed_events::FullEvent, raw_events::FullEvent>> {
+        self.release_pending();
+        self.evict_messages(full_event.time);
+
         Ok(match full_event.event {
             raw_events::Event::Ros2(event) => {
                 match self.process_raw_ros2_event(&event, &full_event.context, full_event.time) {
@@ -335,3 +416,61 @@ impl Processor {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::NsDuration;
+
+    fn publish(
+        processor: &mut Processor,
+        time: i64,
+        timestamp: Option<i64>,
+    ) -> Idx<PublicationMessage> {
+        let event = raw_events::ros2::RmwPublish {
+            rmw_publisher_handle: None,
+            message: 0x10,
+            timestamp,
+        };
+        let full_event = raw_events::FullEvent {
+            context: Context::new(0, 100, 100, "talker".into(), "host".into()),
+            time: Time::from_nanos(time),
+            event: raw_events::ros2::Event::from(event).into(),
+        };
+        let Ok(MaybeProcessed::Processed(processed)) = processor.process_raw_event(full_event)
+        else {
+            panic!("rmw_publish should be processed");
+        };
+        let processed_events::Event::Ros2(processed_events::ros2::Event::RmwPublish(event)) =
+            processed.event
+        else {
+            panic!("Unexpected event {:?}", processed.event);
+        };
+        event.message
+    }
+
+    #[test]
+    fn test_unmatchable_publication_is_released_after_next_event() {
+        let mut processor = Processor::new();
+        let first = publish(&mut processor, 0, None);
+        assert!(processor.model().get(first).is_some());
+
+        let second = publish(&mut processor, 10, None);
+        assert!(processor.model().get(first).is_none());
+        assert!(processor.model().get(second).is_some());
+    }
+
+    #[test]
+    fn test_evicted_publication_is_released() {
+        let retention = MessageRetention::new(Some(NsDuration::from_nanos(100)), None);
+        let mut processor = Processor::with_retention(retention);
+        let first = publish(&mut processor, 0, Some(1));
+        let second = publish(&mut processor, 50, Some(2));
+        assert!(processor.model().get(first).is_some());
+
+        publish(&mut processor, 150, Some(3));
+        assert!(processor.model().get(first).is_none());
+        assert!(processor.model().get(second).is_some());
+        assert_eq!(processor.evicted_messages(), 1);
+    }
+}
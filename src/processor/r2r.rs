@@ -44,24 +44,21 @@ impl super::Processor {
     ) -> Result<processed_events::r2r::SpinStart> {
         let timeout = std::time::Duration::new(event.timeout_s, event.timeout_ns);
 
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.with_r2r_event(&event, time, context))?
-            .clone();
+            .map_err(|e| e.with_r2r_event(&event, time, context))?;
 
-        let spin_instance = SpinInstance::new(&node_arc, time, timeout);
-        {
-            let mut node = node_arc.lock().unwrap();
-            let old = node.replace_spin_instance(spin_instance.clone());
+        let spin_instance = self.model.add(SpinInstance::new(node, time, timeout));
+        let old = self.model[node].replace_spin_instance(spin_instance);
 
-            if let Some(old) = old {
-                log::warn!(target:"r2r::spin_start","Node {node} has a spin instance already set: {old:?}");
-            }
+        if let Some(old) = old {
+            log::warn!(target:"r2r::spin_start","Node {} has a spin instance already set: {:?}", self.model[node], self.model[old]);
+            self.release_later(old);
         }
 
         Ok(processed_events::r2r::SpinStart {
-            node: node_arc,
+            node,
             spin: spin_instance,
         })
     }
@@ -73,25 +70,18 @@ impl super::Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::r2r::SpinEnd> {
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.with_r2r_event(&event, time, context))?
-            .clone();
-
-        let spin = node_arc
-            .lock()
-            .unwrap()
-            .take_spin_instance()
-            .inspect(|spin_arc| {
-                let mut spin = spin_arc.lock().unwrap();
-                spin.set_end_time(time);
-            });
-
-        Ok(processed_events::r2r::SpinEnd {
-            node: node_arc,
-            spin,
-        })
+            .map_err(|e| e.with_r2r_event(&event, time, context))?;
+
+        let spin = self.model[node].take_spin_instance();
+        if let Some(spin) = spin {
+            self.model[spin].set_end_time(time);
+            self.release_later(spin);
+        }
+
+        Ok(processed_events::r2r::SpinEnd { node, spin })
     }
 
     fn process_spin_wake(
@@ -101,24 +91,17 @@ impl super::Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::r2r::SpinWake> {
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.with_r2r_event(&event, time, context))?
-            .clone();
-
-        let node = node_arc.lock().unwrap();
-        let spin_arc = node.borrow_spin_instance();
-        let spin = spin_arc.cloned().inspect(|spin_arc| {
-            let mut spin = spin_arc.lock().unwrap();
-            spin.set_wake_time(time);
-        });
-        drop(node);
-
-        Ok(processed_events::r2r::SpinWake {
-            node: node_arc,
-            spin,
-        })
+            .map_err(|e| e.with_r2r_event(&event, time, context))?;
+
+        let spin = self.model[node].get_spin_instance();
+        if let Some(spin) = spin {
+            self.model[spin].set_wake_time(time);
+        }
+
+        Ok(processed_events::r2r::SpinWake { node, spin })
     }
 
     fn process_spin_timeout(
@@ -128,24 +111,18 @@ impl super::Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::r2r::SpinTimeout> {
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.with_r2r_event(&event, time, context))?
-            .clone();
-
-        let mut node = node_arc.lock().unwrap();
-        let spin_arc = node.take_spin_instance();
-        let spin = spin_arc.inspect(|spin_arc| {
-            let mut spin = spin_arc.lock().unwrap();
-            spin.set_timeouted_on(time);
-        });
-        drop(node);
-
-        Ok(processed_events::r2r::SpinTimeout {
-            node: node_arc,
-            spin,
-        })
+            .map_err(|e| e.with_r2r_event(&event, time, context))?;
+
+        let spin = self.model[node].take_spin_instance();
+        if let Some(spin) = spin {
+            self.model[spin].set_timeouted_on(time);
+            self.release_later(spin);
+        }
+
+        Ok(processed_events::r2r::SpinTimeout { node, spin })
     }
 
     fn process_update_time(
@@ -155,15 +132,14 @@ impl super::Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::r2r::UpdateTime> {
-        let subscriber_arc = self
+        let subscriber = self
             .subscribers_by_rcl
             .get_or_err(event.subscriber.into_id(context_id), "rcl_handle")
             .map_err(|e| e.with_r2r_event(&event, time, context))
-            .wrap_err("Subscriber not found for update_time event")?
-            .clone();
+            .wrap_err("Subscriber not found for update_time event")?;
 
         Ok(processed_events::r2r::UpdateTime {
-            subscriber: subscriber_arc,
+            subscriber,
             time_s: event.time_s,
             time_ns: event.time_ns,
         })
@@ -0,0 +1,227 @@
+use crate::model::{PublicationMessage, SubscriptionMessage, Time};
+use crate::utils::{ByteSize, NsDuration};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Approximate memory used by a retained message besides the message itself.
+///
+/// It covers the arena slot, the map entry, the eviction queue entry
+/// and the data referenced by the message (e.g. topic name).
+const RETAINED_MESSAGE_OVERHEAD: usize = 128;
+
+/// Limits of the messages retained by the [`Processor`](super::Processor) for matching
+/// publications with subscriptions.
+///
+/// Without limits, every published message is kept until the end of the trace.
+/// Evicted published messages are released from the [`Model`](crate::model::Model), so the
+/// subscription messages matched with them no longer refer to their publication.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessageRetention {
+    /// Messages retained longer than the horizon (in nanoseconds of trace time) are evicted.
+    horizon: Option<i64>,
+
+    /// Maximum number of retained published messages.
+    max_published: Option<usize>,
+
+    /// Maximum number of retained received messages.
+    max_received: Option<usize>,
+}
+
+impl MessageRetention {
+    /// Create the retention limits from the matching horizon and the memory limit.
+    ///
+    /// The memory limit is split equally between published and received messages and
+    /// converted to a number of messages using an approximate size of a message.
+    pub fn new(horizon: Option<NsDuration>, max_memory: Option<ByteSize>) -> Self {
+        let max_messages = |message_size: usize| {
+            max_memory.map(|max_memory| {
+                let bytes = usize::try_from(max_memory.as_bytes() / 2).unwrap_or(usize::MAX);
+                (bytes / (message_size + RETAINED_MESSAGE_OVERHEAD)).max(1)
+            })
+        };
+
+        Self {
+            horizon: horizon.map(NsDuration::as_nanos),
+            max_published: max_messages(size_of::<PublicationMessage>()),
+            max_received: max_messages(size_of::<SubscriptionMessage>()),
+        }
+    }
+
+    pub const fn is_unbounded(&self) -> bool {
+        self.horizon.is_none() && self.max_published.is_none() && self.max_received.is_none()
+    }
+
+    pub(super) const fn max_published(&self) -> Option<usize> {
+        self.max_published
+    }
+
+    pub(super) const fn max_received(&self) -> Option<usize> {
+        self.max_received
+    }
+
+    pub(super) const fn horizon(&self) -> Option<i64> {
+        self.horizon
+    }
+}
+
+/// Messages waiting to be matched, evicted in the order of insertion.
+///
+/// The messages are stored in the [`Model`](crate::model::Model), the values are their indices.
+#[derive(Debug)]
+pub(super) struct RetainedMessages<K, V> {
+    messages: HashMap<K, V>,
+
+    /// Insertion time of the messages, oldest first.
+    ///
+    /// Entries of messages that were already removed or replaced are skipped during eviction.
+    order: VecDeque<(Time, K, V)>,
+
+    /// Track the insertion order. Disabled when no retention limit is set.
+    track_order: bool,
+}
+
+impl<K, V> Default for RetainedMessages<K, V> {
+    fn default() -> Self {
+        Self {
+            messages: HashMap::new(),
+            order: VecDeque::new(),
+            track_order: false,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Copy + Eq> RetainedMessages<K, V> {
+    pub(super) fn set_track_order(&mut self, track_order: bool) {
+        self.track_order = track_order;
+        if !track_order {
+            self.order.clear();
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub(super) fn get(&self, key: &K) -> Option<V> {
+        self.messages.get(key).copied()
+    }
+
+    pub(super) fn insert(&mut self, time: Time, key: K, message: V) -> Option<V> {
+        if self.track_order {
+            self.order.push_back((time, key.clone(), message));
+        }
+        self.messages.insert(key, message)
+    }
+
+    pub(super) fn remove(&mut self, key: &K) -> Option<V> {
+        self.messages.remove(key)
+    }
+
+    /// Whether the queued `message` is still retained under `key`.
+    fn is_live(messages: &HashMap<K, V>, key: &K, message: V) -> bool {
+        messages.get(key) == Some(&message)
+    }
+
+    /// Evict messages inserted before `now - horizon` and the oldest messages exceeding
+    /// `max_messages`. Each evicted message is passed to `on_evict`.
+    /// Returns the number of evicted messages.
+    pub(super) fn evict(
+        &mut self,
+        now: Time,
+        horizon: Option<i64>,
+        max_messages: Option<usize>,
+        mut on_evict: impl FnMut(V),
+    ) -> usize {
+        let mut evicted = 0;
+
+        while let Some((time, key, message)) = self.order.front() {
+            let live = Self::is_live(&self.messages, key, *message);
+            let expired = horizon
+                .is_some_and(|horizon| now.timestamp_nanos() - time.timestamp_nanos() > horizon);
+            let over_limit = max_messages.is_some_and(|max| self.messages.len() > max);
+
+            if live && !expired && !over_limit {
+                break;
+            }
+
+            let (_, key, message) = self.order.pop_front().unwrap();
+            if live {
+                self.messages.remove(&key);
+                on_evict(message);
+                evicted += 1;
+            }
+        }
+
+        // Drop entries of removed messages that are stuck behind a live message
+        if self.order.len() > 2 * self.messages.len() + 64 {
+            let messages = &self.messages;
+            self.order
+                .retain(|(_, key, message)| Self::is_live(messages, key, *message));
+        }
+
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Insert a message identified by its insertion time at each of the `times`.
+    fn retained(
+        horizon: Option<i64>,
+        max_messages: Option<usize>,
+        times: &[i64],
+    ) -> (RetainedMessages<i64, i64>, Vec<i64>) {
+        let mut messages = RetainedMessages::default();
+        messages.set_track_order(true);
+        let mut evicted = Vec::new();
+        for &time in times {
+            let now = Time::from_nanos(time);
+            messages.insert(now, time, time);
+            messages.evict(now, horizon, max_messages, |message| evicted.push(message));
+        }
+        (messages, evicted)
+    }
+
+    #[test]
+    fn test_evict_by_horizon() {
+        let (messages, evicted) = retained(Some(10), None, &[0, 5, 10, 15, 20, 30]);
+        assert_eq!(evicted, [0, 5, 10, 15]);
+        assert!(messages.get(&15).is_none());
+        assert!(messages.get(&20).is_some());
+        assert!(messages.get(&30).is_some());
+    }
+
+    #[test]
+    fn test_evict_by_count() {
+        let (messages, evicted) = retained(None, Some(2), &[0, 1, 2, 3]);
+        assert_eq!(evicted, [0, 1]);
+        assert_eq!(messages.len(), 2);
+        assert!(messages.get(&2).is_some());
+    }
+
+    #[test]
+    fn test_removed_messages_are_not_evicted() {
+        let (mut messages, _) = retained(Some(10), None, &[0, 5]);
+        let replacement = 12;
+        messages.remove(&0);
+        messages.insert(Time::from_nanos(12), 5, replacement);
+
+        // The entry of the replaced message must not evict the replacement
+        let mut evicted = Vec::new();
+        let on_evict = |message| evicted.push(message);
+        assert_eq!(
+            messages.evict(Time::from_nanos(20), Some(10), None, on_evict),
+            0
+        );
+        assert_eq!(messages.get(&5), Some(replacement));
+        let on_evict = |message| evicted.push(message);
+        assert_eq!(
+            messages.evict(Time::from_nanos(23), Some(10), None, on_evict),
+            1
+        );
+        assert_eq!(messages.len(), 0);
+        assert_eq!(evicted, [replacement]);
+    }
+}
@@ -1,16 +1,16 @@
 use std::borrow::ToOwned;
 use std::collections::hash_map::Entry;
-use std::sync::{Arc, Mutex};
 
 use crate::events_common::Context;
 use crate::model::{
-    Callback, CallbackCaller, CallbackInstance, Client, Node, PublicationMessage, Publisher,
-    Service, Subscriber, SubscriptionMessage, Time, Timer,
+    Callback, CallbackInstance, CallbackTrigger, Client, Idx, Model, Node, PublicationMessage,
+    Publisher, Service, Subscriber, SubscriptionMessage, Time, Timer,
 };
 use crate::utils::Known;
 use crate::{processed_events, raw_events};
 
-use super::{ContextId, IntoId, MapGetAsResult, Processor, error};
+use super::error::{self, Object};
+use super::{ContextId, Id, IntoId, MapGetAsResult, Processor};
 
 use color_eyre::Result;
 use color_eyre::eyre::{Context as _, eyre};
@@ -24,25 +24,23 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> processed_events::ros2::RclNodeInit {
-        let node_arc = match self
+        let node = match self
             .nodes_by_rcl
             .entry(event.node_handle.into_id(context_id))
         {
             Entry::Occupied(entry) => {
-                let mut node = entry.get().lock().unwrap();
-                node.rcl_init(event.rmw_handle, &event.node_name, &event.namespace);
-                entry.get().clone()
+                let node = *entry.get();
+                self.model[node].rcl_init(event.rmw_handle, &event.node_name, &event.namespace);
+                node
             }
             Entry::Vacant(entry) => {
                 let mut node = Node::new(event.rmw_handle);
                 node.rcl_init(event.rmw_handle, &event.node_name, &event.namespace);
-                let node_arc = Arc::new(Mutex::new(node));
-                entry.insert(node_arc.clone());
-                node_arc
+                *entry.insert(self.model.add(node))
             }
         };
 
-        processed_events::ros2::RclNodeInit { node: node_arc }
+        processed_events::ros2::RclNodeInit { node }
     }
 
     pub(super) fn process_rmw_publisher_init(
@@ -52,15 +50,12 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> processed_events::ros2::RmwPublisherInit {
-        let publisher_arc = self
+        let publisher = self
             .publishers_by_rmw
             .entry(event.rmw_publisher_handle.into_id(context_id))
-            .or_default();
+            .or_insert_with(|| self.model.add(Publisher::default()));
 
-        let init_result = publisher_arc
-            .lock()
-            .unwrap()
-            .rmw_init(event.rmw_publisher_handle, event.gid);
+        let init_result = self.model[*publisher].rmw_init(event.rmw_publisher_handle, event.gid);
 
         if let Err(_e) = init_result {
             log::warn!(
@@ -68,19 +63,17 @@ impl Processor {
                 "Repeated initialization for handle {}. Assuming old publisher was deleted. Creating new.",
                 event.rmw_publisher_handle
             );
-            let mut publisher = publisher_arc.lock().unwrap();
-            publisher.mark_removed();
-            drop(publisher);
+            self.model[*publisher].mark_removed();
 
-            let mut publisher = Publisher::default();
-            publisher
+            let mut new_publisher = Publisher::default();
+            new_publisher
                 .rmw_init(event.rmw_publisher_handle, event.gid)
                 .expect("New Publisher should not be initialized yet");
-            *publisher_arc = Arc::new(Mutex::new(publisher));
+            *publisher = self.model.add(new_publisher);
         }
 
         processed_events::ros2::RmwPublisherInit {
-            publisher: publisher_arc.clone(),
+            publisher: *publisher,
         }
     }
 
@@ -91,7 +84,7 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> processed_events::ros2::RclPublisherInit {
-        let publisher_by_rmw_arc = self
+        let publisher_by_rmw = self
             .publishers_by_rmw
             .entry(event.rmw_publisher_handle.into_id(context_id))
             .or_insert_with_key(|key| {
@@ -99,10 +92,10 @@ impl Processor {
                 publisher
                     .set_rmw_handle(key.id)
                     .expect("New Publisher should not be initialized yet");
-                Arc::new(Mutex::new(publisher))
+                self.model.add(publisher)
             });
 
-        let node_arc = self
+        let node = *self
             .nodes_by_rcl
             .entry(event.node_handle.into_id(context_id))
             .or_insert_with(|| {
@@ -112,26 +105,23 @@ impl Processor {
                     event.topic_name, "/rosout",
                     "Node not found for publisher: {event:?}"
                 );
-                let node = Node::new(event.node_handle);
-                Arc::new(Mutex::new(node))
+                self.model.add(Node::new(event.node_handle))
             });
 
-        let init_result = publisher_by_rmw_arc.lock().unwrap().rcl_init(
+        let init_result = self.model[*publisher_by_rmw].rcl_init(
             event.publisher_handle,
             event.topic_name.clone(),
             event.queue_depth,
-            Arc::downgrade(node_arc),
+            node,
         );
 
-        let publisher_arc = if let Err(_e) = init_result {
+        let publisher = if let Err(_e) = init_result {
             log::warn!(
                 target: "rcl_publisher_init",
                 "Repeated initialization for handle {}. Assuming old publisher was deleted. Creating new.",
                 event.publisher_handle
             );
-            let mut old_publisher = publisher_by_rmw_arc.lock().unwrap();
-            old_publisher.mark_removed();
-            drop(old_publisher);
+            self.model[*publisher_by_rmw].mark_removed();
 
             let mut publisher = Publisher::default();
             publisher
@@ -139,34 +129,30 @@ impl Processor {
                     event.publisher_handle,
                     event.topic_name.clone(),
                     event.queue_depth,
-                    Arc::downgrade(node_arc),
+                    node,
                 )
                 .expect("New Publisher should not be initialized yet");
-            let publisher_arc = Arc::new(Mutex::new(publisher));
-            *publisher_by_rmw_arc = publisher_arc.clone();
+            let publisher = self.model.add(publisher);
+            *publisher_by_rmw = publisher;
 
-            publisher_arc
+            publisher
         } else {
-            publisher_by_rmw_arc.clone()
+            *publisher_by_rmw
         };
 
         self.publishers_by_rcl
-            .insert(event.publisher_handle.into_id(context_id), publisher_arc.clone()).inspect(|old| {
+            .insert(event.publisher_handle.into_id(context_id), publisher).inspect(|&old| {
+                let old = &mut self.model[old];
                 log::warn!(
                     target: "rcl_publisher_init",
                     "Found different Publisher with same address. Assuming old Publisher was deleted: old={old:?}"
                 );
-                old.lock().unwrap().mark_removed();
+                old.mark_removed();
             });
 
-        node_arc
-            .lock()
-            .unwrap()
-            .add_publisher(publisher_arc.clone());
+        self.model[node].add_publisher(publisher);
 
-        processed_events::ros2::RclPublisherInit {
-            publisher: publisher_arc.clone(),
-        }
+        processed_events::ros2::RclPublisherInit { publisher }
     }
 
     pub(super) fn process_rclcpp_publish(
@@ -178,13 +164,15 @@ impl Processor {
     ) -> processed_events::ros2::RclcppPublish {
         let mut message = PublicationMessage::new(event.message);
         message.rclcpp_publish(time);
-        let message_arc = Arc::new(Mutex::new(message));
-        self.published_messages_by_rclcpp
-            .insert(event.message.into_id(context_id), message_arc.clone());
-
-        processed_events::ros2::RclcppPublish {
-            message: message_arc,
+        let message = self.model.add(message);
+        if let Some(old) = self
+            .published_messages_by_rclcpp
+            .insert(event.message.into_id(context_id), message)
+        {
+            self.release_later(old);
         }
+
+        processed_events::ros2::RclcppPublish { message }
     }
 
     pub(super) fn process_rcl_publish(
@@ -195,17 +183,15 @@ impl Processor {
         _context: &Context,
     ) -> processed_events::ros2::RclPublish {
         let id = event.message.into_id(context_id);
-        let message_arc = self
+        let message = self
             .published_messages_by_rclcpp
             .remove(&id)
-            .unwrap_or_else(|| {
-                let message = PublicationMessage::new(event.message);
-                Arc::new(Mutex::new(message))
-            });
-        self.published_messages_by_rcl
-            .insert(id, message_arc.clone());
+            .unwrap_or_else(|| self.model.add(PublicationMessage::new(event.message)));
+        if let Some(old) = self.published_messages_by_rcl.insert(id, message) {
+            self.release_later(old);
+        }
 
-        let publisher = self
+        let publisher = *self
             .publishers_by_rcl
             .entry(event.publisher_handle.into_id(context_id))
             .or_insert_with_key(|key| {
@@ -216,19 +202,14 @@ impl Processor {
                     .set_rcl_handle(key.id)
                     .expect("New Publisher should not be initialized yet");
 
-                Arc::new(Mutex::new(publisher))
-            })
-            .clone();
+                self.model.add(publisher)
+            });
 
-        {
-            let mut message = message_arc.lock().unwrap();
-            message.set_publisher(publisher);
-            message.rcl_publish(time);
-        }
+        let message_data = &mut self.model[message];
+        message_data.set_publisher(publisher);
+        message_data.rcl_publish(time);
 
-        processed_events::ros2::RclPublish {
-            message: message_arc,
-        }
+        processed_events::ros2::RclPublish { message }
     }
 
     pub(super) fn process_rmw_publish(
@@ -238,29 +219,25 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> processed_events::ros2::RmwPublish {
-        let message_arc = self
+        let message_idx = self
             .published_messages_by_rcl
             .remove(&event.message.into_id(context_id))
-            .unwrap_or_else(|| Arc::new(Mutex::new(PublicationMessage::new(event.message))));
-        let mut message = message_arc.lock().unwrap();
+            .unwrap_or_else(|| self.model.add(PublicationMessage::new(event.message)));
         let publisher_id = event.rmw_publisher_handle.map(|h| h.into_id(context_id));
-        let publisher_arc = publisher_id.and_then(|id| self.publishers_by_rmw.get(&id));
-        let topic: Known<String> = match (publisher_arc, message.get_publisher()) {
-            (Some(publisher_arc), Some(message_publisher_arc)) => {
-                let message_publisher = message_publisher_arc.lock().unwrap();
-                if Arc::ptr_eq(publisher_arc, &message_publisher_arc) {
-                    message_publisher.get_topic().map(ToOwned::to_owned)
-                } else if message_publisher.is_stub() {
-                    message.replace_publisher(publisher_arc.clone());
-                    let mut publisher = publisher_arc.lock().unwrap();
-                    self.publishers_by_rcl.insert(
-                        message_publisher
-                            .get_rcl_handle()
-                            .unwrap()
-                            .into_id(context_id),
-                        publisher_arc.clone(),
-                    );
-                    publisher.change_rcl_handle(message_publisher.get_rcl_handle().unwrap());
+        let publisher = publisher_id.and_then(|id| self.publishers_by_rmw.get(&id).copied());
+        let topic: Known<String> = match (publisher, self.model[message_idx].get_publisher()) {
+            (Some(publisher), Some(message_publisher)) => {
+                if publisher == message_publisher {
+                    self.model[message_publisher]
+                        .get_topic()
+                        .map(ToOwned::to_owned)
+                } else if self.model[message_publisher].is_stub() {
+                    self.model[message_idx].replace_publisher(publisher);
+                    let rcl_handle = self.model[message_publisher].get_rcl_handle().unwrap();
+                    self.publishers_by_rcl
+                        .insert(rcl_handle.into_id(context_id), publisher);
+                    let publisher = &mut self.model[publisher];
+                    publisher.change_rcl_handle(rcl_handle);
                     publisher.get_topic().map(ToOwned::to_owned)
                 } else {
                     log::warn!(target: "rmw_publish",
@@ -269,43 +246,40 @@ impl Processor {
                     Known::Unknown
                 }
             }
-            (Some(publisher_arc), None) => {
-                message.set_publisher(publisher_arc.clone());
-                publisher_arc
-                    .lock()
-                    .unwrap()
-                    .get_topic()
-                    .map(ToOwned::to_owned)
+            (Some(publisher), None) => {
+                self.model[message_idx].set_publisher(publisher);
+                self.model[publisher].get_topic().map(ToOwned::to_owned)
             }
-            (None, Some(message_publisher_arc)) => message_publisher_arc
-                .lock()
-                .unwrap()
+            (None, Some(message_publisher)) => self.model[message_publisher]
                 .get_topic()
                 .map(ToOwned::to_owned),
             (None, None) => Known::Unknown,
         };
 
         if let Some(timestamp) = event.timestamp {
-            message.rmw_publish(time, timestamp);
-
-            self.published_messages
-                        .insert((timestamp, topic), message_arc.clone())
-                        .inspect(|old| {
-                            log::warn!(
-                                target: "rmw_publish",
-                                "Replacing different PublicationMessage with same sender timestamp. old_message={old:?}"
-                            );
-                        });
+            self.model[message_idx].rmw_publish(time, timestamp);
+
+            if let Some(old) = self
+                .published_messages
+                .insert(time, (timestamp, topic), message_idx)
+            {
+                log::warn!(
+                    target: "rmw_publish",
+                    "Replacing different PublicationMessage with same sender timestamp. old_message={:?}",
+                    self.model.get(old)
+                );
+                self.release_later(old);
+            }
         } else {
             log::warn!(target: "rmw_publish",
                         "Missing timestamp for RMW publish event. Subscription messages will not match it: [{time}] {event:?} {context:?}");
 
-            message.rmw_publish_old(time);
+            self.model[message_idx].rmw_publish_old(time);
+            self.release_later(message_idx);
         }
-        drop(message);
 
         processed_events::ros2::RmwPublish {
-            message: message_arc,
+            message: message_idx,
         }
     }
 
@@ -316,25 +290,21 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclcppIntraPublish> {
-        let message_arc = self
+        let message = *self
             .published_messages_by_rclcpp
             .get(&event.message.into_id(context_id))
             .ok_or(error::NotFound::published_message(event.message))
-            .map_err(|e| e.with_ros2_event(event, time, context))?
-            .clone();
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
         // TODO: check if event has rcl handle, rclcpp handle or other.
-        let publisher_arc = self
+        let publisher = self
             .publishers_by_rcl
             .get_or_err(event.publisher_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.with_ros2_event(event, time, context))?
-            .clone();
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        message_arc.lock().unwrap().set_publisher(publisher_arc);
+        self.model[message].set_publisher(publisher);
 
-        Ok(processed_events::ros2::RclcppIntraPublish {
-            message: message_arc,
-        })
+        Ok(processed_events::ros2::RclcppIntraPublish { message })
     }
 
     pub(super) fn process_rmw_subscription_init(
@@ -344,15 +314,13 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> processed_events::ros2::RmwSubscriptionInit {
-        let subscriber_arc = self
+        let subscriber = self
             .subscribers_by_rmw
             .entry(event.rmw_subscription_handle.into_id(context_id))
-            .or_default();
+            .or_insert_with(|| self.model.add(Subscriber::default()));
 
-        let init_result = subscriber_arc
-            .lock()
-            .unwrap()
-            .rmw_init(event.rmw_subscription_handle, event.gid);
+        let init_result =
+            self.model[*subscriber].rmw_init(event.rmw_subscription_handle, event.gid);
 
         if let Err(_e) = init_result {
             log::warn!(
@@ -360,19 +328,17 @@ impl Processor {
                 "Repeated initialization for handle {}. Assuming old subscriber was deleted. Creating new.",
                 event.rmw_subscription_handle
             );
-            let mut old_subscriber = subscriber_arc.lock().unwrap();
-            old_subscriber.mark_removed();
-            drop(old_subscriber);
+            self.model[*subscriber].mark_removed();
 
-            let mut subscriber = Subscriber::default();
-            subscriber
+            let mut new_subscriber = Subscriber::default();
+            new_subscriber
                 .rmw_init(event.rmw_subscription_handle, event.gid)
                 .expect("New Subscriber should not be initialized yet");
-            *subscriber_arc = Arc::new(Mutex::new(subscriber));
+            *subscriber = self.model.add(new_subscriber);
         }
 
         processed_events::ros2::RmwSubscriptionInit {
-            subscription: subscriber_arc.clone(),
+            subscription: *subscriber,
         }
     }
 
@@ -383,7 +349,7 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclSubscriptionInit> {
-        let subscriber_arc = self
+        let subscriber = self
             .subscribers_by_rmw
             .entry(event.rmw_subscription_handle.into_id(context_id))
             .or_insert_with_key(|key| {
@@ -391,21 +357,20 @@ impl Processor {
                 subscriber
                     .set_rmw_handle(key.id)
                     .expect("New Subscriber should not have rwm handle yet");
-                Arc::new(Mutex::new(subscriber))
+                self.model.add(subscriber)
             });
 
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.dependent_object(&*subscriber_arc))
-            .map_err(|e| e.with_ros2_event(event, time, context))?
-            .clone();
+            .map_err(|e| e.dependent_object(Object::new(&self.model, &*subscriber)))
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        let init_result = subscriber_arc.lock().unwrap().rcl_init(
+        let init_result = self.model[*subscriber].rcl_init(
             event.subscription_handle,
             event.topic_name.clone(),
             event.queue_depth,
-            Arc::downgrade(&node_arc),
+            node,
         );
 
         if let Err(_e) = init_result {
@@ -414,42 +379,39 @@ impl Processor {
                 "Repeated initialization for handle {}. Assuming old subscriber was deleted. Creating new.",
                 event.subscription_handle
             );
-            let mut old_subscriber = subscriber_arc.lock().unwrap();
-            old_subscriber.mark_removed();
-            drop(old_subscriber);
+            self.model[*subscriber].mark_removed();
 
-            let mut subscriber = Subscriber::default();
-            subscriber
+            let mut new_subscriber = Subscriber::default();
+            new_subscriber
                 .set_rmw_handle(event.rmw_subscription_handle)
                 .expect("New Subscriber should not have rwm handle yet");
-            subscriber
+            new_subscriber
                 .rcl_init(
                     event.subscription_handle,
                     event.topic_name.clone(),
                     event.queue_depth,
-                    Arc::downgrade(&node_arc),
+                    node,
                 )
                 .expect("New Subscriber should not be initialized yet");
-            *subscriber_arc = Arc::new(Mutex::new(subscriber));
+            *subscriber = self.model.add(new_subscriber);
         }
+        let subscriber = *subscriber;
 
         self.subscribers_by_rcl
-            .insert(event.subscription_handle.into_id(context_id), subscriber_arc.clone())
-            .inspect(|old| {
+            .insert(event.subscription_handle.into_id(context_id), subscriber)
+            .inspect(|&old| {
+                let old = &mut self.model[old];
                 log::warn!(
                     target: "rcl_subscription_init",
                     "Found different Subscriber with same address. Assuming old Subscriber was deleted: old={old:?}"
                 );
-                old.lock().unwrap().mark_removed();
+                old.mark_removed();
             });
 
-        node_arc
-            .lock()
-            .unwrap()
-            .add_subscriber(subscriber_arc.clone());
+        self.model[node].add_subscriber(subscriber);
 
         Ok(processed_events::ros2::RclSubscriptionInit {
-            subscription: subscriber_arc.clone(),
+            subscription: subscriber,
         })
     }
 
@@ -460,32 +422,30 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclcppSubscriptionInit> {
-        let subscriber_arc = self
+        let subscriber = self
             .subscribers_by_rcl
             .get_or_err(event.subscription_handle.into_id(context_id), "rcl_handle")
             .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        subscriber_arc
-            .lock()
-            .unwrap()
+        self.model[subscriber]
             .rclcpp_init(event.subscription)
-            .map_err(|e| error::Causes::AlreadyInitialized(e, subscriber_arc.clone().into()))?;
+            .map_err(|e| {
+                error::Causes::AlreadyInitialized(e, Object::new(&self.model, &subscriber))
+            })?;
 
         self.subscribers_by_rclcpp
-            .insert(
-                event.subscription.into_id(context_id),
-                subscriber_arc.clone(),
-            )
-            .inspect(|old| {
+            .insert(event.subscription.into_id(context_id), subscriber)
+            .inspect(|&old| {
+                let old = &mut self.model[old];
                 log::warn!(
                     target: "rclcpp_subscription_init",
                     "Found different Subscriber with same address. Assuming old Subscriber was deleted: old={old:?}"
                 );
-                old.lock().unwrap().mark_removed();
+                old.mark_removed();
             });
 
         Ok(processed_events::ros2::RclcppSubscriptionInit {
-            subscription: subscriber_arc.clone(),
+            subscription: subscriber,
         })
     }
 
@@ -496,39 +456,27 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclcppSubscriptionCallbackAdded> {
-        let subscription_arc = self
+        let subscription = self
             .subscribers_by_rclcpp
             .get_or_err(event.subscription.into_id(context_id), "rclcpp_handle")
-            .map_err(|e| e.with_ros2_event(event, time, context))?
-            .clone();
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        let callback_arc = Callback::new_subscription(
+        let callback = self.model.add(Callback::new_subscription(
             event.callback,
-            &subscription_arc,
+            subscription,
             context.hostname().to_owned(),
-        );
+        ));
 
-        self.callbacks_by_id
-            .insert(event.callback.into_id(context_id), callback_arc.clone())
-            .and_then(filter_out_removed_callers)
-            .map_or(Ok(()), |old: Arc<Mutex<Callback>>| {
-                Err(
-                    error::AlreadyExists::with_id(event.callback, &callback_arc, old)
-                        .with_ros2_event(event, time, context),
-                )
-            })?;
+        self.insert_callback(event.callback.into_id(context_id), callback)
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        subscription_arc
-            .lock()
-            .unwrap()
-            .set_callback(callback_arc.clone())
+        self.model[subscription]
+            .set_callback(callback)
             .map_err(|e| {
                 eyre!("Subscription was already initialized by rclcpp_subscription_init event: {e}")
             })?;
 
-        Ok(processed_events::ros2::RclcppSubscriptionCallbackAdded {
-            callback: callback_arc,
-        })
+        Ok(processed_events::ros2::RclcppSubscriptionCallbackAdded { callback })
     }
 
     pub(super) fn process_rmw_take(
@@ -546,11 +494,7 @@ impl Processor {
             )
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Taken message missing subscriber.")?;
-        let topic = subscriber
-            .lock()
-            .unwrap()
-            .get_topic()
-            .map(ToOwned::to_owned);
+        let topic = self.model[subscriber].get_topic().map(ToOwned::to_owned);
 
         let mut message = SubscriptionMessage::new(event.message);
 
@@ -564,31 +508,31 @@ impl Processor {
                     })
             })
         {
-            message.rmw_take_matched(subscriber.clone(), published_message.clone(), time);
+            message.rmw_take_matched(subscriber, published_message, time);
         } else {
             if event.source_timestamp == 0 {
                 log::info!(target:"rmw_take", "Missing source timestamp. [{time}] {event:?} {context:?}");
             }
-            message.rmw_take_unmatched(subscriber.clone(), event.source_timestamp, time);
+            message.rmw_take_unmatched(subscriber, event.source_timestamp, time);
         }
 
-        let message_arc = Arc::new(Mutex::new(message));
+        let message = self.model.add(message);
 
         if event.taken {
-            let mut subscriber = subscriber.lock().unwrap();
-            if let Some(_old) = subscriber.replace_taken_message(message_arc.clone()) {
+            if let Some(old) = self.model[subscriber].replace_taken_message(message) {
                 // TODO: Save message to dropped messages
+                self.release_later(old);
             }
-            drop(subscriber);
 
             // Override the old message with the new one
             // TODO: Save old message to processed messages if needed
-            self.received_messages
-                .insert(event.message.into_id(context_id), message_arc.clone());
+            self.insert_received_message(time, event.message.into_id(context_id), message);
+        } else {
+            self.release_later(message);
         }
 
         Ok(processed_events::ros2::RmwTake {
-            message: message_arc,
+            message,
             taken: event.taken,
         })
     }
@@ -600,39 +544,36 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> processed_events::ros2::RclTake {
-        let message_arc = self
+        let taken = self
             .received_messages
             .get(&event.message.into_id(context_id))
-            .and_then(|message_arc| {
-                let mut message = message_arc.lock().unwrap();
-                message.rcl_take(time).ok().map(|()| message_arc)
+            .filter(|&message| {
+                self.model
+                    .get_mut(message)
+                    .is_some_and(|message| message.rcl_take(time).is_ok())
             });
 
-        let is_new = message_arc.is_none();
+        let is_new = taken.is_none();
 
-        let message_arc = if let Some(message_arc) = message_arc {
-            message_arc.clone()
+        let message = if let Some(message) = taken {
+            message
         } else {
             let mut message = SubscriptionMessage::new(event.message);
             message
                 .rcl_take(time)
                 .expect("The message was just created, rcl_take was not called before.");
-            let message_arc = Arc::new(Mutex::new(message));
+            let message = self.model.add(message);
 
             log::warn!(target:"rcl_take",
                 "Message was not taken before. Creating new message. [{time}] {event:?} {context:?}"
             );
 
-            self.received_messages
-                .insert(event.message.into_id(context_id), message_arc.clone());
+            self.insert_received_message(time, event.message.into_id(context_id), message);
 
-            message_arc
+            message
         };
 
-        processed_events::ros2::RclTake {
-            message: message_arc,
-            is_new,
-        }
+        processed_events::ros2::RclTake { message, is_new }
     }
 
     pub(super) fn process_rclcpp_take(
@@ -642,38 +583,58 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> processed_events::ros2::RclCppTake {
-        let message_arc = self
+        let taken = self
             .received_messages
             .get(&event.message.into_id(context_id))
-            .and_then(|message_arc| {
-                let mut message = message_arc.lock().unwrap();
-                message.rclcpp_take(time).ok().map(|()| message_arc)
+            .filter(|&message| {
+                self.model
+                    .get_mut(message)
+                    .is_some_and(|message| message.rclcpp_take(time).is_ok())
             });
 
-        let is_new = message_arc.is_none();
+        let is_new = taken.is_none();
 
-        let message_arc = if let Some(message_arc) = message_arc {
-            message_arc.clone()
+        let message = if let Some(message) = taken {
+            message
         } else {
             let mut message = SubscriptionMessage::new(event.message);
             message
                 .rclcpp_take(time)
                 .expect("The message was just created, rclcpp_take was not called before.");
-            let message_arc = Arc::new(Mutex::new(message));
+            let message = self.model.add(message);
 
             log::warn!(
                 "rclcpp_take: Message was not taken before. Creating new message. [{time}] {event:?} {context:?}"
             );
 
-            self.received_messages
-                .insert(event.message.into_id(context_id), message_arc.clone());
+            self.insert_received_message(time, event.message.into_id(context_id), message);
 
-            message_arc
+            message
         };
 
-        processed_events::ros2::RclCppTake {
-            message: message_arc.clone(),
-            is_new,
+        if !self.retention.is_unbounded() {
+            // The message is fully taken, no later event refers to it by its pointer.
+            // Without retention limits, the message is kept as before until the pointer is reused.
+            if let Some(message) = self
+                .received_messages
+                .remove(&event.message.into_id(context_id))
+            {
+                Self::forget_received_message(&self.model, &mut self.pending_release, message);
+            }
+        }
+
+        processed_events::ros2::RclCppTake { message, is_new }
+    }
+
+    /// Retain the received `message` for matching the later take events by its pointer.
+    fn insert_received_message(
+        &mut self,
+        time: Time,
+        id: Id<u64>,
+        message: Idx<SubscriptionMessage>,
+    ) {
+        if let Some(old) = self.received_messages.insert(time, id, message) {
+            Self::forget_received_message(&self.model, &mut self.pending_release, old);
         }
     }
 
@@ -684,23 +645,20 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> Result<processed_events::ros2::RclServiceInit> {
-        let service_arc = self
+        let service = self
             .services_by_rcl
             .entry(event.service_handle.into_id(context_id))
-            .or_insert_with_key(|key| {
-                let service = Service::new(key.id);
-                Arc::new(Mutex::new(service))
-            });
+            .or_insert_with_key(|key| self.model.add(Service::new(key.id)));
 
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.dependent_object(&*service_arc))?;
+            .map_err(|e| e.dependent_object(Object::new(&self.model, &*service)))?;
 
-        let init_result = service_arc.lock().unwrap().rcl_init(
+        let init_result = self.model[*service].rcl_init(
             event.rmw_service_handle,
             event.service_name.clone(),
-            node_arc,
+            node,
         );
         if let Err(_e) = init_result {
             log::warn!(
@@ -708,26 +666,18 @@ impl Processor {
                 "Repeated initialization for handle {}. Assuming old service was deleted. Creating new.",
                 event.service_handle
             );
-            let mut service = service_arc.lock().unwrap();
-            service.mark_removed();
-            drop(service);
+            self.model[*service].mark_removed();
 
-            let mut service = Service::new(event.service_handle.into_id(context_id).id);
-            service
-                .rcl_init(
-                    event.rmw_service_handle,
-                    event.service_name.clone(),
-                    node_arc,
-                )
+            let mut new_service = Service::new(event.service_handle.into_id(context_id).id);
+            new_service
+                .rcl_init(event.rmw_service_handle, event.service_name.clone(), node)
                 .expect("New Service should not be initialized yet");
-            *service_arc = Arc::new(Mutex::new(service));
+            *service = self.model.add(new_service);
         }
 
-        node_arc.lock().unwrap().add_service(service_arc.clone());
+        self.model[node].add_service(*service);
 
-        Ok(processed_events::ros2::RclServiceInit {
-            service: service_arc.clone(),
-        })
+        Ok(processed_events::ros2::RclServiceInit { service: *service })
     }
 
     pub(super) fn process_rclcpp_service_callback_added(
@@ -737,39 +687,28 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclCppServiceCallbackAdded> {
-        let service_arc = self
+        let service = *self
             .services_by_rcl
             .entry(event.service_handle.into_id(context_id))
             .or_insert_with_key(|key| {
                 log::warn!("Service not found for callback. Creating new (possibly duplicate) service. Event: [{time}] {event:?} {context:?}");
-                let service = Service::new(key.id);
-                Arc::new(Mutex::new(service))
+                self.model.add(Service::new(key.id))
             });
 
-        let callback_arc =
-            Callback::new_service(event.callback, service_arc, context.hostname().to_owned());
+        let callback = self.model.add(Callback::new_service(
+            event.callback,
+            service,
+            context.hostname().to_owned(),
+        ));
 
-        self.callbacks_by_id
-            .insert(event.callback.into_id(context_id), callback_arc.clone())
-            .and_then(filter_out_removed_callers)
-            .map_or(Ok(()), |old: Arc<Mutex<Callback>>| {
-                Err(
-                    error::AlreadyExists::with_id(event.callback, &callback_arc, old)
-                        .with_ros2_event(event, time, context),
-                )
-            })?;
+        self.insert_callback(event.callback.into_id(context_id), callback)
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        service_arc
-            .lock()
-            .unwrap()
-            .set_callback(callback_arc.clone())
-            .map_err(|e| {
-                eyre!("Service was already initialized by rclcpp_service_init event: {e}")
-            })?;
+        self.model[service].set_callback(callback).map_err(|e| {
+            eyre!("Service was already initialized by rclcpp_service_init event: {e}")
+        })?;
 
-        Ok(processed_events::ros2::RclCppServiceCallbackAdded {
-            callback: callback_arc,
-        })
+        Ok(processed_events::ros2::RclCppServiceCallbackAdded { callback })
     }
 
     pub(super) fn process_rcl_client_init(
@@ -779,51 +718,37 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclClientInit> {
-        let client_arc = self
+        let client = self
             .clients_by_rcl
             .entry(event.client_handle.into_id(context_id))
-            .or_insert_with_key(|key| {
-                let client = Client::new(key.id);
-                Arc::new(Mutex::new(client))
-            });
+            .or_insert_with_key(|key| self.model.add(Client::new(key.id)));
 
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.dependent_object(&*client_arc))
+            .map_err(|e| e.dependent_object(Object::new(&self.model, &*client)))
             .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        let init_result = client_arc.lock().unwrap().rcl_init(
-            event.rmw_client_handle,
-            event.service_name.clone(),
-            node_arc,
-        );
+        let init_result =
+            self.model[*client].rcl_init(event.rmw_client_handle, event.service_name.clone(), node);
         if let Err(_e) = init_result {
             log::warn!(
                 target: "rcl_client_init",
                 "Repeated initialization for handle {}. Assuming old client was deleted. Creating new.",
                 event.client_handle
             );
-            let mut client = client_arc.lock().unwrap();
-            client.mark_removed();
-            drop(client);
+            self.model[*client].mark_removed();
 
-            let mut client = Client::new(event.client_handle.into_id(context_id).id);
-            client
-                .rcl_init(
-                    event.rmw_client_handle,
-                    event.service_name.clone(),
-                    node_arc,
-                )
+            let mut new_client = Client::new(event.client_handle.into_id(context_id).id);
+            new_client
+                .rcl_init(event.rmw_client_handle, event.service_name.clone(), node)
                 .expect("New Client should not be initialized yet");
-            *client_arc = Arc::new(Mutex::new(client));
+            *client = self.model.add(new_client);
         }
 
-        node_arc.lock().unwrap().add_client(client_arc.clone());
+        self.model[node].add_client(*client);
 
-        Ok(processed_events::ros2::RclClientInit {
-            client: client_arc.clone(),
-        })
+        Ok(processed_events::ros2::RclClientInit { client: *client })
     }
 
     pub(super) fn process_rcl_timer_init(
@@ -833,35 +758,28 @@ impl Processor {
         context_id: ContextId,
         _context: &Context,
     ) -> processed_events::ros2::RclTimerInit {
-        let timer_arc = self
+        let timer = self
             .timers_by_rcl
             .entry(event.timer_handle.into_id(context_id))
-            .or_insert_with_key(|key| {
-                let timer = Timer::new(key.id);
-                Arc::new(Mutex::new(timer))
-            });
+            .or_insert_with_key(|key| self.model.add(Timer::new(key.id)));
 
-        let init_result = timer_arc.lock().unwrap().rcl_init(event.period);
+        let init_result = self.model[*timer].rcl_init(event.period);
         if let Err(_e) = init_result {
             log::warn!(
                 target: "rcl_timer_init",
                 "Repeated initialization for handle {}. Assuming old timer was deleted. Creating new.",
                 event.timer_handle
             );
-            let mut timer = timer_arc.lock().unwrap();
-            timer.mark_removed();
-            drop(timer);
+            self.model[*timer].mark_removed();
 
-            let mut timer = Timer::new(event.timer_handle.into_id(context_id).id);
-            timer
+            let mut new_timer = Timer::new(event.timer_handle.into_id(context_id).id);
+            new_timer
                 .rcl_init(event.period)
                 .expect("New Timer should not be initialized yet");
-            *timer_arc = Arc::new(Mutex::new(timer));
+            *timer = self.model.add(new_timer);
         }
 
-        processed_events::ros2::RclTimerInit {
-            timer: timer_arc.clone(),
-        }
+        processed_events::ros2::RclTimerInit { timer: *timer }
     }
 
     pub(super) fn process_rclcpp_timer_callback_added(
@@ -871,33 +789,24 @@ impl Processor {
         context: &Context,
         time: Time,
     ) -> Result<processed_events::ros2::RclcppTimerCallbackAdded> {
-        let timer_arc = self
+        let timer = self
             .get_timer_by_rcl_handle(event.timer_handle.into_id(context_id))
-            .map_err(|e| e.with_ros2_event(event, time, context))?
-            .clone();
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        let callback_arc =
-            Callback::new_timer(event.callback, &timer_arc, context.hostname().to_owned());
+        let callback = self.model.add(Callback::new_timer(
+            event.callback,
+            timer,
+            context.hostname().to_owned(),
+        ));
 
-        self.callbacks_by_id
-            .insert(event.callback.into_id(context_id), callback_arc.clone())
-            .and_then(filter_out_removed_callers)
-            .map_or(Ok(()), |old: Arc<Mutex<Callback>>| {
-                Err(
-                    error::AlreadyExists::with_id(event.callback, &callback_arc, old)
-                        .with_ros2_event(event, time, context),
-                )
-            })?;
+        self.insert_callback(event.callback.into_id(context_id), callback)
+            .map_err(|e| e.with_ros2_event(event, time, context))?;
 
-        timer_arc
-            .lock()
-            .unwrap()
-            .set_callback(callback_arc.clone())
+        self.model[timer]
+            .set_callback(callback)
             .map_err(|e| eyre!("Timer was already has a callback: {e}"))?;
 
-        Ok(processed_events::ros2::RclcppTimerCallbackAdded {
-            callback: callback_arc,
-        })
+        Ok(processed_events::ros2::RclcppTimerCallbackAdded { callback })
     }
 
     pub(super) fn process_rclcpp_timer_link_node(
@@ -907,29 +816,25 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclcppTimerLinkNode> {
-        let timer_arc = self
+        let timer = self
             .get_timer_by_rcl_handle(event.timer_handle.into_id(context_id))
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Timer not found. Missing rcl_timer_init event")?;
 
-        let node_arc = self
+        let node = self
             .nodes_by_rcl
             .get_or_err(event.node_handle.into_id(context_id), "rcl_handle")
-            .map_err(|e| e.dependent_object(timer_arc))
+            .map_err(|e| e.dependent_object(Object::new(&self.model, &timer)))
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Node not found. Missing rcl_node_init event")?;
 
-        timer_arc
-            .lock()
-            .unwrap()
-            .link_node(node_arc)
+        self.model[timer]
+            .link_node(node)
             .map_err(|e| eyre!("Timer already linked to a node: {e}"))?;
 
-        node_arc.lock().unwrap().add_timer(timer_arc.clone());
+        self.model[node].add_timer(timer);
 
-        Ok(processed_events::ros2::RclcppTimerLinkNode {
-            timer: timer_arc.clone(),
-        })
+        Ok(processed_events::ros2::RclcppTimerLinkNode { timer })
     }
 
     pub(super) fn process_rclcpp_callback_register(
@@ -939,20 +844,16 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::RclcppCallbackRegister> {
-        let callback_arc = self
+        let callback = self
             .get_callback_by_id(event.callback.into_id(context_id))
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Callback not found. Missing rclcpp_*_callback_added event?")?;
 
-        callback_arc
-            .lock()
-            .unwrap()
+        self.model[callback]
             .set_name(event.symbol.clone())
             .map_err(|e| eyre!("Callback already registered with a name: {e}"))?;
 
-        Ok(processed_events::ros2::RclcppCallbackRegister {
-            callback: callback_arc.clone(),
-        })
+        Ok(processed_events::ros2::RclcppCallbackRegister { callback })
     }
 
     pub(super) fn process_callback_start(
@@ -962,12 +863,12 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::CallbackStart> {
-        let callback_arc = self
+        let callback = self
             .get_callback_by_id(event.callback.into_id(context_id))
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Callback not found. Missing rclcpp_*_callback_added event?")?;
 
-        let callback_instance = CallbackInstance::new(callback_arc.clone(), time);
+        let callback_instance = CallbackInstance::new(&mut self.model, callback, time);
 
         Ok(processed_events::ros2::CallbackStart {
             callback: callback_instance,
@@ -982,39 +883,55 @@ impl Processor {
         context_id: ContextId,
         context: &Context,
     ) -> Result<processed_events::ros2::CallbackEnd> {
-        let callback_arc = self
+        let callback = self
             .get_callback_by_id(event.callback.into_id(context_id))
             .map_err(|e| e.with_ros2_event(event, time, context))
             .wrap_err("Callback not found. Missing rclcpp_*_callback_added event?")?;
 
-        let callback_instance = {
-            let mut callback = callback_arc.lock().unwrap();
-            callback
-                .take_running_instance()
-                .expect("No running instance found")
-        };
+        let callback_instance = self.model[callback]
+            .take_running_instance()
+            .expect("No running instance found");
 
-        {
-            let mut callback_instance = callback_instance.lock().unwrap();
-            callback_instance.end(time);
+        let instance = &mut self.model[callback_instance];
+        instance.end(time);
+        if let CallbackTrigger::SubscriptionMessage(message) = instance.get_trigger() {
+            self.release_later(message);
         }
+        self.release_later(callback_instance);
 
         Ok(processed_events::ros2::CallbackEnd {
             callback: callback_instance,
         })
     }
+
+    /// Register the callback by its `id`.
+    ///
+    /// Fails if another callback is registered with the same id and its caller was not removed.
+    fn insert_callback(
+        &mut self,
+        id: Id<u64>,
+        callback: Idx<Callback>,
+    ) -> Result<(), error::AlreadyExists> {
+        self.callbacks_by_id
+            .insert(id, callback)
+            .and_then(|old| filter_out_removed_callers(&self.model, old))
+            .map_or(Ok(()), |old| {
+                Err(error::AlreadyExists::with_id(
+                    id.id,
+                    Object::new(&self.model, &callback),
+                    Object::new(&self.model, &old),
+                ))
+            })
+    }
 }
 
-fn filter_out_removed_callers(old_arc: Arc<Mutex<Callback>>) -> Option<Arc<Mutex<Callback>>> {
-    let old = old_arc.lock().unwrap();
-    if old
+fn filter_out_removed_callers(model: &Model, old: Idx<Callback>) -> Option<Idx<Callback>> {
+    if model[old]
         .get_caller()
-        .and_then(CallbackCaller::is_removed)
-        .unwrap_or(true)
+        .is_none_or(|caller| caller.is_removed(model))
     {
         None
     } else {
-        drop(old);
-        Some(old_arc)
+        Some(old)
     }
 }
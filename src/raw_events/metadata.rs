@@ -0,0 +1,172 @@
+//! Checks of the trace metadata which can be done before any event is decoded.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use bt2_sys::stream::BtStreamClassConst;
+use bt2_sys::trace::{BtEnvironmentEntry, BtTraceConst};
+
+use super::{FromBtEventByName, ros2};
+
+/// Problem of a trace limiting the analysis of its ROS events.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetadataIssue {
+    /// Packets of a stream class with ROS events have no `cpu_id` context field.
+    NoCpuId { trace: String, stream_class: String },
+    /// The trace with ROS events has no `hostname` environment entry.
+    NoHostname { trace: String },
+    /// Supported tracepoints are not in the trace, e.g., because they were not enabled.
+    MissingTracepoints {
+        trace: String,
+        tracepoints: Vec<&'static str>,
+    },
+}
+
+impl MetadataIssue {
+    /// Whether the events cannot be decoded because of the issue.
+    pub const fn is_fatal(&self) -> bool {
+        match self {
+            Self::NoCpuId { .. } | Self::NoHostname { .. } => true,
+            Self::MissingTracepoints { .. } => false,
+        }
+    }
+}
+
+impl Display for MetadataIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoCpuId {
+                trace,
+                stream_class,
+            } => write!(
+                f,
+                "Stream class {stream_class} of trace {trace} has ROS events but no `cpu_id` packet context field"
+            ),
+            Self::NoHostname { trace } => write!(
+                f,
+                "Trace {trace} has ROS events but no `hostname` environment entry"
+            ),
+            Self::MissingTracepoints { trace, tracepoints } => write!(
+                f,
+                "Trace {trace} does not contain the ros2 tracepoints: {}",
+                tracepoints.join(", ")
+            ),
+        }
+    }
+}
+
+/// Check whether the ROS events of the `trace` can be analyzed.
+///
+/// Traces without ROS events, e.g., kernel traces, have no issues.
+/// The missing tracepoints are reported only if `check_tracepoints` is set.
+pub fn check_trace(trace: &BtTraceConst, check_tracepoints: bool) -> Vec<MetadataIssue> {
+    let trace_name = trace.get_name().map_or_else(
+        || "<unnamed>".to_owned(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+
+    let mut issues = Vec::new();
+    let mut ros_events = BTreeSet::new();
+    for stream_class in trace.get_class().stream_classes() {
+        let events: Vec<_> = stream_class
+            .event_classes()
+            .filter_map(|event_class| event_class.get_name().map(str::to_owned))
+            .filter(|name| is_ros_event(name))
+            .collect();
+        if events.is_empty() {
+            continue;
+        }
+
+        if !has_cpu_id(&stream_class) {
+            issues.push(MetadataIssue::NoCpuId {
+                trace: trace_name.clone(),
+                stream_class: stream_class.get_name().map_or_else(
+                    || format!("#{}", stream_class.get_id()),
+                    |name| name.to_string_lossy().into_owned(),
+                ),
+            });
+        }
+        ros_events.extend(events);
+    }
+
+    if ros_events.is_empty() {
+        return issues;
+    }
+
+    if !matches!(
+        trace.get_environment_entry_by_name_cstr(c"hostname"),
+        Some(BtEnvironmentEntry::String(_))
+    ) {
+        issues.push(MetadataIssue::NoHostname {
+            trace: trace_name.clone(),
+        });
+    }
+
+    if check_tracepoints {
+        let tracepoints = missing_tracepoints(
+            ros_events
+                .iter()
+                .filter_map(|name| name.strip_prefix("ros2:")),
+        );
+        if !tracepoints.is_empty() {
+            issues.push(MetadataIssue::MissingTracepoints {
+                trace: trace_name,
+                tracepoints,
+            });
+        }
+    }
+
+    issues
+}
+
+fn is_ros_event(name: &str) -> bool {
+    matches!(name.split(':').next(), Some("ros2" | "r2r"))
+}
+
+fn has_cpu_id(stream_class: &BtStreamClassConst) -> bool {
+    stream_class
+        .get_packet_context_field_class()
+        .and_then(|class| class.try_into_struct().ok())
+        .is_some_and(|class| class.get_member_by_name_cstr(c"cpu_id").is_some())
+}
+
+/// Names of the supported `ros2` tracepoints, without the provider prefix.
+fn ros2_tracepoints() -> Vec<&'static str> {
+    ros2::Event::event_names()
+        .into_iter()
+        .filter_map(|name| name.strip_prefix("ros2:"))
+        .collect()
+}
+
+/// Supported `ros2` tracepoints not among the `present` ones.
+fn missing_tracepoints<'a>(present: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let present: BTreeSet<_> = present.into_iter().collect();
+    ros2_tracepoints()
+        .into_iter()
+        .filter(|tracepoint| !present.contains(tracepoint))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_missing_tracepoints() {
+        assert!(missing_tracepoints(ros2_tracepoints()).is_empty());
+
+        let present = ros2_tracepoints()
+            .into_iter()
+            .filter(|&tracepoint| tracepoint != "callback_start")
+            .chain(["unknown_tracepoint"]);
+        assert_eq!(missing_tracepoints(present), ["callback_start"]);
+    }
+
+    #[test]
+    fn test_is_ros_event() {
+        assert!(is_ros_event("ros2:rcl_init"));
+        assert!(is_ros_event("r2r:spin_start"));
+        assert!(!is_ros_event("sched_switch"));
+        assert!(!is_ros_event("ros2_extra:event"));
+    }
+}
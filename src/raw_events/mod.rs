@@ -1,36 +1,21 @@
-use std::fmt::{Debug, Display};
+use std::fmt::Debug;
 
+use bt2_derive::FromBtEvent;
 use bt2_sys::event::BtEventConst;
-use bt2_sys::field::BtFieldConst;
+pub use bt2_sys::event::{FromBtEvent, FromBtEventByName};
 use bt2_sys::message::BtEventMessageConst;
 use bt2_sys::trace::BtEnvironmentEntry;
 use derive_more::derive::From;
+use serde::{Deserialize, Serialize};
 
 use crate::events_common::{Context, Time};
 
+pub mod metadata;
+
 pub mod ros2;
 
 pub mod r2r;
 
-pub trait FromBtEvent: Sized {
-    fn from_event(event: &BtEventConst) -> Option<Self>;
-}
-
-impl<T> FromBtEvent for T
-where
-    T: TryFrom<BtFieldConst>,
-    <T as TryFrom<BtFieldConst>>::Error: Display,
-{
-    fn from_event(event: &BtEventConst) -> Option<Self> {
-        Some(event.get_payload()?.try_into().unwrap_or_else(|e| {
-            panic!(
-                "Failed to parse event payload into {}: {e}",
-                std::any::type_name::<T>()
-            )
-        }))
-    }
-}
-
 pub fn time_from_message(message: &BtEventMessageConst) -> Time {
     let clock_snapshot = message.get_default_clock_snapshot();
     let value_from_origin = clock_snapshot.get_value_from_origin().unwrap();
@@ -89,33 +74,24 @@ pub fn context_from_event(event: &BtEventConst) -> Context {
     Context::new(cpu_id, vpid, vtid, procname, hostname)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FullEvent {
     pub context: Context,
     pub time: Time,
     pub event: Event,
 }
 
-#[derive(Debug, From, Clone)]
+#[derive(Debug, From, Clone, Serialize, Deserialize, FromBtEvent)]
 pub enum Event {
+    #[bt2(flatten)]
     Ros2(ros2::Event),
+    #[bt2(flatten)]
     R2r(r2r::Event),
 }
 
 pub fn get_full_event(message: &BtEventMessageConst) -> Option<FullEvent> {
     let bt_event = message.get_event();
-
-    let bt_event_class = bt_event.get_class();
-    let full_name = bt_event_class.get_name().unwrap();
-    let provider = full_name.split(':').next().unwrap();
-
-    let event = match provider {
-        "ros2" => Event::Ros2(ros2::Event::from_event(&bt_event)?),
-        "r2r" => Event::R2r(r2r::Event::from_event(&bt_event)?),
-        _ => {
-            return None;
-        }
-    };
+    let event = Event::from_event(&bt_event)?;
 
     let context = context_from_event(&bt_event);
     let time = time_from_message(message);
@@ -126,3 +102,24 @@ pub fn get_full_event(message: &BtEventMessageConst) -> Option<FullEvent> {
         event,
     })
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_event_names() {
+        let names = Event::event_names();
+        let unique: BTreeSet<_> = names.iter().collect();
+        assert_eq!(unique.len(), names.len());
+
+        assert!(names.contains(&"ros2:rcl_init"));
+        assert!(names.contains(&"r2r:spin_start"));
+        assert_eq!(
+            names.len(),
+            ros2::Event::event_names().len() + r2r::Event::event_names().len()
+        );
+    }
+}
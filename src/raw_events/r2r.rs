@@ -1,11 +1,9 @@
-use bt2_derive::TryFromBtFieldConst;
-use bt2_sys::event::BtEventConst;
+use bt2_derive::{FromBtEvent, TryFromBtFieldConst};
 use derive_more::Debug;
 use derive_more::derive::From;
+use serde::{Deserialize, Serialize};
 
-use super::FromBtEvent;
-
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct SpinStart {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
@@ -13,25 +11,25 @@ pub struct SpinStart {
     pub timeout_ns: u32,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct SpinEnd {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct SpinWake {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct SpinTimeout {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct UpdateTime {
     #[debug("{subscriber:#x}")]
     pub subscriber: u64,
@@ -39,28 +37,16 @@ pub struct UpdateTime {
     pub time_ns: u32,
 }
 
-#[derive(Debug, From, Clone)]
+#[derive(Debug, From, Clone, Serialize, Deserialize, FromBtEvent)]
 pub enum Event {
+    #[bt2(event = "r2r:spin_start")]
     SpinStart(SpinStart),
+    #[bt2(event = "r2r:spin_end")]
     SpinEnd(SpinEnd),
+    #[bt2(event = "r2r:spin_wake")]
     SpinWake(SpinWake),
+    #[bt2(event = "r2r:spin_timeout")]
     SpinTimeout(SpinTimeout),
+    #[bt2(event = "r2r:update_time")]
     UpdateTime(UpdateTime),
 }
-impl FromBtEvent for Event {
-    fn from_event(event: &BtEventConst) -> Option<Self> {
-        let event_class = event.get_class();
-        let full_event_name = event_class.get_name().unwrap();
-        let (provider_name, event_name) = full_event_name.split_once(':').unwrap();
-        assert!(provider_name == "r2r");
-
-        Some(match event_name {
-            "spin_start" => SpinStart::from_event(event)?.into(),
-            "spin_end" => SpinEnd::from_event(event)?.into(),
-            "spin_wake" => SpinWake::from_event(event)?.into(),
-            "spin_timeout" => SpinTimeout::from_event(event)?.into(),
-            "update_time" => UpdateTime::from_event(event)?.into(),
-            _ => return None,
-        })
-    }
-}
@@ -1,21 +1,19 @@
-use bt2_derive::TryFromBtFieldConst;
-use bt2_sys::event::BtEventConst;
+use bt2_derive::{FromBtEvent, TryFromBtFieldConst};
 use derive_more::derive::{Debug, From};
+use serde::{Deserialize, Serialize};
 
 use crate::utils::DebugOptionHex;
 
-use super::FromBtEvent;
-
 pub const GID_SIZE: usize = 24;
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclInit {
     #[debug("{context_handle:#x}")]
     pub context_handle: u64,
     pub version: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclNodeInit {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
@@ -25,14 +23,14 @@ pub struct RclNodeInit {
     pub namespace: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RmwPublisherInit {
     #[debug("{rmw_publisher_handle:#x}")]
     pub rmw_publisher_handle: u64,
     pub gid: [u8; GID_SIZE],
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclPublisherInit {
     #[debug("{publisher_handle:#x}")]
     pub publisher_handle: u64,
@@ -44,13 +42,13 @@ pub struct RclPublisherInit {
     pub queue_depth: usize,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppPublish {
     #[debug("{message:#x}")]
     pub message: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppIntraPublish {
     #[debug("{publisher_handle:#x}")]
     pub publisher_handle: u64,
@@ -58,7 +56,7 @@ pub struct RclcppIntraPublish {
     pub message: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclPublish {
     #[debug("{publisher_handle:#x}")]
     pub publisher_handle: u64,
@@ -66,7 +64,7 @@ pub struct RclPublish {
     pub message: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, TryFromBtFieldConst)]
 pub struct RmwPublish {
     #[debug("{:?}", DebugOptionHex(rmw_publisher_handle))]
     pub rmw_publisher_handle: Option<u64>,
@@ -75,14 +73,14 @@ pub struct RmwPublish {
     pub timestamp: Option<i64>,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RmwSubscriptionInit {
     #[debug("{rmw_subscription_handle:#x}")]
     pub rmw_subscription_handle: u64,
     pub gid: [u8; GID_SIZE],
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclSubscriptionInit {
     #[debug("{subscription_handle:#x}")]
     pub subscription_handle: u64,
@@ -94,7 +92,7 @@ pub struct RclSubscriptionInit {
     pub queue_depth: usize,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppSubscriptionInit {
     #[debug("{subscription_handle:#x}")]
     pub subscription_handle: u64,
@@ -102,7 +100,7 @@ pub struct RclcppSubscriptionInit {
     pub subscription: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppSubscriptionCallbackAdded {
     #[debug("{subscription:#x}")]
     pub subscription: u64,
@@ -110,7 +108,7 @@ pub struct RclcppSubscriptionCallbackAdded {
     pub callback: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RmwTake {
     #[debug("{rmw_subscription_handle:#x}")]
     pub rmw_subscription_handle: u64,
@@ -121,19 +119,19 @@ pub struct RmwTake {
     pub taken: bool,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclTake {
     #[debug("{message:#x}")]
     pub message: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppTake {
     #[debug("{message:#x}")]
     pub message: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclServiceInit {
     #[debug("{service_handle:#x}")]
     pub service_handle: u64,
@@ -144,7 +142,7 @@ pub struct RclServiceInit {
     pub service_name: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppServiceCallbackAdded {
     #[debug("{service_handle:#x}")]
     pub service_handle: u64,
@@ -152,7 +150,7 @@ pub struct RclcppServiceCallbackAdded {
     pub callback: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclClientInit {
     #[debug("{client_handle:#x}")]
     pub client_handle: u64,
@@ -163,21 +161,21 @@ pub struct RclClientInit {
     pub service_name: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclTimerInit {
     #[debug("{timer_handle:#x}")]
     pub timer_handle: u64,
     pub period: i64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppTimerCallbackAdded {
     #[debug("{timer_handle:#x}")]
     pub timer_handle: u64,
     pub callback: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppTimerLinkNode {
     #[debug("{timer_handle:#x}")]
     pub timer_handle: u64,
@@ -185,14 +183,14 @@ pub struct RclcppTimerLinkNode {
     pub node_handle: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclcppCallbackRegister {
     #[debug("{callback:#x}")]
     pub callback: u64,
     pub symbol: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct CallbackStart {
     #[debug("{callback:#x}")]
     pub callback: u64,
@@ -200,13 +198,13 @@ pub struct CallbackStart {
     pub is_intra_process: bool,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct CallbackEnd {
     #[debug("{callback:#x}")]
     pub callback: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclLifecycleStateMachineInit {
     #[debug("{node_handle:#x}")]
     pub node_handle: u64,
@@ -214,7 +212,7 @@ pub struct RclLifecycleStateMachineInit {
     pub state_machine: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclLifecycleTransition {
     #[debug("{state_machine:#x}")]
     pub state_machine: u64,
@@ -222,21 +220,21 @@ pub struct RclLifecycleTransition {
     pub goal_label: String,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppExecutorGetNextReady;
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppExecutorWaitForWork {
     pub timeout: i64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppExecutorExecute {
     #[debug("{handle:#x}")]
     pub handle: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppIpbToSubscription {
     #[debug("{ipb:#x}")]
     pub ipb: u64,
@@ -244,7 +242,7 @@ pub struct RclCppIpbToSubscription {
     pub subscription: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppBufferToIpb {
     #[debug("{buffer:#x}")]
     pub buffer: u64,
@@ -252,14 +250,14 @@ pub struct RclCppBufferToIpb {
     pub ipb: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppConstructRingBuffer {
     #[debug("{buffer:#x}")]
     pub buffer: u64,
     pub capacity: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppRingBufferEnqueue {
     #[debug("{buffer:#x}")]
     pub buffer: u64,
@@ -269,7 +267,7 @@ pub struct RclCppRingBufferEnqueue {
     pub overwritten: bool,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppRingBufferDequeue {
     #[debug("{buffer:#x}")]
     pub buffer: u64,
@@ -277,131 +275,82 @@ pub struct RclCppRingBufferDequeue {
     pub size: u64,
 }
 
-#[derive(Debug, TryFromBtFieldConst, Clone)]
+#[derive(Debug, TryFromBtFieldConst, Clone, Serialize, Deserialize)]
 pub struct RclCppRingBufferClear {
     #[debug("{buffer:#x}")]
     pub buffer: u64,
 }
 
-#[derive(Debug, From, Clone)]
+#[derive(Debug, From, Clone, Serialize, Deserialize, FromBtEvent)]
 pub enum Event {
+    #[bt2(event = "ros2:rcl_init")]
     RclInit(RclInit),
+    #[bt2(event = "ros2:rcl_node_init")]
     RclNodeInit(RclNodeInit),
+    #[bt2(event = "ros2:rmw_publisher_init")]
     RmwPublisherInit(RmwPublisherInit),
+    #[bt2(event = "ros2:rcl_publisher_init")]
     RclPublisherInit(RclPublisherInit),
+    #[bt2(event = "ros2:rclcpp_publish")]
     RclcppPublish(RclcppPublish),
+    #[bt2(event = "ros2:rclcpp_intra_publish")]
     RclcppIntraPublish(RclcppIntraPublish),
+    #[bt2(event = "ros2:rcl_publish")]
     RclPublish(RclPublish),
+    #[bt2(event = "ros2:rmw_publish")]
     RmwPublish(RmwPublish),
+    #[bt2(event = "ros2:rmw_subscription_init")]
     RmwSubscriptionInit(RmwSubscriptionInit),
+    #[bt2(event = "ros2:rcl_subscription_init")]
     RclSubscriptionInit(RclSubscriptionInit),
+    #[bt2(event = "ros2:rclcpp_subscription_init")]
     RclcppSubscriptionInit(RclcppSubscriptionInit),
+    #[bt2(event = "ros2:rclcpp_subscription_callback_added")]
     RclcppSubscriptionCallbackAdded(RclcppSubscriptionCallbackAdded),
+    #[bt2(event = "ros2:rmw_take")]
     RmwTake(RmwTake),
+    #[bt2(event = "ros2:rcl_take")]
     RclTake(RclTake),
+    #[bt2(event = "ros2:rclcpp_take")]
     RclcppTake(RclcppTake),
+    #[bt2(event = "ros2:rcl_service_init")]
     RclServiceInit(RclServiceInit),
+    #[bt2(event = "ros2:rclcpp_service_callback_added")]
     RclcppServiceCallbackAdded(RclcppServiceCallbackAdded),
+    #[bt2(event = "ros2:rcl_client_init")]
     RclClientInit(RclClientInit),
+    #[bt2(event = "ros2:rcl_timer_init")]
     RclTimerInit(RclTimerInit),
+    #[bt2(event = "ros2:rclcpp_timer_callback_added")]
     RclcppTimerCallbackAdded(RclcppTimerCallbackAdded),
+    #[bt2(event = "ros2:rclcpp_timer_link_node")]
     RclcppTimerLinkNode(RclcppTimerLinkNode),
+    #[bt2(event = "ros2:rclcpp_callback_register")]
     RclcppCallbackRegister(RclcppCallbackRegister),
+    #[bt2(event = "ros2:callback_start")]
     CallbackStart(CallbackStart),
+    #[bt2(event = "ros2:callback_end")]
     CallbackEnd(CallbackEnd),
+    #[bt2(event = "ros2:rcl_lifecycle_state_machine_init")]
     RclLifecycleStateMachineInit(RclLifecycleStateMachineInit),
+    #[bt2(event = "ros2:rcl_lifecycle_transition")]
     RclLifecycleTransition(RclLifecycleTransition),
+    #[bt2(event = "ros2:rclcpp_executor_get_next_ready")]
     RclcppExecutorGetNextReady(RclCppExecutorGetNextReady),
+    #[bt2(event = "ros2:rclcpp_executor_wait_for_work")]
     RclcppExecutorWaitForWork(RclCppExecutorWaitForWork),
+    #[bt2(event = "ros2:rclcpp_executor_execute")]
     RclcppExecutorExecute(RclCppExecutorExecute),
+    #[bt2(event = "ros2:rcl_ipb_to_subscription")]
     RclcppIpbToSubscription(RclCppIpbToSubscription),
+    #[bt2(event = "ros2:rclcpp_buffer_to_ipb")]
     RclcppBufferToIpb(RclCppBufferToIpb),
+    #[bt2(event = "ros2:rclcpp_construct_ring_buffer")]
     RclcppConstructRingBuffer(RclCppConstructRingBuffer),
+    #[bt2(event = "ros2:rclcpp_ring_buffer_enqueue")]
     RclcppRingBufferEnqueue(RclCppRingBufferEnqueue),
+    #[bt2(event = "ros2:rclcpp_ring_buffer_dequeue")]
     RclcppRingBufferDequeue(RclCppRingBufferDequeue),
+    #[bt2(event = "ros2:rclcpp_ring_buffer_clear")]
     RclcppRingBufferClear(RclCppRingBufferClear),
 }
-
-impl FromBtEvent for RmwPublish {
-    fn from_event(event: &BtEventConst) -> Option<Self> {
-        let rmw_publisher_handle = event
-            .get_payload()?
-            .into_struct()
-            .get_field_by_name("rmw_publisher_handle")
-            .map(|field| field.into_uint().get_value());
-        let message = event
-            .get_payload()?
-            .into_struct()
-            .get_field_by_name("message")
-            .expect("Missing message")
-            .into_uint()
-            .get_value();
-        let timestamp = event
-            .get_payload()?
-            .into_struct()
-            .get_field_by_name("timestamp")
-            .map(|field| field.into_int().get_value());
-        Some(Self {
-            rmw_publisher_handle,
-            message,
-            timestamp,
-        })
-    }
-}
-
-impl FromBtEvent for Event {
-    fn from_event(event: &BtEventConst) -> Option<Self> {
-        let event_class = event.get_class();
-        let full_event_name = event_class.get_name().unwrap();
-        let (provider_name, event_name) = full_event_name.split_once(':').unwrap();
-
-        assert!(provider_name == "ros2");
-
-        Some(match event_name {
-            "rcl_init" => RclInit::from_event(event)?.into(),
-            "rcl_node_init" => RclNodeInit::from_event(event)?.into(),
-            "rmw_publisher_init" => RmwPublisherInit::from_event(event)?.into(),
-            "rcl_publisher_init" => RclPublisherInit::from_event(event)?.into(),
-            "rclcpp_publish" => RclcppPublish::from_event(event)?.into(),
-            "rclcpp_intra_publish" => RclcppIntraPublish::from_event(event)?.into(),
-            "rcl_publish" => RclPublish::from_event(event)?.into(),
-            "rmw_publish" => RmwPublish::from_event(event)?.into(),
-            "rmw_subscription_init" => RmwSubscriptionInit::from_event(event)?.into(),
-            "rcl_subscription_init" => RclSubscriptionInit::from_event(event)?.into(),
-            "rclcpp_subscription_init" => RclcppSubscriptionInit::from_event(event)?.into(),
-            "rclcpp_subscription_callback_added" => {
-                RclcppSubscriptionCallbackAdded::from_event(event)?.into()
-            }
-            "rmw_take" => RmwTake::from_event(event)?.into(),
-            "rcl_take" => RclTake::from_event(event)?.into(),
-            "rclcpp_take" => RclcppTake::from_event(event)?.into(),
-            "rcl_service_init" => RclServiceInit::from_event(event)?.into(),
-            "rclcpp_service_callback_added" => {
-                RclcppServiceCallbackAdded::from_event(event)?.into()
-            }
-            "rcl_client_init" => RclClientInit::from_event(event)?.into(),
-            "rcl_timer_init" => RclTimerInit::from_event(event)?.into(),
-            "rclcpp_timer_callback_added" => RclcppTimerCallbackAdded::from_event(event)?.into(),
-            "rclcpp_timer_link_node" => RclcppTimerLinkNode::from_event(event)?.into(),
-            "rclcpp_callback_register" => RclcppCallbackRegister::from_event(event)?.into(),
-            "callback_start" => CallbackStart::from_event(event)?.into(),
-            "callback_end" => CallbackEnd::from_event(event)?.into(),
-            "rcl_lifecycle_state_machine_init" => {
-                RclLifecycleStateMachineInit::from_event(event)?.into()
-            }
-            "rcl_lifecycle_transition" => RclLifecycleTransition::from_event(event)?.into(),
-            "rclcpp_executor_get_next_ready" => {
-                RclCppExecutorGetNextReady::from_event(event)?.into()
-            }
-            "rclcpp_executor_wait_for_work" => RclCppExecutorWaitForWork::from_event(event)?.into(),
-            "rclcpp_executor_execute" => RclCppExecutorExecute::from_event(event)?.into(),
-            "rcl_ipb_to_subscription" => RclCppIpbToSubscription::from_event(event)?.into(),
-            "rclcpp_buffer_to_ipb" => RclCppBufferToIpb::from_event(event)?.into(),
-            "rclcpp_construct_ring_buffer" => RclCppConstructRingBuffer::from_event(event)?.into(),
-            "rclcpp_ring_buffer_enqueue" => RclCppRingBufferEnqueue::from_event(event)?.into(),
-            "rclcpp_ring_buffer_dequeue" => RclCppRingBufferDequeue::from_event(event)?.into(),
-            "rclcpp_ring_buffer_clear" => RclCppRingBufferClear::from_event(event)?.into(),
-            _ => return None,
-        })
-    }
-}
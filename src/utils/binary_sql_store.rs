@@ -1,9 +1,14 @@
 use std::str::FromStr;
 
+use crate::analyses::analysis::cpu_affinity::{
+    AffinityViolationExport, CallbackAffinityExport, ThreadAffinityExport,
+};
 use crate::analyses::analysis::dependency_graph::{
     ActivationDelayExport, CallbackDurationExport, MessageLatencyExport, MessagesDelayExport,
     NodeOverviewExport, PublicationDelayExport,
 };
+use crate::analyses::analysis::executor_starvation::ExecutorStarvationExport;
+use crate::analyses::analysis::topic_rate::TopicRateExport;
 use crate::extract::{RosChannelCompleteName, RosInterfaceCompleteName};
 
 #[derive(thiserror::Error, std::fmt::Debug)]
@@ -129,6 +134,22 @@ impl BinarySqlStore {
             |r| T::from_row(r),
         )?)
     }
+
+    pub fn get_all<T: Entity>(&self) -> Result<Vec<T>, BinarySQLStoreError> {
+        let mut query = self.connection.prepare(&format!(
+            "SELECT {} FROM {} ORDER BY id",
+            T::PARAMS
+                .iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            T::TABLE
+        ))?;
+
+        Ok(query
+            .query_map((), |r| T::from_row(r))?
+            .collect::<Result<Vec<_>, _>>()?)
+    }
 }
 
 impl BinarySqlStore {
@@ -321,6 +342,170 @@ impl Entity for ActivationDelayExport {
     }
 }
 
+impl Entity for TopicRateExport {
+    const PARAMS: &'static [TableColumn] = &[
+        TableColumn::new("id", "INT PRIMARY KEY"),
+        TableColumn::new("node", "TEXT"),
+        TableColumn::new("interface", "TEXT"),
+        TableColumn::new("stats", "BLOB"),
+    ];
+    const TABLE: &'static str = "topic_rate";
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(TopicRateExport {
+            id: row.get::<_, i64>("id")? as usize,
+            name: RosInterfaceCompleteName {
+                interface: row.get("interface")?,
+                node: row.get("node")?,
+            },
+            stats: postcard::from_bytes(&row.get::<_, Vec<_>>("stats")?).unwrap(),
+        })
+    }
+
+    fn to_params(&self) -> impl rusqlite::Params {
+        (
+            self.id as i64,
+            &self.name.node,
+            &self.name.interface,
+            postcard::to_allocvec(&self.stats).unwrap(),
+        )
+    }
+}
+
+impl Entity for ExecutorStarvationExport {
+    const PARAMS: &'static [TableColumn] = &[
+        TableColumn::new("id", "INT PRIMARY KEY"),
+        TableColumn::new("node", "TEXT"),
+        TableColumn::new("interface", "TEXT"),
+        TableColumn::new("stats", "BLOB"),
+    ];
+    const TABLE: &'static str = "executor_starvation";
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(ExecutorStarvationExport {
+            id: row.get::<_, i64>("id")? as usize,
+            name: RosInterfaceCompleteName {
+                interface: row.get("interface")?,
+                node: row.get("node")?,
+            },
+            stats: postcard::from_bytes(&row.get::<_, Vec<_>>("stats")?).unwrap(),
+        })
+    }
+
+    fn to_params(&self) -> impl rusqlite::Params {
+        (
+            self.id as i64,
+            &self.name.node,
+            &self.name.interface,
+            postcard::to_allocvec(&self.stats).unwrap(),
+        )
+    }
+}
+
+impl Entity for CallbackAffinityExport {
+    const PARAMS: &'static [TableColumn] = &[
+        TableColumn::new("id", "INT PRIMARY KEY"),
+        TableColumn::new("node", "TEXT"),
+        TableColumn::new("interface", "TEXT"),
+        TableColumn::new("stats", "BLOB"),
+    ];
+    const TABLE: &'static str = "callback_affinity";
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(CallbackAffinityExport {
+            id: row.get::<_, i64>("id")? as usize,
+            name: RosInterfaceCompleteName {
+                interface: row.get("interface")?,
+                node: row.get("node")?,
+            },
+            stats: postcard::from_bytes(&row.get::<_, Vec<_>>("stats")?).unwrap(),
+        })
+    }
+
+    fn to_params(&self) -> impl rusqlite::Params {
+        (
+            self.id as i64,
+            &self.name.node,
+            &self.name.interface,
+            postcard::to_allocvec(&self.stats).unwrap(),
+        )
+    }
+}
+
+impl Entity for ThreadAffinityExport {
+    const PARAMS: &'static [TableColumn] = &[
+        TableColumn::new("id", "INT PRIMARY KEY"),
+        TableColumn::new("procname", "TEXT"),
+        TableColumn::new("pid", "INT"),
+        TableColumn::new("tid", "INT"),
+        TableColumn::new("stats", "BLOB"),
+    ];
+    const TABLE: &'static str = "thread_affinity";
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(ThreadAffinityExport {
+            id: row.get::<_, i64>("id")? as usize,
+            procname: row.get("procname")?,
+            pid: row.get("pid")?,
+            tid: row.get("tid")?,
+            stats: postcard::from_bytes(&row.get::<_, Vec<_>>("stats")?).unwrap(),
+        })
+    }
+
+    fn to_params(&self) -> impl rusqlite::Params {
+        (
+            self.id as i64,
+            &self.procname,
+            self.pid,
+            self.tid,
+            postcard::to_allocvec(&self.stats).unwrap(),
+        )
+    }
+}
+
+impl Entity for AffinityViolationExport {
+    const PARAMS: &'static [TableColumn] = &[
+        TableColumn::new("id", "INT PRIMARY KEY"),
+        TableColumn::new("target", "TEXT"),
+        TableColumn::new("allowed_cpus", "TEXT"),
+        TableColumn::new("hostname", "TEXT"),
+        TableColumn::new("pid", "INT"),
+        TableColumn::new("tid", "INT"),
+        TableColumn::new("cpu", "INT"),
+        TableColumn::new("events", "INT"),
+        TableColumn::new("callbacks", "BLOB"),
+    ];
+    const TABLE: &'static str = "affinity_violation";
+
+    fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(AffinityViolationExport {
+            id: row.get::<_, i64>("id")? as usize,
+            target: row.get("target")?,
+            allowed_cpus: row.get("allowed_cpus")?,
+            hostname: row.get("hostname")?,
+            pid: row.get("pid")?,
+            tid: row.get("tid")?,
+            cpu: row.get("cpu")?,
+            events: row.get::<_, i64>("events")? as usize,
+            callbacks: postcard::from_bytes(&row.get::<_, Vec<_>>("callbacks")?).unwrap(),
+        })
+    }
+
+    fn to_params(&self) -> impl rusqlite::Params {
+        (
+            self.id as i64,
+            &self.target,
+            &self.allowed_cpus,
+            &self.hostname,
+            self.pid,
+            self.tid,
+            self.cpu,
+            self.events as i64,
+            postcard::to_allocvec(&self.callbacks).unwrap(),
+        )
+    }
+}
+
 impl Entity for NodeOverviewExport {
     const PARAMS: &'static [TableColumn] = &[
         TableColumn::new("id", "INT PRIMARY KEY"),
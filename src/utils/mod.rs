@@ -1,7 +1,8 @@
 use std::fmt::{Debug, Display};
-use std::sync::{Arc, Mutex, Weak};
+use std::str::FromStr;
 
-use derive_more::derive::From;
+use derive_more::derive::{From, Into};
+use thiserror::Error;
 
 pub mod binary_sql_store;
 
@@ -279,105 +280,6 @@ where
     }
 }
 
-pub struct DisplayArcMutex<'a, T> {
-    arc: &'a Arc<Mutex<T>>,
-    skip: bool,
-}
-
-impl<'a, T: Display> DisplayArcMutex<'a, T> {
-    pub const fn new(arc: &'a Arc<Mutex<T>>, skip: bool) -> Self {
-        Self { arc, skip }
-    }
-}
-
-impl<T: Display> Display for DisplayArcMutex<'_, T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.skip {
-            return write!(f, "...");
-        }
-        let inner = self.arc.lock().unwrap();
-        write!(f, "{inner:#}")
-    }
-}
-
-impl<T: Debug> Debug for DisplayArcMutex<'_, T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.skip {
-            return write!(f, "...");
-        }
-        let inner = self.arc.lock().unwrap();
-        write!(f, "{inner:?}")
-    }
-}
-
-pub struct DisplayArcWeakMutex<'a, T> {
-    arc_weak: &'a ArcWeak<Mutex<T>>,
-    skip: bool,
-}
-
-pub struct DisplayArcWeakMutexMapped<'a, T, F> {
-    arc_weak: &'a ArcWeak<Mutex<T>>,
-    display_fn: F,
-}
-
-impl<'a, T> DisplayArcWeakMutex<'a, T> {
-    pub const fn new(weak: &'a ArcWeak<Mutex<T>>, skip: bool) -> Self {
-        Self {
-            arc_weak: weak,
-            skip,
-        }
-    }
-
-    pub fn map<F>(self, display_fn: F) -> DisplayArcWeakMutexMapped<'a, T, F>
-    where
-        F: Fn(&T, &mut std::fmt::Formatter<'_>) -> std::fmt::Result,
-    {
-        DisplayArcWeakMutexMapped {
-            arc_weak: self.arc_weak,
-            display_fn,
-        }
-    }
-}
-
-impl<T: Display> std::fmt::Display for DisplayArcWeakMutex<'_, T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.skip {
-            return write!(f, "...");
-        }
-        let Some(strong) = self.arc_weak.get_arc() else {
-            return write!(f, "DROPPED");
-        };
-        let inner = strong.lock().unwrap();
-        write!(f, "{inner:#}")
-    }
-}
-
-impl<T, F> std::fmt::Display for DisplayArcWeakMutexMapped<'_, T, F>
-where
-    F: Fn(&T, &mut std::fmt::Formatter<'_>) -> std::fmt::Result,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Some(strong) = self.arc_weak.get_arc() else {
-            return write!(f, "DROPPED");
-        };
-        let inner = strong.lock().unwrap();
-        (self.display_fn)(&inner, f)
-    }
-}
-
-impl<T: Debug> Debug for DisplayArcWeakMutex<'_, T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.skip {
-            return write!(f, "...");
-        }
-        let Some(strong) = self.arc_weak.get_arc() else {
-            return write!(f, "DROPPED");
-        };
-        let inner = strong.lock().unwrap();
-        write!(f, "{inner:#?}")
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 pub struct DisplayDebug<T>(pub(crate) T);
 
@@ -469,91 +371,223 @@ impl std::fmt::Display for DurationDisplayImprecise {
     }
 }
 
-pub struct DebugOptionHex<'a, T>(pub &'a Option<T>);
+/// Duration in nanoseconds parsed from a human readable string.
+///
+/// The string consists of a number followed by a unit, e.g., `500us`, `10ms` or `1.5s`.
+/// Supported units are `ns`, `us` (or `μs`), `ms`, `s` and `min`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Into)]
+pub struct NsDuration(i64);
 
-impl<T: std::fmt::LowerHex> std::fmt::Debug for DebugOptionHex<'_, T> {
+impl NsDuration {
+    pub const fn from_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    pub const fn as_nanos(self) -> i64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for NsDuration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.0 {
-            Some(value) => f
-                .debug_tuple("Some")
-                .field(&format_args!("{value:#x}"))
-                .finish(),
-            None => f.debug_struct("None").finish(),
-        }
+        Display::fmt(&DurationDisplayImprecise(self.0), f)
     }
 }
 
-#[derive(Debug)]
-pub enum ArcWeak<T> {
-    Arc(Arc<T>),
-    Weak(Weak<T>),
+#[derive(Debug, Clone, Error)]
+pub enum NsDurationParseError {
+    #[error("Missing unit in duration {0:?}. Supported units are: ns, us, ms, s, min")]
+    MissingUnit(String),
+
+    #[error("Unknown unit {1:?} in duration {0:?}. Supported units are: ns, us, ms, s, min")]
+    UnknownUnit(String, String),
+
+    #[error("Cannot parse duration: {0} {1:?}")]
+    ParseFloatError(#[source] std::num::ParseFloatError, String),
+
+    #[error("Duration must be a non-negative finite number: {0:?}")]
+    OutOfRange(String),
 }
 
-impl<T> ArcWeak<T> {
-    pub fn get_arc(&self) -> Option<Arc<T>> {
-        match self {
-            Self::Arc(arc) => Some(Arc::clone(arc)),
-            Self::Weak(weak) => weak.upgrade(),
-        }
-    }
+impl FromStr for NsDuration {
+    type Err = NsDurationParseError;
 
-    /// Upgrade the `ArcWeak` to an `Arc` in place.
-    pub fn upgrade_in_place(&mut self) -> bool {
-        match self {
-            Self::Arc(_) => true,
-            Self::Weak(weak) => {
-                if let Some(arc) = weak.upgrade() {
-                    *self = Self::Arc(arc);
-                    true
-                } else {
-                    false
-                }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let unit_start = s
+            .find(|c: char| c.is_alphabetic())
+            .ok_or_else(|| NsDurationParseError::MissingUnit(s.to_owned()))?;
+        let (value, unit) = s.split_at(unit_start);
+
+        let factor = match unit {
+            "ns" => 1.0,
+            "us" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "min" => 60e9,
+            _ => {
+                return Err(NsDurationParseError::UnknownUnit(
+                    s.to_owned(),
+                    unit.to_owned(),
+                ));
             }
+        };
+
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|e| NsDurationParseError::ParseFloatError(e, s.to_owned()))?;
+
+        let nanos = (value * factor).round();
+        if !nanos.is_finite() || nanos < 0.0 || nanos > i64::MAX as f64 {
+            return Err(NsDurationParseError::OutOfRange(s.to_owned()));
         }
+
+        Ok(Self(nanos as i64))
     }
+}
 
-    /// Downgrade the `ArcWeak` to a `Weak` in place.
-    pub fn downgrade_in_place(&mut self) {
-        match self {
-            Self::Arc(arc) => {
-                let weak = Arc::downgrade(arc);
-                *self = Self::Weak(weak);
-            }
-            Self::Weak(_) => {}
-        }
+/// Size in bytes parsed from a human readable string.
+///
+/// The string consists of a number optionally followed by a unit, e.g., `512MiB` or `2GB`.
+/// Supported units are `B`, `kB`, `MB`, `GB` (powers of 1000) and `KiB`, `MiB`, `GiB` (powers of 1024).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    pub const fn as_bytes(self) -> u64 {
+        self.0
     }
+}
 
-    pub(crate) fn get_weak(&self) -> Weak<T> {
-        match self {
-            Self::Arc(arc) => Arc::downgrade(arc),
-            Self::Weak(weak) => weak.clone(),
+impl std::fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} B", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum ByteSizeParseError {
+    #[error("Unknown unit {1:?} in size {0:?}. Supported units are: B, kB, MB, GB, KiB, MiB, GiB")]
+    UnknownUnit(String, String),
+
+    #[error("Cannot parse size: {0} {1:?}")]
+    ParseFloatError(#[source] std::num::ParseFloatError, String),
+
+    #[error("Size must be a non-negative finite number: {0:?}")]
+    OutOfRange(String),
+}
+
+impl FromStr for ByteSize {
+    type Err = ByteSizeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let unit_start = s.find(|c: char| c.is_alphabetic()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(unit_start);
+
+        let factor = match unit {
+            "" | "B" => 1.0,
+            "kB" | "KB" => 1e3,
+            "MB" => 1e6,
+            "GB" => 1e9,
+            "KiB" => 1024.0,
+            "MiB" => 1024.0 * 1024.0,
+            "GiB" => 1024.0 * 1024.0 * 1024.0,
+            _ => {
+                return Err(ByteSizeParseError::UnknownUnit(
+                    s.to_owned(),
+                    unit.to_owned(),
+                ));
+            }
+        };
+
+        let value: f64 = value
+            .trim()
+            .parse()
+            .map_err(|e| ByteSizeParseError::ParseFloatError(e, s.to_owned()))?;
+
+        let bytes = (value * factor).round();
+        if !bytes.is_finite() || bytes < 0.0 || bytes > u64::MAX as f64 {
+            return Err(ByteSizeParseError::OutOfRange(s.to_owned()));
         }
+
+        Ok(Self(bytes as u64))
     }
 }
 
-impl<T> Clone for ArcWeak<T> {
-    fn clone(&self) -> Self {
+/// Point in time parsed from an RFC 3339 timestamp, a number of nanoseconds since the UNIX epoch
+/// or a duration from the beginning of the traces prefixed by `+`.
+///
+/// E.g., `2024-05-01T12:00:00.5Z`, `2024-05-01T14:00:00+02:00`, `1714564800500000000` or `+10s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceTime {
+    /// Nanoseconds since the UNIX epoch.
+    Absolute(i64),
+    /// Duration from the beginning of the traces.
+    FromBeginning(NsDuration),
+}
+
+impl TraceTime {
+    pub const fn is_relative(self) -> bool {
+        matches!(self, Self::FromBeginning(_))
+    }
+
+    /// Nanoseconds since the UNIX epoch.
+    ///
+    /// Returns `None` if the time is relative and the beginning of the traces is unknown.
+    pub fn resolve(self, traces_begin: Option<i64>) -> Option<i64> {
         match self {
-            Self::Arc(arc) => Self::Arc(Arc::clone(arc)),
-            Self::Weak(weak) => Self::Weak(Weak::clone(weak)),
+            Self::Absolute(nanos) => Some(nanos),
+            Self::FromBeginning(duration) => traces_begin?.checked_add(duration.as_nanos()),
         }
     }
 }
 
-impl<T> From<Arc<T>> for ArcWeak<T> {
-    fn from(arc: Arc<T>) -> Self {
-        Self::Arc(arc)
-    }
+#[derive(Debug, Clone, Error)]
+pub enum TraceTimeParseError {
+    #[error(
+        "Cannot parse time {1:?}: {0}. Expected an RFC 3339 timestamp (e.g. `2024-05-01T12:00:00Z`), nanoseconds since the UNIX epoch or a duration from the beginning of the traces (e.g. `+10s`)"
+    )]
+    Invalid(#[source] chrono::ParseError, String),
+
+    #[error("Time is out of range: {0:?}")]
+    OutOfRange(String),
+
+    #[error(transparent)]
+    Duration(#[from] NsDurationParseError),
 }
 
-impl<T> From<Weak<T>> for ArcWeak<T> {
-    fn from(weak: Weak<T>) -> Self {
-        Self::Weak(weak)
+impl FromStr for TraceTime {
+    type Err = TraceTimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(duration) = s.strip_prefix('+') {
+            return Ok(Self::FromBeginning(duration.parse()?));
+        }
+        if let Ok(nanos) = s.parse() {
+            return Ok(Self::Absolute(nanos));
+        }
+
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map_err(|e| TraceTimeParseError::Invalid(e, s.to_owned()))?
+            .timestamp_nanos_opt()
+            .map(Self::Absolute)
+            .ok_or_else(|| TraceTimeParseError::OutOfRange(s.to_owned()))
     }
 }
 
-pub trait CyclicDependency {
-    fn break_cycle(&mut self);
+pub struct DebugOptionHex<'a, T>(pub &'a Option<T>);
 
-    fn create_cycle(&mut self) -> bool;
+impl<T: std::fmt::LowerHex> std::fmt::Debug for DebugOptionHex<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(value) => f
+                .debug_tuple("Some")
+                .field(&format_args!("{value:#x}"))
+                .finish(),
+            None => f.debug_struct("None").finish(),
+        }
+    }
 }